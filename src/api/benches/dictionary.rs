@@ -0,0 +1,75 @@
+//! Compares lookup speed of the pointer-based Trie and the packed Dawg over the
+//! full wordlist. Memory usage is compared by `examples/dictionary_memory.rs`,
+//! since RSS has to be measured in a fresh process.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::path::PathBuf;
+
+use pathfinder::game::{Dawg, Trie};
+
+const WORDLIST: &str = "../../wordlist";
+
+fn sample_words() -> Vec<String> {
+    std::fs::read_to_string(WORDLIST)
+        .expect("wordlist not found")
+        .lines()
+        .step_by(97)
+        .map(String::from)
+        .collect()
+}
+
+fn lookups(c: &mut Criterion) {
+    let words = sample_words();
+    let prefixes: Vec<&str> = words.iter().map(|w| &w[..w.len().div_ceil(2)]).collect();
+    let trie = Trie::from(PathBuf::from(WORDLIST));
+    let dawg = Dawg::from(PathBuf::from(WORDLIST));
+
+    let mut group = c.benchmark_group("search");
+    group.bench_function("trie", |b| {
+        b.iter(|| words.iter().filter(|w| trie.search(black_box(w))).count())
+    });
+    group.bench_function("dawg", |b| {
+        b.iter(|| words.iter().filter(|w| dawg.search(black_box(w))).count())
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("has_prefix");
+    group.bench_function("trie", |b| {
+        b.iter(|| {
+            prefixes
+                .iter()
+                .filter(|p| trie.has_prefix(black_box(p)))
+                .count()
+        })
+    });
+    group.bench_function("dawg", |b| {
+        b.iter(|| {
+            prefixes
+                .iter()
+                .filter(|p| dawg.has_prefix(black_box(p)))
+                .count()
+        })
+    });
+    group.finish();
+}
+
+fn build(c: &mut Criterion) {
+    let text = std::fs::read_to_string(WORDLIST).expect("wordlist not found");
+    let bytes = Dawg::from(text.clone()).to_bytes();
+
+    let mut group = c.benchmark_group("load");
+    group.sample_size(10);
+    group.bench_function("trie_from_text", |b| {
+        b.iter(|| Trie::from(black_box(text.clone())))
+    });
+    group.bench_function("dawg_from_text", |b| {
+        b.iter(|| Dawg::from(black_box(text.clone())))
+    });
+    group.bench_function("dawg_from_bytes", |b| {
+        b.iter(|| Dawg::from_bytes(black_box(bytes.clone())).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, lookups, build);
+criterion_main!(benches);
@@ -0,0 +1,132 @@
+//! Benchmarks the board solver, which game generation runs for every candidate
+//! board (up to ten per date), over a fixed set of seeded boards and the full
+//! wordlist, and the scoring a player's answers go through on every save.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use rand::SeedableRng;
+use std::path::PathBuf;
+
+use pathfinder::game::{
+    scoring::BonusRules, solver::Solver, Board, BoardGenerator, Dawg, GameEngine,
+};
+
+const WORDLIST: &str = "../../wordlist";
+
+fn sample_boards() -> Vec<Board> {
+    (0..10)
+        .map(|seed| {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+            BoardGenerator::new().generate_board(&mut rng)
+        })
+        .collect()
+}
+
+fn solve(c: &mut Criterion) {
+    let dawg = Dawg::from(PathBuf::from(WORDLIST));
+    let boards = sample_boards();
+
+    let mut group = c.benchmark_group("solve");
+    group.bench_function("single_pass", |b| {
+        b.iter(|| {
+            boards
+                .iter()
+                .map(|board| Solver::new(black_box(board)).solve(&dawg).len())
+                .sum::<usize>()
+        })
+    });
+    // The previous approach: find the words, then search the board again for
+    // each word's paths
+    group.bench_function("paths_per_word", |b| {
+        b.iter(|| {
+            boards
+                .iter()
+                .map(|board| {
+                    Solver::new(black_box(board))
+                        .solve(&dawg)
+                        .iter()
+                        .map(|answer| board.paths_for(&answer.word).paths.len())
+                        .sum::<usize>()
+                })
+                .sum::<usize>()
+        })
+    });
+    group.finish();
+}
+
+fn generate(c: &mut Criterion) {
+    let engine = GameEngine::new(PathBuf::from(WORDLIST));
+    let mut group = c.benchmark_group("generate");
+    group.sample_size(20);
+    group.bench_function("try_generate_valid_board", |b| {
+        let mut seed = 0;
+        b.iter(|| {
+            seed += 1;
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+            engine
+                .try_generate_valid_board(&mut rng, black_box(40), None)
+                .is_ok()
+        })
+    });
+    group.finish();
+}
+
+/// The sample board whose words have the most paths through a wildcard, with
+/// as many of its words as can all be played together
+fn wildcard_heavy_entry(engine: &GameEngine, dawg: &Dawg) -> (Board, Vec<String>) {
+    let (board, answers) = sample_boards()
+        .into_iter()
+        .map(|board| {
+            let answers = Solver::new(&board).solve(dawg);
+            (board, answers)
+        })
+        .max_by_key(|(_, answers)| {
+            answers
+                .iter()
+                .flat_map(|answer| &answer.paths)
+                .filter(|path| path.tiles.iter().any(|tile| tile.is_wildcard))
+                .count()
+        })
+        .unwrap();
+
+    let mut session = engine.scoring_session(&board, &BonusRules::default());
+    let mut words = Vec::new();
+    for answer in answers {
+        words.push(answer.word);
+        if session.score(words.clone()).is_err() {
+            words.pop();
+        }
+    }
+    (board, words)
+}
+
+fn score(c: &mut Criterion) {
+    let engine = GameEngine::new(PathBuf::from(WORDLIST));
+    let dawg = Dawg::from(PathBuf::from(WORDLIST));
+    let (board, words) = wildcard_heavy_entry(&engine, &dawg);
+    let rules = BonusRules::default();
+    let earlier = words[..words.len() - 1].to_vec();
+
+    // Each case scores the entry as the player adds its last word
+    let mut group = c.benchmark_group("score_add_word");
+    group.bench_function("from_scratch", |b| {
+        b.iter(|| {
+            engine
+                .score_answer_group(black_box(&board), words.clone(), &rules)
+                .unwrap()
+                .total_score()
+        })
+    });
+    group.bench_function("session", |b| {
+        let mut session = engine.scoring_session(&board, &rules);
+        session.score(earlier.clone()).unwrap();
+        b.iter_batched(
+            || (session.clone(), words.clone()),
+            |(mut session, words)| session.score(words).unwrap().total_score(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, solve, generate, score);
+criterion_main!(benches);
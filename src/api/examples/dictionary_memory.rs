@@ -0,0 +1,54 @@
+//! Reports the resident memory used by each dictionary representation for the
+//! full wordlist. Run once per representation so allocations from one don't
+//! skew the other:
+//!
+//!     cargo run --release --example dictionary_memory -- trie
+//!     cargo run --release --example dictionary_memory -- dawg
+//!     cargo run --release --example dictionary_memory -- dawg-file wordlist.dawg
+
+use std::path::PathBuf;
+use sysinfo::{Pid, System};
+
+use pathfinder::game::{Dawg, Trie};
+
+fn rss_bytes(system: &mut System, pid: Pid) -> u64 {
+    system.refresh_process(pid);
+    system.process(pid).map(|p| p.memory()).unwrap_or(0)
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let kind = args.next().unwrap_or_else(|| "dawg".to_string());
+    let path = PathBuf::from(args.next().unwrap_or_else(|| "../../wordlist".to_string()));
+
+    let pid = Pid::from_u32(std::process::id());
+    let mut system = System::new();
+    let before = rss_bytes(&mut system, pid);
+
+    let found = match kind.as_str() {
+        "trie" => {
+            let trie = Trie::from(path);
+            let after = rss_bytes(&mut system, pid);
+            report(&kind, before, after);
+            trie.search("happy")
+        }
+        "dawg" | "dawg-file" => {
+            let dawg = Dawg::from(path);
+            let after = rss_bytes(&mut system, pid);
+            report(&kind, before, after);
+            println!("  serialized size: {} KiB", dawg.size_in_bytes() / 1024);
+            dawg.search("happy")
+        }
+        other => panic!("unknown dictionary kind {other}, expected trie, dawg or dawg-file"),
+    };
+
+    assert!(found, "dictionary should contain 'happy'");
+}
+
+fn report(kind: &str, before: u64, after: u64) {
+    println!(
+        "{kind}: {} KiB resident ({} KiB before loading)",
+        after.saturating_sub(before) / 1024,
+        before / 1024
+    );
+}
@@ -14,7 +14,7 @@ async fn run_game_generation() -> Result<()> {
     let seed = Seeder::from(date_str).make_seed();
     let mut rng = rand::rngs::StdRng::from_seed(seed);
 
-    match game_engine.try_generate_valid_board(&mut rng, 40).await {
+    match game_engine.try_generate_valid_board(&mut rng, 40, None) {
         Ok((board, _, (optimal_words, metadata))) => {
             info!("Generated board: \n{}\n\nOptimal Answers:", board,);
             for (i, (word, score)) in optimal_words
@@ -53,7 +53,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!();
 
     // Find optimal 5 words
-    let result = engine.find_best_n_words(&board, 5).await?;
+    let result = engine
+        .find_best_n_words(&board, engine.bonus_rules(), 5)
+        .await?;
     let (best_words, metadata) = result;
 
     println!(
@@ -97,7 +99,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!();
     println!("Testing optimal word finding for different n values:");
     for n in [1, 2, 3, 5, 10].iter() {
-        let result = engine.find_best_n_words(&board, *n).await?;
+        let result = engine
+            .find_best_n_words(&board, engine.bonus_rules(), *n)
+            .await?;
         let (words, meta) = result;
         println!(
             "n={}: Found {} words, total score {}",
@@ -0,0 +1,14 @@
+-- drop id from game_answers table, use composite key instead (mirrors the sqlite game_answers2 migration)
+CREATE TABLE IF NOT EXISTS game_answers2 (
+    game_id TEXT NOT NULL,
+    word TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE,
+    PRIMARY KEY (game_id, word)
+);
+INSERT INTO game_answers2 (game_id, word, created_at)
+    SELECT game_id, word, created_at FROM game_answers
+    ON CONFLICT DO NOTHING;
+DROP TABLE IF EXISTS game_answers;
+
+CREATE INDEX IF NOT EXISTS idx_game_answers2_game_id ON game_answers2(game_id);
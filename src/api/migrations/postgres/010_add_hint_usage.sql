@@ -0,0 +1,13 @@
+-- Hint usage table - how far each user has progressed through hints for a
+-- word in a game. level counts hints consumed for that word.
+CREATE TABLE IF NOT EXISTS hint_usage (
+    user_id TEXT NOT NULL,
+    game_id TEXT NOT NULL,
+    word TEXT NOT NULL,
+    level INTEGER NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW(),
+    PRIMARY KEY (user_id, game_id, word),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
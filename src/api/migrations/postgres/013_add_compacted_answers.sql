@@ -0,0 +1,4 @@
+-- Compacted answers for old games. Once a game is compacted its game_answers2
+-- rows are deleted and the words live here as a deflate-compressed JSON blob;
+-- NULL means the answers are still stored as rows.
+ALTER TABLE games ADD COLUMN IF NOT EXISTS answers_blob BYTEA;
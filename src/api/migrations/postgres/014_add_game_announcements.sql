@@ -0,0 +1,7 @@
+-- Games that have been announced on social media. A row is claimed before
+-- posting so a restarted scheduler never announces the same game twice.
+CREATE TABLE IF NOT EXISTS game_announcements (
+    game_id TEXT PRIMARY KEY,
+    announced_at TIMESTAMPTZ DEFAULT NOW(),
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
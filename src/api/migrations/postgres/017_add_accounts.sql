@@ -0,0 +1,36 @@
+-- Accounts table - an anonymous user upgraded with an email address or
+-- passkeys so they can sign in from other devices
+CREATE TABLE IF NOT EXISTS accounts (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL UNIQUE,
+    email TEXT UNIQUE,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- Magic links emailed to attach an address to an account or to sign in.
+-- user_id is set for links that attach an address. Only a hash of each
+-- link's token is stored.
+CREATE TABLE IF NOT EXISTS account_magic_links (
+    token_hash TEXT PRIMARY KEY,
+    email TEXT NOT NULL,
+    user_id TEXT,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- Passkeys registered to an account. public_key is the base64url P-256
+-- point the authenticator signs with.
+CREATE TABLE IF NOT EXISTS account_passkeys (
+    credential_id TEXT PRIMARY KEY,
+    account_id TEXT NOT NULL,
+    public_key TEXT NOT NULL,
+    sign_count BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ,
+    FOREIGN KEY (account_id) REFERENCES accounts(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_passkeys_account_id ON account_passkeys(account_id);
@@ -0,0 +1,37 @@
+-- Entries submitted after a game's stats were frozen are late plays. They
+-- are kept but flagged off the board and left out of the game's stats.
+ALTER TABLE game_entries ADD COLUMN IF NOT EXISTS off_board BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Final aggregates for a game, written once when its stats are frozen. A
+-- game is frozen when it has a row here.
+CREATE TABLE IF NOT EXISTS game_final_stats (
+    game_id TEXT PRIMARY KEY,
+    total_players INTEGER NOT NULL,
+    average_score INTEGER NOT NULL,
+    highest_score INTEGER NOT NULL,
+    frozen_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
+
+-- Which notifications a user has opted in to. Users without a row get none.
+CREATE TABLE IF NOT EXISTS notification_preferences (
+    user_id TEXT PRIMARY KEY,
+    final_rank BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- Notifications delivered to a user, newest shown first
+CREATE TABLE IF NOT EXISTS notifications (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    game_id TEXT,
+    message TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    read_at TIMESTAMPTZ,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_notifications_user_id ON notifications(user_id, created_at);
@@ -0,0 +1,11 @@
+-- How dense a game's solution space was when it was generated, kept to
+-- compare with how players fared on the board
+CREATE TABLE IF NOT EXISTS game_board_density (
+    game_id TEXT PRIMARY KEY,
+    valid_words INTEGER NOT NULL,
+    words_per_tile DOUBLE PRECISION NOT NULL,
+    average_word_length DOUBLE PRECISION NOT NULL,
+    wildcard_reliance DOUBLE PRECISION NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
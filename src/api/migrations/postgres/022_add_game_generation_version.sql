@@ -0,0 +1,3 @@
+-- Version of the generation rules each game's board was made with, so the
+-- board can be regenerated exactly. Games before versioning used version 1.
+ALTER TABLE games ADD COLUMN generation_version INTEGER NOT NULL DEFAULT 1;
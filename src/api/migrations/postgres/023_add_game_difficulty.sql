@@ -0,0 +1,3 @@
+-- Difficulty label each game's board was rated with when it was generated.
+-- Games from before rating have none.
+ALTER TABLE games ADD COLUMN difficulty TEXT;
@@ -0,0 +1,3 @@
+-- Theme spec a game's board was generated to contain, such as word:snow,
+-- kept so a themed board can be regenerated. Unthemed games have none.
+ALTER TABLE games ADD COLUMN theme TEXT;
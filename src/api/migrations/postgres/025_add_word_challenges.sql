@@ -0,0 +1,14 @@
+-- Words a player successfully challenged on a game: rejected by the wordlist
+-- but found in the reference list, so accepted for that player's entry. Every
+-- challenged word is a proposal for adding to the wordlist.
+CREATE TABLE IF NOT EXISTS word_challenges (
+    user_id TEXT NOT NULL,
+    game_id TEXT NOT NULL,
+    word TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (user_id, game_id, word),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_word_challenges_word ON word_challenges(word);
@@ -0,0 +1,11 @@
+-- Blitz games sit alongside the daily games, one of each mode per date with
+-- their own puzzle numbers, so dates and sequence numbers are unique per mode
+ALTER TABLE games ADD COLUMN IF NOT EXISTS mode TEXT NOT NULL DEFAULT 'daily';
+-- Seconds a blitz entry may be saved for after the player starts it
+ALTER TABLE games ADD COLUMN IF NOT EXISTS time_limit_seconds INTEGER;
+
+ALTER TABLE games DROP CONSTRAINT IF EXISTS games_date_key;
+DROP INDEX IF EXISTS idx_games_sequence_number;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_games_mode_date ON games(mode, date);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_games_mode_sequence_number ON games(mode, sequence_number);
+CREATE INDEX IF NOT EXISTS idx_games_sequence_number ON games(sequence_number)
@@ -0,0 +1,15 @@
+-- Sensitive operations, such as admin changes, user merges and game
+-- generation, with who made them and what they changed. before_data and
+-- after_data hold JSON snapshots of the target around the change.
+CREATE TABLE IF NOT EXISTS audit_log (
+    id TEXT PRIMARY KEY,
+    actor TEXT NOT NULL,  -- e.g. 'admin:bootstrap', 'admin:<token id>', 'user:<id>', 'system'
+    action TEXT NOT NULL, -- e.g. 'batch.publish', 'user.merge'
+    target TEXT,
+    before_data TEXT,
+    after_data TEXT,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_created_at ON audit_log(created_at);
+CREATE INDEX IF NOT EXISTS idx_audit_log_target ON audit_log(target);
@@ -0,0 +1,8 @@
+-- Daily games in other languages sit alongside the English ones, one per
+-- language, mode and date with their own puzzle numbers
+ALTER TABLE games ADD COLUMN IF NOT EXISTS language TEXT NOT NULL DEFAULT 'en';
+
+DROP INDEX IF EXISTS idx_games_mode_date;
+DROP INDEX IF EXISTS idx_games_mode_sequence_number;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_games_language_mode_date ON games(language, mode, date);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_games_language_mode_sequence_number ON games(language, mode, sequence_number);
@@ -0,0 +1,13 @@
+-- How players actually fared on each game, written by the game calibrator
+-- once the game's day is over. Rates are shares from 0 to 1.
+CREATE TABLE IF NOT EXISTS game_stats (
+    game_id TEXT PRIMARY KEY,
+    players INTEGER NOT NULL,
+    completed_players INTEGER NOT NULL,
+    median_score INTEGER NOT NULL,
+    optimal_score INTEGER NOT NULL,
+    completion_rate DOUBLE PRECISION NOT NULL,
+    threshold_hit_rate DOUBLE PRECISION NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
@@ -0,0 +1,30 @@
+-- Each answer on a game entry, written alongside answers_data so answers can
+-- be queried without parsing it. path_json is the traced path in the current
+-- answers version, null when none was traced.
+CREATE TABLE IF NOT EXISTS game_entry_answers (
+    entry_id TEXT NOT NULL,
+    word TEXT NOT NULL,
+    score INTEGER NOT NULL,
+    path_json TEXT,
+    PRIMARY KEY (entry_id, word),
+    FOREIGN KEY (entry_id) REFERENCES game_entries(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_game_entry_answers_word ON game_entry_answers(word);
+
+-- Backfill from the answers_data of existing entries, whichever version it
+-- was written in. Paths traced before version 2 were stored differently and
+-- are left out.
+INSERT INTO game_entry_answers (entry_id, word, score, path_json)
+SELECT e.id,
+    a.value->>'word',
+    COALESCE((a.value->>'score')::INTEGER, 0),
+    CASE WHEN e.data->>'version' = '2' AND jsonb_typeof(a.value->'traced_path') = 'object'
+        THEN (a.value->'traced_path')::TEXT END
+FROM (SELECT id, answers_data::JSONB AS data FROM game_entries) e
+CROSS JOIN LATERAL jsonb_array_elements(CASE
+        WHEN jsonb_typeof(e.data) = 'array' THEN e.data
+        WHEN e.data->>'version' = '2' THEN e.data->'payload'->'answers'
+        ELSE e.data->'answers' END) a
+WHERE jsonb_typeof(a.value->'word') = 'string'
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,13 @@
+-- A replaced board is kept as an earlier revision of its game rather than
+-- deleted, so the entries played on it keep their scores. Each replacement
+-- is a new row for the same language, mode, date and sequence number with
+-- the next revision, and the row it replaces is marked deleted. Only one row
+-- per date and number may be active.
+ALTER TABLE games ADD COLUMN IF NOT EXISTS revision INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE games ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+DROP INDEX IF EXISTS idx_games_language_mode_date;
+DROP INDEX IF EXISTS idx_games_language_mode_sequence_number;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_games_language_mode_date_revision ON games(language, mode, date, revision);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_games_active_language_mode_date ON games(language, mode, date) WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_games_active_language_mode_sequence_number ON games(language, mode, sequence_number) WHERE deleted_at IS NULL;
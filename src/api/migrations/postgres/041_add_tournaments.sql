@@ -0,0 +1,40 @@
+-- Tournaments run over a range of dates. Each player who joins accumulates
+-- their daily scores over the range into a standing.
+CREATE TABLE IF NOT EXISTS tournaments (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    start_date TEXT NOT NULL, -- YYYY-MM-DD, the first day that counts
+    end_date TEXT NOT NULL, -- YYYY-MM-DD, the last day that counts
+    language TEXT NOT NULL DEFAULT 'en',
+    created_at TIMESTAMPTZ NOT NULL,
+    finalized_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_tournaments_end_date ON tournaments(end_date);
+
+CREATE TABLE IF NOT EXISTS tournament_participants (
+    tournament_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    joined_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (tournament_id, user_id),
+    FOREIGN KEY (tournament_id) REFERENCES tournaments(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_tournament_participants_user ON tournament_participants(user_id);
+
+-- The final standings, written once when a tournament is finalized so later
+-- changes to entries don't move its winners. A player who deletes their
+-- account keeps their place without their user id.
+CREATE TABLE IF NOT EXISTS tournament_results (
+    id TEXT PRIMARY KEY,
+    tournament_id TEXT NOT NULL,
+    user_id TEXT,
+    rank INTEGER NOT NULL,
+    total_score INTEGER NOT NULL,
+    games_played INTEGER NOT NULL,
+    FOREIGN KEY (tournament_id) REFERENCES tournaments(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_tournament_results_tournament_rank ON tournament_results(tournament_id, rank);
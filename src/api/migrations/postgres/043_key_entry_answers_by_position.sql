@@ -0,0 +1,35 @@
+-- Games can allow a word to be played more than once, so each answer on an
+-- entry is keyed by where it is in the list rather than by its word. Nothing
+-- references the table, so it is rebuilt from answers_data as when it was
+-- added, this time keeping repeated words.
+CREATE TABLE IF NOT EXISTS game_entry_answers_by_position (
+    entry_id TEXT NOT NULL,
+    -- Where the answer is in the entry's list, from 0
+    position INTEGER NOT NULL,
+    word TEXT NOT NULL,
+    score INTEGER NOT NULL,
+    path_json TEXT,
+    PRIMARY KEY (entry_id, position),
+    FOREIGN KEY (entry_id) REFERENCES game_entries(id) ON DELETE CASCADE
+);
+
+INSERT INTO game_entry_answers_by_position (entry_id, position, word, score, path_json)
+SELECT e.id,
+    (a.ordinality - 1)::INTEGER,
+    a.value->>'word',
+    COALESCE((a.value->>'score')::INTEGER, 0),
+    CASE WHEN e.data->>'version' = '2' AND jsonb_typeof(a.value->'traced_path') = 'object'
+        THEN (a.value->'traced_path')::TEXT END
+FROM (SELECT id, answers_data::JSONB AS data FROM game_entries) e
+CROSS JOIN LATERAL jsonb_array_elements(CASE
+        WHEN jsonb_typeof(e.data) = 'array' THEN e.data
+        WHEN e.data->>'version' = '2' THEN e.data->'payload'->'answers'
+        ELSE e.data->'answers' END) WITH ORDINALITY a(value, ordinality)
+WHERE jsonb_typeof(a.value->'word') = 'string'
+ON CONFLICT DO NOTHING;
+
+DROP TABLE IF EXISTS game_entry_answers;
+
+ALTER TABLE game_entry_answers_by_position RENAME TO game_entry_answers;
+
+CREATE INDEX IF NOT EXISTS idx_game_entry_answers_word ON game_entry_answers(word);
@@ -0,0 +1,4 @@
+-- per-word path statistics, precomputed when a game is generated. NULL for
+-- answers stored before these columns existed.
+ALTER TABLE game_answers2 ADD COLUMN path_count INTEGER;
+ALTER TABLE game_answers2 ADD COLUMN requires_wildcard INTEGER; -- SQLite uses INTEGER for boolean
@@ -0,0 +1,15 @@
+-- User sessions table - the distinct browser sessions each user has played
+-- from. Session ids and user agents are stored hashed.
+CREATE TABLE user_sessions (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    session_hash TEXT NOT NULL,
+    user_agent_hash TEXT,
+    first_seen TEXT DEFAULT (datetime('now')),
+    last_seen TEXT DEFAULT (datetime('now')),
+    revoked_at TEXT,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    UNIQUE(user_id, session_hash)
+);
+
+CREATE INDEX idx_user_sessions_revoked_at ON user_sessions(revoked_at);
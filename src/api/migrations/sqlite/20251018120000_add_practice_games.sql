@@ -0,0 +1,14 @@
+-- Practice games table - throwaway boards generated on demand. They are kept
+-- apart from daily games so they never get a date, a sequence number or stats,
+-- and are deleted once expired.
+CREATE TABLE practice_games (
+    id TEXT PRIMARY KEY,
+    seed TEXT NOT NULL, -- u64 seed, stored as text since it can exceed INTEGER
+    board_data TEXT NOT NULL, -- JSON serialized board data
+    threshold_score INTEGER NOT NULL,
+    optimal_score INTEGER NOT NULL,
+    created_at TEXT DEFAULT (datetime('now')),
+    expires_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_practice_games_expires_at ON practice_games(expires_at);
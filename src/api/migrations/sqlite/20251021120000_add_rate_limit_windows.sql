@@ -0,0 +1,10 @@
+-- Requests counted per rate limit bucket and fixed window, shared by every
+-- server instance. Old windows are deleted as new ones fill up.
+CREATE TABLE rate_limit_windows (
+    bucket TEXT NOT NULL,
+    window_start TEXT NOT NULL,
+    request_count INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (bucket, window_start)
+);
+
+CREATE INDEX idx_rate_limit_windows_window_start ON rate_limit_windows(window_start);
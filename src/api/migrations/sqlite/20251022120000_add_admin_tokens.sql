@@ -0,0 +1,11 @@
+-- Admin tokens table - scoped, expiring tokens for the admin API, created
+-- with the bootstrap ADMIN_TOKEN. Only a hash of each token is stored.
+CREATE TABLE admin_tokens (
+    id TEXT PRIMARY KEY,
+    token_hash TEXT NOT NULL UNIQUE,
+    scope TEXT NOT NULL,
+    label TEXT,
+    created_at TEXT DEFAULT (datetime('now')),
+    expires_at TEXT NOT NULL,
+    revoked_at TEXT
+);
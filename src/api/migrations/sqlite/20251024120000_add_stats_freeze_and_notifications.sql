@@ -0,0 +1,37 @@
+-- Entries submitted after a game's stats were frozen are late plays. They
+-- are kept but flagged off the board and left out of the game's stats.
+ALTER TABLE game_entries ADD COLUMN off_board INTEGER NOT NULL DEFAULT 0; -- SQLite uses INTEGER for boolean
+
+-- Final aggregates for a game, written once when its stats are frozen. A
+-- game is frozen when it has a row here.
+CREATE TABLE game_final_stats (
+    game_id TEXT PRIMARY KEY,
+    total_players INTEGER NOT NULL,
+    average_score INTEGER NOT NULL,
+    highest_score INTEGER NOT NULL,
+    frozen_at TEXT NOT NULL,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
+
+-- Which notifications a user has opted in to. Users without a row get none.
+CREATE TABLE notification_preferences (
+    user_id TEXT PRIMARY KEY,
+    final_rank INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- Notifications delivered to a user, newest shown first
+CREATE TABLE notifications (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    game_id TEXT,
+    message TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    read_at TEXT,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_notifications_user_id ON notifications(user_id, created_at);
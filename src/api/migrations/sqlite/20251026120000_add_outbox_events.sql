@@ -0,0 +1,14 @@
+-- Side effects of a write, recorded in the same transaction as the write and
+-- delivered afterwards by the outbox worker. An event is pending until
+-- processed_at is set, and is retried until it has failed max attempts.
+CREATE TABLE outbox_events (
+    id TEXT PRIMARY KEY,
+    event_type TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TEXT NOT NULL,
+    processed_at TEXT
+);
+
+CREATE INDEX idx_outbox_events_pending ON outbox_events(processed_at, created_at);
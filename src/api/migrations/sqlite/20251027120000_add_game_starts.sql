@@ -0,0 +1,14 @@
+-- When each player first opened each game, recorded by the server so that
+-- completion times never rely on the client's clock
+CREATE TABLE game_starts (
+    user_id TEXT NOT NULL,
+    game_id TEXT NOT NULL,
+    started_at TEXT NOT NULL,
+    PRIMARY KEY (user_id, game_id),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
+
+-- When the player submitted the entry. Entries completed by the game ender
+-- at the end of the day rather than by the player have none.
+ALTER TABLE game_entries ADD COLUMN completed_at TEXT;
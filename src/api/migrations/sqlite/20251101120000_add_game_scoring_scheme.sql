@@ -0,0 +1,3 @@
+-- Version of the scoring scheme a game's tiles were given points by. Every
+-- game before schemes were versioned used the standard scheme, version 1.
+ALTER TABLE games ADD COLUMN scoring_scheme_version INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,3 @@
+-- Bonus rules a game is scored under, as JSON. Games from before bonuses
+-- existed have none.
+ALTER TABLE games ADD COLUMN bonus_rules TEXT;
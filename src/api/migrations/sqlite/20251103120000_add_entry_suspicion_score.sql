@@ -0,0 +1,3 @@
+-- How suspicious a completed entry looked when submitted, 0-100. Entries
+-- from before the integrity checks existed were never scored.
+ALTER TABLE game_entries ADD COLUMN suspicion_score INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,13 @@
+-- Autosaved snapshots of an in-progress entry's answers, newest kept, so a
+-- player can recover after a crash and lost words can be traced
+CREATE TABLE entry_revisions (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    game_id TEXT NOT NULL,
+    answers_data TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_entry_revisions_user_game ON entry_revisions(user_id, game_id, created_at);
@@ -0,0 +1,3 @@
+-- The client-chosen id of the request that last wrote the entry, so a retry
+-- of that request can be recognised and answered without writing again
+ALTER TABLE game_entries ADD COLUMN request_id TEXT;
@@ -0,0 +1,28 @@
+-- Boards made by players and shared by slug. Each board code is stored once,
+-- so making the same board again finds the game already shared for it.
+CREATE TABLE custom_games (
+    id TEXT PRIMARY KEY,
+    slug TEXT NOT NULL UNIQUE,
+    board_code TEXT NOT NULL UNIQUE,
+    board_data TEXT NOT NULL, -- JSON serialized board data
+    optimal_score INTEGER NOT NULL,
+    created_by TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- Each player's best submission to a custom game
+CREATE TABLE custom_game_scores (
+    id TEXT PRIMARY KEY,
+    custom_game_id TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    answers_data TEXT NOT NULL,
+    total_score INTEGER NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    UNIQUE (custom_game_id, user_id),
+    FOREIGN KEY (custom_game_id) REFERENCES custom_games(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_custom_game_scores_game_score ON custom_game_scores(custom_game_id, total_score);
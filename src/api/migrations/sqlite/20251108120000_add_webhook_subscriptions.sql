@@ -0,0 +1,20 @@
+-- Services notified when a new daily game goes live. Each has its own secret
+-- that signs what is sent to it, so it can tell deliveries came from here.
+CREATE TABLE webhook_subscriptions (
+    id TEXT PRIMARY KEY,
+    kind TEXT NOT NULL, -- 'discord' or 'generic'
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+-- Games delivered to each subscription. A row is claimed before sending, so
+-- a restarted scheduler never notifies a subscription of a game twice.
+CREATE TABLE webhook_deliveries (
+    subscription_id TEXT NOT NULL,
+    game_id TEXT NOT NULL,
+    delivered_at TEXT NOT NULL,
+    PRIMARY KEY (subscription_id, game_id),
+    FOREIGN KEY (subscription_id) REFERENCES webhook_subscriptions(id) ON DELETE CASCADE,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
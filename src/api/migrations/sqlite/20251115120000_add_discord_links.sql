@@ -0,0 +1,13 @@
+-- Discord users linked to the players they are on the site. Running the bot's
+-- link command stores a short-lived code, and the player entering that code
+-- while signed in fills in user_id.
+CREATE TABLE discord_links (
+    discord_user_id TEXT PRIMARY KEY,
+    user_id TEXT,
+    code_hash TEXT UNIQUE,
+    code_expires_at TEXT,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_discord_links_user_id ON discord_links(user_id);
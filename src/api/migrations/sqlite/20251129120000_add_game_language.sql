@@ -0,0 +1,79 @@
+-- Daily games in other languages sit alongside the English ones, one per
+-- language, mode and date with their own puzzle numbers. As when modes were
+-- added, the table is rebuilt to scope its UNIQUE constraints, and the rows
+-- that reference it are kept aside in case dropping the old table cascades
+-- to them.
+PRAGMA foreign_keys = OFF;
+
+CREATE TABLE games_with_language (
+    id TEXT PRIMARY KEY,
+    date TEXT NOT NULL, -- YYYY-MM-DD format
+    board_data TEXT NOT NULL,  -- JSON serialized board data
+    threshold_score INTEGER NOT NULL,
+    sequence_number INTEGER NOT NULL,
+    completed INTEGER NOT NULL DEFAULT 0, -- SQLite uses INTEGER for boolean
+    completed_at TEXT, -- SQLite uses TEXT for timestamps
+    created_at TEXT DEFAULT (datetime('now')),
+    answers_blob BLOB,
+    generation_version INTEGER NOT NULL DEFAULT 1,
+    difficulty TEXT,
+    theme TEXT,
+    scoring_scheme_version INTEGER NOT NULL DEFAULT 1,
+    bonus_rules TEXT,
+    mode TEXT NOT NULL DEFAULT 'daily',
+    -- Seconds a blitz entry may be saved for after the player starts it
+    time_limit_seconds INTEGER,
+    language TEXT NOT NULL DEFAULT 'en',
+    UNIQUE (language, mode, date),
+    UNIQUE (language, mode, sequence_number)
+);
+
+INSERT INTO games_with_language (id, date, board_data, threshold_score, sequence_number, completed, completed_at, created_at, answers_blob, generation_version, difficulty, theme, scoring_scheme_version, bonus_rules, mode, time_limit_seconds)
+SELECT id, date, board_data, threshold_score, sequence_number, completed, completed_at, created_at, answers_blob, generation_version, difficulty, theme, scoring_scheme_version, bonus_rules, mode, time_limit_seconds FROM games;
+
+CREATE TEMP TABLE kept_game_entries AS SELECT * FROM game_entries;
+CREATE TEMP TABLE kept_game_answers2 AS SELECT * FROM game_answers2;
+CREATE TEMP TABLE kept_optimal_solutions AS SELECT * FROM optimal_solutions;
+CREATE TEMP TABLE kept_hint_usage AS SELECT * FROM hint_usage;
+CREATE TEMP TABLE kept_game_announcements AS SELECT * FROM game_announcements;
+CREATE TEMP TABLE kept_game_final_stats AS SELECT * FROM game_final_stats;
+CREATE TEMP TABLE kept_notifications AS SELECT * FROM notifications;
+CREATE TEMP TABLE kept_game_board_density AS SELECT * FROM game_board_density;
+CREATE TEMP TABLE kept_game_starts AS SELECT * FROM game_starts;
+CREATE TEMP TABLE kept_word_challenges AS SELECT * FROM word_challenges;
+CREATE TEMP TABLE kept_entry_revisions AS SELECT * FROM entry_revisions;
+CREATE TEMP TABLE kept_webhook_deliveries AS SELECT * FROM webhook_deliveries;
+
+DROP TABLE games;
+
+ALTER TABLE games_with_language RENAME TO games;
+
+INSERT OR IGNORE INTO game_entries SELECT * FROM kept_game_entries;
+DROP TABLE kept_game_entries;
+INSERT OR IGNORE INTO game_answers2 SELECT * FROM kept_game_answers2;
+DROP TABLE kept_game_answers2;
+INSERT OR IGNORE INTO optimal_solutions SELECT * FROM kept_optimal_solutions;
+DROP TABLE kept_optimal_solutions;
+INSERT OR IGNORE INTO hint_usage SELECT * FROM kept_hint_usage;
+DROP TABLE kept_hint_usage;
+INSERT OR IGNORE INTO game_announcements SELECT * FROM kept_game_announcements;
+DROP TABLE kept_game_announcements;
+INSERT OR IGNORE INTO game_final_stats SELECT * FROM kept_game_final_stats;
+DROP TABLE kept_game_final_stats;
+INSERT OR IGNORE INTO notifications SELECT * FROM kept_notifications;
+DROP TABLE kept_notifications;
+INSERT OR IGNORE INTO game_board_density SELECT * FROM kept_game_board_density;
+DROP TABLE kept_game_board_density;
+INSERT OR IGNORE INTO game_starts SELECT * FROM kept_game_starts;
+DROP TABLE kept_game_starts;
+INSERT OR IGNORE INTO word_challenges SELECT * FROM kept_word_challenges;
+DROP TABLE kept_word_challenges;
+INSERT OR IGNORE INTO entry_revisions SELECT * FROM kept_entry_revisions;
+DROP TABLE kept_entry_revisions;
+INSERT OR IGNORE INTO webhook_deliveries SELECT * FROM kept_webhook_deliveries;
+DROP TABLE kept_webhook_deliveries;
+
+CREATE INDEX idx_games_date ON games(date);
+CREATE INDEX idx_games_sequence_number ON games(sequence_number);
+
+PRAGMA foreign_keys = ON;
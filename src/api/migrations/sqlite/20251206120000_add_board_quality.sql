@@ -0,0 +1,13 @@
+-- What the quality analyzer found on a game's board when it was generated,
+-- kept to tune generation against. issues is a comma separated list.
+CREATE TABLE game_board_quality (
+    game_id TEXT PRIMARY KEY,
+    long_words INTEGER NOT NULL,
+    dead_tiles INTEGER NOT NULL,
+    dead_corners INTEGER NOT NULL,
+    unused_wildcards INTEGER NOT NULL,
+    vowel_share REAL NOT NULL,
+    issues TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    FOREIGN KEY (game_id) REFERENCES games(id) ON DELETE CASCADE
+);
@@ -0,0 +1,30 @@
+-- Each answer on a game entry, written alongside answers_data so answers can
+-- be queried without parsing it. path_json is the traced path in the current
+-- answers version, null when none was traced.
+CREATE TABLE game_entry_answers (
+    entry_id TEXT NOT NULL,
+    word TEXT NOT NULL,
+    score INTEGER NOT NULL,
+    path_json TEXT,
+    PRIMARY KEY (entry_id, word),
+    FOREIGN KEY (entry_id) REFERENCES game_entries(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_game_entry_answers_word ON game_entry_answers(word);
+
+-- Backfill from the answers_data of existing entries, whichever version it
+-- was written in. Paths traced before version 2 were stored differently and
+-- are left out.
+INSERT OR IGNORE INTO game_entry_answers (entry_id, word, score, path_json)
+SELECT e.id,
+    json_extract(a.value, '$.word'),
+    COALESCE(json_extract(a.value, '$.score'), 0),
+    CASE WHEN json_extract(e.answers_data, '$.version') = 2
+        AND json_type(a.value, '$.traced_path') = 'object'
+        THEN json_extract(a.value, '$.traced_path') END
+FROM game_entries e, json_each(CASE
+        WHEN json_type(e.answers_data) = 'array' THEN e.answers_data
+        WHEN json_extract(e.answers_data, '$.version') = 2
+            THEN json_extract(e.answers_data, '$.payload.answers')
+        ELSE json_extract(e.answers_data, '$.answers') END) a
+WHERE json_valid(e.answers_data) AND json_type(a.value, '$.word') = 'text';
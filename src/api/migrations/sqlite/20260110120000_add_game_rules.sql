@@ -0,0 +1,3 @@
+-- Word length and answer limits a game is played under, as JSON. Games
+-- from before rules were stored have none and use the defaults.
+ALTER TABLE games ADD COLUMN game_rules TEXT;
@@ -0,0 +1,35 @@
+-- Games can allow a word to be played more than once, so each answer on an
+-- entry is keyed by where it is in the list rather than by its word. Nothing
+-- references the table, so it is rebuilt from answers_data as when it was
+-- added, this time keeping repeated words.
+CREATE TABLE game_entry_answers_by_position (
+    entry_id TEXT NOT NULL,
+    -- Where the answer is in the entry's list, from 0
+    position INTEGER NOT NULL,
+    word TEXT NOT NULL,
+    score INTEGER NOT NULL,
+    path_json TEXT,
+    PRIMARY KEY (entry_id, position),
+    FOREIGN KEY (entry_id) REFERENCES game_entries(id) ON DELETE CASCADE
+);
+
+INSERT OR IGNORE INTO game_entry_answers_by_position (entry_id, position, word, score, path_json)
+SELECT e.id,
+    CAST(a.key AS INTEGER),
+    json_extract(a.value, '$.word'),
+    COALESCE(json_extract(a.value, '$.score'), 0),
+    CASE WHEN json_extract(e.answers_data, '$.version') = 2
+        AND json_type(a.value, '$.traced_path') = 'object'
+        THEN json_extract(a.value, '$.traced_path') END
+FROM game_entries e, json_each(CASE
+        WHEN json_type(e.answers_data) = 'array' THEN e.answers_data
+        WHEN json_extract(e.answers_data, '$.version') = 2
+            THEN json_extract(e.answers_data, '$.payload.answers')
+        ELSE json_extract(e.answers_data, '$.answers') END) a
+WHERE json_valid(e.answers_data) AND json_type(a.value, '$.word') = 'text';
+
+DROP TABLE game_entry_answers;
+
+ALTER TABLE game_entry_answers_by_position RENAME TO game_entry_answers;
+
+CREATE INDEX idx_game_entry_answers_word ON game_entry_answers(word);
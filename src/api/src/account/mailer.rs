@@ -0,0 +1,76 @@
+use anyhow::{anyhow, Result};
+use axum::async_trait;
+use serde::Serialize;
+use std::env;
+use tracing::info;
+
+/// Mailer sends the emails accounts need, i.e. magic links
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, to: &str, subject: &str, body: &str) -> Result<()>;
+}
+
+/// LogMailer writes emails to the log instead of sending them, for local
+/// development where no mail service is configured
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, to: &str, subject: &str, body: &str) -> Result<()> {
+        info!("Email to {} ({}): {}", to, subject, body);
+        Ok(())
+    }
+}
+
+/// WebhookMailer hands emails to a mail service's HTTP API as JSON
+/// `{to, subject, text}`, authenticated with a bearer token
+pub struct WebhookMailer {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+}
+
+#[derive(Serialize)]
+struct OutgoingEmail<'a> {
+    to: &'a str,
+    subject: &'a str,
+    text: &'a str,
+}
+
+impl WebhookMailer {
+    pub fn new(url: String, token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            token,
+        }
+    }
+
+    /// Creates a mailer from `MAIL_WEBHOOK_URL` and the optional
+    /// `MAIL_WEBHOOK_TOKEN`. Returns None when the URL is missing.
+    pub fn from_env() -> Option<Self> {
+        let url = env::var("MAIL_WEBHOOK_URL").ok()?;
+        let token = env::var("MAIL_WEBHOOK_TOKEN").ok();
+        Some(Self::new(url, token))
+    }
+}
+
+#[async_trait]
+impl Mailer for WebhookMailer {
+    async fn send(&self, to: &str, subject: &str, body: &str) -> Result<()> {
+        let mut request = self.client.post(&self.url).json(&OutgoingEmail {
+            to,
+            subject,
+            text: body,
+        });
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("mail webhook returned {}", response.status()));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+pub mod mailer;
+pub mod passkey;
+
+use anyhow::Result;
+use moka::future::Cache;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+use mailer::{LogMailer, Mailer, WebhookMailer};
+
+use crate::security::utils::generate_token;
+
+/// How long a passkey ceremony can take between getting options and finishing
+const CHALLENGE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Longest email address that can be delivered (RFC 5321)
+const MAX_EMAIL_LENGTH: usize = 254;
+
+#[derive(Clone, Debug)]
+pub struct AccountConfig {
+    /// Base URL of the web client, used to build magic links
+    pub public_url: String,
+    /// Passkey relying party id, the site's domain
+    pub rp_id: String,
+    /// Origin passkey ceremonies must come from
+    pub origin: String,
+    pub magic_link_ttl: Duration,
+}
+
+impl Default for AccountConfig {
+    fn default() -> Self {
+        Self {
+            public_url: "http://localhost:5173".to_string(),
+            rp_id: "localhost".to_string(),
+            origin: "http://localhost:5173".to_string(),
+            magic_link_ttl: Duration::from_secs(15 * 60),
+        }
+    }
+}
+
+impl AccountConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(url) = env::var("ACCOUNT_PUBLIC_URL") {
+            config.public_url = url.trim_end_matches('/').to_string();
+        }
+
+        if let Ok(rp_id) = env::var("PASSKEY_RP_ID") {
+            config.rp_id = rp_id;
+        }
+
+        if let Ok(origin) = env::var("PASSKEY_ORIGIN") {
+            config.origin = origin.trim_end_matches('/').to_string();
+        }
+
+        if let Ok(ttl) = env::var("MAGIC_LINK_TTL_MINUTES") {
+            if let Ok(minutes) = ttl.parse::<u64>() {
+                config.magic_link_ttl = Duration::from_secs(minutes * 60);
+            }
+        }
+
+        config
+    }
+}
+
+/// What a passkey challenge was issued for
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Challenge {
+    /// Adding a passkey to this account
+    Register {
+        account_id: String,
+    },
+    Login,
+}
+
+/// Accounts holds what the account routes need beyond the repository: the
+/// configuration, the mailer for magic links and outstanding passkey
+/// challenges
+#[derive(Clone)]
+pub struct Accounts {
+    pub config: AccountConfig,
+    mailer: Arc<dyn Mailer>,
+    challenges: Cache<String, Challenge>,
+}
+
+impl Default for Accounts {
+    fn default() -> Self {
+        Self::new(AccountConfig::default(), Arc::new(LogMailer))
+    }
+}
+
+impl Accounts {
+    pub fn new(config: AccountConfig, mailer: Arc<dyn Mailer>) -> Self {
+        Self {
+            config,
+            mailer,
+            challenges: Cache::builder()
+                .max_capacity(10_000)
+                .time_to_live(CHALLENGE_TTL)
+                .build(),
+        }
+    }
+
+    /// Accounts configured from the environment. Magic links are sent
+    /// through the mail webhook when one is configured, and only logged
+    /// otherwise.
+    pub fn from_env() -> Self {
+        let mailer: Arc<dyn Mailer> = match WebhookMailer::from_env() {
+            Some(mailer) => Arc::new(mailer),
+            None => Arc::new(LogMailer),
+        };
+        Self::new(AccountConfig::from_env(), mailer)
+    }
+
+    /// Issue a single use challenge for a passkey ceremony
+    pub async fn issue_challenge(&self, challenge: Challenge) -> String {
+        let value = generate_token();
+        self.challenges.insert(value.clone(), challenge).await;
+        value
+    }
+
+    /// Use up a challenge, returning what it was issued for if it is still
+    /// outstanding
+    pub async fn take_challenge(&self, value: &str) -> Option<Challenge> {
+        self.challenges.remove(value).await
+    }
+
+    pub fn magic_link_url(&self, token: &str) -> String {
+        format!("{}/account/verify?token={}", self.config.public_url, token)
+    }
+
+    /// Email a magic link. Links that attach an address say so, so nobody is
+    /// surprised by a sign in link they didn't ask for.
+    pub async fn send_magic_link(&self, email: &str, token: &str, attach: bool) -> Result<()> {
+        let minutes = self.config.magic_link_ttl.as_secs() / 60;
+        let (subject, action) = if attach {
+            (
+                "Confirm your Pathfinder email",
+                "confirm this address for your Pathfinder account",
+            )
+        } else {
+            ("Sign in to Pathfinder", "sign in to Pathfinder")
+        };
+        let body = format!(
+            "Open this link to {action}:\n\n{}\n\nThe link works once and expires in {minutes} minutes. If you didn't ask for it you can ignore this email.",
+            self.magic_link_url(token)
+        );
+
+        self.mailer.send(email, subject, &body).await
+    }
+}
+
+/// Trim and lowercase an email address, or None if it doesn't look like one
+pub fn normalize_email(email: &str) -> Option<String> {
+    let email = email.trim().to_lowercase();
+    if email.len() > MAX_EMAIL_LENGTH || email.chars().any(char::is_whitespace) {
+        return None;
+    }
+
+    let (local, domain) = email.split_once('@')?;
+    let domain_ok = domain
+        .split('.')
+        .all(|label| !label.is_empty() && !label.contains('@'))
+        && domain.contains('.');
+    if local.is_empty() || !domain_ok {
+        return None;
+    }
+    Some(email)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_email() {
+        assert_eq!(
+            normalize_email("  Player@Example.COM "),
+            Some("player@example.com".to_string())
+        );
+        assert_eq!(normalize_email("player@example"), None);
+        assert_eq!(normalize_email("@example.com"), None);
+        assert_eq!(normalize_email("player@@example.com"), None);
+        assert_eq!(normalize_email("player@example..com"), None);
+        assert_eq!(normalize_email("pla yer@example.com"), None);
+    }
+
+    #[tokio::test]
+    async fn test_challenges_are_single_use() {
+        let accounts = Accounts::default();
+        let challenge = accounts.issue_challenge(Challenge::Login).await;
+
+        assert_eq!(
+            accounts.take_challenge(&challenge).await,
+            Some(Challenge::Login)
+        );
+        assert_eq!(accounts.take_challenge(&challenge).await, None);
+        assert_eq!(accounts.take_challenge("made-up").await, None);
+    }
+}
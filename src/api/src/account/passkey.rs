@@ -0,0 +1,304 @@
+use base64::{engine::general_purpose, Engine as _};
+use ring::{digest, signature};
+use serde::Deserialize;
+use std::fmt;
+
+/// DER prefix of a P-256 SubjectPublicKeyInfo; the uncompressed point follows
+const P256_SPKI_PREFIX: [u8; 26] = [
+    0x30, 0x59, 0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a,
+    0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07, 0x03, 0x42, 0x00,
+];
+
+/// Length of an uncompressed P-256 point: a 0x04 tag then x and y
+const P256_POINT_LENGTH: usize = 65;
+
+/// Authenticator data is the RP id hash, a flags byte and a 4 byte counter,
+/// optionally followed by credential data and extensions
+const AUTHENTICATOR_DATA_MIN_LENGTH: usize = 37;
+
+/// Flag set when the user was present, e.g. touched the authenticator
+const FLAG_USER_PRESENT: u8 = 0x01;
+
+pub const CREATE_TYPE: &str = "webauthn.create";
+pub const GET_TYPE: &str = "webauthn.get";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PasskeyError {
+    /// A field wasn't valid base64url, JSON or long enough
+    Malformed(&'static str),
+    /// The client data was for a different ceremony
+    WrongType,
+    /// The challenge wasn't issued by us, was already used or has expired
+    UnknownChallenge,
+    WrongOrigin,
+    WrongRelyingParty,
+    UserNotPresent,
+    /// The key isn't an ES256 (P-256) key
+    UnsupportedKey,
+    BadSignature,
+    /// The signature counter went backwards, a sign the credential was cloned
+    CounterRegressed,
+}
+
+impl fmt::Display for PasskeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PasskeyError::Malformed(field) => write!(f, "malformed {field}"),
+            PasskeyError::WrongType => write!(f, "client data is for a different ceremony"),
+            PasskeyError::UnknownChallenge => write!(f, "unknown or expired challenge"),
+            PasskeyError::WrongOrigin => write!(f, "origin does not match"),
+            PasskeyError::WrongRelyingParty => write!(f, "relying party does not match"),
+            PasskeyError::UserNotPresent => write!(f, "user presence was not confirmed"),
+            PasskeyError::UnsupportedKey => write!(f, "only ES256 passkeys are supported"),
+            PasskeyError::BadSignature => write!(f, "signature does not verify"),
+            PasskeyError::CounterRegressed => write!(f, "signature counter went backwards"),
+        }
+    }
+}
+
+impl std::error::Error for PasskeyError {}
+
+/// The parts of the browser's clientDataJSON we check
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClientData {
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// base64url challenge the ceremony was started with
+    pub challenge: String,
+    pub origin: String,
+}
+
+pub fn decode(value: &str, field: &'static str) -> Result<Vec<u8>, PasskeyError> {
+    general_purpose::URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|_| PasskeyError::Malformed(field))
+}
+
+pub fn encode(bytes: &[u8]) -> String {
+    general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Parse client data and check it is for the expected ceremony and origin.
+/// The caller checks the challenge against the ones it issued.
+pub fn parse_client_data(
+    client_data_json: &[u8],
+    expected_type: &str,
+    origin: &str,
+) -> Result<ClientData, PasskeyError> {
+    let client_data: ClientData = serde_json::from_slice(client_data_json)
+        .map_err(|_| PasskeyError::Malformed("client data"))?;
+
+    if client_data.kind != expected_type {
+        return Err(PasskeyError::WrongType);
+    }
+    if client_data.origin != origin {
+        return Err(PasskeyError::WrongOrigin);
+    }
+    Ok(client_data)
+}
+
+/// Check authenticator data was produced for our relying party with the user
+/// present, returning the signature counter
+pub fn check_authenticator_data(auth_data: &[u8], rp_id: &str) -> Result<u32, PasskeyError> {
+    if auth_data.len() < AUTHENTICATOR_DATA_MIN_LENGTH {
+        return Err(PasskeyError::Malformed("authenticator data"));
+    }
+
+    let rp_id_hash = digest::digest(&digest::SHA256, rp_id.as_bytes());
+    if auth_data[..32] != *rp_id_hash.as_ref() {
+        return Err(PasskeyError::WrongRelyingParty);
+    }
+    if auth_data[32] & FLAG_USER_PRESENT == 0 {
+        return Err(PasskeyError::UserNotPresent);
+    }
+
+    Ok(u32::from_be_bytes([
+        auth_data[33],
+        auth_data[34],
+        auth_data[35],
+        auth_data[36],
+    ]))
+}
+
+/// The uncompressed P-256 point from a DER SubjectPublicKeyInfo. Only ES256
+/// keys are supported, which every platform authenticator offers. The SPKI is
+/// what the browser's `getPublicKey()` returns at registration, so there is no
+/// attestation object to parse.
+pub fn public_key_from_spki(spki: &[u8]) -> Result<Vec<u8>, PasskeyError> {
+    match spki.strip_prefix(&P256_SPKI_PREFIX[..]) {
+        Some(point) if point.len() == P256_POINT_LENGTH && point[0] == 0x04 => Ok(point.to_vec()),
+        _ => Err(PasskeyError::UnsupportedKey),
+    }
+}
+
+/// Verify an assertion signature, which covers the authenticator data
+/// followed by the SHA-256 of the client data
+pub fn verify_signature(
+    public_key: &[u8],
+    auth_data: &[u8],
+    client_data_json: &[u8],
+    signature_der: &[u8],
+) -> Result<(), PasskeyError> {
+    let client_data_hash = digest::digest(&digest::SHA256, client_data_json);
+    let mut signed = Vec::with_capacity(auth_data.len() + client_data_hash.as_ref().len());
+    signed.extend_from_slice(auth_data);
+    signed.extend_from_slice(client_data_hash.as_ref());
+
+    signature::UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_ASN1, public_key)
+        .verify(&signed, signature_der)
+        .map_err(|_| PasskeyError::BadSignature)
+}
+
+/// Authenticators that keep a counter must increase it on every use.
+/// Authenticators that don't always report zero.
+pub fn check_sign_count(stored: u32, new: u32) -> Result<(), PasskeyError> {
+    if (stored != 0 || new != 0) && new <= stored {
+        return Err(PasskeyError::CounterRegressed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod test_authenticator {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{EcdsaKeyPair, KeyPair};
+
+    /// A software authenticator for tests
+    pub struct TestAuthenticator {
+        key_pair: EcdsaKeyPair,
+        rng: SystemRandom,
+        pub sign_count: u32,
+    }
+
+    impl TestAuthenticator {
+        pub fn new() -> Self {
+            let rng = SystemRandom::new();
+            let pkcs8 =
+                EcdsaKeyPair::generate_pkcs8(&signature::ECDSA_P256_SHA256_ASN1_SIGNING, &rng)
+                    .unwrap();
+            let key_pair = EcdsaKeyPair::from_pkcs8(
+                &signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+                pkcs8.as_ref(),
+                &rng,
+            )
+            .unwrap();
+            Self {
+                key_pair,
+                rng,
+                sign_count: 0,
+            }
+        }
+
+        pub fn spki(&self) -> Vec<u8> {
+            let mut spki = P256_SPKI_PREFIX.to_vec();
+            spki.extend_from_slice(self.key_pair.public_key().as_ref());
+            spki
+        }
+
+        pub fn client_data(&self, kind: &str, challenge: &str, origin: &str) -> Vec<u8> {
+            serde_json::json!({ "type": kind, "challenge": challenge, "origin": origin })
+                .to_string()
+                .into_bytes()
+        }
+
+        pub fn authenticator_data(&mut self, rp_id: &str) -> Vec<u8> {
+            self.sign_count += 1;
+            let mut auth_data = digest::digest(&digest::SHA256, rp_id.as_bytes())
+                .as_ref()
+                .to_vec();
+            auth_data.push(FLAG_USER_PRESENT);
+            auth_data.extend_from_slice(&self.sign_count.to_be_bytes());
+            auth_data
+        }
+
+        pub fn sign(&self, auth_data: &[u8], client_data_json: &[u8]) -> Vec<u8> {
+            let mut signed = auth_data.to_vec();
+            signed.extend_from_slice(digest::digest(&digest::SHA256, client_data_json).as_ref());
+            self.key_pair
+                .sign(&self.rng, &signed)
+                .unwrap()
+                .as_ref()
+                .to_vec()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_authenticator::TestAuthenticator;
+    use super::*;
+
+    const ORIGIN: &str = "https://pathfinder.example";
+    const RP_ID: &str = "pathfinder.example";
+
+    #[test]
+    fn test_valid_assertion_verifies() {
+        let mut authenticator = TestAuthenticator::new();
+        let public_key = public_key_from_spki(&authenticator.spki()).unwrap();
+
+        let client_data = authenticator.client_data(GET_TYPE, "abc", ORIGIN);
+        let auth_data = authenticator.authenticator_data(RP_ID);
+        let signature = authenticator.sign(&auth_data, &client_data);
+
+        let parsed = parse_client_data(&client_data, GET_TYPE, ORIGIN).unwrap();
+        assert_eq!(parsed.challenge, "abc");
+        assert_eq!(check_authenticator_data(&auth_data, RP_ID), Ok(1));
+        assert_eq!(
+            verify_signature(&public_key, &auth_data, &client_data, &signature),
+            Ok(())
+        );
+
+        // Any change to what was signed breaks the signature
+        let tampered = authenticator.client_data(GET_TYPE, "abd", ORIGIN);
+        assert_eq!(
+            verify_signature(&public_key, &auth_data, &tampered, &signature),
+            Err(PasskeyError::BadSignature)
+        );
+    }
+
+    #[test]
+    fn test_wrong_ceremony_origin_and_rp_rejected() {
+        let mut authenticator = TestAuthenticator::new();
+
+        let client_data = authenticator.client_data(CREATE_TYPE, "abc", ORIGIN);
+        assert_eq!(
+            parse_client_data(&client_data, GET_TYPE, ORIGIN).unwrap_err(),
+            PasskeyError::WrongType
+        );
+
+        let client_data = authenticator.client_data(GET_TYPE, "abc", "https://evil.example");
+        assert_eq!(
+            parse_client_data(&client_data, GET_TYPE, ORIGIN).unwrap_err(),
+            PasskeyError::WrongOrigin
+        );
+
+        let auth_data = authenticator.authenticator_data("evil.example");
+        assert_eq!(
+            check_authenticator_data(&auth_data, RP_ID),
+            Err(PasskeyError::WrongRelyingParty)
+        );
+    }
+
+    #[test]
+    fn test_only_p256_keys_supported() {
+        assert_eq!(
+            public_key_from_spki(&[0x30, 0x2a]),
+            Err(PasskeyError::UnsupportedKey)
+        );
+        let mut spki = TestAuthenticator::new().spki();
+        spki.push(0);
+        assert_eq!(
+            public_key_from_spki(&spki),
+            Err(PasskeyError::UnsupportedKey)
+        );
+    }
+
+    #[test]
+    fn test_sign_count() {
+        assert_eq!(check_sign_count(0, 0), Ok(()));
+        assert_eq!(check_sign_count(4, 5), Ok(()));
+        assert_eq!(check_sign_count(5, 5), Err(PasskeyError::CounterRegressed));
+        assert_eq!(check_sign_count(5, 0), Err(PasskeyError::CounterRegressed));
+    }
+}
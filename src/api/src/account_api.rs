@@ -0,0 +1,956 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{Extension, Json},
+    routing::{get, post},
+    Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::account::{
+    normalize_email,
+    passkey::{self, PasskeyError},
+    Challenge,
+};
+use crate::api_error::ApiError;
+use crate::audit::AuditActor;
+use crate::db::{
+    models::{DbAccount, DbPasskey, DbUser, NewMagicLink, NewPasskey},
+    Repository,
+};
+use crate::http_api::{
+    authenticate_credentials, authenticate_user, find_user, track_session, ApiState,
+};
+use crate::security::{
+    session::SessionInfo,
+    user_cookie::UserIdentity,
+    utils::{generate_token, hash_token},
+};
+
+/// Longest credential id accepted; authenticators use at most 1023 bytes
+const MAX_CREDENTIAL_ID_LENGTH: usize = 1366;
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct AccountQuery {
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiAccount {
+    /// None until an address has been confirmed
+    pub email: Option<String>,
+    pub passkeys: Vec<ApiPasskey>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiPasskey {
+    pub credential_id: String,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AttachEmailRequest {
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+    pub email: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EmailLoginRequest {
+    pub email: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MagicLinkRequest {
+    pub token: String,
+    /// The identity the device is currently playing as, merged into the
+    /// account when signing in
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MergeRequest {
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+    /// The identity to merge into the first one; it stops working afterwards
+    pub other_user_id: Option<String>,
+    pub other_cookie_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MergeResponse {
+    /// Game entries moved over from the other identity
+    pub merged_entries: u64,
+}
+
+/// The identity a signed in device should play as from now on
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiAccountSession {
+    pub user_id: String,
+    pub cookie_token: String,
+    pub email: Option<String>,
+    /// Game entries moved over from the identity the device was playing as
+    pub merged_entries: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiPasskeyOptions {
+    /// base64url challenge to pass to navigator.credentials
+    pub challenge: String,
+    pub rp_id: String,
+    /// base64url user handle for registration; None when signing in
+    pub user_handle: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RegisterPasskeyRequest {
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+    /// base64url fields from the browser's PublicKeyCredential
+    pub credential_id: String,
+    pub public_key: String,
+    pub authenticator_data: String,
+    pub client_data_json: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PasskeyLoginRequest {
+    /// base64url fields from the browser's PublicKeyCredential
+    pub credential_id: String,
+    pub authenticator_data: String,
+    pub client_data_json: String,
+    pub signature: String,
+    /// The identity the device is currently playing as, merged into the
+    /// account when signing in
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+}
+
+/// Routes under /api/account. Accounts are optional: an anonymous user can
+/// attach an email address or passkeys to sign in as the same user on other
+/// devices, and anonymous play keeps working for everyone else.
+pub fn account_router<R: Repository + Clone + Send + Sync + 'static>() -> Router<ApiState<R>> {
+    Router::new()
+        .route("/api/account", get(get_account))
+        .route("/api/account/email", post(attach_email))
+        .route("/api/account/merge", post(merge_identities))
+        .route(
+            "/api/account/passkeys/options",
+            post(passkey_register_options),
+        )
+        .route("/api/account/passkeys", post(register_passkey))
+        .route("/api/account/login/email", post(request_email_login))
+        .route("/api/account/magic-link", post(use_magic_link))
+        .route(
+            "/api/account/login/passkey/options",
+            post(passkey_login_options),
+        )
+        .route("/api/account/login/passkey", post(passkey_login))
+}
+
+impl From<DbPasskey> for ApiPasskey {
+    fn from(passkey: DbPasskey) -> Self {
+        ApiPasskey {
+            credential_id: passkey.credential_id,
+            created_at: passkey.created_at.to_rfc3339(),
+            last_used_at: passkey.last_used_at.map(|dt| dt.to_rfc3339()),
+        }
+    }
+}
+
+fn internal_error(e: anyhow::Error) -> ApiError {
+    ApiError::Internal(e.context("Account operation failed"))
+}
+
+fn passkey_error(e: PasskeyError) -> ApiError {
+    warn!("Rejected passkey: {}", e);
+    let message = format!("Passkey rejected: {e}");
+    match e {
+        PasskeyError::Malformed(_) | PasskeyError::UnsupportedKey => ApiError::validation(message),
+        _ => ApiError::unauthorized(message),
+    }
+}
+
+fn invalid_email() -> ApiError {
+    ApiError::validation("That isn't a valid email address")
+}
+
+fn unknown_account() -> ApiError {
+    ApiError::unauthorized("Unknown account")
+}
+
+async fn get_account<R: Repository + Clone + Send + Sync + 'static>(
+    Query(query): Query<AccountQuery>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+) -> Result<Json<ApiAccount>, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        query.user_id.as_ref(),
+        query.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+
+    let Some(account) = state
+        .repository
+        .get_account_by_user(&user.id)
+        .await
+        .map_err(internal_error)?
+    else {
+        return Ok(Json(ApiAccount {
+            email: None,
+            passkeys: vec![],
+        }));
+    };
+
+    let passkeys = state
+        .repository
+        .get_account_passkeys(&account.id)
+        .await
+        .map_err(internal_error)?;
+
+    Ok(Json(ApiAccount {
+        email: account.email,
+        passkeys: passkeys.into_iter().map(ApiPasskey::from).collect(),
+    }))
+}
+
+/// Email a link that confirms an address for the user's account. The address
+/// is only attached once the link is used.
+async fn attach_email<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+    Json(request): Json<AttachEmailRequest>,
+) -> Result<StatusCode, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        request.user_id.as_ref(),
+        request.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+    let email = normalize_email(&request.email).ok_or_else(invalid_email)?;
+
+    send_magic_link(&state, email, Some(user.id)).await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+/// Email a sign in link if an account has this address. The response is the
+/// same either way so it can't be used to find out who has an account.
+async fn request_email_login<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    Json(request): Json<EmailLoginRequest>,
+) -> Result<StatusCode, ApiError> {
+    let email = normalize_email(&request.email).ok_or_else(invalid_email)?;
+
+    let account = state
+        .repository
+        .get_account_by_email(&email)
+        .await
+        .map_err(internal_error)?;
+    if account.is_some() {
+        send_magic_link(&state, email, None).await?;
+    } else {
+        info!("Sign in requested for an address with no account");
+    }
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn send_magic_link<R: Repository + Clone + Send + Sync + 'static>(
+    state: &ApiState<R>,
+    email: String,
+    user_id: Option<String>,
+) -> Result<(), ApiError> {
+    let token = generate_token();
+    let ttl = chrono::Duration::from_std(state.accounts.config.magic_link_ttl)
+        .map_err(|e| internal_error(e.into()))?;
+    let attach = user_id.is_some();
+
+    state
+        .repository
+        .create_magic_link(NewMagicLink {
+            token_hash: hash_token(&token),
+            email: email.clone(),
+            user_id,
+            expires_at: Utc::now() + ttl,
+        })
+        .await
+        .map_err(internal_error)?;
+
+    state
+        .accounts
+        .send_magic_link(&email, &token, attach)
+        .await
+        .map_err(internal_error)
+}
+
+/// Use an emailed link. A link that attaches an address confirms it for that
+/// user's account; a sign in link signs the device in to the account with the
+/// address.
+async fn use_magic_link<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+    Json(request): Json<MagicLinkRequest>,
+) -> Result<Json<ApiAccountSession>, ApiError> {
+    let link = state
+        .repository
+        .use_magic_link(&hash_token(&request.token))
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| ApiError::unauthorized("This link is invalid or has expired"))?;
+
+    let existing = state
+        .repository
+        .get_account_by_email(&link.email)
+        .await
+        .map_err(internal_error)?;
+
+    let account = match link.user_id {
+        Some(user_id) => {
+            let account = state
+                .repository
+                .get_or_create_account(&user_id)
+                .await
+                .map_err(internal_error)?;
+            if existing.is_some_and(|existing| existing.id != account.id) {
+                return Err(ApiError::conflict(
+                    "That address belongs to another account",
+                ));
+            }
+            state
+                .repository
+                .set_account_email(&account.id, &link.email)
+                .await
+                .map_err(internal_error)?;
+            DbAccount {
+                email: Some(link.email),
+                ..account
+            }
+        }
+        None => existing.ok_or_else(|| ApiError::unauthorized("No account uses this address"))?,
+    };
+
+    let current = current_user(&state, &identity, request.user_id, request.cookie_token).await;
+    sign_in(&state, &identity, account, current, session.as_deref())
+        .await
+        .map(Json)
+}
+
+/// Merge another anonymous identity, e.g. from a second device, into the
+/// user's. The user gets an account if they didn't have one.
+async fn merge_identities<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+    Json(request): Json<MergeRequest>,
+) -> Result<Json<MergeResponse>, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        request.user_id.as_ref(),
+        request.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+    let other = authenticate_credentials(
+        &state,
+        request.other_user_id.as_ref(),
+        request.other_cookie_token.as_ref(),
+    )
+    .await?;
+    if user.id == other.id {
+        return Err(ApiError::validation("Can't merge an identity into itself"));
+    }
+
+    state
+        .repository
+        .get_or_create_account(&user.id)
+        .await
+        .map_err(internal_error)?;
+    let merged_entries = merge_into(&state, &user.id, &other.id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::conflict("That identity has an account of its own and can't be merged")
+        })?;
+
+    Ok(Json(MergeResponse { merged_entries }))
+}
+
+async fn passkey_register_options<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+    Json(request): Json<AccountQuery>,
+) -> Result<Json<ApiPasskeyOptions>, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        request.user_id.as_ref(),
+        request.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+    let account = state
+        .repository
+        .get_or_create_account(&user.id)
+        .await
+        .map_err(internal_error)?;
+
+    let challenge = state
+        .accounts
+        .issue_challenge(Challenge::Register {
+            account_id: account.id.clone(),
+        })
+        .await;
+
+    Ok(Json(ApiPasskeyOptions {
+        challenge,
+        rp_id: state.accounts.config.rp_id.clone(),
+        user_handle: Some(passkey::encode(account.id.as_bytes())),
+    }))
+}
+
+async fn register_passkey<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+    Json(request): Json<RegisterPasskeyRequest>,
+) -> Result<Json<ApiPasskey>, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        request.user_id.as_ref(),
+        request.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+    let config = &state.accounts.config;
+
+    let client_data_json =
+        passkey::decode(&request.client_data_json, "client data").map_err(passkey_error)?;
+    let client_data =
+        passkey::parse_client_data(&client_data_json, passkey::CREATE_TYPE, &config.origin)
+            .map_err(passkey_error)?;
+    let account = state
+        .repository
+        .get_account_by_user(&user.id)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| ApiError::unauthorized("There is no account to add a passkey to"))?;
+    let expected = Challenge::Register {
+        account_id: account.id.clone(),
+    };
+    if state.accounts.take_challenge(&client_data.challenge).await != Some(expected) {
+        return Err(passkey_error(PasskeyError::UnknownChallenge));
+    }
+
+    let auth_data = passkey::decode(&request.authenticator_data, "authenticator data")
+        .map_err(passkey_error)?;
+    let sign_count =
+        passkey::check_authenticator_data(&auth_data, &config.rp_id).map_err(passkey_error)?;
+    let spki = passkey::decode(&request.public_key, "public key").map_err(passkey_error)?;
+    let public_key = passkey::public_key_from_spki(&spki).map_err(passkey_error)?;
+
+    let credential_id = request.credential_id;
+    if credential_id.is_empty() || credential_id.len() > MAX_CREDENTIAL_ID_LENGTH {
+        return Err(ApiError::validation("Credential id is empty or too long"));
+    }
+    passkey::decode(&credential_id, "credential id").map_err(passkey_error)?;
+    if state
+        .repository
+        .get_passkey(&credential_id)
+        .await
+        .map_err(internal_error)?
+        .is_some()
+    {
+        return Err(ApiError::conflict("This passkey is already registered"));
+    }
+
+    let passkey = state
+        .repository
+        .add_passkey(NewPasskey {
+            credential_id,
+            account_id: account.id,
+            public_key: passkey::encode(&public_key),
+            sign_count: sign_count.into(),
+        })
+        .await
+        .map_err(internal_error)?;
+
+    Ok(Json(passkey.into()))
+}
+
+async fn passkey_login_options<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+) -> Json<ApiPasskeyOptions> {
+    let challenge = state.accounts.issue_challenge(Challenge::Login).await;
+    Json(ApiPasskeyOptions {
+        challenge,
+        rp_id: state.accounts.config.rp_id.clone(),
+        user_handle: None,
+    })
+}
+
+async fn passkey_login<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+    Json(request): Json<PasskeyLoginRequest>,
+) -> Result<Json<ApiAccountSession>, ApiError> {
+    let config = &state.accounts.config;
+
+    let client_data_json =
+        passkey::decode(&request.client_data_json, "client data").map_err(passkey_error)?;
+    let client_data =
+        passkey::parse_client_data(&client_data_json, passkey::GET_TYPE, &config.origin)
+            .map_err(passkey_error)?;
+    if state.accounts.take_challenge(&client_data.challenge).await != Some(Challenge::Login) {
+        return Err(passkey_error(PasskeyError::UnknownChallenge));
+    }
+
+    let stored = state
+        .repository
+        .get_passkey(&request.credential_id)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| ApiError::unauthorized("Unknown passkey"))?;
+
+    let auth_data = passkey::decode(&request.authenticator_data, "authenticator data")
+        .map_err(passkey_error)?;
+    let sign_count =
+        passkey::check_authenticator_data(&auth_data, &config.rp_id).map_err(passkey_error)?;
+    let stored_count = u32::try_from(stored.sign_count).unwrap_or(u32::MAX);
+    passkey::check_sign_count(stored_count, sign_count).map_err(passkey_error)?;
+
+    let public_key =
+        passkey::decode(&stored.public_key, "public key").map_err(|e| internal_error(e.into()))?;
+    let signature = passkey::decode(&request.signature, "signature").map_err(passkey_error)?;
+    passkey::verify_signature(&public_key, &auth_data, &client_data_json, &signature)
+        .map_err(passkey_error)?;
+
+    state
+        .repository
+        .update_passkey_sign_count(&stored.credential_id, sign_count.into())
+        .await
+        .map_err(internal_error)?;
+    let account = state
+        .repository
+        .get_account_by_id(&stored.account_id)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(unknown_account)?;
+
+    let current = current_user(&state, &identity, request.user_id, request.cookie_token).await;
+    sign_in(&state, &identity, account, current, session.as_deref())
+        .await
+        .map(Json)
+}
+
+/// The identity a device says it is playing as, if its cookie or credentials
+/// are valid. Signing in never fails because of stale credentials.
+async fn current_user<R: Repository + Clone + Send + Sync + 'static>(
+    state: &ApiState<R>,
+    identity: &UserIdentity,
+    user_id: Option<String>,
+    cookie_token: Option<String>,
+) -> Option<DbUser> {
+    find_user(state, identity, user_id.as_ref(), cookie_token.as_ref())
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Sign a device in to an account, merging the anonymous identity it was
+/// playing as so its history isn't lost
+async fn sign_in<R: Repository + Clone + Send + Sync + 'static>(
+    state: &ApiState<R>,
+    identity: &UserIdentity,
+    account: DbAccount,
+    current: Option<DbUser>,
+    session: Option<&SessionInfo>,
+) -> Result<ApiAccountSession, ApiError> {
+    let user = state
+        .repository
+        .get_user_by_id(&account.user_id)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(unknown_account)?;
+
+    let merged_entries = match current {
+        Some(current) if current.id != user.id => {
+            // Identities that are accounts themselves are left alone; the
+            // device just switches to the account it signed in to
+            merge_into(state, &user.id, &current.id).await?.unwrap_or(0)
+        }
+        _ => 0,
+    };
+
+    track_session(state, &user.id, session).await;
+    identity.issue(&user);
+    info!("Signed in to account {}", account.id);
+
+    Ok(ApiAccountSession {
+        user_id: user.id,
+        cookie_token: user.cookie_token,
+        email: account.email,
+        merged_entries,
+    })
+}
+
+/// Merge an anonymous identity into a user, returning how many entries moved,
+/// or None if the identity has an account of its own and can't be merged
+async fn merge_into<R: Repository + Clone + Send + Sync + 'static>(
+    state: &ApiState<R>,
+    into_user_id: &str,
+    from_user_id: &str,
+) -> Result<Option<u64>, ApiError> {
+    if state
+        .repository
+        .get_account_by_user(from_user_id)
+        .await
+        .map_err(internal_error)?
+        .is_some()
+    {
+        return Ok(None);
+    }
+
+    let merged = state
+        .repository
+        .merge_users(into_user_id, from_user_id)
+        .await
+        .map_err(internal_error)?;
+    state.user_stats.invalidate(into_user_id).await;
+    info!(
+        "Merged user {} into {} ({} entries)",
+        from_user_id, into_user_id, merged
+    );
+    state
+        .audit
+        .record(
+            &AuditActor::User(into_user_id.to_string()),
+            "user.merge",
+            Some(from_user_id),
+            None,
+            Some(serde_json::json!({ "into": into_user_id, "merged_entries": merged })),
+        )
+        .await;
+    Ok(Some(merged))
+}
+
+#[cfg(all(test, feature = "database-tests"))]
+mod tests {
+    use super::*;
+    use axum::http::Method;
+    use std::sync::{Arc, Mutex};
+    use tower::util::ServiceExt;
+
+    use crate::account::{
+        mailer::Mailer, passkey::test_authenticator::TestAuthenticator, AccountConfig, Accounts,
+    };
+    use crate::db::{models::NewGameEntry, SqliteRepository};
+    use crate::http_api::create_secure_router;
+    use crate::security::SecurityConfig;
+    use crate::test_utils::{create_new_test_game, create_test_game_engine, create_test_request};
+
+    /// Keeps sent emails so tests can follow the links in them
+    #[derive(Default)]
+    struct CapturingMailer {
+        sent: Mutex<Vec<(String, String)>>,
+    }
+
+    impl CapturingMailer {
+        /// The token from the last magic link sent to an address
+        fn token_for(&self, to: &str) -> Option<String> {
+            let sent = self.sent.lock().unwrap();
+            let (_, body) = sent.iter().rev().find(|(address, _)| address == to)?;
+            let start = body.find("token=")? + "token=".len();
+            let token = body[start..].split_whitespace().next()?;
+            Some(token.to_string())
+        }
+    }
+
+    #[axum::async_trait]
+    impl Mailer for CapturingMailer {
+        async fn send(&self, to: &str, _subject: &str, body: &str) -> anyhow::Result<()> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((to.to_string(), body.to_string()));
+            Ok(())
+        }
+    }
+
+    fn setup_account_app(
+        pool: sqlx::Pool<sqlx::Sqlite>,
+    ) -> (SqliteRepository, Arc<CapturingMailer>, Router) {
+        let repository = SqliteRepository::new(pool);
+        let mailer = Arc::new(CapturingMailer::default());
+        let (game_engine, _wordlist) = create_test_game_engine();
+        let state = ApiState::new(repository.clone(), game_engine)
+            .with_accounts(Accounts::new(AccountConfig::default(), mailer.clone()));
+        let app = create_secure_router(state, SecurityConfig::default());
+        (repository, mailer, app)
+    }
+
+    async fn send(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        let body = body.to_string();
+        let request = create_test_request(method, uri, Some(&body));
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    /// An anonymous user with an entry for a game on the given date
+    async fn create_player(
+        repository: &SqliteRepository,
+        app: &Router,
+        date: &str,
+    ) -> (String, String) {
+        let (status, user) = send(app, Method::POST, "/api/user", serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+        let user_id = user["user_id"].as_str().unwrap().to_string();
+        let cookie_token = user["cookie_token"].as_str().unwrap().to_string();
+
+        let game = match repository.get_game_by_date(date).await.unwrap() {
+            Some(game) => game,
+            None => {
+                let mut new_game = create_new_test_game();
+                new_game.date = date.to_string();
+                new_game.sequence_number = date[8..].parse().unwrap();
+                let (game, _) = repository
+                    .create_game_with_answers(new_game, vec![], None)
+                    .await
+                    .unwrap();
+                game
+            }
+        };
+        repository
+            .create_or_update_game_entry(NewGameEntry {
+                user_id: user_id.clone(),
+                game_id: game.id,
+                answers_data: "[]".to_string(),
+                total_score: 10,
+                completed: true,
+                suspicion_score: 0,
+                exclude: false,
+                request_id: None,
+            })
+            .await
+            .unwrap();
+
+        (user_id, cookie_token)
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_email_sign_in_merges_other_device(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let (repository, mailer, app) = setup_account_app(pool);
+        let (phone_id, phone_token) = create_player(&repository, &app, "2024-01-01").await;
+        let (laptop_id, laptop_token) = create_player(&repository, &app, "2024-01-02").await;
+
+        // Attach an address on the phone and confirm it
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            "/api/account/email",
+            serde_json::json!({"user_id": phone_id, "cookie_token": phone_token, "email": " Player@Example.com"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let token = mailer.token_for("player@example.com").unwrap();
+        let (status, session) = send(
+            &app,
+            Method::POST,
+            "/api/account/magic-link",
+            serde_json::json!({"token": token, "user_id": phone_id, "cookie_token": phone_token}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(session["email"], "player@example.com");
+
+        // Links work once
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            "/api/account/magic-link",
+            serde_json::json!({"token": token}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        // Sign in on the laptop, bringing its history along
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            "/api/account/login/email",
+            serde_json::json!({"email": "player@example.com"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let token = mailer.token_for("player@example.com").unwrap();
+        let (status, session) = send(
+            &app,
+            Method::POST,
+            "/api/account/magic-link",
+            serde_json::json!({"token": token, "user_id": laptop_id, "cookie_token": laptop_token}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(session["user_id"], phone_id.as_str());
+        assert_eq!(session["cookie_token"], phone_token.as_str());
+        assert_eq!(session["merged_entries"], 1);
+        assert!(repository
+            .get_user_by_id(&laptop_id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_email_login_does_not_reveal_accounts(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let (_repository, mailer, app) = setup_account_app(pool);
+
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            "/api/account/login/email",
+            serde_json::json!({"email": "nobody@example.com"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert!(mailer.token_for("nobody@example.com").is_none());
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_passkey_register_and_sign_in(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let (repository, _mailer, app) = setup_account_app(pool);
+        let (user_id, cookie_token) = create_player(&repository, &app, "2024-01-01").await;
+        let mut authenticator = TestAuthenticator::new();
+        let credential_id = passkey::encode(b"test-credential");
+        let origin = "http://localhost:5173";
+
+        let (status, options) = send(
+            &app,
+            Method::POST,
+            "/api/account/passkeys/options",
+            serde_json::json!({"user_id": user_id, "cookie_token": cookie_token}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let challenge = options["challenge"].as_str().unwrap();
+        let client_data = authenticator.client_data(passkey::CREATE_TYPE, challenge, origin);
+        let auth_data = authenticator.authenticator_data("localhost");
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            "/api/account/passkeys",
+            serde_json::json!({
+                "user_id": user_id,
+                "cookie_token": cookie_token,
+                "credential_id": credential_id,
+                "public_key": passkey::encode(&authenticator.spki()),
+                "authenticator_data": passkey::encode(&auth_data),
+                "client_data_json": passkey::encode(&client_data),
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        // Sign in from a fresh device
+        let (status, options) = send(
+            &app,
+            Method::POST,
+            "/api/account/login/passkey/options",
+            serde_json::json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let challenge = options["challenge"].as_str().unwrap();
+        let client_data = authenticator.client_data(passkey::GET_TYPE, challenge, origin);
+        let auth_data = authenticator.authenticator_data("localhost");
+        let login = serde_json::json!({
+            "credential_id": credential_id,
+            "authenticator_data": passkey::encode(&auth_data),
+            "client_data_json": passkey::encode(&client_data),
+            "signature": passkey::encode(&authenticator.sign(&auth_data, &client_data)),
+        });
+        let (status, session) = send(
+            &app,
+            Method::POST,
+            "/api/account/login/passkey",
+            login.clone(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(session["user_id"], user_id.as_str());
+
+        // The challenge was used up
+        let (status, _) = send(&app, Method::POST, "/api/account/login/passkey", login).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_merge_identities(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let (repository, _mailer, app) = setup_account_app(pool);
+        let (user_id, cookie_token) = create_player(&repository, &app, "2024-01-01").await;
+        let (other_id, other_token) = create_player(&repository, &app, "2024-01-02").await;
+
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            "/api/account/merge",
+            serde_json::json!({
+                "user_id": user_id,
+                "cookie_token": cookie_token,
+                "other_user_id": user_id,
+                "other_cookie_token": cookie_token,
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, merged) = send(
+            &app,
+            Method::POST,
+            "/api/account/merge",
+            serde_json::json!({
+                "user_id": user_id,
+                "cookie_token": cookie_token,
+                "other_user_id": other_id,
+                "other_cookie_token": other_token,
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(merged["merged_entries"], 1);
+        assert!(repository
+            .get_user_by_id(&other_id)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(repository
+            .get_account_by_user(&user_id)
+            .await
+            .unwrap()
+            .is_some());
+    }
+}
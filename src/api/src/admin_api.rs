@@ -0,0 +1,1419 @@
+use anyhow::Context;
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    response::{Json, Response},
+    routing::{delete, get, post},
+    Router,
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+use tracing::{error, info};
+
+use crate::api_error::ApiError;
+use crate::audit::AuditActor;
+use crate::db::{
+    models::{
+        AuditFilter, DbAdminToken, DbAuditEntry, DbGameDensity, DbWordProposal, NewAdminToken,
+        NewGameWithAnswers, NewTournament, OptimalAnswer,
+    },
+    Repository,
+};
+use crate::game::density::{correlation, BoardDensity};
+use crate::game::input_guard::RejectionCounts;
+use crate::game::language::Language;
+use crate::game::mode::GameMode;
+use crate::game::quality::BoardQuality;
+use crate::game_batch::{BatchError, StagedBatch};
+use crate::game_cache::GameTags;
+use crate::http_api::{
+    convert_db_game_to_api_game_direct, latest_started_date, parse_api_board, ApiBoard, ApiGame,
+    ApiState,
+};
+use crate::memory_profiler::MemorySample;
+use crate::metrics::metrics_response;
+use crate::progress_api::ApiProgress;
+use crate::security::{
+    admin::{AdminAuthLayer, AdminCaller, AdminScope},
+    policy::RoutePolicies,
+    utils::{generate_token, hash_token},
+    SecurityConfig,
+};
+use crate::tile_fairness::ApiTileFairnessReport;
+use crate::tournament_api::ApiTournament;
+use crate::wordlist::VariantUsage;
+
+/// Scoped tokens can live for at most 90 days
+const MAX_TOKEN_LIFETIME_HOURS: i64 = 90 * 24;
+
+/// Games covered by the density report unless the request asks for more
+const DEFAULT_DENSITY_REPORT_GAMES: i32 = 90;
+const MAX_DENSITY_REPORT_GAMES: i32 = 365;
+
+/// Memory samples returned unless the request asks for more
+const DEFAULT_MEMORY_SAMPLES: usize = 120;
+
+/// Days of upcoming games shown for review
+const UPCOMING_GAME_DAYS: i64 = 7;
+
+/// Tournament names are shown in lists, so are kept short
+const MAX_TOURNAMENT_NAME_LENGTH: usize = 100;
+
+/// Audit entries returned unless the request asks for more
+const DEFAULT_AUDIT_ENTRIES: i64 = 100;
+const MAX_AUDIT_ENTRIES: i64 = 1000;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StageBatchRequest {
+    /// Optional label used in the announcement, e.g. "Holiday week"
+    pub name: Option<String>,
+    /// Dates (YYYY-MM-DD) to generate games for
+    pub dates: Vec<String>,
+    /// Theme specs by date, e.g. `{"2025-12-25": "word:snow"}`, for boards
+    /// that must contain a word or letters
+    #[serde(default)]
+    pub themes: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiStagedBatch {
+    pub batch_id: String,
+    pub name: Option<String>,
+    pub created_at: String,
+    pub games: Vec<ApiStagedGame>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiStagedGame {
+    pub date: String,
+    pub board: ApiBoard,
+    pub threshold_score: i32,
+    pub word_count: usize,
+    pub optimal_score: Option<i32>,
+    pub theme: Option<String>,
+    /// What the quality analyzer found on the board, for reviewers to weigh
+    pub quality: Option<BoardQuality>,
+}
+
+/// A generated game that hasn't gone live anywhere yet, with what a
+/// reviewer needs to judge it
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiUpcomingGame {
+    pub game: ApiGame,
+    pub theme: Option<String>,
+    pub word_count: usize,
+    /// The best words the generator found, highest scoring first
+    pub optimal_words: Vec<OptimalAnswer>,
+    pub optimal_score: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InvalidateGameResponse {
+    /// Cached games dropped; a game cached under an old id, date or number
+    /// counts separately
+    pub invalidated: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateTournamentRequest {
+    pub name: String,
+    /// The first and last dates (YYYY-MM-DD) whose daily games count
+    pub start_date: String,
+    pub end_date: String,
+    /// The language whose daily games count, English unless given
+    #[serde(default)]
+    pub language: Language,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReplaceGameResponse {
+    /// The new revision, now served for the replaced game's date and number
+    pub game: ApiGame,
+    pub replaced_game_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PublishBatchResponse {
+    pub games: Vec<ApiGame>,
+    pub announced: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateAdminTokenRequest {
+    pub scope: AdminScope,
+    /// Optional note on who or what the token is for
+    pub label: Option<String>,
+    pub expires_in_hours: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiAdminToken {
+    pub id: String,
+    pub scope: String,
+    pub label: Option<String>,
+    pub created_at: String,
+    pub expires_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateAdminTokenResponse {
+    /// The token itself. Only its hash is stored, so it can't be shown again.
+    pub token: String,
+    pub details: ApiAdminToken,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DensityReportQuery {
+    pub limit: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MemoryReportQuery {
+    pub limit: Option<usize>,
+}
+
+/// Which audit entries to list. Unset fields match every entry.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AuditQuery {
+    /// e.g. "admin:bootstrap", "admin:<token id>" or "user:<id>"
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub target: Option<String>,
+    /// RFC 3339 times bounding when the entries were made
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiAuditEntry {
+    pub id: String,
+    pub actor: String,
+    pub action: String,
+    pub target: Option<String>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub created_at: String,
+}
+
+/// Recent memory samples alongside the size of the in-process caches, to
+/// tell growth in the caches apart from growth elsewhere
+#[derive(Serialize, Debug)]
+pub struct ApiMemoryReport {
+    pub interval_secs: u64,
+    /// Most samples kept; older ones have been dropped
+    pub capacity: usize,
+    /// Oldest first
+    pub samples: Vec<MemorySample>,
+    pub caches: ApiCacheSizes,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ApiCacheSizes {
+    pub games: u64,
+    pub practice_games: u64,
+    pub path_responses: u64,
+    pub path_response_bytes: u64,
+    pub preview_images: u64,
+    pub preview_image_bytes: u64,
+}
+
+/// How board density relates to whether players finish, to guide tuning the
+/// generator's threshold
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiDensityReport {
+    /// Newest first
+    pub games: Vec<ApiGameDensity>,
+    pub correlations: ApiDensityCorrelations,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiGameDensity {
+    pub game_id: String,
+    pub date: String,
+    pub sequence_number: i32,
+    pub threshold_score: i32,
+    #[serde(flatten)]
+    pub density: BoardDensity,
+    /// What the quality analyzer found on the board; None for games
+    /// generated before it was recorded
+    pub quality: Option<BoardQuality>,
+    pub players_started: i32,
+    pub players_completed: i32,
+    /// Share of players who started the game and completed it, 0 to 1;
+    /// None until someone starts it
+    pub completion_rate: Option<f64>,
+}
+
+/// Pearson correlation of each density metric with completion rate over the
+/// reported games someone started. None when there's too little data.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ApiDensityCorrelations {
+    pub valid_words: Option<f64>,
+    pub words_per_tile: Option<f64>,
+    pub average_word_length: Option<f64>,
+    pub wildcard_reliance: Option<f64>,
+}
+
+impl From<DbGameDensity> for ApiGameDensity {
+    fn from(game: DbGameDensity) -> Self {
+        ApiGameDensity {
+            completion_rate: (game.players_started > 0)
+                .then(|| game.players_completed as f64 / game.players_started as f64),
+            game_id: game.game_id,
+            date: game.date,
+            sequence_number: game.sequence_number,
+            threshold_score: game.threshold_score,
+            density: game.density,
+            quality: game.quality,
+            players_started: game.players_started,
+            players_completed: game.players_completed,
+        }
+    }
+}
+
+impl ApiDensityCorrelations {
+    fn from_games(games: &[ApiGameDensity]) -> Self {
+        let with_metric = |metric: fn(&BoardDensity) -> f64| {
+            let pairs: Vec<(f64, f64)> = games
+                .iter()
+                .filter_map(|game| Some((metric(&game.density), game.completion_rate?)))
+                .collect();
+            correlation(&pairs)
+        };
+
+        ApiDensityCorrelations {
+            valid_words: with_metric(|density| density.valid_words as f64),
+            words_per_tile: with_metric(|density| density.words_per_tile),
+            average_word_length: with_metric(|density| density.average_word_length),
+            wildcard_reliance: with_metric(|density| density.wildcard_reliance),
+        }
+    }
+}
+
+/// Routes under /api/admin, each guarded by an admin bearer token allowed
+/// the route's scope. Scoped tokens are looked up in the repository.
+pub fn admin_router<R: Repository + Clone + Send + Sync + 'static>(
+    config: &SecurityConfig,
+    policies: &RoutePolicies,
+    repository: R,
+) -> Router<ApiState<R>> {
+    Router::new()
+        .route("/api/admin/batches", post(stage_batch))
+        .route(
+            "/api/admin/batches/:batch_id",
+            get(get_staged_batch).delete(discard_staged_batch),
+        )
+        .route("/api/admin/batches/:batch_id/publish", post(publish_batch))
+        .route("/api/admin/input-rejections", get(get_input_rejections))
+        .route("/api/admin/spelling-variants", get(get_spelling_variants))
+        .route("/api/admin/wordlist-proposals", get(get_wordlist_proposals))
+        .route(
+            "/api/admin/users/:user_id/games/:game_id/revisions",
+            get(get_entry_revisions),
+        )
+        .route("/api/admin/density-report", get(get_density_report))
+        .route("/api/admin/tile-fairness", get(get_tile_fairness))
+        .route("/api/admin/memory", get(get_memory_report))
+        .route("/api/admin/cache", delete(clear_game_cache))
+        .route("/api/admin/cache/games/:game_id", delete(invalidate_game))
+        .route("/api/admin/games/:game_id/replace", post(replace_game))
+        .route("/api/admin/tournaments", post(create_tournament))
+        .route(
+            "/api/admin/tokens",
+            get(list_admin_tokens).post(create_admin_token),
+        )
+        .route("/api/admin/tokens/:token_id", delete(revoke_admin_token))
+        .route("/api/admin/audit", get(get_audit_log))
+        .route("/api/games/upcoming", get(get_upcoming_games))
+        .route("/metrics", get(get_metrics))
+        .route_layer(
+            AdminAuthLayer::new(config.clone())
+                .with_policies(policies.clone())
+                .with_tokens(Arc::new(repository)),
+        )
+}
+
+impl From<BatchError> for ApiError {
+    fn from(e: BatchError) -> Self {
+        match e {
+            BatchError::InvalidRequest(_) => ApiError::validation(e.to_string()),
+            BatchError::DateConflict(ref dates) => {
+                let dates = dates.clone();
+                ApiError::conflict(e.to_string())
+                    .with_details(serde_json::json!({ "dates": dates }))
+            }
+            BatchError::NotFound => ApiError::NotFound(e.to_string()),
+            BatchError::Internal(e) => ApiError::Internal(e.context("Batch operation failed")),
+        }
+    }
+}
+
+impl TryFrom<&StagedBatch> for ApiStagedBatch {
+    type Error = ApiError;
+
+    fn try_from(batch: &StagedBatch) -> Result<Self, Self::Error> {
+        let games = batch
+            .games
+            .iter()
+            .map(ApiStagedGame::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(ApiStagedBatch {
+            batch_id: batch.id.clone(),
+            name: batch.name.clone(),
+            created_at: batch.created_at.to_rfc3339(),
+            games,
+        })
+    }
+}
+
+impl From<DbAuditEntry> for ApiAuditEntry {
+    fn from(entry: DbAuditEntry) -> Self {
+        let parse = |data: Option<String>| data.and_then(|data| serde_json::from_str(&data).ok());
+        ApiAuditEntry {
+            id: entry.id,
+            actor: entry.actor,
+            action: entry.action,
+            target: entry.target,
+            before: parse(entry.before_data),
+            after: parse(entry.after_data),
+            created_at: entry.created_at.to_rfc3339(),
+        }
+    }
+}
+
+impl From<DbAdminToken> for ApiAdminToken {
+    fn from(token: DbAdminToken) -> Self {
+        ApiAdminToken {
+            id: token.id,
+            scope: token.scope,
+            label: token.label,
+            created_at: token.created_at.to_rfc3339(),
+            expires_at: token.expires_at.to_rfc3339(),
+        }
+    }
+}
+
+impl TryFrom<&NewGameWithAnswers> for ApiStagedGame {
+    type Error = ApiError;
+
+    fn try_from(staged: &NewGameWithAnswers) -> Result<Self, Self::Error> {
+        Ok(ApiStagedGame {
+            date: staged.game.date.clone(),
+            board: parse_api_board(&staged.game.board_data)?,
+            threshold_score: staged.game.threshold_score,
+            word_count: staged.answers.len(),
+            optimal_score: staged
+                .optimal_solution
+                .as_ref()
+                .map(|solution| solution.total_score),
+            theme: staged.game.theme.clone(),
+            quality: staged.quality.clone(),
+        })
+    }
+}
+
+async fn stage_batch<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    Extension(caller): Extension<AdminCaller>,
+    Json(request): Json<StageBatchRequest>,
+) -> Result<Json<ApiStagedBatch>, ApiError> {
+    let batch = state
+        .batch_publisher
+        .stage(request.name, &request.dates, &request.themes)
+        .await?;
+    let staged = ApiStagedBatch::try_from(batch.as_ref())?;
+
+    state
+        .audit
+        .record(
+            &AuditActor::from(&caller),
+            "batch.stage",
+            Some(&staged.batch_id),
+            None,
+            serde_json::to_value(&staged).ok(),
+        )
+        .await;
+
+    Ok(Json(staged))
+}
+
+async fn get_staged_batch<R: Repository + Clone + Send + Sync + 'static>(
+    Path(batch_id): Path<String>,
+    State(state): State<ApiState<R>>,
+) -> Result<Json<ApiStagedBatch>, ApiError> {
+    let batch = state
+        .batch_publisher
+        .get(&batch_id)
+        .await
+        .ok_or_else(|| ApiError::from(BatchError::NotFound))?;
+
+    ApiStagedBatch::try_from(batch.as_ref()).map(Json)
+}
+
+async fn discard_staged_batch<R: Repository + Clone + Send + Sync + 'static>(
+    Path(batch_id): Path<String>,
+    State(state): State<ApiState<R>>,
+    Extension(caller): Extension<AdminCaller>,
+) -> Result<StatusCode, ApiError> {
+    let before = state
+        .batch_publisher
+        .get(&batch_id)
+        .await
+        .and_then(|batch| ApiStagedBatch::try_from(batch.as_ref()).ok());
+    if !state.batch_publisher.discard(&batch_id).await {
+        return Err(BatchError::NotFound.into());
+    }
+
+    state
+        .audit
+        .record(
+            &AuditActor::from(&caller),
+            "batch.discard",
+            Some(&batch_id),
+            before.and_then(|batch| serde_json::to_value(batch).ok()),
+            None,
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Publish a staged batch, then warm the game cache for every new game before
+/// announcing the batch so the first players after the post are served from cache
+async fn publish_batch<R: Repository + Clone + Send + Sync + 'static>(
+    Path(batch_id): Path<String>,
+    State(state): State<ApiState<R>>,
+    Extension(caller): Extension<AdminCaller>,
+) -> Result<Json<PublishBatchResponse>, ApiError> {
+    let published = state.batch_publisher.publish(&batch_id).await?;
+    state
+        .audit
+        .record(
+            &AuditActor::from(&caller),
+            "batch.publish",
+            Some(&batch_id),
+            None,
+            serde_json::to_value(&published.games).ok(),
+        )
+        .await;
+
+    let mut games = Vec::with_capacity(published.games.len());
+    for db_game in &published.games {
+        // The games are already committed, so a board that fails to convert
+        // is only logged rather than failing the whole response
+        match convert_db_game_to_api_game_direct(db_game.clone()) {
+            Ok(api_game) => {
+                // Anything cached under the new game's date or number is stale
+                let displaced = state.game_cache.insert(api_game.clone()).await;
+                state.metrics.record_cache_invalidations("game", displaced);
+                games.push(api_game);
+            }
+            Err(_) => error!("Failed to convert published game {}", db_game.id),
+        }
+    }
+
+    let announced = state.batch_publisher.announce(&published).await;
+
+    Ok(Json(PublishBatchResponse { games, announced }))
+}
+
+/// Counts of words turned away before the dictionary lookup since startup,
+/// to see how often players try other languages
+async fn get_input_rejections<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+) -> Json<RejectionCounts> {
+    Json(state.game_engine.rejection_counts())
+}
+
+/// Spellings added to the dictionary as variants of words it has, with how
+/// often each has been accepted since startup
+async fn get_spelling_variants<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+) -> Json<Vec<VariantUsage>> {
+    Json(state.game_engine.variant_usage())
+}
+
+/// Words players challenged into their entries from the reference list, most
+/// challenged first, as candidates for the wordlist
+async fn get_wordlist_proposals<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+) -> Result<Json<Vec<DbWordProposal>>, ApiError> {
+    let proposals = state
+        .repository
+        .get_wordlist_proposals()
+        .await
+        .context("Failed to load wordlist proposals")?;
+    Ok(Json(proposals))
+}
+
+/// A player's autosaved revisions for a game, newest first, for tracing
+/// reports of answers going missing
+async fn get_entry_revisions<R: Repository + Clone + Send + Sync + 'static>(
+    Path((user_id, game_id)): Path<(String, String)>,
+    State(state): State<ApiState<R>>,
+) -> Result<Json<ApiProgress>, ApiError> {
+    let revisions = state
+        .repository
+        .get_entry_revisions(&user_id, &game_id)
+        .await
+        .with_context(|| format!("Failed to load revisions of {user_id}'s entry on {game_id}"))?;
+    ApiProgress::from_revisions(revisions).map(Json)
+}
+
+/// Request, database, cache, generation and memory metrics for Prometheus
+async fn get_metrics<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+) -> Response {
+    state
+        .metrics
+        .set_cache_size("game", state.game_cache.entry_count(), None);
+    metrics_response(&state.metrics)
+}
+
+/// Density of recently generated boards against their completion rates
+async fn get_density_report<R: Repository + Clone + Send + Sync + 'static>(
+    Query(query): Query<DensityReportQuery>,
+    State(state): State<ApiState<R>>,
+) -> Result<Json<ApiDensityReport>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_DENSITY_REPORT_GAMES)
+        .clamp(1, MAX_DENSITY_REPORT_GAMES);
+
+    let games: Vec<ApiGameDensity> = state
+        .repository
+        .get_game_densities(limit)
+        .await
+        .context("Failed to load game densities")?
+        .into_iter()
+        .map(ApiGameDensity::from)
+        .collect();
+    let correlations = ApiDensityCorrelations::from_games(&games);
+
+    Ok(Json(ApiDensityReport {
+        games,
+        correlations,
+    }))
+}
+
+/// How generated tiles compare with the generator's configured frequencies.
+/// The report is recomputed on a schedule; this returns the latest one.
+async fn get_tile_fairness<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+) -> Result<Json<ApiTileFairnessReport>, ApiError> {
+    let report = state
+        .tile_fairness
+        .get(&state.repository)
+        .await
+        .context("Failed to compute tile fairness")?;
+    Ok(Json(report))
+}
+
+/// The games generated for the week after the latest live date, so they can
+/// be reviewed before they go live. Dates without a game yet are skipped.
+async fn get_upcoming_games<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+) -> Result<Json<Vec<ApiUpcomingGame>>, ApiError> {
+    let latest_live = latest_started_date();
+    let mut upcoming = Vec::new();
+    for day in 1..=UPCOMING_GAME_DAYS {
+        let date = (latest_live + Duration::days(day))
+            .format("%Y-%m-%d")
+            .to_string();
+        let Some(game) = state
+            .repository
+            .get_game_by_date(&date)
+            .await
+            .context("Failed to get upcoming game")?
+        else {
+            continue;
+        };
+
+        let word_count = state
+            .repository
+            .get_game_words(&game.id)
+            .await
+            .context("Failed to get game words")?
+            .len();
+        let mut optimal_words = state
+            .repository
+            .get_optimal_solutions(&game.id)
+            .await
+            .context("Failed to get optimal solution")?;
+        optimal_words.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.word.cmp(&b.word)));
+        let theme = game.theme.clone();
+
+        upcoming.push(ApiUpcomingGame {
+            game: convert_db_game_to_api_game_direct(game)?,
+            theme,
+            word_count,
+            optimal_score: optimal_words.iter().map(|answer| answer.score).sum(),
+            optimal_words,
+        });
+    }
+    Ok(Json(upcoming))
+}
+
+/// Recent process memory samples and cache sizes
+async fn get_memory_report<R: Repository + Clone + Send + Sync + 'static>(
+    Query(query): Query<MemoryReportQuery>,
+    State(state): State<ApiState<R>>,
+) -> Json<ApiMemoryReport> {
+    let config = state.memory.config();
+    let limit = query.limit.unwrap_or(DEFAULT_MEMORY_SAMPLES);
+
+    Json(ApiMemoryReport {
+        interval_secs: config.interval.as_secs(),
+        capacity: config.capacity,
+        samples: state.memory.recent(limit),
+        caches: ApiCacheSizes {
+            games: state.game_cache.entry_count(),
+            practice_games: state.practice_cache.entry_count(),
+            path_responses: state.paths_cache.entry_count(),
+            path_response_bytes: state.paths_cache.weighted_size(),
+            preview_images: state.preview_cache.entry_count(),
+            preview_image_bytes: state.preview_cache.weighted_size(),
+        },
+    })
+}
+
+/// Drop every cached game, path response and preview image so the next
+/// requests load them from the database
+async fn clear_game_cache<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    Extension(caller): Extension<AdminCaller>,
+) -> StatusCode {
+    let invalidated = state.game_cache.invalidate_all();
+    state
+        .metrics
+        .record_cache_invalidations("game", invalidated);
+    state.paths_cache.invalidate_all();
+    state.preview_cache.invalidate_all();
+    info!("Cleared the game, paths and preview caches");
+    state
+        .audit
+        .record(
+            &AuditActor::from(&caller),
+            "cache.clear",
+            None,
+            Some(serde_json::json!({ "games": invalidated })),
+            None,
+        )
+        .await;
+    StatusCode::NO_CONTENT
+}
+
+/// Drop a game from the game cache under its id, date and sequence number,
+/// e.g. after its board was replaced in the database. Its path responses
+/// and preview images are dropped with it.
+async fn invalidate_game<R: Repository + Clone + Send + Sync + 'static>(
+    Path(game_id): Path<String>,
+    State(state): State<ApiState<R>>,
+    Extension(caller): Extension<AdminCaller>,
+) -> Result<Json<InvalidateGameResponse>, ApiError> {
+    let game = state
+        .repository
+        .get_game_by_id(&game_id)
+        .await
+        .context("Failed to get game")?;
+    let tags = match &game {
+        Some(game) => GameTags::of_db_game(game),
+        // A game no longer in the database may still be cached under its id
+        None => GameTags {
+            game_id: Some(game_id.clone()),
+            ..GameTags::default()
+        },
+    };
+
+    let invalidated = state.game_cache.invalidate(&tags).await;
+    state
+        .metrics
+        .record_cache_invalidations("game", invalidated);
+    state.paths_cache.invalidate_game(&game_id);
+    state.preview_cache.invalidate_game(&game_id);
+    info!(
+        "Invalidated {} cached games for game {}",
+        invalidated, game_id
+    );
+    // The game as the database has it now, which the cache will serve from here on
+    state
+        .audit
+        .record(
+            &AuditActor::from(&caller),
+            "cache.invalidate_game",
+            Some(&game_id),
+            None,
+            game.and_then(|game| serde_json::to_value(game).ok()),
+        )
+        .await;
+
+    Ok(Json(InvalidateGameResponse { invalidated }))
+}
+
+/// Replace a broken daily board with a newly generated revision. The old
+/// revision is kept, so players partway through it can finish and their
+/// scores stand, but its date and number now serve the new board.
+async fn replace_game<R: Repository + Clone + Send + Sync + 'static>(
+    Path(game_id): Path<String>,
+    State(state): State<ApiState<R>>,
+    Extension(caller): Extension<AdminCaller>,
+) -> Result<Json<ReplaceGameResponse>, ApiError> {
+    let game = state
+        .repository
+        .get_game_by_id(&game_id)
+        .await
+        .context("Failed to get game")?
+        .ok_or_else(|| ApiError::not_found("Game"))?;
+    if game.is_replaced() {
+        return Err(ApiError::conflict("Game has already been replaced"));
+    }
+    if game.game_mode() != GameMode::Daily {
+        return Err(ApiError::validation("Only daily games can be replaced"));
+    }
+
+    let replacement = state
+        .game_generator
+        .replace_game(&game)
+        .await
+        .context("Failed to replace game")?
+        // Another admin replaced it first
+        .ok_or_else(|| ApiError::conflict("Game has already been replaced"))?;
+    info!(
+        "Replaced game {} for {} with revision {} as game {}",
+        game.id, game.date, replacement.revision, replacement.id
+    );
+    state
+        .audit
+        .record(
+            &AuditActor::from(&caller),
+            "game.replace",
+            Some(&game_id),
+            serde_json::to_value(&game).ok(),
+            serde_json::to_value(&replacement).ok(),
+        )
+        .await;
+
+    // The old revision is dropped from the cache along with its date and
+    // number, and is loaded again by id for players still on it
+    let api_game = convert_db_game_to_api_game_direct(replacement)?;
+    let displaced = state.game_cache.insert(api_game.clone()).await;
+    state.metrics.record_cache_invalidations("game", displaced);
+
+    Ok(Json(ReplaceGameResponse {
+        game: api_game,
+        replaced_game_id: game_id,
+    }))
+}
+
+/// Define a tournament over a range of dates, which players can then join
+async fn create_tournament<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    Extension(caller): Extension<AdminCaller>,
+    Json(request): Json<CreateTournamentRequest>,
+) -> Result<Json<ApiTournament>, ApiError> {
+    let name = request.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_TOURNAMENT_NAME_LENGTH {
+        return Err(ApiError::validation(format!(
+            "Tournament name must be 1 to {MAX_TOURNAMENT_NAME_LENGTH} characters"
+        )));
+    }
+    let parse_date = |date: &str| {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| ApiError::validation(format!("Invalid date: {date}")))
+    };
+    let start_date = parse_date(&request.start_date)?;
+    let end_date = parse_date(&request.end_date)?;
+    if end_date < start_date {
+        return Err(ApiError::validation(
+            "Tournament must end on or after its start date",
+        ));
+    }
+
+    let tournament = state
+        .repository
+        .create_tournament(NewTournament {
+            name: name.to_string(),
+            start_date: start_date.format("%Y-%m-%d").to_string(),
+            end_date: end_date.format("%Y-%m-%d").to_string(),
+            language: request.language.to_string(),
+        })
+        .await
+        .context("Failed to create tournament")?;
+    info!(
+        "Created tournament {} from {} to {}",
+        tournament.id, tournament.start_date, tournament.end_date
+    );
+    state
+        .audit
+        .record(
+            &AuditActor::from(&caller),
+            "tournament.create",
+            Some(&tournament.id),
+            None,
+            serde_json::to_value(&tournament).ok(),
+        )
+        .await;
+
+    Ok(Json(ApiTournament::from(&tournament)))
+}
+
+async fn list_admin_tokens<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+) -> Result<Json<Vec<ApiAdminToken>>, ApiError> {
+    let tokens = state
+        .repository
+        .get_admin_tokens()
+        .await
+        .context("Failed to list admin tokens")?;
+
+    Ok(Json(tokens.into_iter().map(ApiAdminToken::from).collect()))
+}
+
+/// Create a scoped token. Superadmin tokens can't be created; that scope
+/// belongs to the bootstrap token alone.
+async fn create_admin_token<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    Extension(caller): Extension<AdminCaller>,
+    Json(request): Json<CreateAdminTokenRequest>,
+) -> Result<Json<CreateAdminTokenResponse>, ApiError> {
+    if request.scope == AdminScope::Superadmin {
+        return Err(ApiError::validation(
+            "Superadmin tokens can't be created through the API",
+        ));
+    }
+    if !(1..=MAX_TOKEN_LIFETIME_HOURS).contains(&request.expires_in_hours) {
+        return Err(ApiError::validation(format!(
+            "expires_in_hours must be between 1 and {MAX_TOKEN_LIFETIME_HOURS}"
+        )));
+    }
+
+    let token = generate_token();
+    let stored = state
+        .repository
+        .create_admin_token(NewAdminToken {
+            token_hash: hash_token(&token),
+            scope: request.scope.to_string(),
+            label: request.label,
+            expires_at: Utc::now() + Duration::hours(request.expires_in_hours),
+        })
+        .await
+        .context("Failed to create admin token")?;
+    info!(
+        "Created {} admin token {} expiring {}",
+        stored.scope, stored.id, stored.expires_at
+    );
+    let details = ApiAdminToken::from(stored);
+    state
+        .audit
+        .record(
+            &AuditActor::from(&caller),
+            "admin_token.create",
+            Some(&details.id),
+            None,
+            serde_json::to_value(&details).ok(),
+        )
+        .await;
+
+    Ok(Json(CreateAdminTokenResponse { token, details }))
+}
+
+async fn revoke_admin_token<R: Repository + Clone + Send + Sync + 'static>(
+    Path(token_id): Path<String>,
+    State(state): State<ApiState<R>>,
+    Extension(caller): Extension<AdminCaller>,
+) -> Result<StatusCode, ApiError> {
+    let token = state
+        .repository
+        .revoke_admin_token(&token_id)
+        .await
+        .with_context(|| format!("Failed to revoke admin token {token_id}"))?
+        .ok_or_else(|| ApiError::not_found("Admin token"))?;
+    info!("Revoked {} admin token {}", token.scope, token.id);
+    state
+        .audit
+        .record(
+            &AuditActor::from(&caller),
+            "admin_token.revoke",
+            Some(&token.id),
+            serde_json::to_value(ApiAdminToken::from(token.clone())).ok(),
+            None,
+        )
+        .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Recorded sensitive operations, newest first, to reconstruct what changed
+/// and who changed it
+async fn get_audit_log<R: Repository + Clone + Send + Sync + 'static>(
+    Query(query): Query<AuditQuery>,
+    State(state): State<ApiState<R>>,
+) -> Result<Json<Vec<ApiAuditEntry>>, ApiError> {
+    let parse_time = |name: &str, value: Option<String>| {
+        value
+            .map(|value| {
+                DateTime::parse_from_rfc3339(&value)
+                    .map(|time| time.with_timezone(&Utc))
+                    .map_err(|_| ApiError::validation(format!("{name} must be an RFC 3339 time")))
+            })
+            .transpose()
+    };
+    let filter = AuditFilter {
+        actor: query.actor,
+        action: query.action,
+        target: query.target,
+        since: parse_time("since", query.since)?,
+        until: parse_time("until", query.until)?,
+        limit: query
+            .limit
+            .unwrap_or(DEFAULT_AUDIT_ENTRIES)
+            .clamp(1, MAX_AUDIT_ENTRIES),
+    };
+
+    let entries = state
+        .repository
+        .get_audit_entries(&filter)
+        .await
+        .context("Failed to get audit log")?;
+
+    Ok(Json(entries.into_iter().map(ApiAuditEntry::from).collect()))
+}
+
+#[cfg(all(test, feature = "database-tests"))]
+mod tests {
+    use super::*;
+    use axum::http::Method;
+    use tower::util::ServiceExt;
+
+    use crate::db::{models::NewOptimalSolution, SqliteRepository};
+    use crate::game::language::Language;
+    use crate::game_cache::GameAlias;
+    use crate::http_api::create_secure_router;
+    use crate::memory_profiler::MemoryProfiler;
+    use crate::test_utils::{create_new_test_game, create_test_game_engine, create_test_request};
+
+    const ADMIN_TOKEN: &str = "test-admin-token";
+
+    fn setup_admin_app(pool: sqlx::Pool<sqlx::Sqlite>) -> Router {
+        let (game_engine, _wordlist) = create_test_game_engine();
+        let state = ApiState::new(SqliteRepository::new(pool), game_engine);
+        let config = SecurityConfig {
+            admin_token: Some(ADMIN_TOKEN.to_string()),
+            ..Default::default()
+        };
+        create_secure_router(state, config)
+    }
+
+    fn admin_request(
+        method: Method,
+        uri: &str,
+        body: Option<&str>,
+    ) -> axum::http::Request<axum::body::Body> {
+        bearer_request(method, uri, ADMIN_TOKEN, body)
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_admin_routes_require_token(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let app = setup_admin_app(pool);
+
+        let request = create_test_request(Method::GET, "/api/admin/batches/missing", None);
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let request = admin_request(Method::GET, "/api/admin/batches/missing", None);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_metrics_are_labelled_by_route(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let app = setup_admin_app(pool);
+
+        let request = create_test_request(Method::GET, "/api/game/missing/words", None);
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let request = create_test_request(Method::GET, "/metrics", None);
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let request = admin_request(Method::GET, "/metrics", None);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains(
+            "pathfinder_http_requests_total{method=\"GET\",route=\"/api/game/:game_id/words\",status=\"200\"} 1\n"
+        ));
+        assert!(body.contains(
+            "pathfinder_http_requests_total{method=\"GET\",route=\"/metrics\",status=\"401\"} 1\n"
+        ));
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_memory_report(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let (game_engine, _wordlist) = create_test_game_engine();
+        let mut profiler = MemoryProfiler::new();
+        for checkpoint in ["startup", "after_game_engine_init", "after_api_state"] {
+            profiler.log_memory(checkpoint);
+        }
+        let state = ApiState::new(SqliteRepository::new(pool), game_engine)
+            .with_memory_history(profiler.history());
+        let config = SecurityConfig {
+            admin_token: Some(ADMIN_TOKEN.to_string()),
+            ..Default::default()
+        };
+        let app = create_secure_router(state, config);
+
+        let request = admin_request(Method::GET, "/api/admin/memory?limit=2", None);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        let checkpoints: Vec<&str> = report["samples"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|sample| sample["checkpoint"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            checkpoints,
+            vec!["after_game_engine_init", "after_api_state"]
+        );
+        assert_eq!(report["caches"]["games"], 0);
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_stage_batch_rejects_invalid_dates(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let app = setup_admin_app(pool);
+
+        let body = r#"{"name": "Theme week", "dates": ["2999-01-01", "2999-01-01"]}"#;
+        let request = admin_request(Method::POST, "/api/admin/batches", Some(body));
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error["code"], "validation");
+        assert_eq!(error["message"], "duplicate date: 2999-01-01");
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_density_report(pool: sqlx::Pool<sqlx::Sqlite>) {
+        use crate::db::models::{NewGameEntry, NewUser};
+        use crate::game::quality::QualityIssue;
+        use crate::test_utils::create_new_test_game;
+
+        let repo = SqliteRepository::new(pool.clone());
+        let app = setup_admin_app(pool);
+
+        // Denser boards are finished more often
+        let games: Vec<NewGameWithAnswers> = [("2025-06-01", 20), ("2025-06-02", 40)]
+            .into_iter()
+            .enumerate()
+            .map(|(i, (date, valid_words))| {
+                let mut game = create_new_test_game();
+                game.date = date.to_string();
+                game.sequence_number = i as i32 + 1;
+                NewGameWithAnswers {
+                    game,
+                    answers: vec![],
+                    optimal_solution: None,
+                    density: Some(BoardDensity {
+                        valid_words,
+                        words_per_tile: valid_words as f64 / 16.0,
+                        average_word_length: 4.0,
+                        wildcard_reliance: 0.25,
+                    }),
+                    // Only the newer board had its quality recorded
+                    quality: (i == 1).then(|| BoardQuality {
+                        long_words: 2,
+                        issues: vec![QualityIssue::FewLongWords],
+                        ..BoardQuality::default()
+                    }),
+                }
+            })
+            .collect();
+        let games = repo.create_games_with_answers(games).await.unwrap();
+        for (game, completions) in games.iter().zip([[true, false], [true, true]]) {
+            for (player, completed) in completions.into_iter().enumerate() {
+                let user = repo
+                    .create_user(NewUser {
+                        cookie_token: format!("{}-{player}", game.date),
+                    })
+                    .await
+                    .unwrap();
+                repo.create_or_update_game_entry(NewGameEntry {
+                    user_id: user.id,
+                    game_id: game.id.clone(),
+                    answers_data: "[]".to_string(),
+                    total_score: 10,
+                    completed,
+                    suspicion_score: 0,
+                    exclude: false,
+                    request_id: None,
+                })
+                .await
+                .unwrap();
+            }
+        }
+
+        let request = admin_request(Method::GET, "/api/admin/density-report", None);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let report: ApiDensityReport = serde_json::from_slice(&body).unwrap();
+
+        let rates: Vec<_> = report
+            .games
+            .iter()
+            .map(|game| (game.date.as_str(), game.completion_rate))
+            .collect();
+        assert_eq!(
+            rates,
+            vec![("2025-06-02", Some(1.0)), ("2025-06-01", Some(0.5))]
+        );
+        assert_eq!(report.games[0].density.valid_words, 40);
+        let quality = report.games[0].quality.as_ref().unwrap();
+        assert_eq!(quality.long_words, 2);
+        assert_eq!(quality.issues, [QualityIssue::FewLongWords]);
+        assert_eq!(report.games[1].quality, None);
+        assert_eq!(report.correlations.valid_words, Some(1.0));
+        // Every board relied on wildcards equally, so there's nothing to correlate
+        assert_eq!(report.correlations.wildcard_reliance, None);
+    }
+
+    fn bearer_request(
+        method: Method,
+        uri: &str,
+        token: &str,
+        body: Option<&str>,
+    ) -> axum::http::Request<axum::body::Body> {
+        let mut request = create_test_request(method, uri, body);
+        request
+            .headers_mut()
+            .insert("authorization", format!("Bearer {token}").parse().unwrap());
+        request
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_scoped_token_lifecycle(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let app = setup_admin_app(pool);
+
+        // Superadmin tokens can only be the bootstrap token
+        let body = r#"{"scope": "superadmin", "expires_in_hours": 1}"#;
+        let request = admin_request(Method::POST, "/api/admin/tokens", Some(body));
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = r#"{"scope": "stats", "label": "dashboard", "expires_in_hours": 24}"#;
+        let request = admin_request(Method::POST, "/api/admin/tokens", Some(body));
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let created: CreateAdminTokenResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(created.details.scope, "stats");
+        let token = created.token;
+
+        let request = bearer_request(Method::GET, "/api/admin/input-rejections", &token, None);
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let request = bearer_request(Method::DELETE, "/api/admin/cache", &token, None);
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let request = bearer_request(Method::GET, "/api/admin/tokens", &token, None);
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let request = admin_request(Method::GET, "/api/admin/tokens", None);
+        let response = app.clone().oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let tokens: Vec<ApiAdminToken> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].id, created.details.id);
+
+        let uri = format!("/api/admin/tokens/{}", created.details.id);
+        let request = admin_request(Method::DELETE, &uri, None);
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let request = bearer_request(Method::GET, "/api/admin/input-rejections", &token, None);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_audit_log_records_token_changes(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let app = setup_admin_app(pool);
+
+        let body = r#"{"scope": "stats", "expires_in_hours": 24}"#;
+        let request = admin_request(Method::POST, "/api/admin/tokens", Some(body));
+        let response = app.clone().oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let created: CreateAdminTokenResponse = serde_json::from_slice(&body).unwrap();
+        let uri = format!("/api/admin/tokens/{}", created.details.id);
+        let request = admin_request(Method::DELETE, &uri, None);
+        app.clone().oneshot(request).await.unwrap();
+
+        // Scoped tokens can't read the audit log
+        let request = bearer_request(Method::GET, "/api/admin/audit", &created.token, None);
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let request = admin_request(Method::GET, "/api/admin/audit", None);
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let entries: Vec<ApiAuditEntry> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, "admin_token.revoke");
+        assert_eq!(entries[1].action, "admin_token.create");
+        for entry in &entries {
+            assert_eq!(entry.actor, "admin:bootstrap");
+            assert_eq!(entry.target.as_ref(), Some(&created.details.id));
+        }
+        assert_eq!(entries[0].before.as_ref().unwrap()["scope"], "stats");
+        let after = entries[1].after.as_ref().unwrap();
+        assert_eq!(after["id"], created.details.id.as_str());
+        assert!(!after.to_string().contains(&created.token));
+
+        let request = admin_request(
+            Method::GET,
+            "/api/admin/audit?action=admin_token.create&limit=5",
+            None,
+        );
+        let response = app.clone().oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let entries: Vec<ApiAuditEntry> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(entries.len(), 1);
+
+        let request = admin_request(Method::GET, "/api/admin/audit?since=yesterday", None);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_upcoming_games_cover_the_next_week(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let repository = SqliteRepository::new(pool.clone());
+        let latest_live = latest_started_date();
+        for (sequence_number, day) in [(1, 0), (2, 1), (3, 8)] {
+            let mut game = create_new_test_game();
+            game.date = (latest_live + Duration::days(day))
+                .format("%Y-%m-%d")
+                .to_string();
+            game.sequence_number = sequence_number;
+            let optimal = NewOptimalSolution {
+                game_id: String::new(),
+                words_and_scores: serde_json::to_string(&[("stop", 4), ("thing", 5)]).unwrap(),
+                total_score: 9,
+            };
+            repository
+                .create_game_with_answers(game, Vec::new(), Some(optimal))
+                .await
+                .unwrap();
+        }
+        let app = setup_admin_app(pool);
+
+        let request = create_test_request(Method::GET, "/api/games/upcoming", None);
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let request = admin_request(Method::GET, "/api/games/upcoming", None);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let upcoming: Vec<ApiUpcomingGame> = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(upcoming.len(), 1);
+        assert_eq!(upcoming[0].game.sequence_number, 2);
+        let words: Vec<&str> = upcoming[0]
+            .optimal_words
+            .iter()
+            .map(|answer| answer.word.as_str())
+            .collect();
+        assert_eq!(words, vec!["thing", "stop"]);
+        assert_eq!(upcoming[0].optimal_score, 9);
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_invalidating_a_game_drops_it_from_the_cache(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let (game_engine, _wordlist) = create_test_game_engine();
+        let state = ApiState::new(SqliteRepository::new(pool), game_engine);
+        let mut new_game = create_new_test_game();
+        new_game.sequence_number = 1;
+        let (game, _) = state
+            .repository
+            .create_game_with_answers(new_game, vec![], None)
+            .await
+            .unwrap();
+        let config = SecurityConfig {
+            admin_token: Some(ADMIN_TOKEN.to_string()),
+            ..Default::default()
+        };
+        let app = create_secure_router(state.clone(), config);
+
+        let request = create_test_request(Method::GET, "/api/game/sequence/1", None);
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let by_date = GameAlias::Date(Language::English, game.date.clone());
+        assert!(state.game_cache.get(&by_date).await.is_some());
+
+        let uri = format!("/api/admin/cache/games/{}", game.id);
+        let request = admin_request(Method::DELETE, &uri, None);
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let invalidated: InvalidateGameResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(invalidated.invalidated, 1);
+        assert!(state.game_cache.get(&by_date).await.is_none());
+        assert!(state
+            .game_cache
+            .get(&GameAlias::Sequence(Language::English, 1))
+            .await
+            .is_none());
+
+        let request = admin_request(Method::GET, "/metrics", None);
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("pathfinder_cache_invalidations_total{cache=\"game\"} 1\n"));
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_publish_unknown_batch_not_found(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let app = setup_admin_app(pool);
+
+        let request = admin_request(Method::POST, "/api/admin/batches/missing/publish", None);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,218 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use tracing::error;
+
+use crate::game::GameError;
+
+/// ApiError is why a request failed. Every variant is sent as an
+/// `ApiErrorBody`, so clients can show the message and branch on the code
+/// whichever handler failed.
+#[derive(Debug)]
+pub enum ApiError {
+    /// What was asked for doesn't exist, e.g. "Game not found"
+    NotFound(String),
+    /// The request is malformed or breaks a rule of the game
+    Validation {
+        message: String,
+        details: Option<Value>,
+    },
+    /// The caller isn't who they claim to be
+    Unauthorized(String),
+    /// The caller is known but not allowed to do this
+    Forbidden(String),
+    /// The request clashes with the current state, e.g. a name already taken
+    Conflict {
+        message: String,
+        details: Option<Value>,
+    },
+    /// Anything else. Logged, and reported to the client without detail.
+    Internal(anyhow::Error),
+}
+
+/// The JSON body of every error response
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApiErrorBody {
+    /// not_found, validation, unauthorized, forbidden, conflict or internal
+    pub code: String,
+    pub message: String,
+    pub details: Option<Value>,
+}
+
+impl ApiError {
+    pub fn not_found(what: &str) -> Self {
+        ApiError::NotFound(format!("{what} not found"))
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        ApiError::Validation {
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        ApiError::Unauthorized(message.into())
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        ApiError::Forbidden(message.into())
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        ApiError::Conflict {
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    /// with_details attaches machine readable context to a validation error
+    /// or conflict; other errors are returned unchanged
+    pub fn with_details<T: Serialize>(mut self, value: T) -> Self {
+        if let ApiError::Validation { details, .. } | ApiError::Conflict { details, .. } = &mut self
+        {
+            *details = serde_json::to_value(value).ok();
+        }
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Validation { .. } => StatusCode::BAD_REQUEST,
+            ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::Conflict { .. } => StatusCode::CONFLICT,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::NotFound(_) => "not_found",
+            ApiError::Validation { .. } => "validation",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::Forbidden(_) => "forbidden",
+            ApiError::Conflict { .. } => "conflict",
+            ApiError::Internal(_) => "internal",
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::NotFound(message)
+            | ApiError::Validation { message, .. }
+            | ApiError::Unauthorized(message)
+            | ApiError::Forbidden(message)
+            | ApiError::Conflict { message, .. } => write!(f, "{message}"),
+            ApiError::Internal(_) => write!(f, "Something went wrong, please try again"),
+        }
+    }
+}
+
+/// Repository and other internal failures become 500s, so handlers can use `?`
+/// after adding context with `anyhow::Context`
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        ApiError::Internal(e)
+    }
+}
+
+/// Answers the game won't accept are validation errors, with the error
+/// itself as details so clients can tell which word to fix and why
+impl From<GameError> for ApiError {
+    fn from(e: GameError) -> Self {
+        ApiError::validation(e.to_string()).with_details(&e)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        if let ApiError::Internal(e) = &self {
+            error!("Request failed: {e:#}");
+        }
+
+        let body = ApiErrorBody {
+            code: self.code().to_string(),
+            message: self.to_string(),
+            details: match &self {
+                ApiError::Validation { details, .. } | ApiError::Conflict { details, .. } => {
+                    details.clone()
+                }
+                _ => None,
+            },
+        };
+        (self.status(), Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    async fn body_of(error: ApiError) -> (StatusCode, ApiErrorBody) {
+        let response = error.into_response();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_errors_share_one_body_shape() {
+        let (status, body) = body_of(
+            ApiError::validation("Word 'qz' is not in the dictionary")
+                .with_details(serde_json::json!({ "word": "qz" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body,
+            ApiErrorBody {
+                code: "validation".to_string(),
+                message: "Word 'qz' is not in the dictionary".to_string(),
+                details: Some(serde_json::json!({ "word": "qz" })),
+            }
+        );
+
+        let (status, body) = body_of(ApiError::not_found("Game")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body.code, "not_found");
+        assert_eq!(body.message, "Game not found");
+        assert_eq!(body.details, None);
+    }
+
+    #[tokio::test]
+    async fn test_game_errors_name_the_word() {
+        let (status, body) = body_of(
+            GameError::NotInDictionary {
+                word: "qz".to_string(),
+            }
+            .into(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body.message, "Word 'qz' is not in the dictionary");
+        assert_eq!(
+            body.details,
+            Some(serde_json::json!({ "kind": "not_in_dictionary", "word": "qz" }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_internal_errors_hide_their_cause() {
+        let (status, body) = body_of(anyhow!("connection refused: db.internal:5432").into()).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body.code, "internal");
+        assert!(!body.message.contains("db.internal"));
+    }
+}
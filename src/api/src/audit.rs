@@ -0,0 +1,144 @@
+use serde_json::Value;
+use std::fmt;
+use tracing::error;
+
+use crate::db::{models::NewAuditEntry, Repository};
+use crate::security::admin::AdminCaller;
+
+/// Who made an audited change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditActor {
+    /// An admin, by the id of the scoped token they used, or None for the
+    /// bootstrap token
+    Admin { token_id: Option<String> },
+    /// A player, by user id
+    User(String),
+    /// The server itself, such as the scheduler generating games
+    System,
+}
+
+impl fmt::Display for AuditActor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditActor::Admin { token_id: None } => write!(f, "admin:bootstrap"),
+            AuditActor::Admin {
+                token_id: Some(token_id),
+            } => write!(f, "admin:{token_id}"),
+            AuditActor::User(user_id) => write!(f, "user:{user_id}"),
+            AuditActor::System => write!(f, "system"),
+        }
+    }
+}
+
+impl From<&AdminCaller> for AuditActor {
+    fn from(caller: &AdminCaller) -> Self {
+        AuditActor::Admin {
+            token_id: caller.token_id.clone(),
+        }
+    }
+}
+
+/// AuditLogger records sensitive operations, with who made them and
+/// snapshots of what they changed, so disputes can be traced back later.
+/// Recording is best effort: a failure is logged and never fails the
+/// operation being recorded.
+#[derive(Clone)]
+pub struct AuditLogger<R> {
+    repository: R,
+}
+
+impl<R: Repository> AuditLogger<R> {
+    pub fn new(repository: R) -> Self {
+        Self { repository }
+    }
+
+    /// record stores one operation. `before` and `after` are snapshots of the
+    /// target around the change, where there is one to take.
+    pub async fn record(
+        &self,
+        actor: &AuditActor,
+        action: &str,
+        target: Option<&str>,
+        before: Option<Value>,
+        after: Option<Value>,
+    ) {
+        let entry = NewAuditEntry {
+            actor: actor.to_string(),
+            action: action.to_string(),
+            target: target.map(str::to_string),
+            before_data: before.map(|before| before.to_string()),
+            after_data: after.map(|after| after.to_string()),
+        };
+        if let Err(e) = self.repository.record_audit_entry(entry).await {
+            error!(
+                "Failed to record {} by {} in the audit log: {}",
+                action, actor, e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{models::AuditFilter, InMemoryRepository};
+
+    #[test]
+    fn test_actor_names() {
+        assert_eq!(
+            AuditActor::Admin { token_id: None }.to_string(),
+            "admin:bootstrap"
+        );
+        assert_eq!(
+            AuditActor::Admin {
+                token_id: Some("abc".to_string())
+            }
+            .to_string(),
+            "admin:abc"
+        );
+        assert_eq!(AuditActor::User("42".to_string()).to_string(), "user:42");
+        assert_eq!(AuditActor::System.to_string(), "system");
+    }
+
+    #[tokio::test]
+    async fn test_record_stores_snapshots() {
+        let repository = InMemoryRepository::new();
+        let audit = AuditLogger::new(repository.clone());
+
+        audit
+            .record(
+                &AuditActor::System,
+                "game.generate",
+                Some("game-1"),
+                None,
+                Some(serde_json::json!({"date": "2025-06-01"})),
+            )
+            .await;
+        audit
+            .record(
+                &AuditActor::User("42".to_string()),
+                "user.merge",
+                Some("7"),
+                None,
+                None,
+            )
+            .await;
+
+        let entries = repository
+            .get_audit_entries(&AuditFilter {
+                action: Some("game.generate".to_string()),
+                limit: 10,
+                ..AuditFilter::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].actor, "system");
+        assert_eq!(entries[0].target.as_deref(), Some("game-1"));
+        assert_eq!(
+            entries[0].after_data.as_deref(),
+            Some(r#"{"date":"2025-06-01"}"#)
+        );
+        assert_eq!(entries[0].before_data, None);
+    }
+}
@@ -0,0 +1,368 @@
+pub mod s3;
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Utc;
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePool},
+    ConnectOptions,
+};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use s3::S3Storage;
+
+/// Backup file names are this prefix followed by the time they were taken,
+/// so sorting them by name sorts them by age
+const BACKUP_PREFIX: &str = "pathfinder-";
+const BACKUP_EXTENSION: &str = "db";
+
+#[derive(Clone, Debug)]
+pub struct BackupConfig {
+    pub enabled: bool,
+    /// How often a backup is taken while the server runs
+    pub interval: Duration,
+    /// Where backups are written before any upload
+    pub directory: PathBuf,
+    /// How many backups are kept in the directory; older ones are deleted
+    pub keep: usize,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: Duration::from_secs(6 * 60 * 60),
+            directory: PathBuf::from("backups"),
+            keep: 14,
+        }
+    }
+}
+
+impl BackupConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(enabled) = env::var("SQLITE_BACKUP_ENABLED") {
+            config.enabled = enabled.to_lowercase() == "true";
+        }
+
+        if let Ok(hours) = env::var("SQLITE_BACKUP_INTERVAL_HOURS") {
+            if let Ok(value) = hours.parse::<u64>() {
+                config.interval = Duration::from_secs(value.max(1) * 60 * 60);
+            }
+        }
+
+        if let Ok(directory) = env::var("SQLITE_BACKUP_DIR") {
+            config.directory = PathBuf::from(directory);
+        }
+
+        if let Ok(keep) = env::var("SQLITE_BACKUP_KEEP") {
+            if let Ok(value) = keep.parse::<usize>() {
+                config.keep = value.max(1);
+            }
+        }
+
+        config
+    }
+}
+
+/// SqliteBackups takes online backups of a live SQLite database, which keeps
+/// serving requests while each one is written
+#[derive(Clone)]
+pub struct SqliteBackups {
+    pool: SqlitePool,
+    config: BackupConfig,
+    storage: Option<S3Storage>,
+}
+
+impl SqliteBackups {
+    pub fn new(pool: SqlitePool, config: BackupConfig) -> Self {
+        Self {
+            pool,
+            config,
+            storage: None,
+        }
+    }
+
+    /// Upload each backup as well as keeping it in the backup directory
+    pub fn with_storage(mut self, storage: S3Storage) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
+    /// Take a backup now, returning where it was written. A backup is only
+    /// given its final name once it passes an integrity check, so a crash
+    /// part way through never leaves something that looks restorable.
+    pub async fn run(&self) -> Result<PathBuf> {
+        fs::create_dir_all(&self.config.directory).with_context(|| {
+            format!(
+                "Failed to create backup directory {}",
+                self.config.directory.display()
+            )
+        })?;
+
+        let file_name = format!(
+            "{BACKUP_PREFIX}{}.{BACKUP_EXTENSION}",
+            Utc::now().format("%Y%m%dT%H%M%SZ")
+        );
+        let path = self.config.directory.join(&file_name);
+        let partial = path.with_extension("partial");
+
+        backup_to(&self.pool, &partial).await?;
+        if let Err(e) = verify(&partial).await {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+        fs::rename(&partial, &path)?;
+
+        if let Some(storage) = &self.storage {
+            let key = storage.key_for(&file_name);
+            storage.put(&key, fs::read(&path)?).await?;
+            info!("Uploaded backup to {}", key);
+        }
+
+        prune(&self.config.directory, self.config.keep)?;
+        Ok(path)
+    }
+
+    /// Takes a backup in the background every interval, starting straight
+    /// away
+    pub fn start(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(self.config.interval);
+            loop {
+                ticks.tick().await;
+                match self.run().await {
+                    Ok(path) => info!("Backed up the database to {}", path.display()),
+                    Err(e) => error!("Failed to back up the database: {:#}", e),
+                }
+            }
+        })
+    }
+}
+
+/// Write a consistent copy of the database to `path` with VACUUM INTO, which
+/// reads inside one transaction so writers carry on meanwhile
+pub async fn backup_to(pool: &SqlitePool, path: &Path) -> Result<()> {
+    if path.exists() {
+        bail!("{} already exists", path.display());
+    }
+    let target = path
+        .to_str()
+        .ok_or_else(|| anyhow!("Backup path {} isn't valid UTF-8", path.display()))?;
+    sqlx::query("VACUUM INTO ?")
+        .bind(target)
+        .execute(pool)
+        .await
+        .with_context(|| format!("Failed to back up the database to {}", path.display()))?;
+    Ok(())
+}
+
+/// Check a database file opens and passes SQLite's integrity check
+pub async fn verify(path: &Path) -> Result<()> {
+    let mut connection = SqliteConnectOptions::new()
+        .filename(path)
+        .read_only(true)
+        .connect()
+        .await
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let result: String = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_one(&mut connection)
+        .await
+        .with_context(|| format!("Failed to check {}", path.display()))?;
+    if result != "ok" {
+        bail!("{} failed its integrity check: {}", path.display(), result);
+    }
+    Ok(())
+}
+
+/// Replace the database at `database` with `backup`, which must pass an
+/// integrity check first. The database being replaced, along with its WAL
+/// and shared memory files, is moved aside rather than deleted, and its new
+/// path is returned. The server must be stopped while this runs.
+pub async fn restore(backup: &Path, database: &Path) -> Result<Option<PathBuf>> {
+    verify(backup).await?;
+
+    let suffix = format!("replaced-{}", Utc::now().format("%Y%m%dT%H%M%SZ"));
+    let replaced = if database.exists() {
+        let replaced = with_suffix(database, &suffix);
+        fs::rename(database, &replaced)
+            .with_context(|| format!("Failed to move {} out of the way", database.display()))?;
+        Some(replaced)
+    } else {
+        None
+    };
+    for sidecar in ["-wal", "-shm"] {
+        let path = with_suffix(database, sidecar);
+        if path.exists() {
+            fs::rename(&path, with_suffix(&path, &suffix))?;
+        }
+    }
+
+    // Copy next to the database first so the final rename can't leave a
+    // half-written file in its place
+    let restoring = with_suffix(database, "restoring");
+    fs::copy(backup, &restoring).with_context(|| format!("Failed to copy {}", backup.display()))?;
+    fs::rename(&restoring, database)?;
+    verify(database).await?;
+    Ok(replaced)
+}
+
+/// The file a `sqlite:` connection url points at
+pub fn sqlite_path(database_url: &str) -> Result<PathBuf> {
+    let path = database_url
+        .strip_prefix("sqlite://")
+        .or_else(|| database_url.strip_prefix("sqlite:"))
+        .ok_or_else(|| anyhow!("{database_url} isn't a sqlite: url"))?;
+    let path = path.split('?').next().unwrap_or_default();
+    if path.is_empty() || path == ":memory:" {
+        bail!("{database_url} isn't a database file");
+    }
+    Ok(PathBuf::from(path))
+}
+
+/// Delete all but the newest `keep` backups in `directory`
+fn prune(directory: &Path, keep: usize) -> Result<()> {
+    let mut backups: Vec<PathBuf> = fs::read_dir(directory)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == BACKUP_EXTENSION)
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(BACKUP_PREFIX))
+        })
+        .collect();
+    backups.sort();
+
+    let excess = backups.len().saturating_sub(keep);
+    for path in &backups[..excess] {
+        if let Err(e) = fs::remove_file(path) {
+            warn!("Failed to delete old backup {}: {}", path.display(), e);
+        }
+    }
+    Ok(())
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    if !suffix.starts_with('-') {
+        name.push(".");
+    }
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sqlite_path() {
+        assert_eq!(
+            sqlite_path("sqlite://pathfinder.db").unwrap(),
+            PathBuf::from("pathfinder.db")
+        );
+        assert_eq!(
+            sqlite_path("sqlite:/var/lib/pathfinder/pathfinder.db?mode=rwc").unwrap(),
+            PathBuf::from("/var/lib/pathfinder/pathfinder.db")
+        );
+        assert!(sqlite_path("sqlite::memory:").is_err());
+        assert!(sqlite_path("postgres://localhost/pathfinder").is_err());
+    }
+
+    #[test]
+    fn test_prune_keeps_newest_backups() {
+        let directory = tempfile::tempdir().unwrap();
+        for stamp in ["20251120T000000Z", "20251121T000000Z", "20251122T000000Z"] {
+            fs::write(directory.path().join(format!("pathfinder-{stamp}.db")), b"").unwrap();
+        }
+        fs::write(directory.path().join("notes.db"), b"").unwrap();
+
+        prune(directory.path(), 2).unwrap();
+
+        let mut left: Vec<String> = fs::read_dir(directory.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        left.sort();
+        assert_eq!(
+            left,
+            [
+                "notes.db",
+                "pathfinder-20251121T000000Z.db",
+                "pathfinder-20251122T000000Z.db"
+            ]
+        );
+    }
+
+    #[cfg(feature = "database-tests")]
+    #[tokio::test]
+    async fn test_backup_and_restore() {
+        let directory = tempfile::tempdir().unwrap();
+        let database = directory.path().join("pathfinder.db");
+        let pool = SqlitePool::connect_with(
+            SqliteConnectOptions::new()
+                .filename(&database)
+                .create_if_missing(true),
+        )
+        .await
+        .unwrap();
+        sqlx::query("CREATE TABLE words (word TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO words (word) VALUES ('path')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let backups = SqliteBackups::new(
+            pool.clone(),
+            BackupConfig {
+                enabled: true,
+                directory: directory.path().join("backups"),
+                ..BackupConfig::default()
+            },
+        );
+        let backup = backups.run().await.unwrap();
+        assert!(backup.exists());
+
+        // Writes after the backup are lost by restoring it
+        sqlx::query("INSERT INTO words (word) VALUES ('finder')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+
+        let replaced = restore(&backup, &database).await.unwrap().unwrap();
+        assert!(replaced.exists());
+
+        let pool = SqlitePool::connect_with(SqliteConnectOptions::new().filename(&database))
+            .await
+            .unwrap();
+        let words: Vec<String> = sqlx::query_scalar("SELECT word FROM words")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(words, ["path"]);
+    }
+
+    #[cfg(feature = "database-tests")]
+    #[tokio::test]
+    async fn test_restore_refuses_a_corrupt_backup() {
+        let directory = tempfile::tempdir().unwrap();
+        let backup = directory.path().join("backup.db");
+        fs::write(&backup, b"not a database").unwrap();
+        let database = directory.path().join("pathfinder.db");
+        fs::write(&database, b"current").unwrap();
+
+        assert!(restore(&backup, &database).await.is_err());
+        assert_eq!(fs::read(&database).unwrap(), b"current");
+    }
+}
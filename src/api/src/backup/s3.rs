@@ -0,0 +1,248 @@
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use ring::{digest, hmac};
+use std::env;
+use url::Url;
+
+/// Where backups are uploaded in S3-compatible storage
+#[derive(Clone, Debug)]
+pub struct S3Config {
+    /// e.g. https://s3.us-east-1.amazonaws.com or another provider's endpoint
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Prepended to each backup's file name to make its key
+    pub prefix: String,
+}
+
+impl S3Config {
+    /// from_env returns the upload configuration, or None when BACKUP_S3_BUCKET
+    /// isn't set and backups stay local
+    pub fn from_env() -> Option<Self> {
+        let bucket = env::var("BACKUP_S3_BUCKET")
+            .ok()
+            .filter(|bucket| !bucket.is_empty())?;
+        let region = env::var("BACKUP_S3_REGION").unwrap_or_else(|_| "us-east-1".to_string());
+        Some(Self {
+            endpoint: env::var("BACKUP_S3_ENDPOINT")
+                .unwrap_or_else(|_| format!("https://s3.{region}.amazonaws.com")),
+            bucket,
+            region,
+            access_key_id: env::var("BACKUP_S3_ACCESS_KEY_ID").unwrap_or_default(),
+            secret_access_key: env::var("BACKUP_S3_SECRET_ACCESS_KEY").unwrap_or_default(),
+            prefix: env::var("BACKUP_S3_PREFIX").unwrap_or_else(|_| "backups/".to_string()),
+        })
+    }
+}
+
+/// S3Storage puts and gets whole objects with path-style URLs, signing each
+/// request with AWS Signature Version 4, which S3-compatible providers accept
+#[derive(Clone)]
+pub struct S3Storage {
+    config: S3Config,
+    client: reqwest::Client,
+}
+
+impl S3Storage {
+    pub fn new(config: S3Config) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn from_env() -> Option<Self> {
+        S3Config::from_env().map(Self::new)
+    }
+
+    /// The key a backup file is uploaded under
+    pub fn key_for(&self, file_name: &str) -> String {
+        format!("{}{}", self.config.prefix, file_name)
+    }
+
+    pub async fn put(&self, key: &str, body: Vec<u8>) -> Result<()> {
+        let request = self.sign("PUT", &self.config.bucket, key, &body, Utc::now())?;
+        let response = self
+            .client
+            .put(request.url)
+            .headers(request.headers)
+            .body(body)
+            .send()
+            .await
+            .with_context(|| format!("Failed to upload {key}"))?;
+        if !response.status().is_success() {
+            bail!("Uploading {} failed with {}", key, response.status());
+        }
+        Ok(())
+    }
+
+    /// get downloads an object, from the configured bucket unless another is
+    /// named
+    pub async fn get(&self, bucket: Option<&str>, key: &str) -> Result<Vec<u8>> {
+        let bucket = bucket.unwrap_or(&self.config.bucket);
+        let request = self.sign("GET", bucket, key, &[], Utc::now())?;
+        let response = self
+            .client
+            .get(request.url)
+            .headers(request.headers)
+            .send()
+            .await
+            .with_context(|| format!("Failed to download {key}"))?;
+        if !response.status().is_success() {
+            bail!("Downloading {} failed with {}", key, response.status());
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    fn sign(
+        &self,
+        method: &str,
+        bucket: &str,
+        key: &str,
+        body: &[u8],
+        now: DateTime<Utc>,
+    ) -> Result<SignedRequest> {
+        let endpoint = Url::parse(&self.config.endpoint)
+            .with_context(|| format!("Invalid BACKUP_S3_ENDPOINT {}", self.config.endpoint))?;
+        let host = match (endpoint.host_str(), endpoint.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(anyhow!("BACKUP_S3_ENDPOINT has no host")),
+        };
+        let path = format!(
+            "{}/{}/{}",
+            endpoint.path().trim_end_matches('/'),
+            uri_encode(bucket),
+            key.split('/').map(uri_encode).collect::<Vec<_>>().join("/")
+        );
+
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = hex(digest::digest(&digest::SHA256, body).as_ref());
+        let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+        let canonical_request = format!(
+            "{method}\n{path}\n\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\n{signed_headers}\n{payload_hash}"
+        );
+        let scope = format!("{date}/{}/s3/aws4_request", self.config.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            hex(digest::digest(&digest::SHA256, canonical_request.as_bytes()).as_ref())
+        );
+        let key = signing_key(
+            &self.config.secret_access_key,
+            &date,
+            &self.config.region,
+            "s3",
+        );
+        let key = hmac::Key::new(hmac::HMAC_SHA256, &key);
+        let signature = hex(hmac::sign(&key, string_to_sign.as_bytes()).as_ref());
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("x-amz-date", amz_date.parse()?);
+        headers.insert("x-amz-content-sha256", payload_hash.parse()?);
+        headers.insert(
+            reqwest::header::AUTHORIZATION,
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+                self.config.access_key_id
+            )
+            .parse()?,
+        );
+
+        let mut url = endpoint;
+        url.set_path(&path);
+        Ok(SignedRequest { url, headers })
+    }
+}
+
+struct SignedRequest {
+    url: Url,
+    headers: reqwest::header::HeaderMap,
+}
+
+/// The key requests made on `date` are signed with, derived from the secret
+/// so the secret itself never signs anything
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let mut key = format!("AWS4{secret}").into_bytes();
+    for part in [date, region, service, "aws4_request"] {
+        key = hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, &key), part.as_bytes())
+            .as_ref()
+            .to_vec();
+    }
+    key
+}
+
+/// Percent-encode everything but unreserved characters, as SigV4 expects of
+/// each path segment
+fn uri_encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing_key_matches_aws_example() {
+        // From AWS's documentation of deriving a signing key
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex(&key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+    }
+
+    #[test]
+    fn test_uri_encode() {
+        assert_eq!(uri_encode("pathfinder-1.db"), "pathfinder-1.db");
+        assert_eq!(uri_encode("a b+c"), "a%20b%2Bc");
+    }
+
+    #[test]
+    fn test_signed_request_uses_path_style_urls() {
+        let storage = S3Storage::new(S3Config {
+            endpoint: "http://localhost:9000".to_string(),
+            bucket: "pathfinder".to_string(),
+            region: "us-east-1".to_string(),
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "secret".to_string(),
+            prefix: "backups/".to_string(),
+        });
+        let now = DateTime::parse_from_rfc3339("2025-11-22T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let key = storage.key_for("pathfinder-20251122T120000Z.db");
+        let request = storage
+            .sign("PUT", "pathfinder", &key, b"backup", now)
+            .unwrap();
+        assert_eq!(
+            request.url.as_str(),
+            "http://localhost:9000/pathfinder/backups/pathfinder-20251122T120000Z.db"
+        );
+        assert_eq!(request.headers["x-amz-date"], "20251122T120000Z");
+        let authorization = request.headers["authorization"].to_str().unwrap();
+        assert!(authorization.starts_with(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20251122/us-east-1/s3/aws4_request, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature="
+        ));
+    }
+}
@@ -0,0 +1,42 @@
+use anyhow::Result;
+use chrono::Utc;
+use pathfinder::db::compaction::{compact_answers, CompactionConfig};
+use pathfinder::db::{memory_backend_unsupported, DatabaseBackend, PgRepository, SqliteRepository};
+use sqlx::{PgPool, SqlitePool};
+
+/// Moves the answer rows of old games into compressed blobs on the games
+/// table. Safe to run repeatedly; each run picks up where the last stopped.
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize tracing
+    tracing_subscriber::fmt::init();
+
+    let database_backend = DatabaseBackend::from_env()?;
+    let database_url = database_backend.database_url()?;
+    let config = CompactionConfig::from_env();
+    let today = Utc::now().date_naive();
+
+    println!(
+        "Compacting answers for games more than {} days old",
+        config.keep_days
+    );
+
+    let summary = match database_backend {
+        DatabaseBackend::Sqlite => {
+            let pool = SqlitePool::connect(&database_url).await?;
+            compact_answers(&SqliteRepository::new(pool), &config, today).await?
+        }
+        DatabaseBackend::Postgres => {
+            let pool = PgPool::connect(&database_url).await?;
+            compact_answers(&PgRepository::new(pool), &config, today).await?
+        }
+        DatabaseBackend::Memory => return Err(memory_backend_unsupported("answer-compactor")),
+    };
+
+    println!(
+        "Compacted {} games, deleting {} answer rows",
+        summary.games_compacted, summary.rows_deleted
+    );
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anyhow::{anyhow, Result};
+use std::{env, path::PathBuf};
+use tracing::info;
+
+use pathfinder::game::{dawg::wordlist_checksum, Dawg};
+use pathfinder::wordlist::{load_wordlist, WordlistFormat};
+
+const USAGE: &str = "usage: build-dawg <wordlist> <output>";
+
+fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let mut args = env::args().skip(1);
+    let source = PathBuf::from(args.next().ok_or_else(|| anyhow!(USAGE))?);
+    let output = PathBuf::from(args.next().ok_or_else(|| anyhow!(USAGE))?);
+    if args.next().is_some() {
+        return Err(anyhow!(USAGE));
+    }
+
+    info!("Loading wordlist from {}", source.display());
+    let words = load_wordlist(&source, Some(WordlistFormat::Plain))?;
+
+    // Recorded so the dawg is only loaded alongside the wordlist it came from
+    let checksum = wordlist_checksum(&source)?;
+    let dawg = Dawg::build(&words).with_source_checksum(checksum);
+    std::fs::write(&output, dawg.to_bytes())?;
+
+    println!(
+        "Wrote {} words to {} ({} bytes, source checksum {:08x})",
+        dawg.len(),
+        output.display(),
+        dawg.size_in_bytes(),
+        checksum
+    );
+
+    Ok(())
+}
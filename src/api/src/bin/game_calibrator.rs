@@ -0,0 +1,59 @@
+use anyhow::Result;
+use chrono::Utc;
+use pathfinder::calibration::{
+    load_calibration, record_due_game_stats, CalibrationConfig, StatsSummary,
+};
+use pathfinder::db::{
+    memory_backend_unsupported, DatabaseBackend, PgRepository, Repository, SqliteRepository,
+};
+use pathfinder::stats_freeze::FreezeConfig;
+use sqlx::{PgPool, SqlitePool};
+
+/// Measures how players fared on each game once its stats are due to be
+/// frozen: the median score, how many finished and how many reached the
+/// threshold. The generator calibrates new thresholds from these when
+/// THRESHOLD_CALIBRATION_ENABLED is set. Safe to run as often as wanted;
+/// each game is measured once.
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize tracing
+    tracing_subscriber::fmt::init();
+
+    let database_backend = DatabaseBackend::from_env()?;
+    let database_url = database_backend.database_url()?;
+
+    let summary = match database_backend {
+        DatabaseBackend::Sqlite => {
+            let pool = SqlitePool::connect(&database_url).await?;
+            calibrate(SqliteRepository::new(pool)).await?
+        }
+        DatabaseBackend::Postgres => {
+            let pool = PgPool::connect(&database_url).await?;
+            calibrate(PgRepository::new(pool)).await?
+        }
+        DatabaseBackend::Memory => return Err(memory_backend_unsupported("game-calibrator")),
+    };
+
+    println!("Measured {} games", summary.games_measured);
+
+    Ok(())
+}
+
+async fn calibrate<R: Repository + Send + Sync>(repo: R) -> Result<StatsSummary> {
+    let summary = record_due_game_stats(&repo, &FreezeConfig::from_env(), Utc::now()).await?;
+
+    let config = CalibrationConfig {
+        enabled: true,
+        ..CalibrationConfig::from_env()
+    };
+    match load_calibration(&repo, &config).await? {
+        Some(calibration) => println!(
+            "Players found a median {:.0}% of the optimal score over the last {} games",
+            calibration.median_share * 100.0,
+            calibration.games
+        ),
+        None => println!("Too few recent games with enough players to calibrate from"),
+    }
+
+    Ok(summary)
+}
@@ -2,25 +2,38 @@ use anyhow::Result;
 use chrono::{Duration, NaiveDate, Utc};
 use pathfinder::db::conversions::AnswerStorage;
 use pathfinder::db::repository::Repository;
-use pathfinder::db::SqliteRepository;
-use pathfinder::game::{conversion::SerializableBoard, GameEngine};
-use sqlx::SqlitePool;
-use std::env;
+use pathfinder::db::{memory_backend_unsupported, DatabaseBackend, PgRepository, SqliteRepository};
+use pathfinder::game::{conversion::SerializableBoard, dawg::dictionary_path, GameEngine};
+use pathfinder::wordlist::SpellingVariants;
+use sqlx::{PgPool, SqlitePool};
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize tracing
     tracing_subscriber::fmt::init();
 
-    let sqlite_database_url =
-        env::var("SQLITE_DATABASE_URL").unwrap_or_else(|_| "sqlite://pathfinder.db".to_string());
+    let database_backend = DatabaseBackend::from_env()?;
+    let database_url = database_backend.database_url()?;
 
     // Create database connection
-    let pool = SqlitePool::connect(&sqlite_database_url).await?;
-    let repo = SqliteRepository::new(pool);
+    match database_backend {
+        DatabaseBackend::Sqlite => {
+            let pool = SqlitePool::connect(&database_url).await?;
+            end_games(SqliteRepository::new(pool)).await
+        }
+        DatabaseBackend::Postgres => {
+            let pool = PgPool::connect(&database_url).await?;
+            end_games(PgRepository::new(pool)).await
+        }
+        DatabaseBackend::Memory => Err(memory_backend_unsupported("game-ender")),
+    }
+}
 
+/// Marks valid entries and their games completed for the most recent fully-ended date
+async fn end_games<R: Repository>(repo: R) -> Result<()> {
     // Initialize game engine for validation
-    let game_engine = GameEngine::new(std::path::PathBuf::from("wordlist"));
+    let game_engine =
+        GameEngine::new(dictionary_path()).with_spelling_variants(&SpellingVariants::from_env());
 
     // Find the most recent UTC date that has ended in all timezones (including Baker Island/Howland Island at UTC-12)
     let target_date = get_most_recent_completed_date();
@@ -98,8 +111,16 @@ async fn main() -> Result<()> {
                 continue;
             }
 
-            // Validate answers using GameEngine
-            if let Err(validation_error) = game_engine.validate_api_answer_group(&board, answers) {
+            // Validate answers using GameEngine, accepting the words the
+            // player challenged
+            let challenged = repo
+                .get_challenged_words(&entry.user_id, &game.id)
+                .await?
+                .into_iter()
+                .collect();
+            if let Err(validation_error) =
+                game_engine.validate_api_answer_group_accepting(&board, answers, &challenged)
+            {
                 println!(
                     "    Entry {} has invalid answers: {}",
                     entry.id, validation_error
@@ -1,39 +1,103 @@
 use anyhow::Result;
 use dotenvy::dotenv;
 use std::env;
-use tokio_cron_scheduler::{Job, JobScheduler};
-use tracing::{error, info};
-
-use pathfinder::db::{setup_database, SqliteRepository};
-use pathfinder::game::GameEngine;
-use pathfinder::game_generator::GameGenerator;
+use tracing::{error, info, warn};
+
+use pathfinder::calibration::CalibrationConfig;
+use pathfinder::db::{
+    memory_backend_unsupported, setup_database, setup_postgres_database, DatabaseBackend,
+    PgRepository, Repository, SqliteConfig,
+};
+use pathfinder::game::{
+    dawg::dictionary_path, optimizer::optimization_budget_from_env, theme::ThemeSchedule,
+    GameEngine,
+};
+use pathfinder::game_generator::{generation_concurrency_from_env, GameGenerator};
+use pathfinder::scheduler::{GenerationScheduler, SchedulerConfig};
+use pathfinder::social::{
+    announcer::{AnnouncerConfig, DailyAnnouncer},
+    fanout::SocialPoster,
+};
+use pathfinder::wordlist::SpellingVariants;
+
+/// Builds an announcer when any social platform is configured and accepts
+/// its credentials
+async fn announcer<R: Repository + Send + Sync>(
+    repository: R,
+) -> Option<DailyAnnouncer<R, SocialPoster>> {
+    let poster = SocialPoster::from_env().await?;
+    Some(DailyAnnouncer::new(
+        repository,
+        poster,
+        AnnouncerConfig::from_env(),
+    ))
+}
 
-async fn run_game_generation() -> Result<()> {
-    let sqlite_database_url =
-        env::var("SQLITE_DATABASE_URL").unwrap_or_else(|_| "sqlite://pathfinder.db".to_string());
+async fn run_game_generation<R: Repository>(game_generator: &GameGenerator<R>) -> Result<()> {
+    // Generate missing games
+    info!("Generating missing games");
+    match game_generator.generate_missing_games().await {
+        Ok(games) => {
+            info!(
+                "Game generation completed successfully, {} new games",
+                games.len()
+            );
+        }
+        Err(e) => {
+            error!("Game generation failed: {}", e);
+            return Err(e);
+        }
+    }
 
-    // Setup database
-    info!("Setting up database connection");
-    let pool = setup_database(&sqlite_database_url).await?;
-    let sqlite_repository = SqliteRepository::new(pool);
+    Ok(())
+}
 
+async fn run<R: Repository + Clone + Send + Sync + 'static>(
+    repository: R,
+    is_cron_mode: bool,
+) -> Result<()> {
     // Setup game engine
     info!("Initializing game engine");
-    let game_engine = GameEngine::new(std::path::PathBuf::from("wordlist"));
+    let game_engine = GameEngine::new(dictionary_path())
+        .with_spelling_variants(&SpellingVariants::from_env())
+        .with_generation_concurrency(generation_concurrency_from_env())
+        .with_optimization_budget(optimization_budget_from_env());
 
     // Setup game generator
-    let game_generator = GameGenerator::new(sqlite_repository, game_engine);
+    let game_generator = GameGenerator::new(repository.clone(), game_engine)
+        .with_themes(ThemeSchedule::from_env())
+        .with_calibration(CalibrationConfig::from_env());
 
-    // Generate missing games
-    info!("Generating missing games");
-    match game_generator.generate_missing_games().await {
-        Ok(()) => {
-            info!("Game generation completed successfully");
+    if is_cron_mode {
+        info!("Starting game generator in cron mode");
+
+        let config = SchedulerConfig {
+            enabled: true,
+            ..SchedulerConfig::from_env()
+        };
+        let mut scheduler: GenerationScheduler<_, SocialPoster> =
+            GenerationScheduler::new(game_generator, config);
+        if let Some(announcer) = announcer(repository).await {
+            scheduler = scheduler.with_announcer(announcer);
         }
-        Err(e) => {
-            error!("Game generation failed: {}", e);
-            return Err(e);
+
+        let _sched = scheduler.start().await?;
+
+        // Keep the process running
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(3600)).await; // Sleep for 1 hour
+        }
+    } else {
+        info!("Starting game generator (single run)");
+        run_game_generation(&game_generator).await?;
+
+        if let Some(announcer) = announcer(repository).await {
+            let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+            if let Err(e) = announcer.announce_date(&today).await {
+                warn!("Failed to announce game for {}: {:#}", today, e);
+            }
         }
+        info!("Game generator finished successfully");
     }
 
     Ok(())
@@ -53,44 +117,23 @@ async fn main() -> Result<()> {
     let args: Vec<String> = env::args().collect();
     let is_cron_mode = args.contains(&"--cron".to_string());
 
-    if is_cron_mode {
-        info!("Starting game generator in cron mode");
+    let database_backend = DatabaseBackend::from_env()?;
+    let database_url = database_backend.database_url()?;
 
-        // Run once on startup
-        info!("Running initial game generation on startup");
-        if let Err(e) = run_game_generation().await {
-            error!("Initial game generation failed: {}", e);
+    // Setup database
+    info!(
+        "Setting up database connection ({:?} backend)",
+        database_backend
+    );
+    match database_backend {
+        DatabaseBackend::Sqlite => {
+            let pools = setup_database(&database_url, &SqliteConfig::from_env()).await?;
+            run(pools.repository(), is_cron_mode).await
         }
-
-        // Create scheduler
-        let sched = JobScheduler::new().await?;
-
-        // Add job that runs at 1:30 AM UTC daily
-        sched
-            .add(Job::new_async("0 30 1 * * *", |_uuid, _l| {
-                Box::pin(async {
-                    info!("Running scheduled game generation");
-                    if let Err(e) = run_game_generation().await {
-                        error!("Scheduled game generation failed: {}", e);
-                    }
-                })
-            })?)
-            .await?;
-
-        // Start the scheduler
-        sched.start().await?;
-
-        info!("Game generator cron scheduler started - running daily at 1:30 AM UTC");
-
-        // Keep the process running
-        loop {
-            tokio::time::sleep(tokio::time::Duration::from_secs(3600)).await; // Sleep for 1 hour
+        DatabaseBackend::Postgres => {
+            let pool = setup_postgres_database(&database_url).await?;
+            run(PgRepository::new(pool), is_cron_mode).await
         }
-    } else {
-        info!("Starting game generator (single run)");
-        run_game_generation().await?;
-        info!("Game generator finished successfully");
+        DatabaseBackend::Memory => Err(memory_backend_unsupported("game-generator")),
     }
-
-    Ok(())
 }
@@ -1,9 +1,8 @@
 use anyhow::Result;
 use dotenvy::dotenv;
-use std::env;
 use tracing::info;
 
-use pathfinder::db::setup_database;
+use pathfinder::db::{setup_database, setup_postgres_database, DatabaseBackend, SqliteConfig};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -17,12 +16,20 @@ async fn main() -> Result<()> {
 
     info!("Starting stat-poster for previous day's puzzle");
 
-    let sqlite_database_url =
-        env::var("SQLITE_DATABASE_URL").unwrap_or_else(|_| "sqlite://pathfinder.db".to_string());
+    let database_backend = DatabaseBackend::from_env()?;
+    let database_url = database_backend.database_url()?;
 
     // Setup database
     info!("Setting up database connection");
-    let _ = setup_database(&sqlite_database_url).await?;
+    match database_backend {
+        DatabaseBackend::Sqlite => {
+            let _ = setup_database(&database_url, &SqliteConfig::from_env()).await?;
+        }
+        DatabaseBackend::Postgres => {
+            let _ = setup_postgres_database(&database_url).await?;
+        }
+        DatabaseBackend::Memory => info!("The in-memory backend has no migrations to run"),
+    }
 
     Ok(())
 }
@@ -1,11 +1,15 @@
 use anyhow::Result;
 use chrono::{Duration, Utc};
 use dotenvy::dotenv;
-use std::env;
+use std::path::Path;
 use tracing::{info, warn};
 
-use pathfinder::db::{setup_database, Repository, SqliteRepository};
-use pathfinder::social::{bluesky::BlueSkyPoster, Post};
+use pathfinder::db::{
+    memory_backend_unsupported, setup_database, setup_postgres_database, DatabaseBackend,
+    PgRepository, Repository, SqliteConfig,
+};
+use pathfinder::social::{fanout::SocialPoster, Post};
+use pathfinder::wordlist::{DisplayCasing, DISPLAY_CASING_PATH};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -19,14 +23,26 @@ async fn main() -> Result<()> {
 
     info!("Starting stat-poster for previous day's puzzle");
 
-    let sqlite_database_url =
-        env::var("SQLITE_DATABASE_URL").unwrap_or_else(|_| "sqlite://pathfinder.db".to_string());
+    let database_backend = DatabaseBackend::from_env()?;
+    let database_url = database_backend.database_url()?;
 
     // Setup database
     info!("Setting up database connection");
-    let pool = setup_database(&sqlite_database_url).await?;
-    let sqlite_repository = SqliteRepository::new(pool);
+    match database_backend {
+        DatabaseBackend::Sqlite => {
+            let pools = setup_database(&database_url, &SqliteConfig::from_env()).await?;
+            post_stats(pools.repository()).await
+        }
+        DatabaseBackend::Postgres => {
+            let pool = setup_postgres_database(&database_url).await?;
+            post_stats(PgRepository::new(pool)).await
+        }
+        DatabaseBackend::Memory => Err(memory_backend_unsupported("stat-poster")),
+    }
+}
 
+/// Builds the previous day's report and posts it to BlueSky when configured
+async fn post_stats<R: Repository>(repository: R) -> Result<()> {
     // Calculate previous day's date
     let previous_day = (Utc::now() - Duration::days(1))
         .format("%Y-%m-%d")
@@ -34,14 +50,15 @@ async fn main() -> Result<()> {
     info!("Fetching stats for date: {}", previous_day);
 
     // Get the previous day's game
-    let game = sqlite_repository.get_game_by_date(&previous_day).await?;
+    let game = repository.get_game_by_date(&previous_day).await?;
 
     let report = match game {
         Some(game) => {
             info!("Found game for {}: {}", previous_day, game.id);
 
             // Get optimal solutions (professor's answers)
-            let optimal_solutions = sqlite_repository.get_optimal_solutions(&game.id).await?;
+            let optimal_solutions = repository.get_optimal_solutions(&game.id).await?;
+            let display_casing = DisplayCasing::load_or_default(Path::new(DISPLAY_CASING_PATH));
 
             // Generate report
             let mut report = String::new();
@@ -51,7 +68,11 @@ async fn main() -> Result<()> {
 
             let mut total_score = 0;
             for solution in optimal_solutions {
-                report.push_str(&format!("{}: {}\n", solution.word, solution.score));
+                report.push_str(&format!(
+                    "{}: {}\n",
+                    display_casing.display(&solution.word),
+                    solution.score
+                ));
                 total_score += solution.score;
             }
 
@@ -69,39 +90,12 @@ async fn main() -> Result<()> {
     if let Some(report) = report {
         print!("{report}");
 
-        // Post to BlueSky if credentials are available. Prefer a password
-        // provided via `BLUESKY_PASSWORD_FILE`. Only fall back to
-        // `BLUESKY_PASSWORD` when `BLUESKY_PASSWORD_FILE` is not set.
-        if let Ok(handle) = env::var("BLUESKY_HANDLE") {
-            // Try password file first
-            let password_result: Result<String, ()> = match env::var("BLUESKY_PASSWORD_FILE") {
-                Ok(path) => match std::fs::read_to_string(&path) {
-                    Ok(s) => Ok(s.trim().to_string()),
-                    Err(e) => {
-                        warn!("Failed to read BLUESKY_PASSWORD_FILE '{}': {}", path, e);
-                        Err(())
-                    }
-                },
-                Err(_) => env::var("BLUESKY_PASSWORD").map_err(|_| ()),
-            };
-
-            if let Ok(password) = password_result {
-                info!("Posting to BlueSky...");
-
-                let mut poster = BlueSkyPoster::new(handle).with_password(password);
-
-                match poster.authenticate().await {
-                    Ok(()) => match poster.post(report).await {
-                        Ok(()) => info!("Successfully posted to BlueSky"),
-                        Err(e) => warn!("Failed to post to BlueSky: {}", e),
-                    },
-                    Err(e) => warn!("Failed to authenticate with BlueSky: {}", e),
-                }
-            } else {
-                info!("BlueSky password not found, skipping post");
+        if let Some(poster) = SocialPoster::from_env().await {
+            info!("Posting to {}...", poster.platforms().join(", "));
+
+            if let Err(e) = poster.post(report).await {
+                warn!("{}", e);
             }
-        } else {
-            info!("BlueSky handle not found in environment, skipping post");
         }
     }
     Ok(())
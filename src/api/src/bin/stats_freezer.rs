@@ -0,0 +1,55 @@
+use anyhow::Result;
+use chrono::Utc;
+use pathfinder::account::mailer::{Mailer, WebhookMailer};
+use pathfinder::db::{
+    memory_backend_unsupported, DatabaseBackend, PgRepository, Repository, SqliteRepository,
+};
+use pathfinder::notifications::Notifier;
+use pathfinder::stats_freeze::{freeze_due_games, FreezeConfig, FreezeSummary};
+use sqlx::{PgPool, SqlitePool};
+use std::sync::Arc;
+
+/// Freezes the stats of games whose day ended long enough ago and notifies
+/// players who opted in of their final rank, then finalizes tournaments whose
+/// last day is frozen. Safe to run as often as wanted; each game is frozen
+/// and announced once, and each tournament finalized once.
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize tracing
+    tracing_subscriber::fmt::init();
+
+    let database_backend = DatabaseBackend::from_env()?;
+    let database_url = database_backend.database_url()?;
+    let config = FreezeConfig::from_env();
+
+    let summary = match database_backend {
+        DatabaseBackend::Sqlite => {
+            let pool = SqlitePool::connect(&database_url).await?;
+            freeze(SqliteRepository::new(pool), &config).await?
+        }
+        DatabaseBackend::Postgres => {
+            let pool = PgPool::connect(&database_url).await?;
+            freeze(PgRepository::new(pool), &config).await?
+        }
+        DatabaseBackend::Memory => return Err(memory_backend_unsupported("stats-freezer")),
+    };
+
+    println!(
+        "Froze stats for {} games, sending {} final rank notifications, and finalized {} tournaments",
+        summary.games_frozen, summary.notifications_sent, summary.tournaments_finalized
+    );
+
+    Ok(())
+}
+
+async fn freeze<R: Repository + Clone + Send + Sync>(
+    repo: R,
+    config: &FreezeConfig,
+) -> Result<FreezeSummary> {
+    let mut notifier = Notifier::new(repo.clone());
+    if let Some(mailer) = WebhookMailer::from_env() {
+        notifier = notifier.with_mailer(Arc::new(mailer) as Arc<dyn Mailer>);
+    }
+
+    freeze_due_games(&repo, &notifier, config, Utc::now()).await
+}
@@ -0,0 +1,79 @@
+use anyhow::{anyhow, Result};
+use std::{env, path::PathBuf};
+use tracing::info;
+
+use pathfinder::wordlist::{load_wordlist, WordlistDiff, WordlistFormat};
+
+const USAGE: &str = "usage: wordlist-import <source> [--format plain|annotated] [--current <wordlist>] [--write <output>] [--verbose]";
+
+struct Args {
+    source: PathBuf,
+    format: Option<WordlistFormat>,
+    current: PathBuf,
+    write: Option<PathBuf>,
+    verbose: bool,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut args = env::args().skip(1);
+    let mut source = None;
+    let mut format = None;
+    let mut current = PathBuf::from("wordlist");
+    let mut write = None;
+    let mut verbose = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                format = Some(args.next().ok_or_else(|| anyhow!(USAGE))?.parse()?);
+            }
+            "--current" => current = PathBuf::from(args.next().ok_or_else(|| anyhow!(USAGE))?),
+            "--write" => write = Some(PathBuf::from(args.next().ok_or_else(|| anyhow!(USAGE))?)),
+            "--verbose" => verbose = true,
+            _ if source.is_none() && !arg.starts_with("--") => source = Some(PathBuf::from(arg)),
+            _ => return Err(anyhow!(USAGE)),
+        }
+    }
+
+    Ok(Args {
+        source: source.ok_or_else(|| anyhow!(USAGE))?,
+        format,
+        current,
+        write,
+        verbose,
+    })
+}
+
+fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let args = parse_args()?;
+
+    info!("Loading candidate wordlist from {}", args.source.display());
+    let candidate = load_wordlist(&args.source, args.format)?;
+
+    info!("Loading current wordlist from {}", args.current.display());
+    let current = load_wordlist(&args.current, Some(WordlistFormat::Plain))?;
+
+    let diff = WordlistDiff::between(&current, &candidate);
+    if args.verbose {
+        print!("{diff}");
+    } else {
+        println!(
+            "{} words imported: {} added, {} removed, {} unchanged",
+            candidate.len(),
+            diff.added.len(),
+            diff.removed.len(),
+            diff.unchanged
+        );
+    }
+
+    if let Some(output) = args.write {
+        std::fs::write(&output, candidate.join("\n") + "\n")?;
+        info!("Wrote {} words to {}", candidate.len(), output.display());
+    }
+
+    Ok(())
+}
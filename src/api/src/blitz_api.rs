@@ -0,0 +1,389 @@
+use anyhow::Context;
+use axum::{
+    extract::{Path, Query, State},
+    response::{Extension, Json},
+    routing::get,
+    Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{env, time::Duration};
+use tracing::warn;
+
+use crate::api_error::ApiError;
+use crate::db::{
+    conversions::AnswerStorage,
+    models::{DbBlitzRun, DbGame},
+    Repository,
+};
+use crate::game::mode::GameMode;
+use crate::http_api::{
+    authenticate_user, convert_db_game_to_api_game_direct, future_puzzle, is_date_in_future,
+    ApiGame, ApiState,
+};
+use crate::security::{session::SessionInfo, start_token::StartClaims, user_cookie::UserIdentity};
+
+/// Submissions arriving this long after the clock runs out still count, so a
+/// save sent as time expires isn't lost to network latency
+const SUBMISSION_GRACE: chrono::Duration = chrono::Duration::seconds(5);
+
+#[derive(Clone, Debug)]
+pub struct BlitzConfig {
+    /// How long players have from opening a blitz board to submit it. Boards
+    /// keep the limit they were generated with.
+    pub time_limit: Duration,
+}
+
+impl Default for BlitzConfig {
+    fn default() -> Self {
+        Self {
+            time_limit: Duration::from_secs(120),
+        }
+    }
+}
+
+impl BlitzConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(seconds) = env::var("BLITZ_TIME_LIMIT_SECONDS") {
+            if let Ok(value) = seconds.parse::<u64>() {
+                config.time_limit = Duration::from_secs(value);
+            }
+        }
+
+        config
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct BlitzQuery {
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+}
+
+/// A blitz board along with the caller's clock for it
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiBlitzGame {
+    pub game: ApiGame,
+    /// When the caller first opened this board
+    pub started_at: DateTime<Utc>,
+    /// When submissions stop being accepted
+    pub ends_at: DateTime<Utc>,
+    /// Sent back with the player's entries for this game, as from starting a
+    /// daily game
+    pub start_token: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ApiBlitzStats {
+    /// Blitz games whose clock has stopped, by submitting or running out
+    pub games_played: i32,
+    pub average_score: Option<f64>,
+    pub best_score: Option<i32>,
+    /// Words found per minute of play over every finished blitz game. A game
+    /// that was never submitted counts its whole time limit.
+    pub words_per_minute: Option<f64>,
+    /// Words per minute of the fastest single game
+    pub best_words_per_minute: Option<f64>,
+}
+
+/// Routes for playing blitz boards against the clock
+pub fn blitz_router<R: Repository + Clone + Send + Sync + 'static>() -> Router<ApiState<R>> {
+    Router::new()
+        .route("/api/blitz/date/:date", get(get_blitz_game))
+        .route("/api/blitz/stats", get(get_blitz_stats))
+}
+
+/// Get the blitz board for a date. The first time a player opens it starts
+/// their clock; opening it again, e.g. on reload, returns the same clock, so
+/// the board is never shown without the clock running.
+async fn get_blitz_game<R: Repository + Clone + Send + Sync + 'static>(
+    Path(date): Path<String>,
+    Query(query): Query<BlitzQuery>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+) -> Result<Json<ApiBlitzGame>, ApiError> {
+    if is_date_in_future(&date) {
+        return Err(future_puzzle());
+    }
+    let user = authenticate_user(
+        &state,
+        &identity,
+        query.user_id.as_ref(),
+        query.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+    let game = load_blitz_game(&state, &date).await?;
+
+    let started_at = state
+        .repository
+        .record_game_start(&user.id, &game.id)
+        .await
+        .with_context(|| format!("Failed to record start of blitz game {}", game.id))?;
+    let start_token = state.start_tokens.sign(&StartClaims {
+        game_id: game.id.clone(),
+        date: game.date.clone(),
+        session_hash: session.map(|session| session.session_hash.clone()),
+    });
+    let ends_at = started_at + time_limit(&game);
+
+    Ok(Json(ApiBlitzGame {
+        game: convert_db_game_to_api_game_direct(game)?,
+        started_at,
+        ends_at,
+        start_token,
+    }))
+}
+
+/// Load the blitz game for a date, generating it if it doesn't exist yet.
+/// Blitz games stay out of the game cache, which looks games up by date.
+async fn load_blitz_game<R: Repository>(
+    state: &ApiState<R>,
+    date: &str,
+) -> Result<DbGame, ApiError> {
+    if let Some(game) = state
+        .repository
+        .get_game_by_date_in_mode(date, GameMode::Blitz)
+        .await
+        .context("Failed to get blitz game by date")?
+    {
+        return Ok(game);
+    }
+
+    let time_limit_seconds = i32::try_from(state.blitz_config.time_limit.as_secs())
+        .context("Blitz time limit is too long")?;
+    Ok(state
+        .game_generator
+        .generate_blitz_game_for_date(date, time_limit_seconds)
+        .await
+        .with_context(|| format!("Failed to generate blitz game for {date}"))?)
+}
+
+/// Reject entries to a blitz game from a player whose clock isn't running:
+/// one who never opened the board, or whose time ran out
+pub(crate) async fn check_blitz_clock<R: Repository>(
+    state: &ApiState<R>,
+    user_id: &str,
+    game: &DbGame,
+) -> Result<(), ApiError> {
+    let started_at = state
+        .repository
+        .get_game_start(user_id, &game.id)
+        .await
+        .with_context(|| format!("Failed to get start of blitz game {}", game.id))?
+        .ok_or_else(|| ApiError::conflict("Open the blitz game to start its clock first"))?;
+
+    if Utc::now() > started_at + time_limit(game) + SUBMISSION_GRACE {
+        return Err(ApiError::conflict("Time is up for this blitz game"));
+    }
+    Ok(())
+}
+
+fn time_limit(game: &DbGame) -> chrono::Duration {
+    chrono::Duration::seconds(game.time_limit_seconds.unwrap_or_default().into())
+}
+
+async fn get_blitz_stats<R: Repository + Clone + Send + Sync + 'static>(
+    Query(query): Query<BlitzQuery>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+) -> Result<Json<ApiBlitzStats>, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        query.user_id.as_ref(),
+        query.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+    let runs = state
+        .repository
+        .get_blitz_runs(&user.id)
+        .await
+        .context("Failed to load blitz runs")?;
+
+    Ok(Json(blitz_stats(&runs, Utc::now())))
+}
+
+/// Summarize the runs whose clock had stopped by `now`
+fn blitz_stats(runs: &[DbBlitzRun], now: DateTime<Utc>) -> ApiBlitzStats {
+    let mut scores = Vec::new();
+    let mut total_words = 0usize;
+    let mut total_minutes = 0.0;
+    let mut best_words_per_minute: Option<f64> = None;
+
+    for run in runs {
+        let limit = chrono::Duration::seconds(run.time_limit_seconds.into());
+        // The clock stops at submission, or at the limit for players who
+        // never submitted
+        let played = match run.completed_at {
+            Some(completed_at) => (completed_at - run.started_at).min(limit),
+            None if now >= run.started_at + limit => limit,
+            None => continue,
+        };
+        let minutes = played.num_milliseconds().max(1) as f64 / 60_000.0;
+        let words = match AnswerStorage::deserialize_to_api_answers(&run.answers_data) {
+            Ok(answers) => answers.len(),
+            Err(e) => {
+                warn!("Unreadable answers in blitz game {}: {}", run.game_id, e);
+                0
+            }
+        };
+
+        scores.push(run.total_score);
+        total_words += words;
+        total_minutes += minutes;
+        let words_per_minute = words as f64 / minutes;
+        best_words_per_minute =
+            Some(best_words_per_minute.map_or(words_per_minute, |best| best.max(words_per_minute)));
+    }
+
+    ApiBlitzStats {
+        games_played: scores.len() as i32,
+        average_score: (!scores.is_empty())
+            .then(|| scores.iter().sum::<i32>() as f64 / scores.len() as f64),
+        best_score: scores.iter().copied().max(),
+        words_per_minute: (!scores.is_empty()).then(|| total_words as f64 / total_minutes),
+        best_words_per_minute,
+    }
+}
+
+#[cfg(all(test, feature = "database-tests"))]
+mod tests {
+    use super::*;
+    use axum::http::{Method, StatusCode};
+    use tower::util::ServiceExt;
+
+    use crate::db::models::{DbUser, NewUser};
+    use crate::http_api::{ApiAnswer, UpdateGameEntryRequest};
+    use crate::test_utils::{create_new_test_game, create_test_request, setup_app};
+
+    async fn submit(app: &Router, game: &DbGame, user: &DbUser) -> StatusCode {
+        let body = serde_json::to_string(&UpdateGameEntryRequest {
+            user_id: Some(user.id.clone()),
+            cookie_token: Some(user.cookie_token.clone()),
+            answers: vec![ApiAnswer {
+                word: "test".to_string(),
+                score: 0,
+                path: None,
+            }],
+            game_id: game.id.clone(),
+            completed: false,
+            start_token: None,
+            request_id: None,
+        })
+        .unwrap();
+        let uri = format!("/api/game-entry/{}", game.id);
+        app.clone()
+            .oneshot(create_test_request(Method::POST, &uri, Some(&body)))
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_blitz_clock_gates_entries(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let (state, app) = setup_app(pool.clone()).await;
+        let repo = &state.repository;
+        let mut new_game = create_new_test_game();
+        new_game.mode = GameMode::Blitz.to_string();
+        new_game.time_limit_seconds = Some(60);
+        let (game, _) = repo
+            .create_game_with_answers(new_game, vec![], None)
+            .await
+            .unwrap();
+        let user = repo
+            .create_user(NewUser {
+                cookie_token: "blitzer".to_string(),
+            })
+            .await
+            .unwrap();
+
+        // The daily game for the date is a different board
+        assert!(repo.get_game_by_date(&game.date).await.unwrap().is_none());
+
+        // Entries before opening the board have no clock to run against
+        assert_eq!(submit(&app, &game, &user).await, StatusCode::CONFLICT);
+
+        let uri = format!(
+            "/api/blitz/date/{}?user_id={}&cookie_token=blitzer",
+            game.date, user.id
+        );
+        let response = app
+            .clone()
+            .oneshot(create_test_request(Method::GET, &uri, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let opened: ApiBlitzGame = serde_json::from_slice(&body).unwrap();
+        assert_eq!(opened.game.id, game.id);
+        assert_eq!(opened.game.mode, GameMode::Blitz);
+        assert_eq!(opened.game.time_limit_seconds, Some(60));
+        assert_eq!(
+            opened.ends_at - opened.started_at,
+            chrono::Duration::seconds(60)
+        );
+
+        assert_eq!(submit(&app, &game, &user).await, StatusCode::OK);
+
+        // Backdate the start past the limit and its grace
+        let started_at = Utc::now() - chrono::Duration::seconds(90);
+        sqlx::query("UPDATE game_starts SET started_at = ?1 WHERE user_id = ?2")
+            .bind(started_at.to_rfc3339())
+            .bind(&user.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(submit(&app, &game, &user).await, StatusCode::CONFLICT);
+    }
+
+    fn run(words: usize, played_seconds: Option<i64>, started_ago: i64) -> DbBlitzRun {
+        let answers: Vec<ApiAnswer> = (0..words)
+            .map(|i| ApiAnswer {
+                word: format!("word{i}"),
+                score: 1,
+                path: None,
+            })
+            .collect();
+        let started_at = Utc::now() - chrono::Duration::seconds(started_ago);
+        DbBlitzRun {
+            game_id: "game".to_string(),
+            date: "2025-06-08".to_string(),
+            answers_data: AnswerStorage::serialize_api_answers(&answers).unwrap(),
+            total_score: words as i32 * 10,
+            started_at,
+            completed_at: played_seconds
+                .map(|seconds| started_at + chrono::Duration::seconds(seconds)),
+            time_limit_seconds: 120,
+        }
+    }
+
+    #[test]
+    fn test_blitz_stats_words_per_minute() {
+        let runs = vec![
+            // 6 words in 30 seconds
+            run(6, Some(30), 600),
+            // Never submitted, so all 2 minutes count
+            run(4, None, 600),
+            // Still playing, so left out
+            run(10, None, 10),
+        ];
+        let stats = blitz_stats(&runs, Utc::now());
+
+        assert_eq!(stats.games_played, 2);
+        assert_eq!(stats.best_score, Some(60));
+        assert_eq!(stats.average_score, Some(50.0));
+        // 10 words over 2.5 minutes
+        assert!((stats.words_per_minute.unwrap() - 4.0).abs() < 1e-9);
+        assert!((stats.best_words_per_minute.unwrap() - 12.0).abs() < 1e-9);
+
+        assert_eq!(blitz_stats(&[], Utc::now()).words_per_minute, None);
+    }
+}
@@ -0,0 +1,196 @@
+use axum::body::Bytes;
+use moka::future::Cache;
+use std::env;
+
+use crate::game::board::Tile;
+use crate::game::letters::strip_marks;
+use crate::game::Board;
+use crate::raster::{text_width, Canvas, Rgb};
+
+/// Default budget for cached preview images. A preview encodes to a few KB,
+/// so this holds every game's previews for years.
+pub const DEFAULT_PREVIEW_CACHE_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Draw a game's board as a link preview image: the grid on the left and the
+/// puzzle number and date on the right. Unless `reveal` is set the tiles are
+/// left blank, showing only where the wildcards are, so a shared link gives
+/// nothing away.
+pub fn render_board_preview(
+    board: &Board,
+    sequence_number: i32,
+    date: &str,
+    reveal: bool,
+) -> Vec<u8> {
+    let mut canvas = Canvas::new(PREVIEW_WIDTH, PREVIEW_HEIGHT, BACKGROUND);
+
+    let rows = board.rows.len();
+    let grid_height = rows * TILE_SIZE + rows.saturating_sub(1) * TILE_GAP;
+    let grid_top = PREVIEW_HEIGHT.saturating_sub(grid_height) / 2;
+    for (r, row) in board.rows.iter().enumerate() {
+        for (c, tile) in row.tiles.iter().enumerate() {
+            let left = GRID_LEFT + c * (TILE_SIZE + TILE_GAP);
+            let top = grid_top + r * (TILE_SIZE + TILE_GAP);
+            let color = if tile.is_wildcard {
+                WILDCARD_TILE
+            } else {
+                TILE
+            };
+            canvas.fill_rect(left, top, TILE_SIZE, TILE_SIZE, color);
+            if reveal {
+                draw_tile_face(&mut canvas, left, top, tile);
+            }
+        }
+    }
+
+    canvas.draw_text(TEXT_LEFT, 80, 8, &format!("#{sequence_number}"), TEXT);
+    canvas.draw_text(TEXT_LEFT, 160, 4, date, MUTED_TEXT);
+
+    canvas.encode_png()
+}
+
+/// Draw a tile's letter, or several for tiles like qu, centred, with its
+/// points in the corner. Wildcards show a star.
+fn draw_tile_face(canvas: &mut Canvas, left: usize, top: usize, tile: &Tile) {
+    let label = if tile.is_wildcard {
+        "*".to_string()
+    } else {
+        // The font has no accents, so ñ is drawn as N
+        strip_marks(&tile.letter).to_uppercase()
+    };
+    let scale = if label.chars().count() > 1 { 4 } else { 6 };
+    canvas.draw_text(
+        left + TILE_SIZE.saturating_sub(text_width(&label, scale)) / 2,
+        top + (TILE_SIZE - 5 * scale) / 2,
+        scale,
+        &label,
+        TEXT,
+    );
+    if !tile.is_wildcard {
+        let points = tile.points.to_string();
+        canvas.draw_text(
+            left + TILE_SIZE - 3 - text_width(&points, 2),
+            top + TILE_SIZE - 3 - 5 * 2,
+            2,
+            &points,
+            MUTED_TEXT,
+        );
+    }
+}
+
+const PREVIEW_WIDTH: usize = 600;
+const PREVIEW_HEIGHT: usize = 315;
+const GRID_LEFT: usize = 36;
+const TILE_SIZE: usize = 56;
+const TILE_GAP: usize = 8;
+const TEXT_LEFT: usize = 330;
+
+const BACKGROUND: Rgb = [17, 24, 39];
+const TEXT: Rgb = [243, 244, 246];
+const MUTED_TEXT: Rgb = [156, 163, 175];
+const TILE: Rgb = [55, 65, 81];
+const WILDCARD_TILE: Rgb = [124, 58, 237];
+
+/// PreviewCache holds rendered preview images by game id and whether their
+/// letters are shown, since a game's board never changes once it exists.
+/// Entries are weighed by their encoded size and the least recently used
+/// are evicted once the cache holds `max_bytes`.
+#[derive(Clone)]
+pub struct PreviewCache {
+    cache: Cache<(String, bool), Bytes>,
+    max_bytes: u64,
+}
+
+impl Default for PreviewCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_PREVIEW_CACHE_BYTES)
+    }
+}
+
+impl PreviewCache {
+    pub fn new(max_bytes: u64) -> Self {
+        let cache = Cache::builder()
+            .max_capacity(max_bytes)
+            .weigher(|_key, png: &Bytes| png.len().try_into().unwrap_or(u32::MAX))
+            .support_invalidation_closures()
+            .build();
+        Self { cache, max_bytes }
+    }
+
+    pub fn from_env() -> Self {
+        env::var("PREVIEW_CACHE_MAX_BYTES")
+            .ok()
+            .and_then(|bytes| bytes.parse().ok())
+            .map(Self::new)
+            .unwrap_or_default()
+    }
+
+    pub async fn get(&self, game_id: &str, reveal: bool) -> Option<Bytes> {
+        self.cache.get(&(game_id.to_string(), reveal)).await
+    }
+
+    pub async fn insert(&self, game_id: &str, reveal: bool, png: Bytes) {
+        self.cache.insert((game_id.to_string(), reveal), png).await;
+    }
+
+    pub fn invalidate_all(&self) {
+        self.cache.invalidate_all();
+    }
+
+    /// invalidate_game drops both previews of one game's board
+    pub fn invalidate_game(&self, game_id: &str) {
+        let game_id = game_id.to_string();
+        // Only fails when the cache is built without invalidation closures
+        let _ = self
+            .cache
+            .invalidate_entries_if(move |(id, _), _| *id == game_id);
+    }
+
+    pub fn entry_count(&self) -> u64 {
+        self.cache.entry_count()
+    }
+
+    /// Total encoded size of the cached images, in bytes
+    pub fn weighted_size(&self) -> u64 {
+        self.cache.weighted_size()
+    }
+
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raster::testing::decode_scanlines;
+    use crate::test_utils::create_test_board;
+
+    #[test]
+    fn test_revealed_previews_show_the_letters() {
+        let board = create_test_board("catsa*etteasstac");
+        let hidden = render_board_preview(&board, 12, "2026-10-15", false);
+        let revealed = render_board_preview(&board, 12, "2026-10-15", true);
+
+        let hidden = decode_scanlines(&hidden, PREVIEW_WIDTH, PREVIEW_HEIGHT);
+        let revealed = decode_scanlines(&revealed, PREVIEW_WIDTH, PREVIEW_HEIGHT);
+        assert_eq!(hidden.len(), PREVIEW_HEIGHT * (PREVIEW_WIDTH * 3 + 1));
+        assert_ne!(hidden, revealed);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_game_drops_both_previews() {
+        let cache = PreviewCache::default();
+        for (game_id, reveal) in [("a", false), ("a", true), ("b", false)] {
+            cache
+                .insert(game_id, reveal, Bytes::from_static(b"png"))
+                .await;
+        }
+
+        cache.invalidate_game("a");
+        cache.cache.run_pending_tasks().await;
+
+        assert!(cache.get("a", false).await.is_none());
+        assert!(cache.get("a", true).await.is_none());
+        assert!(cache.get("b", false).await.is_some());
+    }
+}
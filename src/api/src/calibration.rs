@@ -0,0 +1,394 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::env;
+use tracing::info;
+
+use crate::db::{
+    models::{DbGame, DbGameStats, DbPlayerCounts},
+    Repository,
+};
+use crate::stats_freeze::{latest_freezable_date, FreezeConfig};
+
+#[derive(Clone, Debug)]
+pub struct CalibrationConfig {
+    /// Whether new daily games' thresholds are calibrated against how
+    /// players fared on recent ones
+    pub enabled: bool,
+    /// How many of the most recent daily games' stats are looked at
+    pub games: i32,
+    /// Games fewer players completed than this are too noisy to learn from
+    pub min_players: i32,
+    /// With fewer games to learn from than this, thresholds are left alone
+    pub min_games: usize,
+    /// The largest share, 0 to 1, a threshold is moved by either way
+    pub max_adjustment: f64,
+}
+
+impl Default for CalibrationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            games: 28,
+            min_players: 20,
+            min_games: 7,
+            max_adjustment: 0.2,
+        }
+    }
+}
+
+impl CalibrationConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(enabled) = env::var("THRESHOLD_CALIBRATION_ENABLED") {
+            config.enabled = enabled.to_lowercase() == "true";
+        }
+
+        if let Ok(games) = env::var("THRESHOLD_CALIBRATION_GAMES") {
+            if let Ok(value) = games.parse::<i32>() {
+                config.games = value.max(1);
+            }
+        }
+
+        if let Ok(players) = env::var("THRESHOLD_CALIBRATION_MIN_PLAYERS") {
+            if let Ok(value) = players.parse::<i32>() {
+                config.min_players = value.max(1);
+            }
+        }
+
+        if let Ok(games) = env::var("THRESHOLD_CALIBRATION_MIN_GAMES") {
+            if let Ok(value) = games.parse::<usize>() {
+                config.min_games = value.max(1);
+            }
+        }
+
+        if let Ok(adjustment) = env::var("THRESHOLD_CALIBRATION_MAX_ADJUSTMENT") {
+            if let Ok(value) = adjustment.parse::<f64>() {
+                config.max_adjustment = value.clamp(0.0, 1.0);
+            }
+        }
+
+        config
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct StatsSummary {
+    pub games_measured: usize,
+}
+
+/// Measure how players fared on every game whose stats are due to be frozen
+/// and hasn't been measured, oldest first. Measuring waits as long as the
+/// freeze does, so late plays never count.
+pub async fn record_due_game_stats<R: Repository + Send + Sync>(
+    repo: &R,
+    freeze: &FreezeConfig,
+    now: DateTime<Utc>,
+) -> Result<StatsSummary> {
+    let through = latest_freezable_date(now, freeze.delay)
+        .format("%Y-%m-%d")
+        .to_string();
+    let mut summary = StatsSummary::default();
+
+    for game in repo.get_games_without_stats_through(&through).await? {
+        let counts = repo.get_player_counts(&game.id).await?;
+        let scores = repo.get_score_distribution(&game.id).await?;
+        let optimal_score = repo
+            .get_optimal_solutions(&game.id)
+            .await?
+            .iter()
+            .map(|answer| answer.score)
+            .sum();
+
+        let stats = measure_game(&game, &counts, scores, optimal_score, now);
+        info!(
+            "Measured game {} ({}): median {} of {}, {:.0}% completed, {:.0}% reached {}",
+            game.id,
+            game.date,
+            stats.median_score,
+            stats.optimal_score,
+            stats.completion_rate * 100.0,
+            stats.threshold_hit_rate * 100.0,
+            game.threshold_score
+        );
+        repo.upsert_game_stats(stats).await?;
+        summary.games_measured += 1;
+    }
+
+    Ok(summary)
+}
+
+/// measure_game works out a game's stats from its player counts and the
+/// scores of its completed, on-board entries
+pub fn measure_game(
+    game: &DbGame,
+    counts: &DbPlayerCounts,
+    mut scores: Vec<i32>,
+    optimal_score: i32,
+    now: DateTime<Utc>,
+) -> DbGameStats {
+    let players = counts.in_progress + counts.completed;
+    let hits = scores
+        .iter()
+        .filter(|score| **score >= game.threshold_score)
+        .count();
+    scores.sort_unstable();
+
+    DbGameStats {
+        game_id: game.id.clone(),
+        players,
+        completed_players: scores.len() as i32,
+        median_score: median(&scores).unwrap_or(0.0).round() as i32,
+        optimal_score,
+        completion_rate: share(counts.completed as usize, players as usize),
+        threshold_hit_rate: share(hits, scores.len()),
+        computed_at: now,
+    }
+}
+
+fn share(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
+/// The middle of sorted values, averaging the middle two of an even count
+fn median<T: Copy + Into<f64>>(sorted: &[T]) -> Option<f64> {
+    let middle = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        len if len % 2 == 1 => Some(sorted[middle].into()),
+        _ => Some((sorted[middle - 1].into() + sorted[middle].into()) / 2.0),
+    }
+}
+
+/// ThresholdCalibration is what recent games say thresholds should be. Each
+/// game's median score as a share of its optimal score measures how much of
+/// a board players typically find, which doesn't depend on the threshold the
+/// game was published with, so calibrating never feeds back on itself. New
+/// thresholds are aimed at that share of the board's optimal score, keeping
+/// about half of players reaching them however boards and wordlists change.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThresholdCalibration {
+    /// The median, across recent games, of each one's median score as a
+    /// share of its optimal score
+    pub median_share: f64,
+    /// How many games it was learned from
+    pub games: usize,
+    /// The largest share, 0 to 1, a threshold is moved by either way
+    pub max_adjustment: f64,
+}
+
+impl ThresholdCalibration {
+    /// Learn from recent games' stats, None when too few had enough players
+    pub fn from_stats(stats: &[DbGameStats], config: &CalibrationConfig) -> Option<Self> {
+        let mut shares: Vec<f64> = stats
+            .iter()
+            .filter(|stats| {
+                stats.completed_players >= config.min_players && stats.optimal_score > 0
+            })
+            .map(|stats| stats.median_score as f64 / stats.optimal_score as f64)
+            .collect();
+        if shares.len() < config.min_games {
+            return None;
+        }
+        shares.sort_by(f64::total_cmp);
+
+        Some(Self {
+            median_share: median(&shares)?,
+            games: shares.len(),
+            max_adjustment: config.max_adjustment,
+        })
+    }
+
+    /// The threshold to publish for a board generated with `threshold_score`
+    /// whose best words score `optimal_score`. It moves by at most
+    /// max_adjustment, and like the week curve's feedback is only raised up
+    /// to 90% of the optimal score.
+    pub fn adjust_threshold(&self, threshold_score: i32, optimal_score: i32) -> i32 {
+        let base = threshold_score as f64;
+        let target = (optimal_score as f64 * self.median_share).clamp(
+            base * (1.0 - self.max_adjustment),
+            base * (1.0 + self.max_adjustment),
+        );
+        let ceiling = threshold_score.max(optimal_score * 9 / 10);
+        (target.round() as i32).min(ceiling).max(1)
+    }
+}
+
+/// Load the calibration to apply to new daily games, None when it's turned
+/// off or recent games don't have enough players to go on
+pub async fn load_calibration<R: Repository>(
+    repo: &R,
+    config: &CalibrationConfig,
+) -> Result<Option<ThresholdCalibration>> {
+    if !config.enabled {
+        return Ok(None);
+    }
+    let stats = repo.get_recent_game_stats(config.games).await?;
+    Ok(ThresholdCalibration::from_stats(&stats, config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{NewGameEntry, NewOptimalSolution, NewUser};
+    use crate::db::InMemoryRepository;
+    use crate::test_utils::create_new_test_game;
+
+    fn stats(completed_players: i32, median_score: i32, optimal_score: i32) -> DbGameStats {
+        DbGameStats {
+            game_id: "game".to_string(),
+            players: completed_players,
+            completed_players,
+            median_score,
+            optimal_score,
+            completion_rate: 1.0,
+            threshold_hit_rate: 0.5,
+            computed_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_due_game_stats() {
+        let repo = InMemoryRepository::new();
+        let mut new_game = create_new_test_game();
+        new_game.date = "2025-06-01".to_string();
+        new_game.threshold_score = 35;
+        let (game, _) = repo
+            .create_game_with_answers(
+                new_game,
+                vec![],
+                Some(NewOptimalSolution {
+                    game_id: String::new(),
+                    words_and_scores: r#"[["paths", 50], ["finder", 30]]"#.to_string(),
+                    total_score: 80,
+                }),
+            )
+            .await
+            .unwrap();
+        for (player, (score, completed)) in
+            [(50, true), (20, true), (40, true), (30, true), (10, false)]
+                .into_iter()
+                .enumerate()
+        {
+            let user = repo
+                .create_user(NewUser {
+                    cookie_token: format!("player-{player}"),
+                })
+                .await
+                .unwrap();
+            repo.create_or_update_game_entry(NewGameEntry {
+                user_id: user.id,
+                game_id: game.id.clone(),
+                answers_data: "[]".to_string(),
+                total_score: score,
+                completed,
+                suspicion_score: 0,
+                exclude: false,
+                request_id: None,
+            })
+            .await
+            .unwrap();
+        }
+
+        let freeze = FreezeConfig::default();
+        let at = |time: &str| {
+            DateTime::parse_from_rfc3339(time)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        let summary = record_due_game_stats(&repo, &freeze, at("2025-06-02T23:00:00Z"))
+            .await
+            .unwrap();
+        assert_eq!(summary, StatsSummary::default());
+
+        let summary = record_due_game_stats(&repo, &freeze, at("2025-06-03T00:00:00Z"))
+            .await
+            .unwrap();
+        assert_eq!(summary, StatsSummary { games_measured: 1 });
+        let recorded = repo.get_recent_game_stats(10).await.unwrap();
+        assert_eq!(recorded.len(), 1);
+        let stats = &recorded[0];
+        assert_eq!((stats.players, stats.completed_players), (5, 4));
+        assert_eq!((stats.median_score, stats.optimal_score), (35, 80));
+        assert_eq!(stats.completion_rate, 0.8);
+        assert_eq!(stats.threshold_hit_rate, 0.5);
+
+        // Each game is measured once
+        let summary = record_due_game_stats(&repo, &freeze, at("2025-06-03T00:00:00Z"))
+            .await
+            .unwrap();
+        assert_eq!(summary, StatsSummary::default());
+    }
+
+    #[test]
+    fn test_calibration_needs_enough_games_with_enough_players() {
+        let config = CalibrationConfig {
+            min_players: 10,
+            min_games: 3,
+            ..CalibrationConfig::default()
+        };
+        let mut recent = vec![stats(10, 30, 60), stats(10, 40, 80), stats(5, 80, 80)];
+        assert_eq!(ThresholdCalibration::from_stats(&recent, &config), None);
+
+        recent.push(stats(12, 30, 50));
+        let calibration = ThresholdCalibration::from_stats(&recent, &config).unwrap();
+        assert_eq!(calibration.games, 3);
+        assert_eq!(calibration.median_share, 0.5);
+    }
+
+    #[test]
+    fn test_calibration_moves_thresholds_within_bounds() {
+        let calibration = ThresholdCalibration {
+            median_share: 0.5,
+            games: 10,
+            max_adjustment: 0.2,
+        };
+        // Aimed at half the optimal score
+        assert_eq!(calibration.adjust_threshold(40, 84), 42);
+        // Moved no more than 20% either way
+        assert_eq!(calibration.adjust_threshold(40, 200), 48);
+        assert_eq!(calibration.adjust_threshold(40, 20), 32);
+        // Never raised past 90% of the optimal score
+        let generous = ThresholdCalibration {
+            median_share: 1.0,
+            ..calibration
+        };
+        assert_eq!(generous.adjust_threshold(40, 50), 45);
+    }
+
+    #[cfg(feature = "database-tests")]
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_sqlite_game_stats_are_replaced(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let repo = crate::db::SqliteRepository::new(pool);
+        let (game, _) = repo
+            .create_game_with_answers(create_new_test_game(), vec![], None)
+            .await
+            .unwrap();
+        assert_eq!(
+            repo.get_games_without_stats_through("9999-12-31")
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let mut recorded = stats(10, 30, 60);
+        recorded.game_id = game.id.clone();
+        repo.upsert_game_stats(recorded.clone()).await.unwrap();
+        recorded.median_score = 35;
+        repo.upsert_game_stats(recorded.clone()).await.unwrap();
+
+        let recent = repo.get_recent_game_stats(10).await.unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].median_score, 35);
+        assert!(repo
+            .get_games_without_stats_through("9999-12-31")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,533 @@
+use anyhow::Context;
+use axum::{
+    extract::{Path, Query, State},
+    response::{Extension, Json},
+    routing::{get, post},
+    Router,
+};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use std::env;
+use tracing::info;
+
+use crate::api_error::ApiError;
+use crate::db::{
+    conversions::AnswerStorage,
+    models::{DbCustomGame, DbUser, NewCustomGame, NewCustomGameScore},
+    Repository,
+};
+use crate::game::conversion::SerializableBoard;
+use crate::game::scoring::BonusRules;
+use crate::game::Board;
+use crate::http_api::{authenticate_user, parse_api_board, ApiAnswer, ApiBoard, ApiState};
+use crate::security::{session::SessionInfo, user_cookie::UserIdentity};
+
+const SLUG_LENGTH: usize = 8;
+const DEFAULT_SCORES_SIZE: i32 = 10;
+const MAX_SCORES_SIZE: i32 = 100;
+
+#[derive(Clone, Debug)]
+pub struct CustomGameConfig {
+    /// Boards whose best five words sum to less than this are turned away,
+    /// as there is too little on them to compare scores over
+    pub min_optimal_score: i32,
+}
+
+impl Default for CustomGameConfig {
+    fn default() -> Self {
+        Self {
+            min_optimal_score: 20,
+        }
+    }
+}
+
+impl CustomGameConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(score) = env::var("CUSTOM_GAME_MIN_OPTIMAL_SCORE") {
+            if let Ok(value) = score.parse::<i32>() {
+                config.min_optimal_score = value;
+            }
+        }
+
+        config
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct NewCustomGameRequest {
+    /// A board code, as /api/board/decode reads, or just the board's 16 letters
+    pub board: String,
+    /// Identify the caller to record them as the game's creator
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiCustomGame {
+    /// Short id to share the game by
+    pub slug: String,
+    pub board_code: String,
+    pub board: ApiBoard,
+    pub optimal_score: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CustomGameSubmitRequest {
+    pub answers: Vec<ApiAnswer>,
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CustomGameSubmitResponse {
+    pub total_score: i32,
+    /// The player's best score on this game so far, including this one
+    pub best_score: i32,
+    pub optimal_score: i32,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct CustomGameScoresQuery {
+    pub limit: Option<i32>,
+    /// Identify the caller to mark their own score
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ApiCustomGameScore {
+    pub rank: i32,
+    pub total_score: i32,
+    pub is_you: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiCustomGameScores {
+    pub optimal_score: i32,
+    pub scores: Vec<ApiCustomGameScore>,
+}
+
+/// Routes under /api/custom-game. Custom games are boards players make and
+/// share by slug; like practice games they stay out of the daily stats, but
+/// each player's best score is kept so everyone who plays can compare.
+pub fn custom_game_router<R: Repository + Clone + Send + Sync + 'static>() -> Router<ApiState<R>> {
+    Router::new()
+        .route("/api/custom-game", post(create_custom_game))
+        .route("/api/custom-game/:slug", get(get_custom_game))
+        .route("/api/custom-game/:slug/submit", post(submit_custom_game))
+        .route("/api/custom-game/:slug/scores", get(get_custom_game_scores))
+}
+
+impl TryFrom<&DbCustomGame> for ApiCustomGame {
+    type Error = ApiError;
+
+    fn try_from(game: &DbCustomGame) -> Result<Self, Self::Error> {
+        Ok(ApiCustomGame {
+            slug: game.slug.clone(),
+            board_code: game.board_code.clone(),
+            board: parse_api_board(&game.board_data)?,
+            optimal_score: game.optimal_score,
+        })
+    }
+}
+
+/// Validate a board and store it as a custom game. The same board always
+/// gets the same slug, so sharing it twice doesn't split its scores.
+async fn create_custom_game<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+    Json(request): Json<NewCustomGameRequest>,
+) -> Result<Json<ApiCustomGame>, ApiError> {
+    let creator = caller(
+        &state,
+        &identity,
+        request.user_id.as_ref(),
+        request.cookie_token.as_ref(),
+        session,
+    )
+    .await;
+
+    let board = Board::from_code(request.board.trim()).map_err(ApiError::validation)?;
+    let board_code = board.to_code().map_err(ApiError::validation)?;
+
+    // Custom games are played under the rules new games are generated with,
+    // with submissions scored on their tiles alone
+    let max_answers = state.game_engine.game_rules().max_answers;
+    let (optimal_words, metadata) = state
+        .game_engine
+        .find_best_n_words(&board, &BonusRules::default(), max_answers)
+        .await
+        .context("Failed to solve custom board")?;
+    if optimal_words.is_empty() {
+        return Err(ApiError::validation("No words can be made on this board"));
+    }
+    let min_optimal_score = state.custom_game_config.min_optimal_score;
+    if metadata.total_score < min_optimal_score {
+        return Err(ApiError::validation(format!(
+            "This board's best {} words score {}, below the {} a custom game needs",
+            max_answers, metadata.total_score, min_optimal_score
+        )));
+    }
+
+    let board_data = serde_json::to_string(&SerializableBoard::from(&board))
+        .context("Failed to serialize custom board")?;
+    let game = state
+        .repository
+        .create_custom_game(NewCustomGame {
+            slug: new_slug(),
+            board_code,
+            board_data,
+            optimal_score: metadata.total_score,
+            created_by: creator.map(|user| user.id),
+        })
+        .await
+        .context("Failed to store custom game")?;
+    info!("Custom game {} has board {}", game.slug, game.board_code);
+
+    ApiCustomGame::try_from(&game).map(Json)
+}
+
+async fn get_custom_game<R: Repository + Clone + Send + Sync + 'static>(
+    Path(slug): Path<String>,
+    State(state): State<ApiState<R>>,
+) -> Result<Json<ApiCustomGame>, ApiError> {
+    let game = load_custom_game(&state, &slug).await?;
+    ApiCustomGame::try_from(&game).map(Json)
+}
+
+/// Score a submission the way practice games are scored, keeping it if it is
+/// the player's best
+async fn submit_custom_game<R: Repository + Clone + Send + Sync + 'static>(
+    Path(slug): Path<String>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+    Json(request): Json<CustomGameSubmitRequest>,
+) -> Result<Json<CustomGameSubmitResponse>, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        request.user_id.as_ref(),
+        request.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+    let game = load_custom_game(&state, &slug).await?;
+
+    let serializable_board: SerializableBoard = serde_json::from_str(&game.board_data)
+        .with_context(|| format!("Failed to parse board of custom game {}", game.slug))?;
+    let board: Board = serializable_board.into();
+
+    if let Err(error) = state
+        .game_engine
+        .validate_api_answer_group(&board, request.answers.clone())
+    {
+        info!("Custom game answer validation failed: {error}");
+        return Err(error.into());
+    }
+
+    let answers_data = AnswerStorage::serialize_api_answers(&request.answers)
+        .context("Failed to serialize custom game answers")?;
+    let answers = request
+        .answers
+        .into_iter()
+        .map(|answer| answer.sanitize().word)
+        .collect();
+    let score_sheet = state
+        .game_engine
+        // Measured against the optimal score, which counts tiles alone too
+        .score_answer_group(&board, answers, &BonusRules::default())
+        .map_err(|error| {
+            info!("Custom game answer scoring failed: {error}");
+            ApiError::from(error)
+        })?;
+    let total_score: i32 = score_sheet
+        .total_score()
+        .try_into()
+        .context("Custom game score is out of range")?;
+
+    let best = state
+        .repository
+        .save_custom_game_score(NewCustomGameScore {
+            custom_game_id: game.id.clone(),
+            user_id: user.id,
+            answers_data,
+            total_score,
+        })
+        .await
+        .with_context(|| format!("Failed to save score for custom game {}", game.slug))?;
+
+    Ok(Json(CustomGameSubmitResponse {
+        total_score,
+        best_score: best.total_score,
+        optimal_score: game.optimal_score,
+    }))
+}
+
+/// The best score of each player of a custom game
+async fn get_custom_game_scores<R: Repository + Clone + Send + Sync + 'static>(
+    Path(slug): Path<String>,
+    Query(query): Query<CustomGameScoresQuery>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+) -> Result<Json<ApiCustomGameScores>, ApiError> {
+    let game = load_custom_game(&state, &slug).await?;
+    let viewer = caller(
+        &state,
+        &identity,
+        query.user_id.as_ref(),
+        query.cookie_token.as_ref(),
+        session,
+    )
+    .await;
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SCORES_SIZE)
+        .clamp(1, MAX_SCORES_SIZE);
+    let scores = state
+        .repository
+        .get_custom_game_scores(&game.id, limit)
+        .await
+        .with_context(|| format!("Failed to load scores for custom game {}", game.slug))?;
+
+    Ok(Json(ApiCustomGameScores {
+        optimal_score: game.optimal_score,
+        scores: scores
+            .into_iter()
+            .enumerate()
+            .map(|(index, score)| ApiCustomGameScore {
+                rank: index as i32 + 1,
+                total_score: score.total_score,
+                is_you: viewer.as_ref().is_some_and(|user| user.id == score.user_id),
+            })
+            .collect(),
+    }))
+}
+
+/// The caller, when they identified themselves and could be authenticated.
+/// These routes work for anyone, so failing to authenticate isn't an error.
+pub(crate) async fn caller<R: Repository + Clone + Send + Sync + 'static>(
+    state: &ApiState<R>,
+    identity: &UserIdentity,
+    user_id: Option<&String>,
+    cookie_token: Option<&String>,
+    session: Option<Extension<SessionInfo>>,
+) -> Option<DbUser> {
+    if user_id.is_none() && cookie_token.is_none() && session.is_none() {
+        return None;
+    }
+    authenticate_user(state, identity, user_id, cookie_token, session.as_deref())
+        .await
+        .ok()
+}
+
+async fn load_custom_game<R: Repository>(
+    state: &ApiState<R>,
+    slug: &str,
+) -> Result<DbCustomGame, ApiError> {
+    state
+        .repository
+        .get_custom_game(slug)
+        .await
+        .context("Failed to get custom game")?
+        .ok_or_else(|| ApiError::NotFound("Custom game not found".to_string()))
+}
+
+fn new_slug() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(SLUG_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+#[cfg(all(test, feature = "database-tests"))]
+mod tests {
+    use super::*;
+    use axum::http::{Method, StatusCode};
+    use tower::util::ServiceExt;
+
+    use crate::db::models::NewUser;
+    use crate::game::rules::GameRules;
+    use crate::http_api::create_secure_router;
+    use crate::security::SecurityConfig;
+    use crate::test_utils::{create_test_game_engine, create_test_request, setup_app};
+
+    async fn call<T: serde::de::DeserializeOwned>(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<&str>,
+    ) -> T {
+        let response = app
+            .clone()
+            .oneshot(create_test_request(method, uri, body))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_custom_game_can_be_shared_and_compared(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let (state, _app) = setup_app(pool).await;
+
+        // The test board's best five words score 24, short of this minimum
+        let strict = state.clone().with_custom_game_config(CustomGameConfig {
+            min_optimal_score: 25,
+        });
+        let app = create_secure_router(strict, SecurityConfig::default());
+        let body = r#"{"board": "testh*ngar*astop"}"#;
+        let request = create_test_request(Method::POST, "/api/custom-game", Some(body));
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let state = state.with_custom_game_config(CustomGameConfig {
+            min_optimal_score: 24,
+        });
+        let app = create_secure_router(state.clone(), SecurityConfig::default());
+        let mut players = Vec::new();
+        for cookie_token in ["maker", "friend"] {
+            let user = state
+                .repository
+                .create_user(NewUser {
+                    cookie_token: cookie_token.to_string(),
+                })
+                .await
+                .unwrap();
+            players.push(user);
+        }
+        let (maker, friend) = (&players[0], &players[1]);
+
+        // Nothing in the test wordlist can be made from these
+        let body = r#"{"board": "zzzzzzzzzzzzzzzz"}"#;
+        let request = create_test_request(Method::POST, "/api/custom-game", Some(body));
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = format!(
+            r#"{{"board": "testh*ngar*astop", "user_id": "{}", "cookie_token": "maker"}}"#,
+            maker.id
+        );
+        let created: ApiCustomGame =
+            call(&app, Method::POST, "/api/custom-game", Some(&body)).await;
+        assert_eq!(created.board_code, "testh*ngar*astop");
+        assert_eq!(created.board.tiles.len(), 4);
+        assert_eq!(created.optimal_score, 24);
+        let stored = state
+            .repository
+            .get_custom_game(&created.slug)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.created_by.as_deref(), Some(maker.id.as_str()));
+
+        // The same board keeps its slug
+        let again: ApiCustomGame = call(
+            &app,
+            Method::POST,
+            "/api/custom-game",
+            Some(r#"{"board": "testh*ngar*astop"}"#),
+        )
+        .await;
+        assert_eq!(again.slug, created.slug);
+
+        let uri = format!("/api/custom-game/{}", created.slug);
+        let fetched: ApiCustomGame = call(&app, Method::GET, &uri, None).await;
+        assert_eq!(fetched.optimal_score, created.optimal_score);
+
+        let submit_uri = format!("/api/custom-game/{}/submit", created.slug);
+        let submit = |user_id: &str, cookie_token: &str, words: &[&str]| {
+            let answers: Vec<String> = words
+                .iter()
+                .map(|word| format!(r#"{{"word": "{word}", "score": 0}}"#))
+                .collect();
+            format!(
+                r#"{{"answers": [{}], "user_id": "{}", "cookie_token": "{}"}}"#,
+                answers.join(", "),
+                user_id,
+                cookie_token
+            )
+        };
+
+        let body = submit(&maker.id, "maker", &["test", "stop"]);
+        let first: CustomGameSubmitResponse =
+            call(&app, Method::POST, &submit_uri, Some(&body)).await;
+        assert_eq!(first.best_score, first.total_score);
+
+        // A worse try doesn't replace the maker's best
+        let body = submit(&maker.id, "maker", &["test"]);
+        let second: CustomGameSubmitResponse =
+            call(&app, Method::POST, &submit_uri, Some(&body)).await;
+        assert!(second.total_score < first.total_score);
+        assert_eq!(second.best_score, first.total_score);
+
+        let body = submit(&friend.id, "friend", &["stop"]);
+        let _: CustomGameSubmitResponse = call(&app, Method::POST, &submit_uri, Some(&body)).await;
+
+        let body = submit(&friend.id, "wrong", &["stop"]);
+        let request = create_test_request(Method::POST, &submit_uri, Some(&body));
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let body = submit(&friend.id, "friend", &["zzz"]);
+        let request = create_test_request(Method::POST, &submit_uri, Some(&body));
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let uri = format!(
+            "/api/custom-game/{}/scores?user_id={}&cookie_token=friend",
+            created.slug, friend.id
+        );
+        let scores: ApiCustomGameScores = call(&app, Method::GET, &uri, None).await;
+        assert_eq!(scores.optimal_score, created.optimal_score);
+        assert_eq!(scores.scores.len(), 2);
+        assert_eq!(scores.scores[0].total_score, first.total_score);
+        assert!(!scores.scores[0].is_you);
+        assert_eq!(scores.scores[1].rank, 2);
+        assert!(scores.scores[1].is_you);
+
+        let request = create_test_request(Method::GET, "/api/custom-game/missing", None);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_custom_game_is_solved_under_the_configured_rules(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let (state, _app) = setup_app(pool).await;
+        let rules = GameRules {
+            max_answers: 1,
+            ..GameRules::default()
+        };
+        let engine = create_test_game_engine().0.with_game_rules(rules);
+        let board = Board::from_code("testh*ngar*astop").unwrap();
+        let (_, best_word) = engine
+            .find_best_n_words(&board, &BonusRules::default(), 1)
+            .await
+            .unwrap();
+        let state = ApiState::new(state.repository.clone(), engine).with_custom_game_config(
+            CustomGameConfig {
+                min_optimal_score: 1,
+            },
+        );
+        let app = create_secure_router(state, SecurityConfig::default());
+
+        let created: ApiCustomGame = call(
+            &app,
+            Method::POST,
+            "/api/custom-game",
+            Some(r#"{"board": "testh*ngar*astop"}"#),
+        )
+        .await;
+        assert_eq!(created.optimal_score, best_word.total_score);
+        assert!(created.optimal_score < 24);
+    }
+}
@@ -0,0 +1,186 @@
+use anyhow::{anyhow, Result};
+use chrono::{Duration, NaiveDate};
+use std::env;
+use tracing::{info, warn};
+
+use crate::db::{storage_types::DbCompactedAnswers, Repository};
+
+#[derive(Clone, Debug)]
+pub struct CompactionConfig {
+    /// Games from the last `keep_days` days keep their answers as rows
+    pub keep_days: i64,
+    /// How many games are compacted per repository query
+    pub batch_size: i32,
+}
+
+impl Default for CompactionConfig {
+    fn default() -> Self {
+        Self {
+            keep_days: 30,
+            batch_size: 100,
+        }
+    }
+}
+
+impl CompactionConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(keep_days) = env::var("ANSWER_COMPACTION_KEEP_DAYS") {
+            if let Ok(value) = keep_days.parse::<i64>() {
+                config.keep_days = value.max(0);
+            }
+        }
+
+        if let Ok(batch_size) = env::var("ANSWER_COMPACTION_BATCH_SIZE") {
+            if let Ok(value) = batch_size.parse::<i32>() {
+                config.batch_size = value.max(1);
+            }
+        }
+
+        config
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CompactionSummary {
+    pub games_compacted: usize,
+    pub rows_deleted: u64,
+}
+
+/// Compact the answer rows of every game older than the configured window.
+/// Games are processed oldest first in batches and each one is committed on
+/// its own, so an interrupted run can simply be started again.
+pub async fn compact_answers<R: Repository>(
+    repo: &R,
+    config: &CompactionConfig,
+    today: NaiveDate,
+) -> Result<CompactionSummary> {
+    let cutoff = (today - Duration::days(config.keep_days))
+        .format("%Y-%m-%d")
+        .to_string();
+    let mut summary = CompactionSummary::default();
+
+    loop {
+        let game_ids = repo
+            .get_uncompacted_game_ids(&cutoff, config.batch_size)
+            .await?;
+        if game_ids.is_empty() {
+            break;
+        }
+
+        for game_id in game_ids {
+            summary.rows_deleted += compact_game(repo, &game_id).await?;
+            summary.games_compacted += 1;
+        }
+
+        info!(
+            "Compacted answers for {} games before {}",
+            summary.games_compacted, cutoff
+        );
+    }
+
+    Ok(summary)
+}
+
+/// Compact one game's answers, checking the blob reads back to the same
+/// answers before any rows are deleted
+async fn compact_game<R: Repository>(repo: &R, game_id: &str) -> Result<u64> {
+    let answers = repo.get_game_answers(game_id).await?;
+    let compacted = DbCompactedAnswers::from(answers.as_slice());
+    let blob = compacted
+        .compress()
+        .map_err(|e| anyhow!("Failed to compress answers for game {game_id}: {e}"))?;
+
+    let read_back = DbCompactedAnswers::decompress(&blob)
+        .map_err(|e| anyhow!("Failed to read back answers for game {game_id}: {e}"))?;
+    if read_back != compacted {
+        return Err(anyhow!(
+            "Compacted answers for game {game_id} did not round trip"
+        ));
+    }
+
+    let deleted = repo.store_compacted_answers(game_id, blob).await?;
+    if deleted as usize != answers.len() {
+        warn!(
+            "Expected to delete {} answer rows for game {} but deleted {}",
+            answers.len(),
+            game_id,
+            deleted
+        );
+    }
+
+    Ok(deleted)
+}
+
+#[cfg(all(test, feature = "database-tests"))]
+mod tests {
+    use super::*;
+    use crate::db::models::{NewGameAnswer, WordPathStats};
+    use crate::db::SqliteRepository;
+    use crate::test_utils::create_new_test_game;
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_compaction_keeps_answers_readable(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let repo = SqliteRepository::new(pool);
+
+        let mut game_ids = Vec::new();
+        for (sequence_number, date) in [(1, "2025-01-01"), (2, "2025-06-01")] {
+            let mut new_game = create_new_test_game();
+            new_game.date = date.to_string();
+            new_game.sequence_number = sequence_number;
+            let answers = ["stop", "test"]
+                .iter()
+                .map(|word| NewGameAnswer {
+                    game_id: String::new(),
+                    word: word.to_string(),
+                    path_stats: Some(WordPathStats {
+                        path_count: 1,
+                        requires_wildcard: false,
+                    }),
+                })
+                .collect();
+            let (game, _) = repo
+                .create_game_with_answers(new_game, answers, None)
+                .await
+                .unwrap();
+            game_ids.push(game.id);
+        }
+        let before = repo.get_game_answers(&game_ids[0]).await.unwrap();
+
+        let today = NaiveDate::from_ymd_opt(2025, 6, 10).unwrap();
+        let summary = compact_answers(&repo, &CompactionConfig::default(), today)
+            .await
+            .unwrap();
+        assert_eq!(
+            summary,
+            CompactionSummary {
+                games_compacted: 1,
+                rows_deleted: 2,
+            }
+        );
+
+        // The old game reads the same from its blob; the recent one keeps its rows
+        let after = repo.get_game_answers(&game_ids[0]).await.unwrap();
+        assert_eq!(
+            after.iter().map(|a| &a.word).collect::<Vec<_>>(),
+            before.iter().map(|a| &a.word).collect::<Vec<_>>()
+        );
+        assert_eq!(after[0].path_stats, before[0].path_stats);
+        assert_eq!(
+            repo.get_game_words(&game_ids[0]).await.unwrap(),
+            vec!["stop", "test"]
+        );
+        assert!(repo
+            .get_uncompacted_game_ids("2025-12-31", 10)
+            .await
+            .unwrap()
+            .contains(&game_ids[1]));
+
+        // Running again finds nothing left to do
+        let summary = compact_answers(&repo, &CompactionConfig::default(), today)
+            .await
+            .unwrap();
+        assert_eq!(summary, CompactionSummary::default());
+    }
+}
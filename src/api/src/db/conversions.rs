@@ -1,5 +1,10 @@
-use crate::db::storage_types::{DbAnswer, DbPosition, DbStoredAnswers};
-use crate::http_api::{ApiAnswer, ApiPosition};
+use crate::db::models::{DbEntryAnswer, DbGameAnswer, WordPathStats};
+use crate::db::storage_types::{
+    DbAnswer, DbCompactedAnswer, DbCompactedAnswers, DbPosition, DbStoredAnswers, DbTracedPath,
+    DbWildcardChoice,
+};
+use crate::game::board::answer::Answer;
+use crate::http_api::{ApiAnswer, ApiAnswerPath, ApiPathConstraintSet, ApiPosition};
 
 // Conversion functions between HTTP API types and stable database types.
 // These provide a compatibility layer that allows API types to evolve
@@ -10,6 +15,7 @@ impl From<ApiAnswer> for DbAnswer {
         Self {
             word: api.word,
             score: api.score,
+            traced_path: api.path.map(DbTracedPath::from),
         }
     }
 }
@@ -19,6 +25,46 @@ impl From<DbAnswer> for ApiAnswer {
         Self {
             word: db.word,
             score: db.score,
+            path: db.traced_path.map(ApiAnswerPath::from),
+        }
+    }
+}
+
+impl From<ApiAnswerPath> for DbTracedPath {
+    fn from(api: ApiAnswerPath) -> Self {
+        let choice = |wildcard, letter| DbWildcardChoice { wildcard, letter };
+        let wildcards = match api.constraints {
+            ApiPathConstraintSet::Unconstrainted => vec![],
+            ApiPathConstraintSet::FirstDecided(first) => vec![choice(0, first)],
+            ApiPathConstraintSet::SecondDecided(second) => vec![choice(1, second)],
+            ApiPathConstraintSet::BothDecided(first, second) => {
+                vec![choice(0, first), choice(1, second)]
+            }
+        };
+        Self {
+            positions: api.positions.into_iter().map(DbPosition::from).collect(),
+            wildcards,
+        }
+    }
+}
+
+impl From<DbTracedPath> for ApiAnswerPath {
+    fn from(db: DbTracedPath) -> Self {
+        let letter_for = |wildcard| {
+            db.wildcards
+                .iter()
+                .find(|choice| choice.wildcard == wildcard)
+                .map(|choice| choice.letter)
+        };
+        let constraints = match (letter_for(0), letter_for(1)) {
+            (None, None) => ApiPathConstraintSet::Unconstrainted,
+            (Some(first), None) => ApiPathConstraintSet::FirstDecided(first),
+            (None, Some(second)) => ApiPathConstraintSet::SecondDecided(second),
+            (Some(first), Some(second)) => ApiPathConstraintSet::BothDecided(first, second),
+        };
+        Self {
+            positions: db.positions.into_iter().map(ApiPosition::from).collect(),
+            constraints,
         }
     }
 }
@@ -54,6 +100,55 @@ impl From<DbStoredAnswers> for Vec<ApiAnswer> {
     }
 }
 
+impl From<&Answer> for WordPathStats {
+    fn from(answer: &Answer) -> Self {
+        Self {
+            path_count: answer.path_count() as i32,
+            requires_wildcard: answer.requires_wildcard(),
+        }
+    }
+}
+
+impl From<&[DbGameAnswer]> for DbCompactedAnswers {
+    fn from(answers: &[DbGameAnswer]) -> Self {
+        DbCompactedAnswers::new(
+            answers
+                .iter()
+                .map(|answer| DbCompactedAnswer {
+                    word: answer.word.clone(),
+                    path_count: answer.path_stats.as_ref().map(|stats| stats.path_count),
+                    requires_wildcard: answer
+                        .path_stats
+                        .as_ref()
+                        .map(|stats| stats.requires_wildcard),
+                    created_at: answer.created_at,
+                })
+                .collect(),
+        )
+    }
+}
+
+impl DbCompactedAnswers {
+    /// Expand back into the rows the answers were compacted from
+    pub fn into_game_answers(self, game_id: &str) -> Vec<DbGameAnswer> {
+        self.answers
+            .into_iter()
+            .map(|answer| DbGameAnswer {
+                game_id: game_id.to_string(),
+                word: answer.word,
+                path_stats: match (answer.path_count, answer.requires_wildcard) {
+                    (Some(path_count), Some(requires_wildcard)) => Some(WordPathStats {
+                        path_count,
+                        requires_wildcard,
+                    }),
+                    _ => None,
+                },
+                created_at: answer.created_at,
+            })
+            .collect()
+    }
+}
+
 /// Helper functions for working with answer data in the database
 pub struct AnswerStorage;
 
@@ -71,6 +166,26 @@ impl AnswerStorage {
         let stored = DbStoredAnswers::from_json(json)?;
         Ok(Vec::<ApiAnswer>::from(stored))
     }
+
+    /// Split answers_data written in any version into the rows of
+    /// game_entry_answers, one per answer, repeated words included
+    pub fn entry_answers(json: &str) -> Result<Vec<DbEntryAnswer>, Box<dyn std::error::Error>> {
+        let stored = DbStoredAnswers::from_json(json)?;
+        let mut answers = Vec::with_capacity(stored.answers.len());
+        for (position, answer) in stored.answers.into_iter().enumerate() {
+            answers.push(DbEntryAnswer {
+                position: position.try_into()?,
+                path_json: answer
+                    .traced_path
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()?,
+                word: answer.word,
+                score: answer.score,
+            });
+        }
+        Ok(answers)
+    }
 }
 
 #[cfg(test)]
@@ -82,6 +197,7 @@ mod tests {
         let api_answer = ApiAnswer {
             word: "test".to_string(),
             score: 15,
+            path: None,
         };
 
         let db_answer = DbAnswer::from(api_answer.clone());
@@ -97,10 +213,12 @@ mod tests {
             ApiAnswer {
                 word: "hello".to_string(),
                 score: 10,
+                path: None,
             },
             ApiAnswer {
                 word: "world".to_string(),
                 score: 20,
+                path: None,
             },
         ];
 
@@ -111,19 +229,93 @@ mod tests {
         assert_eq!(api_answers[0].word, deserialized[0].word);
     }
 
+    #[test]
+    fn test_answer_storage_keeps_traced_paths() {
+        let api_answers = vec![ApiAnswer {
+            word: "dog".to_string(),
+            score: 4,
+            path: Some(ApiAnswerPath {
+                positions: vec![
+                    ApiPosition { row: 3, col: 2 },
+                    ApiPosition { row: 2, col: 3 },
+                    ApiPosition { row: 3, col: 3 },
+                ],
+                constraints: ApiPathConstraintSet::SecondDecided('o'),
+            }),
+        }];
+
+        let json = AnswerStorage::serialize_api_answers(&api_answers).unwrap();
+        assert!(json.contains(r#""wildcards":[{"letter":"o","wildcard":1}]"#));
+        let deserialized = AnswerStorage::deserialize_to_api_answers(&json).unwrap();
+        assert_eq!(deserialized[0].path, api_answers[0].path);
+
+        // Answers stored without a path still read back
+        let json = r#"{"version":"1.0","answers":[{"word":"dog","score":4}]}"#;
+        let deserialized = AnswerStorage::deserialize_to_api_answers(json).unwrap();
+        assert_eq!(deserialized[0].path, None);
+
+        // As do paths stored before wildcard choices were listed
+        let json = r#"{"version":"1.0","answers":[{"word":"dog","score":4,
+            "traced_path":{"positions":[{"row":3,"col":2},{"row":2,"col":3},{"row":3,"col":3}],
+            "second_wildcard":"o"}}]}"#;
+        let deserialized = AnswerStorage::deserialize_to_api_answers(json).unwrap();
+        assert_eq!(deserialized[0].path, api_answers[0].path);
+    }
+
     #[test]
     fn test_versioned_storage() {
         let api_answers = vec![ApiAnswer {
             word: "test".to_string(),
             score: 5,
+            path: None,
         }];
 
         let json = AnswerStorage::serialize_api_answers(&api_answers).unwrap();
 
         // Verify the JSON contains version information
-        assert!(json.contains(r#""version":"1.0""#));
+        assert!(json.contains(r#""version":2"#));
 
         let deserialized = AnswerStorage::deserialize_to_api_answers(&json).unwrap();
         assert_eq!(api_answers[0].word, deserialized[0].word);
     }
+
+    #[test]
+    fn test_entry_answers() {
+        let api_answers = vec![
+            ApiAnswer {
+                word: "dog".to_string(),
+                score: 4,
+                path: Some(ApiAnswerPath {
+                    positions: vec![ApiPosition { row: 0, col: 0 }],
+                    constraints: ApiPathConstraintSet::Unconstrainted,
+                }),
+            },
+            ApiAnswer {
+                word: "god".to_string(),
+                score: 4,
+                path: None,
+            },
+            ApiAnswer {
+                word: "dog".to_string(),
+                score: 9,
+                path: None,
+            },
+        ];
+        let json = AnswerStorage::serialize_api_answers(&api_answers).unwrap();
+
+        let rows = AnswerStorage::entry_answers(&json).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!((rows[0].word.as_str(), rows[0].score), ("dog", 4));
+        assert_eq!(
+            (rows[2].position, rows[2].word.as_str(), rows[2].score),
+            (2, "dog", 9)
+        );
+        assert_eq!(
+            rows[0].path_json.as_deref(),
+            Some(r#"{"positions":[{"row":0,"col":0}]}"#)
+        );
+        assert_eq!(rows[1].path_json, None);
+
+        assert!(AnswerStorage::entry_answers("not answers").is_err());
+    }
 }
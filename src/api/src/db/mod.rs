@@ -1,18 +1,161 @@
+pub mod compaction;
 pub mod conversions;
 pub mod models;
 pub mod repository;
+pub mod repository_memory;
+pub mod repository_metered;
+pub mod repository_postgres;
 pub mod repository_sqlite;
 pub mod storage_types;
 
 pub use models::OptimalAnswer;
 pub use repository::Repository;
+pub use repository_memory::InMemoryRepository;
+pub use repository_metered::MeteredRepository;
+pub use repository_postgres::PgRepository;
 pub use repository_sqlite::SqliteRepository;
 
-use anyhow::Result;
-use sqlx::postgres::PgPool;
-use sqlx::{migrate::MigrateDatabase, sqlite::SqlitePool, Sqlite};
+use anyhow::{anyhow, Result};
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
+use sqlx::{migrate::MigrateDatabase, sqlite::SqlitePool, Postgres, Sqlite};
+use std::{env, str::FromStr, time::Duration};
 
-pub async fn setup_database(sqlite_database_url: &str) -> Result<SqlitePool> {
+/// DatabaseBackend selects which Repository implementation binaries run against.
+/// SQLite is the default for local development; production can opt into Postgres.
+/// Memory keeps everything in the API server's process, for demo deployments
+/// whose data doesn't need to survive a restart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseBackend {
+    Sqlite,
+    Postgres,
+    Memory,
+}
+
+impl FromStr for DatabaseBackend {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "sqlite" => Ok(DatabaseBackend::Sqlite),
+            "postgres" | "postgresql" => Ok(DatabaseBackend::Postgres),
+            "memory" => Ok(DatabaseBackend::Memory),
+            other => Err(anyhow!("Unknown DATABASE_BACKEND: {other}")),
+        }
+    }
+}
+
+impl DatabaseBackend {
+    /// from_env reads DATABASE_BACKEND, defaulting to SQLite when unset
+    pub fn from_env() -> Result<Self> {
+        match env::var("DATABASE_BACKEND") {
+            Ok(value) => value.parse(),
+            Err(_) => Ok(DatabaseBackend::Sqlite),
+        }
+    }
+
+    /// database_url returns the connection url for this backend: SQLITE_DATABASE_URL
+    /// for SQLite, DATABASE_URL for Postgres and nothing for Memory
+    pub fn database_url(&self) -> Result<String> {
+        match self {
+            DatabaseBackend::Sqlite => Ok(env::var("SQLITE_DATABASE_URL")
+                .unwrap_or_else(|_| "sqlite://pathfinder.db".to_string())),
+            DatabaseBackend::Postgres => env::var("DATABASE_URL")
+                .map_err(|_| anyhow!("DATABASE_URL must be set when DATABASE_BACKEND=postgres")),
+            DatabaseBackend::Memory => Ok(String::new()),
+        }
+    }
+}
+
+/// The error binaries that run apart from the API server give for the
+/// in-memory backend, since they could never see the server's data
+pub fn memory_backend_unsupported(binary: &str) -> anyhow::Error {
+    anyhow!("{binary} needs a database; DATABASE_BACKEND=memory only works for the API server")
+}
+
+/// SqliteConfig tunes the connections opened to a SQLite database
+#[derive(Debug, Clone)]
+pub struct SqliteConfig {
+    /// Connections available for reads
+    pub max_connections: u32,
+    /// How long a connection waits on a lock before failing with SQLITE_BUSY
+    pub busy_timeout: Duration,
+    pub journal_mode: SqliteJournalMode,
+    pub synchronous: SqliteSynchronous,
+    /// Read through a pool of read-only connections and write through a
+    /// single connection, so writes queue among themselves without taking
+    /// connections from reads. Only concurrent in WAL mode.
+    pub separate_read_pool: bool,
+}
+
+impl Default for SqliteConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 10,
+            busy_timeout: Duration::from_secs(5),
+            journal_mode: SqliteJournalMode::Wal,
+            // NORMAL can't corrupt a WAL database, it only risks losing the
+            // last commits on power loss
+            synchronous: SqliteSynchronous::Normal,
+            separate_read_pool: true,
+        }
+    }
+}
+
+impl SqliteConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(max) = env::var("SQLITE_MAX_CONNECTIONS") {
+            if let Ok(value) = max.parse::<u32>() {
+                config.max_connections = value.max(1);
+            }
+        }
+
+        if let Ok(timeout) = env::var("SQLITE_BUSY_TIMEOUT_MS") {
+            if let Ok(value) = timeout.parse::<u64>() {
+                config.busy_timeout = Duration::from_millis(value);
+            }
+        }
+
+        if let Ok(mode) = env::var("SQLITE_JOURNAL_MODE") {
+            if let Ok(value) = mode.parse() {
+                config.journal_mode = value;
+            }
+        }
+
+        if let Ok(synchronous) = env::var("SQLITE_SYNCHRONOUS") {
+            if let Ok(value) = synchronous.parse() {
+                config.synchronous = value;
+            }
+        }
+
+        if let Ok(separate) = env::var("SQLITE_SEPARATE_READ_POOL") {
+            config.separate_read_pool = separate.to_lowercase() != "false";
+        }
+
+        config
+    }
+}
+
+/// The pools a SQLite database is used through. `reader` is the same pool
+/// as `writer` unless reads are given their own.
+#[derive(Clone)]
+pub struct SqlitePools {
+    pub writer: SqlitePool,
+    pub reader: SqlitePool,
+}
+
+impl SqlitePools {
+    pub fn repository(&self) -> SqliteRepository {
+        SqliteRepository::new(self.writer.clone()).with_reader(self.reader.clone())
+    }
+}
+
+pub async fn setup_database(
+    sqlite_database_url: &str,
+    config: &SqliteConfig,
+) -> Result<SqlitePools> {
     if !Sqlite::database_exists(sqlite_database_url)
         .await
         .unwrap_or(false)
@@ -20,14 +163,62 @@ pub async fn setup_database(sqlite_database_url: &str) -> Result<SqlitePool> {
         Sqlite::create_database(sqlite_database_url).await?;
     }
 
-    let sqlite_pool = SqlitePool::connect(sqlite_database_url).await?;
+    let options =
+        SqliteConnectOptions::from_str(sqlite_database_url)?.busy_timeout(config.busy_timeout);
+    // Every connection to an in-memory database gets its own database, so
+    // those can't be split
+    let separate_read_pool = config.separate_read_pool && !is_in_memory(sqlite_database_url);
+
+    let writer = SqlitePoolOptions::new()
+        .max_connections(if separate_read_pool {
+            1
+        } else {
+            config.max_connections
+        })
+        .connect_with(
+            options
+                .clone()
+                .journal_mode(config.journal_mode)
+                .synchronous(config.synchronous),
+        )
+        .await?;
+
+    run_migrations_sqlite(&writer).await?;
+
+    let reader = if separate_read_pool {
+        SqlitePoolOptions::new()
+            .max_connections(config.max_connections)
+            .connect_with(options.read_only(true))
+            .await?
+    } else {
+        writer.clone()
+    };
+
+    Ok(SqlitePools { writer, reader })
+}
+
+fn is_in_memory(sqlite_database_url: &str) -> bool {
+    sqlite_database_url.contains(":memory:") || sqlite_database_url.contains("mode=memory")
+}
+
+pub async fn setup_postgres_database(postgres_database_url: &str) -> Result<PgPool> {
+    if !Postgres::database_exists(postgres_database_url)
+        .await
+        .unwrap_or(false)
+    {
+        Postgres::create_database(postgres_database_url).await?;
+    }
+
+    let postgres_pool = PgPoolOptions::new()
+        .max_connections(10)
+        .connect(postgres_database_url)
+        .await?;
 
-    run_migrations_sqlite(&sqlite_pool).await?;
+    run_migrations_postgres(&postgres_pool).await?;
 
-    Ok(sqlite_pool)
+    Ok(postgres_pool)
 }
 
-#[allow(unused)]
 async fn run_migrations_postgres(pool: &PgPool) -> Result<()> {
     // Create migrations table if it doesn't exist
     sqlx::query(
@@ -72,6 +263,150 @@ async fn run_migrations_postgres(pool: &PgPool) -> Result<()> {
             "007_change_completed_at_to_timestamp_tz.sql",
             include_str!("../../migrations/postgres/007_change_completed_at_to_timestamp_tz.sql"),
         ),
+        (
+            "008_game_answers_composite_key.sql",
+            include_str!("../../migrations/postgres/008_game_answers_composite_key.sql"),
+        ),
+        (
+            "009_add_game_answer_path_stats.sql",
+            include_str!("../../migrations/postgres/009_add_game_answer_path_stats.sql"),
+        ),
+        (
+            "010_add_hint_usage.sql",
+            include_str!("../../migrations/postgres/010_add_hint_usage.sql"),
+        ),
+        (
+            "011_add_user_sessions.sql",
+            include_str!("../../migrations/postgres/011_add_user_sessions.sql"),
+        ),
+        (
+            "012_add_practice_games.sql",
+            include_str!("../../migrations/postgres/012_add_practice_games.sql"),
+        ),
+        (
+            "013_add_compacted_answers.sql",
+            include_str!("../../migrations/postgres/013_add_compacted_answers.sql"),
+        ),
+        (
+            "014_add_game_announcements.sql",
+            include_str!("../../migrations/postgres/014_add_game_announcements.sql"),
+        ),
+        (
+            "015_add_rate_limit_windows.sql",
+            include_str!("../../migrations/postgres/015_add_rate_limit_windows.sql"),
+        ),
+        (
+            "016_add_admin_tokens.sql",
+            include_str!("../../migrations/postgres/016_add_admin_tokens.sql"),
+        ),
+        (
+            "017_add_accounts.sql",
+            include_str!("../../migrations/postgres/017_add_accounts.sql"),
+        ),
+        (
+            "018_add_stats_freeze_and_notifications.sql",
+            include_str!("../../migrations/postgres/018_add_stats_freeze_and_notifications.sql"),
+        ),
+        (
+            "019_add_board_density.sql",
+            include_str!("../../migrations/postgres/019_add_board_density.sql"),
+        ),
+        (
+            "020_add_outbox_events.sql",
+            include_str!("../../migrations/postgres/020_add_outbox_events.sql"),
+        ),
+        (
+            "021_add_game_starts.sql",
+            include_str!("../../migrations/postgres/021_add_game_starts.sql"),
+        ),
+        (
+            "022_add_game_generation_version.sql",
+            include_str!("../../migrations/postgres/022_add_game_generation_version.sql"),
+        ),
+        (
+            "023_add_game_difficulty.sql",
+            include_str!("../../migrations/postgres/023_add_game_difficulty.sql"),
+        ),
+        (
+            "024_add_game_theme.sql",
+            include_str!("../../migrations/postgres/024_add_game_theme.sql"),
+        ),
+        (
+            "025_add_word_challenges.sql",
+            include_str!("../../migrations/postgres/025_add_word_challenges.sql"),
+        ),
+        (
+            "026_add_game_scoring_scheme.sql",
+            include_str!("../../migrations/postgres/026_add_game_scoring_scheme.sql"),
+        ),
+        (
+            "027_add_game_bonus_rules.sql",
+            include_str!("../../migrations/postgres/027_add_game_bonus_rules.sql"),
+        ),
+        (
+            "028_add_entry_suspicion_score.sql",
+            include_str!("../../migrations/postgres/028_add_entry_suspicion_score.sql"),
+        ),
+        (
+            "029_add_entry_revisions.sql",
+            include_str!("../../migrations/postgres/029_add_entry_revisions.sql"),
+        ),
+        (
+            "030_add_entry_request_id.sql",
+            include_str!("../../migrations/postgres/030_add_entry_request_id.sql"),
+        ),
+        (
+            "031_add_custom_games.sql",
+            include_str!("../../migrations/postgres/031_add_custom_games.sql"),
+        ),
+        (
+            "032_add_blitz_games.sql",
+            include_str!("../../migrations/postgres/032_add_blitz_games.sql"),
+        ),
+        (
+            "033_add_webhook_subscriptions.sql",
+            include_str!("../../migrations/postgres/033_add_webhook_subscriptions.sql"),
+        ),
+        (
+            "034_add_discord_links.sql",
+            include_str!("../../migrations/postgres/034_add_discord_links.sql"),
+        ),
+        (
+            "035_add_audit_log.sql",
+            include_str!("../../migrations/postgres/035_add_audit_log.sql"),
+        ),
+        (
+            "036_add_game_language.sql",
+            include_str!("../../migrations/postgres/036_add_game_language.sql"),
+        ),
+        (
+            "037_add_board_quality.sql",
+            include_str!("../../migrations/postgres/037_add_board_quality.sql"),
+        ),
+        (
+            "038_add_game_stats.sql",
+            include_str!("../../migrations/postgres/038_add_game_stats.sql"),
+        ),
+        (
+            "039_add_game_entry_answers.sql",
+            include_str!("../../migrations/postgres/039_add_game_entry_answers.sql"),
+        ),
+        (
+            "040_add_game_revisions.sql",
+            include_str!("../../migrations/postgres/040_add_game_revisions.sql"),
+        ),
+        (
+            "041_add_tournaments.sql",
+            include_str!("../../migrations/postgres/041_add_tournaments.sql"),
+        ),
+        (
+            "042_add_game_rules.sql",
+            include_str!("../../migrations/postgres/042_add_game_rules.sql"),
+        ),
+        (
+            "043_key_entry_answers_by_position.sql",
+            include_str!("../../migrations/postgres/043_key_entry_answers_by_position.sql"),
+        ),
     ];
 
     for (filename, migration_sql) in &migrations {
@@ -138,6 +473,150 @@ async fn run_migrations_sqlite(pool: &SqlitePool) -> Result<()> {
             "20250924213229_remove_idx_game_answers_word.sql",
             include_str!("../../migrations/sqlite/20250924213229_remove_idx_game_answers_word.sql"),
         ),
+        (
+            "20251015120000_add_game_answer_path_stats.sql",
+            include_str!("../../migrations/sqlite/20251015120000_add_game_answer_path_stats.sql"),
+        ),
+        (
+            "20251016120000_add_hint_usage.sql",
+            include_str!("../../migrations/sqlite/20251016120000_add_hint_usage.sql"),
+        ),
+        (
+            "20251017120000_add_user_sessions.sql",
+            include_str!("../../migrations/sqlite/20251017120000_add_user_sessions.sql"),
+        ),
+        (
+            "20251018120000_add_practice_games.sql",
+            include_str!("../../migrations/sqlite/20251018120000_add_practice_games.sql"),
+        ),
+        (
+            "20251019120000_add_compacted_answers.sql",
+            include_str!("../../migrations/sqlite/20251019120000_add_compacted_answers.sql"),
+        ),
+        (
+            "20251020120000_add_game_announcements.sql",
+            include_str!("../../migrations/sqlite/20251020120000_add_game_announcements.sql"),
+        ),
+        (
+            "20251021120000_add_rate_limit_windows.sql",
+            include_str!("../../migrations/sqlite/20251021120000_add_rate_limit_windows.sql"),
+        ),
+        (
+            "20251022120000_add_admin_tokens.sql",
+            include_str!("../../migrations/sqlite/20251022120000_add_admin_tokens.sql"),
+        ),
+        (
+            "20251023120000_add_accounts.sql",
+            include_str!("../../migrations/sqlite/20251023120000_add_accounts.sql"),
+        ),
+        (
+            "20251024120000_add_stats_freeze_and_notifications.sql",
+            include_str!(
+                "../../migrations/sqlite/20251024120000_add_stats_freeze_and_notifications.sql"
+            ),
+        ),
+        (
+            "20251025120000_add_board_density.sql",
+            include_str!("../../migrations/sqlite/20251025120000_add_board_density.sql"),
+        ),
+        (
+            "20251026120000_add_outbox_events.sql",
+            include_str!("../../migrations/sqlite/20251026120000_add_outbox_events.sql"),
+        ),
+        (
+            "20251027120000_add_game_starts.sql",
+            include_str!("../../migrations/sqlite/20251027120000_add_game_starts.sql"),
+        ),
+        (
+            "20251028120000_add_game_generation_version.sql",
+            include_str!("../../migrations/sqlite/20251028120000_add_game_generation_version.sql"),
+        ),
+        (
+            "20251029120000_add_game_difficulty.sql",
+            include_str!("../../migrations/sqlite/20251029120000_add_game_difficulty.sql"),
+        ),
+        (
+            "20251030120000_add_game_theme.sql",
+            include_str!("../../migrations/sqlite/20251030120000_add_game_theme.sql"),
+        ),
+        (
+            "20251031120000_add_word_challenges.sql",
+            include_str!("../../migrations/sqlite/20251031120000_add_word_challenges.sql"),
+        ),
+        (
+            "20251101120000_add_game_scoring_scheme.sql",
+            include_str!("../../migrations/sqlite/20251101120000_add_game_scoring_scheme.sql"),
+        ),
+        (
+            "20251102120000_add_game_bonus_rules.sql",
+            include_str!("../../migrations/sqlite/20251102120000_add_game_bonus_rules.sql"),
+        ),
+        (
+            "20251103120000_add_entry_suspicion_score.sql",
+            include_str!("../../migrations/sqlite/20251103120000_add_entry_suspicion_score.sql"),
+        ),
+        (
+            "20251104120000_add_entry_revisions.sql",
+            include_str!("../../migrations/sqlite/20251104120000_add_entry_revisions.sql"),
+        ),
+        (
+            "20251105120000_add_entry_request_id.sql",
+            include_str!("../../migrations/sqlite/20251105120000_add_entry_request_id.sql"),
+        ),
+        (
+            "20251106120000_add_custom_games.sql",
+            include_str!("../../migrations/sqlite/20251106120000_add_custom_games.sql"),
+        ),
+        (
+            "20251107120000_add_blitz_games.sql",
+            include_str!("../../migrations/sqlite/20251107120000_add_blitz_games.sql"),
+        ),
+        (
+            "20251108120000_add_webhook_subscriptions.sql",
+            include_str!("../../migrations/sqlite/20251108120000_add_webhook_subscriptions.sql"),
+        ),
+        (
+            "20251115120000_add_discord_links.sql",
+            include_str!("../../migrations/sqlite/20251115120000_add_discord_links.sql"),
+        ),
+        (
+            "20251122120000_add_audit_log.sql",
+            include_str!("../../migrations/sqlite/20251122120000_add_audit_log.sql"),
+        ),
+        (
+            "20251129120000_add_game_language.sql",
+            include_str!("../../migrations/sqlite/20251129120000_add_game_language.sql"),
+        ),
+        (
+            "20251206120000_add_board_quality.sql",
+            include_str!("../../migrations/sqlite/20251206120000_add_board_quality.sql"),
+        ),
+        (
+            "20251213120000_add_game_stats.sql",
+            include_str!("../../migrations/sqlite/20251213120000_add_game_stats.sql"),
+        ),
+        (
+            "20251220120000_add_game_entry_answers.sql",
+            include_str!("../../migrations/sqlite/20251220120000_add_game_entry_answers.sql"),
+        ),
+        (
+            "20251227120000_add_game_revisions.sql",
+            include_str!("../../migrations/sqlite/20251227120000_add_game_revisions.sql"),
+        ),
+        (
+            "20260103120000_add_tournaments.sql",
+            include_str!("../../migrations/sqlite/20260103120000_add_tournaments.sql"),
+        ),
+        (
+            "20260110120000_add_game_rules.sql",
+            include_str!("../../migrations/sqlite/20260110120000_add_game_rules.sql"),
+        ),
+        (
+            "20260117120000_key_entry_answers_by_position.sql",
+            include_str!(
+                "../../migrations/sqlite/20260117120000_key_entry_answers_by_position.sql"
+            ),
+        ),
     ];
 
     for (filename, migration_sql) in &migrations {
@@ -154,18 +633,22 @@ async fn run_migrations_sqlite(pool: &SqlitePool) -> Result<()> {
 
         tracing::info!("Running migration: {}", filename);
 
+        // Run every statement on one connection, so pragmas and transactions
+        // a migration sets up apply to the rest of it
+        let mut conn = pool.acquire().await?;
         // Split by semicolon and execute each statement
         for statement in migration_sql.split(';') {
             let statement = statement.trim();
             if !statement.is_empty() {
                 // Use execute and ignore "table already exists" errors for CREATE TABLE statements
                 if statement.to_uppercase().starts_with("CREATE TABLE") {
-                    let _ = sqlx::query(statement).execute(pool).await;
+                    let _ = sqlx::query(statement).execute(&mut *conn).await;
                 } else {
-                    sqlx::query(statement).execute(pool).await?;
+                    sqlx::query(statement).execute(&mut *conn).await?;
                 }
             }
         }
+        drop(conn);
 
         // Record that this migration was applied
         sqlx::query("INSERT INTO migrations (filename) VALUES (?)")
@@ -179,3 +662,174 @@ async fn run_migrations_sqlite(pool: &SqlitePool) -> Result<()> {
     tracing::info!("SQLite database migrations completed");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_database_backend_from_str() {
+        assert_eq!(
+            "sqlite".parse::<DatabaseBackend>().unwrap(),
+            DatabaseBackend::Sqlite
+        );
+        assert_eq!(
+            "Postgres".parse::<DatabaseBackend>().unwrap(),
+            DatabaseBackend::Postgres
+        );
+        assert_eq!(
+            "postgresql".parse::<DatabaseBackend>().unwrap(),
+            DatabaseBackend::Postgres
+        );
+        assert_eq!(
+            "memory".parse::<DatabaseBackend>().unwrap(),
+            DatabaseBackend::Memory
+        );
+        assert!("mysql".parse::<DatabaseBackend>().is_err());
+    }
+
+    #[cfg(feature = "database-tests")]
+    #[tokio::test]
+    async fn test_separate_read_pool_is_read_only() {
+        let directory = tempfile::tempdir().unwrap();
+        let url = format!(
+            "sqlite://{}",
+            directory.path().join("pathfinder.db").display()
+        );
+        let pools = setup_database(&url, &SqliteConfig::default())
+            .await
+            .unwrap();
+
+        let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+            .fetch_one(&pools.reader)
+            .await
+            .unwrap();
+        assert_eq!(journal_mode, "wal");
+
+        // Reads through the repository see what it wrote through the writer
+        let repository = pools.repository();
+        let user = repository
+            .create_user(models::NewUser {
+                cookie_token: "token".to_string(),
+            })
+            .await
+            .unwrap();
+        assert!(repository.get_user_by_id(&user.id).await.unwrap().is_some());
+
+        assert!(sqlx::query("DELETE FROM users")
+            .execute(&pools.reader)
+            .await
+            .is_err());
+
+        // Backups can be taken without holding the writer
+        let backup = directory.path().join("backup.db");
+        crate::backup::backup_to(&pools.reader, &backup)
+            .await
+            .unwrap();
+        crate::backup::verify(&backup).await.unwrap();
+    }
+
+    /// Copies the SQLite migrations numbered below `before` into a directory
+    /// of their own, so a database can be migrated part of the way
+    #[cfg(feature = "database-tests")]
+    fn migrations_before(before: i64) -> tempfile::TempDir {
+        let directory = tempfile::tempdir().unwrap();
+        let migrations = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations/sqlite");
+        for file in std::fs::read_dir(migrations).unwrap() {
+            let path = file.unwrap().path();
+            let name = path.file_name().unwrap().to_str().unwrap().to_string();
+            let version: i64 = name.split('_').next().unwrap().parse().unwrap();
+            if version < before {
+                std::fs::copy(&path, directory.path().join(name)).unwrap();
+            }
+        }
+        directory
+    }
+
+    /// Migrates a database to just before `version` and saves a player's
+    /// entry on a game with its optimal solution
+    #[cfg(feature = "database-tests")]
+    async fn seeded_before(version: i64, directory: &std::path::Path) -> SqlitePool {
+        let options = SqliteConnectOptions::new()
+            .filename(directory.join("pathfinder.db"))
+            .create_if_missing(true);
+        let pool = SqlitePool::connect_with(options).await.unwrap();
+        let migrations = migrations_before(version);
+        sqlx::migrate::Migrator::new(migrations.path())
+            .await
+            .unwrap()
+            .run(&pool)
+            .await
+            .unwrap();
+
+        for statement in [
+            "INSERT INTO users (id, cookie_token) VALUES ('user', 'token')",
+            "INSERT INTO games (id, date, board_data, threshold_score, sequence_number) \
+             VALUES ('game', '2025-06-08', '{}', 40, 1)",
+            "INSERT INTO game_entries (id, user_id, game_id, answers_data, total_score) \
+             VALUES ('entry', 'user', 'game', '[]', 12)",
+            "INSERT INTO optimal_solutions (id, game_id, words_and_scores, total_score) \
+             VALUES ('solution', 'game', '[]', 40)",
+        ] {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+        pool
+    }
+
+    #[cfg(feature = "database-tests")]
+    async fn entries_and_solutions(pool: &SqlitePool) -> (i64, i64) {
+        let entries = sqlx::query_scalar("SELECT COUNT(*) FROM game_entries")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        let solutions = sqlx::query_scalar("SELECT COUNT(*) FROM optimal_solutions")
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        (entries, solutions)
+    }
+
+    /// The migrations that rebuild the games table keep the rows that
+    /// reference it under sqlx's migrator, which runs each SQLite migration
+    /// in a transaction where foreign keys can't be switched off
+    #[cfg(feature = "database-tests")]
+    #[tokio::test]
+    async fn test_rebuilding_games_keeps_entries() {
+        for version in [20251107120000, 20251129120000, 20251227120000] {
+            let directory = tempfile::tempdir().unwrap();
+            let pool = seeded_before(version, directory.path()).await;
+            let through = migrations_before(version + 1);
+            sqlx::migrate::Migrator::new(through.path())
+                .await
+                .unwrap()
+                .run(&pool)
+                .await
+                .unwrap();
+
+            assert_eq!(entries_and_solutions(&pool).await, (1, 1), "{version}");
+        }
+    }
+
+    /// The same holds for the migrations run when the server starts
+    #[cfg(feature = "database-tests")]
+    #[tokio::test]
+    async fn test_startup_migrations_keep_entries() {
+        let directory = tempfile::tempdir().unwrap();
+        let pool = seeded_before(20251107120000, directory.path()).await;
+        sqlx::query("CREATE TABLE migrations (filename TEXT UNIQUE NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let applied = migrations_before(20251107120000);
+        for file in std::fs::read_dir(applied.path()).unwrap() {
+            sqlx::query("INSERT INTO migrations (filename) VALUES (?)")
+                .bind(file.unwrap().file_name().to_str().unwrap())
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        run_migrations_sqlite(&pool).await.unwrap();
+        assert_eq!(entries_and_solutions(&pool).await, (1, 1));
+    }
+}
@@ -2,6 +2,11 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::game::density::BoardDensity;
+use crate::game::language::Language;
+use crate::game::mode::GameMode;
+use crate::game::quality::BoardQuality;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DbUser {
     pub id: String,
@@ -10,6 +15,62 @@ pub struct DbUser {
     pub last_seen: DateTime<Utc>,
 }
 
+/// A browser session a user has played from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbUserSession {
+    pub id: String,
+    pub user_id: String,
+    pub session_hash: String,            // SHA-256 of the session cookie
+    pub user_agent_hash: Option<String>, // SHA-256 of the User-Agent header
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// An anonymous user upgraded so they can sign in from other devices. The
+/// account's user is the identity every signed in device plays as.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbAccount {
+    pub id: String,
+    pub user_id: String,
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An emailed link that attaches an address to an account or signs in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbMagicLink {
+    pub token_hash: String, // SHA-256 of the link's token
+    pub email: String,
+    pub user_id: Option<String>, // Set for links attaching an address to this user's account
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A passkey registered to an account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbPasskey {
+    pub credential_id: String,
+    pub account_id: String,
+    pub public_key: String, // base64url P-256 point
+    pub sign_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// A scoped, expiring token for the admin API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbAdminToken {
+    pub id: String,
+    pub token_hash: String, // SHA-256 of the token
+    pub scope: String,
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DbGame {
     pub id: String,
@@ -17,11 +78,231 @@ pub struct DbGame {
     pub board_data: String, // JSON serialized board
     pub threshold_score: i32,
     pub sequence_number: i32,
+    /// Version of the generation rules the board was made with
+    pub generation_version: i32,
+    /// Difficulty label the board was rated with, when it was rated
+    pub difficulty: Option<String>,
+    /// Theme spec the board was generated to contain, if it was themed
+    pub theme: Option<String>,
+    /// Version of the ScoringScheme the board's tiles were given points by
+    pub scoring_scheme_version: i32,
+    /// BonusRules the game is scored under as JSON; None for no bonuses
+    pub bonus_rules: Option<String>,
+    /// GameRules the game is played under as JSON; None for the defaults
+    pub game_rules: Option<String>,
+    /// GameMode the game is played in
+    pub mode: String,
+    /// Seconds a blitz game's entries may be saved for after the player
+    /// starts it; None for untimed games
+    pub time_limit_seconds: Option<i32>,
+    /// Language the board was generated in, by its code
+    pub language: String,
+    /// Which board this is for its date, counting from 1. A replaced board
+    /// stays as an earlier revision so the entries played on it keep their
+    /// scores.
+    pub revision: i32,
+    /// When a newer revision replaced this one; None while it's the board
+    /// served for its date
+    pub deleted_at: Option<DateTime<Utc>>,
     pub completed: bool,
     pub completed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
 }
 
+/// A throwaway board generated for practice, outside the daily schedule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbPracticeGame {
+    pub id: String,
+    pub seed: u64,
+    pub board_data: String, // JSON serialized board
+    pub threshold_score: i32,
+    pub optimal_score: i32,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// A board a player made, which anyone with its slug can play
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DbCustomGame {
+    pub id: String,
+    /// Short id the game is shared by
+    pub slug: String,
+    /// The board's code, which identifies the board itself
+    pub board_code: String,
+    pub board_data: String, // JSON serialized board
+    pub optimal_score: i32,
+    /// The player who made it, if they were signed in
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A service notified whenever a new daily game goes live
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DbWebhookSubscription {
+    pub id: String,
+    /// How deliveries are shaped: "discord" or "generic"
+    pub kind: String,
+    pub url: String,
+    /// Signs each delivery; only shown to the subscriber when they register
+    pub secret: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A sensitive operation recorded in the audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DbAuditEntry {
+    pub id: String,
+    /// Who made the change, e.g. "admin:bootstrap" or "user:<id>"
+    pub actor: String,
+    pub action: String,
+    /// What was changed, e.g. a game or user id
+    pub target: Option<String>,
+    /// JSON snapshots of the target before and after the change
+    pub before_data: Option<String>,
+    pub after_data: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A player's best submission to a custom game
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DbCustomGameScore {
+    pub id: String,
+    pub custom_game_id: String,
+    pub user_id: String,
+    pub answers_data: String, // JSON serialized answers
+    pub total_score: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A tournament over a range of dates. Each player who joins has their daily
+/// scores on those dates added up into a standing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DbTournament {
+    pub id: String,
+    pub name: String,
+    /// The first and last dates whose daily games count, YYYY-MM-DD
+    pub start_date: String,
+    pub end_date: String,
+    /// Only daily games in this language count
+    pub language: String,
+    pub created_at: DateTime<Utc>,
+    /// When its final standings were written
+    pub finalized_at: Option<DateTime<Utc>>,
+}
+
+impl DbTournament {
+    pub fn tournament_language(&self) -> Language {
+        self.language.parse().unwrap_or_default()
+    }
+
+    pub fn is_finalized(&self) -> bool {
+        self.finalized_at.is_some()
+    }
+}
+
+/// A participant's standing in a tournament: their best score on each of its
+/// dates, added up
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbTournamentStanding {
+    pub user_id: String,
+    pub total_score: i32,
+    /// Dates the participant completed the daily game on
+    pub games_played: i32,
+}
+
+/// A place in a tournament's final standings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbTournamentResult {
+    pub tournament_id: String,
+    /// None once the player has deleted their account
+    pub user_id: Option<String>,
+    /// Players with the same total share a rank
+    pub rank: i32,
+    pub total_score: i32,
+    pub games_played: i32,
+}
+
+impl DbTournamentResult {
+    /// Rank standings ordered highest total first, as the final results of
+    /// `tournament_id`
+    pub fn from_standings(tournament_id: &str, standings: &[DbTournamentStanding]) -> Vec<Self> {
+        let mut rank = 0;
+        let mut previous_score = None;
+        standings
+            .iter()
+            .enumerate()
+            .map(|(index, standing)| {
+                if previous_score != Some(standing.total_score) {
+                    rank = index as i32 + 1;
+                    previous_score = Some(standing.total_score);
+                }
+                Self {
+                    tournament_id: tournament_id.to_string(),
+                    user_id: Some(standing.user_id.clone()),
+                    rank,
+                    total_score: standing.total_score,
+                    games_played: standing.games_played,
+                }
+            })
+            .collect()
+    }
+}
+
+/// One game a user has played, as listed in their history export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DbHistoryEntry {
+    pub game_id: String,
+    pub date: String, // YYYY-MM-DD format
+    pub sequence_number: i32,
+    pub answers_data: String, // JSON serialized answers
+    pub total_score: i32,
+    pub completed: bool,
+    pub off_board: bool,
+    /// The user's percentile among everyone who completed the game; None for
+    /// unfinished or off the board entries
+    pub percentile: Option<f64>,
+}
+
+/// One of a user's entries, on a game in any mode, as listed in their data
+/// export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DbUserEntry {
+    pub game_id: String,
+    pub date: String, // YYYY-MM-DD format
+    pub mode: String,
+    pub sequence_number: i32,
+    pub answers_data: String, // JSON serialized answers
+    pub total_score: i32,
+    pub completed: bool,
+    pub off_board: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// What deleting a user did with their entries. Completed entries that count
+/// towards a game's stats are kept, without their answers, under an
+/// anonymous user no one can sign in as, so other players' ranks and
+/// percentiles don't shift; every other entry is deleted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbUserDeletion {
+    pub anonymized_entries: u64,
+    pub deleted_entries: u64,
+}
+
+/// Aggregates over every game a user has completed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DbUserStats {
+    pub games_played: i32,
+    pub average_score: Option<f64>, // None until the user completes a game
+    pub best_score: Option<i32>,
+    /// Mean of the user's percentile on each game, leaving out games they
+    /// completed after its stats were frozen
+    pub average_percentile: Option<f64>,
+    /// Games where the user reached the threshold score
+    pub threshold_hits: i32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DbGameEntry {
     pub id: String,
@@ -30,15 +311,143 @@ pub struct DbGameEntry {
     pub answers_data: String, // JSON serialized answers
     pub total_score: i32,
     pub completed: bool,
+    pub off_board: bool,      // Submitted after the game's stats were frozen
+    pub suspicion_score: i32, // 0-100, from the submission integrity checks
+    pub request_id: Option<String>, // Client id of the request that last wrote it
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// An autosaved snapshot of an in-progress entry's answers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DbEntryRevision {
+    pub id: String,
+    pub user_id: String,
+    pub game_id: String,
+    pub answers_data: String, // JSON serialized answers
+    pub created_at: DateTime<Utc>,
+}
+
+/// A game's aggregates as they stood when its stats were frozen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbFinalGameStats {
+    pub game_id: String,
+    pub total_players: i32,
+    pub average_score: i32,
+    pub highest_score: i32,
+    pub frozen_at: DateTime<Utc>,
+}
+
+/// How players actually fared on a game, measured once its day is over and
+/// used to calibrate future thresholds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DbGameStats {
+    pub game_id: String,
+    /// Entries started, whether or not they were completed
+    pub players: i32,
+    /// Completed entries that count towards the game's stats
+    pub completed_players: i32,
+    pub median_score: i32,
+    /// What the game's best five words score
+    pub optimal_score: i32,
+    /// Share of players, 0 to 1, who completed the game
+    pub completion_rate: f64,
+    /// Share of completed players, 0 to 1, who reached the threshold
+    pub threshold_hit_rate: f64,
+    pub computed_at: DateTime<Utc>,
+}
+
+/// A player's final place on a frozen game
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbFinalRank {
+    pub user_id: String,
+    pub total_score: i32,
+    pub rank: i32,
+}
+
+/// The notifications a user has opted in to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbNotificationPreferences {
+    pub user_id: String,
+    pub final_rank: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbNotification {
+    pub id: String,
+    pub user_id: String,
+    pub kind: String,
+    pub game_id: Option<String>,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+/// A past game as listed in the archive, with one user's progress on it and
+/// aggregates over everyone who completed it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbArchiveGame {
+    pub game_id: String,
+    pub date: String, // YYYY-MM-DD format
+    pub sequence_number: i32,
+    pub threshold_score: i32,
+    pub user_completed: bool,
+    pub user_score: Option<i32>, // None if the user never played the game
+    pub total_players: i32,
+    pub average_score: Option<f64>, // None until someone completes the game
+}
+
+/// One answer on a game entry, as game_entry_answers holds it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbEntryAnswer {
+    /// Where the answer is in the entry's list, from 0
+    pub position: i32,
+    pub word: String,
+    pub score: i32,
+    /// The traced path as JSON in the current answers version
+    pub path_json: Option<String>,
+}
+
+/// How many completed, on-board entries on a game found a word
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbWordCount {
+    pub word: String,
+    pub players: i32,
+}
+
+/// How many players have started a game and how many have finished it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DbPlayerCounts {
+    pub in_progress: i32,
+    pub completed: i32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DbGameAnswer {
     pub game_id: String,
     pub word: String,
+    pub path_stats: Option<WordPathStats>, // None for answers stored before stats were recorded
+    pub created_at: DateTime<Utc>,
+}
+
+/// How a word can be formed on its game's board
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WordPathStats {
+    /// Number of distinct paths that spell the word
+    pub path_count: i32,
+    /// Whether every path uses at least one wildcard tile
+    pub requires_wildcard: bool,
+}
+
+/// How many hints a user has consumed for one word of a game
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbHintUsage {
+    pub user_id: String,
+    pub game_id: String,
+    pub word: String,
+    pub level: i32, // Number of hints revealed for this word
     pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,12 +465,153 @@ pub struct NewUser {
     pub cookie_token: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewUserSession {
+    pub user_id: String,
+    pub session_hash: String,
+    pub user_agent_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewMagicLink {
+    pub token_hash: String,
+    pub email: String,
+    pub user_id: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewPasskey {
+    pub credential_id: String,
+    pub account_id: String,
+    pub public_key: String,
+    pub sign_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewAdminToken {
+    pub token_hash: String,
+    pub scope: String,
+    pub label: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewGame {
     pub date: String,
     pub board_data: String,
     pub threshold_score: i32,
     pub sequence_number: i32,
+    pub generation_version: i32,
+    pub difficulty: Option<String>,
+    pub theme: Option<String>,
+    pub scoring_scheme_version: i32,
+    pub bonus_rules: Option<String>,
+    pub game_rules: Option<String>,
+    pub mode: String,
+    pub time_limit_seconds: Option<i32>,
+    pub language: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewPracticeGame {
+    pub seed: u64,
+    pub board_data: String,
+    pub threshold_score: i32,
+    pub optimal_score: i32,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewCustomGame {
+    pub slug: String,
+    pub board_code: String,
+    pub board_data: String,
+    pub optimal_score: i32,
+    pub created_by: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewWebhookSubscription {
+    pub kind: String,
+    pub url: String,
+    pub secret: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewAuditEntry {
+    pub actor: String,
+    pub action: String,
+    pub target: Option<String>,
+    pub before_data: Option<String>,
+    pub after_data: Option<String>,
+}
+
+/// Which audit entries to read. Unset fields match every entry.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditFilter {
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub target: Option<String>,
+    /// Only entries made at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only entries made before this time
+    pub until: Option<DateTime<Utc>>,
+    pub limit: i64,
+}
+
+/// Which games to list. Unset fields match every game.
+#[derive(Debug, Clone, Default)]
+pub struct GameFilter {
+    pub mode: Option<GameMode>,
+    pub language: Option<Language>,
+    /// Only games dated on or after this YYYY-MM-DD date
+    pub from_date: Option<String>,
+    /// Only games dated on or before this YYYY-MM-DD date
+    pub to_date: Option<String>,
+    /// Also list boards a newer revision has replaced
+    pub include_replaced: bool,
+}
+
+/// Which page of a list to read: up to `limit` rows after the first `offset`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageRequest {
+    pub limit: i32,
+    pub offset: i32,
+}
+
+impl PageRequest {
+    /// The 1-based `page` of a list split into pages of `page_size` rows.
+    /// Pages before the first read the first.
+    pub fn new(page: i32, page_size: i32) -> Self {
+        Self {
+            limit: page_size.max(0),
+            offset: (page.max(1) - 1).saturating_mul(page_size.max(0)),
+        }
+    }
+}
+
+/// A page of a list along with how many rows the whole list has
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbPage<T> {
+    pub items: Vec<T>,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewCustomGameScore {
+    pub custom_game_id: String,
+    pub user_id: String,
+    pub answers_data: String,
+    pub total_score: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewTournament {
+    pub name: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub language: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,12 +621,74 @@ pub struct NewGameEntry {
     pub answers_data: String,
     pub total_score: i32,
     pub completed: bool,
+    /// From the submission integrity checks; never lowers a stored score
+    pub suspicion_score: i32,
+    /// Keep the entry out of stats and leaderboards, as a late play is
+    pub exclude: bool,
+    /// Idempotency key from the client. A write repeating the key the entry
+    /// was last written with changes nothing.
+    pub request_id: Option<String>,
+}
+
+/// A completion timed from the server-recorded start to the submission
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DbSpeedRun {
+    pub user_id: String,
+    pub total_score: i32,
+    pub elapsed_ms: i64,
+}
+
+/// A player's entry in a blitz game, with when they started it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DbBlitzRun {
+    pub game_id: String,
+    pub date: String, // YYYY-MM-DD format
+    pub answers_data: String,
+    pub total_score: i32,
+    pub started_at: DateTime<Utc>,
+    /// When the player submitted; None when they let the clock run out
+    pub completed_at: Option<DateTime<Utc>>,
+    pub time_limit_seconds: i32,
+}
+
+/// An event waiting in the outbox
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DbOutboxEvent {
+    pub id: String,
+    pub event_type: String,
+    pub payload: String, // JSON serialized event
+    /// How many times delivering the event has failed
+    pub attempts: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewOutboxEvent {
+    pub event_type: String,
+    pub payload: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewNotification {
+    pub user_id: String,
+    pub kind: String,
+    pub game_id: Option<String>,
+    pub message: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewGameAnswer {
     pub game_id: String,
     pub word: String,
+    pub path_stats: Option<WordPathStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewHintUsage {
+    pub user_id: String,
+    pub game_id: String,
+    pub word: String,
+    pub level: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -86,6 +698,54 @@ pub struct NewOptimalSolution {
     pub total_score: i32,
 }
 
+/// A game bundled with everything written alongside it, for creating several
+/// games in one transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewGameWithAnswers {
+    pub game: NewGame,
+    pub answers: Vec<NewGameAnswer>,
+    pub optimal_solution: Option<NewOptimalSolution>,
+    /// The board's solution density, measured when it was generated
+    #[serde(default)]
+    pub density: Option<BoardDensity>,
+    /// What the quality analyzer found wrong with the board when it was
+    /// generated
+    #[serde(default)]
+    pub quality: Option<BoardQuality>,
+}
+
+/// A generated game's solution density alongside how many players started
+/// and completed it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DbGameDensity {
+    pub game_id: String,
+    pub date: String, // YYYY-MM-DD format
+    pub sequence_number: i32,
+    pub threshold_score: i32,
+    pub density: BoardDensity,
+    /// None for games generated before board quality was recorded
+    pub quality: Option<BoardQuality>,
+    pub players_started: i32,
+    pub players_completed: i32,
+}
+
+/// A word players have challenged into their entries, proposed for adding to
+/// the wordlist
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DbWordProposal {
+    pub word: String,
+    pub challenges: i64, // Entries it was challenged into
+    pub first_challenged_at: DateTime<Utc>,
+}
+
+/// A game's stored board and the generation rules it was made with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DbGameBoard {
+    pub date: String, // YYYY-MM-DD format
+    pub generation_version: i32,
+    pub board_data: String,
+}
+
 impl DbUser {
     pub fn new(cookie_token: String) -> Self {
         let now = Utc::now();
@@ -96,24 +756,192 @@ impl DbUser {
             last_seen: now,
         }
     }
+
+    /// A user with a random cookie token that is never handed out, so no one
+    /// can sign in as it. Deleted users' anonymized entries are moved to one.
+    pub fn anonymous() -> Self {
+        Self::new(Uuid::new_v4().to_string())
+    }
+}
+
+impl DbUserSession {
+    pub fn new(user_id: String, session_hash: String, user_agent_hash: Option<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            session_hash,
+            user_agent_hash,
+            first_seen: now,
+            last_seen: now,
+            revoked_at: None,
+        }
+    }
+}
+
+impl DbAccount {
+    pub fn new(user_id: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            email: None,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+impl DbAdminToken {
+    pub fn new(
+        token_hash: String,
+        scope: String,
+        label: Option<String>,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            token_hash,
+            scope,
+            label,
+            created_at: Utc::now(),
+            expires_at,
+            revoked_at: None,
+        }
+    }
+
+    /// Whether the token can still be used
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at > now
+    }
 }
 
 impl DbGame {
+    /// The mode the game is played in. Games stored before modes existed, or
+    /// with one this build doesn't know, are played as daily games.
+    pub fn game_mode(&self) -> GameMode {
+        self.mode.parse().unwrap_or_default()
+    }
+
+    /// The language the game is played in. Games stored before languages
+    /// existed are English.
+    pub fn game_language(&self) -> Language {
+        self.language.parse().unwrap_or_default()
+    }
+
+    /// Whether a newer revision replaced this board for its date
+    pub fn is_replaced(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    pub fn new(new_game: NewGame) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            date: new_game.date,
+            board_data: new_game.board_data,
+            threshold_score: new_game.threshold_score,
+            sequence_number: new_game.sequence_number,
+            generation_version: new_game.generation_version,
+            difficulty: new_game.difficulty,
+            theme: new_game.theme,
+            scoring_scheme_version: new_game.scoring_scheme_version,
+            bonus_rules: new_game.bonus_rules,
+            game_rules: new_game.game_rules,
+            mode: new_game.mode,
+            time_limit_seconds: new_game.time_limit_seconds,
+            language: new_game.language,
+            revision: 1,
+            deleted_at: None,
+            completed: false,
+            completed_at: None,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+impl DbPracticeGame {
     pub fn new(
-        date: String,
+        seed: u64,
         board_data: String,
         threshold_score: i32,
-        sequence_number: i32,
+        optimal_score: i32,
+        expires_at: DateTime<Utc>,
     ) -> Self {
         Self {
             id: Uuid::new_v4().to_string(),
-            date,
+            seed,
             board_data,
             threshold_score,
-            sequence_number,
-            completed: false,
-            completed_at: None,
+            optimal_score,
+            created_at: Utc::now(),
+            expires_at,
+        }
+    }
+}
+
+impl From<NewCustomGame> for DbCustomGame {
+    fn from(new_game: NewCustomGame) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            slug: new_game.slug,
+            board_code: new_game.board_code,
+            board_data: new_game.board_data,
+            optimal_score: new_game.optimal_score,
+            created_by: new_game.created_by,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+impl From<NewWebhookSubscription> for DbWebhookSubscription {
+    fn from(subscription: NewWebhookSubscription) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            kind: subscription.kind,
+            url: subscription.url,
+            secret: subscription.secret,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+impl From<NewAuditEntry> for DbAuditEntry {
+    fn from(entry: NewAuditEntry) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            actor: entry.actor,
+            action: entry.action,
+            target: entry.target,
+            before_data: entry.before_data,
+            after_data: entry.after_data,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+impl From<NewCustomGameScore> for DbCustomGameScore {
+    fn from(score: NewCustomGameScore) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            custom_game_id: score.custom_game_id,
+            user_id: score.user_id,
+            answers_data: score.answers_data,
+            total_score: score.total_score,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+impl From<NewTournament> for DbTournament {
+    fn from(tournament: NewTournament) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: tournament.name,
+            start_date: tournament.start_date,
+            end_date: tournament.end_date,
+            language: tournament.language,
             created_at: Utc::now(),
+            finalized_at: None,
         }
     }
 }
@@ -134,22 +962,88 @@ impl DbGameEntry {
             answers_data,
             total_score,
             completed,
+            off_board: false,
+            suspicion_score: 0,
+            request_id: None,
             created_at: now,
             updated_at: now,
         }
     }
 }
 
+impl DbNotificationPreferences {
+    /// Preferences for a user who hasn't opted in to anything
+    pub fn none(user_id: String) -> Self {
+        Self {
+            user_id,
+            final_rank: false,
+        }
+    }
+}
+
+impl DbOutboxEvent {
+    pub fn new(new_event: NewOutboxEvent) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            event_type: new_event.event_type,
+            payload: new_event.payload,
+            attempts: 0,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+impl DbNotification {
+    pub fn new(new_notification: NewNotification) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id: new_notification.user_id,
+            kind: new_notification.kind,
+            game_id: new_notification.game_id,
+            message: new_notification.message,
+            created_at: Utc::now(),
+            read_at: None,
+        }
+    }
+}
+
 impl DbGameAnswer {
-    pub fn new(game_id: String, word: String) -> Self {
+    pub fn new(game_id: String, word: String, path_stats: Option<WordPathStats>) -> Self {
         Self {
             game_id,
             word,
+            path_stats,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+impl DbEntryRevision {
+    pub fn new(user_id: String, game_id: String, answers_data: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id,
+            game_id,
+            answers_data,
             created_at: Utc::now(),
         }
     }
 }
 
+impl DbHintUsage {
+    pub fn new(user_id: String, game_id: String, word: String, level: i32) -> Self {
+        let now = Utc::now();
+        Self {
+            user_id,
+            game_id,
+            word,
+            level,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
 impl DbOptimalSolution {
     pub fn new(game_id: String, words_and_scores: String, total_score: i32) -> Self {
         Self {
@@ -1,9 +1,21 @@
 use anyhow::Result;
 use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::game::language::Language;
+use crate::game::mode::GameMode;
 
 use super::models::{
-    DbGame, DbGameAnswer, DbGameEntry, DbUser, NewGame, NewGameAnswer, NewGameEntry,
-    NewOptimalSolution, NewUser, OptimalAnswer,
+    AuditFilter, DbAccount, DbAdminToken, DbArchiveGame, DbAuditEntry, DbBlitzRun, DbCustomGame,
+    DbCustomGameScore, DbEntryRevision, DbFinalGameStats, DbFinalRank, DbGame, DbGameAnswer,
+    DbGameBoard, DbGameDensity, DbGameEntry, DbGameStats, DbHintUsage, DbHistoryEntry, DbMagicLink,
+    DbNotification, DbNotificationPreferences, DbOutboxEvent, DbPage, DbPasskey, DbPlayerCounts,
+    DbPracticeGame, DbSpeedRun, DbTournament, DbTournamentResult, DbTournamentStanding, DbUser,
+    DbUserDeletion, DbUserEntry, DbUserSession, DbUserStats, DbWebhookSubscription, DbWordCount,
+    DbWordProposal, GameFilter, NewAdminToken, NewAuditEntry, NewCustomGame, NewCustomGameScore,
+    NewGame, NewGameAnswer, NewGameEntry, NewGameWithAnswers, NewHintUsage, NewMagicLink,
+    NewNotification, NewOptimalSolution, NewOutboxEvent, NewPasskey, NewPracticeGame,
+    NewTournament, NewUser, NewUserSession, NewWebhookSubscription, OptimalAnswer, PageRequest,
 };
 
 #[async_trait]
@@ -17,21 +29,231 @@ pub trait Repository {
 
     async fn update_user_last_seen(&self, user_id: &str) -> Result<()>;
 
+    // A page of users, oldest first
+    async fn list_users(&self, page: PageRequest) -> Result<DbPage<DbUser>>;
+
+    // Session operations
+
+    // Record that a user was seen on a session, creating it the first time
+    async fn touch_user_session(&self, session: NewUserSession) -> Result<DbUserSession>;
+
+    // Get a user's sessions that haven't been revoked, most recently seen first
+    async fn get_user_sessions(&self, user_id: &str) -> Result<Vec<DbUserSession>>;
+
+    // Revoke one of a user's sessions, returning it if it existed and wasn't already revoked
+    async fn revoke_user_session(
+        &self,
+        user_id: &str,
+        session_id: &str,
+    ) -> Result<Option<DbUserSession>>;
+
+    // Get hashes of sessions revoked since the given time
+    async fn get_revoked_session_hashes(&self, since: DateTime<Utc>) -> Result<Vec<String>>;
+
+    // Account operations
+
+    // Get the account for a user, creating it the first time
+    async fn get_or_create_account(&self, user_id: &str) -> Result<DbAccount>;
+
+    async fn get_account_by_user(&self, user_id: &str) -> Result<Option<DbAccount>>;
+
+    async fn get_account_by_email(&self, email: &str) -> Result<Option<DbAccount>>;
+
+    async fn get_account_by_id(&self, account_id: &str) -> Result<Option<DbAccount>>;
+
+    async fn set_account_email(&self, account_id: &str, email: &str) -> Result<()>;
+
+    async fn create_magic_link(&self, link: NewMagicLink) -> Result<DbMagicLink>;
+
+    // Mark a magic link used, returning it only if it was unused and unexpired
+    async fn use_magic_link(&self, token_hash: &str) -> Result<Option<DbMagicLink>>;
+
+    async fn add_passkey(&self, passkey: NewPasskey) -> Result<DbPasskey>;
+
+    async fn get_passkey(&self, credential_id: &str) -> Result<Option<DbPasskey>>;
+
+    async fn get_account_passkeys(&self, account_id: &str) -> Result<Vec<DbPasskey>>;
+
+    // Record a sign in with a passkey and the authenticator's new signature count
+    async fn update_passkey_sign_count(&self, credential_id: &str, sign_count: i64) -> Result<()>;
+
+    // Move a user's game entries, hints and notifications to another user,
+    // then delete the first user. Entries for games both users played keep
+    // the target's entry. Returns how many entries were moved.
+    async fn merge_users(&self, into_user_id: &str, from_user_id: &str) -> Result<u64>;
+
+    // Delete a user and everything stored about them. Completed, on the board
+    // entries move, answers cleared, to a new anonymous user so game stats
+    // and other players' percentiles stay as they were.
+    async fn delete_user(&self, user_id: &str) -> Result<DbUserDeletion>;
+
+    // The English daily game for a date. The game, sequence number and archive
+    // operations below are all about English games unless they take a
+    // language.
     async fn get_game_by_date(&self, date: &str) -> Result<Option<DbGame>>;
 
+    async fn get_game_by_date_in_mode(&self, date: &str, mode: GameMode) -> Result<Option<DbGame>>;
+
     async fn get_game_by_id(&self, game_id: &str) -> Result<Option<DbGame>>;
 
     async fn get_game_by_sequence_number(&self, sequence_number: i32) -> Result<Option<DbGame>>;
 
     async fn game_exists_for_date(&self, date: &str) -> Result<bool>;
 
+    // The sequence number for the next daily game
     async fn get_next_sequence_number(&self) -> Result<i32>;
 
+    async fn get_next_sequence_number_in_mode(&self, mode: GameMode) -> Result<i32>;
+
+    // The daily game for a date in a language
+    async fn get_game_by_date_in_language(
+        &self,
+        date: &str,
+        language: Language,
+    ) -> Result<Option<DbGame>>;
+
+    async fn get_game_by_sequence_number_in_language(
+        &self,
+        sequence_number: i32,
+        language: Language,
+    ) -> Result<Option<DbGame>>;
+
+    // The sequence number for the next daily game in a language, which counts
+    // its puzzles separately from English
+    async fn get_next_sequence_number_in_language(&self, language: Language) -> Result<i32>;
+
+    // Get a page of games dated on or before `latest_date`, newest first, with
+    // the given user's progress on each
+    async fn get_archive_games(
+        &self,
+        user_id: Option<&str>,
+        latest_date: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<DbArchiveGame>>;
+
+    // Count games dated on or before `latest_date`
+    async fn count_archive_games(&self, latest_date: &str) -> Result<i32>;
+
+    // A page of the games matching `filter` in any mode and language, newest
+    // first
+    async fn list_games(&self, filter: &GameFilter, page: PageRequest) -> Result<DbPage<DbGame>>;
+
+    // Practice game operations
+    async fn create_practice_game(&self, new_game: NewPracticeGame) -> Result<DbPracticeGame>;
+
+    // Get a practice game, if it exists and hasn't expired
+    async fn get_practice_game(&self, practice_id: &str) -> Result<Option<DbPracticeGame>>;
+
+    // Delete expired practice games, returning how many were removed
+    async fn delete_expired_practice_games(&self) -> Result<u64>;
+
+    // Custom game operations
+    // Store a custom game, or return the one already stored for its board code
+    async fn create_custom_game(&self, new_game: NewCustomGame) -> Result<DbCustomGame>;
+
+    async fn get_custom_game(&self, slug: &str) -> Result<Option<DbCustomGame>>;
+
+    // Keep a submission if it beats the player's best for the game, returning
+    // their best either way
+    async fn save_custom_game_score(&self, score: NewCustomGameScore) -> Result<DbCustomGameScore>;
+
+    // The best score of each player of a custom game, highest first
+    async fn get_custom_game_scores(
+        &self,
+        custom_game_id: &str,
+        limit: i32,
+    ) -> Result<Vec<DbCustomGameScore>>;
+
+    // Tournament operations
+    async fn create_tournament(&self, new_tournament: NewTournament) -> Result<DbTournament>;
+
+    async fn get_tournament(&self, tournament_id: &str) -> Result<Option<DbTournament>>;
+
+    // Tournaments starting latest first
+    async fn list_tournaments(&self, limit: i32) -> Result<Vec<DbTournament>>;
+
+    // Add a player to a tournament, returning false if they had already joined
+    async fn join_tournament(&self, tournament_id: &str, user_id: &str) -> Result<bool>;
+
+    // Every participant's standing so far, highest total first and earliest
+    // joined first among ties. A participant's score for a date is their best
+    // completed, on-board entry on any revision of the date's daily game in
+    // the tournament's language.
+    async fn get_tournament_standings(
+        &self,
+        tournament_id: &str,
+    ) -> Result<Vec<DbTournamentStanding>>;
+
+    // Tournaments ending on or before `date` that haven't been finalized,
+    // earliest ending first
+    async fn get_unfinalized_tournaments_through(&self, date: &str) -> Result<Vec<DbTournament>>;
+
+    // Write a tournament's final results from its standings. Returns None if
+    // it was already finalized.
+    async fn finalize_tournament(
+        &self,
+        tournament_id: &str,
+    ) -> Result<Option<Vec<DbTournamentResult>>>;
+
+    // A finalized tournament's results, best first
+    async fn get_tournament_results(&self, tournament_id: &str) -> Result<Vec<DbTournamentResult>>;
+
     // Game entry operations
     async fn create_or_update_game_entry(&self, new_entry: NewGameEntry) -> Result<DbGameEntry>;
 
+    // Create or update a game entry and add events to the outbox in a single
+    // transaction, so the events exist exactly when the write does
+    async fn create_or_update_game_entry_with_events(
+        &self,
+        new_entry: NewGameEntry,
+        events: Vec<NewOutboxEvent>,
+    ) -> Result<DbGameEntry>;
+
     async fn get_game_entry(&self, user_id: &str, game_id: &str) -> Result<Option<DbGameEntry>>;
 
+    // A page of every entry on a game, including unfinished and off the board
+    // entries, oldest first
+    async fn list_entries_for_game(
+        &self,
+        game_id: &str,
+        page: PageRequest,
+    ) -> Result<DbPage<DbGameEntry>>;
+
+    // Hint operations
+    async fn get_hint_usage(&self, user_id: &str, game_id: &str) -> Result<Vec<DbHintUsage>>;
+
+    // Insert or raise the hint level a user has reached for a word
+    async fn record_hint_usage(&self, usage: NewHintUsage) -> Result<DbHintUsage>;
+
+    // Record a word the user successfully challenged on a game. Challenging
+    // the same word again is a no-op.
+    async fn record_word_challenge(&self, user_id: &str, game_id: &str, word: &str) -> Result<()>;
+
+    // The words the user has challenged on a game, which their entry accepts
+    async fn get_challenged_words(&self, user_id: &str, game_id: &str) -> Result<Vec<String>>;
+
+    // Every challenged word with how many entries it was challenged into,
+    // most challenged first
+    async fn get_wordlist_proposals(&self) -> Result<Vec<DbWordProposal>>;
+
+    // Autosave a snapshot of the user's answers on a game, then drop all but
+    // the newest `keep` snapshots
+    async fn save_entry_revision(
+        &self,
+        user_id: &str,
+        game_id: &str,
+        answers_data: &str,
+        keep: usize,
+    ) -> Result<DbEntryRevision>;
+
+    // The user's autosaved snapshots on a game, newest first
+    async fn get_entry_revisions(
+        &self,
+        user_id: &str,
+        game_id: &str,
+    ) -> Result<Vec<DbEntryRevision>>;
+
     // Create game and answers atomically
     async fn create_game_with_answers(
         &self,
@@ -40,14 +262,127 @@ pub trait Repository {
         optimal_solution: Option<NewOptimalSolution>,
     ) -> Result<(DbGame, Vec<DbGameAnswer>)>;
 
+    // Create several games and their answers, and the density of any that
+    // has one, in a single transaction; if any insert fails none of the
+    // games are created
+    async fn create_games_with_answers(
+        &self,
+        games: Vec<NewGameWithAnswers>,
+    ) -> Result<Vec<DbGame>>;
+
+    // Replace a game's board with the next revision in a single transaction.
+    // The replacement takes the game's date, mode, language and sequence
+    // number, and the game is marked deleted but kept along with the entries
+    // played on it. Returns None if the game doesn't exist or was already
+    // replaced.
+    async fn replace_game(
+        &self,
+        game_id: &str,
+        replacement: NewGameWithAnswers,
+    ) -> Result<Option<DbGame>>;
+
     async fn get_game_words(&self, game_id: &str) -> Result<Vec<String>>;
 
+    // Get a game's answers, with path statistics where they were recorded.
+    // Reads either the game_answers2 rows or the compacted blob.
+    async fn get_game_answers(&self, game_id: &str) -> Result<Vec<DbGameAnswer>>;
+
+    // Get up to `limit` games dated before `before_date` whose answers are
+    // still stored as rows, oldest first
+    async fn get_uncompacted_game_ids(&self, before_date: &str, limit: i32) -> Result<Vec<String>>;
+
+    // Store a game's compacted answers and delete its answer rows in one
+    // transaction, returning how many rows were deleted
+    async fn store_compacted_answers(&self, game_id: &str, answers_blob: Vec<u8>) -> Result<u64>;
+
     // Get score distribution for a specific game
     async fn get_score_distribution(&self, game_id: &str) -> Result<Vec<i32>>;
 
+    // How many completed, on-board entries on a game found each word, most
+    // found first
+    async fn get_word_counts(&self, game_id: &str) -> Result<Vec<DbWordCount>>;
+
     // Get optimal solutions for a specific game
     async fn get_optimal_solutions(&self, game_id: &str) -> Result<Vec<OptimalAnswer>>;
 
+    // Announcement operations
+
+    // Claim a game for announcing, returning false if it was already claimed
+    async fn claim_game_announcement(&self, game_id: &str) -> Result<bool>;
+
+    // Release a claim whose announcement could not be posted
+    async fn release_game_announcement(&self, game_id: &str) -> Result<()>;
+
+    // Webhook operations
+    async fn create_webhook_subscription(
+        &self,
+        subscription: NewWebhookSubscription,
+    ) -> Result<DbWebhookSubscription>;
+
+    async fn get_webhook_subscription(&self, id: &str) -> Result<Option<DbWebhookSubscription>>;
+
+    // Every subscription, oldest first
+    async fn get_webhook_subscriptions(&self) -> Result<Vec<DbWebhookSubscription>>;
+
+    // Delete a subscription and its deliveries, returning whether it existed
+    async fn delete_webhook_subscription(&self, id: &str) -> Result<bool>;
+
+    // Claim a game's delivery to a subscription, returning false if it was
+    // already claimed
+    async fn claim_webhook_delivery(&self, subscription_id: &str, game_id: &str) -> Result<bool>;
+
+    // Release a claim whose delivery could not be made
+    async fn release_webhook_delivery(&self, subscription_id: &str, game_id: &str) -> Result<()>;
+
+    // Discord link operations
+    // Store a code that links a Discord user to whoever enters it before it
+    // expires, replacing any code they were given before
+    async fn start_discord_link(
+        &self,
+        discord_user_id: &str,
+        code_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<()>;
+
+    // Link the Discord user given an unexpired code to a player, using the
+    // code up. Returns the Discord user's id, or None if no code matched.
+    async fn complete_discord_link(&self, code_hash: &str, user_id: &str)
+        -> Result<Option<String>>;
+
+    // The player a Discord user is linked to
+    async fn get_discord_linked_user(&self, discord_user_id: &str) -> Result<Option<String>>;
+
+    // The Discord users linked to a player
+    async fn get_linked_discord_users(&self, user_id: &str) -> Result<Vec<String>>;
+
+    // Audit log operations
+    async fn record_audit_entry(&self, entry: NewAuditEntry) -> Result<DbAuditEntry>;
+
+    // Audit entries matching the filter, newest first
+    async fn get_audit_entries(&self, filter: &AuditFilter) -> Result<Vec<DbAuditEntry>>;
+
+    // Rate limiting operations
+    // Count a request in a bucket's window, returning the window's count including it
+    async fn increment_rate_limit_window(
+        &self,
+        bucket: &str,
+        window_start: DateTime<Utc>,
+    ) -> Result<i32>;
+
+    async fn delete_rate_limit_windows_before(&self, before: DateTime<Utc>) -> Result<u64>;
+
+    // Admin token operations
+    async fn create_admin_token(&self, token: NewAdminToken) -> Result<DbAdminToken>;
+
+    // Get a token by its hash, whether or not it has expired or been revoked
+    async fn get_admin_token_by_hash(&self, token_hash: &str) -> Result<Option<DbAdminToken>>;
+
+    // Get tokens that haven't been revoked, newest first
+    async fn get_admin_tokens(&self) -> Result<Vec<DbAdminToken>>;
+
+    // Revoke a token, returning it if it existed and wasn't already revoked
+    async fn revoke_admin_token(&self, token_id: &str) -> Result<Option<DbAdminToken>>;
+
     // Completion tracking operations
     async fn mark_game_completed(&self, game_id: &str) -> Result<()>;
 
@@ -64,4 +399,117 @@ pub trait Repository {
         game_id: &str,
         user_score: i32,
     ) -> Result<(i32, i32, f64, i32, i32)>;
+
+    async fn get_player_counts(&self, game_id: &str) -> Result<DbPlayerCounts>;
+
+    // Record when a user started a game, keeping the first start. Returns
+    // the recorded start.
+    async fn record_game_start(&self, user_id: &str, game_id: &str) -> Result<DateTime<Utc>>;
+
+    async fn get_game_start(&self, user_id: &str, game_id: &str) -> Result<Option<DateTime<Utc>>>;
+
+    // A user's entries in blitz games they started, oldest first
+    async fn get_blitz_runs(&self, user_id: &str) -> Result<Vec<DbBlitzRun>>;
+
+    // The fastest completions of a game, from the recorded start to the
+    // player's submission, scoring at least `min_score` and taking at least
+    // `min_elapsed_ms`. Entries saved before the start was recorded, off the
+    // board or completed by the game ender are left out.
+    async fn get_speed_runs(
+        &self,
+        game_id: &str,
+        min_score: i32,
+        min_elapsed_ms: i64,
+        limit: i32,
+    ) -> Result<Vec<DbSpeedRun>>;
+
+    // The most recent `limit` games with a recorded density, newest first,
+    // with how many players started and completed each
+    async fn get_game_densities(&self, limit: i32) -> Result<Vec<DbGameDensity>>;
+
+    // Every game's board, oldest first
+    async fn get_game_boards(&self) -> Result<Vec<DbGameBoard>>;
+
+    // Aggregate a user's scores, percentiles and threshold hits over every game they completed
+    async fn get_user_stats(&self, user_id: &str) -> Result<DbUserStats>;
+
+    // The answers_data of every game a user completed
+    async fn get_completed_answers_data(&self, user_id: &str) -> Result<Vec<String>>;
+
+    // A page of the games a user has played, oldest first, dated after
+    // `after_date` when given
+    async fn get_user_history(
+        &self,
+        user_id: &str,
+        after_date: Option<&str>,
+        limit: i32,
+    ) -> Result<Vec<DbHistoryEntry>>;
+
+    // Every entry a user has, on games in any mode, oldest first
+    async fn get_user_entries(&self, user_id: &str) -> Result<Vec<DbUserEntry>>;
+
+    // A user's scores on custom games
+    async fn get_user_custom_game_scores(&self, user_id: &str) -> Result<Vec<DbCustomGameScore>>;
+
+    // Stats freeze operations
+    // Games dated on or before `date` whose stats haven't been frozen, oldest first
+    async fn get_unfrozen_games_through(&self, date: &str) -> Result<Vec<DbGame>>;
+
+    // Write a game's final aggregates from its completed, on-board entries.
+    // Returns None if the game was already frozen.
+    async fn freeze_game_stats(&self, game_id: &str) -> Result<Option<DbFinalGameStats>>;
+
+    async fn get_final_game_stats(&self, game_id: &str) -> Result<Option<DbFinalGameStats>>;
+
+    // Final ranks on a game of the players who opted in to hearing them
+    async fn get_final_ranks_to_notify(&self, game_id: &str) -> Result<Vec<DbFinalRank>>;
+
+    // Game stats operations
+    // Games dated on or before `date` with no recorded game stats, oldest first
+    async fn get_games_without_stats_through(&self, date: &str) -> Result<Vec<DbGame>>;
+
+    // Record how players fared on a game, replacing any earlier record
+    async fn upsert_game_stats(&self, stats: DbGameStats) -> Result<()>;
+
+    // The game stats of the most recent `limit` English daily games, newest first
+    async fn get_recent_game_stats(&self, limit: i32) -> Result<Vec<DbGameStats>>;
+
+    // Notification operations
+    async fn get_notification_preferences(
+        &self,
+        user_id: &str,
+    ) -> Result<DbNotificationPreferences>;
+
+    async fn set_notification_preferences(
+        &self,
+        preferences: DbNotificationPreferences,
+    ) -> Result<()>;
+
+    async fn create_notification(&self, notification: NewNotification) -> Result<DbNotification>;
+
+    // A user's most recent notifications, newest first
+    async fn get_notifications(&self, user_id: &str, limit: i32) -> Result<Vec<DbNotification>>;
+
+    // Mark all of a user's notifications read, returning how many were unread
+    async fn mark_notifications_read(&self, user_id: &str) -> Result<u64>;
+
+    // Outbox operations
+    // Up to `limit` unprocessed events that have failed fewer than
+    // `max_attempts` times, oldest first
+    async fn get_pending_outbox_events(
+        &self,
+        max_attempts: i32,
+        limit: i32,
+    ) -> Result<Vec<DbOutboxEvent>>;
+
+    async fn mark_outbox_event_processed(&self, event_id: &str) -> Result<()>;
+
+    // Count a failed delivery of an event, keeping the error for inspection
+    async fn record_outbox_event_failure(&self, event_id: &str, error: &str) -> Result<()>;
+
+    // Delete events processed before the given time, returning how many were removed
+    async fn delete_processed_outbox_events(&self, before: DateTime<Utc>) -> Result<u64>;
+
+    // Close the connection pool once in-flight queries finish; later calls fail
+    async fn close(&self);
 }
@@ -0,0 +1,2604 @@
+use anyhow::{anyhow, bail, Result};
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::db::{
+    conversions::AnswerStorage,
+    storage_types::{DbCompactedAnswers, DbStoredAnswers},
+    Repository,
+};
+use crate::game::density::BoardDensity;
+use crate::game::language::Language;
+use crate::game::mode::GameMode;
+use crate::game::quality::BoardQuality;
+
+use super::models::{
+    AuditFilter, DbAccount, DbAdminToken, DbArchiveGame, DbAuditEntry, DbBlitzRun, DbCustomGame,
+    DbCustomGameScore, DbEntryRevision, DbFinalGameStats, DbFinalRank, DbGame, DbGameAnswer,
+    DbGameBoard, DbGameDensity, DbGameEntry, DbGameStats, DbHintUsage, DbHistoryEntry, DbMagicLink,
+    DbNotification, DbNotificationPreferences, DbOptimalSolution, DbOutboxEvent, DbPage, DbPasskey,
+    DbPlayerCounts, DbPracticeGame, DbSpeedRun, DbTournament, DbTournamentResult,
+    DbTournamentStanding, DbUser, DbUserDeletion, DbUserEntry, DbUserSession, DbUserStats,
+    DbWebhookSubscription, DbWordCount, DbWordProposal, GameFilter, NewAdminToken, NewAuditEntry,
+    NewCustomGame, NewCustomGameScore, NewGame, NewGameAnswer, NewGameEntry, NewGameWithAnswers,
+    NewHintUsage, NewMagicLink, NewNotification, NewOptimalSolution, NewOutboxEvent, NewPasskey,
+    NewPracticeGame, NewTournament, NewUser, NewUserSession, NewWebhookSubscription, OptimalAnswer,
+    PageRequest,
+};
+
+/// InMemoryRepository keeps everything in HashMaps, for tests that don't
+/// need a database and demo deployments that don't need to keep their data.
+/// Clones share the same data.
+#[derive(Clone, Default)]
+pub struct InMemoryRepository {
+    tables: Arc<RwLock<Tables>>,
+}
+
+impl InMemoryRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// The stored rows, keyed the way the database tables are
+#[derive(Default)]
+struct Tables {
+    users: HashMap<String, DbUser>,
+    sessions: HashMap<String, DbUserSession>,
+    accounts: HashMap<String, DbAccount>,
+    magic_links: HashMap<String, DbMagicLink>,
+    passkeys: HashMap<String, DbPasskey>,
+    games: HashMap<String, DbGame>,
+    // Keyed by game id
+    game_answers: HashMap<String, Vec<DbGameAnswer>>,
+    compacted_answers: HashMap<String, Vec<u8>>,
+    optimal_solutions: HashMap<String, DbOptimalSolution>,
+    densities: HashMap<String, BoardDensity>,
+    qualities: HashMap<String, BoardQuality>,
+    final_stats: HashMap<String, DbFinalGameStats>,
+    game_stats: HashMap<String, DbGameStats>,
+    announcements: HashSet<String>,
+    webhook_subscriptions: HashMap<String, DbWebhookSubscription>,
+    // Keyed by (subscription id, game id)
+    webhook_deliveries: HashSet<(String, String)>,
+    // Keyed by Discord user id
+    discord_links: HashMap<String, StoredDiscordLink>,
+    // Oldest first
+    audit_log: Vec<DbAuditEntry>,
+    practice_games: HashMap<String, DbPracticeGame>,
+    custom_games: HashMap<String, DbCustomGame>,
+    // Keyed by (custom game id, user id)
+    custom_game_scores: HashMap<(String, String), DbCustomGameScore>,
+    tournaments: HashMap<String, DbTournament>,
+    // Keyed by (tournament id, user id), to when the user joined
+    tournament_participants: HashMap<(String, String), DateTime<Utc>>,
+    // Keyed by tournament id, best first
+    tournament_results: HashMap<String, Vec<DbTournamentResult>>,
+    // Keyed by (user id, game id)
+    entries: HashMap<(String, String), StoredEntry>,
+    game_starts: HashMap<(String, String), DateTime<Utc>>,
+    // Keyed by (user id, game id, word)
+    hints: HashMap<(String, String, String), DbHintUsage>,
+    word_challenges: HashMap<(String, String, String), DateTime<Utc>>,
+    // Keyed by (user id, game id), oldest first
+    entry_revisions: HashMap<(String, String), Vec<DbEntryRevision>>,
+    rate_limit_windows: HashMap<(String, DateTime<Utc>), i32>,
+    admin_tokens: HashMap<String, DbAdminToken>,
+    notification_preferences: HashMap<String, bool>,
+    // In the order they were created
+    notifications: Vec<DbNotification>,
+    outbox: Vec<StoredOutboxEvent>,
+}
+
+struct StoredEntry {
+    entry: DbGameEntry,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Default)]
+struct StoredDiscordLink {
+    user_id: Option<String>,
+    code_hash: Option<String>,
+    code_expires_at: Option<DateTime<Utc>>,
+}
+
+struct StoredOutboxEvent {
+    event: DbOutboxEvent,
+    processed_at: Option<DateTime<Utc>>,
+}
+
+impl Tables {
+    /// Completed entries that count towards a game's stats
+    fn on_board_scores(&self, game_id: &str) -> Vec<i32> {
+        self.entries
+            .values()
+            .map(|stored| &stored.entry)
+            .filter(|entry| entry.game_id == game_id && entry.completed && !entry.off_board)
+            .map(|entry| entry.total_score)
+            .collect()
+    }
+
+    /// The share of a game's on-board scores at or below `score`, out of 100
+    fn percentile(&self, game_id: &str, score: i32) -> Option<f64> {
+        let scores = self.on_board_scores(game_id);
+        if scores.is_empty() {
+            return None;
+        }
+        let at_or_below = scores.iter().filter(|s| **s <= score).count();
+        Some(100.0 * at_or_below as f64 / scores.len() as f64)
+    }
+
+    fn games_by_date(&self) -> Vec<&DbGame> {
+        let mut games: Vec<&DbGame> = self.games.values().collect();
+        games.sort_by(|a, b| a.date.cmp(&b.date));
+        games
+    }
+
+    fn daily_games_by_date(&self) -> Vec<&DbGame> {
+        let mut games = self.games_by_date();
+        games.retain(|game| {
+            game.game_mode() == GameMode::Daily && game.game_language() == Language::English
+        });
+        games
+    }
+
+    /// The daily games currently served for their dates, leaving out boards
+    /// a newer revision replaced
+    fn active_daily_games_by_date(&self) -> Vec<&DbGame> {
+        let mut games = self.daily_games_by_date();
+        games.retain(|game| !game.is_replaced());
+        games
+    }
+
+    fn tournament_standings(&self, tournament: &DbTournament) -> Vec<DbTournamentStanding> {
+        let mut participants: Vec<(&String, &DateTime<Utc>)> = self
+            .tournament_participants
+            .iter()
+            .filter(|((tournament_id, _), _)| *tournament_id == tournament.id)
+            .map(|((_, user_id), joined_at)| (user_id, joined_at))
+            .collect();
+        participants.sort_by_key(|(_, joined_at)| **joined_at);
+
+        let mut standings: Vec<DbTournamentStanding> = participants
+            .into_iter()
+            .map(|(user_id, _)| {
+                // Each participant's best score on each date, over every revision
+                let mut best: HashMap<&str, i32> = HashMap::new();
+                for stored in self.entries.values() {
+                    let entry = &stored.entry;
+                    if entry.user_id != *user_id || !entry.completed || entry.off_board {
+                        continue;
+                    }
+                    let Some(game) = self.games.get(&entry.game_id) else {
+                        continue;
+                    };
+                    if game.game_mode() == GameMode::Daily
+                        && game.language == tournament.language
+                        && game.date >= tournament.start_date
+                        && game.date <= tournament.end_date
+                    {
+                        let score = best.entry(game.date.as_str()).or_insert(entry.total_score);
+                        *score = (*score).max(entry.total_score);
+                    }
+                }
+                DbTournamentStanding {
+                    user_id: user_id.clone(),
+                    total_score: best.values().sum(),
+                    games_played: best.len() as i32,
+                }
+            })
+            .collect();
+        // Stable, so ties stay in the order they joined
+        standings.sort_by_key(|standing| std::cmp::Reverse(standing.total_score));
+        standings
+    }
+
+    fn is_daily_game(&self, game_id: &str) -> bool {
+        self.games.get(game_id).is_some_and(|game| {
+            game.game_mode() == GameMode::Daily && game.game_language() == Language::English
+        })
+    }
+
+    fn insert_game_with_answers(
+        &mut self,
+        game: DbGame,
+        game_answers: Vec<NewGameAnswer>,
+        optimal_solution: Option<NewOptimalSolution>,
+    ) -> Result<(DbGame, Vec<DbGameAnswer>)> {
+        let same_mode = |other: &&DbGame| {
+            other.mode == game.mode && other.language == game.language && !other.is_replaced()
+        };
+        if self
+            .games
+            .values()
+            .filter(same_mode)
+            .any(|other| other.date == game.date)
+        {
+            bail!("A game already exists for {}", game.date);
+        }
+        if self
+            .games
+            .values()
+            .filter(same_mode)
+            .any(|other| other.sequence_number == game.sequence_number)
+        {
+            bail!(
+                "A game already has sequence number {}",
+                game.sequence_number
+            );
+        }
+
+        let answers: Vec<DbGameAnswer> = game_answers
+            .into_iter()
+            .map(|answer| DbGameAnswer::new(game.id.clone(), answer.word, answer.path_stats))
+            .collect();
+
+        if !answers.is_empty() {
+            self.game_answers.insert(game.id.clone(), answers.clone());
+        }
+        if let Some(solution) = optimal_solution {
+            self.optimal_solutions.insert(
+                game.id.clone(),
+                DbOptimalSolution::new(
+                    game.id.clone(),
+                    solution.words_and_scores,
+                    solution.total_score,
+                ),
+            );
+        }
+        self.games.insert(game.id.clone(), game.clone());
+
+        Ok((game, answers))
+    }
+
+    fn compacted_answers(&self, game_id: &str) -> Result<Option<Vec<DbGameAnswer>>> {
+        self.compacted_answers
+            .get(game_id)
+            .map(|blob| {
+                DbCompactedAnswers::decompress(blob)
+                    .map(|compacted| compacted.into_game_answers(game_id))
+                    .map_err(|e| anyhow!("Failed to read compacted answers: {e}"))
+            })
+            .transpose()
+    }
+}
+
+#[async_trait]
+impl Repository for InMemoryRepository {
+    // User operations
+    async fn create_user(&self, new_user: NewUser) -> Result<DbUser> {
+        let mut tables = self.tables.write().await;
+        if tables
+            .users
+            .values()
+            .any(|user| user.cookie_token == new_user.cookie_token)
+        {
+            bail!("A user already has this cookie token");
+        }
+
+        let user = DbUser::new(new_user.cookie_token);
+        tables.users.insert(user.id.clone(), user.clone());
+        Ok(user)
+    }
+
+    async fn get_user_by_cookie(&self, cookie_token: &str) -> Result<Option<DbUser>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .users
+            .values()
+            .find(|user| user.cookie_token == cookie_token)
+            .cloned())
+    }
+
+    async fn get_user_by_id(&self, user_id: &str) -> Result<Option<DbUser>> {
+        Ok(self.tables.read().await.users.get(user_id).cloned())
+    }
+
+    async fn update_user_last_seen(&self, user_id: &str) -> Result<()> {
+        if let Some(user) = self.tables.write().await.users.get_mut(user_id) {
+            user.last_seen = Utc::now();
+        }
+        Ok(())
+    }
+
+    async fn list_users(&self, page: PageRequest) -> Result<DbPage<DbUser>> {
+        let tables = self.tables.read().await;
+        let mut users: Vec<&DbUser> = tables.users.values().collect();
+        users.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+        Ok(paginate(users, page))
+    }
+
+    async fn touch_user_session(&self, session: NewUserSession) -> Result<DbUserSession> {
+        let mut tables = self.tables.write().await;
+        if let Some(existing) = tables.sessions.values_mut().find(|existing| {
+            existing.user_id == session.user_id && existing.session_hash == session.session_hash
+        }) {
+            existing.user_agent_hash = session.user_agent_hash;
+            existing.last_seen = Utc::now();
+            return Ok(existing.clone());
+        }
+
+        let session = DbUserSession::new(
+            session.user_id,
+            session.session_hash,
+            session.user_agent_hash,
+        );
+        tables.sessions.insert(session.id.clone(), session.clone());
+        Ok(session)
+    }
+
+    async fn get_user_sessions(&self, user_id: &str) -> Result<Vec<DbUserSession>> {
+        let tables = self.tables.read().await;
+        let mut sessions: Vec<DbUserSession> = tables
+            .sessions
+            .values()
+            .filter(|session| session.user_id == user_id && session.revoked_at.is_none())
+            .cloned()
+            .collect();
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.last_seen));
+        Ok(sessions)
+    }
+
+    async fn revoke_user_session(
+        &self,
+        user_id: &str,
+        session_id: &str,
+    ) -> Result<Option<DbUserSession>> {
+        let mut tables = self.tables.write().await;
+        Ok(tables
+            .sessions
+            .get_mut(session_id)
+            .filter(|session| session.user_id == user_id && session.revoked_at.is_none())
+            .map(|session| {
+                session.revoked_at = Some(Utc::now());
+                session.clone()
+            }))
+    }
+
+    async fn get_revoked_session_hashes(&self, since: DateTime<Utc>) -> Result<Vec<String>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .sessions
+            .values()
+            .filter(|session| session.revoked_at.is_some_and(|revoked| revoked >= since))
+            .map(|session| session.session_hash.clone())
+            .collect())
+    }
+
+    async fn get_or_create_account(&self, user_id: &str) -> Result<DbAccount> {
+        let mut tables = self.tables.write().await;
+        if let Some(account) = tables
+            .accounts
+            .values()
+            .find(|account| account.user_id == user_id)
+        {
+            return Ok(account.clone());
+        }
+
+        let account = DbAccount::new(user_id.to_string());
+        tables.accounts.insert(account.id.clone(), account.clone());
+        Ok(account)
+    }
+
+    async fn get_account_by_user(&self, user_id: &str) -> Result<Option<DbAccount>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .accounts
+            .values()
+            .find(|account| account.user_id == user_id)
+            .cloned())
+    }
+
+    async fn get_account_by_email(&self, email: &str) -> Result<Option<DbAccount>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .accounts
+            .values()
+            .find(|account| account.email.as_deref() == Some(email))
+            .cloned())
+    }
+
+    async fn get_account_by_id(&self, account_id: &str) -> Result<Option<DbAccount>> {
+        Ok(self.tables.read().await.accounts.get(account_id).cloned())
+    }
+
+    async fn set_account_email(&self, account_id: &str, email: &str) -> Result<()> {
+        let mut tables = self.tables.write().await;
+        if tables
+            .accounts
+            .values()
+            .any(|account| account.id != account_id && account.email.as_deref() == Some(email))
+        {
+            bail!("Another account already has this email");
+        }
+        if let Some(account) = tables.accounts.get_mut(account_id) {
+            account.email = Some(email.to_string());
+        }
+        Ok(())
+    }
+
+    async fn create_magic_link(&self, link: NewMagicLink) -> Result<DbMagicLink> {
+        let mut tables = self.tables.write().await;
+        if tables.magic_links.contains_key(&link.token_hash) {
+            bail!("A magic link already has this token");
+        }
+
+        let link = DbMagicLink {
+            token_hash: link.token_hash,
+            email: link.email,
+            user_id: link.user_id,
+            expires_at: link.expires_at,
+            used_at: None,
+            created_at: Utc::now(),
+        };
+        tables
+            .magic_links
+            .insert(link.token_hash.clone(), link.clone());
+        Ok(link)
+    }
+
+    async fn use_magic_link(&self, token_hash: &str) -> Result<Option<DbMagicLink>> {
+        let now = Utc::now();
+        let mut tables = self.tables.write().await;
+        Ok(tables
+            .magic_links
+            .get_mut(token_hash)
+            .filter(|link| link.used_at.is_none() && link.expires_at > now)
+            .map(|link| {
+                link.used_at = Some(now);
+                link.clone()
+            }))
+    }
+
+    async fn add_passkey(&self, passkey: NewPasskey) -> Result<DbPasskey> {
+        let mut tables = self.tables.write().await;
+        if tables.passkeys.contains_key(&passkey.credential_id) {
+            bail!("This passkey is already registered");
+        }
+
+        let passkey = DbPasskey {
+            credential_id: passkey.credential_id,
+            account_id: passkey.account_id,
+            public_key: passkey.public_key,
+            sign_count: passkey.sign_count,
+            created_at: Utc::now(),
+            last_used_at: None,
+        };
+        tables
+            .passkeys
+            .insert(passkey.credential_id.clone(), passkey.clone());
+        Ok(passkey)
+    }
+
+    async fn get_passkey(&self, credential_id: &str) -> Result<Option<DbPasskey>> {
+        Ok(self
+            .tables
+            .read()
+            .await
+            .passkeys
+            .get(credential_id)
+            .cloned())
+    }
+
+    async fn get_account_passkeys(&self, account_id: &str) -> Result<Vec<DbPasskey>> {
+        let tables = self.tables.read().await;
+        let mut passkeys: Vec<DbPasskey> = tables
+            .passkeys
+            .values()
+            .filter(|passkey| passkey.account_id == account_id)
+            .cloned()
+            .collect();
+        passkeys.sort_by_key(|passkey| passkey.created_at);
+        Ok(passkeys)
+    }
+
+    async fn update_passkey_sign_count(&self, credential_id: &str, sign_count: i64) -> Result<()> {
+        if let Some(passkey) = self.tables.write().await.passkeys.get_mut(credential_id) {
+            passkey.sign_count = sign_count;
+            passkey.last_used_at = Some(Utc::now());
+        }
+        Ok(())
+    }
+
+    async fn merge_users(&self, into_user_id: &str, from_user_id: &str) -> Result<u64> {
+        let mut tables = self.tables.write().await;
+        let key = |user_id: &str, game_id: &str| (user_id.to_string(), game_id.to_string());
+
+        let mut moved = 0;
+        let from_entries: Vec<(String, String)> = tables
+            .entries
+            .keys()
+            .filter(|(user_id, _)| user_id == from_user_id)
+            .cloned()
+            .collect();
+        for (_, game_id) in from_entries {
+            let mut stored = tables
+                .entries
+                .remove(&key(from_user_id, &game_id))
+                .expect("entry was just listed");
+            if let Entry::Vacant(into) = tables.entries.entry(key(into_user_id, &game_id)) {
+                stored.entry.user_id = into_user_id.to_string();
+                into.insert(stored);
+                moved += 1;
+            }
+        }
+
+        let from_hints: Vec<(String, String, String)> = tables
+            .hints
+            .keys()
+            .filter(|(user_id, _, _)| user_id == from_user_id)
+            .cloned()
+            .collect();
+        for (_, game_id, word) in from_hints {
+            let mut usage = tables
+                .hints
+                .remove(&(from_user_id.to_string(), game_id.clone(), word.clone()))
+                .expect("hint was just listed");
+            if let Entry::Vacant(into) =
+                tables
+                    .hints
+                    .entry((into_user_id.to_string(), game_id, word))
+            {
+                usage.user_id = into_user_id.to_string();
+                into.insert(usage);
+            }
+        }
+
+        for notification in &mut tables.notifications {
+            if notification.user_id == from_user_id {
+                notification.user_id = into_user_id.to_string();
+            }
+        }
+
+        if let Some(final_rank) = tables.notification_preferences.remove(from_user_id) {
+            tables
+                .notification_preferences
+                .entry(into_user_id.to_string())
+                .or_insert(final_rank);
+        }
+
+        let from_starts: Vec<(String, String)> = tables
+            .game_starts
+            .keys()
+            .filter(|(user_id, _)| user_id == from_user_id)
+            .cloned()
+            .collect();
+        for (_, game_id) in from_starts {
+            let started_at = tables
+                .game_starts
+                .remove(&key(from_user_id, &game_id))
+                .expect("start was just listed");
+            tables
+                .game_starts
+                .entry(key(into_user_id, &game_id))
+                .or_insert(started_at);
+        }
+
+        let from_challenges: Vec<(String, String, String)> = tables
+            .word_challenges
+            .keys()
+            .filter(|(user_id, _, _)| user_id == from_user_id)
+            .cloned()
+            .collect();
+        for (user_id, game_id, word) in from_challenges {
+            let created_at = tables
+                .word_challenges
+                .remove(&(user_id, game_id.clone(), word.clone()))
+                .expect("challenge was just listed");
+            tables
+                .word_challenges
+                .entry((into_user_id.to_string(), game_id, word))
+                .or_insert(created_at);
+        }
+
+        let from_scores: Vec<(String, String)> = tables
+            .custom_game_scores
+            .keys()
+            .filter(|(_, user_id)| user_id == from_user_id)
+            .cloned()
+            .collect();
+        for (custom_game_id, _) in from_scores {
+            let mut score = tables
+                .custom_game_scores
+                .remove(&(custom_game_id.clone(), from_user_id.to_string()))
+                .expect("score was just listed");
+            if let Entry::Vacant(into) = tables
+                .custom_game_scores
+                .entry((custom_game_id, into_user_id.to_string()))
+            {
+                score.user_id = into_user_id.to_string();
+                into.insert(score);
+            }
+        }
+        for game in tables.custom_games.values_mut() {
+            if game.created_by.as_deref() == Some(from_user_id) {
+                game.created_by = Some(into_user_id.to_string());
+            }
+        }
+        let from_tournaments: Vec<(String, String)> = tables
+            .tournament_participants
+            .keys()
+            .filter(|(_, user_id)| user_id == from_user_id)
+            .cloned()
+            .collect();
+        for (tournament_id, _) in from_tournaments {
+            let joined_at = tables
+                .tournament_participants
+                .remove(&(tournament_id.clone(), from_user_id.to_string()))
+                .expect("participant was just listed");
+            tables
+                .tournament_participants
+                .entry((tournament_id, into_user_id.to_string()))
+                .or_insert(joined_at);
+        }
+        for results in tables.tournament_results.values_mut() {
+            let into_placed = results
+                .iter()
+                .any(|result| result.user_id.as_deref() == Some(into_user_id));
+            for result in results.iter_mut() {
+                if result.user_id.as_deref() == Some(from_user_id) {
+                    result.user_id = (!into_placed).then(|| into_user_id.to_string());
+                }
+            }
+        }
+        for link in tables.discord_links.values_mut() {
+            if link.user_id.as_deref() == Some(from_user_id) {
+                link.user_id = Some(into_user_id.to_string());
+            }
+        }
+
+        tables
+            .entry_revisions
+            .retain(|(user_id, _), _| user_id != from_user_id);
+        tables
+            .sessions
+            .retain(|_, session| session.user_id != from_user_id);
+        tables
+            .magic_links
+            .retain(|_, link| link.user_id.as_deref() != Some(from_user_id));
+        tables.users.remove(from_user_id);
+
+        Ok(moved)
+    }
+
+    async fn delete_user(&self, user_id: &str) -> Result<DbUserDeletion> {
+        let cleared_answers = DbStoredAnswers {
+            answers: Vec::new(),
+        }
+        .to_json()?;
+        let mut tables = self.tables.write().await;
+        let anonymous = DbUser::anonymous();
+        let mut deletion = DbUserDeletion::default();
+
+        let user_entries: Vec<(String, String)> = tables
+            .entries
+            .keys()
+            .filter(|(entry_user_id, _)| entry_user_id == user_id)
+            .cloned()
+            .collect();
+        for (_, game_id) in user_entries {
+            let mut stored = tables
+                .entries
+                .remove(&(user_id.to_string(), game_id.clone()))
+                .expect("entry was just listed");
+            if stored.entry.completed && !stored.entry.off_board {
+                stored.entry.user_id = anonymous.id.clone();
+                stored.entry.answers_data = cleared_answers.clone();
+                stored.entry.request_id = None;
+                tables
+                    .entries
+                    .insert((anonymous.id.clone(), game_id), stored);
+                deletion.anonymized_entries += 1;
+            } else {
+                deletion.deleted_entries += 1;
+            }
+        }
+
+        let user_scores: Vec<(String, String)> = tables
+            .custom_game_scores
+            .keys()
+            .filter(|(_, score_user_id)| score_user_id == user_id)
+            .cloned()
+            .collect();
+        for (custom_game_id, _) in user_scores {
+            let mut score = tables
+                .custom_game_scores
+                .remove(&(custom_game_id.clone(), user_id.to_string()))
+                .expect("score was just listed");
+            score.user_id = anonymous.id.clone();
+            score.answers_data = cleared_answers.clone();
+            tables
+                .custom_game_scores
+                .insert((custom_game_id, anonymous.id.clone()), score);
+        }
+        let keeps_anonymous = tables
+            .entries
+            .keys()
+            .any(|(entry_user_id, _)| *entry_user_id == anonymous.id)
+            || tables
+                .custom_game_scores
+                .keys()
+                .any(|(_, score_user_id)| *score_user_id == anonymous.id);
+        if keeps_anonymous {
+            tables.users.insert(anonymous.id.clone(), anonymous);
+        }
+
+        for game in tables.custom_games.values_mut() {
+            if game.created_by.as_deref() == Some(user_id) {
+                game.created_by = None;
+            }
+        }
+        tables
+            .tournament_participants
+            .retain(|(_, participant_id), _| participant_id != user_id);
+        for result in tables.tournament_results.values_mut().flatten() {
+            if result.user_id.as_deref() == Some(user_id) {
+                result.user_id = None;
+            }
+        }
+
+        let accounts: Vec<DbAccount> = tables
+            .accounts
+            .values()
+            .filter(|account| account.user_id == user_id)
+            .cloned()
+            .collect();
+        for account in accounts {
+            tables
+                .passkeys
+                .retain(|_, passkey| passkey.account_id != account.id);
+            // Links emailed to the account's address name the address rather than the user
+            if let Some(email) = &account.email {
+                tables.magic_links.retain(|_, link| link.email != *email);
+            }
+            tables.accounts.remove(&account.id);
+        }
+        tables
+            .magic_links
+            .retain(|_, link| link.user_id.as_deref() != Some(user_id));
+        tables
+            .hints
+            .retain(|(hint_user_id, _, _), _| hint_user_id != user_id);
+        tables
+            .sessions
+            .retain(|_, session| session.user_id != user_id);
+        tables.notification_preferences.remove(user_id);
+        tables
+            .notifications
+            .retain(|notification| notification.user_id != user_id);
+        tables
+            .game_starts
+            .retain(|(start_user_id, _), _| start_user_id != user_id);
+        tables
+            .word_challenges
+            .retain(|(challenge_user_id, _, _), _| challenge_user_id != user_id);
+        tables
+            .entry_revisions
+            .retain(|(revision_user_id, _), _| revision_user_id != user_id);
+        tables
+            .discord_links
+            .retain(|_, link| link.user_id.as_deref() != Some(user_id));
+        tables.users.remove(user_id);
+
+        Ok(deletion)
+    }
+
+    async fn get_game_by_date(&self, date: &str) -> Result<Option<DbGame>> {
+        self.get_game_by_date_in_mode(date, GameMode::Daily).await
+    }
+
+    async fn get_game_by_date_in_mode(&self, date: &str, mode: GameMode) -> Result<Option<DbGame>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .games
+            .values()
+            .find(|game| {
+                game.date == date
+                    && game.game_mode() == mode
+                    && game.game_language() == Language::English
+                    && !game.is_replaced()
+            })
+            .cloned())
+    }
+
+    async fn get_game_by_id(&self, game_id: &str) -> Result<Option<DbGame>> {
+        Ok(self.tables.read().await.games.get(game_id).cloned())
+    }
+
+    async fn get_game_by_sequence_number(&self, sequence_number: i32) -> Result<Option<DbGame>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .games
+            .values()
+            .find(|game| {
+                game.sequence_number == sequence_number
+                    && game.game_mode() == GameMode::Daily
+                    && game.game_language() == Language::English
+                    && !game.is_replaced()
+            })
+            .cloned())
+    }
+
+    async fn game_exists_for_date(&self, date: &str) -> Result<bool> {
+        let tables = self.tables.read().await;
+        Ok(tables.games.values().any(|game| {
+            game.date == date
+                && game.game_mode() == GameMode::Daily
+                && game.game_language() == Language::English
+                && !game.is_replaced()
+        }))
+    }
+
+    async fn get_next_sequence_number(&self) -> Result<i32> {
+        self.get_next_sequence_number_in_mode(GameMode::Daily).await
+    }
+
+    async fn get_next_sequence_number_in_mode(&self, mode: GameMode) -> Result<i32> {
+        let tables = self.tables.read().await;
+        let max_sequence = tables
+            .games
+            .values()
+            .filter(|game| game.game_mode() == mode && game.game_language() == Language::English)
+            .map(|game| game.sequence_number)
+            .max();
+        Ok(max_sequence.unwrap_or(0) + 1)
+    }
+
+    async fn get_game_by_date_in_language(
+        &self,
+        date: &str,
+        language: Language,
+    ) -> Result<Option<DbGame>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .games
+            .values()
+            .find(|game| {
+                game.date == date
+                    && game.game_mode() == GameMode::Daily
+                    && game.game_language() == language
+                    && !game.is_replaced()
+            })
+            .cloned())
+    }
+
+    async fn get_game_by_sequence_number_in_language(
+        &self,
+        sequence_number: i32,
+        language: Language,
+    ) -> Result<Option<DbGame>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .games
+            .values()
+            .find(|game| {
+                game.sequence_number == sequence_number
+                    && game.game_mode() == GameMode::Daily
+                    && game.game_language() == language
+                    && !game.is_replaced()
+            })
+            .cloned())
+    }
+
+    async fn get_next_sequence_number_in_language(&self, language: Language) -> Result<i32> {
+        let tables = self.tables.read().await;
+        let max_sequence = tables
+            .games
+            .values()
+            .filter(|game| game.game_mode() == GameMode::Daily && game.game_language() == language)
+            .map(|game| game.sequence_number)
+            .max();
+        Ok(max_sequence.unwrap_or(0) + 1)
+    }
+
+    async fn get_archive_games(
+        &self,
+        user_id: Option<&str>,
+        latest_date: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<DbArchiveGame>> {
+        let tables = self.tables.read().await;
+        let mut games: Vec<&DbGame> = tables
+            .active_daily_games_by_date()
+            .into_iter()
+            .filter(|game| game.date.as_str() <= latest_date)
+            .collect();
+        games.reverse();
+
+        Ok(games
+            .into_iter()
+            .skip(offset.max(0) as usize)
+            .take(limit.max(0) as usize)
+            .map(|game| {
+                let user_entry = user_id.and_then(|user_id| {
+                    tables
+                        .entries
+                        .get(&(user_id.to_string(), game.id.clone()))
+                        .map(|stored| &stored.entry)
+                });
+                let scores = tables.on_board_scores(&game.id);
+                DbArchiveGame {
+                    game_id: game.id.clone(),
+                    date: game.date.clone(),
+                    sequence_number: game.sequence_number,
+                    threshold_score: game.threshold_score,
+                    user_completed: user_entry.is_some_and(|entry| entry.completed),
+                    user_score: user_entry.map(|entry| entry.total_score),
+                    total_players: scores.len() as i32,
+                    average_score: average(&scores),
+                }
+            })
+            .collect())
+    }
+
+    async fn count_archive_games(&self, latest_date: &str) -> Result<i32> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .active_daily_games_by_date()
+            .into_iter()
+            .filter(|game| game.date.as_str() <= latest_date)
+            .count() as i32)
+    }
+
+    async fn list_games(&self, filter: &GameFilter, page: PageRequest) -> Result<DbPage<DbGame>> {
+        let tables = self.tables.read().await;
+        let mut games: Vec<&DbGame> = tables
+            .games
+            .values()
+            .filter(|game| {
+                filter.mode.is_none_or(|mode| game.game_mode() == mode)
+                    && filter
+                        .language
+                        .is_none_or(|language| game.game_language() == language)
+                    && filter
+                        .from_date
+                        .as_ref()
+                        .is_none_or(|from| &game.date >= from)
+                    && filter.to_date.as_ref().is_none_or(|to| &game.date <= to)
+                    && (filter.include_replaced || !game.is_replaced())
+            })
+            .collect();
+        games.sort_by(|a, b| (&b.date, b.created_at, &b.id).cmp(&(&a.date, a.created_at, &a.id)));
+        Ok(paginate(games, page))
+    }
+
+    async fn create_practice_game(&self, new_game: NewPracticeGame) -> Result<DbPracticeGame> {
+        let game = DbPracticeGame::new(
+            new_game.seed,
+            new_game.board_data,
+            new_game.threshold_score,
+            new_game.optimal_score,
+            new_game.expires_at,
+        );
+        self.tables
+            .write()
+            .await
+            .practice_games
+            .insert(game.id.clone(), game.clone());
+        Ok(game)
+    }
+
+    async fn get_practice_game(&self, practice_id: &str) -> Result<Option<DbPracticeGame>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .practice_games
+            .get(practice_id)
+            .filter(|game| game.expires_at > Utc::now())
+            .cloned())
+    }
+
+    async fn delete_expired_practice_games(&self) -> Result<u64> {
+        let now = Utc::now();
+        let mut tables = self.tables.write().await;
+        let before = tables.practice_games.len();
+        tables
+            .practice_games
+            .retain(|_, game| game.expires_at > now);
+        Ok((before - tables.practice_games.len()) as u64)
+    }
+
+    async fn create_custom_game(&self, new_game: NewCustomGame) -> Result<DbCustomGame> {
+        let mut tables = self.tables.write().await;
+        if let Some(existing) = tables
+            .custom_games
+            .values()
+            .find(|game| game.board_code == new_game.board_code)
+        {
+            return Ok(existing.clone());
+        }
+        let game = DbCustomGame::from(new_game);
+        tables.custom_games.insert(game.id.clone(), game.clone());
+        Ok(game)
+    }
+
+    async fn get_custom_game(&self, slug: &str) -> Result<Option<DbCustomGame>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .custom_games
+            .values()
+            .find(|game| game.slug == slug)
+            .cloned())
+    }
+
+    async fn save_custom_game_score(&self, score: NewCustomGameScore) -> Result<DbCustomGameScore> {
+        let mut tables = self.tables.write().await;
+        let key = (score.custom_game_id.clone(), score.user_id.clone());
+        match tables.custom_game_scores.entry(key) {
+            Entry::Occupied(mut best) => {
+                if score.total_score > best.get().total_score {
+                    let best = best.get_mut();
+                    best.answers_data = score.answers_data;
+                    best.total_score = score.total_score;
+                    best.updated_at = Utc::now();
+                }
+                Ok(best.get().clone())
+            }
+            Entry::Vacant(slot) => Ok(slot.insert(DbCustomGameScore::from(score)).clone()),
+        }
+    }
+
+    async fn get_custom_game_scores(
+        &self,
+        custom_game_id: &str,
+        limit: i32,
+    ) -> Result<Vec<DbCustomGameScore>> {
+        let tables = self.tables.read().await;
+        let mut scores: Vec<DbCustomGameScore> = tables
+            .custom_game_scores
+            .values()
+            .filter(|score| score.custom_game_id == custom_game_id)
+            .cloned()
+            .collect();
+        scores.sort_by(|a, b| {
+            b.total_score
+                .cmp(&a.total_score)
+                .then(a.updated_at.cmp(&b.updated_at))
+        });
+        scores.truncate(limit.max(0) as usize);
+        Ok(scores)
+    }
+
+    async fn create_tournament(&self, new_tournament: NewTournament) -> Result<DbTournament> {
+        let tournament = DbTournament::from(new_tournament);
+        self.tables
+            .write()
+            .await
+            .tournaments
+            .insert(tournament.id.clone(), tournament.clone());
+        Ok(tournament)
+    }
+
+    async fn get_tournament(&self, tournament_id: &str) -> Result<Option<DbTournament>> {
+        Ok(self
+            .tables
+            .read()
+            .await
+            .tournaments
+            .get(tournament_id)
+            .cloned())
+    }
+
+    async fn list_tournaments(&self, limit: i32) -> Result<Vec<DbTournament>> {
+        let tables = self.tables.read().await;
+        let mut tournaments: Vec<DbTournament> = tables.tournaments.values().cloned().collect();
+        tournaments.sort_by(|a, b| {
+            b.start_date
+                .cmp(&a.start_date)
+                .then(b.created_at.cmp(&a.created_at))
+        });
+        tournaments.truncate(limit.max(0) as usize);
+        Ok(tournaments)
+    }
+
+    async fn join_tournament(&self, tournament_id: &str, user_id: &str) -> Result<bool> {
+        let mut tables = self.tables.write().await;
+        match tables
+            .tournament_participants
+            .entry((tournament_id.to_string(), user_id.to_string()))
+        {
+            Entry::Occupied(_) => Ok(false),
+            Entry::Vacant(slot) => {
+                slot.insert(Utc::now());
+                Ok(true)
+            }
+        }
+    }
+
+    async fn get_tournament_standings(
+        &self,
+        tournament_id: &str,
+    ) -> Result<Vec<DbTournamentStanding>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .tournaments
+            .get(tournament_id)
+            .map(|tournament| tables.tournament_standings(tournament))
+            .unwrap_or_default())
+    }
+
+    async fn get_unfinalized_tournaments_through(&self, date: &str) -> Result<Vec<DbTournament>> {
+        let tables = self.tables.read().await;
+        let mut tournaments: Vec<DbTournament> = tables
+            .tournaments
+            .values()
+            .filter(|tournament| !tournament.is_finalized() && tournament.end_date.as_str() <= date)
+            .cloned()
+            .collect();
+        tournaments.sort_by(|a, b| a.end_date.cmp(&b.end_date));
+        Ok(tournaments)
+    }
+
+    async fn finalize_tournament(
+        &self,
+        tournament_id: &str,
+    ) -> Result<Option<Vec<DbTournamentResult>>> {
+        let mut tables = self.tables.write().await;
+        let Some(tournament) = tables
+            .tournaments
+            .get(tournament_id)
+            .filter(|tournament| !tournament.is_finalized())
+            .cloned()
+        else {
+            return Ok(None);
+        };
+        let standings = tables.tournament_standings(&tournament);
+        let results = DbTournamentResult::from_standings(tournament_id, &standings);
+        tables
+            .tournament_results
+            .insert(tournament_id.to_string(), results.clone());
+        if let Some(tournament) = tables.tournaments.get_mut(tournament_id) {
+            tournament.finalized_at = Some(Utc::now());
+        }
+        Ok(Some(results))
+    }
+
+    async fn get_tournament_results(&self, tournament_id: &str) -> Result<Vec<DbTournamentResult>> {
+        Ok(self
+            .tables
+            .read()
+            .await
+            .tournament_results
+            .get(tournament_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    // Game entry operations
+    async fn create_or_update_game_entry(&self, new_entry: NewGameEntry) -> Result<DbGameEntry> {
+        self.create_or_update_game_entry_with_events(new_entry, Vec::new())
+            .await
+    }
+
+    async fn create_or_update_game_entry_with_events(
+        &self,
+        new_entry: NewGameEntry,
+        events: Vec<NewOutboxEvent>,
+    ) -> Result<DbGameEntry> {
+        let mut tables = self.tables.write().await;
+        let now = Utc::now();
+        // Writes after the game's stats were frozen are late plays
+        let frozen = tables.final_stats.contains_key(&new_entry.game_id);
+        let key = (new_entry.user_id.clone(), new_entry.game_id.clone());
+
+        let entry = match tables.entries.get_mut(&key) {
+            // A retry of the write that produced the entry changes nothing
+            Some(stored)
+                if new_entry.request_id.is_some()
+                    && stored.entry.request_id == new_entry.request_id =>
+            {
+                return Ok(stored.entry.clone());
+            }
+            Some(stored) => {
+                if new_entry.completed && !stored.entry.completed {
+                    stored.completed_at = Some(now);
+                }
+                let entry = &mut stored.entry;
+                entry.answers_data = new_entry.answers_data;
+                entry.total_score = new_entry.total_score;
+                entry.completed = new_entry.completed;
+                entry.suspicion_score = entry.suspicion_score.max(new_entry.suspicion_score);
+                entry.off_board = entry.off_board || new_entry.exclude || frozen;
+                entry.request_id = new_entry.request_id;
+                entry.updated_at = now;
+                entry.clone()
+            }
+            None => {
+                let mut entry = DbGameEntry::new(
+                    new_entry.user_id,
+                    new_entry.game_id,
+                    new_entry.answers_data,
+                    new_entry.total_score,
+                    new_entry.completed,
+                );
+                entry.suspicion_score = new_entry.suspicion_score;
+                entry.off_board = new_entry.exclude || frozen;
+                entry.request_id = new_entry.request_id;
+                let completed_at = entry.completed.then_some(entry.updated_at);
+                tables.entries.insert(
+                    key,
+                    StoredEntry {
+                        entry: entry.clone(),
+                        completed_at,
+                    },
+                );
+                entry
+            }
+        };
+
+        for event in events {
+            tables.outbox.push(StoredOutboxEvent {
+                event: DbOutboxEvent::new(event),
+                processed_at: None,
+            });
+        }
+
+        Ok(entry)
+    }
+
+    async fn get_game_entry(&self, user_id: &str, game_id: &str) -> Result<Option<DbGameEntry>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .entries
+            .get(&(user_id.to_string(), game_id.to_string()))
+            .map(|stored| stored.entry.clone()))
+    }
+
+    async fn list_entries_for_game(
+        &self,
+        game_id: &str,
+        page: PageRequest,
+    ) -> Result<DbPage<DbGameEntry>> {
+        let tables = self.tables.read().await;
+        let mut entries: Vec<&DbGameEntry> = tables
+            .entries
+            .values()
+            .map(|stored| &stored.entry)
+            .filter(|entry| entry.game_id == game_id)
+            .collect();
+        entries.sort_by(|a, b| (a.created_at, &a.id).cmp(&(b.created_at, &b.id)));
+        Ok(paginate(entries, page))
+    }
+
+    async fn get_hint_usage(&self, user_id: &str, game_id: &str) -> Result<Vec<DbHintUsage>> {
+        let tables = self.tables.read().await;
+        let mut usage: Vec<DbHintUsage> = tables
+            .hints
+            .values()
+            .filter(|usage| usage.user_id == user_id && usage.game_id == game_id)
+            .cloned()
+            .collect();
+        usage.sort_by(|a, b| (a.created_at, &a.word).cmp(&(b.created_at, &b.word)));
+        Ok(usage)
+    }
+
+    async fn record_hint_usage(&self, usage: NewHintUsage) -> Result<DbHintUsage> {
+        let mut tables = self.tables.write().await;
+        let key = (
+            usage.user_id.clone(),
+            usage.game_id.clone(),
+            usage.word.clone(),
+        );
+
+        // A level is never lowered, so replaying an older request can't hand back hints
+        let recorded = tables
+            .hints
+            .entry(key)
+            .and_modify(|existing| {
+                existing.level = existing.level.max(usage.level);
+                existing.updated_at = Utc::now();
+            })
+            .or_insert_with(|| {
+                DbHintUsage::new(usage.user_id, usage.game_id, usage.word, usage.level)
+            });
+        Ok(recorded.clone())
+    }
+
+    async fn record_word_challenge(&self, user_id: &str, game_id: &str, word: &str) -> Result<()> {
+        let mut tables = self.tables.write().await;
+        tables
+            .word_challenges
+            .entry((user_id.to_string(), game_id.to_string(), word.to_string()))
+            .or_insert_with(Utc::now);
+        Ok(())
+    }
+
+    async fn get_challenged_words(&self, user_id: &str, game_id: &str) -> Result<Vec<String>> {
+        let tables = self.tables.read().await;
+        let mut words: Vec<String> = tables
+            .word_challenges
+            .keys()
+            .filter(|(challenger, challenged_game, _)| {
+                challenger == user_id && challenged_game == game_id
+            })
+            .map(|(_, _, word)| word.clone())
+            .collect();
+        words.sort();
+        Ok(words)
+    }
+
+    async fn get_wordlist_proposals(&self) -> Result<Vec<DbWordProposal>> {
+        let tables = self.tables.read().await;
+        let mut proposals: HashMap<&str, DbWordProposal> = HashMap::new();
+        for ((_, _, word), created_at) in &tables.word_challenges {
+            let proposal = proposals.entry(word).or_insert_with(|| DbWordProposal {
+                word: word.clone(),
+                challenges: 0,
+                first_challenged_at: *created_at,
+            });
+            proposal.challenges += 1;
+            proposal.first_challenged_at = proposal.first_challenged_at.min(*created_at);
+        }
+
+        let mut proposals: Vec<DbWordProposal> = proposals.into_values().collect();
+        proposals.sort_by(|a, b| {
+            b.challenges
+                .cmp(&a.challenges)
+                .then_with(|| a.word.cmp(&b.word))
+        });
+        Ok(proposals)
+    }
+
+    async fn save_entry_revision(
+        &self,
+        user_id: &str,
+        game_id: &str,
+        answers_data: &str,
+        keep: usize,
+    ) -> Result<DbEntryRevision> {
+        let revision = DbEntryRevision::new(
+            user_id.to_string(),
+            game_id.to_string(),
+            answers_data.to_string(),
+        );
+        let mut tables = self.tables.write().await;
+        let revisions = tables
+            .entry_revisions
+            .entry((user_id.to_string(), game_id.to_string()))
+            .or_default();
+        revisions.push(revision.clone());
+        let dropped = revisions.len().saturating_sub(keep);
+        revisions.drain(..dropped);
+        Ok(revision)
+    }
+
+    async fn get_entry_revisions(
+        &self,
+        user_id: &str,
+        game_id: &str,
+    ) -> Result<Vec<DbEntryRevision>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .entry_revisions
+            .get(&(user_id.to_string(), game_id.to_string()))
+            .map(|revisions| revisions.iter().rev().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn create_game_with_answers(
+        &self,
+        new_game: NewGame,
+        game_answers: Vec<NewGameAnswer>,
+        optimal_solution: Option<NewOptimalSolution>,
+    ) -> Result<(DbGame, Vec<DbGameAnswer>)> {
+        self.tables.write().await.insert_game_with_answers(
+            DbGame::new(new_game),
+            game_answers,
+            optimal_solution,
+        )
+    }
+
+    async fn create_games_with_answers(
+        &self,
+        games: Vec<NewGameWithAnswers>,
+    ) -> Result<Vec<DbGame>> {
+        let mut tables = self.tables.write().await;
+
+        // Check every game first, so either all of them are created or none
+        let mut dates: HashSet<(&str, &str, &str)> = tables
+            .games
+            .values()
+            .filter(|g| !g.is_replaced())
+            .map(|g| (g.language.as_str(), g.mode.as_str(), g.date.as_str()))
+            .collect();
+        let mut sequence_numbers: HashSet<(&str, &str, i32)> = tables
+            .games
+            .values()
+            .filter(|g| !g.is_replaced())
+            .map(|g| (g.language.as_str(), g.mode.as_str(), g.sequence_number))
+            .collect();
+        for new_game in &games {
+            let language = new_game.game.language.as_str();
+            let mode = new_game.game.mode.as_str();
+            if !dates.insert((language, mode, &new_game.game.date)) {
+                bail!("A game already exists for {}", new_game.game.date);
+            }
+            if !sequence_numbers.insert((language, mode, new_game.game.sequence_number)) {
+                bail!(
+                    "A game already has sequence number {}",
+                    new_game.game.sequence_number
+                );
+            }
+        }
+
+        let mut created_games = Vec::with_capacity(games.len());
+        for new_game in games {
+            let (game, _answers) = tables.insert_game_with_answers(
+                DbGame::new(new_game.game),
+                new_game.answers,
+                new_game.optimal_solution,
+            )?;
+            if let Some(density) = new_game.density {
+                tables.densities.insert(game.id.clone(), density);
+            }
+            if let Some(quality) = new_game.quality {
+                tables.qualities.insert(game.id.clone(), quality);
+            }
+            created_games.push(game);
+        }
+        Ok(created_games)
+    }
+
+    async fn replace_game(
+        &self,
+        game_id: &str,
+        replacement: NewGameWithAnswers,
+    ) -> Result<Option<DbGame>> {
+        let mut tables = self.tables.write().await;
+        let Some(replaced) = tables
+            .games
+            .get_mut(game_id)
+            .filter(|game| !game.is_replaced())
+        else {
+            return Ok(None);
+        };
+        replaced.deleted_at = Some(Utc::now());
+        let replaced = replaced.clone();
+
+        let mut game = DbGame::new(NewGame {
+            date: replaced.date,
+            sequence_number: replaced.sequence_number,
+            mode: replaced.mode,
+            language: replaced.language,
+            ..replacement.game
+        });
+        game.revision = replaced.revision + 1;
+        let (game, _answers) = tables.insert_game_with_answers(
+            game,
+            replacement.answers,
+            replacement.optimal_solution,
+        )?;
+        if let Some(density) = replacement.density {
+            tables.densities.insert(game.id.clone(), density);
+        }
+        if let Some(quality) = replacement.quality {
+            tables.qualities.insert(game.id.clone(), quality);
+        }
+        Ok(Some(game))
+    }
+
+    async fn get_game_words(&self, game_id: &str) -> Result<Vec<String>> {
+        let answers = self.get_game_answers(game_id).await?;
+        let mut words: Vec<String> = answers.into_iter().map(|answer| answer.word).collect();
+        words.sort();
+        words.dedup();
+        Ok(words)
+    }
+
+    async fn get_game_answers(&self, game_id: &str) -> Result<Vec<DbGameAnswer>> {
+        let tables = self.tables.read().await;
+        match tables.game_answers.get(game_id) {
+            Some(answers) => {
+                let mut answers = answers.clone();
+                answers.sort_by(|a, b| a.word.cmp(&b.word));
+                Ok(answers)
+            }
+            None => Ok(tables.compacted_answers(game_id)?.unwrap_or_default()),
+        }
+    }
+
+    async fn get_uncompacted_game_ids(&self, before_date: &str, limit: i32) -> Result<Vec<String>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .games_by_date()
+            .into_iter()
+            .filter(|game| {
+                game.date.as_str() < before_date
+                    && !tables.compacted_answers.contains_key(&game.id)
+                    && tables.game_answers.contains_key(&game.id)
+            })
+            .take(limit.max(0) as usize)
+            .map(|game| game.id.clone())
+            .collect())
+    }
+
+    async fn store_compacted_answers(&self, game_id: &str, answers_blob: Vec<u8>) -> Result<u64> {
+        let mut tables = self.tables.write().await;
+        if tables.games.contains_key(game_id) {
+            tables
+                .compacted_answers
+                .insert(game_id.to_string(), answers_blob);
+        }
+        let deleted = tables
+            .game_answers
+            .remove(game_id)
+            .map_or(0, |answers| answers.len());
+        Ok(deleted as u64)
+    }
+
+    async fn get_score_distribution(&self, game_id: &str) -> Result<Vec<i32>> {
+        Ok(self.tables.read().await.on_board_scores(game_id))
+    }
+
+    async fn get_word_counts(&self, game_id: &str) -> Result<Vec<DbWordCount>> {
+        let tables = self.tables.read().await;
+        let mut counts: HashMap<String, i32> = HashMap::new();
+        for entry in tables.entries.values().map(|stored| &stored.entry) {
+            if entry.game_id != game_id || !entry.completed || entry.off_board {
+                continue;
+            }
+            // Entries the database couldn't split into rows have none
+            let Ok(answers) = AnswerStorage::entry_answers(&entry.answers_data) else {
+                continue;
+            };
+            // A word played twice in one entry is still one player finding it
+            let words: HashSet<String> = answers.into_iter().map(|answer| answer.word).collect();
+            for word in words {
+                *counts.entry(word).or_default() += 1;
+            }
+        }
+        let mut counts: Vec<DbWordCount> = counts
+            .into_iter()
+            .map(|(word, players)| DbWordCount { word, players })
+            .collect();
+        counts.sort_by(|a, b| b.players.cmp(&a.players).then_with(|| a.word.cmp(&b.word)));
+        Ok(counts)
+    }
+
+    async fn get_optimal_solutions(&self, game_id: &str) -> Result<Vec<OptimalAnswer>> {
+        let tables = self.tables.read().await;
+        match tables.optimal_solutions.get(game_id) {
+            Some(solution) => Ok(serde_json::from_str(&solution.words_and_scores)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn claim_game_announcement(&self, game_id: &str) -> Result<bool> {
+        Ok(self
+            .tables
+            .write()
+            .await
+            .announcements
+            .insert(game_id.to_string()))
+    }
+
+    async fn release_game_announcement(&self, game_id: &str) -> Result<()> {
+        self.tables.write().await.announcements.remove(game_id);
+        Ok(())
+    }
+
+    async fn create_webhook_subscription(
+        &self,
+        subscription: NewWebhookSubscription,
+    ) -> Result<DbWebhookSubscription> {
+        let subscription = DbWebhookSubscription::from(subscription);
+        self.tables
+            .write()
+            .await
+            .webhook_subscriptions
+            .insert(subscription.id.clone(), subscription.clone());
+        Ok(subscription)
+    }
+
+    async fn get_webhook_subscription(&self, id: &str) -> Result<Option<DbWebhookSubscription>> {
+        Ok(self
+            .tables
+            .read()
+            .await
+            .webhook_subscriptions
+            .get(id)
+            .cloned())
+    }
+
+    async fn get_webhook_subscriptions(&self) -> Result<Vec<DbWebhookSubscription>> {
+        let mut subscriptions: Vec<DbWebhookSubscription> = self
+            .tables
+            .read()
+            .await
+            .webhook_subscriptions
+            .values()
+            .cloned()
+            .collect();
+        subscriptions.sort_by_key(|subscription| subscription.created_at);
+        Ok(subscriptions)
+    }
+
+    async fn delete_webhook_subscription(&self, id: &str) -> Result<bool> {
+        let mut tables = self.tables.write().await;
+        tables
+            .webhook_deliveries
+            .retain(|(subscription_id, _)| subscription_id != id);
+        Ok(tables.webhook_subscriptions.remove(id).is_some())
+    }
+
+    async fn claim_webhook_delivery(&self, subscription_id: &str, game_id: &str) -> Result<bool> {
+        Ok(self
+            .tables
+            .write()
+            .await
+            .webhook_deliveries
+            .insert((subscription_id.to_string(), game_id.to_string())))
+    }
+
+    async fn release_webhook_delivery(&self, subscription_id: &str, game_id: &str) -> Result<()> {
+        self.tables
+            .write()
+            .await
+            .webhook_deliveries
+            .remove(&(subscription_id.to_string(), game_id.to_string()));
+        Ok(())
+    }
+
+    async fn start_discord_link(
+        &self,
+        discord_user_id: &str,
+        code_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let mut tables = self.tables.write().await;
+        let link = tables
+            .discord_links
+            .entry(discord_user_id.to_string())
+            .or_default();
+        link.code_hash = Some(code_hash.to_string());
+        link.code_expires_at = Some(expires_at);
+        Ok(())
+    }
+
+    async fn complete_discord_link(
+        &self,
+        code_hash: &str,
+        user_id: &str,
+    ) -> Result<Option<String>> {
+        let now = Utc::now();
+        let mut tables = self.tables.write().await;
+        let Some((discord_user_id, link)) = tables.discord_links.iter_mut().find(|(_, link)| {
+            link.code_hash.as_deref() == Some(code_hash)
+                && link
+                    .code_expires_at
+                    .is_some_and(|expires_at| expires_at > now)
+        }) else {
+            return Ok(None);
+        };
+        link.user_id = Some(user_id.to_string());
+        link.code_hash = None;
+        link.code_expires_at = None;
+        Ok(Some(discord_user_id.clone()))
+    }
+
+    async fn get_discord_linked_user(&self, discord_user_id: &str) -> Result<Option<String>> {
+        Ok(self
+            .tables
+            .read()
+            .await
+            .discord_links
+            .get(discord_user_id)
+            .and_then(|link| link.user_id.clone()))
+    }
+
+    async fn get_linked_discord_users(&self, user_id: &str) -> Result<Vec<String>> {
+        let tables = self.tables.read().await;
+        let mut discord_user_ids: Vec<String> = tables
+            .discord_links
+            .iter()
+            .filter(|(_, link)| link.user_id.as_deref() == Some(user_id))
+            .map(|(discord_user_id, _)| discord_user_id.clone())
+            .collect();
+        discord_user_ids.sort();
+        Ok(discord_user_ids)
+    }
+
+    async fn record_audit_entry(&self, entry: NewAuditEntry) -> Result<DbAuditEntry> {
+        let entry = DbAuditEntry::from(entry);
+        self.tables.write().await.audit_log.push(entry.clone());
+        Ok(entry)
+    }
+
+    async fn get_audit_entries(&self, filter: &AuditFilter) -> Result<Vec<DbAuditEntry>> {
+        let matches = |wanted: &Option<String>, value: Option<&String>| {
+            wanted.as_ref().is_none_or(|wanted| value == Some(wanted))
+        };
+        Ok(self
+            .tables
+            .read()
+            .await
+            .audit_log
+            .iter()
+            .rev()
+            .filter(|entry| {
+                matches(&filter.actor, Some(&entry.actor))
+                    && matches(&filter.action, Some(&entry.action))
+                    && matches(&filter.target, entry.target.as_ref())
+                    && filter.since.is_none_or(|since| entry.created_at >= since)
+                    && filter.until.is_none_or(|until| entry.created_at < until)
+            })
+            .take(filter.limit.max(0) as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn increment_rate_limit_window(
+        &self,
+        bucket: &str,
+        window_start: DateTime<Utc>,
+    ) -> Result<i32> {
+        let mut tables = self.tables.write().await;
+        let count = tables
+            .rate_limit_windows
+            .entry((bucket.to_string(), window_start))
+            .or_insert(0);
+        *count += 1;
+        Ok(*count)
+    }
+
+    async fn delete_rate_limit_windows_before(&self, before: DateTime<Utc>) -> Result<u64> {
+        let mut tables = self.tables.write().await;
+        let count = tables.rate_limit_windows.len();
+        tables
+            .rate_limit_windows
+            .retain(|(_, window_start), _| *window_start >= before);
+        Ok((count - tables.rate_limit_windows.len()) as u64)
+    }
+
+    async fn create_admin_token(&self, token: NewAdminToken) -> Result<DbAdminToken> {
+        let mut tables = self.tables.write().await;
+        if tables
+            .admin_tokens
+            .values()
+            .any(|existing| existing.token_hash == token.token_hash)
+        {
+            bail!("An admin token already has this hash");
+        }
+
+        let token = DbAdminToken::new(token.token_hash, token.scope, token.label, token.expires_at);
+        tables.admin_tokens.insert(token.id.clone(), token.clone());
+        Ok(token)
+    }
+
+    async fn get_admin_token_by_hash(&self, token_hash: &str) -> Result<Option<DbAdminToken>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .admin_tokens
+            .values()
+            .find(|token| token.token_hash == token_hash)
+            .cloned())
+    }
+
+    async fn get_admin_tokens(&self) -> Result<Vec<DbAdminToken>> {
+        let tables = self.tables.read().await;
+        let mut tokens: Vec<DbAdminToken> = tables
+            .admin_tokens
+            .values()
+            .filter(|token| token.revoked_at.is_none())
+            .cloned()
+            .collect();
+        tokens.sort_by_key(|token| std::cmp::Reverse(token.created_at));
+        Ok(tokens)
+    }
+
+    async fn revoke_admin_token(&self, token_id: &str) -> Result<Option<DbAdminToken>> {
+        let mut tables = self.tables.write().await;
+        Ok(tables
+            .admin_tokens
+            .get_mut(token_id)
+            .filter(|token| token.revoked_at.is_none())
+            .map(|token| {
+                token.revoked_at = Some(Utc::now());
+                token.clone()
+            }))
+    }
+
+    async fn mark_game_completed(&self, game_id: &str) -> Result<()> {
+        if let Some(game) = self.tables.write().await.games.get_mut(game_id) {
+            game.completed = true;
+            game.completed_at = Some(Utc::now());
+        }
+        Ok(())
+    }
+
+    async fn get_incomplete_games_for_date(&self, date: &str) -> Result<Vec<DbGame>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .games
+            .values()
+            .filter(|game| game.date == date && !game.completed)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_incomplete_game_entries_for_game(
+        &self,
+        game_id: &str,
+    ) -> Result<Vec<DbGameEntry>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .entries
+            .values()
+            .map(|stored| &stored.entry)
+            .filter(|entry| entry.game_id == game_id && !entry.completed)
+            .cloned()
+            .collect())
+    }
+
+    async fn mark_game_entry_completed(&self, entry_id: &str) -> Result<()> {
+        let mut tables = self.tables.write().await;
+        if let Some(stored) = tables
+            .entries
+            .values_mut()
+            .find(|stored| stored.entry.id == entry_id)
+        {
+            stored.entry.completed = true;
+            stored.entry.updated_at = Utc::now();
+        }
+        Ok(())
+    }
+
+    async fn get_game_stats(
+        &self,
+        game_id: &str,
+        user_score: i32,
+    ) -> Result<(i32, i32, f64, i32, i32)> {
+        let scores = self.tables.read().await.on_board_scores(game_id);
+        let total_players = scores.len() as i32;
+        let players_at_or_below = scores.iter().filter(|s| **s <= user_score).count() as i32;
+
+        let user_rank = total_players - players_at_or_below + 1;
+        let percentile = if total_players > 0 {
+            (players_at_or_below as f64 / total_players as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        Ok((
+            total_players,
+            user_rank,
+            percentile,
+            average(&scores).unwrap_or(0.0) as i32,
+            scores.iter().copied().max().unwrap_or(0),
+        ))
+    }
+
+    async fn get_player_counts(&self, game_id: &str) -> Result<DbPlayerCounts> {
+        let tables = self.tables.read().await;
+        let mut counts = DbPlayerCounts::default();
+        for entry in tables
+            .entries
+            .values()
+            .map(|stored| &stored.entry)
+            .filter(|entry| entry.game_id == game_id)
+        {
+            if entry.completed {
+                counts.completed += 1;
+            } else {
+                counts.in_progress += 1;
+            }
+        }
+        Ok(counts)
+    }
+
+    async fn record_game_start(&self, user_id: &str, game_id: &str) -> Result<DateTime<Utc>> {
+        let mut tables = self.tables.write().await;
+        Ok(*tables
+            .game_starts
+            .entry((user_id.to_string(), game_id.to_string()))
+            .or_insert_with(Utc::now))
+    }
+
+    async fn get_game_start(&self, user_id: &str, game_id: &str) -> Result<Option<DateTime<Utc>>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .game_starts
+            .get(&(user_id.to_string(), game_id.to_string()))
+            .copied())
+    }
+
+    async fn get_blitz_runs(&self, user_id: &str) -> Result<Vec<DbBlitzRun>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .games_by_date()
+            .into_iter()
+            .filter(|game| game.game_mode() == GameMode::Blitz)
+            .filter_map(|game| {
+                let key = (user_id.to_string(), game.id.clone());
+                let stored = tables.entries.get(&key)?;
+                Some(DbBlitzRun {
+                    game_id: game.id.clone(),
+                    date: game.date.clone(),
+                    answers_data: stored.entry.answers_data.clone(),
+                    total_score: stored.entry.total_score,
+                    started_at: *tables.game_starts.get(&key)?,
+                    completed_at: stored.completed_at,
+                    time_limit_seconds: game.time_limit_seconds?,
+                })
+            })
+            .collect())
+    }
+
+    async fn get_speed_runs(
+        &self,
+        game_id: &str,
+        min_score: i32,
+        min_elapsed_ms: i64,
+        limit: i32,
+    ) -> Result<Vec<DbSpeedRun>> {
+        let tables = self.tables.read().await;
+        let mut runs: Vec<(i64, DateTime<Utc>, DbSpeedRun)> = tables
+            .entries
+            .values()
+            .filter(|stored| {
+                let entry = &stored.entry;
+                entry.game_id == game_id
+                    && entry.completed
+                    && !entry.off_board
+                    && entry.total_score >= min_score
+            })
+            .filter_map(|stored| {
+                let entry = &stored.entry;
+                let completed_at = stored.completed_at?;
+                let started_at = tables
+                    .game_starts
+                    .get(&(entry.user_id.clone(), entry.game_id.clone()))
+                    .filter(|started_at| **started_at <= entry.created_at)?;
+                let elapsed_ms = (completed_at - *started_at).num_milliseconds();
+                (elapsed_ms >= min_elapsed_ms).then(|| {
+                    (
+                        elapsed_ms,
+                        completed_at,
+                        DbSpeedRun {
+                            user_id: entry.user_id.clone(),
+                            total_score: entry.total_score,
+                            elapsed_ms,
+                        },
+                    )
+                })
+            })
+            .collect();
+        runs.sort_by_key(|(elapsed_ms, completed_at, _)| (*elapsed_ms, *completed_at));
+
+        Ok(runs
+            .into_iter()
+            .take(limit.max(0) as usize)
+            .map(|(_, _, run)| run)
+            .collect())
+    }
+
+    async fn get_game_densities(&self, limit: i32) -> Result<Vec<DbGameDensity>> {
+        let tables = self.tables.read().await;
+        let mut games: Vec<&DbGame> = tables
+            .games_by_date()
+            .into_iter()
+            .filter(|game| tables.densities.contains_key(&game.id))
+            .collect();
+        games.reverse();
+
+        Ok(games
+            .into_iter()
+            .take(limit.max(0) as usize)
+            .map(|game| {
+                let entries: Vec<&DbGameEntry> = tables
+                    .entries
+                    .values()
+                    .map(|stored| &stored.entry)
+                    .filter(|entry| entry.game_id == game.id)
+                    .collect();
+                DbGameDensity {
+                    game_id: game.id.clone(),
+                    date: game.date.clone(),
+                    sequence_number: game.sequence_number,
+                    threshold_score: game.threshold_score,
+                    density: tables.densities[&game.id],
+                    quality: tables.qualities.get(&game.id).cloned(),
+                    players_started: entries.len() as i32,
+                    players_completed: entries.iter().filter(|entry| entry.completed).count()
+                        as i32,
+                }
+            })
+            .collect())
+    }
+
+    async fn get_game_boards(&self) -> Result<Vec<DbGameBoard>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .active_daily_games_by_date()
+            .into_iter()
+            .map(|game| DbGameBoard {
+                date: game.date.clone(),
+                generation_version: game.generation_version,
+                board_data: game.board_data.clone(),
+            })
+            .collect())
+    }
+
+    async fn get_user_stats(&self, user_id: &str) -> Result<DbUserStats> {
+        let tables = self.tables.read().await;
+        let completed: Vec<&DbGameEntry> = tables
+            .entries
+            .values()
+            .map(|stored| &stored.entry)
+            .filter(|entry| {
+                entry.user_id == user_id && entry.completed && tables.is_daily_game(&entry.game_id)
+            })
+            .collect();
+
+        let scores: Vec<i32> = completed.iter().map(|entry| entry.total_score).collect();
+        let percentiles: Vec<f64> = completed
+            .iter()
+            .filter(|entry| !entry.off_board)
+            .filter_map(|entry| tables.percentile(&entry.game_id, entry.total_score))
+            .collect();
+        let threshold_hits = completed
+            .iter()
+            .filter(|entry| {
+                tables
+                    .games
+                    .get(&entry.game_id)
+                    .is_some_and(|game| entry.total_score >= game.threshold_score)
+            })
+            .count();
+
+        Ok(DbUserStats {
+            games_played: completed.len() as i32,
+            average_score: average(&scores),
+            best_score: scores.iter().copied().max(),
+            average_percentile: (!percentiles.is_empty())
+                .then(|| percentiles.iter().sum::<f64>() / percentiles.len() as f64),
+            threshold_hits: threshold_hits as i32,
+        })
+    }
+
+    async fn get_completed_answers_data(&self, user_id: &str) -> Result<Vec<String>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .entries
+            .values()
+            .map(|stored| &stored.entry)
+            .filter(|entry| entry.user_id == user_id && entry.completed)
+            .map(|entry| entry.answers_data.clone())
+            .collect())
+    }
+
+    async fn get_user_history(
+        &self,
+        user_id: &str,
+        after_date: Option<&str>,
+        limit: i32,
+    ) -> Result<Vec<DbHistoryEntry>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .daily_games_by_date()
+            .into_iter()
+            .filter(|game| after_date.is_none_or(|after_date| game.date.as_str() > after_date))
+            .filter_map(|game| {
+                let entry = &tables
+                    .entries
+                    .get(&(user_id.to_string(), game.id.clone()))?
+                    .entry;
+                Some(DbHistoryEntry {
+                    game_id: game.id.clone(),
+                    date: game.date.clone(),
+                    sequence_number: game.sequence_number,
+                    answers_data: entry.answers_data.clone(),
+                    total_score: entry.total_score,
+                    completed: entry.completed,
+                    off_board: entry.off_board,
+                    percentile: if entry.completed && !entry.off_board {
+                        tables.percentile(&game.id, entry.total_score)
+                    } else {
+                        None
+                    },
+                })
+            })
+            .take(limit.max(0) as usize)
+            .collect())
+    }
+
+    async fn get_user_entries(&self, user_id: &str) -> Result<Vec<DbUserEntry>> {
+        let tables = self.tables.read().await;
+        let mut games = tables.games_by_date();
+        games.sort_by_key(|game| (game.date.clone(), game.game_mode().as_str()));
+        Ok(games
+            .into_iter()
+            .filter_map(|game| {
+                let entry = &tables
+                    .entries
+                    .get(&(user_id.to_string(), game.id.clone()))?
+                    .entry;
+                Some(DbUserEntry {
+                    game_id: game.id.clone(),
+                    date: game.date.clone(),
+                    mode: game.game_mode().as_str().to_string(),
+                    sequence_number: game.sequence_number,
+                    answers_data: entry.answers_data.clone(),
+                    total_score: entry.total_score,
+                    completed: entry.completed,
+                    off_board: entry.off_board,
+                    created_at: entry.created_at,
+                    updated_at: entry.updated_at,
+                })
+            })
+            .collect())
+    }
+
+    async fn get_user_custom_game_scores(&self, user_id: &str) -> Result<Vec<DbCustomGameScore>> {
+        let tables = self.tables.read().await;
+        let mut scores: Vec<DbCustomGameScore> = tables
+            .custom_game_scores
+            .values()
+            .filter(|score| score.user_id == user_id)
+            .cloned()
+            .collect();
+        scores.sort_by_key(|score| score.created_at);
+        Ok(scores)
+    }
+
+    async fn get_unfrozen_games_through(&self, date: &str) -> Result<Vec<DbGame>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .games_by_date()
+            .into_iter()
+            .filter(|game| game.date.as_str() <= date && !tables.final_stats.contains_key(&game.id))
+            .cloned()
+            .collect())
+    }
+
+    async fn freeze_game_stats(&self, game_id: &str) -> Result<Option<DbFinalGameStats>> {
+        let mut tables = self.tables.write().await;
+        if tables.final_stats.contains_key(game_id) {
+            return Ok(None);
+        }
+
+        // Averages are truncated the same way get_game_stats truncates them
+        let scores = tables.on_board_scores(game_id);
+        let stats = DbFinalGameStats {
+            game_id: game_id.to_string(),
+            total_players: scores.len() as i32,
+            average_score: average(&scores).unwrap_or(0.0) as i32,
+            highest_score: scores.iter().copied().max().unwrap_or(0),
+            frozen_at: Utc::now(),
+        };
+        tables
+            .final_stats
+            .insert(game_id.to_string(), stats.clone());
+        Ok(Some(stats))
+    }
+
+    async fn get_final_game_stats(&self, game_id: &str) -> Result<Option<DbFinalGameStats>> {
+        Ok(self.tables.read().await.final_stats.get(game_id).cloned())
+    }
+
+    async fn get_final_ranks_to_notify(&self, game_id: &str) -> Result<Vec<DbFinalRank>> {
+        let tables = self.tables.read().await;
+        let scores = tables.on_board_scores(game_id);
+        let mut ranks: Vec<DbFinalRank> = tables
+            .entries
+            .values()
+            .map(|stored| &stored.entry)
+            .filter(|entry| {
+                entry.game_id == game_id
+                    && entry.completed
+                    && !entry.off_board
+                    && tables
+                        .notification_preferences
+                        .get(&entry.user_id)
+                        .copied()
+                        .unwrap_or(false)
+            })
+            .map(|entry| DbFinalRank {
+                user_id: entry.user_id.clone(),
+                total_score: entry.total_score,
+                rank: 1 + scores.iter().filter(|s| **s > entry.total_score).count() as i32,
+            })
+            .collect();
+        ranks.sort_by(|a, b| (a.rank, &a.user_id).cmp(&(b.rank, &b.user_id)));
+        Ok(ranks)
+    }
+
+    async fn get_games_without_stats_through(&self, date: &str) -> Result<Vec<DbGame>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .games_by_date()
+            .into_iter()
+            .filter(|game| game.date.as_str() <= date && !tables.game_stats.contains_key(&game.id))
+            .cloned()
+            .collect())
+    }
+
+    async fn upsert_game_stats(&self, stats: DbGameStats) -> Result<()> {
+        let mut tables = self.tables.write().await;
+        if !tables.games.contains_key(&stats.game_id) {
+            return Err(anyhow!("no game {}", stats.game_id));
+        }
+        tables.game_stats.insert(stats.game_id.clone(), stats);
+        Ok(())
+    }
+
+    async fn get_recent_game_stats(&self, limit: i32) -> Result<Vec<DbGameStats>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .active_daily_games_by_date()
+            .into_iter()
+            .rev()
+            .filter_map(|game| tables.game_stats.get(&game.id).cloned())
+            .take(limit.max(0) as usize)
+            .collect())
+    }
+
+    async fn get_notification_preferences(
+        &self,
+        user_id: &str,
+    ) -> Result<DbNotificationPreferences> {
+        let tables = self.tables.read().await;
+        Ok(match tables.notification_preferences.get(user_id) {
+            Some(final_rank) => DbNotificationPreferences {
+                user_id: user_id.to_string(),
+                final_rank: *final_rank,
+            },
+            None => DbNotificationPreferences::none(user_id.to_string()),
+        })
+    }
+
+    async fn set_notification_preferences(
+        &self,
+        preferences: DbNotificationPreferences,
+    ) -> Result<()> {
+        self.tables
+            .write()
+            .await
+            .notification_preferences
+            .insert(preferences.user_id, preferences.final_rank);
+        Ok(())
+    }
+
+    async fn create_notification(&self, notification: NewNotification) -> Result<DbNotification> {
+        let notification = DbNotification::new(notification);
+        self.tables
+            .write()
+            .await
+            .notifications
+            .push(notification.clone());
+        Ok(notification)
+    }
+
+    async fn get_notifications(&self, user_id: &str, limit: i32) -> Result<Vec<DbNotification>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .notifications
+            .iter()
+            .rev()
+            .filter(|notification| notification.user_id == user_id)
+            .take(limit.max(0) as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn mark_notifications_read(&self, user_id: &str) -> Result<u64> {
+        let now = Utc::now();
+        let mut tables = self.tables.write().await;
+        let mut unread = 0;
+        for notification in tables.notifications.iter_mut().filter(|notification| {
+            notification.user_id == user_id && notification.read_at.is_none()
+        }) {
+            notification.read_at = Some(now);
+            unread += 1;
+        }
+        Ok(unread)
+    }
+
+    async fn get_pending_outbox_events(
+        &self,
+        max_attempts: i32,
+        limit: i32,
+    ) -> Result<Vec<DbOutboxEvent>> {
+        let tables = self.tables.read().await;
+        Ok(tables
+            .outbox
+            .iter()
+            .filter(|stored| stored.processed_at.is_none() && stored.event.attempts < max_attempts)
+            .take(limit.max(0) as usize)
+            .map(|stored| stored.event.clone())
+            .collect())
+    }
+
+    async fn mark_outbox_event_processed(&self, event_id: &str) -> Result<()> {
+        let mut tables = self.tables.write().await;
+        if let Some(stored) = tables
+            .outbox
+            .iter_mut()
+            .find(|stored| stored.event.id == event_id)
+        {
+            stored.processed_at = Some(Utc::now());
+        }
+        Ok(())
+    }
+
+    // There is nowhere to inspect errors from, so only the attempt is counted
+    async fn record_outbox_event_failure(&self, event_id: &str, _error: &str) -> Result<()> {
+        let mut tables = self.tables.write().await;
+        if let Some(stored) = tables
+            .outbox
+            .iter_mut()
+            .find(|stored| stored.event.id == event_id)
+        {
+            stored.event.attempts += 1;
+        }
+        Ok(())
+    }
+
+    async fn delete_processed_outbox_events(&self, before: DateTime<Utc>) -> Result<u64> {
+        let mut tables = self.tables.write().await;
+        let count = tables.outbox.len();
+        tables.outbox.retain(|stored| {
+            stored
+                .processed_at
+                .is_none_or(|processed| processed >= before)
+        });
+        Ok((count - tables.outbox.len()) as u64)
+    }
+
+    /// Nothing to close; the tables live as long as the repository
+    async fn close(&self) {}
+}
+
+/// paginate cuts a page out of a whole list, already in order
+fn paginate<T: Clone>(rows: Vec<&T>, page: PageRequest) -> DbPage<T> {
+    DbPage {
+        total: rows.len() as i32,
+        items: rows
+            .into_iter()
+            .skip(page.offset.max(0) as usize)
+            .take(page.limit.max(0) as usize)
+            .cloned()
+            .collect(),
+    }
+}
+
+fn average(scores: &[i32]) -> Option<f64> {
+    (!scores.is_empty())
+        .then(|| scores.iter().map(|s| *s as f64).sum::<f64>() / scores.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_new_test_game;
+
+    fn entry(user_id: &str, game_id: &str, total_score: i32) -> NewGameEntry {
+        NewGameEntry {
+            user_id: user_id.to_string(),
+            game_id: game_id.to_string(),
+            answers_data: "[]".to_string(),
+            total_score,
+            completed: true,
+            suspicion_score: 0,
+            exclude: false,
+            request_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_entries_after_freeze_are_off_board() {
+        let repo = InMemoryRepository::new();
+        let (game, _) = repo
+            .create_game_with_answers(create_new_test_game(), vec![], None)
+            .await
+            .unwrap();
+
+        repo.create_or_update_game_entry(entry("early", &game.id, 30))
+            .await
+            .unwrap();
+        let stats = repo.freeze_game_stats(&game.id).await.unwrap().unwrap();
+        assert_eq!((stats.total_players, stats.highest_score), (1, 30));
+        assert!(repo.freeze_game_stats(&game.id).await.unwrap().is_none());
+
+        let late = repo
+            .create_or_update_game_entry(entry("late", &game.id, 50))
+            .await
+            .unwrap();
+        assert!(late.off_board);
+        assert_eq!(
+            repo.get_score_distribution(&game.id).await.unwrap(),
+            vec![30]
+        );
+        assert_eq!(
+            repo.get_game_stats(&game.id, 30).await.unwrap(),
+            (1, 1, 100.0, 30, 30)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lists_are_filtered_and_paged() {
+        let repo = InMemoryRepository::new();
+        let mut game_ids = Vec::new();
+        for (i, (date, mode)) in [
+            ("2025-06-01", "daily"),
+            ("2025-06-02", "daily"),
+            ("2025-06-02", "blitz"),
+            ("2025-06-03", "daily"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let (game, _) = repo
+                .create_game_with_answers(
+                    NewGame {
+                        date: date.to_string(),
+                        sequence_number: i as i32 + 1,
+                        mode: mode.to_string(),
+                        ..create_new_test_game()
+                    },
+                    vec![],
+                    None,
+                )
+                .await
+                .unwrap();
+            game_ids.push(game.id);
+        }
+
+        let daily = GameFilter {
+            mode: Some(GameMode::Daily),
+            ..GameFilter::default()
+        };
+        let first = repo
+            .list_games(&daily, PageRequest::new(1, 2))
+            .await
+            .unwrap();
+        let dates: Vec<_> = first.items.iter().map(|game| game.date.as_str()).collect();
+        assert_eq!((dates, first.total), (vec!["2025-06-03", "2025-06-02"], 3));
+        let last = repo
+            .list_games(&daily, PageRequest::new(2, 2))
+            .await
+            .unwrap();
+        assert_eq!(last.items.len(), 1);
+        assert_eq!(last.items[0].date, "2025-06-01");
+
+        let june_second = GameFilter {
+            from_date: Some("2025-06-02".to_string()),
+            to_date: Some("2025-06-02".to_string()),
+            ..GameFilter::default()
+        };
+        let page = repo
+            .list_games(&june_second, PageRequest::new(1, 10))
+            .await
+            .unwrap();
+        assert_eq!(page.total, 2);
+
+        for user_id in ["a", "b", "c"] {
+            repo.create_or_update_game_entry(entry(user_id, &game_ids[0], 10))
+                .await
+                .unwrap();
+        }
+        let entries = repo
+            .list_entries_for_game(&game_ids[0], PageRequest::new(2, 2))
+            .await
+            .unwrap();
+        assert_eq!((entries.items.len(), entries.total), (1, 3));
+        let entries = repo
+            .list_entries_for_game(&game_ids[1], PageRequest::new(1, 2))
+            .await
+            .unwrap();
+        assert_eq!((entries.items.len(), entries.total), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_batch_creation_is_all_or_nothing() {
+        let repo = InMemoryRepository::new();
+        let batch = |dates: &[&str]| {
+            dates
+                .iter()
+                .enumerate()
+                .map(|(i, date)| NewGameWithAnswers {
+                    game: NewGame {
+                        date: date.to_string(),
+                        sequence_number: i as i32 + 1,
+                        ..create_new_test_game()
+                    },
+                    answers: vec![],
+                    optimal_solution: None,
+                    density: None,
+                    quality: None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert!(repo
+            .create_games_with_answers(batch(&["2025-06-01", "2025-06-01"]))
+            .await
+            .is_err());
+        assert_eq!(repo.get_next_sequence_number().await.unwrap(), 1);
+
+        repo.create_games_with_answers(batch(&["2025-06-01", "2025-06-02"]))
+            .await
+            .unwrap();
+        assert_eq!(repo.count_archive_games("2025-06-01").await.unwrap(), 1);
+        assert_eq!(repo.get_next_sequence_number().await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_languages_number_their_games_separately() {
+        let repo = InMemoryRepository::new();
+        for language in ["en", "es"] {
+            repo.create_game_with_answers(
+                NewGame {
+                    date: "2025-06-01".to_string(),
+                    sequence_number: 1,
+                    language: language.to_string(),
+                    ..create_new_test_game()
+                },
+                vec![],
+                None,
+            )
+            .await
+            .unwrap();
+        }
+
+        let spanish = repo
+            .get_game_by_date_in_language("2025-06-01", Language::Spanish)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(spanish.game_language(), Language::Spanish);
+        let english = repo.get_game_by_date("2025-06-01").await.unwrap().unwrap();
+        assert_ne!(english.id, spanish.id);
+        // Spanish games stay out of the English archive and numbering
+        assert_eq!(repo.count_archive_games("2025-06-01").await.unwrap(), 1);
+        assert_eq!(repo.get_next_sequence_number().await.unwrap(), 2);
+        assert_eq!(
+            repo.get_next_sequence_number_in_language(Language::German)
+                .await
+                .unwrap(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merge_users_keeps_target_entries() {
+        let repo = InMemoryRepository::new();
+        let into = repo
+            .create_user(NewUser {
+                cookie_token: "into".to_string(),
+            })
+            .await
+            .unwrap();
+        let from = repo
+            .create_user(NewUser {
+                cookie_token: "from".to_string(),
+            })
+            .await
+            .unwrap();
+
+        repo.create_or_update_game_entry(entry(&into.id, "shared", 10))
+            .await
+            .unwrap();
+        repo.create_or_update_game_entry(entry(&from.id, "shared", 20))
+            .await
+            .unwrap();
+        repo.create_or_update_game_entry(entry(&from.id, "other", 30))
+            .await
+            .unwrap();
+
+        assert_eq!(repo.merge_users(&into.id, &from.id).await.unwrap(), 1);
+        let shared = repo.get_game_entry(&into.id, "shared").await.unwrap();
+        assert_eq!(shared.map(|entry| entry.total_score), Some(10));
+        let other = repo.get_game_entry(&into.id, "other").await.unwrap();
+        assert_eq!(other.map(|entry| entry.user_id), Some(into.id));
+        assert!(repo.get_user_by_id(&from.id).await.unwrap().is_none());
+        assert!(repo
+            .get_game_entry(&from.id, "other")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,1083 @@
+use anyhow::Result;
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::models::{
+    AuditFilter, DbAccount, DbAdminToken, DbArchiveGame, DbAuditEntry, DbBlitzRun, DbCustomGame,
+    DbCustomGameScore, DbEntryRevision, DbFinalGameStats, DbFinalRank, DbGame, DbGameAnswer,
+    DbGameBoard, DbGameDensity, DbGameEntry, DbGameStats, DbHintUsage, DbHistoryEntry, DbMagicLink,
+    DbNotification, DbNotificationPreferences, DbOutboxEvent, DbPage, DbPasskey, DbPlayerCounts,
+    DbPracticeGame, DbSpeedRun, DbTournament, DbTournamentResult, DbTournamentStanding, DbUser,
+    DbUserDeletion, DbUserEntry, DbUserSession, DbUserStats, DbWebhookSubscription, DbWordCount,
+    DbWordProposal, GameFilter, NewAdminToken, NewAuditEntry, NewCustomGame, NewCustomGameScore,
+    NewGame, NewGameAnswer, NewGameEntry, NewGameWithAnswers, NewHintUsage, NewMagicLink,
+    NewNotification, NewOptimalSolution, NewOutboxEvent, NewPasskey, NewPracticeGame,
+    NewTournament, NewUser, NewUserSession, NewWebhookSubscription, OptimalAnswer, PageRequest,
+};
+use std::future::Future;
+use std::time::Instant;
+
+use super::Repository;
+use crate::game::language::Language;
+use crate::game::mode::GameMode;
+use crate::metrics::Metrics;
+
+/// MeteredRepository times every call to the repository it wraps, recording
+/// each under the name of the operation
+#[derive(Clone)]
+pub struct MeteredRepository<R> {
+    inner: R,
+    metrics: Metrics,
+}
+
+impl<R> MeteredRepository<R> {
+    pub fn new(inner: R, metrics: Metrics) -> Self {
+        Self { inner, metrics }
+    }
+
+    async fn timed<T>(&self, operation: &'static str, call: impl Future<Output = T>) -> T {
+        let started = Instant::now();
+        let result = call.await;
+        self.metrics.record_query(operation, started.elapsed());
+        result
+    }
+}
+
+#[async_trait]
+impl<R: Repository + Send + Sync> Repository for MeteredRepository<R> {
+    async fn create_user(&self, new_user: NewUser) -> Result<DbUser> {
+        self.timed("create_user", self.inner.create_user(new_user))
+            .await
+    }
+
+    async fn get_user_by_cookie(&self, cookie_token: &str) -> Result<Option<DbUser>> {
+        self.timed(
+            "get_user_by_cookie",
+            self.inner.get_user_by_cookie(cookie_token),
+        )
+        .await
+    }
+
+    async fn get_user_by_id(&self, user_id: &str) -> Result<Option<DbUser>> {
+        self.timed("get_user_by_id", self.inner.get_user_by_id(user_id))
+            .await
+    }
+
+    async fn update_user_last_seen(&self, user_id: &str) -> Result<()> {
+        self.timed(
+            "update_user_last_seen",
+            self.inner.update_user_last_seen(user_id),
+        )
+        .await
+    }
+
+    async fn list_users(&self, page: PageRequest) -> Result<DbPage<DbUser>> {
+        self.timed("list_users", self.inner.list_users(page)).await
+    }
+
+    async fn touch_user_session(&self, session: NewUserSession) -> Result<DbUserSession> {
+        self.timed("touch_user_session", self.inner.touch_user_session(session))
+            .await
+    }
+
+    async fn get_user_sessions(&self, user_id: &str) -> Result<Vec<DbUserSession>> {
+        self.timed("get_user_sessions", self.inner.get_user_sessions(user_id))
+            .await
+    }
+
+    async fn revoke_user_session(
+        &self,
+        user_id: &str,
+        session_id: &str,
+    ) -> Result<Option<DbUserSession>> {
+        self.timed(
+            "revoke_user_session",
+            self.inner.revoke_user_session(user_id, session_id),
+        )
+        .await
+    }
+
+    async fn get_revoked_session_hashes(&self, since: DateTime<Utc>) -> Result<Vec<String>> {
+        self.timed(
+            "get_revoked_session_hashes",
+            self.inner.get_revoked_session_hashes(since),
+        )
+        .await
+    }
+
+    async fn get_or_create_account(&self, user_id: &str) -> Result<DbAccount> {
+        self.timed(
+            "get_or_create_account",
+            self.inner.get_or_create_account(user_id),
+        )
+        .await
+    }
+
+    async fn get_account_by_user(&self, user_id: &str) -> Result<Option<DbAccount>> {
+        self.timed(
+            "get_account_by_user",
+            self.inner.get_account_by_user(user_id),
+        )
+        .await
+    }
+
+    async fn get_account_by_email(&self, email: &str) -> Result<Option<DbAccount>> {
+        self.timed(
+            "get_account_by_email",
+            self.inner.get_account_by_email(email),
+        )
+        .await
+    }
+
+    async fn get_account_by_id(&self, account_id: &str) -> Result<Option<DbAccount>> {
+        self.timed(
+            "get_account_by_id",
+            self.inner.get_account_by_id(account_id),
+        )
+        .await
+    }
+
+    async fn set_account_email(&self, account_id: &str, email: &str) -> Result<()> {
+        self.timed(
+            "set_account_email",
+            self.inner.set_account_email(account_id, email),
+        )
+        .await
+    }
+
+    async fn create_magic_link(&self, link: NewMagicLink) -> Result<DbMagicLink> {
+        self.timed("create_magic_link", self.inner.create_magic_link(link))
+            .await
+    }
+
+    async fn use_magic_link(&self, token_hash: &str) -> Result<Option<DbMagicLink>> {
+        self.timed("use_magic_link", self.inner.use_magic_link(token_hash))
+            .await
+    }
+
+    async fn add_passkey(&self, passkey: NewPasskey) -> Result<DbPasskey> {
+        self.timed("add_passkey", self.inner.add_passkey(passkey))
+            .await
+    }
+
+    async fn get_passkey(&self, credential_id: &str) -> Result<Option<DbPasskey>> {
+        self.timed("get_passkey", self.inner.get_passkey(credential_id))
+            .await
+    }
+
+    async fn get_account_passkeys(&self, account_id: &str) -> Result<Vec<DbPasskey>> {
+        self.timed(
+            "get_account_passkeys",
+            self.inner.get_account_passkeys(account_id),
+        )
+        .await
+    }
+
+    async fn update_passkey_sign_count(&self, credential_id: &str, sign_count: i64) -> Result<()> {
+        self.timed(
+            "update_passkey_sign_count",
+            self.inner
+                .update_passkey_sign_count(credential_id, sign_count),
+        )
+        .await
+    }
+
+    async fn merge_users(&self, into_user_id: &str, from_user_id: &str) -> Result<u64> {
+        self.timed(
+            "merge_users",
+            self.inner.merge_users(into_user_id, from_user_id),
+        )
+        .await
+    }
+
+    async fn delete_user(&self, user_id: &str) -> Result<DbUserDeletion> {
+        self.timed("delete_user", self.inner.delete_user(user_id))
+            .await
+    }
+
+    async fn get_game_by_date(&self, date: &str) -> Result<Option<DbGame>> {
+        self.timed("get_game_by_date", self.inner.get_game_by_date(date))
+            .await
+    }
+
+    async fn get_game_by_date_in_mode(&self, date: &str, mode: GameMode) -> Result<Option<DbGame>> {
+        self.timed(
+            "get_game_by_date_in_mode",
+            self.inner.get_game_by_date_in_mode(date, mode),
+        )
+        .await
+    }
+
+    async fn get_game_by_id(&self, game_id: &str) -> Result<Option<DbGame>> {
+        self.timed("get_game_by_id", self.inner.get_game_by_id(game_id))
+            .await
+    }
+
+    async fn get_game_by_sequence_number(&self, sequence_number: i32) -> Result<Option<DbGame>> {
+        self.timed(
+            "get_game_by_sequence_number",
+            self.inner.get_game_by_sequence_number(sequence_number),
+        )
+        .await
+    }
+
+    async fn game_exists_for_date(&self, date: &str) -> Result<bool> {
+        self.timed(
+            "game_exists_for_date",
+            self.inner.game_exists_for_date(date),
+        )
+        .await
+    }
+
+    async fn get_next_sequence_number(&self) -> Result<i32> {
+        self.timed(
+            "get_next_sequence_number",
+            self.inner.get_next_sequence_number(),
+        )
+        .await
+    }
+
+    async fn get_next_sequence_number_in_mode(&self, mode: GameMode) -> Result<i32> {
+        self.timed(
+            "get_next_sequence_number_in_mode",
+            self.inner.get_next_sequence_number_in_mode(mode),
+        )
+        .await
+    }
+
+    async fn get_game_by_date_in_language(
+        &self,
+        date: &str,
+        language: Language,
+    ) -> Result<Option<DbGame>> {
+        self.timed(
+            "get_game_by_date_in_language",
+            self.inner.get_game_by_date_in_language(date, language),
+        )
+        .await
+    }
+
+    async fn get_game_by_sequence_number_in_language(
+        &self,
+        sequence_number: i32,
+        language: Language,
+    ) -> Result<Option<DbGame>> {
+        self.timed(
+            "get_game_by_sequence_number_in_language",
+            self.inner
+                .get_game_by_sequence_number_in_language(sequence_number, language),
+        )
+        .await
+    }
+
+    async fn get_next_sequence_number_in_language(&self, language: Language) -> Result<i32> {
+        self.timed(
+            "get_next_sequence_number_in_language",
+            self.inner.get_next_sequence_number_in_language(language),
+        )
+        .await
+    }
+
+    async fn get_archive_games(
+        &self,
+        user_id: Option<&str>,
+        latest_date: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<DbArchiveGame>> {
+        self.timed(
+            "get_archive_games",
+            self.inner
+                .get_archive_games(user_id, latest_date, limit, offset),
+        )
+        .await
+    }
+
+    async fn count_archive_games(&self, latest_date: &str) -> Result<i32> {
+        self.timed(
+            "count_archive_games",
+            self.inner.count_archive_games(latest_date),
+        )
+        .await
+    }
+
+    async fn list_games(&self, filter: &GameFilter, page: PageRequest) -> Result<DbPage<DbGame>> {
+        self.timed("list_games", self.inner.list_games(filter, page))
+            .await
+    }
+
+    async fn create_practice_game(&self, new_game: NewPracticeGame) -> Result<DbPracticeGame> {
+        self.timed(
+            "create_practice_game",
+            self.inner.create_practice_game(new_game),
+        )
+        .await
+    }
+
+    async fn get_practice_game(&self, practice_id: &str) -> Result<Option<DbPracticeGame>> {
+        self.timed(
+            "get_practice_game",
+            self.inner.get_practice_game(practice_id),
+        )
+        .await
+    }
+
+    async fn delete_expired_practice_games(&self) -> Result<u64> {
+        self.timed(
+            "delete_expired_practice_games",
+            self.inner.delete_expired_practice_games(),
+        )
+        .await
+    }
+
+    async fn create_custom_game(&self, new_game: NewCustomGame) -> Result<DbCustomGame> {
+        self.timed(
+            "create_custom_game",
+            self.inner.create_custom_game(new_game),
+        )
+        .await
+    }
+
+    async fn get_custom_game(&self, slug: &str) -> Result<Option<DbCustomGame>> {
+        self.timed("get_custom_game", self.inner.get_custom_game(slug))
+            .await
+    }
+
+    async fn save_custom_game_score(&self, score: NewCustomGameScore) -> Result<DbCustomGameScore> {
+        self.timed(
+            "save_custom_game_score",
+            self.inner.save_custom_game_score(score),
+        )
+        .await
+    }
+
+    async fn get_custom_game_scores(
+        &self,
+        custom_game_id: &str,
+        limit: i32,
+    ) -> Result<Vec<DbCustomGameScore>> {
+        self.timed(
+            "get_custom_game_scores",
+            self.inner.get_custom_game_scores(custom_game_id, limit),
+        )
+        .await
+    }
+
+    async fn create_tournament(&self, new_tournament: NewTournament) -> Result<DbTournament> {
+        self.timed(
+            "create_tournament",
+            self.inner.create_tournament(new_tournament),
+        )
+        .await
+    }
+
+    async fn get_tournament(&self, tournament_id: &str) -> Result<Option<DbTournament>> {
+        self.timed("get_tournament", self.inner.get_tournament(tournament_id))
+            .await
+    }
+
+    async fn list_tournaments(&self, limit: i32) -> Result<Vec<DbTournament>> {
+        self.timed("list_tournaments", self.inner.list_tournaments(limit))
+            .await
+    }
+
+    async fn join_tournament(&self, tournament_id: &str, user_id: &str) -> Result<bool> {
+        self.timed(
+            "join_tournament",
+            self.inner.join_tournament(tournament_id, user_id),
+        )
+        .await
+    }
+
+    async fn get_tournament_standings(
+        &self,
+        tournament_id: &str,
+    ) -> Result<Vec<DbTournamentStanding>> {
+        self.timed(
+            "get_tournament_standings",
+            self.inner.get_tournament_standings(tournament_id),
+        )
+        .await
+    }
+
+    async fn get_unfinalized_tournaments_through(&self, date: &str) -> Result<Vec<DbTournament>> {
+        self.timed(
+            "get_unfinalized_tournaments_through",
+            self.inner.get_unfinalized_tournaments_through(date),
+        )
+        .await
+    }
+
+    async fn finalize_tournament(
+        &self,
+        tournament_id: &str,
+    ) -> Result<Option<Vec<DbTournamentResult>>> {
+        self.timed(
+            "finalize_tournament",
+            self.inner.finalize_tournament(tournament_id),
+        )
+        .await
+    }
+
+    async fn get_tournament_results(&self, tournament_id: &str) -> Result<Vec<DbTournamentResult>> {
+        self.timed(
+            "get_tournament_results",
+            self.inner.get_tournament_results(tournament_id),
+        )
+        .await
+    }
+
+    async fn create_or_update_game_entry(&self, new_entry: NewGameEntry) -> Result<DbGameEntry> {
+        self.timed(
+            "create_or_update_game_entry",
+            self.inner.create_or_update_game_entry(new_entry),
+        )
+        .await
+    }
+
+    async fn create_or_update_game_entry_with_events(
+        &self,
+        new_entry: NewGameEntry,
+        events: Vec<NewOutboxEvent>,
+    ) -> Result<DbGameEntry> {
+        self.timed(
+            "create_or_update_game_entry_with_events",
+            self.inner
+                .create_or_update_game_entry_with_events(new_entry, events),
+        )
+        .await
+    }
+
+    async fn get_game_entry(&self, user_id: &str, game_id: &str) -> Result<Option<DbGameEntry>> {
+        self.timed(
+            "get_game_entry",
+            self.inner.get_game_entry(user_id, game_id),
+        )
+        .await
+    }
+
+    async fn list_entries_for_game(
+        &self,
+        game_id: &str,
+        page: PageRequest,
+    ) -> Result<DbPage<DbGameEntry>> {
+        self.timed(
+            "list_entries_for_game",
+            self.inner.list_entries_for_game(game_id, page),
+        )
+        .await
+    }
+
+    async fn get_hint_usage(&self, user_id: &str, game_id: &str) -> Result<Vec<DbHintUsage>> {
+        self.timed(
+            "get_hint_usage",
+            self.inner.get_hint_usage(user_id, game_id),
+        )
+        .await
+    }
+
+    async fn record_hint_usage(&self, usage: NewHintUsage) -> Result<DbHintUsage> {
+        self.timed("record_hint_usage", self.inner.record_hint_usage(usage))
+            .await
+    }
+
+    async fn record_word_challenge(&self, user_id: &str, game_id: &str, word: &str) -> Result<()> {
+        self.timed(
+            "record_word_challenge",
+            self.inner.record_word_challenge(user_id, game_id, word),
+        )
+        .await
+    }
+
+    async fn get_challenged_words(&self, user_id: &str, game_id: &str) -> Result<Vec<String>> {
+        self.timed(
+            "get_challenged_words",
+            self.inner.get_challenged_words(user_id, game_id),
+        )
+        .await
+    }
+
+    async fn get_wordlist_proposals(&self) -> Result<Vec<DbWordProposal>> {
+        self.timed(
+            "get_wordlist_proposals",
+            self.inner.get_wordlist_proposals(),
+        )
+        .await
+    }
+
+    async fn save_entry_revision(
+        &self,
+        user_id: &str,
+        game_id: &str,
+        answers_data: &str,
+        keep: usize,
+    ) -> Result<DbEntryRevision> {
+        self.timed(
+            "save_entry_revision",
+            self.inner
+                .save_entry_revision(user_id, game_id, answers_data, keep),
+        )
+        .await
+    }
+
+    async fn get_entry_revisions(
+        &self,
+        user_id: &str,
+        game_id: &str,
+    ) -> Result<Vec<DbEntryRevision>> {
+        self.timed(
+            "get_entry_revisions",
+            self.inner.get_entry_revisions(user_id, game_id),
+        )
+        .await
+    }
+
+    async fn create_game_with_answers(
+        &self,
+        new_game: NewGame,
+        game_answers: Vec<NewGameAnswer>,
+        optimal_solution: Option<NewOptimalSolution>,
+    ) -> Result<(DbGame, Vec<DbGameAnswer>)> {
+        self.timed(
+            "create_game_with_answers",
+            self.inner
+                .create_game_with_answers(new_game, game_answers, optimal_solution),
+        )
+        .await
+    }
+
+    async fn create_games_with_answers(
+        &self,
+        games: Vec<NewGameWithAnswers>,
+    ) -> Result<Vec<DbGame>> {
+        self.timed(
+            "create_games_with_answers",
+            self.inner.create_games_with_answers(games),
+        )
+        .await
+    }
+
+    async fn replace_game(
+        &self,
+        game_id: &str,
+        replacement: NewGameWithAnswers,
+    ) -> Result<Option<DbGame>> {
+        self.timed(
+            "replace_game",
+            self.inner.replace_game(game_id, replacement),
+        )
+        .await
+    }
+
+    async fn get_game_words(&self, game_id: &str) -> Result<Vec<String>> {
+        self.timed("get_game_words", self.inner.get_game_words(game_id))
+            .await
+    }
+
+    async fn get_game_answers(&self, game_id: &str) -> Result<Vec<DbGameAnswer>> {
+        self.timed("get_game_answers", self.inner.get_game_answers(game_id))
+            .await
+    }
+
+    async fn get_uncompacted_game_ids(&self, before_date: &str, limit: i32) -> Result<Vec<String>> {
+        self.timed(
+            "get_uncompacted_game_ids",
+            self.inner.get_uncompacted_game_ids(before_date, limit),
+        )
+        .await
+    }
+
+    async fn store_compacted_answers(&self, game_id: &str, answers_blob: Vec<u8>) -> Result<u64> {
+        self.timed(
+            "store_compacted_answers",
+            self.inner.store_compacted_answers(game_id, answers_blob),
+        )
+        .await
+    }
+
+    async fn get_score_distribution(&self, game_id: &str) -> Result<Vec<i32>> {
+        self.timed(
+            "get_score_distribution",
+            self.inner.get_score_distribution(game_id),
+        )
+        .await
+    }
+
+    async fn get_word_counts(&self, game_id: &str) -> Result<Vec<DbWordCount>> {
+        self.timed("get_word_counts", self.inner.get_word_counts(game_id))
+            .await
+    }
+
+    async fn get_optimal_solutions(&self, game_id: &str) -> Result<Vec<OptimalAnswer>> {
+        self.timed(
+            "get_optimal_solutions",
+            self.inner.get_optimal_solutions(game_id),
+        )
+        .await
+    }
+
+    async fn claim_game_announcement(&self, game_id: &str) -> Result<bool> {
+        self.timed(
+            "claim_game_announcement",
+            self.inner.claim_game_announcement(game_id),
+        )
+        .await
+    }
+
+    async fn release_game_announcement(&self, game_id: &str) -> Result<()> {
+        self.timed(
+            "release_game_announcement",
+            self.inner.release_game_announcement(game_id),
+        )
+        .await
+    }
+
+    async fn create_webhook_subscription(
+        &self,
+        subscription: NewWebhookSubscription,
+    ) -> Result<DbWebhookSubscription> {
+        self.timed(
+            "create_webhook_subscription",
+            self.inner.create_webhook_subscription(subscription),
+        )
+        .await
+    }
+
+    async fn get_webhook_subscription(&self, id: &str) -> Result<Option<DbWebhookSubscription>> {
+        self.timed(
+            "get_webhook_subscription",
+            self.inner.get_webhook_subscription(id),
+        )
+        .await
+    }
+
+    async fn get_webhook_subscriptions(&self) -> Result<Vec<DbWebhookSubscription>> {
+        self.timed(
+            "get_webhook_subscriptions",
+            self.inner.get_webhook_subscriptions(),
+        )
+        .await
+    }
+
+    async fn delete_webhook_subscription(&self, id: &str) -> Result<bool> {
+        self.timed(
+            "delete_webhook_subscription",
+            self.inner.delete_webhook_subscription(id),
+        )
+        .await
+    }
+
+    async fn claim_webhook_delivery(&self, subscription_id: &str, game_id: &str) -> Result<bool> {
+        self.timed(
+            "claim_webhook_delivery",
+            self.inner.claim_webhook_delivery(subscription_id, game_id),
+        )
+        .await
+    }
+
+    async fn release_webhook_delivery(&self, subscription_id: &str, game_id: &str) -> Result<()> {
+        self.timed(
+            "release_webhook_delivery",
+            self.inner
+                .release_webhook_delivery(subscription_id, game_id),
+        )
+        .await
+    }
+
+    async fn start_discord_link(
+        &self,
+        discord_user_id: &str,
+        code_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        self.timed(
+            "start_discord_link",
+            self.inner
+                .start_discord_link(discord_user_id, code_hash, expires_at),
+        )
+        .await
+    }
+
+    async fn complete_discord_link(
+        &self,
+        code_hash: &str,
+        user_id: &str,
+    ) -> Result<Option<String>> {
+        self.timed(
+            "complete_discord_link",
+            self.inner.complete_discord_link(code_hash, user_id),
+        )
+        .await
+    }
+
+    async fn get_discord_linked_user(&self, discord_user_id: &str) -> Result<Option<String>> {
+        self.timed(
+            "get_discord_linked_user",
+            self.inner.get_discord_linked_user(discord_user_id),
+        )
+        .await
+    }
+
+    async fn get_linked_discord_users(&self, user_id: &str) -> Result<Vec<String>> {
+        self.timed(
+            "get_linked_discord_users",
+            self.inner.get_linked_discord_users(user_id),
+        )
+        .await
+    }
+
+    async fn record_audit_entry(&self, entry: NewAuditEntry) -> Result<DbAuditEntry> {
+        self.timed("record_audit_entry", self.inner.record_audit_entry(entry))
+            .await
+    }
+
+    async fn get_audit_entries(&self, filter: &AuditFilter) -> Result<Vec<DbAuditEntry>> {
+        self.timed("get_audit_entries", self.inner.get_audit_entries(filter))
+            .await
+    }
+
+    async fn increment_rate_limit_window(
+        &self,
+        bucket: &str,
+        window_start: DateTime<Utc>,
+    ) -> Result<i32> {
+        self.timed(
+            "increment_rate_limit_window",
+            self.inner.increment_rate_limit_window(bucket, window_start),
+        )
+        .await
+    }
+
+    async fn delete_rate_limit_windows_before(&self, before: DateTime<Utc>) -> Result<u64> {
+        self.timed(
+            "delete_rate_limit_windows_before",
+            self.inner.delete_rate_limit_windows_before(before),
+        )
+        .await
+    }
+
+    async fn create_admin_token(&self, token: NewAdminToken) -> Result<DbAdminToken> {
+        self.timed("create_admin_token", self.inner.create_admin_token(token))
+            .await
+    }
+
+    async fn get_admin_token_by_hash(&self, token_hash: &str) -> Result<Option<DbAdminToken>> {
+        self.timed(
+            "get_admin_token_by_hash",
+            self.inner.get_admin_token_by_hash(token_hash),
+        )
+        .await
+    }
+
+    async fn get_admin_tokens(&self) -> Result<Vec<DbAdminToken>> {
+        self.timed("get_admin_tokens", self.inner.get_admin_tokens())
+            .await
+    }
+
+    async fn revoke_admin_token(&self, token_id: &str) -> Result<Option<DbAdminToken>> {
+        self.timed(
+            "revoke_admin_token",
+            self.inner.revoke_admin_token(token_id),
+        )
+        .await
+    }
+
+    async fn mark_game_completed(&self, game_id: &str) -> Result<()> {
+        self.timed(
+            "mark_game_completed",
+            self.inner.mark_game_completed(game_id),
+        )
+        .await
+    }
+
+    async fn get_incomplete_games_for_date(&self, date: &str) -> Result<Vec<DbGame>> {
+        self.timed(
+            "get_incomplete_games_for_date",
+            self.inner.get_incomplete_games_for_date(date),
+        )
+        .await
+    }
+
+    async fn get_incomplete_game_entries_for_game(
+        &self,
+        game_id: &str,
+    ) -> Result<Vec<DbGameEntry>> {
+        self.timed(
+            "get_incomplete_game_entries_for_game",
+            self.inner.get_incomplete_game_entries_for_game(game_id),
+        )
+        .await
+    }
+
+    async fn mark_game_entry_completed(&self, entry_id: &str) -> Result<()> {
+        self.timed(
+            "mark_game_entry_completed",
+            self.inner.mark_game_entry_completed(entry_id),
+        )
+        .await
+    }
+
+    async fn get_game_stats(
+        &self,
+        game_id: &str,
+        user_score: i32,
+    ) -> Result<(i32, i32, f64, i32, i32)> {
+        self.timed(
+            "get_game_stats",
+            self.inner.get_game_stats(game_id, user_score),
+        )
+        .await
+    }
+
+    async fn get_player_counts(&self, game_id: &str) -> Result<DbPlayerCounts> {
+        self.timed("get_player_counts", self.inner.get_player_counts(game_id))
+            .await
+    }
+
+    async fn record_game_start(&self, user_id: &str, game_id: &str) -> Result<DateTime<Utc>> {
+        self.timed(
+            "record_game_start",
+            self.inner.record_game_start(user_id, game_id),
+        )
+        .await
+    }
+
+    async fn get_game_start(&self, user_id: &str, game_id: &str) -> Result<Option<DateTime<Utc>>> {
+        self.timed(
+            "get_game_start",
+            self.inner.get_game_start(user_id, game_id),
+        )
+        .await
+    }
+
+    async fn get_blitz_runs(&self, user_id: &str) -> Result<Vec<DbBlitzRun>> {
+        self.timed("get_blitz_runs", self.inner.get_blitz_runs(user_id))
+            .await
+    }
+
+    async fn get_speed_runs(
+        &self,
+        game_id: &str,
+        min_score: i32,
+        min_elapsed_ms: i64,
+        limit: i32,
+    ) -> Result<Vec<DbSpeedRun>> {
+        self.timed(
+            "get_speed_runs",
+            self.inner
+                .get_speed_runs(game_id, min_score, min_elapsed_ms, limit),
+        )
+        .await
+    }
+
+    async fn get_game_densities(&self, limit: i32) -> Result<Vec<DbGameDensity>> {
+        self.timed("get_game_densities", self.inner.get_game_densities(limit))
+            .await
+    }
+
+    async fn get_game_boards(&self) -> Result<Vec<DbGameBoard>> {
+        self.timed("get_game_boards", self.inner.get_game_boards())
+            .await
+    }
+
+    async fn get_user_stats(&self, user_id: &str) -> Result<DbUserStats> {
+        self.timed("get_user_stats", self.inner.get_user_stats(user_id))
+            .await
+    }
+
+    async fn get_completed_answers_data(&self, user_id: &str) -> Result<Vec<String>> {
+        self.timed(
+            "get_completed_answers_data",
+            self.inner.get_completed_answers_data(user_id),
+        )
+        .await
+    }
+
+    async fn get_user_history(
+        &self,
+        user_id: &str,
+        after_date: Option<&str>,
+        limit: i32,
+    ) -> Result<Vec<DbHistoryEntry>> {
+        self.timed(
+            "get_user_history",
+            self.inner.get_user_history(user_id, after_date, limit),
+        )
+        .await
+    }
+
+    async fn get_user_entries(&self, user_id: &str) -> Result<Vec<DbUserEntry>> {
+        self.timed("get_user_entries", self.inner.get_user_entries(user_id))
+            .await
+    }
+
+    async fn get_user_custom_game_scores(&self, user_id: &str) -> Result<Vec<DbCustomGameScore>> {
+        self.timed(
+            "get_user_custom_game_scores",
+            self.inner.get_user_custom_game_scores(user_id),
+        )
+        .await
+    }
+
+    async fn get_unfrozen_games_through(&self, date: &str) -> Result<Vec<DbGame>> {
+        self.timed(
+            "get_unfrozen_games_through",
+            self.inner.get_unfrozen_games_through(date),
+        )
+        .await
+    }
+
+    async fn freeze_game_stats(&self, game_id: &str) -> Result<Option<DbFinalGameStats>> {
+        self.timed("freeze_game_stats", self.inner.freeze_game_stats(game_id))
+            .await
+    }
+
+    async fn get_final_game_stats(&self, game_id: &str) -> Result<Option<DbFinalGameStats>> {
+        self.timed(
+            "get_final_game_stats",
+            self.inner.get_final_game_stats(game_id),
+        )
+        .await
+    }
+
+    async fn get_final_ranks_to_notify(&self, game_id: &str) -> Result<Vec<DbFinalRank>> {
+        self.timed(
+            "get_final_ranks_to_notify",
+            self.inner.get_final_ranks_to_notify(game_id),
+        )
+        .await
+    }
+
+    async fn get_games_without_stats_through(&self, date: &str) -> Result<Vec<DbGame>> {
+        self.timed(
+            "get_games_without_stats_through",
+            self.inner.get_games_without_stats_through(date),
+        )
+        .await
+    }
+
+    async fn upsert_game_stats(&self, stats: DbGameStats) -> Result<()> {
+        self.timed("upsert_game_stats", self.inner.upsert_game_stats(stats))
+            .await
+    }
+
+    async fn get_recent_game_stats(&self, limit: i32) -> Result<Vec<DbGameStats>> {
+        self.timed(
+            "get_recent_game_stats",
+            self.inner.get_recent_game_stats(limit),
+        )
+        .await
+    }
+
+    async fn get_notification_preferences(
+        &self,
+        user_id: &str,
+    ) -> Result<DbNotificationPreferences> {
+        self.timed(
+            "get_notification_preferences",
+            self.inner.get_notification_preferences(user_id),
+        )
+        .await
+    }
+
+    async fn set_notification_preferences(
+        &self,
+        preferences: DbNotificationPreferences,
+    ) -> Result<()> {
+        self.timed(
+            "set_notification_preferences",
+            self.inner.set_notification_preferences(preferences),
+        )
+        .await
+    }
+
+    async fn create_notification(&self, notification: NewNotification) -> Result<DbNotification> {
+        self.timed(
+            "create_notification",
+            self.inner.create_notification(notification),
+        )
+        .await
+    }
+
+    async fn get_notifications(&self, user_id: &str, limit: i32) -> Result<Vec<DbNotification>> {
+        self.timed(
+            "get_notifications",
+            self.inner.get_notifications(user_id, limit),
+        )
+        .await
+    }
+
+    async fn mark_notifications_read(&self, user_id: &str) -> Result<u64> {
+        self.timed(
+            "mark_notifications_read",
+            self.inner.mark_notifications_read(user_id),
+        )
+        .await
+    }
+
+    async fn get_pending_outbox_events(
+        &self,
+        max_attempts: i32,
+        limit: i32,
+    ) -> Result<Vec<DbOutboxEvent>> {
+        self.timed(
+            "get_pending_outbox_events",
+            self.inner.get_pending_outbox_events(max_attempts, limit),
+        )
+        .await
+    }
+
+    async fn mark_outbox_event_processed(&self, event_id: &str) -> Result<()> {
+        self.timed(
+            "mark_outbox_event_processed",
+            self.inner.mark_outbox_event_processed(event_id),
+        )
+        .await
+    }
+
+    async fn record_outbox_event_failure(&self, event_id: &str, error: &str) -> Result<()> {
+        self.timed(
+            "record_outbox_event_failure",
+            self.inner.record_outbox_event_failure(event_id, error),
+        )
+        .await
+    }
+
+    async fn delete_processed_outbox_events(&self, before: DateTime<Utc>) -> Result<u64> {
+        self.timed(
+            "delete_processed_outbox_events",
+            self.inner.delete_processed_outbox_events(before),
+        )
+        .await
+    }
+
+    async fn close(&self) {
+        self.inner.close().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::InMemoryRepository;
+
+    #[tokio::test]
+    async fn test_calls_are_timed_by_operation() {
+        let metrics = Metrics::default();
+        let repository = MeteredRepository::new(InMemoryRepository::new(), metrics.clone());
+
+        assert!(repository
+            .get_game_by_date("2025-06-01")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(!repository.game_exists_for_date("2025-06-01").await.unwrap());
+
+        let rendered = metrics.render();
+        for operation in ["get_game_by_date", "game_exists_for_date"] {
+            assert!(rendered.contains(&format!(
+                "pathfinder_db_query_duration_seconds_count{{operation=\"{operation}\"}} 1\n"
+            )));
+        }
+    }
+}
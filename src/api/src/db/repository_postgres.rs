@@ -0,0 +1,3058 @@
+use anyhow::Result;
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{postgres::PgRow, PgPool, Row};
+use tracing::warn;
+
+use crate::db::{
+    conversions::AnswerStorage,
+    storage_types::{DbCompactedAnswers, DbStoredAnswers},
+    Repository,
+};
+use crate::game::density::BoardDensity;
+use crate::game::language::Language;
+use crate::game::mode::GameMode;
+use crate::game::quality::{join_issues, split_issues, BoardQuality};
+
+use super::models::{
+    AuditFilter, DbAccount, DbAdminToken, DbArchiveGame, DbAuditEntry, DbBlitzRun, DbCustomGame,
+    DbCustomGameScore, DbEntryRevision, DbFinalGameStats, DbFinalRank, DbGame, DbGameAnswer,
+    DbGameBoard, DbGameDensity, DbGameEntry, DbGameStats, DbHintUsage, DbHistoryEntry, DbMagicLink,
+    DbNotification, DbNotificationPreferences, DbOptimalSolution, DbOutboxEvent, DbPage, DbPasskey,
+    DbPlayerCounts, DbPracticeGame, DbSpeedRun, DbTournament, DbTournamentResult,
+    DbTournamentStanding, DbUser, DbUserDeletion, DbUserEntry, DbUserSession, DbUserStats,
+    DbWebhookSubscription, DbWordCount, DbWordProposal, GameFilter, NewAdminToken, NewAuditEntry,
+    NewCustomGame, NewCustomGameScore, NewGame, NewGameAnswer, NewGameEntry, NewGameWithAnswers,
+    NewHintUsage, NewMagicLink, NewNotification, NewOptimalSolution, NewOutboxEvent, NewPasskey,
+    NewPracticeGame, NewTournament, NewUser, NewUserSession, NewWebhookSubscription, OptimalAnswer,
+    PageRequest, WordPathStats,
+};
+
+const GAME_COLUMNS: &str =
+    "id, date, board_data, threshold_score, sequence_number, generation_version, difficulty, theme, scoring_scheme_version, bonus_rules, game_rules, mode, time_limit_seconds, language, revision, deleted_at, completed, completed_at, created_at";
+const PRACTICE_GAME_COLUMNS: &str =
+    "id, seed, board_data, threshold_score, optimal_score, created_at, expires_at";
+const USER_SESSION_COLUMNS: &str =
+    "id, user_id, session_hash, user_agent_hash, first_seen, last_seen, revoked_at";
+const WEBHOOK_SUBSCRIPTION_COLUMNS: &str = "id, kind, url, secret, created_at";
+const AUDIT_ENTRY_COLUMNS: &str = "id, actor, action, target, before_data, after_data, created_at";
+const CUSTOM_GAME_COLUMNS: &str =
+    "id, slug, board_code, board_data, optimal_score, created_by, created_at";
+const CUSTOM_GAME_SCORE_COLUMNS: &str =
+    "id, custom_game_id, user_id, answers_data, total_score, created_at, updated_at";
+const TOURNAMENT_COLUMNS: &str =
+    "id, name, start_date, end_date, language, created_at, finalized_at";
+const TOURNAMENT_RESULT_COLUMNS: &str = "tournament_id, user_id, rank, total_score, games_played";
+
+/// Each participant's standing in tournament $1: their best completed,
+/// on-board score on each of its dates, added up
+const TOURNAMENT_STANDINGS_QUERY: &str =
+    "SELECT p.user_id, COALESCE(SUM(d.best_score), 0)::INTEGER AS total_score,
+        COUNT(d.date)::INTEGER AS games_played
+     FROM tournament_participants p
+     LEFT JOIN (
+         SELECT e.user_id, g.date, MAX(e.total_score) AS best_score
+         FROM tournaments t
+         JOIN games g ON g.language = t.language AND g.mode = 'daily'
+             AND g.date BETWEEN t.start_date AND t.end_date
+         JOIN game_entries e ON e.game_id = g.id
+         WHERE t.id = $1 AND e.completed = TRUE AND e.off_board = FALSE
+         GROUP BY e.user_id, g.date
+     ) d ON d.user_id = p.user_id
+     WHERE p.tournament_id = $1
+     GROUP BY p.user_id, p.joined_at
+     ORDER BY total_score DESC, p.joined_at ASC";
+const ACCOUNT_COLUMNS: &str = "id, user_id, email, created_at";
+const MAGIC_LINK_COLUMNS: &str = "token_hash, email, user_id, expires_at, used_at, created_at";
+const PASSKEY_COLUMNS: &str =
+    "credential_id, account_id, public_key, sign_count, created_at, last_used_at";
+const ADMIN_TOKEN_COLUMNS: &str =
+    "id, token_hash, scope, label, created_at, expires_at, revoked_at";
+const GAME_ENTRY_COLUMNS: &str =
+    "id, user_id, game_id, answers_data, total_score, completed, off_board, suspicion_score, request_id, created_at, updated_at";
+const FINAL_GAME_STATS_COLUMNS: &str =
+    "game_id, total_players, average_score, highest_score, frozen_at";
+const GAME_STATS_COLUMNS: &str = "game_id, players, completed_players, median_score, optimal_score, completion_rate, threshold_hit_rate, computed_at";
+const NOTIFICATION_COLUMNS: &str = "id, user_id, kind, game_id, message, created_at, read_at";
+
+/// PgRepository is the Postgres implementation of Repository. Unlike the
+/// sqlite schema, timestamps are stored as TIMESTAMPTZ and flags as BOOLEAN,
+/// so values are bound and read natively rather than as strings/integers.
+#[derive(Clone)]
+pub struct PgRepository {
+    pool: PgPool,
+}
+
+impl PgRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Read a game's compacted answers, if it has been compacted
+    async fn get_compacted_answers(&self, game_id: &str) -> Result<Option<Vec<DbGameAnswer>>> {
+        let blob: Option<Vec<u8>> =
+            sqlx::query_scalar("SELECT answers_blob FROM games WHERE id = $1")
+                .bind(game_id)
+                .fetch_optional(&self.pool)
+                .await?
+                .flatten();
+
+        blob.map(|blob| {
+            DbCompactedAnswers::decompress(&blob)
+                .map(|compacted| compacted.into_game_answers(game_id))
+                .map_err(|e| anyhow::anyhow!("Failed to read compacted answers: {e}"))
+        })
+        .transpose()
+    }
+}
+
+fn user_from_row(row: &PgRow) -> DbUser {
+    DbUser {
+        id: row.get("id"),
+        cookie_token: row.get("cookie_token"),
+        created_at: row.get("created_at"),
+        last_seen: row.get("last_seen"),
+    }
+}
+
+fn user_session_from_row(row: &PgRow) -> DbUserSession {
+    DbUserSession {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        session_hash: row.get("session_hash"),
+        user_agent_hash: row.get("user_agent_hash"),
+        first_seen: row.get("first_seen"),
+        last_seen: row.get("last_seen"),
+        revoked_at: row.get("revoked_at"),
+    }
+}
+
+fn account_from_row(row: &PgRow) -> DbAccount {
+    DbAccount {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        email: row.get("email"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn magic_link_from_row(row: &PgRow) -> DbMagicLink {
+    DbMagicLink {
+        token_hash: row.get("token_hash"),
+        email: row.get("email"),
+        user_id: row.get("user_id"),
+        expires_at: row.get("expires_at"),
+        used_at: row.get("used_at"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn passkey_from_row(row: &PgRow) -> DbPasskey {
+    DbPasskey {
+        credential_id: row.get("credential_id"),
+        account_id: row.get("account_id"),
+        public_key: row.get("public_key"),
+        sign_count: row.get("sign_count"),
+        created_at: row.get("created_at"),
+        last_used_at: row.get("last_used_at"),
+    }
+}
+
+fn admin_token_from_row(row: &PgRow) -> DbAdminToken {
+    DbAdminToken {
+        id: row.get("id"),
+        token_hash: row.get("token_hash"),
+        scope: row.get("scope"),
+        label: row.get("label"),
+        created_at: row.get("created_at"),
+        expires_at: row.get("expires_at"),
+        revoked_at: row.get("revoked_at"),
+    }
+}
+
+fn webhook_subscription_from_row(row: &PgRow) -> DbWebhookSubscription {
+    DbWebhookSubscription {
+        id: row.get("id"),
+        kind: row.get("kind"),
+        url: row.get("url"),
+        secret: row.get("secret"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn audit_entry_from_row(row: &PgRow) -> DbAuditEntry {
+    DbAuditEntry {
+        id: row.get("id"),
+        actor: row.get("actor"),
+        action: row.get("action"),
+        target: row.get("target"),
+        before_data: row.get("before_data"),
+        after_data: row.get("after_data"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn custom_game_from_row(row: &PgRow) -> DbCustomGame {
+    DbCustomGame {
+        id: row.get("id"),
+        slug: row.get("slug"),
+        board_code: row.get("board_code"),
+        board_data: row.get("board_data"),
+        optimal_score: row.get("optimal_score"),
+        created_by: row.get("created_by"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn custom_game_score_from_row(row: &PgRow) -> DbCustomGameScore {
+    DbCustomGameScore {
+        id: row.get("id"),
+        custom_game_id: row.get("custom_game_id"),
+        user_id: row.get("user_id"),
+        answers_data: row.get("answers_data"),
+        total_score: row.get("total_score"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn tournament_from_row(row: &PgRow) -> DbTournament {
+    DbTournament {
+        id: row.get("id"),
+        name: row.get("name"),
+        start_date: row.get("start_date"),
+        end_date: row.get("end_date"),
+        language: row.get("language"),
+        created_at: row.get("created_at"),
+        finalized_at: row.get("finalized_at"),
+    }
+}
+
+fn tournament_standing_from_row(row: &PgRow) -> DbTournamentStanding {
+    DbTournamentStanding {
+        user_id: row.get("user_id"),
+        total_score: row.get("total_score"),
+        games_played: row.get("games_played"),
+    }
+}
+
+fn tournament_result_from_row(row: &PgRow) -> DbTournamentResult {
+    DbTournamentResult {
+        tournament_id: row.get("tournament_id"),
+        user_id: row.get("user_id"),
+        rank: row.get("rank"),
+        total_score: row.get("total_score"),
+        games_played: row.get("games_played"),
+    }
+}
+
+fn practice_game_from_row(row: &PgRow) -> Result<DbPracticeGame> {
+    Ok(DbPracticeGame {
+        id: row.get("id"),
+        seed: row.get::<String, _>("seed").parse()?,
+        board_data: row.get("board_data"),
+        threshold_score: row.get("threshold_score"),
+        optimal_score: row.get("optimal_score"),
+        created_at: row.get("created_at"),
+        expires_at: row.get("expires_at"),
+    })
+}
+
+fn archive_game_from_row(row: &PgRow) -> DbArchiveGame {
+    DbArchiveGame {
+        game_id: row.get("id"),
+        date: row.get("date"),
+        sequence_number: row.get("sequence_number"),
+        threshold_score: row.get("threshold_score"),
+        user_completed: row
+            .get::<Option<bool>, _>("user_completed")
+            .unwrap_or_default(),
+        user_score: row.get("user_score"),
+        total_players: row.get("total_players"),
+        average_score: row.get("average_score"),
+    }
+}
+
+fn game_from_row(row: &PgRow) -> DbGame {
+    DbGame {
+        id: row.get("id"),
+        date: row.get("date"),
+        board_data: row.get("board_data"),
+        threshold_score: row.get("threshold_score"),
+        sequence_number: row.get("sequence_number"),
+        generation_version: row.get("generation_version"),
+        difficulty: row.get("difficulty"),
+        theme: row.get("theme"),
+        scoring_scheme_version: row.get("scoring_scheme_version"),
+        bonus_rules: row.get("bonus_rules"),
+        game_rules: row.get("game_rules"),
+        mode: row.get("mode"),
+        time_limit_seconds: row.get("time_limit_seconds"),
+        language: row.get("language"),
+        revision: row.get("revision"),
+        deleted_at: row.get("deleted_at"),
+        completed: row.get("completed"),
+        completed_at: row.get("completed_at"),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn hint_usage_from_row(row: &PgRow) -> DbHintUsage {
+    DbHintUsage {
+        user_id: row.get("user_id"),
+        game_id: row.get("game_id"),
+        word: row.get("word"),
+        level: row.get("level"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn game_entry_from_row(row: &PgRow) -> DbGameEntry {
+    DbGameEntry {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        game_id: row.get("game_id"),
+        answers_data: row.get("answers_data"),
+        total_score: row.get("total_score"),
+        completed: row.get::<Option<bool>, _>("completed").unwrap_or_default(),
+        off_board: row.get("off_board"),
+        suspicion_score: row.get("suspicion_score"),
+        request_id: row.get("request_id"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn final_game_stats_from_row(row: &PgRow) -> DbFinalGameStats {
+    DbFinalGameStats {
+        game_id: row.get("game_id"),
+        total_players: row.get("total_players"),
+        average_score: row.get("average_score"),
+        highest_score: row.get("highest_score"),
+        frozen_at: row.get("frozen_at"),
+    }
+}
+
+fn game_stats_from_row(row: &PgRow) -> DbGameStats {
+    DbGameStats {
+        game_id: row.get("game_id"),
+        players: row.get("players"),
+        completed_players: row.get("completed_players"),
+        median_score: row.get("median_score"),
+        optimal_score: row.get("optimal_score"),
+        completion_rate: row.get("completion_rate"),
+        threshold_hit_rate: row.get("threshold_hit_rate"),
+        computed_at: row.get("computed_at"),
+    }
+}
+
+fn notification_from_row(row: &PgRow) -> DbNotification {
+    DbNotification {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        kind: row.get("kind"),
+        game_id: row.get("game_id"),
+        message: row.get("message"),
+        created_at: row.get("created_at"),
+        read_at: row.get("read_at"),
+    }
+}
+
+#[async_trait]
+impl Repository for PgRepository {
+    // User operations
+    async fn create_user(&self, new_user: NewUser) -> Result<DbUser> {
+        let user = DbUser::new(new_user.cookie_token);
+
+        sqlx::query(
+            "INSERT INTO users (id, cookie_token, created_at, last_seen) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(&user.id)
+        .bind(&user.cookie_token)
+        .bind(user.created_at)
+        .bind(user.last_seen)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    async fn get_user_by_cookie(&self, cookie_token: &str) -> Result<Option<DbUser>> {
+        let row = sqlx::query(
+            "SELECT id, cookie_token, created_at, last_seen FROM users WHERE cookie_token = $1",
+        )
+        .bind(cookie_token)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(user_from_row))
+    }
+
+    async fn get_user_by_id(&self, user_id: &str) -> Result<Option<DbUser>> {
+        let row =
+            sqlx::query("SELECT id, cookie_token, created_at, last_seen FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(row.as_ref().map(user_from_row))
+    }
+
+    async fn update_user_last_seen(&self, user_id: &str) -> Result<()> {
+        sqlx::query("UPDATE users SET last_seen = $1 WHERE id = $2")
+            .bind(Utc::now())
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn list_users(&self, page: PageRequest) -> Result<DbPage<DbUser>> {
+        let rows = sqlx::query(
+            "SELECT id, cookie_token, created_at, last_seen FROM users ORDER BY created_at, id LIMIT $1 OFFSET $2",
+        )
+        .bind(page.limit)
+        .bind(page.offset)
+        .fetch_all(&self.pool)
+        .await?;
+        let total = sqlx::query("SELECT COUNT(*)::INTEGER as count FROM users")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(DbPage {
+            items: rows.iter().map(user_from_row).collect(),
+            total: total.get("count"),
+        })
+    }
+
+    async fn touch_user_session(&self, session: NewUserSession) -> Result<DbUserSession> {
+        let session = DbUserSession::new(
+            session.user_id,
+            session.session_hash,
+            session.user_agent_hash,
+        );
+
+        let row = sqlx::query(&format!(
+            "INSERT INTO user_sessions ({USER_SESSION_COLUMNS}) VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (user_id, session_hash) DO UPDATE SET
+                user_agent_hash = EXCLUDED.user_agent_hash,
+                last_seen = EXCLUDED.last_seen
+             RETURNING {USER_SESSION_COLUMNS}"
+        ))
+        .bind(&session.id)
+        .bind(&session.user_id)
+        .bind(&session.session_hash)
+        .bind(&session.user_agent_hash)
+        .bind(session.first_seen)
+        .bind(session.last_seen)
+        .bind(session.revoked_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(user_session_from_row(&row))
+    }
+
+    async fn get_user_sessions(&self, user_id: &str) -> Result<Vec<DbUserSession>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {USER_SESSION_COLUMNS} FROM user_sessions
+             WHERE user_id = $1 AND revoked_at IS NULL ORDER BY last_seen DESC"
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(user_session_from_row).collect())
+    }
+
+    async fn revoke_user_session(
+        &self,
+        user_id: &str,
+        session_id: &str,
+    ) -> Result<Option<DbUserSession>> {
+        let row = sqlx::query(&format!(
+            "UPDATE user_sessions SET revoked_at = $1
+             WHERE id = $2 AND user_id = $3 AND revoked_at IS NULL
+             RETURNING {USER_SESSION_COLUMNS}"
+        ))
+        .bind(Utc::now())
+        .bind(session_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(user_session_from_row))
+    }
+
+    async fn get_revoked_session_hashes(&self, since: DateTime<Utc>) -> Result<Vec<String>> {
+        let rows = sqlx::query("SELECT session_hash FROM user_sessions WHERE revoked_at >= $1")
+            .bind(since)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(|row| row.get("session_hash")).collect())
+    }
+
+    async fn get_or_create_account(&self, user_id: &str) -> Result<DbAccount> {
+        let account = DbAccount::new(user_id.to_string());
+
+        sqlx::query(
+            "INSERT INTO accounts (id, user_id, email, created_at) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (user_id) DO NOTHING",
+        )
+        .bind(&account.id)
+        .bind(&account.user_id)
+        .bind(&account.email)
+        .bind(account.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_account_by_user(user_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("account for user {user_id} missing after insert"))
+    }
+
+    async fn get_account_by_user(&self, user_id: &str) -> Result<Option<DbAccount>> {
+        let row = sqlx::query(&format!(
+            "SELECT {ACCOUNT_COLUMNS} FROM accounts WHERE user_id = $1"
+        ))
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(account_from_row))
+    }
+
+    async fn get_account_by_email(&self, email: &str) -> Result<Option<DbAccount>> {
+        let row = sqlx::query(&format!(
+            "SELECT {ACCOUNT_COLUMNS} FROM accounts WHERE email = $1"
+        ))
+        .bind(email)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(account_from_row))
+    }
+
+    async fn get_account_by_id(&self, account_id: &str) -> Result<Option<DbAccount>> {
+        let row = sqlx::query(&format!(
+            "SELECT {ACCOUNT_COLUMNS} FROM accounts WHERE id = $1"
+        ))
+        .bind(account_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(account_from_row))
+    }
+
+    async fn set_account_email(&self, account_id: &str, email: &str) -> Result<()> {
+        sqlx::query("UPDATE accounts SET email = $1 WHERE id = $2")
+            .bind(email)
+            .bind(account_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn create_magic_link(&self, link: NewMagicLink) -> Result<DbMagicLink> {
+        let row = sqlx::query(&format!(
+            "INSERT INTO account_magic_links (token_hash, email, user_id, expires_at, created_at)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING {MAGIC_LINK_COLUMNS}"
+        ))
+        .bind(&link.token_hash)
+        .bind(&link.email)
+        .bind(&link.user_id)
+        .bind(link.expires_at)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(magic_link_from_row(&row))
+    }
+
+    async fn use_magic_link(&self, token_hash: &str) -> Result<Option<DbMagicLink>> {
+        let row = sqlx::query(&format!(
+            "UPDATE account_magic_links SET used_at = $1
+             WHERE token_hash = $2 AND used_at IS NULL AND expires_at > $1
+             RETURNING {MAGIC_LINK_COLUMNS}"
+        ))
+        .bind(Utc::now())
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(magic_link_from_row))
+    }
+
+    async fn add_passkey(&self, passkey: NewPasskey) -> Result<DbPasskey> {
+        let row = sqlx::query(&format!(
+            "INSERT INTO account_passkeys (credential_id, account_id, public_key, sign_count, created_at)
+             VALUES ($1, $2, $3, $4, $5)
+             RETURNING {PASSKEY_COLUMNS}"
+        ))
+        .bind(&passkey.credential_id)
+        .bind(&passkey.account_id)
+        .bind(&passkey.public_key)
+        .bind(passkey.sign_count)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(passkey_from_row(&row))
+    }
+
+    async fn get_passkey(&self, credential_id: &str) -> Result<Option<DbPasskey>> {
+        let row = sqlx::query(&format!(
+            "SELECT {PASSKEY_COLUMNS} FROM account_passkeys WHERE credential_id = $1"
+        ))
+        .bind(credential_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(passkey_from_row))
+    }
+
+    async fn get_account_passkeys(&self, account_id: &str) -> Result<Vec<DbPasskey>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {PASSKEY_COLUMNS} FROM account_passkeys WHERE account_id = $1 ORDER BY created_at"
+        ))
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(passkey_from_row).collect())
+    }
+
+    async fn update_passkey_sign_count(&self, credential_id: &str, sign_count: i64) -> Result<()> {
+        sqlx::query(
+            "UPDATE account_passkeys SET sign_count = $1, last_used_at = $2 WHERE credential_id = $3",
+        )
+        .bind(sign_count)
+        .bind(Utc::now())
+        .bind(credential_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn merge_users(&self, into_user_id: &str, from_user_id: &str) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        let moved = sqlx::query(
+            "UPDATE game_entries SET user_id = $1
+             WHERE user_id = $2
+               AND game_id NOT IN (SELECT game_id FROM game_entries WHERE user_id = $1)",
+        )
+        .bind(into_user_id)
+        .bind(from_user_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        sqlx::query(
+            "UPDATE hint_usage SET user_id = $1
+             WHERE user_id = $2
+               AND NOT EXISTS (
+                   SELECT 1 FROM hint_usage AS kept
+                   WHERE kept.user_id = $1 AND kept.game_id = hint_usage.game_id
+                     AND kept.word = hint_usage.word
+               )",
+        )
+        .bind(into_user_id)
+        .bind(from_user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE notifications SET user_id = $1 WHERE user_id = $2")
+            .bind(into_user_id)
+            .bind(from_user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            "UPDATE notification_preferences SET user_id = $1
+             WHERE user_id = $2
+               AND NOT EXISTS (SELECT 1 FROM notification_preferences WHERE user_id = $1)",
+        )
+        .bind(into_user_id)
+        .bind(from_user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "UPDATE game_starts SET user_id = $1
+             WHERE user_id = $2
+               AND NOT EXISTS (
+                   SELECT 1 FROM game_starts AS kept
+                   WHERE kept.user_id = $1 AND kept.game_id = game_starts.game_id
+               )",
+        )
+        .bind(into_user_id)
+        .bind(from_user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "UPDATE word_challenges SET user_id = $1
+             WHERE user_id = $2
+               AND NOT EXISTS (
+                   SELECT 1 FROM word_challenges AS kept
+                   WHERE kept.user_id = $1 AND kept.game_id = word_challenges.game_id
+                     AND kept.word = word_challenges.word
+               )",
+        )
+        .bind(into_user_id)
+        .bind(from_user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "UPDATE custom_game_scores SET user_id = $1
+             WHERE user_id = $2
+               AND NOT EXISTS (
+                   SELECT 1 FROM custom_game_scores AS kept
+                   WHERE kept.user_id = $1
+                     AND kept.custom_game_id = custom_game_scores.custom_game_id
+               )",
+        )
+        .bind(into_user_id)
+        .bind(from_user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE custom_games SET created_by = $1 WHERE created_by = $2")
+            .bind(into_user_id)
+            .bind(from_user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            "UPDATE tournament_participants SET user_id = $1
+             WHERE user_id = $2
+               AND NOT EXISTS (
+                   SELECT 1 FROM tournament_participants AS kept
+                   WHERE kept.user_id = $1
+                     AND kept.tournament_id = tournament_participants.tournament_id
+               )",
+        )
+        .bind(into_user_id)
+        .bind(from_user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "UPDATE tournament_results
+             SET user_id = CASE WHEN EXISTS (
+                 SELECT 1 FROM tournament_results AS kept
+                 WHERE kept.user_id = $1
+                   AND kept.tournament_id = tournament_results.tournament_id
+             ) THEN NULL ELSE $1 END
+             WHERE user_id = $2",
+        )
+        .bind(into_user_id)
+        .bind(from_user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE discord_links SET user_id = $1 WHERE user_id = $2")
+            .bind(into_user_id)
+            .bind(from_user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for table in [
+            "game_entries",
+            "hint_usage",
+            "user_sessions",
+            "account_magic_links",
+            "notification_preferences",
+            "game_starts",
+            "word_challenges",
+            "entry_revisions",
+            "custom_game_scores",
+            "tournament_participants",
+        ] {
+            sqlx::query(&format!("DELETE FROM {table} WHERE user_id = $1"))
+                .bind(from_user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        sqlx::query("DELETE FROM users WHERE id = $1")
+            .bind(from_user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(moved)
+    }
+
+    async fn delete_user(&self, user_id: &str) -> Result<DbUserDeletion> {
+        let cleared_answers = DbStoredAnswers {
+            answers: Vec::new(),
+        }
+        .to_json()?;
+        let mut tx = self.pool.begin().await?;
+
+        let anonymous = DbUser::anonymous();
+        sqlx::query(
+            "INSERT INTO users (id, cookie_token, created_at, last_seen) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(&anonymous.id)
+        .bind(&anonymous.cookie_token)
+        .bind(anonymous.created_at)
+        .bind(anonymous.last_seen)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "DELETE FROM game_entry_answers WHERE entry_id IN
+                (SELECT id FROM game_entries WHERE user_id = $1 AND completed = TRUE AND off_board = FALSE)",
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        let anonymized_entries = sqlx::query(
+            "UPDATE game_entries SET user_id = $1, answers_data = $2, request_id = NULL
+             WHERE user_id = $3 AND completed = TRUE AND off_board = FALSE",
+        )
+        .bind(&anonymous.id)
+        .bind(&cleared_answers)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        sqlx::query(
+            "UPDATE custom_game_scores SET user_id = $1, answers_data = $2 WHERE user_id = $3",
+        )
+        .bind(&anonymous.id)
+        .bind(&cleared_answers)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "DELETE FROM users
+             WHERE id = $1
+               AND NOT EXISTS (SELECT 1 FROM game_entries WHERE user_id = $1)
+               AND NOT EXISTS (SELECT 1 FROM custom_game_scores WHERE user_id = $1)",
+        )
+        .bind(&anonymous.id)
+        .execute(&mut *tx)
+        .await?;
+
+        let deleted_entries = sqlx::query("DELETE FROM game_entries WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        // Links emailed to the account's address name the address rather than the user
+        sqlx::query(
+            "DELETE FROM account_magic_links
+             WHERE user_id = $1 OR email IN (SELECT email FROM accounts WHERE user_id = $1)",
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "DELETE FROM account_passkeys
+             WHERE account_id IN (SELECT id FROM accounts WHERE user_id = $1)",
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("UPDATE custom_games SET created_by = NULL WHERE created_by = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE tournament_results SET user_id = NULL WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for table in [
+            "accounts",
+            "hint_usage",
+            "user_sessions",
+            "notification_preferences",
+            "notifications",
+            "game_starts",
+            "word_challenges",
+            "entry_revisions",
+            "discord_links",
+            "tournament_participants",
+        ] {
+            sqlx::query(&format!("DELETE FROM {table} WHERE user_id = $1"))
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        sqlx::query("DELETE FROM users WHERE id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(DbUserDeletion {
+            anonymized_entries,
+            deleted_entries,
+        })
+    }
+
+    async fn get_game_by_date(&self, date: &str) -> Result<Option<DbGame>> {
+        self.get_game_by_date_in_mode(date, GameMode::Daily).await
+    }
+
+    async fn get_game_by_date_in_mode(&self, date: &str, mode: GameMode) -> Result<Option<DbGame>> {
+        let row = sqlx::query(&format!(
+            "SELECT {GAME_COLUMNS} FROM games WHERE date = $1 AND mode = $2 AND language = 'en' AND deleted_at IS NULL"
+        ))
+        .bind(date)
+        .bind(mode.as_str())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(game_from_row))
+    }
+
+    async fn get_game_by_id(&self, game_id: &str) -> Result<Option<DbGame>> {
+        let row = sqlx::query(&format!("SELECT {GAME_COLUMNS} FROM games WHERE id = $1"))
+            .bind(game_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.as_ref().map(game_from_row))
+    }
+
+    async fn get_game_by_sequence_number(&self, sequence_number: i32) -> Result<Option<DbGame>> {
+        let row = sqlx::query(&format!(
+            "SELECT {GAME_COLUMNS} FROM games WHERE sequence_number = $1 AND mode = 'daily' AND language = 'en' AND deleted_at IS NULL"
+        ))
+        .bind(sequence_number)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(game_from_row))
+    }
+
+    async fn game_exists_for_date(&self, date: &str) -> Result<bool> {
+        let row =
+            sqlx::query("SELECT COUNT(*) as count FROM games WHERE date = $1 AND mode = 'daily' AND language = 'en' AND deleted_at IS NULL")
+                .bind(date)
+                .fetch_one(&self.pool)
+                .await?;
+
+        let count: i64 = row.get("count");
+        Ok(count > 0)
+    }
+
+    async fn get_next_sequence_number(&self) -> Result<i32> {
+        self.get_next_sequence_number_in_mode(GameMode::Daily).await
+    }
+
+    async fn get_game_by_date_in_language(
+        &self,
+        date: &str,
+        language: Language,
+    ) -> Result<Option<DbGame>> {
+        let row = sqlx::query(&format!(
+            "SELECT {GAME_COLUMNS} FROM games WHERE date = $1 AND mode = 'daily' AND language = $2 AND deleted_at IS NULL"
+        ))
+        .bind(date)
+        .bind(language.as_str())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(game_from_row))
+    }
+
+    async fn get_game_by_sequence_number_in_language(
+        &self,
+        sequence_number: i32,
+        language: Language,
+    ) -> Result<Option<DbGame>> {
+        let row = sqlx::query(&format!(
+            "SELECT {GAME_COLUMNS} FROM games WHERE sequence_number = $1 AND mode = 'daily' AND language = $2 AND deleted_at IS NULL"
+        ))
+        .bind(sequence_number)
+        .bind(language.as_str())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(game_from_row))
+    }
+
+    async fn get_next_sequence_number_in_language(&self, language: Language) -> Result<i32> {
+        let row = sqlx::query(
+            "SELECT MAX(sequence_number) as max_seq FROM games WHERE mode = 'daily' AND language = $1",
+        )
+        .bind(language.as_str())
+        .fetch_one(&self.pool)
+        .await?;
+
+        let max_sequence: Option<i32> = row.get("max_seq");
+        Ok(max_sequence.unwrap_or(0) + 1)
+    }
+
+    async fn get_next_sequence_number_in_mode(&self, mode: GameMode) -> Result<i32> {
+        let row = sqlx::query(
+            "SELECT MAX(sequence_number) as max_seq FROM games WHERE mode = $1 AND language = 'en'",
+        )
+        .bind(mode.as_str())
+        .fetch_one(&self.pool)
+        .await?;
+
+        let max_sequence: Option<i32> = row.get("max_seq");
+        Ok(max_sequence.unwrap_or(0) + 1)
+    }
+
+    async fn get_archive_games(
+        &self,
+        user_id: Option<&str>,
+        latest_date: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<DbArchiveGame>> {
+        let rows = sqlx::query(
+            "SELECT g.id, g.date, g.sequence_number, g.threshold_score,
+                ue.completed as user_completed, ue.total_score as user_score,
+                COALESCE(s.total_players, 0)::INTEGER as total_players,
+                s.average_score::FLOAT8 as average_score
+             FROM games g
+             LEFT JOIN game_entries ue ON ue.game_id = g.id AND ue.user_id = $1
+             LEFT JOIN (
+                SELECT game_id, COUNT(*) as total_players, AVG(total_score) as average_score
+                FROM game_entries WHERE completed = TRUE AND off_board = FALSE GROUP BY game_id
+             ) s ON s.game_id = g.id
+             WHERE g.date <= $2 AND g.mode = 'daily' AND g.language = 'en' AND g.deleted_at IS NULL
+             ORDER BY g.date DESC
+             LIMIT $3 OFFSET $4",
+        )
+        .bind(user_id)
+        .bind(latest_date)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(archive_game_from_row).collect())
+    }
+
+    async fn count_archive_games(&self, latest_date: &str) -> Result<i32> {
+        let row = sqlx::query(
+            "SELECT COUNT(*)::INTEGER as count FROM games WHERE date <= $1 AND mode = 'daily' AND language = 'en' AND deleted_at IS NULL",
+        )
+        .bind(latest_date)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row.get("count"))
+    }
+
+    async fn list_games(&self, filter: &GameFilter, page: PageRequest) -> Result<DbPage<DbGame>> {
+        const FILTER: &str = "($1::TEXT IS NULL OR mode = $1)
+               AND ($2::TEXT IS NULL OR language = $2)
+               AND ($3::TEXT IS NULL OR date >= $3)
+               AND ($4::TEXT IS NULL OR date <= $4)
+               AND ($5 OR deleted_at IS NULL)";
+        let mode = filter.mode.map(|mode| mode.as_str());
+        let language = filter.language.map(|language| language.as_str());
+
+        let rows = sqlx::query(&format!(
+            "SELECT {GAME_COLUMNS} FROM games WHERE {FILTER}
+             ORDER BY date DESC, created_at DESC, id DESC LIMIT $6 OFFSET $7"
+        ))
+        .bind(mode)
+        .bind(language)
+        .bind(&filter.from_date)
+        .bind(&filter.to_date)
+        .bind(filter.include_replaced)
+        .bind(page.limit)
+        .bind(page.offset)
+        .fetch_all(&self.pool)
+        .await?;
+        let total = sqlx::query(&format!(
+            "SELECT COUNT(*)::INTEGER as count FROM games WHERE {FILTER}"
+        ))
+        .bind(mode)
+        .bind(language)
+        .bind(&filter.from_date)
+        .bind(&filter.to_date)
+        .bind(filter.include_replaced)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(DbPage {
+            items: rows.iter().map(game_from_row).collect(),
+            total: total.get("count"),
+        })
+    }
+
+    async fn create_practice_game(&self, new_game: NewPracticeGame) -> Result<DbPracticeGame> {
+        let game = DbPracticeGame::new(
+            new_game.seed,
+            new_game.board_data,
+            new_game.threshold_score,
+            new_game.optimal_score,
+            new_game.expires_at,
+        );
+
+        sqlx::query(&format!(
+            "INSERT INTO practice_games ({PRACTICE_GAME_COLUMNS}) VALUES ($1, $2, $3, $4, $5, $6, $7)"
+        ))
+        .bind(&game.id)
+        .bind(game.seed.to_string())
+        .bind(&game.board_data)
+        .bind(game.threshold_score)
+        .bind(game.optimal_score)
+        .bind(game.created_at)
+        .bind(game.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(game)
+    }
+
+    async fn get_practice_game(&self, practice_id: &str) -> Result<Option<DbPracticeGame>> {
+        let row = sqlx::query(&format!(
+            "SELECT {PRACTICE_GAME_COLUMNS} FROM practice_games WHERE id = $1 AND expires_at > $2"
+        ))
+        .bind(practice_id)
+        .bind(Utc::now())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(practice_game_from_row).transpose()
+    }
+
+    async fn delete_expired_practice_games(&self) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM practice_games WHERE expires_at <= $1")
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    // Custom game operations
+    async fn create_custom_game(&self, new_game: NewCustomGame) -> Result<DbCustomGame> {
+        let game = DbCustomGame::from(new_game);
+
+        sqlx::query(&format!(
+            "INSERT INTO custom_games ({CUSTOM_GAME_COLUMNS}) VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (board_code) DO NOTHING"
+        ))
+        .bind(&game.id)
+        .bind(&game.slug)
+        .bind(&game.board_code)
+        .bind(&game.board_data)
+        .bind(game.optimal_score)
+        .bind(&game.created_by)
+        .bind(game.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        let row = sqlx::query(&format!(
+            "SELECT {CUSTOM_GAME_COLUMNS} FROM custom_games WHERE board_code = $1"
+        ))
+        .bind(&game.board_code)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(custom_game_from_row(&row))
+    }
+
+    async fn get_custom_game(&self, slug: &str) -> Result<Option<DbCustomGame>> {
+        let row = sqlx::query(&format!(
+            "SELECT {CUSTOM_GAME_COLUMNS} FROM custom_games WHERE slug = $1"
+        ))
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(custom_game_from_row))
+    }
+
+    async fn save_custom_game_score(&self, score: NewCustomGameScore) -> Result<DbCustomGameScore> {
+        let score = DbCustomGameScore::from(score);
+
+        let row = sqlx::query(&format!(
+            "INSERT INTO custom_game_scores ({CUSTOM_GAME_SCORE_COLUMNS})
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (custom_game_id, user_id) DO UPDATE SET
+                 answers_data = EXCLUDED.answers_data,
+                 total_score = EXCLUDED.total_score,
+                 updated_at = EXCLUDED.updated_at
+             WHERE EXCLUDED.total_score > custom_game_scores.total_score
+             RETURNING {CUSTOM_GAME_SCORE_COLUMNS}"
+        ))
+        .bind(&score.id)
+        .bind(&score.custom_game_id)
+        .bind(&score.user_id)
+        .bind(&score.answers_data)
+        .bind(score.total_score)
+        .bind(score.created_at)
+        .bind(score.updated_at)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        // Nothing is returned when the player's best was kept
+        let row = match row {
+            Some(row) => row,
+            None => {
+                sqlx::query(&format!(
+                    "SELECT {CUSTOM_GAME_SCORE_COLUMNS} FROM custom_game_scores
+                     WHERE custom_game_id = $1 AND user_id = $2"
+                ))
+                .bind(&score.custom_game_id)
+                .bind(&score.user_id)
+                .fetch_one(&self.pool)
+                .await?
+            }
+        };
+        Ok(custom_game_score_from_row(&row))
+    }
+
+    async fn get_custom_game_scores(
+        &self,
+        custom_game_id: &str,
+        limit: i32,
+    ) -> Result<Vec<DbCustomGameScore>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {CUSTOM_GAME_SCORE_COLUMNS} FROM custom_game_scores
+             WHERE custom_game_id = $1
+             ORDER BY total_score DESC, updated_at ASC
+             LIMIT $2"
+        ))
+        .bind(custom_game_id)
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(custom_game_score_from_row).collect())
+    }
+
+    async fn create_tournament(&self, new_tournament: NewTournament) -> Result<DbTournament> {
+        let tournament = DbTournament::from(new_tournament);
+
+        sqlx::query(&format!(
+            "INSERT INTO tournaments ({TOURNAMENT_COLUMNS}) VALUES ($1, $2, $3, $4, $5, $6, $7)"
+        ))
+        .bind(&tournament.id)
+        .bind(&tournament.name)
+        .bind(&tournament.start_date)
+        .bind(&tournament.end_date)
+        .bind(&tournament.language)
+        .bind(tournament.created_at)
+        .bind(tournament.finalized_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(tournament)
+    }
+
+    async fn get_tournament(&self, tournament_id: &str) -> Result<Option<DbTournament>> {
+        let row = sqlx::query(&format!(
+            "SELECT {TOURNAMENT_COLUMNS} FROM tournaments WHERE id = $1"
+        ))
+        .bind(tournament_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(tournament_from_row))
+    }
+
+    async fn list_tournaments(&self, limit: i32) -> Result<Vec<DbTournament>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {TOURNAMENT_COLUMNS} FROM tournaments
+             ORDER BY start_date DESC, created_at DESC
+             LIMIT $1"
+        ))
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(tournament_from_row).collect())
+    }
+
+    async fn join_tournament(&self, tournament_id: &str, user_id: &str) -> Result<bool> {
+        let joined = sqlx::query(
+            "INSERT INTO tournament_participants (tournament_id, user_id, joined_at)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (tournament_id, user_id) DO NOTHING",
+        )
+        .bind(tournament_id)
+        .bind(user_id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        Ok(joined > 0)
+    }
+
+    async fn get_tournament_standings(
+        &self,
+        tournament_id: &str,
+    ) -> Result<Vec<DbTournamentStanding>> {
+        let rows = sqlx::query(TOURNAMENT_STANDINGS_QUERY)
+            .bind(tournament_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(tournament_standing_from_row).collect())
+    }
+
+    async fn get_unfinalized_tournaments_through(&self, date: &str) -> Result<Vec<DbTournament>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {TOURNAMENT_COLUMNS} FROM tournaments
+             WHERE end_date <= $1 AND finalized_at IS NULL
+             ORDER BY end_date"
+        ))
+        .bind(date)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(tournament_from_row).collect())
+    }
+
+    async fn finalize_tournament(
+        &self,
+        tournament_id: &str,
+    ) -> Result<Option<Vec<DbTournamentResult>>> {
+        let mut tx = self.pool.begin().await?;
+
+        let finalized = sqlx::query(
+            "UPDATE tournaments SET finalized_at = $2 WHERE id = $1 AND finalized_at IS NULL",
+        )
+        .bind(tournament_id)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if finalized == 0 {
+            return Ok(None);
+        }
+
+        let rows = sqlx::query(TOURNAMENT_STANDINGS_QUERY)
+            .bind(tournament_id)
+            .fetch_all(&mut *tx)
+            .await?;
+        let standings: Vec<DbTournamentStanding> =
+            rows.iter().map(tournament_standing_from_row).collect();
+        let results = DbTournamentResult::from_standings(tournament_id, &standings);
+        for result in &results {
+            sqlx::query(&format!(
+                "INSERT INTO tournament_results (id, {TOURNAMENT_RESULT_COLUMNS})
+                 VALUES ($1, $2, $3, $4, $5, $6)"
+            ))
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&result.tournament_id)
+            .bind(&result.user_id)
+            .bind(result.rank)
+            .bind(result.total_score)
+            .bind(result.games_played)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(Some(results))
+    }
+
+    async fn get_tournament_results(&self, tournament_id: &str) -> Result<Vec<DbTournamentResult>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {TOURNAMENT_RESULT_COLUMNS} FROM tournament_results
+             WHERE tournament_id = $1
+             ORDER BY rank, total_score DESC, games_played DESC"
+        ))
+        .bind(tournament_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(tournament_result_from_row).collect())
+    }
+
+    // Game entry operations
+    async fn create_or_update_game_entry(&self, new_entry: NewGameEntry) -> Result<DbGameEntry> {
+        self.create_or_update_game_entry_with_events(new_entry, Vec::new())
+            .await
+    }
+
+    async fn create_or_update_game_entry_with_events(
+        &self,
+        new_entry: NewGameEntry,
+        events: Vec<NewOutboxEvent>,
+    ) -> Result<DbGameEntry> {
+        let entry = DbGameEntry::new(
+            new_entry.user_id,
+            new_entry.game_id,
+            new_entry.answers_data,
+            new_entry.total_score,
+            new_entry.completed,
+        );
+
+        let mut tx = self.pool.begin().await?;
+
+        // Upsert on the (user_id, game_id) unique constraint, keeping the
+        // original id and created_at when the entry already exists. Writes
+        // after the game's stats were frozen are late plays, and a retry of
+        // the write that produced the entry updates nothing.
+        let row = sqlx::query(&format!(
+            "INSERT INTO game_entries ({GAME_ENTRY_COLUMNS}, completed_at)
+             VALUES ($1, $2, $3, $4, $5, $6,
+                ($10 OR EXISTS (SELECT 1 FROM game_final_stats WHERE game_id = $3)), $9, $11, $7, $8,
+                CASE WHEN $6 THEN $8::TIMESTAMPTZ END)
+             ON CONFLICT (user_id, game_id) DO UPDATE SET
+                answers_data = EXCLUDED.answers_data,
+                total_score = EXCLUDED.total_score,
+                completed = EXCLUDED.completed,
+                completed_at = CASE
+                    WHEN EXCLUDED.completed AND NOT COALESCE(game_entries.completed, FALSE)
+                    THEN EXCLUDED.updated_at ELSE game_entries.completed_at END,
+                off_board = game_entries.off_board OR EXCLUDED.off_board,
+                suspicion_score = GREATEST(game_entries.suspicion_score, EXCLUDED.suspicion_score),
+                request_id = EXCLUDED.request_id,
+                updated_at = EXCLUDED.updated_at
+             WHERE EXCLUDED.request_id IS NULL
+                OR game_entries.request_id IS DISTINCT FROM EXCLUDED.request_id
+             RETURNING {GAME_ENTRY_COLUMNS}"
+        ))
+        .bind(&entry.id)
+        .bind(&entry.user_id)
+        .bind(&entry.game_id)
+        .bind(&entry.answers_data)
+        .bind(entry.total_score)
+        .bind(entry.completed)
+        .bind(entry.created_at)
+        .bind(entry.updated_at)
+        .bind(new_entry.suspicion_score)
+        .bind(new_entry.exclude)
+        .bind(&new_entry.request_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(row) = row else {
+            let existing = sqlx::query(&format!(
+                "SELECT {GAME_ENTRY_COLUMNS} FROM game_entries WHERE user_id = $1 AND game_id = $2"
+            ))
+            .bind(&entry.user_id)
+            .bind(&entry.game_id)
+            .fetch_one(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            return Ok(game_entry_from_row(&existing));
+        };
+        let entry = game_entry_from_row(&row);
+        write_entry_answers(&mut tx, &entry.id, &entry.answers_data).await?;
+
+        for event in events {
+            let event = DbOutboxEvent::new(event);
+            sqlx::query("INSERT INTO outbox_events (id, event_type, payload, attempts, created_at) VALUES ($1, $2, $3, $4, $5)")
+                .bind(&event.id)
+                .bind(&event.event_type)
+                .bind(&event.payload)
+                .bind(event.attempts)
+                .bind(event.created_at)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(entry)
+    }
+
+    async fn get_game_entry(&self, user_id: &str, game_id: &str) -> Result<Option<DbGameEntry>> {
+        let row = sqlx::query(&format!(
+            "SELECT {GAME_ENTRY_COLUMNS} FROM game_entries WHERE user_id = $1 AND game_id = $2"
+        ))
+        .bind(user_id)
+        .bind(game_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(game_entry_from_row))
+    }
+
+    async fn list_entries_for_game(
+        &self,
+        game_id: &str,
+        page: PageRequest,
+    ) -> Result<DbPage<DbGameEntry>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {GAME_ENTRY_COLUMNS} FROM game_entries WHERE game_id = $1
+             ORDER BY created_at, id LIMIT $2 OFFSET $3"
+        ))
+        .bind(game_id)
+        .bind(page.limit)
+        .bind(page.offset)
+        .fetch_all(&self.pool)
+        .await?;
+        let total =
+            sqlx::query("SELECT COUNT(*)::INTEGER as count FROM game_entries WHERE game_id = $1")
+                .bind(game_id)
+                .fetch_one(&self.pool)
+                .await?;
+
+        Ok(DbPage {
+            items: rows.iter().map(game_entry_from_row).collect(),
+            total: total.get("count"),
+        })
+    }
+
+    async fn get_hint_usage(&self, user_id: &str, game_id: &str) -> Result<Vec<DbHintUsage>> {
+        let rows = sqlx::query(
+            "SELECT user_id, game_id, word, level, created_at, updated_at FROM hint_usage
+             WHERE user_id = $1 AND game_id = $2 ORDER BY created_at, word",
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(hint_usage_from_row).collect())
+    }
+
+    async fn record_hint_usage(&self, usage: NewHintUsage) -> Result<DbHintUsage> {
+        let usage = DbHintUsage::new(usage.user_id, usage.game_id, usage.word, usage.level);
+
+        // A level is never lowered, so replaying an older request can't hand back hints
+        let row = sqlx::query(
+            "INSERT INTO hint_usage (user_id, game_id, word, level, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             ON CONFLICT (user_id, game_id, word) DO UPDATE SET
+                level = GREATEST(hint_usage.level, EXCLUDED.level),
+                updated_at = EXCLUDED.updated_at
+             RETURNING user_id, game_id, word, level, created_at, updated_at",
+        )
+        .bind(&usage.user_id)
+        .bind(&usage.game_id)
+        .bind(&usage.word)
+        .bind(usage.level)
+        .bind(usage.created_at)
+        .bind(usage.updated_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(hint_usage_from_row(&row))
+    }
+
+    // Create game and answers atomically
+    async fn record_word_challenge(&self, user_id: &str, game_id: &str, word: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO word_challenges (user_id, game_id, word, created_at) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (user_id, game_id, word) DO NOTHING",
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .bind(word)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_challenged_words(&self, user_id: &str, game_id: &str) -> Result<Vec<String>> {
+        let rows = sqlx::query(
+            "SELECT word FROM word_challenges WHERE user_id = $1 AND game_id = $2 ORDER BY word",
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.iter().map(|row| row.get("word")).collect())
+    }
+
+    async fn get_wordlist_proposals(&self) -> Result<Vec<DbWordProposal>> {
+        let rows = sqlx::query(
+            "SELECT word, COUNT(*) AS challenges, MIN(created_at) AS first_challenged_at
+             FROM word_challenges
+             GROUP BY word
+             ORDER BY challenges DESC, word",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbWordProposal {
+                word: row.get("word"),
+                challenges: row.get("challenges"),
+                first_challenged_at: row.get("first_challenged_at"),
+            })
+            .collect())
+    }
+
+    async fn save_entry_revision(
+        &self,
+        user_id: &str,
+        game_id: &str,
+        answers_data: &str,
+        keep: usize,
+    ) -> Result<DbEntryRevision> {
+        let revision = DbEntryRevision::new(
+            user_id.to_string(),
+            game_id.to_string(),
+            answers_data.to_string(),
+        );
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "INSERT INTO entry_revisions (id, user_id, game_id, answers_data, created_at)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(&revision.id)
+        .bind(&revision.user_id)
+        .bind(&revision.game_id)
+        .bind(&revision.answers_data)
+        .bind(revision.created_at)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "DELETE FROM entry_revisions
+             WHERE user_id = $1 AND game_id = $2
+               AND id NOT IN (
+                   SELECT id FROM entry_revisions
+                   WHERE user_id = $1 AND game_id = $2
+                   ORDER BY created_at DESC LIMIT $3
+               )",
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .bind(keep as i64)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(revision)
+    }
+
+    async fn get_entry_revisions(
+        &self,
+        user_id: &str,
+        game_id: &str,
+    ) -> Result<Vec<DbEntryRevision>> {
+        let rows = sqlx::query(
+            "SELECT id, user_id, game_id, answers_data, created_at FROM entry_revisions
+             WHERE user_id = $1 AND game_id = $2 ORDER BY created_at DESC",
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbEntryRevision {
+                id: row.get("id"),
+                user_id: row.get("user_id"),
+                game_id: row.get("game_id"),
+                answers_data: row.get("answers_data"),
+                created_at: row.get("created_at"),
+            })
+            .collect())
+    }
+
+    async fn create_game_with_answers(
+        &self,
+        new_game: NewGame,
+        game_answers: Vec<NewGameAnswer>,
+        optimal_solution: Option<NewOptimalSolution>,
+    ) -> Result<(DbGame, Vec<DbGameAnswer>)> {
+        let mut tx = self.pool.begin().await?;
+        let created = insert_game_with_answers(
+            &mut tx,
+            DbGame::new(new_game),
+            game_answers,
+            optimal_solution,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    // Create several games and their answers in a single transaction
+    async fn create_games_with_answers(
+        &self,
+        games: Vec<NewGameWithAnswers>,
+    ) -> Result<Vec<DbGame>> {
+        let mut tx = self.pool.begin().await?;
+        let mut created_games = Vec::with_capacity(games.len());
+        for new_game in games {
+            let (game, _answers) = insert_game_with_answers(
+                &mut tx,
+                DbGame::new(new_game.game),
+                new_game.answers,
+                new_game.optimal_solution,
+            )
+            .await?;
+            if let Some(density) = new_game.density {
+                insert_board_density(&mut tx, &game.id, &density).await?;
+            }
+            if let Some(quality) = new_game.quality {
+                insert_board_quality(&mut tx, &game.id, &quality).await?;
+            }
+            created_games.push(game);
+        }
+        tx.commit().await?;
+        Ok(created_games)
+    }
+
+    async fn replace_game(
+        &self,
+        game_id: &str,
+        replacement: NewGameWithAnswers,
+    ) -> Result<Option<DbGame>> {
+        let mut tx = self.pool.begin().await?;
+        let Some(replaced) = sqlx::query(
+            "UPDATE games SET deleted_at = $2 WHERE id = $1 AND deleted_at IS NULL
+             RETURNING date, sequence_number, mode, language, revision",
+        )
+        .bind(game_id)
+        .bind(Utc::now())
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let mut game = DbGame::new(NewGame {
+            date: replaced.get("date"),
+            sequence_number: replaced.get("sequence_number"),
+            mode: replaced.get("mode"),
+            language: replaced.get("language"),
+            ..replacement.game
+        });
+        game.revision = replaced.get::<i32, _>("revision") + 1;
+        let (game, _answers) = insert_game_with_answers(
+            &mut tx,
+            game,
+            replacement.answers,
+            replacement.optimal_solution,
+        )
+        .await?;
+        if let Some(density) = replacement.density {
+            insert_board_density(&mut tx, &game.id, &density).await?;
+        }
+        if let Some(quality) = replacement.quality {
+            insert_board_quality(&mut tx, &game.id, &quality).await?;
+        }
+        tx.commit().await?;
+        Ok(Some(game))
+    }
+
+    async fn get_game_words(&self, game_id: &str) -> Result<Vec<String>> {
+        let rows = sqlx::query("SELECT DISTINCT word FROM game_answers2 WHERE game_id = $1")
+            .bind(game_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        if rows.is_empty() {
+            if let Some(answers) = self.get_compacted_answers(game_id).await? {
+                return Ok(answers.into_iter().map(|answer| answer.word).collect());
+            }
+        }
+
+        Ok(rows.into_iter().map(|row| row.get("word")).collect())
+    }
+
+    async fn get_game_answers(&self, game_id: &str) -> Result<Vec<DbGameAnswer>> {
+        let rows = sqlx::query(
+            "SELECT game_id, word, path_count, requires_wildcard, created_at FROM game_answers2 WHERE game_id = $1 ORDER BY word",
+        )
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        if rows.is_empty() {
+            if let Some(answers) = self.get_compacted_answers(game_id).await? {
+                return Ok(answers);
+            }
+        }
+
+        let answers = rows
+            .into_iter()
+            .map(|row| {
+                let path_stats = match (
+                    row.get::<Option<i32>, _>("path_count"),
+                    row.get::<Option<bool>, _>("requires_wildcard"),
+                ) {
+                    (Some(path_count), Some(requires_wildcard)) => Some(WordPathStats {
+                        path_count,
+                        requires_wildcard,
+                    }),
+                    _ => None,
+                };
+
+                DbGameAnswer {
+                    game_id: row.get("game_id"),
+                    word: row.get("word"),
+                    path_stats,
+                    created_at: row.get("created_at"),
+                }
+            })
+            .collect();
+
+        Ok(answers)
+    }
+
+    async fn get_uncompacted_game_ids(&self, before_date: &str, limit: i32) -> Result<Vec<String>> {
+        let ids = sqlx::query_scalar(
+            "SELECT id FROM games WHERE date < $1 AND answers_blob IS NULL
+             AND EXISTS (SELECT 1 FROM game_answers2 WHERE game_id = games.id)
+             ORDER BY date LIMIT $2",
+        )
+        .bind(before_date)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    async fn store_compacted_answers(&self, game_id: &str, answers_blob: Vec<u8>) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("UPDATE games SET answers_blob = $2 WHERE id = $1")
+            .bind(game_id)
+            .bind(answers_blob)
+            .execute(&mut *tx)
+            .await?;
+        let deleted = sqlx::query("DELETE FROM game_answers2 WHERE game_id = $1")
+            .bind(game_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(deleted.rows_affected())
+    }
+
+    // Get score distribution for a specific game
+    async fn get_score_distribution(&self, game_id: &str) -> Result<Vec<i32>> {
+        let rows = sqlx::query(
+            "SELECT total_score FROM game_entries WHERE game_id = $1 AND completed = TRUE AND off_board = FALSE",
+        )
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| row.get("total_score")).collect())
+    }
+
+    async fn get_word_counts(&self, game_id: &str) -> Result<Vec<DbWordCount>> {
+        let rows = sqlx::query(
+            "SELECT a.word, COUNT(DISTINCT a.entry_id)::INTEGER as players
+             FROM game_entry_answers a
+             JOIN game_entries e ON e.id = a.entry_id
+             WHERE e.game_id = $1 AND e.completed = TRUE AND e.off_board = FALSE
+             GROUP BY a.word
+             ORDER BY players DESC, a.word",
+        )
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbWordCount {
+                word: row.get("word"),
+                players: row.get("players"),
+            })
+            .collect())
+    }
+
+    // Get optimal solutions for a specific game
+    async fn get_optimal_solutions(&self, game_id: &str) -> Result<Vec<OptimalAnswer>> {
+        let row = sqlx::query("SELECT words_and_scores FROM optimal_solutions WHERE game_id = $1")
+            .bind(game_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        if let Some(row) = row {
+            let words_and_scores: String = row.get("words_and_scores");
+            let optimal_answers: Vec<OptimalAnswer> = serde_json::from_str(&words_and_scores)?;
+            Ok(optimal_answers)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    // Completion tracking operations
+    async fn claim_game_announcement(&self, game_id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT INTO game_announcements (game_id, announced_at) VALUES ($1, $2) ON CONFLICT (game_id) DO NOTHING",
+        )
+        .bind(game_id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn release_game_announcement(&self, game_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM game_announcements WHERE game_id = $1")
+            .bind(game_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn create_webhook_subscription(
+        &self,
+        subscription: NewWebhookSubscription,
+    ) -> Result<DbWebhookSubscription> {
+        let subscription = DbWebhookSubscription::from(subscription);
+
+        sqlx::query(&format!(
+            "INSERT INTO webhook_subscriptions ({WEBHOOK_SUBSCRIPTION_COLUMNS}) VALUES ($1, $2, $3, $4, $5)"
+        ))
+        .bind(&subscription.id)
+        .bind(&subscription.kind)
+        .bind(&subscription.url)
+        .bind(&subscription.secret)
+        .bind(subscription.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(subscription)
+    }
+
+    async fn get_webhook_subscription(&self, id: &str) -> Result<Option<DbWebhookSubscription>> {
+        let row = sqlx::query(&format!(
+            "SELECT {WEBHOOK_SUBSCRIPTION_COLUMNS} FROM webhook_subscriptions WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(webhook_subscription_from_row))
+    }
+
+    async fn get_webhook_subscriptions(&self) -> Result<Vec<DbWebhookSubscription>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {WEBHOOK_SUBSCRIPTION_COLUMNS} FROM webhook_subscriptions ORDER BY created_at"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(webhook_subscription_from_row).collect())
+    }
+
+    async fn delete_webhook_subscription(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM webhook_subscriptions WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn claim_webhook_delivery(&self, subscription_id: &str, game_id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT INTO webhook_deliveries (subscription_id, game_id, delivered_at) VALUES ($1, $2, $3) ON CONFLICT (subscription_id, game_id) DO NOTHING",
+        )
+        .bind(subscription_id)
+        .bind(game_id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn release_webhook_delivery(&self, subscription_id: &str, game_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM webhook_deliveries WHERE subscription_id = $1 AND game_id = $2")
+            .bind(subscription_id)
+            .bind(game_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn start_discord_link(
+        &self,
+        discord_user_id: &str,
+        code_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO discord_links (discord_user_id, code_hash, code_expires_at, created_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (discord_user_id) DO UPDATE
+             SET code_hash = EXCLUDED.code_hash, code_expires_at = EXCLUDED.code_expires_at",
+        )
+        .bind(discord_user_id)
+        .bind(code_hash)
+        .bind(expires_at)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn complete_discord_link(
+        &self,
+        code_hash: &str,
+        user_id: &str,
+    ) -> Result<Option<String>> {
+        let discord_user_id = sqlx::query_scalar(
+            "UPDATE discord_links SET user_id = $1, code_hash = NULL, code_expires_at = NULL
+             WHERE code_hash = $2 AND code_expires_at > $3
+             RETURNING discord_user_id",
+        )
+        .bind(user_id)
+        .bind(code_hash)
+        .bind(Utc::now())
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(discord_user_id)
+    }
+
+    async fn get_discord_linked_user(&self, discord_user_id: &str) -> Result<Option<String>> {
+        let user_id: Option<Option<String>> =
+            sqlx::query_scalar("SELECT user_id FROM discord_links WHERE discord_user_id = $1")
+                .bind(discord_user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(user_id.flatten())
+    }
+
+    async fn get_linked_discord_users(&self, user_id: &str) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar(
+            "SELECT discord_user_id FROM discord_links WHERE user_id = $1 ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    async fn record_audit_entry(&self, entry: NewAuditEntry) -> Result<DbAuditEntry> {
+        let entry = DbAuditEntry::from(entry);
+
+        sqlx::query(&format!(
+            "INSERT INTO audit_log ({AUDIT_ENTRY_COLUMNS}) VALUES ($1, $2, $3, $4, $5, $6, $7)"
+        ))
+        .bind(&entry.id)
+        .bind(&entry.actor)
+        .bind(&entry.action)
+        .bind(&entry.target)
+        .bind(&entry.before_data)
+        .bind(&entry.after_data)
+        .bind(entry.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    async fn get_audit_entries(&self, filter: &AuditFilter) -> Result<Vec<DbAuditEntry>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {AUDIT_ENTRY_COLUMNS} FROM audit_log
+             WHERE ($1::TEXT IS NULL OR actor = $1)
+               AND ($2::TEXT IS NULL OR action = $2)
+               AND ($3::TEXT IS NULL OR target = $3)
+               AND ($4::TIMESTAMPTZ IS NULL OR created_at >= $4)
+               AND ($5::TIMESTAMPTZ IS NULL OR created_at < $5)
+             ORDER BY created_at DESC LIMIT $6"
+        ))
+        .bind(&filter.actor)
+        .bind(&filter.action)
+        .bind(&filter.target)
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(filter.limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(audit_entry_from_row).collect())
+    }
+
+    async fn increment_rate_limit_window(
+        &self,
+        bucket: &str,
+        window_start: DateTime<Utc>,
+    ) -> Result<i32> {
+        let row = sqlx::query(
+            "INSERT INTO rate_limit_windows (bucket, window_start, request_count) VALUES ($1, $2, 1)
+             ON CONFLICT (bucket, window_start)
+             DO UPDATE SET request_count = rate_limit_windows.request_count + 1
+             RETURNING request_count",
+        )
+        .bind(bucket)
+        .bind(window_start)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row.get("request_count"))
+    }
+
+    async fn delete_rate_limit_windows_before(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM rate_limit_windows WHERE window_start < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn create_admin_token(&self, token: NewAdminToken) -> Result<DbAdminToken> {
+        let token = DbAdminToken::new(token.token_hash, token.scope, token.label, token.expires_at);
+
+        sqlx::query(
+            "INSERT INTO admin_tokens (id, token_hash, scope, label, created_at, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(&token.id)
+        .bind(&token.token_hash)
+        .bind(&token.scope)
+        .bind(&token.label)
+        .bind(token.created_at)
+        .bind(token.expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    async fn get_admin_token_by_hash(&self, token_hash: &str) -> Result<Option<DbAdminToken>> {
+        let row = sqlx::query(&format!(
+            "SELECT {ADMIN_TOKEN_COLUMNS} FROM admin_tokens WHERE token_hash = $1"
+        ))
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(admin_token_from_row))
+    }
+
+    async fn get_admin_tokens(&self) -> Result<Vec<DbAdminToken>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {ADMIN_TOKEN_COLUMNS} FROM admin_tokens
+             WHERE revoked_at IS NULL ORDER BY created_at DESC"
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(admin_token_from_row).collect())
+    }
+
+    async fn revoke_admin_token(&self, token_id: &str) -> Result<Option<DbAdminToken>> {
+        let row = sqlx::query(&format!(
+            "UPDATE admin_tokens SET revoked_at = $1
+             WHERE id = $2 AND revoked_at IS NULL
+             RETURNING {ADMIN_TOKEN_COLUMNS}"
+        ))
+        .bind(Utc::now())
+        .bind(token_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(admin_token_from_row))
+    }
+
+    async fn mark_game_completed(&self, game_id: &str) -> Result<()> {
+        sqlx::query("UPDATE games SET completed = TRUE, completed_at = $1 WHERE id = $2")
+            .bind(Utc::now())
+            .bind(game_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_incomplete_games_for_date(&self, date: &str) -> Result<Vec<DbGame>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {GAME_COLUMNS} FROM games WHERE date = $1 AND completed = FALSE"
+        ))
+        .bind(date)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(game_from_row).collect())
+    }
+
+    async fn get_incomplete_game_entries_for_game(
+        &self,
+        game_id: &str,
+    ) -> Result<Vec<DbGameEntry>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {GAME_ENTRY_COLUMNS} FROM game_entries WHERE game_id = $1 AND completed IS NOT TRUE"
+        ))
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(game_entry_from_row).collect())
+    }
+
+    async fn mark_game_entry_completed(&self, entry_id: &str) -> Result<()> {
+        sqlx::query("UPDATE game_entries SET completed = TRUE, updated_at = $1 WHERE id = $2")
+            .bind(Utc::now())
+            .bind(entry_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // Statistics operations
+    async fn get_game_stats(
+        &self,
+        game_id: &str,
+        user_score: i32,
+    ) -> Result<(i32, i32, f64, i32, i32)> {
+        // Gather all aggregates in one round trip; COUNT returns BIGINT and
+        // AVG returns NUMERIC in Postgres, so cast them to the types we read
+        let row = sqlx::query(
+            "SELECT
+                COUNT(*)::INTEGER as total_players,
+                COUNT(*) FILTER (WHERE total_score <= $2)::INTEGER as players_at_or_below,
+                AVG(total_score)::FLOAT8 as avg_score,
+                MAX(total_score) as max_score
+             FROM game_entries WHERE game_id = $1 AND completed = TRUE AND off_board = FALSE",
+        )
+        .bind(game_id)
+        .bind(user_score)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let total_players: i32 = row.get("total_players");
+        let players_at_or_below: i32 = row.get("players_at_or_below");
+        let avg_score: Option<f64> = row.get("avg_score");
+        let highest_score: Option<i32> = row.get("max_score");
+
+        // Calculate rank and percentile
+        let user_rank = total_players - players_at_or_below + 1;
+        let percentile = if total_players > 0 {
+            (players_at_or_below as f64 / total_players as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        Ok((
+            total_players,
+            user_rank,
+            percentile,
+            avg_score.unwrap_or(0.0) as i32,
+            highest_score.unwrap_or(0),
+        ))
+    }
+
+    async fn get_player_counts(&self, game_id: &str) -> Result<DbPlayerCounts> {
+        let row = sqlx::query(
+            "SELECT
+                COUNT(*) FILTER (WHERE completed = FALSE)::INTEGER as in_progress,
+                COUNT(*) FILTER (WHERE completed = TRUE)::INTEGER as completed
+             FROM game_entries WHERE game_id = $1",
+        )
+        .bind(game_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(DbPlayerCounts {
+            in_progress: row.get("in_progress"),
+            completed: row.get("completed"),
+        })
+    }
+
+    async fn get_user_history(
+        &self,
+        user_id: &str,
+        after_date: Option<&str>,
+        limit: i32,
+    ) -> Result<Vec<DbHistoryEntry>> {
+        let rows = sqlx::query(
+            "SELECT g.id, g.date, g.sequence_number, e.answers_data, e.total_score, e.completed, e.off_board,
+                CASE WHEN e.completed AND NOT e.off_board THEN (100.0
+                    * (SELECT COUNT(*) FROM game_entries o WHERE o.game_id = e.game_id AND o.completed = TRUE AND o.off_board = FALSE AND o.total_score <= e.total_score)
+                    / (SELECT COUNT(*) FROM game_entries o WHERE o.game_id = e.game_id AND o.completed = TRUE AND o.off_board = FALSE)
+                )::FLOAT8 END as percentile
+             FROM game_entries e
+             JOIN games g ON g.id = e.game_id
+             WHERE e.user_id = $1 AND g.mode = 'daily' AND g.language = 'en' AND ($2::TEXT IS NULL OR g.date > $2)
+             ORDER BY g.date
+             LIMIT $3",
+        )
+        .bind(user_id)
+        .bind(after_date)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbHistoryEntry {
+                game_id: row.get("id"),
+                date: row.get("date"),
+                sequence_number: row.get("sequence_number"),
+                answers_data: row.get("answers_data"),
+                total_score: row.get("total_score"),
+                completed: row.get("completed"),
+                off_board: row.get("off_board"),
+                percentile: row.get("percentile"),
+            })
+            .collect())
+    }
+
+    async fn get_user_entries(&self, user_id: &str) -> Result<Vec<DbUserEntry>> {
+        let rows = sqlx::query(
+            "SELECT g.id, g.date, g.mode, g.sequence_number, e.answers_data, e.total_score,
+                e.completed, e.off_board, e.created_at, e.updated_at
+             FROM game_entries e
+             JOIN games g ON g.id = e.game_id
+             WHERE e.user_id = $1
+             ORDER BY g.date, g.mode",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbUserEntry {
+                game_id: row.get("id"),
+                date: row.get("date"),
+                mode: row.get("mode"),
+                sequence_number: row.get("sequence_number"),
+                answers_data: row.get("answers_data"),
+                total_score: row.get("total_score"),
+                completed: row.get::<Option<bool>, _>("completed").unwrap_or_default(),
+                off_board: row.get("off_board"),
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+            })
+            .collect())
+    }
+
+    async fn get_user_custom_game_scores(&self, user_id: &str) -> Result<Vec<DbCustomGameScore>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {CUSTOM_GAME_SCORE_COLUMNS} FROM custom_game_scores
+             WHERE user_id = $1 ORDER BY created_at"
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(custom_game_score_from_row).collect())
+    }
+
+    async fn record_game_start(&self, user_id: &str, game_id: &str) -> Result<DateTime<Utc>> {
+        // The no-op update makes RETURNING yield the existing row on conflict
+        let row = sqlx::query(
+            "INSERT INTO game_starts (user_id, game_id, started_at) VALUES ($1, $2, $3)
+             ON CONFLICT (user_id, game_id) DO UPDATE SET started_at = game_starts.started_at
+             RETURNING started_at",
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row.get("started_at"))
+    }
+
+    async fn get_game_start(&self, user_id: &str, game_id: &str) -> Result<Option<DateTime<Utc>>> {
+        let started_at = sqlx::query_scalar(
+            "SELECT started_at FROM game_starts WHERE user_id = $1 AND game_id = $2",
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(started_at)
+    }
+
+    async fn get_blitz_runs(&self, user_id: &str) -> Result<Vec<DbBlitzRun>> {
+        let rows = sqlx::query(
+            "SELECT g.id, g.date, g.time_limit_seconds, e.answers_data, e.total_score, e.completed_at, s.started_at
+             FROM game_entries e
+             JOIN games g ON g.id = e.game_id
+             JOIN game_starts s ON s.user_id = e.user_id AND s.game_id = e.game_id
+             WHERE e.user_id = $1 AND g.mode = 'blitz' AND g.time_limit_seconds IS NOT NULL
+             ORDER BY g.date",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbBlitzRun {
+                game_id: row.get("id"),
+                date: row.get("date"),
+                answers_data: row.get("answers_data"),
+                total_score: row.get("total_score"),
+                started_at: row.get("started_at"),
+                completed_at: row.get("completed_at"),
+                time_limit_seconds: row.get("time_limit_seconds"),
+            })
+            .collect())
+    }
+
+    async fn get_speed_runs(
+        &self,
+        game_id: &str,
+        min_score: i32,
+        min_elapsed_ms: i64,
+        limit: i32,
+    ) -> Result<Vec<DbSpeedRun>> {
+        let rows = sqlx::query(
+            "SELECT user_id, total_score, elapsed_ms FROM (
+                SELECT e.user_id, e.total_score, e.completed_at,
+                    ROUND(EXTRACT(EPOCH FROM (e.completed_at - s.started_at)) * 1000)::BIGINT AS elapsed_ms
+                FROM game_entries e
+                JOIN game_starts s ON s.user_id = e.user_id AND s.game_id = e.game_id
+                WHERE e.game_id = $1 AND e.completed = TRUE AND NOT e.off_board
+                    AND e.completed_at IS NOT NULL AND e.total_score >= $2
+                    AND s.started_at <= e.created_at
+            ) runs
+            WHERE elapsed_ms >= $3
+            ORDER BY elapsed_ms, completed_at
+            LIMIT $4",
+        )
+        .bind(game_id)
+        .bind(min_score)
+        .bind(min_elapsed_ms)
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbSpeedRun {
+                user_id: row.get("user_id"),
+                total_score: row.get("total_score"),
+                elapsed_ms: row.get("elapsed_ms"),
+            })
+            .collect())
+    }
+
+    async fn get_game_densities(&self, limit: i32) -> Result<Vec<DbGameDensity>> {
+        let rows = sqlx::query(
+            "SELECT g.id, g.date, g.sequence_number, g.threshold_score,
+                d.valid_words, d.words_per_tile, d.average_word_length, d.wildcard_reliance,
+                q.long_words, q.dead_tiles, q.dead_corners, q.unused_wildcards, q.vowel_share, q.issues,
+                COUNT(e.id)::INTEGER as players_started,
+                COUNT(e.id) FILTER (WHERE e.completed = TRUE)::INTEGER as players_completed
+             FROM game_board_density d
+             JOIN games g ON g.id = d.game_id
+             LEFT JOIN game_board_quality q ON q.game_id = g.id
+             LEFT JOIN game_entries e ON e.game_id = g.id
+             GROUP BY g.id, d.game_id, q.game_id
+             ORDER BY g.date DESC
+             LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbGameDensity {
+                game_id: row.get("id"),
+                date: row.get("date"),
+                sequence_number: row.get("sequence_number"),
+                threshold_score: row.get("threshold_score"),
+                density: BoardDensity {
+                    valid_words: row.get("valid_words"),
+                    words_per_tile: row.get("words_per_tile"),
+                    average_word_length: row.get("average_word_length"),
+                    wildcard_reliance: row.get("wildcard_reliance"),
+                },
+                quality: row
+                    .get::<Option<String>, _>("issues")
+                    .map(|issues| BoardQuality {
+                        long_words: row.get("long_words"),
+                        dead_tiles: row.get("dead_tiles"),
+                        dead_corners: row.get("dead_corners"),
+                        unused_wildcards: row.get("unused_wildcards"),
+                        vowel_share: row.get("vowel_share"),
+                        issues: split_issues(&issues),
+                    }),
+                players_started: row.get("players_started"),
+                players_completed: row.get("players_completed"),
+            })
+            .collect())
+    }
+
+    async fn get_game_boards(&self) -> Result<Vec<DbGameBoard>> {
+        let rows = sqlx::query(
+            "SELECT date, generation_version, board_data FROM games WHERE mode = 'daily' AND language = 'en' AND deleted_at IS NULL ORDER BY date",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbGameBoard {
+                date: row.get("date"),
+                generation_version: row.get("generation_version"),
+                board_data: row.get("board_data"),
+            })
+            .collect())
+    }
+
+    async fn get_user_stats(&self, user_id: &str) -> Result<DbUserStats> {
+        let row = sqlx::query(
+            "SELECT
+                COUNT(*)::INTEGER as games_played,
+                AVG(e.total_score)::FLOAT8 as average_score,
+                MAX(e.total_score) as best_score,
+                AVG(100.0
+                    * (SELECT COUNT(*) FROM game_entries o WHERE o.game_id = e.game_id AND o.completed = TRUE AND o.off_board = FALSE AND o.total_score <= e.total_score)
+                    / (SELECT COUNT(*) FROM game_entries o WHERE o.game_id = e.game_id AND o.completed = TRUE AND o.off_board = FALSE)
+                ) FILTER (WHERE e.off_board = FALSE)::FLOAT8 as average_percentile,
+                COUNT(*) FILTER (WHERE e.total_score >= g.threshold_score)::INTEGER as threshold_hits
+             FROM game_entries e
+             JOIN games g ON g.id = e.game_id
+             WHERE e.user_id = $1 AND e.completed = TRUE AND g.mode = 'daily' AND g.language = 'en'",
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(DbUserStats {
+            games_played: row.get("games_played"),
+            average_score: row.get("average_score"),
+            best_score: row.get("best_score"),
+            average_percentile: row.get("average_percentile"),
+            threshold_hits: row.get("threshold_hits"),
+        })
+    }
+
+    async fn get_completed_answers_data(&self, user_id: &str) -> Result<Vec<String>> {
+        let rows = sqlx::query(
+            "SELECT answers_data FROM game_entries WHERE user_id = $1 AND completed = TRUE",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| row.get("answers_data")).collect())
+    }
+
+    async fn get_unfrozen_games_through(&self, date: &str) -> Result<Vec<DbGame>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {GAME_COLUMNS} FROM games
+             WHERE date <= $1 AND id NOT IN (SELECT game_id FROM game_final_stats)
+             ORDER BY date"
+        ))
+        .bind(date)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(game_from_row).collect())
+    }
+
+    async fn freeze_game_stats(&self, game_id: &str) -> Result<Option<DbFinalGameStats>> {
+        // Averages are truncated the same way get_game_stats truncates them
+        let row = sqlx::query(&format!(
+            "INSERT INTO game_final_stats ({FINAL_GAME_STATS_COLUMNS})
+             SELECT $1, COUNT(*)::INTEGER, TRUNC(COALESCE(AVG(total_score), 0))::INTEGER,
+                COALESCE(MAX(total_score), 0), $2
+             FROM game_entries WHERE game_id = $1 AND completed = TRUE AND off_board = FALSE
+             ON CONFLICT (game_id) DO NOTHING
+             RETURNING {FINAL_GAME_STATS_COLUMNS}"
+        ))
+        .bind(game_id)
+        .bind(Utc::now())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(final_game_stats_from_row))
+    }
+
+    async fn get_final_game_stats(&self, game_id: &str) -> Result<Option<DbFinalGameStats>> {
+        let row = sqlx::query(&format!(
+            "SELECT {FINAL_GAME_STATS_COLUMNS} FROM game_final_stats WHERE game_id = $1"
+        ))
+        .bind(game_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(final_game_stats_from_row))
+    }
+
+    async fn get_final_ranks_to_notify(&self, game_id: &str) -> Result<Vec<DbFinalRank>> {
+        let rows = sqlx::query(
+            "SELECT e.user_id, e.total_score,
+                (1 + (SELECT COUNT(*) FROM game_entries o
+                      WHERE o.game_id = e.game_id AND o.completed = TRUE AND o.off_board = FALSE
+                        AND o.total_score > e.total_score))::INTEGER as user_rank
+             FROM game_entries e
+             JOIN notification_preferences p ON p.user_id = e.user_id
+             WHERE e.game_id = $1 AND e.completed = TRUE AND e.off_board = FALSE
+               AND p.final_rank = TRUE
+             ORDER BY user_rank, e.user_id",
+        )
+        .bind(game_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbFinalRank {
+                user_id: row.get("user_id"),
+                total_score: row.get("total_score"),
+                rank: row.get("user_rank"),
+            })
+            .collect())
+    }
+
+    async fn get_games_without_stats_through(&self, date: &str) -> Result<Vec<DbGame>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {GAME_COLUMNS} FROM games
+             WHERE date <= $1 AND id NOT IN (SELECT game_id FROM game_stats)
+             ORDER BY date"
+        ))
+        .bind(date)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(game_from_row).collect())
+    }
+
+    async fn upsert_game_stats(&self, stats: DbGameStats) -> Result<()> {
+        sqlx::query(&format!(
+            "INSERT INTO game_stats ({GAME_STATS_COLUMNS})
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             ON CONFLICT (game_id) DO UPDATE SET
+                players = EXCLUDED.players,
+                completed_players = EXCLUDED.completed_players,
+                median_score = EXCLUDED.median_score,
+                optimal_score = EXCLUDED.optimal_score,
+                completion_rate = EXCLUDED.completion_rate,
+                threshold_hit_rate = EXCLUDED.threshold_hit_rate,
+                computed_at = EXCLUDED.computed_at"
+        ))
+        .bind(&stats.game_id)
+        .bind(stats.players)
+        .bind(stats.completed_players)
+        .bind(stats.median_score)
+        .bind(stats.optimal_score)
+        .bind(stats.completion_rate)
+        .bind(stats.threshold_hit_rate)
+        .bind(stats.computed_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_recent_game_stats(&self, limit: i32) -> Result<Vec<DbGameStats>> {
+        let rows = sqlx::query(
+            "SELECT s.game_id, s.players, s.completed_players, s.median_score, s.optimal_score,
+                s.completion_rate, s.threshold_hit_rate, s.computed_at
+             FROM game_stats s
+             JOIN games g ON g.id = s.game_id
+             WHERE g.mode = 'daily' AND g.language = 'en' AND g.deleted_at IS NULL
+             ORDER BY g.date DESC
+             LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(game_stats_from_row).collect())
+    }
+
+    async fn get_notification_preferences(
+        &self,
+        user_id: &str,
+    ) -> Result<DbNotificationPreferences> {
+        let row = sqlx::query("SELECT final_rank FROM notification_preferences WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(match row {
+            Some(row) => DbNotificationPreferences {
+                user_id: user_id.to_string(),
+                final_rank: row.get("final_rank"),
+            },
+            None => DbNotificationPreferences::none(user_id.to_string()),
+        })
+    }
+
+    async fn set_notification_preferences(
+        &self,
+        preferences: DbNotificationPreferences,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO notification_preferences (user_id, final_rank, updated_at) VALUES ($1, $2, $3)
+             ON CONFLICT (user_id) DO UPDATE SET
+                final_rank = EXCLUDED.final_rank,
+                updated_at = EXCLUDED.updated_at",
+        )
+        .bind(&preferences.user_id)
+        .bind(preferences.final_rank)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn create_notification(&self, notification: NewNotification) -> Result<DbNotification> {
+        let notification = DbNotification::new(notification);
+
+        sqlx::query(&format!(
+            "INSERT INTO notifications ({NOTIFICATION_COLUMNS}) VALUES ($1, $2, $3, $4, $5, $6, $7)"
+        ))
+        .bind(&notification.id)
+        .bind(&notification.user_id)
+        .bind(&notification.kind)
+        .bind(&notification.game_id)
+        .bind(&notification.message)
+        .bind(notification.created_at)
+        .bind(notification.read_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(notification)
+    }
+
+    async fn get_notifications(&self, user_id: &str, limit: i32) -> Result<Vec<DbNotification>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {NOTIFICATION_COLUMNS} FROM notifications
+             WHERE user_id = $1 ORDER BY created_at DESC LIMIT $2"
+        ))
+        .bind(user_id)
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(notification_from_row).collect())
+    }
+
+    async fn mark_notifications_read(&self, user_id: &str) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE notifications SET read_at = $1 WHERE user_id = $2 AND read_at IS NULL",
+        )
+        .bind(Utc::now())
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn get_pending_outbox_events(
+        &self,
+        max_attempts: i32,
+        limit: i32,
+    ) -> Result<Vec<DbOutboxEvent>> {
+        let rows = sqlx::query(
+            "SELECT id, event_type, payload, attempts, created_at FROM outbox_events
+             WHERE processed_at IS NULL AND attempts < $1 ORDER BY created_at LIMIT $2",
+        )
+        .bind(max_attempts)
+        .bind(i64::from(limit))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbOutboxEvent {
+                id: row.get("id"),
+                event_type: row.get("event_type"),
+                payload: row.get("payload"),
+                attempts: row.get("attempts"),
+                created_at: row.get("created_at"),
+            })
+            .collect())
+    }
+
+    async fn mark_outbox_event_processed(&self, event_id: &str) -> Result<()> {
+        sqlx::query("UPDATE outbox_events SET processed_at = $1 WHERE id = $2")
+            .bind(Utc::now())
+            .bind(event_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn record_outbox_event_failure(&self, event_id: &str, error: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE outbox_events SET attempts = attempts + 1, last_error = $1 WHERE id = $2",
+        )
+        .bind(error)
+        .bind(event_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_processed_outbox_events(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM outbox_events WHERE processed_at < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn close(&self) {
+        self.pool.close().await;
+    }
+}
+
+/// insert_board_density records a generated game's density using the
+/// caller's transaction
+async fn insert_board_density(
+    conn: &mut sqlx::PgConnection,
+    game_id: &str,
+    density: &BoardDensity,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO game_board_density (game_id, valid_words, words_per_tile, average_word_length, wildcard_reliance, created_at) VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(game_id)
+    .bind(density.valid_words)
+    .bind(density.words_per_tile)
+    .bind(density.average_word_length)
+    .bind(density.wildcard_reliance)
+    .bind(Utc::now())
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// insert_board_quality records what the quality analyzer found on a
+/// generated game's board using the caller's transaction
+async fn insert_board_quality(
+    conn: &mut sqlx::PgConnection,
+    game_id: &str,
+    quality: &BoardQuality,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO game_board_quality (game_id, long_words, dead_tiles, dead_corners, unused_wildcards, vowel_share, issues, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+    )
+    .bind(game_id)
+    .bind(quality.long_words)
+    .bind(quality.dead_tiles)
+    .bind(quality.dead_corners)
+    .bind(quality.unused_wildcards)
+    .bind(quality.vowel_share)
+    .bind(join_issues(&quality.issues))
+    .bind(Utc::now())
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// write_entry_answers replaces the rows of game_entry_answers for an entry
+/// with the answers in its answers_data, using the caller's transaction.
+/// answers_data stays the record of an entry, so data that can't be split is
+/// logged and leaves the entry without rows rather than failing the write.
+async fn write_entry_answers(
+    conn: &mut sqlx::PgConnection,
+    entry_id: &str,
+    answers_data: &str,
+) -> Result<()> {
+    sqlx::query("DELETE FROM game_entry_answers WHERE entry_id = $1")
+        .bind(entry_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let answers = match AnswerStorage::entry_answers(answers_data) {
+        Ok(answers) => answers,
+        Err(e) => {
+            warn!(
+                "Not splitting unreadable answers of entry {}: {}",
+                entry_id, e
+            );
+            return Ok(());
+        }
+    };
+    for answer in answers {
+        sqlx::query(
+            "INSERT INTO game_entry_answers (entry_id, position, word, score, path_json) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(entry_id)
+        .bind(answer.position)
+        .bind(&answer.word)
+        .bind(answer.score)
+        .bind(&answer.path_json)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// insert_game_with_answers writes a game, its answers and its optimal solution
+/// using the caller's transaction
+async fn insert_game_with_answers(
+    conn: &mut sqlx::PgConnection,
+    game: DbGame,
+    mut game_answers: Vec<NewGameAnswer>,
+    optimal_solution: Option<NewOptimalSolution>,
+) -> Result<(DbGame, Vec<DbGameAnswer>)> {
+    // Create the game first
+    sqlx::query(&format!(
+        "INSERT INTO games ({GAME_COLUMNS}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)"
+    ))
+    .bind(&game.id)
+    .bind(&game.date)
+    .bind(&game.board_data)
+    .bind(game.threshold_score)
+    .bind(game.sequence_number)
+    .bind(game.generation_version)
+    .bind(&game.difficulty)
+    .bind(&game.theme)
+    .bind(game.scoring_scheme_version)
+    .bind(&game.bonus_rules)
+    .bind(&game.game_rules)
+    .bind(&game.mode)
+    .bind(game.time_limit_seconds)
+    .bind(&game.language)
+    .bind(game.revision)
+    .bind(game.deleted_at)
+    .bind(game.completed)
+    .bind(game.completed_at)
+    .bind(game.created_at)
+    .execute(&mut *conn)
+    .await?;
+
+    // Update all game_answers to use the actual game ID
+    for answer in &mut game_answers {
+        answer.game_id = game.id.clone();
+    }
+
+    // Create all the game answers
+    let mut created_answers = Vec::new();
+    for new_answer in game_answers {
+        let answer = DbGameAnswer::new(new_answer.game_id, new_answer.word, new_answer.path_stats);
+
+        sqlx::query("INSERT INTO game_answers2 (game_id, word, path_count, requires_wildcard, created_at) VALUES ($1, $2, $3, $4, $5)")
+            .bind(&answer.game_id)
+            .bind(&answer.word)
+            .bind(answer.path_stats.map(|stats| stats.path_count))
+            .bind(answer.path_stats.map(|stats| stats.requires_wildcard))
+            .bind(answer.created_at)
+            .execute(&mut *conn)
+            .await?;
+
+        created_answers.push(answer);
+    }
+
+    // Create the optimal solution if provided
+    if let Some(mut optimal_sol) = optimal_solution {
+        optimal_sol.game_id = game.id.clone();
+        let solution = DbOptimalSolution::new(
+            optimal_sol.game_id,
+            optimal_sol.words_and_scores,
+            optimal_sol.total_score,
+        );
+
+        sqlx::query(
+            "INSERT INTO optimal_solutions (id, game_id, words_and_scores, total_score, created_at) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(&solution.id)
+        .bind(&solution.game_id)
+        .bind(&solution.words_and_scores)
+        .bind(solution.total_score)
+        .bind(solution.created_at)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok((game, created_answers))
+}
+
+#[cfg(all(test, feature = "postgres-tests"))]
+mod tests {
+    use sqlx::{Pool, Postgres};
+
+    use super::*;
+
+    fn new_game(date: &str, sequence_number: i32) -> NewGame {
+        NewGame {
+            date: date.to_string(),
+            board_data: "{}".to_string(),
+            threshold_score: 40,
+            sequence_number,
+            generation_version: 1,
+            difficulty: None,
+            theme: None,
+            scoring_scheme_version: 1,
+            bonus_rules: None,
+            game_rules: None,
+            mode: "daily".to_string(),
+            time_limit_seconds: None,
+            language: "en".to_string(),
+        }
+    }
+
+    #[sqlx::test(migrations = "migrations/postgres")]
+    async fn test_create_and_get_game(pool: Pool<Postgres>) {
+        let repo = PgRepository::new(pool);
+
+        assert_eq!(repo.get_next_sequence_number().await.unwrap(), 1);
+        assert!(!repo.game_exists_for_date("2025-06-08").await.unwrap());
+
+        let answers = vec![NewGameAnswer {
+            game_id: String::new(),
+            word: "test".to_string(),
+            path_stats: None,
+        }];
+        let (created, _) = repo
+            .create_game_with_answers(new_game("2025-06-08", 1), answers, None)
+            .await
+            .unwrap();
+
+        let game = repo.get_game_by_date("2025-06-08").await.unwrap().unwrap();
+        assert_eq!(game.id, created.id);
+        assert!(!game.completed);
+        assert!(repo.game_exists_for_date("2025-06-08").await.unwrap());
+        assert_eq!(repo.get_next_sequence_number().await.unwrap(), 2);
+        assert_eq!(repo.get_game_words(&game.id).await.unwrap(), vec!["test"]);
+
+        repo.mark_game_completed(&game.id).await.unwrap();
+        let game = repo.get_game_by_sequence_number(1).await.unwrap().unwrap();
+        assert!(game.completed);
+        assert!(game.completed_at.is_some());
+    }
+
+    #[sqlx::test(migrations = "migrations/postgres")]
+    async fn test_game_entry_upsert_and_stats(pool: Pool<Postgres>) {
+        let repo = PgRepository::new(pool);
+        let (game, _) = repo
+            .create_game_with_answers(new_game("2025-06-08", 1), vec![], None)
+            .await
+            .unwrap();
+
+        let mut scores = Vec::new();
+        for score in [10, 20, 30] {
+            let user = repo
+                .create_user(NewUser {
+                    cookie_token: format!("token-{score}"),
+                })
+                .await
+                .unwrap();
+            let first = repo
+                .create_or_update_game_entry(NewGameEntry {
+                    user_id: user.id.clone(),
+                    game_id: game.id.clone(),
+                    answers_data: "[]".to_string(),
+                    total_score: 0,
+                    completed: false,
+                    suspicion_score: 0,
+                    exclude: false,
+                    request_id: None,
+                })
+                .await
+                .unwrap();
+            let updated = repo
+                .create_or_update_game_entry(NewGameEntry {
+                    user_id: user.id,
+                    game_id: game.id.clone(),
+                    answers_data: "[]".to_string(),
+                    total_score: score,
+                    completed: true,
+                    suspicion_score: 0,
+                    exclude: false,
+                    request_id: None,
+                })
+                .await
+                .unwrap();
+            assert_eq!(first.id, updated.id);
+            scores.push(updated.total_score);
+        }
+
+        let mut distribution = repo.get_score_distribution(&game.id).await.unwrap();
+        distribution.sort();
+        assert_eq!(distribution, scores);
+
+        let (total, rank, _, average, highest) = repo.get_game_stats(&game.id, 20).await.unwrap();
+        assert_eq!((total, rank, average, highest), (3, 2, 20, 30));
+    }
+}
@@ -1,23 +1,99 @@
 use anyhow::Result;
 use axum::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use sqlx::{Row, SqlitePool};
+use tracing::warn;
 
-use crate::db::Repository;
+use crate::db::{
+    conversions::AnswerStorage,
+    storage_types::{DbCompactedAnswers, DbStoredAnswers},
+    Repository,
+};
+use crate::game::density::BoardDensity;
+use crate::game::language::Language;
+use crate::game::mode::GameMode;
+use crate::game::quality::{join_issues, split_issues, BoardQuality};
 
 use super::models::{
-    DbGame, DbGameAnswer, DbGameEntry, DbOptimalSolution, DbUser, NewGame, NewGameAnswer,
-    NewGameEntry, NewOptimalSolution, NewUser, OptimalAnswer,
+    AuditFilter, DbAccount, DbAdminToken, DbArchiveGame, DbAuditEntry, DbBlitzRun, DbCustomGame,
+    DbCustomGameScore, DbEntryRevision, DbFinalGameStats, DbFinalRank, DbGame, DbGameAnswer,
+    DbGameBoard, DbGameDensity, DbGameEntry, DbGameStats, DbHintUsage, DbHistoryEntry, DbMagicLink,
+    DbNotification, DbNotificationPreferences, DbOptimalSolution, DbOutboxEvent, DbPage, DbPasskey,
+    DbPlayerCounts, DbPracticeGame, DbSpeedRun, DbTournament, DbTournamentResult,
+    DbTournamentStanding, DbUser, DbUserDeletion, DbUserEntry, DbUserSession, DbUserStats,
+    DbWebhookSubscription, DbWordCount, DbWordProposal, GameFilter, NewAdminToken, NewAuditEntry,
+    NewCustomGame, NewCustomGameScore, NewGame, NewGameAnswer, NewGameEntry, NewGameWithAnswers,
+    NewHintUsage, NewMagicLink, NewNotification, NewOptimalSolution, NewOutboxEvent, NewPasskey,
+    NewPracticeGame, NewTournament, NewUser, NewUserSession, NewWebhookSubscription, OptimalAnswer,
+    PageRequest, WordPathStats,
 };
 
+const GAME_ENTRY_COLUMNS: &str = "id, user_id, game_id, answers_data, total_score, completed, off_board, suspicion_score, request_id, created_at, updated_at";
+const WEBHOOK_SUBSCRIPTION_COLUMNS: &str = "id, kind, url, secret, created_at";
+const AUDIT_ENTRY_COLUMNS: &str = "id, actor, action, target, before_data, after_data, created_at";
+const CUSTOM_GAME_COLUMNS: &str =
+    "id, slug, board_code, board_data, optimal_score, created_by, created_at";
+const CUSTOM_GAME_SCORE_COLUMNS: &str =
+    "id, custom_game_id, user_id, answers_data, total_score, created_at, updated_at";
+const TOURNAMENT_COLUMNS: &str =
+    "id, name, start_date, end_date, language, created_at, finalized_at";
+const TOURNAMENT_RESULT_COLUMNS: &str = "tournament_id, user_id, rank, total_score, games_played";
+
+/// Each participant's standing in tournament ?1: their best completed,
+/// on-board score on each of its dates, added up
+const TOURNAMENT_STANDINGS_QUERY: &str = "SELECT p.user_id, COALESCE(SUM(d.best_score), 0) AS total_score, COUNT(d.date) AS games_played
+     FROM tournament_participants p
+     LEFT JOIN (
+         SELECT e.user_id, g.date, MAX(e.total_score) AS best_score
+         FROM tournaments t
+         JOIN games g ON g.language = t.language AND g.mode = 'daily' AND g.date BETWEEN t.start_date AND t.end_date
+         JOIN game_entries e ON e.game_id = g.id
+         WHERE t.id = ?1 AND e.completed = 1 AND e.off_board = 0
+         GROUP BY e.user_id, g.date
+     ) d ON d.user_id = p.user_id
+     WHERE p.tournament_id = ?1
+     GROUP BY p.user_id, p.joined_at
+     ORDER BY total_score DESC, p.joined_at ASC";
+
 #[derive(Clone)]
 pub struct SqliteRepository {
+    /// Writes and transactions go through this pool
     pool: SqlitePool,
+    /// Plain reads go through this pool, which is the same as `pool` unless
+    /// a separate one is given
+    reader: SqlitePool,
 }
 
 impl SqliteRepository {
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self {
+            reader: pool.clone(),
+            pool,
+        }
+    }
+
+    /// Send reads to their own pool, so that with WAL they run alongside a
+    /// write instead of queueing behind it on the writer's connections
+    pub fn with_reader(mut self, reader: SqlitePool) -> Self {
+        self.reader = reader;
+        self
+    }
+
+    /// Read a game's compacted answers, if it has been compacted
+    async fn get_compacted_answers(&self, game_id: &str) -> Result<Option<Vec<DbGameAnswer>>> {
+        let blob: Option<Vec<u8>> =
+            sqlx::query_scalar("SELECT answers_blob FROM games WHERE id = ?1")
+                .bind(game_id)
+                .fetch_optional(&self.reader)
+                .await?
+                .flatten();
+
+        blob.map(|blob| {
+            DbCompactedAnswers::decompress(&blob)
+                .map(|compacted| compacted.into_game_answers(game_id))
+                .map_err(|e| anyhow::anyhow!("Failed to read compacted answers: {e}"))
+        })
+        .transpose()
     }
 }
 
@@ -45,7 +121,7 @@ impl Repository for SqliteRepository {
             "SELECT id, cookie_token, created_at, last_seen FROM users WHERE cookie_token = ?1",
         )
         .bind(cookie_token)
-        .fetch_optional(&self.pool)
+        .fetch_optional(&self.reader)
         .await?;
 
         if let Some(row) = row {
@@ -70,7 +146,7 @@ impl Repository for SqliteRepository {
         let row =
             sqlx::query("SELECT id, cookie_token, created_at, last_seen FROM users WHERE id = ?1")
                 .bind(user_id)
-                .fetch_optional(&self.pool)
+                .fetch_optional(&self.reader)
                 .await?;
 
         if let Some(row) = row {
@@ -101,466 +177,2682 @@ impl Repository for SqliteRepository {
         Ok(())
     }
 
-    async fn get_game_by_date(&self, date: &str) -> Result<Option<DbGame>> {
-        let row = sqlx::query("SELECT id, date, board_data, threshold_score, sequence_number, completed, completed_at, created_at FROM games WHERE date = ?1")
-            .bind(date)
-            .fetch_optional(&self.pool)
+    async fn list_users(&self, page: PageRequest) -> Result<DbPage<DbUser>> {
+        let rows = sqlx::query(
+            "SELECT id, cookie_token, created_at, last_seen FROM users ORDER BY created_at, id LIMIT ?1 OFFSET ?2",
+        )
+        .bind(page.limit)
+        .bind(page.offset)
+        .fetch_all(&self.reader)
+        .await?;
+        let total = sqlx::query("SELECT COUNT(*) as count FROM users")
+            .fetch_one(&self.reader)
             .await?;
 
-        if let Some(row) = row {
-            Ok(Some(DbGame {
-                id: row.get("id"),
-                date: row.get("date"),
-                board_data: row.get("board_data"),
-                threshold_score: row.get("threshold_score"),
-                sequence_number: row.get("sequence_number"),
-                completed: row.get::<i32, _>("completed") != 0,
-                completed_at: row
-                    .get::<Option<String>, _>("completed_at")
-                    .map(|s| {
-                        chrono::DateTime::parse_from_rfc3339(&s).map(|dt| dt.with_timezone(&Utc))
-                    })
-                    .transpose()?,
-                created_at: chrono::DateTime::parse_from_rfc3339(
-                    &row.get::<String, _>("created_at"),
-                )?
-                .with_timezone(&Utc),
-            }))
-        } else {
-            Ok(None)
-        }
+        Ok(DbPage {
+            items: rows.iter().map(user_from_row).collect::<Result<_>>()?,
+            total: total.get("count"),
+        })
     }
 
-    async fn get_game_by_id(&self, game_id: &str) -> Result<Option<DbGame>> {
-        let row = sqlx::query("SELECT id, date, board_data, threshold_score, sequence_number, completed, completed_at, created_at FROM games WHERE id = ?1")
-            .bind(game_id)
-            .fetch_optional(&self.pool)
+    async fn touch_user_session(&self, session: NewUserSession) -> Result<DbUserSession> {
+        let session = DbUserSession::new(
+            session.user_id,
+            session.session_hash,
+            session.user_agent_hash,
+        );
+
+        let row = sqlx::query("INSERT INTO user_sessions (id, user_id, session_hash, user_agent_hash, first_seen, last_seen, revoked_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7) ON CONFLICT (user_id, session_hash) DO UPDATE SET user_agent_hash = excluded.user_agent_hash, last_seen = excluded.last_seen RETURNING id, user_id, session_hash, user_agent_hash, first_seen, last_seen, revoked_at")
+            .bind(&session.id)
+            .bind(&session.user_id)
+            .bind(&session.session_hash)
+            .bind(&session.user_agent_hash)
+            .bind(session.first_seen.to_rfc3339())
+            .bind(session.last_seen.to_rfc3339())
+            .bind(session.revoked_at.map(|dt| dt.to_rfc3339()))
+            .fetch_one(&self.pool)
             .await?;
 
-        if let Some(row) = row {
-            Ok(Some(DbGame {
-                id: row.get("id"),
-                date: row.get("date"),
-                board_data: row.get("board_data"),
-                threshold_score: row.get("threshold_score"),
-                sequence_number: row.get("sequence_number"),
-                completed: row.get::<i32, _>("completed") != 0,
-                completed_at: row
-                    .get::<Option<String>, _>("completed_at")
-                    .map(|s| {
-                        chrono::DateTime::parse_from_rfc3339(&s).map(|dt| dt.with_timezone(&Utc))
-                    })
-                    .transpose()?,
-                created_at: chrono::DateTime::parse_from_rfc3339(
-                    &row.get::<String, _>("created_at"),
-                )?
-                .with_timezone(&Utc),
-            }))
-        } else {
-            Ok(None)
-        }
+        user_session_from_row(&row)
     }
 
-    async fn get_game_by_sequence_number(&self, sequence_number: i32) -> Result<Option<DbGame>> {
-        let row = sqlx::query("SELECT id, date, board_data, threshold_score, sequence_number, completed, completed_at, created_at FROM games WHERE sequence_number = ?1")
-            .bind(sequence_number)
-            .fetch_optional(&self.pool)
+    async fn get_user_sessions(&self, user_id: &str) -> Result<Vec<DbUserSession>> {
+        let rows = sqlx::query("SELECT id, user_id, session_hash, user_agent_hash, first_seen, last_seen, revoked_at FROM user_sessions WHERE user_id = ?1 AND revoked_at IS NULL ORDER BY last_seen DESC")
+            .bind(user_id)
+            .fetch_all(&self.reader)
             .await?;
 
-        if let Some(row) = row {
-            Ok(Some(DbGame {
-                id: row.get("id"),
-                date: row.get("date"),
-                board_data: row.get("board_data"),
-                threshold_score: row.get("threshold_score"),
-                sequence_number: row.get("sequence_number"),
-                completed: row.get::<i32, _>("completed") != 0,
-                completed_at: row
-                    .get::<Option<String>, _>("completed_at")
-                    .map(|s| {
-                        chrono::DateTime::parse_from_rfc3339(&s).map(|dt| dt.with_timezone(&Utc))
-                    })
-                    .transpose()?,
-                created_at: chrono::DateTime::parse_from_rfc3339(
-                    &row.get::<String, _>("created_at"),
-                )?
-                .with_timezone(&Utc),
-            }))
-        } else {
-            Ok(None)
-        }
+        rows.iter().map(user_session_from_row).collect()
     }
 
-    async fn game_exists_for_date(&self, date: &str) -> Result<bool> {
-        let row = sqlx::query("SELECT COUNT(*) as count FROM games WHERE date = ?1")
-            .bind(date)
-            .fetch_one(&self.pool)
+    async fn revoke_user_session(
+        &self,
+        user_id: &str,
+        session_id: &str,
+    ) -> Result<Option<DbUserSession>> {
+        let row = sqlx::query("UPDATE user_sessions SET revoked_at = ?1 WHERE id = ?2 AND user_id = ?3 AND revoked_at IS NULL RETURNING id, user_id, session_hash, user_agent_hash, first_seen, last_seen, revoked_at")
+            .bind(Utc::now().to_rfc3339())
+            .bind(session_id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
             .await?;
 
-        let count: i32 = row.get("count");
-        Ok(count > 0)
+        row.as_ref().map(user_session_from_row).transpose()
     }
 
-    async fn get_next_sequence_number(&self) -> Result<i32> {
-        let row = sqlx::query("SELECT MAX(sequence_number) as max_seq FROM games")
-            .fetch_one(&self.pool)
+    async fn get_revoked_session_hashes(&self, since: DateTime<Utc>) -> Result<Vec<String>> {
+        let rows = sqlx::query("SELECT session_hash FROM user_sessions WHERE revoked_at >= ?1")
+            .bind(since.to_rfc3339())
+            .fetch_all(&self.reader)
             .await?;
 
-        let max_sequence: Option<i32> = row.get("max_seq");
-        Ok(max_sequence.unwrap_or(0) + 1)
+        Ok(rows.iter().map(|row| row.get("session_hash")).collect())
     }
 
-    // Game entry operations
-    async fn create_or_update_game_entry(&self, new_entry: NewGameEntry) -> Result<DbGameEntry> {
-        // Check if entry already exists
-        let existing = self
-            .get_game_entry(&new_entry.user_id, &new_entry.game_id)
+    async fn get_or_create_account(&self, user_id: &str) -> Result<DbAccount> {
+        let account = DbAccount::new(user_id.to_string());
+
+        sqlx::query("INSERT INTO accounts (id, user_id, email, created_at) VALUES (?1, ?2, ?3, ?4) ON CONFLICT (user_id) DO NOTHING")
+            .bind(&account.id)
+            .bind(&account.user_id)
+            .bind(&account.email)
+            .bind(account.created_at.to_rfc3339())
+            .execute(&self.pool)
             .await?;
 
-        if let Some(existing_entry) = existing {
-            // Update existing entry
-            let now = Utc::now();
-            sqlx::query("UPDATE game_entries SET answers_data = ?1, total_score = ?2, completed = ?3, updated_at = ?4 WHERE id = ?5")
-                .bind(&new_entry.answers_data)
-                .bind(new_entry.total_score)
-                .bind(if new_entry.completed { 1 } else { 0 })
-                .bind(now.to_rfc3339())
-                .bind(&existing_entry.id)
-                .execute(&self.pool)
+        self.get_account_by_user(user_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("account for user {user_id} missing after insert"))
+    }
+
+    async fn get_account_by_user(&self, user_id: &str) -> Result<Option<DbAccount>> {
+        let row =
+            sqlx::query("SELECT id, user_id, email, created_at FROM accounts WHERE user_id = ?1")
+                .bind(user_id)
+                .fetch_optional(&self.reader)
                 .await?;
 
-            Ok(DbGameEntry {
-                id: existing_entry.id,
-                user_id: new_entry.user_id,
-                game_id: new_entry.game_id,
-                answers_data: new_entry.answers_data,
-                total_score: new_entry.total_score,
-                completed: new_entry.completed,
-                created_at: existing_entry.created_at,
-                updated_at: now,
-            })
-        } else {
-            // Create new entry
-            let entry = DbGameEntry::new(
-                new_entry.user_id,
-                new_entry.game_id,
-                new_entry.answers_data,
-                new_entry.total_score,
-                new_entry.completed,
-            );
+        row.as_ref().map(account_from_row).transpose()
+    }
 
-            sqlx::query("INSERT INTO game_entries (id, user_id, game_id, answers_data, total_score, completed, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)")
-                .bind(&entry.id)
-                .bind(&entry.user_id)
-                .bind(&entry.game_id)
-                .bind(&entry.answers_data)
-                .bind(entry.total_score)
-                .bind(if entry.completed { 1 } else { 0 })
-                .bind(entry.created_at.to_rfc3339())
-                .bind(entry.updated_at.to_rfc3339())
-                .execute(&self.pool)
+    async fn get_account_by_email(&self, email: &str) -> Result<Option<DbAccount>> {
+        let row =
+            sqlx::query("SELECT id, user_id, email, created_at FROM accounts WHERE email = ?1")
+                .bind(email)
+                .fetch_optional(&self.reader)
                 .await?;
 
-            Ok(entry)
-        }
+        row.as_ref().map(account_from_row).transpose()
     }
 
-    async fn get_game_entry(&self, user_id: &str, game_id: &str) -> Result<Option<DbGameEntry>> {
-        let row = sqlx::query("SELECT id, user_id, game_id, answers_data, total_score, completed, created_at, updated_at FROM game_entries WHERE user_id = ?1 AND game_id = ?2")
-            .bind(user_id)
-            .bind(game_id)
-            .fetch_optional(&self.pool)
+    async fn get_account_by_id(&self, account_id: &str) -> Result<Option<DbAccount>> {
+        let row = sqlx::query("SELECT id, user_id, email, created_at FROM accounts WHERE id = ?1")
+            .bind(account_id)
+            .fetch_optional(&self.reader)
             .await?;
 
-        if let Some(row) = row {
-            Ok(Some(DbGameEntry {
-                id: row.get("id"),
-                user_id: row.get("user_id"),
-                game_id: row.get("game_id"),
-                answers_data: row.get("answers_data"),
-                total_score: row.get("total_score"),
-                completed: row.get::<i32, _>("completed") != 0,
-                created_at: chrono::DateTime::parse_from_rfc3339(
-                    &row.get::<String, _>("created_at"),
-                )?
-                .with_timezone(&Utc),
-                updated_at: chrono::DateTime::parse_from_rfc3339(
-                    &row.get::<String, _>("updated_at"),
-                )?
-                .with_timezone(&Utc),
-            }))
-        } else {
-            Ok(None)
-        }
+        row.as_ref().map(account_from_row).transpose()
     }
 
-    // Create game and answers atomically
-    async fn create_game_with_answers(
-        &self,
-        new_game: NewGame,
-        mut game_answers: Vec<NewGameAnswer>,
-        optimal_solution: Option<NewOptimalSolution>,
-    ) -> Result<(DbGame, Vec<DbGameAnswer>)> {
-        let mut tx = self.pool.begin().await?;
-
-        // Create the game first
-        let game = DbGame::new(
-            new_game.date,
-            new_game.board_data,
-            new_game.threshold_score,
-            new_game.sequence_number,
-        );
-
-        sqlx::query("INSERT INTO games (id, date, board_data, threshold_score, sequence_number, completed, completed_at, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)")
-            .bind(&game.id)
-            .bind(&game.date)
-            .bind(&game.board_data)
-            .bind(game.threshold_score)
-            .bind(game.sequence_number)
-            .bind(if game.completed { 1 } else { 0 })
-            .bind(game.completed_at.map(|dt| dt.to_rfc3339()))
-            .bind(game.created_at.to_rfc3339())
-            .execute(&mut *tx)
+    async fn set_account_email(&self, account_id: &str, email: &str) -> Result<()> {
+        sqlx::query("UPDATE accounts SET email = ?1 WHERE id = ?2")
+            .bind(email)
+            .bind(account_id)
+            .execute(&self.pool)
             .await?;
+        Ok(())
+    }
 
-        // Update all game_answers to use the actual game ID
-        for answer in &mut game_answers {
-            answer.game_id = game.id.clone();
-        }
-
-        // Create all the game answers
-        let mut created_answers = Vec::new();
-        for new_answer in game_answers {
-            let answer = DbGameAnswer::new(new_answer.game_id, new_answer.word);
-
-            sqlx::query(
-                "INSERT INTO game_answers2 (game_id, word, created_at) VALUES (?1, ?2, ?3)",
-            )
-            .bind(&answer.game_id)
-            .bind(&answer.word)
-            .bind(answer.created_at.to_rfc3339())
-            .execute(&mut *tx)
+    async fn create_magic_link(&self, link: NewMagicLink) -> Result<DbMagicLink> {
+        let row = sqlx::query("INSERT INTO account_magic_links (token_hash, email, user_id, expires_at, created_at) VALUES (?1, ?2, ?3, ?4, ?5) RETURNING token_hash, email, user_id, expires_at, used_at, created_at")
+            .bind(&link.token_hash)
+            .bind(&link.email)
+            .bind(&link.user_id)
+            .bind(link.expires_at.to_rfc3339())
+            .bind(Utc::now().to_rfc3339())
+            .fetch_one(&self.pool)
             .await?;
 
-            created_answers.push(answer);
-        }
-
-        // Create the optimal solution if provided
-        if let Some(mut optimal_sol) = optimal_solution {
-            optimal_sol.game_id = game.id.clone();
-            let solution = DbOptimalSolution::new(
-                optimal_sol.game_id,
-                optimal_sol.words_and_scores,
-                optimal_sol.total_score,
-            );
+        magic_link_from_row(&row)
+    }
 
-            sqlx::query(
-                "INSERT INTO optimal_solutions (id, game_id, words_and_scores, total_score, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
-            )
-            .bind(&solution.id)
-            .bind(&solution.game_id)
-            .bind(&solution.words_and_scores)
-            .bind(solution.total_score)
-            .bind(solution.created_at.to_rfc3339())
-            .execute(&mut *tx)
+    async fn use_magic_link(&self, token_hash: &str) -> Result<Option<DbMagicLink>> {
+        let now = Utc::now().to_rfc3339();
+        let row = sqlx::query("UPDATE account_magic_links SET used_at = ?1 WHERE token_hash = ?2 AND used_at IS NULL AND expires_at > ?1 RETURNING token_hash, email, user_id, expires_at, used_at, created_at")
+            .bind(&now)
+            .bind(token_hash)
+            .fetch_optional(&self.pool)
             .await?;
-        }
 
-        tx.commit().await?;
-        Ok((game, created_answers))
+        row.as_ref().map(magic_link_from_row).transpose()
     }
 
-    async fn get_game_words(&self, game_id: &str) -> Result<Vec<String>> {
-        let rows = sqlx::query("SELECT DISTINCT word FROM game_answers2 WHERE game_id = ?1")
-            .bind(game_id)
-            .fetch_all(&self.pool)
+    async fn add_passkey(&self, passkey: NewPasskey) -> Result<DbPasskey> {
+        let row = sqlx::query("INSERT INTO account_passkeys (credential_id, account_id, public_key, sign_count, created_at) VALUES (?1, ?2, ?3, ?4, ?5) RETURNING credential_id, account_id, public_key, sign_count, created_at, last_used_at")
+            .bind(&passkey.credential_id)
+            .bind(&passkey.account_id)
+            .bind(&passkey.public_key)
+            .bind(passkey.sign_count)
+            .bind(Utc::now().to_rfc3339())
+            .fetch_one(&self.pool)
             .await?;
 
-        let words = rows
-            .into_iter()
-            .map(|row| row.get::<String, _>("word"))
-            .collect();
-
-        Ok(words)
+        passkey_from_row(&row)
     }
 
-    // Get score distribution for a specific game
-    async fn get_score_distribution(&self, game_id: &str) -> Result<Vec<i32>> {
-        let rows = sqlx::query(
-            "SELECT total_score FROM game_entries WHERE game_id = ?1 AND completed = 1",
-        )
-        .bind(game_id)
-        .fetch_all(&self.pool)
-        .await?;
-
-        let scores = rows
-            .into_iter()
-            .map(|row| row.get::<i32, _>("total_score"))
-            .collect();
+    async fn get_passkey(&self, credential_id: &str) -> Result<Option<DbPasskey>> {
+        let row = sqlx::query("SELECT credential_id, account_id, public_key, sign_count, created_at, last_used_at FROM account_passkeys WHERE credential_id = ?1")
+            .bind(credential_id)
+            .fetch_optional(&self.reader)
+            .await?;
 
-        Ok(scores)
+        row.as_ref().map(passkey_from_row).transpose()
     }
 
-    // Get optimal solutions for a specific game
-    async fn get_optimal_solutions(&self, game_id: &str) -> Result<Vec<OptimalAnswer>> {
-        let row = sqlx::query("SELECT words_and_scores FROM optimal_solutions WHERE game_id = ?1")
-            .bind(game_id)
-            .fetch_optional(&self.pool)
+    async fn get_account_passkeys(&self, account_id: &str) -> Result<Vec<DbPasskey>> {
+        let rows = sqlx::query("SELECT credential_id, account_id, public_key, sign_count, created_at, last_used_at FROM account_passkeys WHERE account_id = ?1 ORDER BY created_at")
+            .bind(account_id)
+            .fetch_all(&self.reader)
             .await?;
 
-        if let Some(row) = row {
-            let words_and_scores: String = row.get("words_and_scores");
-            let optimal_answers: Vec<OptimalAnswer> = serde_json::from_str(&words_and_scores)?;
-            Ok(optimal_answers)
-        } else {
-            Ok(Vec::new())
-        }
+        rows.iter().map(passkey_from_row).collect()
     }
 
-    // Completion tracking operations
-    async fn mark_game_completed(&self, game_id: &str) -> Result<()> {
-        let now = Utc::now();
-        sqlx::query("UPDATE games SET completed = 1, completed_at = ?1 WHERE id = ?2")
-            .bind(now.to_rfc3339())
-            .bind(game_id)
+    async fn update_passkey_sign_count(&self, credential_id: &str, sign_count: i64) -> Result<()> {
+        sqlx::query("UPDATE account_passkeys SET sign_count = ?1, last_used_at = ?2 WHERE credential_id = ?3")
+            .bind(sign_count)
+            .bind(Utc::now().to_rfc3339())
+            .bind(credential_id)
             .execute(&self.pool)
             .await?;
         Ok(())
     }
 
-    async fn get_incomplete_games_for_date(&self, date: &str) -> Result<Vec<DbGame>> {
-        let rows = sqlx::query("SELECT id, date, board_data, threshold_score, sequence_number, completed, completed_at, created_at FROM games WHERE date = ?1 AND completed = 0")
-            .bind(date)
-            .fetch_all(&self.pool)
+    async fn merge_users(&self, into_user_id: &str, from_user_id: &str) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        let moved = sqlx::query("UPDATE game_entries SET user_id = ?1 WHERE user_id = ?2 AND game_id NOT IN (SELECT game_id FROM game_entries WHERE user_id = ?1)")
+            .bind(into_user_id)
+            .bind(from_user_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        sqlx::query("UPDATE hint_usage SET user_id = ?1 WHERE user_id = ?2 AND NOT EXISTS (SELECT 1 FROM hint_usage AS kept WHERE kept.user_id = ?1 AND kept.game_id = hint_usage.game_id AND kept.word = hint_usage.word)")
+            .bind(into_user_id)
+            .bind(from_user_id)
+            .execute(&mut *tx)
             .await?;
 
-        let mut games = Vec::new();
-        for row in rows {
-            games.push(DbGame {
-                id: row.get("id"),
-                date: row.get("date"),
-                board_data: row.get("board_data"),
-                threshold_score: row.get("threshold_score"),
-                sequence_number: row.get("sequence_number"),
-                completed: row.get::<i32, _>("completed") != 0,
-                completed_at: row
-                    .get::<Option<String>, _>("completed_at")
-                    .map(|s| {
-                        chrono::DateTime::parse_from_rfc3339(&s).map(|dt| dt.with_timezone(&Utc))
-                    })
-                    .transpose()?,
-                created_at: chrono::DateTime::parse_from_rfc3339(
-                    &row.get::<String, _>("created_at"),
-                )?
-                .with_timezone(&Utc),
-            });
-        }
+        sqlx::query("UPDATE notifications SET user_id = ?1 WHERE user_id = ?2")
+            .bind(into_user_id)
+            .bind(from_user_id)
+            .execute(&mut *tx)
+            .await?;
 
-        Ok(games)
-    }
+        sqlx::query("UPDATE notification_preferences SET user_id = ?1 WHERE user_id = ?2 AND NOT EXISTS (SELECT 1 FROM notification_preferences WHERE user_id = ?1)")
+            .bind(into_user_id)
+            .bind(from_user_id)
+            .execute(&mut *tx)
+            .await?;
 
-    async fn get_incomplete_game_entries_for_game(
-        &self,
-        game_id: &str,
-    ) -> Result<Vec<DbGameEntry>> {
-        let rows = sqlx::query("SELECT id, user_id, game_id, answers_data, total_score, completed, created_at, updated_at FROM game_entries WHERE game_id = ?1 AND completed = 0")
-            .bind(game_id)
-            .fetch_all(&self.pool)
+        sqlx::query("UPDATE game_starts SET user_id = ?1 WHERE user_id = ?2 AND NOT EXISTS (SELECT 1 FROM game_starts AS kept WHERE kept.user_id = ?1 AND kept.game_id = game_starts.game_id)")
+            .bind(into_user_id)
+            .bind(from_user_id)
+            .execute(&mut *tx)
             .await?;
 
-        let mut entries = Vec::new();
-        for row in rows {
-            entries.push(DbGameEntry {
-                id: row.get("id"),
-                user_id: row.get("user_id"),
-                game_id: row.get("game_id"),
+        sqlx::query("UPDATE word_challenges SET user_id = ?1 WHERE user_id = ?2 AND NOT EXISTS (SELECT 1 FROM word_challenges AS kept WHERE kept.user_id = ?1 AND kept.game_id = word_challenges.game_id AND kept.word = word_challenges.word)")
+            .bind(into_user_id)
+            .bind(from_user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE custom_game_scores SET user_id = ?1 WHERE user_id = ?2 AND NOT EXISTS (SELECT 1 FROM custom_game_scores AS kept WHERE kept.user_id = ?1 AND kept.custom_game_id = custom_game_scores.custom_game_id)")
+            .bind(into_user_id)
+            .bind(from_user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE custom_games SET created_by = ?1 WHERE created_by = ?2")
+            .bind(into_user_id)
+            .bind(from_user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE tournament_participants SET user_id = ?1 WHERE user_id = ?2 AND NOT EXISTS (SELECT 1 FROM tournament_participants AS kept WHERE kept.user_id = ?1 AND kept.tournament_id = tournament_participants.tournament_id)")
+            .bind(into_user_id)
+            .bind(from_user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE tournament_results SET user_id = CASE WHEN EXISTS (SELECT 1 FROM tournament_results AS kept WHERE kept.user_id = ?1 AND kept.tournament_id = tournament_results.tournament_id) THEN NULL ELSE ?1 END WHERE user_id = ?2")
+            .bind(into_user_id)
+            .bind(from_user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE discord_links SET user_id = ?1 WHERE user_id = ?2")
+            .bind(into_user_id)
+            .bind(from_user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for table in [
+            "game_entries",
+            "hint_usage",
+            "user_sessions",
+            "account_magic_links",
+            "notification_preferences",
+            "game_starts",
+            "word_challenges",
+            "entry_revisions",
+            "custom_game_scores",
+            "tournament_participants",
+        ] {
+            sqlx::query(&format!("DELETE FROM {table} WHERE user_id = ?1"))
+                .bind(from_user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        sqlx::query("DELETE FROM users WHERE id = ?1")
+            .bind(from_user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(moved)
+    }
+
+    async fn delete_user(&self, user_id: &str) -> Result<DbUserDeletion> {
+        let cleared_answers = DbStoredAnswers {
+            answers: Vec::new(),
+        }
+        .to_json()?;
+        let mut tx = self.pool.begin().await?;
+
+        let anonymous = DbUser::anonymous();
+        sqlx::query(
+            "INSERT INTO users (id, cookie_token, created_at, last_seen) VALUES (?1, ?2, ?3, ?4)",
+        )
+        .bind(&anonymous.id)
+        .bind(&anonymous.cookie_token)
+        .bind(anonymous.created_at.to_rfc3339())
+        .bind(anonymous.last_seen.to_rfc3339())
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM game_entry_answers WHERE entry_id IN (SELECT id FROM game_entries WHERE user_id = ?1 AND completed = 1 AND off_board = 0)")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        let anonymized_entries = sqlx::query("UPDATE game_entries SET user_id = ?1, answers_data = ?2, request_id = NULL WHERE user_id = ?3 AND completed = 1 AND off_board = 0")
+            .bind(&anonymous.id)
+            .bind(&cleared_answers)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        sqlx::query(
+            "UPDATE custom_game_scores SET user_id = ?1, answers_data = ?2 WHERE user_id = ?3",
+        )
+        .bind(&anonymous.id)
+        .bind(&cleared_answers)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM users WHERE id = ?1 AND NOT EXISTS (SELECT 1 FROM game_entries WHERE user_id = ?1) AND NOT EXISTS (SELECT 1 FROM custom_game_scores WHERE user_id = ?1)")
+            .bind(&anonymous.id)
+            .execute(&mut *tx)
+            .await?;
+
+        let deleted_entries = sqlx::query("DELETE FROM game_entries WHERE user_id = ?1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        // Links emailed to the account's address name the address rather than the user
+        sqlx::query("DELETE FROM account_magic_links WHERE user_id = ?1 OR email IN (SELECT email FROM accounts WHERE user_id = ?1)")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM account_passkeys WHERE account_id IN (SELECT id FROM accounts WHERE user_id = ?1)")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE custom_games SET created_by = NULL WHERE created_by = ?1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE tournament_results SET user_id = NULL WHERE user_id = ?1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for table in [
+            "accounts",
+            "hint_usage",
+            "user_sessions",
+            "notification_preferences",
+            "notifications",
+            "game_starts",
+            "word_challenges",
+            "entry_revisions",
+            "discord_links",
+            "tournament_participants",
+        ] {
+            sqlx::query(&format!("DELETE FROM {table} WHERE user_id = ?1"))
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        sqlx::query("DELETE FROM users WHERE id = ?1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(DbUserDeletion {
+            anonymized_entries,
+            deleted_entries,
+        })
+    }
+
+    async fn get_game_by_date(&self, date: &str) -> Result<Option<DbGame>> {
+        self.get_game_by_date_in_mode(date, GameMode::Daily).await
+    }
+
+    async fn get_game_by_date_in_mode(&self, date: &str, mode: GameMode) -> Result<Option<DbGame>> {
+        let row = sqlx::query("SELECT id, date, board_data, threshold_score, sequence_number, generation_version, difficulty, theme, scoring_scheme_version, bonus_rules, game_rules, mode, time_limit_seconds, language, revision, deleted_at, completed, completed_at, created_at FROM games WHERE date = ?1 AND mode = ?2 AND language = 'en' AND deleted_at IS NULL")
+            .bind(date)
+            .bind(mode.as_str())
+            .fetch_optional(&self.reader)
+            .await?;
+
+        row.as_ref().map(game_from_row).transpose()
+    }
+
+    async fn get_game_by_id(&self, game_id: &str) -> Result<Option<DbGame>> {
+        let row = sqlx::query("SELECT id, date, board_data, threshold_score, sequence_number, generation_version, difficulty, theme, scoring_scheme_version, bonus_rules, game_rules, mode, time_limit_seconds, language, revision, deleted_at, completed, completed_at, created_at FROM games WHERE id = ?1")
+            .bind(game_id)
+            .fetch_optional(&self.reader)
+            .await?;
+
+        row.as_ref().map(game_from_row).transpose()
+    }
+
+    async fn get_game_by_sequence_number(&self, sequence_number: i32) -> Result<Option<DbGame>> {
+        let row = sqlx::query("SELECT id, date, board_data, threshold_score, sequence_number, generation_version, difficulty, theme, scoring_scheme_version, bonus_rules, game_rules, mode, time_limit_seconds, language, revision, deleted_at, completed, completed_at, created_at FROM games WHERE sequence_number = ?1 AND mode = 'daily' AND language = 'en' AND deleted_at IS NULL")
+            .bind(sequence_number)
+            .fetch_optional(&self.reader)
+            .await?;
+
+        row.as_ref().map(game_from_row).transpose()
+    }
+
+    async fn game_exists_for_date(&self, date: &str) -> Result<bool> {
+        let row =
+            sqlx::query("SELECT COUNT(*) as count FROM games WHERE date = ?1 AND mode = 'daily' AND language = 'en' AND deleted_at IS NULL")
+                .bind(date)
+                .fetch_one(&self.reader)
+                .await?;
+
+        let count: i32 = row.get("count");
+        Ok(count > 0)
+    }
+
+    async fn get_next_sequence_number(&self) -> Result<i32> {
+        self.get_next_sequence_number_in_mode(GameMode::Daily).await
+    }
+
+    async fn get_game_by_date_in_language(
+        &self,
+        date: &str,
+        language: Language,
+    ) -> Result<Option<DbGame>> {
+        let row = sqlx::query("SELECT id, date, board_data, threshold_score, sequence_number, generation_version, difficulty, theme, scoring_scheme_version, bonus_rules, game_rules, mode, time_limit_seconds, language, revision, deleted_at, completed, completed_at, created_at FROM games WHERE date = ?1 AND mode = 'daily' AND language = ?2 AND deleted_at IS NULL")
+            .bind(date)
+            .bind(language.as_str())
+            .fetch_optional(&self.reader)
+            .await?;
+
+        row.as_ref().map(game_from_row).transpose()
+    }
+
+    async fn get_game_by_sequence_number_in_language(
+        &self,
+        sequence_number: i32,
+        language: Language,
+    ) -> Result<Option<DbGame>> {
+        let row = sqlx::query("SELECT id, date, board_data, threshold_score, sequence_number, generation_version, difficulty, theme, scoring_scheme_version, bonus_rules, game_rules, mode, time_limit_seconds, language, revision, deleted_at, completed, completed_at, created_at FROM games WHERE sequence_number = ?1 AND mode = 'daily' AND language = ?2 AND deleted_at IS NULL")
+            .bind(sequence_number)
+            .bind(language.as_str())
+            .fetch_optional(&self.reader)
+            .await?;
+
+        row.as_ref().map(game_from_row).transpose()
+    }
+
+    async fn get_next_sequence_number_in_language(&self, language: Language) -> Result<i32> {
+        let row = sqlx::query(
+            "SELECT MAX(sequence_number) as max_seq FROM games WHERE mode = 'daily' AND language = ?1",
+        )
+        .bind(language.as_str())
+        .fetch_one(&self.reader)
+        .await?;
+
+        let max_sequence: Option<i32> = row.get("max_seq");
+        Ok(max_sequence.unwrap_or(0) + 1)
+    }
+
+    async fn get_next_sequence_number_in_mode(&self, mode: GameMode) -> Result<i32> {
+        let row = sqlx::query(
+            "SELECT MAX(sequence_number) as max_seq FROM games WHERE mode = ?1 AND language = 'en'",
+        )
+        .bind(mode.as_str())
+        .fetch_one(&self.reader)
+        .await?;
+
+        let max_sequence: Option<i32> = row.get("max_seq");
+        Ok(max_sequence.unwrap_or(0) + 1)
+    }
+
+    async fn get_archive_games(
+        &self,
+        user_id: Option<&str>,
+        latest_date: &str,
+        limit: i32,
+        offset: i32,
+    ) -> Result<Vec<DbArchiveGame>> {
+        let rows = sqlx::query(
+            "SELECT g.id, g.date, g.sequence_number, g.threshold_score,
+                ue.completed as user_completed, ue.total_score as user_score,
+                COALESCE(s.total_players, 0) as total_players, s.average_score
+             FROM games g
+             LEFT JOIN game_entries ue ON ue.game_id = g.id AND ue.user_id = ?1
+             LEFT JOIN (
+                SELECT game_id, COUNT(*) as total_players, AVG(CAST(total_score AS REAL)) as average_score
+                FROM game_entries WHERE completed = 1 AND off_board = 0 GROUP BY game_id
+             ) s ON s.game_id = g.id
+             WHERE g.date <= ?2 AND g.mode = 'daily' AND g.language = 'en' AND g.deleted_at IS NULL
+             ORDER BY g.date DESC
+             LIMIT ?3 OFFSET ?4",
+        )
+        .bind(user_id)
+        .bind(latest_date)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.reader)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbArchiveGame {
+                game_id: row.get("id"),
+                date: row.get("date"),
+                sequence_number: row.get("sequence_number"),
+                threshold_score: row.get("threshold_score"),
+                user_completed: row
+                    .get::<Option<i32>, _>("user_completed")
+                    .is_some_and(|completed| completed != 0),
+                user_score: row.get("user_score"),
+                total_players: row.get("total_players"),
+                average_score: row.get("average_score"),
+            })
+            .collect())
+    }
+
+    async fn count_archive_games(&self, latest_date: &str) -> Result<i32> {
+        let row =
+            sqlx::query("SELECT COUNT(*) as count FROM games WHERE date <= ?1 AND mode = 'daily' AND language = 'en' AND deleted_at IS NULL")
+                .bind(latest_date)
+                .fetch_one(&self.reader)
+                .await?;
+        Ok(row.get("count"))
+    }
+
+    async fn list_games(&self, filter: &GameFilter, page: PageRequest) -> Result<DbPage<DbGame>> {
+        const FILTER: &str = "(?1 IS NULL OR mode = ?1) AND (?2 IS NULL OR language = ?2) AND (?3 IS NULL OR date >= ?3) AND (?4 IS NULL OR date <= ?4) AND (?5 OR deleted_at IS NULL)";
+        let mode = filter.mode.map(|mode| mode.as_str());
+        let language = filter.language.map(|language| language.as_str());
+
+        let rows = sqlx::query(&format!("SELECT id, date, board_data, threshold_score, sequence_number, generation_version, difficulty, theme, scoring_scheme_version, bonus_rules, game_rules, mode, time_limit_seconds, language, revision, deleted_at, completed, completed_at, created_at FROM games WHERE {FILTER} ORDER BY date DESC, created_at DESC, id DESC LIMIT ?6 OFFSET ?7"))
+            .bind(mode)
+            .bind(language)
+            .bind(&filter.from_date)
+            .bind(&filter.to_date)
+            .bind(filter.include_replaced)
+            .bind(page.limit)
+            .bind(page.offset)
+            .fetch_all(&self.reader)
+            .await?;
+        let total = sqlx::query(&format!(
+            "SELECT COUNT(*) as count FROM games WHERE {FILTER}"
+        ))
+        .bind(mode)
+        .bind(language)
+        .bind(&filter.from_date)
+        .bind(&filter.to_date)
+        .bind(filter.include_replaced)
+        .fetch_one(&self.reader)
+        .await?;
+
+        Ok(DbPage {
+            items: rows.iter().map(game_from_row).collect::<Result<_>>()?,
+            total: total.get("count"),
+        })
+    }
+
+    async fn create_practice_game(&self, new_game: NewPracticeGame) -> Result<DbPracticeGame> {
+        let game = DbPracticeGame::new(
+            new_game.seed,
+            new_game.board_data,
+            new_game.threshold_score,
+            new_game.optimal_score,
+            new_game.expires_at,
+        );
+
+        sqlx::query("INSERT INTO practice_games (id, seed, board_data, threshold_score, optimal_score, created_at, expires_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)")
+            .bind(&game.id)
+            .bind(game.seed.to_string())
+            .bind(&game.board_data)
+            .bind(game.threshold_score)
+            .bind(game.optimal_score)
+            .bind(game.created_at.to_rfc3339())
+            .bind(game.expires_at.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(game)
+    }
+
+    async fn get_practice_game(&self, practice_id: &str) -> Result<Option<DbPracticeGame>> {
+        let row = sqlx::query("SELECT id, seed, board_data, threshold_score, optimal_score, created_at, expires_at FROM practice_games WHERE id = ?1 AND expires_at > ?2")
+            .bind(practice_id)
+            .bind(Utc::now().to_rfc3339())
+            .fetch_optional(&self.reader)
+            .await?;
+
+        row.as_ref().map(practice_game_from_row).transpose()
+    }
+
+    async fn delete_expired_practice_games(&self) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM practice_games WHERE expires_at <= ?1")
+            .bind(Utc::now().to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    // Custom game operations
+    async fn create_custom_game(&self, new_game: NewCustomGame) -> Result<DbCustomGame> {
+        let game = DbCustomGame::from(new_game);
+
+        sqlx::query(&format!("INSERT INTO custom_games ({CUSTOM_GAME_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7) ON CONFLICT(board_code) DO NOTHING"))
+            .bind(&game.id)
+            .bind(&game.slug)
+            .bind(&game.board_code)
+            .bind(&game.board_data)
+            .bind(game.optimal_score)
+            .bind(&game.created_by)
+            .bind(game.created_at.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+
+        let row = sqlx::query(&format!(
+            "SELECT {CUSTOM_GAME_COLUMNS} FROM custom_games WHERE board_code = ?1"
+        ))
+        .bind(&game.board_code)
+        .fetch_one(&self.reader)
+        .await?;
+        custom_game_from_row(&row)
+    }
+
+    async fn get_custom_game(&self, slug: &str) -> Result<Option<DbCustomGame>> {
+        let row = sqlx::query(&format!(
+            "SELECT {CUSTOM_GAME_COLUMNS} FROM custom_games WHERE slug = ?1"
+        ))
+        .bind(slug)
+        .fetch_optional(&self.reader)
+        .await?;
+
+        row.as_ref().map(custom_game_from_row).transpose()
+    }
+
+    async fn save_custom_game_score(&self, score: NewCustomGameScore) -> Result<DbCustomGameScore> {
+        let score = DbCustomGameScore::from(score);
+
+        sqlx::query(&format!("INSERT INTO custom_game_scores ({CUSTOM_GAME_SCORE_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7) ON CONFLICT(custom_game_id, user_id) DO UPDATE SET answers_data = excluded.answers_data, total_score = excluded.total_score, updated_at = excluded.updated_at WHERE excluded.total_score > custom_game_scores.total_score"))
+            .bind(&score.id)
+            .bind(&score.custom_game_id)
+            .bind(&score.user_id)
+            .bind(&score.answers_data)
+            .bind(score.total_score)
+            .bind(score.created_at.to_rfc3339())
+            .bind(score.updated_at.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+
+        let row = sqlx::query(&format!("SELECT {CUSTOM_GAME_SCORE_COLUMNS} FROM custom_game_scores WHERE custom_game_id = ?1 AND user_id = ?2"))
+            .bind(&score.custom_game_id)
+            .bind(&score.user_id)
+            .fetch_one(&self.reader)
+            .await?;
+        custom_game_score_from_row(&row)
+    }
+
+    async fn get_custom_game_scores(
+        &self,
+        custom_game_id: &str,
+        limit: i32,
+    ) -> Result<Vec<DbCustomGameScore>> {
+        let rows = sqlx::query(&format!("SELECT {CUSTOM_GAME_SCORE_COLUMNS} FROM custom_game_scores WHERE custom_game_id = ?1 ORDER BY total_score DESC, updated_at ASC LIMIT ?2"))
+            .bind(custom_game_id)
+            .bind(limit)
+            .fetch_all(&self.reader)
+            .await?;
+
+        rows.iter().map(custom_game_score_from_row).collect()
+    }
+
+    async fn create_tournament(&self, new_tournament: NewTournament) -> Result<DbTournament> {
+        let tournament = DbTournament::from(new_tournament);
+
+        sqlx::query(&format!(
+            "INSERT INTO tournaments ({TOURNAMENT_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+        ))
+        .bind(&tournament.id)
+        .bind(&tournament.name)
+        .bind(&tournament.start_date)
+        .bind(&tournament.end_date)
+        .bind(&tournament.language)
+        .bind(tournament.created_at.to_rfc3339())
+        .bind(tournament.finalized_at.map(|dt| dt.to_rfc3339()))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(tournament)
+    }
+
+    async fn get_tournament(&self, tournament_id: &str) -> Result<Option<DbTournament>> {
+        let row = sqlx::query(&format!(
+            "SELECT {TOURNAMENT_COLUMNS} FROM tournaments WHERE id = ?1"
+        ))
+        .bind(tournament_id)
+        .fetch_optional(&self.reader)
+        .await?;
+
+        row.as_ref().map(tournament_from_row).transpose()
+    }
+
+    async fn list_tournaments(&self, limit: i32) -> Result<Vec<DbTournament>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {TOURNAMENT_COLUMNS} FROM tournaments ORDER BY start_date DESC, created_at DESC LIMIT ?1"
+        ))
+        .bind(limit)
+        .fetch_all(&self.reader)
+        .await?;
+
+        rows.iter().map(tournament_from_row).collect()
+    }
+
+    async fn join_tournament(&self, tournament_id: &str, user_id: &str) -> Result<bool> {
+        let joined = sqlx::query("INSERT INTO tournament_participants (tournament_id, user_id, joined_at) VALUES (?1, ?2, ?3) ON CONFLICT (tournament_id, user_id) DO NOTHING")
+            .bind(tournament_id)
+            .bind(user_id)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+
+        Ok(joined > 0)
+    }
+
+    async fn get_tournament_standings(
+        &self,
+        tournament_id: &str,
+    ) -> Result<Vec<DbTournamentStanding>> {
+        let rows = sqlx::query(TOURNAMENT_STANDINGS_QUERY)
+            .bind(tournament_id)
+            .fetch_all(&self.reader)
+            .await?;
+
+        Ok(rows.iter().map(tournament_standing_from_row).collect())
+    }
+
+    async fn get_unfinalized_tournaments_through(&self, date: &str) -> Result<Vec<DbTournament>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {TOURNAMENT_COLUMNS} FROM tournaments WHERE end_date <= ?1 AND finalized_at IS NULL ORDER BY end_date"
+        ))
+        .bind(date)
+        .fetch_all(&self.reader)
+        .await?;
+
+        rows.iter().map(tournament_from_row).collect()
+    }
+
+    async fn finalize_tournament(
+        &self,
+        tournament_id: &str,
+    ) -> Result<Option<Vec<DbTournamentResult>>> {
+        let mut tx = self.pool.begin().await?;
+
+        let finalized = sqlx::query(
+            "UPDATE tournaments SET finalized_at = ?2 WHERE id = ?1 AND finalized_at IS NULL",
+        )
+        .bind(tournament_id)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if finalized == 0 {
+            return Ok(None);
+        }
+
+        let rows = sqlx::query(TOURNAMENT_STANDINGS_QUERY)
+            .bind(tournament_id)
+            .fetch_all(&mut *tx)
+            .await?;
+        let standings: Vec<DbTournamentStanding> =
+            rows.iter().map(tournament_standing_from_row).collect();
+        let results = DbTournamentResult::from_standings(tournament_id, &standings);
+        for result in &results {
+            sqlx::query(&format!(
+                "INSERT INTO tournament_results (id, {TOURNAMENT_RESULT_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6)"
+            ))
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&result.tournament_id)
+            .bind(&result.user_id)
+            .bind(result.rank)
+            .bind(result.total_score)
+            .bind(result.games_played)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(Some(results))
+    }
+
+    async fn get_tournament_results(&self, tournament_id: &str) -> Result<Vec<DbTournamentResult>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {TOURNAMENT_RESULT_COLUMNS} FROM tournament_results WHERE tournament_id = ?1 ORDER BY rank, total_score DESC, games_played DESC"
+        ))
+        .bind(tournament_id)
+        .fetch_all(&self.reader)
+        .await?;
+
+        Ok(rows.iter().map(tournament_result_from_row).collect())
+    }
+
+    // Game entry operations
+    async fn create_or_update_game_entry(&self, new_entry: NewGameEntry) -> Result<DbGameEntry> {
+        self.create_or_update_game_entry_with_events(new_entry, Vec::new())
+            .await
+    }
+
+    async fn create_or_update_game_entry_with_events(
+        &self,
+        new_entry: NewGameEntry,
+        events: Vec<NewOutboxEvent>,
+    ) -> Result<DbGameEntry> {
+        let mut tx = self.pool.begin().await?;
+
+        // Check if entry already exists
+        let existing = sqlx::query(&format!(
+            "SELECT {GAME_ENTRY_COLUMNS} FROM game_entries WHERE user_id = ?1 AND game_id = ?2"
+        ))
+        .bind(&new_entry.user_id)
+        .bind(&new_entry.game_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let entry = if let Some(existing) = existing {
+            let existing = game_entry_from_row(&existing)?;
+            // A retry of the write that produced the entry changes nothing
+            if new_entry.request_id.is_some() && existing.request_id == new_entry.request_id {
+                return Ok(existing);
+            }
+
+            // Update existing entry
+            let now = Utc::now();
+            // Writes after the game's stats were frozen are late plays
+            let row = sqlx::query("UPDATE game_entries SET answers_data = ?1, total_score = ?2, completed = ?3, updated_at = ?4, completed_at = CASE WHEN ?3 = 1 AND completed = 0 THEN ?4 ELSE completed_at END, suspicion_score = MAX(suspicion_score, ?6), off_board = (off_board OR ?7 OR EXISTS (SELECT 1 FROM game_final_stats WHERE game_id = game_entries.game_id)), request_id = ?8 WHERE id = ?5 RETURNING off_board, suspicion_score")
+                .bind(&new_entry.answers_data)
+                .bind(new_entry.total_score)
+                .bind(if new_entry.completed { 1 } else { 0 })
+                .bind(now.to_rfc3339())
+                .bind(&existing.id)
+                .bind(new_entry.suspicion_score)
+                .bind(if new_entry.exclude { 1 } else { 0 })
+                .bind(&new_entry.request_id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+            DbGameEntry {
+                answers_data: new_entry.answers_data,
+                total_score: new_entry.total_score,
+                completed: new_entry.completed,
+                off_board: row.get::<i32, _>("off_board") != 0,
+                suspicion_score: row.get("suspicion_score"),
+                request_id: new_entry.request_id,
+                updated_at: now,
+                ..existing
+            }
+        } else {
+            // Create new entry
+            let mut entry = DbGameEntry::new(
+                new_entry.user_id,
+                new_entry.game_id,
+                new_entry.answers_data,
+                new_entry.total_score,
+                new_entry.completed,
+            );
+
+            entry.suspicion_score = new_entry.suspicion_score;
+            entry.request_id = new_entry.request_id;
+
+            let row = sqlx::query("INSERT INTO game_entries (id, user_id, game_id, answers_data, total_score, completed, created_at, updated_at, completed_at, suspicion_score, off_board, request_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, CASE WHEN ?6 = 1 THEN ?8 END, ?9, (?10 OR EXISTS (SELECT 1 FROM game_final_stats WHERE game_id = ?3)), ?11) RETURNING off_board")
+                .bind(&entry.id)
+                .bind(&entry.user_id)
+                .bind(&entry.game_id)
+                .bind(&entry.answers_data)
+                .bind(entry.total_score)
+                .bind(if entry.completed { 1 } else { 0 })
+                .bind(entry.created_at.to_rfc3339())
+                .bind(entry.updated_at.to_rfc3339())
+                .bind(entry.suspicion_score)
+                .bind(if new_entry.exclude { 1 } else { 0 })
+                .bind(&entry.request_id)
+                .fetch_one(&mut *tx)
+                .await?;
+            entry.off_board = row.get::<i32, _>("off_board") != 0;
+
+            entry
+        };
+        write_entry_answers(&mut tx, &entry.id, &entry.answers_data).await?;
+
+        for event in events {
+            let event = DbOutboxEvent::new(event);
+            sqlx::query("INSERT INTO outbox_events (id, event_type, payload, attempts, created_at) VALUES (?1, ?2, ?3, ?4, ?5)")
+                .bind(&event.id)
+                .bind(&event.event_type)
+                .bind(&event.payload)
+                .bind(event.attempts)
+                .bind(event.created_at.to_rfc3339())
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(entry)
+    }
+
+    async fn get_game_entry(&self, user_id: &str, game_id: &str) -> Result<Option<DbGameEntry>> {
+        let row = sqlx::query(&format!(
+            "SELECT {GAME_ENTRY_COLUMNS} FROM game_entries WHERE user_id = ?1 AND game_id = ?2"
+        ))
+        .bind(user_id)
+        .bind(game_id)
+        .fetch_optional(&self.reader)
+        .await?;
+
+        row.as_ref().map(game_entry_from_row).transpose()
+    }
+
+    async fn list_entries_for_game(
+        &self,
+        game_id: &str,
+        page: PageRequest,
+    ) -> Result<DbPage<DbGameEntry>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {GAME_ENTRY_COLUMNS} FROM game_entries WHERE game_id = ?1 ORDER BY created_at, id LIMIT ?2 OFFSET ?3"
+        ))
+        .bind(game_id)
+        .bind(page.limit)
+        .bind(page.offset)
+        .fetch_all(&self.reader)
+        .await?;
+        let total = sqlx::query("SELECT COUNT(*) as count FROM game_entries WHERE game_id = ?1")
+            .bind(game_id)
+            .fetch_one(&self.reader)
+            .await?;
+
+        Ok(DbPage {
+            items: rows
+                .iter()
+                .map(game_entry_from_row)
+                .collect::<Result<_>>()?,
+            total: total.get("count"),
+        })
+    }
+
+    async fn get_hint_usage(&self, user_id: &str, game_id: &str) -> Result<Vec<DbHintUsage>> {
+        let rows = sqlx::query("SELECT user_id, game_id, word, level, created_at, updated_at FROM hint_usage WHERE user_id = ?1 AND game_id = ?2 ORDER BY created_at, word")
+            .bind(user_id)
+            .bind(game_id)
+            .fetch_all(&self.reader)
+            .await?;
+
+        rows.iter().map(hint_usage_from_row).collect()
+    }
+
+    async fn record_hint_usage(&self, usage: NewHintUsage) -> Result<DbHintUsage> {
+        let usage = DbHintUsage::new(usage.user_id, usage.game_id, usage.word, usage.level);
+
+        // A level is never lowered, so replaying an older request can't hand back hints
+        let row = sqlx::query("INSERT INTO hint_usage (user_id, game_id, word, level, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6) ON CONFLICT (user_id, game_id, word) DO UPDATE SET level = MAX(level, excluded.level), updated_at = excluded.updated_at RETURNING user_id, game_id, word, level, created_at, updated_at")
+            .bind(&usage.user_id)
+            .bind(&usage.game_id)
+            .bind(&usage.word)
+            .bind(usage.level)
+            .bind(usage.created_at.to_rfc3339())
+            .bind(usage.updated_at.to_rfc3339())
+            .fetch_one(&self.pool)
+            .await?;
+
+        hint_usage_from_row(&row)
+    }
+
+    // Create game and answers atomically
+    async fn record_word_challenge(&self, user_id: &str, game_id: &str, word: &str) -> Result<()> {
+        sqlx::query("INSERT INTO word_challenges (user_id, game_id, word, created_at) VALUES (?1, ?2, ?3, ?4) ON CONFLICT (user_id, game_id, word) DO NOTHING")
+            .bind(user_id)
+            .bind(game_id)
+            .bind(word)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_challenged_words(&self, user_id: &str, game_id: &str) -> Result<Vec<String>> {
+        let rows = sqlx::query(
+            "SELECT word FROM word_challenges WHERE user_id = ?1 AND game_id = ?2 ORDER BY word",
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .fetch_all(&self.reader)
+        .await?;
+        Ok(rows.iter().map(|row| row.get("word")).collect())
+    }
+
+    async fn get_wordlist_proposals(&self) -> Result<Vec<DbWordProposal>> {
+        let rows = sqlx::query(
+            "SELECT word, COUNT(*) AS challenges, MIN(created_at) AS first_challenged_at
+            FROM word_challenges
+            GROUP BY word
+            ORDER BY challenges DESC, word",
+        )
+        .fetch_all(&self.reader)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let first_challenged_at: String = row.get("first_challenged_at");
+                Ok(DbWordProposal {
+                    word: row.get("word"),
+                    challenges: row.get("challenges"),
+                    first_challenged_at: chrono::DateTime::parse_from_rfc3339(
+                        &first_challenged_at,
+                    )?
+                    .with_timezone(&Utc),
+                })
+            })
+            .collect()
+    }
+
+    async fn save_entry_revision(
+        &self,
+        user_id: &str,
+        game_id: &str,
+        answers_data: &str,
+        keep: usize,
+    ) -> Result<DbEntryRevision> {
+        let revision = DbEntryRevision::new(
+            user_id.to_string(),
+            game_id.to_string(),
+            answers_data.to_string(),
+        );
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("INSERT INTO entry_revisions (id, user_id, game_id, answers_data, created_at) VALUES (?1, ?2, ?3, ?4, ?5)")
+            .bind(&revision.id)
+            .bind(&revision.user_id)
+            .bind(&revision.game_id)
+            .bind(&revision.answers_data)
+            .bind(revision.created_at.to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM entry_revisions WHERE user_id = ?1 AND game_id = ?2 AND id NOT IN (SELECT id FROM entry_revisions WHERE user_id = ?1 AND game_id = ?2 ORDER BY created_at DESC LIMIT ?3)")
+            .bind(user_id)
+            .bind(game_id)
+            .bind(keep as i64)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(revision)
+    }
+
+    async fn get_entry_revisions(
+        &self,
+        user_id: &str,
+        game_id: &str,
+    ) -> Result<Vec<DbEntryRevision>> {
+        let rows = sqlx::query("SELECT id, user_id, game_id, answers_data, created_at FROM entry_revisions WHERE user_id = ?1 AND game_id = ?2 ORDER BY created_at DESC")
+            .bind(user_id)
+            .bind(game_id)
+            .fetch_all(&self.reader)
+            .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(DbEntryRevision {
+                    id: row.get("id"),
+                    user_id: row.get("user_id"),
+                    game_id: row.get("game_id"),
+                    answers_data: row.get("answers_data"),
+                    created_at: chrono::DateTime::parse_from_rfc3339(
+                        &row.get::<String, _>("created_at"),
+                    )?
+                    .with_timezone(&Utc),
+                })
+            })
+            .collect()
+    }
+
+    async fn create_game_with_answers(
+        &self,
+        new_game: NewGame,
+        game_answers: Vec<NewGameAnswer>,
+        optimal_solution: Option<NewOptimalSolution>,
+    ) -> Result<(DbGame, Vec<DbGameAnswer>)> {
+        let mut tx = self.pool.begin().await?;
+        let created = insert_game_with_answers(
+            &mut tx,
+            DbGame::new(new_game),
+            game_answers,
+            optimal_solution,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    // Create several games and their answers in a single transaction
+    async fn create_games_with_answers(
+        &self,
+        games: Vec<NewGameWithAnswers>,
+    ) -> Result<Vec<DbGame>> {
+        let mut tx = self.pool.begin().await?;
+        let mut created_games = Vec::with_capacity(games.len());
+        for new_game in games {
+            let (game, _answers) = insert_game_with_answers(
+                &mut tx,
+                DbGame::new(new_game.game),
+                new_game.answers,
+                new_game.optimal_solution,
+            )
+            .await?;
+            if let Some(density) = new_game.density {
+                insert_board_density(&mut tx, &game.id, &density).await?;
+            }
+            if let Some(quality) = new_game.quality {
+                insert_board_quality(&mut tx, &game.id, &quality).await?;
+            }
+            created_games.push(game);
+        }
+        tx.commit().await?;
+        Ok(created_games)
+    }
+
+    async fn replace_game(
+        &self,
+        game_id: &str,
+        replacement: NewGameWithAnswers,
+    ) -> Result<Option<DbGame>> {
+        let mut tx = self.pool.begin().await?;
+        let Some(replaced) = sqlx::query(
+            "UPDATE games SET deleted_at = ?2 WHERE id = ?1 AND deleted_at IS NULL
+             RETURNING date, sequence_number, mode, language, revision",
+        )
+        .bind(game_id)
+        .bind(Utc::now().to_rfc3339())
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let mut game = DbGame::new(NewGame {
+            date: replaced.get("date"),
+            sequence_number: replaced.get("sequence_number"),
+            mode: replaced.get("mode"),
+            language: replaced.get("language"),
+            ..replacement.game
+        });
+        game.revision = replaced.get::<i32, _>("revision") + 1;
+        let (game, _answers) = insert_game_with_answers(
+            &mut tx,
+            game,
+            replacement.answers,
+            replacement.optimal_solution,
+        )
+        .await?;
+        if let Some(density) = replacement.density {
+            insert_board_density(&mut tx, &game.id, &density).await?;
+        }
+        if let Some(quality) = replacement.quality {
+            insert_board_quality(&mut tx, &game.id, &quality).await?;
+        }
+        tx.commit().await?;
+        Ok(Some(game))
+    }
+
+    async fn get_game_words(&self, game_id: &str) -> Result<Vec<String>> {
+        let rows = sqlx::query("SELECT DISTINCT word FROM game_answers2 WHERE game_id = ?1")
+            .bind(game_id)
+            .fetch_all(&self.reader)
+            .await?;
+
+        if rows.is_empty() {
+            if let Some(answers) = self.get_compacted_answers(game_id).await? {
+                return Ok(answers.into_iter().map(|answer| answer.word).collect());
+            }
+        }
+
+        let words = rows
+            .into_iter()
+            .map(|row| row.get::<String, _>("word"))
+            .collect();
+
+        Ok(words)
+    }
+
+    async fn get_game_answers(&self, game_id: &str) -> Result<Vec<DbGameAnswer>> {
+        let rows = sqlx::query(
+            "SELECT game_id, word, path_count, requires_wildcard, created_at FROM game_answers2 WHERE game_id = ?1 ORDER BY word",
+        )
+        .bind(game_id)
+        .fetch_all(&self.reader)
+        .await?;
+
+        if rows.is_empty() {
+            if let Some(answers) = self.get_compacted_answers(game_id).await? {
+                return Ok(answers);
+            }
+        }
+
+        let mut answers = Vec::with_capacity(rows.len());
+        for row in rows {
+            let path_stats = match (
+                row.get::<Option<i32>, _>("path_count"),
+                row.get::<Option<i32>, _>("requires_wildcard"),
+            ) {
+                (Some(path_count), Some(requires_wildcard)) => Some(WordPathStats {
+                    path_count,
+                    requires_wildcard: requires_wildcard != 0,
+                }),
+                _ => None,
+            };
+
+            answers.push(DbGameAnswer {
+                game_id: row.get("game_id"),
+                word: row.get("word"),
+                path_stats,
+                created_at: chrono::DateTime::parse_from_rfc3339(
+                    &row.get::<String, _>("created_at"),
+                )?
+                .with_timezone(&Utc),
+            });
+        }
+
+        Ok(answers)
+    }
+
+    async fn get_uncompacted_game_ids(&self, before_date: &str, limit: i32) -> Result<Vec<String>> {
+        let ids = sqlx::query_scalar(
+            "SELECT id FROM games WHERE date < ?1 AND answers_blob IS NULL
+             AND EXISTS (SELECT 1 FROM game_answers2 WHERE game_id = games.id)
+             ORDER BY date LIMIT ?2",
+        )
+        .bind(before_date)
+        .bind(limit)
+        .fetch_all(&self.reader)
+        .await?;
+
+        Ok(ids)
+    }
+
+    async fn store_compacted_answers(&self, game_id: &str, answers_blob: Vec<u8>) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("UPDATE games SET answers_blob = ?2 WHERE id = ?1")
+            .bind(game_id)
+            .bind(answers_blob)
+            .execute(&mut *tx)
+            .await?;
+        let deleted = sqlx::query("DELETE FROM game_answers2 WHERE game_id = ?1")
+            .bind(game_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(deleted.rows_affected())
+    }
+
+    // Get score distribution for a specific game
+    async fn get_score_distribution(&self, game_id: &str) -> Result<Vec<i32>> {
+        let rows = sqlx::query(
+            "SELECT total_score FROM game_entries WHERE game_id = ?1 AND completed = 1 AND off_board = 0",
+        )
+        .bind(game_id)
+        .fetch_all(&self.reader)
+        .await?;
+
+        let scores = rows
+            .into_iter()
+            .map(|row| row.get::<i32, _>("total_score"))
+            .collect();
+
+        Ok(scores)
+    }
+
+    async fn get_word_counts(&self, game_id: &str) -> Result<Vec<DbWordCount>> {
+        let rows = sqlx::query(
+            "SELECT a.word, COUNT(DISTINCT a.entry_id) as players
+             FROM game_entry_answers a
+             JOIN game_entries e ON e.id = a.entry_id
+             WHERE e.game_id = ?1 AND e.completed = 1 AND e.off_board = 0
+             GROUP BY a.word
+             ORDER BY players DESC, a.word",
+        )
+        .bind(game_id)
+        .fetch_all(&self.reader)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbWordCount {
+                word: row.get("word"),
+                players: row.get("players"),
+            })
+            .collect())
+    }
+
+    // Get optimal solutions for a specific game
+    async fn get_optimal_solutions(&self, game_id: &str) -> Result<Vec<OptimalAnswer>> {
+        let row = sqlx::query("SELECT words_and_scores FROM optimal_solutions WHERE game_id = ?1")
+            .bind(game_id)
+            .fetch_optional(&self.reader)
+            .await?;
+
+        if let Some(row) = row {
+            let words_and_scores: String = row.get("words_and_scores");
+            let optimal_answers: Vec<OptimalAnswer> = serde_json::from_str(&words_and_scores)?;
+            Ok(optimal_answers)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    // Completion tracking operations
+    async fn claim_game_announcement(&self, game_id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT INTO game_announcements (game_id, announced_at) VALUES (?1, ?2) ON CONFLICT (game_id) DO NOTHING",
+        )
+        .bind(game_id)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn release_game_announcement(&self, game_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM game_announcements WHERE game_id = ?1")
+            .bind(game_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn create_webhook_subscription(
+        &self,
+        subscription: NewWebhookSubscription,
+    ) -> Result<DbWebhookSubscription> {
+        let subscription = DbWebhookSubscription::from(subscription);
+
+        sqlx::query(&format!(
+            "INSERT INTO webhook_subscriptions ({WEBHOOK_SUBSCRIPTION_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5)"
+        ))
+        .bind(&subscription.id)
+        .bind(&subscription.kind)
+        .bind(&subscription.url)
+        .bind(&subscription.secret)
+        .bind(subscription.created_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(subscription)
+    }
+
+    async fn get_webhook_subscription(&self, id: &str) -> Result<Option<DbWebhookSubscription>> {
+        let row = sqlx::query(&format!(
+            "SELECT {WEBHOOK_SUBSCRIPTION_COLUMNS} FROM webhook_subscriptions WHERE id = ?1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.reader)
+        .await?;
+
+        row.as_ref().map(webhook_subscription_from_row).transpose()
+    }
+
+    async fn get_webhook_subscriptions(&self) -> Result<Vec<DbWebhookSubscription>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {WEBHOOK_SUBSCRIPTION_COLUMNS} FROM webhook_subscriptions ORDER BY created_at"
+        ))
+        .fetch_all(&self.reader)
+        .await?;
+
+        rows.iter().map(webhook_subscription_from_row).collect()
+    }
+
+    async fn delete_webhook_subscription(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM webhook_subscriptions WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn claim_webhook_delivery(&self, subscription_id: &str, game_id: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT INTO webhook_deliveries (subscription_id, game_id, delivered_at) VALUES (?1, ?2, ?3) ON CONFLICT (subscription_id, game_id) DO NOTHING",
+        )
+        .bind(subscription_id)
+        .bind(game_id)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn release_webhook_delivery(&self, subscription_id: &str, game_id: &str) -> Result<()> {
+        sqlx::query("DELETE FROM webhook_deliveries WHERE subscription_id = ?1 AND game_id = ?2")
+            .bind(subscription_id)
+            .bind(game_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn start_discord_link(
+        &self,
+        discord_user_id: &str,
+        code_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query("INSERT INTO discord_links (discord_user_id, code_hash, code_expires_at, created_at) VALUES (?1, ?2, ?3, ?4) ON CONFLICT(discord_user_id) DO UPDATE SET code_hash = excluded.code_hash, code_expires_at = excluded.code_expires_at")
+            .bind(discord_user_id)
+            .bind(code_hash)
+            .bind(expires_at.to_rfc3339())
+            .bind(Utc::now().to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn complete_discord_link(
+        &self,
+        code_hash: &str,
+        user_id: &str,
+    ) -> Result<Option<String>> {
+        let discord_user_id = sqlx::query_scalar("UPDATE discord_links SET user_id = ?1, code_hash = NULL, code_expires_at = NULL WHERE code_hash = ?2 AND code_expires_at > ?3 RETURNING discord_user_id")
+            .bind(user_id)
+            .bind(code_hash)
+            .bind(Utc::now().to_rfc3339())
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(discord_user_id)
+    }
+
+    async fn get_discord_linked_user(&self, discord_user_id: &str) -> Result<Option<String>> {
+        let user_id: Option<Option<String>> =
+            sqlx::query_scalar("SELECT user_id FROM discord_links WHERE discord_user_id = ?1")
+                .bind(discord_user_id)
+                .fetch_optional(&self.reader)
+                .await?;
+        Ok(user_id.flatten())
+    }
+
+    async fn get_linked_discord_users(&self, user_id: &str) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar(
+            "SELECT discord_user_id FROM discord_links WHERE user_id = ?1 ORDER BY created_at",
+        )
+        .bind(user_id)
+        .fetch_all(&self.reader)
+        .await?)
+    }
+
+    async fn record_audit_entry(&self, entry: NewAuditEntry) -> Result<DbAuditEntry> {
+        let entry = DbAuditEntry::from(entry);
+
+        sqlx::query(&format!(
+            "INSERT INTO audit_log ({AUDIT_ENTRY_COLUMNS}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+        ))
+        .bind(&entry.id)
+        .bind(&entry.actor)
+        .bind(&entry.action)
+        .bind(&entry.target)
+        .bind(&entry.before_data)
+        .bind(&entry.after_data)
+        .bind(entry.created_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(entry)
+    }
+
+    async fn get_audit_entries(&self, filter: &AuditFilter) -> Result<Vec<DbAuditEntry>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {AUDIT_ENTRY_COLUMNS} FROM audit_log WHERE (?1 IS NULL OR actor = ?1) AND (?2 IS NULL OR action = ?2) AND (?3 IS NULL OR target = ?3) AND (?4 IS NULL OR created_at >= ?4) AND (?5 IS NULL OR created_at < ?5) ORDER BY created_at DESC LIMIT ?6"
+        ))
+        .bind(&filter.actor)
+        .bind(&filter.action)
+        .bind(&filter.target)
+        .bind(filter.since.map(|since| since.to_rfc3339()))
+        .bind(filter.until.map(|until| until.to_rfc3339()))
+        .bind(filter.limit)
+        .fetch_all(&self.reader)
+        .await?;
+
+        rows.iter().map(audit_entry_from_row).collect()
+    }
+
+    async fn increment_rate_limit_window(
+        &self,
+        bucket: &str,
+        window_start: DateTime<Utc>,
+    ) -> Result<i32> {
+        let row = sqlx::query(
+            "INSERT INTO rate_limit_windows (bucket, window_start, request_count) VALUES (?1, ?2, 1)
+             ON CONFLICT (bucket, window_start) DO UPDATE SET request_count = request_count + 1
+             RETURNING request_count",
+        )
+        .bind(bucket)
+        .bind(window_start.to_rfc3339())
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row.get("request_count"))
+    }
+
+    async fn delete_rate_limit_windows_before(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM rate_limit_windows WHERE window_start < ?1")
+            .bind(before.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn create_admin_token(&self, token: NewAdminToken) -> Result<DbAdminToken> {
+        let token = DbAdminToken::new(token.token_hash, token.scope, token.label, token.expires_at);
+
+        sqlx::query("INSERT INTO admin_tokens (id, token_hash, scope, label, created_at, expires_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)")
+            .bind(&token.id)
+            .bind(&token.token_hash)
+            .bind(&token.scope)
+            .bind(&token.label)
+            .bind(token.created_at.to_rfc3339())
+            .bind(token.expires_at.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(token)
+    }
+
+    async fn get_admin_token_by_hash(&self, token_hash: &str) -> Result<Option<DbAdminToken>> {
+        let row = sqlx::query("SELECT id, token_hash, scope, label, created_at, expires_at, revoked_at FROM admin_tokens WHERE token_hash = ?1")
+            .bind(token_hash)
+            .fetch_optional(&self.reader)
+            .await?;
+
+        row.as_ref().map(admin_token_from_row).transpose()
+    }
+
+    async fn get_admin_tokens(&self) -> Result<Vec<DbAdminToken>> {
+        let rows = sqlx::query("SELECT id, token_hash, scope, label, created_at, expires_at, revoked_at FROM admin_tokens WHERE revoked_at IS NULL ORDER BY created_at DESC")
+            .fetch_all(&self.reader)
+            .await?;
+
+        rows.iter().map(admin_token_from_row).collect()
+    }
+
+    async fn revoke_admin_token(&self, token_id: &str) -> Result<Option<DbAdminToken>> {
+        let row = sqlx::query("UPDATE admin_tokens SET revoked_at = ?1 WHERE id = ?2 AND revoked_at IS NULL RETURNING id, token_hash, scope, label, created_at, expires_at, revoked_at")
+            .bind(Utc::now().to_rfc3339())
+            .bind(token_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.as_ref().map(admin_token_from_row).transpose()
+    }
+
+    async fn mark_game_completed(&self, game_id: &str) -> Result<()> {
+        let now = Utc::now();
+        sqlx::query("UPDATE games SET completed = 1, completed_at = ?1 WHERE id = ?2")
+            .bind(now.to_rfc3339())
+            .bind(game_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_incomplete_games_for_date(&self, date: &str) -> Result<Vec<DbGame>> {
+        let rows = sqlx::query("SELECT id, date, board_data, threshold_score, sequence_number, generation_version, difficulty, theme, scoring_scheme_version, bonus_rules, game_rules, mode, time_limit_seconds, language, revision, deleted_at, completed, completed_at, created_at FROM games WHERE date = ?1 AND completed = 0")
+            .bind(date)
+            .fetch_all(&self.reader)
+            .await?;
+
+        rows.iter().map(game_from_row).collect()
+    }
+
+    async fn get_incomplete_game_entries_for_game(
+        &self,
+        game_id: &str,
+    ) -> Result<Vec<DbGameEntry>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {GAME_ENTRY_COLUMNS} FROM game_entries WHERE game_id = ?1 AND completed = 0"
+        ))
+        .bind(game_id)
+        .fetch_all(&self.reader)
+        .await?;
+
+        rows.iter().map(game_entry_from_row).collect()
+    }
+
+    async fn mark_game_entry_completed(&self, entry_id: &str) -> Result<()> {
+        let now = Utc::now();
+        sqlx::query("UPDATE game_entries SET completed = 1, updated_at = ?1 WHERE id = ?2")
+            .bind(now.to_rfc3339())
+            .bind(entry_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // Statistics operations
+    async fn get_game_stats(
+        &self,
+        game_id: &str,
+        user_score: i32,
+    ) -> Result<(i32, i32, f64, i32, i32)> {
+        // Get total players for this game
+        let total_row = sqlx::query(
+            "SELECT COUNT(*) as count FROM game_entries WHERE game_id = ?1 AND completed = 1 AND off_board = 0",
+        )
+        .bind(game_id)
+        .fetch_one(&self.reader)
+        .await?;
+        let total_players: i32 = total_row.get("count");
+
+        // Get number of players with score <= user_score (for ranking)
+        let rank_row = sqlx::query("SELECT COUNT(*) as count FROM game_entries WHERE game_id = ?1 AND completed = 1 AND off_board = 0 AND total_score <= ?2")
+            .bind(game_id)
+            .bind(user_score)
+            .fetch_one(&self.reader)
+            .await?;
+        let players_at_or_below: i32 = rank_row.get("count");
+
+        // Calculate rank and percentile
+        let user_rank = total_players - players_at_or_below + 1;
+        let percentile = if total_players > 0 {
+            (players_at_or_below as f64 / total_players as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        // Get average score
+        let avg_row = sqlx::query("SELECT AVG(CAST(total_score AS REAL)) as avg_score FROM game_entries WHERE game_id = ?1 AND completed = 1 AND off_board = 0")
+            .bind(game_id)
+            .fetch_one(&self.reader)
+            .await?;
+        let avg_score: Option<f64> = avg_row.get("avg_score");
+
+        // Get highest score
+        let max_row = sqlx::query("SELECT MAX(total_score) as max_score FROM game_entries WHERE game_id = ?1 AND completed = 1 AND off_board = 0")
+            .bind(game_id)
+            .fetch_one(&self.reader)
+            .await?;
+        let highest_score: Option<i32> = max_row.get("max_score");
+
+        Ok((
+            total_players,
+            user_rank,
+            percentile,
+            avg_score.unwrap_or(0.0) as i32,
+            highest_score.unwrap_or(0),
+        ))
+    }
+
+    async fn get_player_counts(&self, game_id: &str) -> Result<DbPlayerCounts> {
+        let row = sqlx::query(
+            "SELECT
+                COALESCE(SUM(CASE WHEN completed = 1 THEN 0 ELSE 1 END), 0) as in_progress,
+                COALESCE(SUM(CASE WHEN completed = 1 THEN 1 ELSE 0 END), 0) as completed
+             FROM game_entries WHERE game_id = ?1",
+        )
+        .bind(game_id)
+        .fetch_one(&self.reader)
+        .await?;
+
+        Ok(DbPlayerCounts {
+            in_progress: row.get("in_progress"),
+            completed: row.get("completed"),
+        })
+    }
+
+    async fn get_user_history(
+        &self,
+        user_id: &str,
+        after_date: Option<&str>,
+        limit: i32,
+    ) -> Result<Vec<DbHistoryEntry>> {
+        let rows = sqlx::query(
+            "SELECT g.id, g.date, g.sequence_number, e.answers_data, e.total_score, e.completed, e.off_board,
+                CASE WHEN e.completed = 1 AND e.off_board = 0 THEN 100.0
+                    * (SELECT COUNT(*) FROM game_entries o WHERE o.game_id = e.game_id AND o.completed = 1 AND o.off_board = 0 AND o.total_score <= e.total_score)
+                    / (SELECT COUNT(*) FROM game_entries o WHERE o.game_id = e.game_id AND o.completed = 1 AND o.off_board = 0)
+                END as percentile
+             FROM game_entries e
+             JOIN games g ON g.id = e.game_id
+             WHERE e.user_id = ?1 AND g.mode = 'daily' AND g.language = 'en' AND (?2 IS NULL OR g.date > ?2)
+             ORDER BY g.date
+             LIMIT ?3",
+        )
+        .bind(user_id)
+        .bind(after_date)
+        .bind(limit)
+        .fetch_all(&self.reader)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbHistoryEntry {
+                game_id: row.get("id"),
+                date: row.get("date"),
+                sequence_number: row.get("sequence_number"),
                 answers_data: row.get("answers_data"),
                 total_score: row.get("total_score"),
                 completed: row.get::<i32, _>("completed") != 0,
-                created_at: chrono::DateTime::parse_from_rfc3339(
-                    &row.get::<String, _>("created_at"),
-                )?
-                .with_timezone(&Utc),
-                updated_at: chrono::DateTime::parse_from_rfc3339(
-                    &row.get::<String, _>("updated_at"),
-                )?
-                .with_timezone(&Utc),
-            });
-        }
+                off_board: row.get::<i32, _>("off_board") != 0,
+                percentile: row.get("percentile"),
+            })
+            .collect())
+    }
+
+    async fn get_user_entries(&self, user_id: &str) -> Result<Vec<DbUserEntry>> {
+        let rows = sqlx::query(
+            "SELECT g.id, g.date, g.mode, g.sequence_number, e.answers_data, e.total_score, e.completed, e.off_board, e.created_at, e.updated_at
+             FROM game_entries e
+             JOIN games g ON g.id = e.game_id
+             WHERE e.user_id = ?1
+             ORDER BY g.date, g.mode",
+        )
+        .bind(user_id)
+        .fetch_all(&self.reader)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(DbUserEntry {
+                    game_id: row.get("id"),
+                    date: row.get("date"),
+                    mode: row.get("mode"),
+                    sequence_number: row.get("sequence_number"),
+                    answers_data: row.get("answers_data"),
+                    total_score: row.get("total_score"),
+                    completed: row.get::<i32, _>("completed") != 0,
+                    off_board: row.get::<i32, _>("off_board") != 0,
+                    created_at: chrono::DateTime::parse_from_rfc3339(
+                        &row.get::<String, _>("created_at"),
+                    )?
+                    .with_timezone(&Utc),
+                    updated_at: chrono::DateTime::parse_from_rfc3339(
+                        &row.get::<String, _>("updated_at"),
+                    )?
+                    .with_timezone(&Utc),
+                })
+            })
+            .collect()
+    }
+
+    async fn get_user_custom_game_scores(&self, user_id: &str) -> Result<Vec<DbCustomGameScore>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {CUSTOM_GAME_SCORE_COLUMNS} FROM custom_game_scores WHERE user_id = ?1 ORDER BY created_at"
+        ))
+        .bind(user_id)
+        .fetch_all(&self.reader)
+        .await?;
+
+        rows.iter().map(custom_game_score_from_row).collect()
+    }
+
+    async fn record_game_start(&self, user_id: &str, game_id: &str) -> Result<DateTime<Utc>> {
+        sqlx::query("INSERT INTO game_starts (user_id, game_id, started_at) VALUES (?1, ?2, ?3) ON CONFLICT (user_id, game_id) DO NOTHING")
+            .bind(user_id)
+            .bind(game_id)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+
+        let started_at: String =
+            sqlx::query("SELECT started_at FROM game_starts WHERE user_id = ?1 AND game_id = ?2")
+                .bind(user_id)
+                .bind(game_id)
+                .fetch_one(&self.reader)
+                .await?
+                .get("started_at");
+        Ok(chrono::DateTime::parse_from_rfc3339(&started_at)?.with_timezone(&Utc))
+    }
+
+    async fn get_game_start(&self, user_id: &str, game_id: &str) -> Result<Option<DateTime<Utc>>> {
+        let started_at: Option<String> = sqlx::query_scalar(
+            "SELECT started_at FROM game_starts WHERE user_id = ?1 AND game_id = ?2",
+        )
+        .bind(user_id)
+        .bind(game_id)
+        .fetch_optional(&self.reader)
+        .await?;
+        started_at
+            .map(|started_at| {
+                Ok(chrono::DateTime::parse_from_rfc3339(&started_at)?.with_timezone(&Utc))
+            })
+            .transpose()
+    }
+
+    async fn get_blitz_runs(&self, user_id: &str) -> Result<Vec<DbBlitzRun>> {
+        let rows = sqlx::query(
+            "SELECT g.id, g.date, g.time_limit_seconds, e.answers_data, e.total_score, e.completed_at, s.started_at
+             FROM game_entries e
+             JOIN games g ON g.id = e.game_id
+             JOIN game_starts s ON s.user_id = e.user_id AND s.game_id = e.game_id
+             WHERE e.user_id = ?1 AND g.mode = 'blitz' AND g.time_limit_seconds IS NOT NULL
+             ORDER BY g.date",
+        )
+        .bind(user_id)
+        .fetch_all(&self.reader)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(DbBlitzRun {
+                    game_id: row.get("id"),
+                    date: row.get("date"),
+                    answers_data: row.get("answers_data"),
+                    total_score: row.get("total_score"),
+                    started_at: chrono::DateTime::parse_from_rfc3339(
+                        &row.get::<String, _>("started_at"),
+                    )?
+                    .with_timezone(&Utc),
+                    completed_at: row
+                        .get::<Option<String>, _>("completed_at")
+                        .map(|dt| {
+                            chrono::DateTime::parse_from_rfc3339(&dt)
+                                .map(|dt| dt.with_timezone(&Utc))
+                        })
+                        .transpose()?,
+                    time_limit_seconds: row.get("time_limit_seconds"),
+                })
+            })
+            .collect()
+    }
+
+    async fn get_speed_runs(
+        &self,
+        game_id: &str,
+        min_score: i32,
+        min_elapsed_ms: i64,
+        limit: i32,
+    ) -> Result<Vec<DbSpeedRun>> {
+        let rows = sqlx::query(
+            "SELECT user_id, total_score, elapsed_ms FROM (
+                SELECT e.user_id, e.total_score, e.completed_at,
+                    CAST(ROUND((julianday(e.completed_at) - julianday(s.started_at)) * 86400000) AS INTEGER) AS elapsed_ms
+                FROM game_entries e
+                JOIN game_starts s ON s.user_id = e.user_id AND s.game_id = e.game_id
+                WHERE e.game_id = ?1 AND e.completed = 1 AND e.off_board = 0
+                    AND e.completed_at IS NOT NULL AND e.total_score >= ?2
+                    AND julianday(s.started_at) <= julianday(e.created_at)
+            )
+            WHERE elapsed_ms >= ?3
+            ORDER BY elapsed_ms, completed_at
+            LIMIT ?4",
+        )
+        .bind(game_id)
+        .bind(min_score)
+        .bind(min_elapsed_ms)
+        .bind(limit)
+        .fetch_all(&self.reader)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbSpeedRun {
+                user_id: row.get("user_id"),
+                total_score: row.get("total_score"),
+                elapsed_ms: row.get("elapsed_ms"),
+            })
+            .collect())
+    }
+
+    async fn get_game_densities(&self, limit: i32) -> Result<Vec<DbGameDensity>> {
+        let rows = sqlx::query(
+            "SELECT g.id, g.date, g.sequence_number, g.threshold_score,
+                d.valid_words, d.words_per_tile, d.average_word_length, d.wildcard_reliance,
+                q.long_words, q.dead_tiles, q.dead_corners, q.unused_wildcards, q.vowel_share, q.issues,
+                COUNT(e.id) as players_started,
+                COALESCE(SUM(CASE WHEN e.completed = 1 THEN 1 ELSE 0 END), 0) as players_completed
+             FROM game_board_density d
+             JOIN games g ON g.id = d.game_id
+             LEFT JOIN game_board_quality q ON q.game_id = g.id
+             LEFT JOIN game_entries e ON e.game_id = g.id
+             GROUP BY g.id
+             ORDER BY g.date DESC
+             LIMIT ?1",
+        )
+        .bind(limit)
+        .fetch_all(&self.reader)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbGameDensity {
+                game_id: row.get("id"),
+                date: row.get("date"),
+                sequence_number: row.get("sequence_number"),
+                threshold_score: row.get("threshold_score"),
+                density: BoardDensity {
+                    valid_words: row.get("valid_words"),
+                    words_per_tile: row.get("words_per_tile"),
+                    average_word_length: row.get("average_word_length"),
+                    wildcard_reliance: row.get("wildcard_reliance"),
+                },
+                quality: row
+                    .get::<Option<String>, _>("issues")
+                    .map(|issues| BoardQuality {
+                        long_words: row.get("long_words"),
+                        dead_tiles: row.get("dead_tiles"),
+                        dead_corners: row.get("dead_corners"),
+                        unused_wildcards: row.get("unused_wildcards"),
+                        vowel_share: row.get("vowel_share"),
+                        issues: split_issues(&issues),
+                    }),
+                players_started: row.get("players_started"),
+                players_completed: row.get("players_completed"),
+            })
+            .collect())
+    }
+
+    async fn get_game_boards(&self) -> Result<Vec<DbGameBoard>> {
+        let rows =
+            sqlx::query("SELECT date, generation_version, board_data FROM games WHERE mode = 'daily' AND language = 'en' AND deleted_at IS NULL ORDER BY date")
+                .fetch_all(&self.reader)
+                .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbGameBoard {
+                date: row.get("date"),
+                generation_version: row.get("generation_version"),
+                board_data: row.get("board_data"),
+            })
+            .collect())
+    }
+
+    async fn get_user_stats(&self, user_id: &str) -> Result<DbUserStats> {
+        let row = sqlx::query(
+            "SELECT
+                COUNT(*) as games_played,
+                AVG(CAST(e.total_score AS REAL)) as average_score,
+                MAX(e.total_score) as best_score,
+                AVG(CASE WHEN e.off_board = 0 THEN 100.0
+                    * (SELECT COUNT(*) FROM game_entries o WHERE o.game_id = e.game_id AND o.completed = 1 AND o.off_board = 0 AND o.total_score <= e.total_score)
+                    / (SELECT COUNT(*) FROM game_entries o WHERE o.game_id = e.game_id AND o.completed = 1 AND o.off_board = 0)
+                END) as average_percentile,
+                COALESCE(SUM(CASE WHEN e.total_score >= g.threshold_score THEN 1 ELSE 0 END), 0) as threshold_hits
+             FROM game_entries e
+             JOIN games g ON g.id = e.game_id
+             WHERE e.user_id = ?1 AND e.completed = 1 AND g.mode = 'daily' AND g.language = 'en'",
+        )
+        .bind(user_id)
+        .fetch_one(&self.reader)
+        .await?;
+
+        Ok(DbUserStats {
+            games_played: row.get("games_played"),
+            average_score: row.get("average_score"),
+            best_score: row.get("best_score"),
+            average_percentile: row.get("average_percentile"),
+            threshold_hits: row.get("threshold_hits"),
+        })
+    }
+
+    async fn get_completed_answers_data(&self, user_id: &str) -> Result<Vec<String>> {
+        let rows = sqlx::query(
+            "SELECT answers_data FROM game_entries WHERE user_id = ?1 AND completed = 1",
+        )
+        .bind(user_id)
+        .fetch_all(&self.reader)
+        .await?;
+
+        Ok(rows.iter().map(|row| row.get("answers_data")).collect())
+    }
+
+    async fn get_unfrozen_games_through(&self, date: &str) -> Result<Vec<DbGame>> {
+        let rows = sqlx::query("SELECT id, date, board_data, threshold_score, sequence_number, generation_version, difficulty, theme, scoring_scheme_version, bonus_rules, game_rules, mode, time_limit_seconds, language, revision, deleted_at, completed, completed_at, created_at FROM games WHERE date <= ?1 AND id NOT IN (SELECT game_id FROM game_final_stats) ORDER BY date")
+            .bind(date)
+            .fetch_all(&self.reader)
+            .await?;
+
+        rows.iter().map(game_from_row).collect()
+    }
+
+    async fn freeze_game_stats(&self, game_id: &str) -> Result<Option<DbFinalGameStats>> {
+        // Averages are truncated the same way get_game_stats truncates them
+        let row = sqlx::query(
+            "INSERT INTO game_final_stats (game_id, total_players, average_score, highest_score, frozen_at)
+             SELECT ?1, COUNT(*), CAST(COALESCE(AVG(CAST(total_score AS REAL)), 0) AS INTEGER), COALESCE(MAX(total_score), 0), ?2
+             FROM game_entries WHERE game_id = ?1 AND completed = 1 AND off_board = 0
+             ON CONFLICT (game_id) DO NOTHING
+             RETURNING game_id, total_players, average_score, highest_score, frozen_at",
+        )
+        .bind(game_id)
+        .bind(Utc::now().to_rfc3339())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(final_game_stats_from_row).transpose()
+    }
+
+    async fn get_final_game_stats(&self, game_id: &str) -> Result<Option<DbFinalGameStats>> {
+        let row = sqlx::query("SELECT game_id, total_players, average_score, highest_score, frozen_at FROM game_final_stats WHERE game_id = ?1")
+            .bind(game_id)
+            .fetch_optional(&self.reader)
+            .await?;
+
+        row.as_ref().map(final_game_stats_from_row).transpose()
+    }
+
+    async fn get_final_ranks_to_notify(&self, game_id: &str) -> Result<Vec<DbFinalRank>> {
+        let rows = sqlx::query(
+            "SELECT e.user_id, e.total_score,
+                1 + (SELECT COUNT(*) FROM game_entries o
+                     WHERE o.game_id = e.game_id AND o.completed = 1 AND o.off_board = 0
+                       AND o.total_score > e.total_score) as user_rank
+             FROM game_entries e
+             JOIN notification_preferences p ON p.user_id = e.user_id
+             WHERE e.game_id = ?1 AND e.completed = 1 AND e.off_board = 0 AND p.final_rank = 1
+             ORDER BY user_rank, e.user_id",
+        )
+        .bind(game_id)
+        .fetch_all(&self.reader)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DbFinalRank {
+                user_id: row.get("user_id"),
+                total_score: row.get("total_score"),
+                rank: row.get("user_rank"),
+            })
+            .collect())
+    }
+
+    async fn get_games_without_stats_through(&self, date: &str) -> Result<Vec<DbGame>> {
+        let rows = sqlx::query("SELECT id, date, board_data, threshold_score, sequence_number, generation_version, difficulty, theme, scoring_scheme_version, bonus_rules, game_rules, mode, time_limit_seconds, language, revision, deleted_at, completed, completed_at, created_at FROM games WHERE date <= ?1 AND id NOT IN (SELECT game_id FROM game_stats) ORDER BY date")
+            .bind(date)
+            .fetch_all(&self.reader)
+            .await?;
+
+        rows.iter().map(game_from_row).collect()
+    }
+
+    async fn upsert_game_stats(&self, stats: DbGameStats) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO game_stats (game_id, players, completed_players, median_score, optimal_score, completion_rate, threshold_hit_rate, computed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT (game_id) DO UPDATE SET
+                players = excluded.players,
+                completed_players = excluded.completed_players,
+                median_score = excluded.median_score,
+                optimal_score = excluded.optimal_score,
+                completion_rate = excluded.completion_rate,
+                threshold_hit_rate = excluded.threshold_hit_rate,
+                computed_at = excluded.computed_at",
+        )
+        .bind(&stats.game_id)
+        .bind(stats.players)
+        .bind(stats.completed_players)
+        .bind(stats.median_score)
+        .bind(stats.optimal_score)
+        .bind(stats.completion_rate)
+        .bind(stats.threshold_hit_rate)
+        .bind(stats.computed_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_recent_game_stats(&self, limit: i32) -> Result<Vec<DbGameStats>> {
+        let rows = sqlx::query(
+            "SELECT s.game_id, s.players, s.completed_players, s.median_score, s.optimal_score,
+                s.completion_rate, s.threshold_hit_rate, s.computed_at
+             FROM game_stats s
+             JOIN games g ON g.id = s.game_id
+             WHERE g.mode = 'daily' AND g.language = 'en' AND g.deleted_at IS NULL
+             ORDER BY g.date DESC
+             LIMIT ?1",
+        )
+        .bind(limit)
+        .fetch_all(&self.reader)
+        .await?;
+
+        rows.iter().map(game_stats_from_row).collect()
+    }
+
+    async fn get_notification_preferences(
+        &self,
+        user_id: &str,
+    ) -> Result<DbNotificationPreferences> {
+        let row = sqlx::query("SELECT final_rank FROM notification_preferences WHERE user_id = ?1")
+            .bind(user_id)
+            .fetch_optional(&self.reader)
+            .await?;
+
+        Ok(match row {
+            Some(row) => DbNotificationPreferences {
+                user_id: user_id.to_string(),
+                final_rank: row.get::<i32, _>("final_rank") != 0,
+            },
+            None => DbNotificationPreferences::none(user_id.to_string()),
+        })
+    }
+
+    async fn set_notification_preferences(
+        &self,
+        preferences: DbNotificationPreferences,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO notification_preferences (user_id, final_rank, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT (user_id) DO UPDATE SET final_rank = excluded.final_rank, updated_at = excluded.updated_at",
+        )
+        .bind(&preferences.user_id)
+        .bind(if preferences.final_rank { 1 } else { 0 })
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn create_notification(&self, notification: NewNotification) -> Result<DbNotification> {
+        let notification = DbNotification::new(notification);
+
+        sqlx::query("INSERT INTO notifications (id, user_id, kind, game_id, message, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)")
+            .bind(&notification.id)
+            .bind(&notification.user_id)
+            .bind(&notification.kind)
+            .bind(&notification.game_id)
+            .bind(&notification.message)
+            .bind(notification.created_at.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(notification)
+    }
+
+    async fn get_notifications(&self, user_id: &str, limit: i32) -> Result<Vec<DbNotification>> {
+        let rows = sqlx::query("SELECT id, user_id, kind, game_id, message, created_at, read_at FROM notifications WHERE user_id = ?1 ORDER BY created_at DESC LIMIT ?2")
+            .bind(user_id)
+            .bind(limit)
+            .fetch_all(&self.reader)
+            .await?;
+
+        rows.iter().map(notification_from_row).collect()
+    }
+
+    async fn mark_notifications_read(&self, user_id: &str) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE notifications SET read_at = ?1 WHERE user_id = ?2 AND read_at IS NULL",
+        )
+        .bind(Utc::now().to_rfc3339())
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn get_pending_outbox_events(
+        &self,
+        max_attempts: i32,
+        limit: i32,
+    ) -> Result<Vec<DbOutboxEvent>> {
+        let rows = sqlx::query("SELECT id, event_type, payload, attempts, created_at FROM outbox_events WHERE processed_at IS NULL AND attempts < ?1 ORDER BY created_at LIMIT ?2")
+            .bind(max_attempts)
+            .bind(limit)
+            .fetch_all(&self.reader)
+            .await?;
 
-        Ok(entries)
+        rows.iter()
+            .map(|row| {
+                Ok(DbOutboxEvent {
+                    id: row.get("id"),
+                    event_type: row.get("event_type"),
+                    payload: row.get("payload"),
+                    attempts: row.get("attempts"),
+                    created_at: chrono::DateTime::parse_from_rfc3339(
+                        &row.get::<String, _>("created_at"),
+                    )?
+                    .with_timezone(&Utc),
+                })
+            })
+            .collect()
     }
 
-    async fn mark_game_entry_completed(&self, entry_id: &str) -> Result<()> {
-        let now = Utc::now();
-        sqlx::query("UPDATE game_entries SET completed = 1, updated_at = ?1 WHERE id = ?2")
-            .bind(now.to_rfc3339())
-            .bind(entry_id)
+    async fn mark_outbox_event_processed(&self, event_id: &str) -> Result<()> {
+        sqlx::query("UPDATE outbox_events SET processed_at = ?1 WHERE id = ?2")
+            .bind(Utc::now().to_rfc3339())
+            .bind(event_id)
             .execute(&self.pool)
             .await?;
         Ok(())
     }
 
-    // Statistics operations
-    async fn get_game_stats(
-        &self,
-        game_id: &str,
-        user_score: i32,
-    ) -> Result<(i32, i32, f64, i32, i32)> {
-        // Get total players for this game
-        let total_row = sqlx::query(
-            "SELECT COUNT(*) as count FROM game_entries WHERE game_id = ?1 AND completed = 1",
+    async fn record_outbox_event_failure(&self, event_id: &str, error: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE outbox_events SET attempts = attempts + 1, last_error = ?1 WHERE id = ?2",
         )
-        .bind(game_id)
-        .fetch_one(&self.pool)
+        .bind(error)
+        .bind(event_id)
+        .execute(&self.pool)
         .await?;
-        let total_players: i32 = total_row.get("count");
+        Ok(())
+    }
 
-        // Get number of players with score <= user_score (for ranking)
-        let rank_row = sqlx::query("SELECT COUNT(*) as count FROM game_entries WHERE game_id = ?1 AND completed = 1 AND total_score <= ?2")
-            .bind(game_id)
-            .bind(user_score)
-            .fetch_one(&self.pool)
+    async fn delete_processed_outbox_events(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM outbox_events WHERE processed_at < ?1")
+            .bind(before.to_rfc3339())
+            .execute(&self.pool)
             .await?;
-        let players_at_or_below: i32 = rank_row.get("count");
+        Ok(result.rows_affected())
+    }
 
-        // Calculate rank and percentile
-        let user_rank = total_players - players_at_or_below + 1;
-        let percentile = if total_players > 0 {
-            (players_at_or_below as f64 / total_players as f64) * 100.0
-        } else {
-            0.0
-        };
+    async fn close(&self) {
+        self.reader.close().await;
+        self.pool.close().await;
+    }
+}
 
-        // Get average score
-        let avg_row = sqlx::query("SELECT AVG(CAST(total_score AS REAL)) as avg_score FROM game_entries WHERE game_id = ?1 AND completed = 1")
-            .bind(game_id)
-            .fetch_one(&self.pool)
-            .await?;
-        let avg_score: Option<f64> = avg_row.get("avg_score");
+/// insert_board_density records a generated game's density using the
+/// caller's transaction
+async fn insert_board_density(
+    conn: &mut sqlx::SqliteConnection,
+    game_id: &str,
+    density: &BoardDensity,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO game_board_density (game_id, valid_words, words_per_tile, average_word_length, wildcard_reliance, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )
+    .bind(game_id)
+    .bind(density.valid_words)
+    .bind(density.words_per_tile)
+    .bind(density.average_word_length)
+    .bind(density.wildcard_reliance)
+    .bind(Utc::now().to_rfc3339())
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
 
-        // Get highest score
-        let max_row = sqlx::query("SELECT MAX(total_score) as max_score FROM game_entries WHERE game_id = ?1 AND completed = 1")
-            .bind(game_id)
-            .fetch_one(&self.pool)
+/// insert_board_quality records what the quality analyzer found on a
+/// generated game's board using the caller's transaction
+async fn insert_board_quality(
+    conn: &mut sqlx::SqliteConnection,
+    game_id: &str,
+    quality: &BoardQuality,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO game_board_quality (game_id, long_words, dead_tiles, dead_corners, unused_wildcards, vowel_share, issues, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )
+    .bind(game_id)
+    .bind(quality.long_words)
+    .bind(quality.dead_tiles)
+    .bind(quality.dead_corners)
+    .bind(quality.unused_wildcards)
+    .bind(quality.vowel_share)
+    .bind(join_issues(&quality.issues))
+    .bind(Utc::now().to_rfc3339())
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// write_entry_answers replaces the rows of game_entry_answers for an entry
+/// with the answers in its answers_data, using the caller's transaction.
+/// answers_data stays the record of an entry, so data that can't be split is
+/// logged and leaves the entry without rows rather than failing the write.
+async fn write_entry_answers(
+    conn: &mut sqlx::SqliteConnection,
+    entry_id: &str,
+    answers_data: &str,
+) -> Result<()> {
+    sqlx::query("DELETE FROM game_entry_answers WHERE entry_id = ?1")
+        .bind(entry_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let answers = match AnswerStorage::entry_answers(answers_data) {
+        Ok(answers) => answers,
+        Err(e) => {
+            warn!(
+                "Not splitting unreadable answers of entry {}: {}",
+                entry_id, e
+            );
+            return Ok(());
+        }
+    };
+    for answer in answers {
+        sqlx::query(
+            "INSERT INTO game_entry_answers (entry_id, position, word, score, path_json) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .bind(entry_id)
+        .bind(answer.position)
+        .bind(&answer.word)
+        .bind(answer.score)
+        .bind(&answer.path_json)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// insert_game_with_answers writes a game, its answers and its optimal solution
+/// using the caller's transaction
+async fn insert_game_with_answers(
+    conn: &mut sqlx::SqliteConnection,
+    game: DbGame,
+    mut game_answers: Vec<NewGameAnswer>,
+    optimal_solution: Option<NewOptimalSolution>,
+) -> Result<(DbGame, Vec<DbGameAnswer>)> {
+    // Create the game first
+    sqlx::query("INSERT INTO games (id, date, board_data, threshold_score, sequence_number, generation_version, difficulty, theme, scoring_scheme_version, bonus_rules, game_rules, mode, time_limit_seconds, language, revision, completed, completed_at, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)")
+        .bind(&game.id)
+        .bind(&game.date)
+        .bind(&game.board_data)
+        .bind(game.threshold_score)
+        .bind(game.sequence_number)
+        .bind(game.generation_version)
+        .bind(&game.difficulty)
+        .bind(&game.theme)
+        .bind(game.scoring_scheme_version)
+        .bind(&game.bonus_rules)
+        .bind(&game.game_rules)
+        .bind(&game.mode)
+        .bind(game.time_limit_seconds)
+        .bind(&game.language)
+        .bind(game.revision)
+        .bind(if game.completed { 1 } else { 0 })
+        .bind(game.completed_at.map(|dt| dt.to_rfc3339()))
+        .bind(game.created_at.to_rfc3339())
+        .execute(&mut *conn)
+        .await?;
+
+    // Update all game_answers to use the actual game ID
+    for answer in &mut game_answers {
+        answer.game_id = game.id.clone();
+    }
+
+    // Create all the game answers
+    let mut created_answers = Vec::new();
+    for new_answer in game_answers {
+        let answer = DbGameAnswer::new(new_answer.game_id, new_answer.word, new_answer.path_stats);
+
+        sqlx::query("INSERT INTO game_answers2 (game_id, word, path_count, requires_wildcard, created_at) VALUES (?1, ?2, ?3, ?4, ?5)")
+            .bind(&answer.game_id)
+            .bind(&answer.word)
+            .bind(answer.path_stats.map(|stats| stats.path_count))
+            .bind(
+                answer
+                    .path_stats
+                    .map(|stats| if stats.requires_wildcard { 1 } else { 0 }),
+            )
+            .bind(answer.created_at.to_rfc3339())
+            .execute(&mut *conn)
             .await?;
-        let highest_score: Option<i32> = max_row.get("max_score");
 
-        Ok((
-            total_players,
-            user_rank,
-            percentile,
-            avg_score.unwrap_or(0.0) as i32,
-            highest_score.unwrap_or(0),
-        ))
+        created_answers.push(answer);
+    }
+
+    // Create the optimal solution if provided
+    if let Some(mut optimal_sol) = optimal_solution {
+        optimal_sol.game_id = game.id.clone();
+        let solution = DbOptimalSolution::new(
+            optimal_sol.game_id,
+            optimal_sol.words_and_scores,
+            optimal_sol.total_score,
+        );
+
+        sqlx::query(
+            "INSERT INTO optimal_solutions (id, game_id, words_and_scores, total_score, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .bind(&solution.id)
+        .bind(&solution.game_id)
+        .bind(&solution.words_and_scores)
+        .bind(solution.total_score)
+        .bind(solution.created_at.to_rfc3339())
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok((game, created_answers))
+}
+
+fn user_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbUser> {
+    Ok(DbUser {
+        id: row.get("id"),
+        cookie_token: row.get("cookie_token"),
+        created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?
+            .with_timezone(&Utc),
+        last_seen: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("last_seen"))?
+            .with_timezone(&Utc),
+    })
+}
+
+fn game_entry_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbGameEntry> {
+    Ok(DbGameEntry {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        game_id: row.get("game_id"),
+        answers_data: row.get("answers_data"),
+        total_score: row.get("total_score"),
+        completed: row.get::<i32, _>("completed") != 0,
+        off_board: row.get::<i32, _>("off_board") != 0,
+        suspicion_score: row.get("suspicion_score"),
+        request_id: row.get("request_id"),
+        created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?
+            .with_timezone(&Utc),
+        updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?
+            .with_timezone(&Utc),
+    })
+}
+
+fn webhook_subscription_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbWebhookSubscription> {
+    Ok(DbWebhookSubscription {
+        id: row.get("id"),
+        kind: row.get("kind"),
+        url: row.get("url"),
+        secret: row.get("secret"),
+        created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?
+            .with_timezone(&Utc),
+    })
+}
+
+fn audit_entry_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbAuditEntry> {
+    Ok(DbAuditEntry {
+        id: row.get("id"),
+        actor: row.get("actor"),
+        action: row.get("action"),
+        target: row.get("target"),
+        before_data: row.get("before_data"),
+        after_data: row.get("after_data"),
+        created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?
+            .with_timezone(&Utc),
+    })
+}
+
+fn custom_game_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbCustomGame> {
+    Ok(DbCustomGame {
+        id: row.get("id"),
+        slug: row.get("slug"),
+        board_code: row.get("board_code"),
+        board_data: row.get("board_data"),
+        optimal_score: row.get("optimal_score"),
+        created_by: row.get("created_by"),
+        created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?
+            .with_timezone(&Utc),
+    })
+}
+
+fn custom_game_score_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbCustomGameScore> {
+    Ok(DbCustomGameScore {
+        id: row.get("id"),
+        custom_game_id: row.get("custom_game_id"),
+        user_id: row.get("user_id"),
+        answers_data: row.get("answers_data"),
+        total_score: row.get("total_score"),
+        created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?
+            .with_timezone(&Utc),
+        updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?
+            .with_timezone(&Utc),
+    })
+}
+
+fn tournament_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbTournament> {
+    Ok(DbTournament {
+        id: row.get("id"),
+        name: row.get("name"),
+        start_date: row.get("start_date"),
+        end_date: row.get("end_date"),
+        language: row.get("language"),
+        created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?
+            .with_timezone(&Utc),
+        finalized_at: row
+            .get::<Option<String>, _>("finalized_at")
+            .map(|dt| chrono::DateTime::parse_from_rfc3339(&dt).map(|dt| dt.with_timezone(&Utc)))
+            .transpose()?,
+    })
+}
+
+fn tournament_standing_from_row(row: &sqlx::sqlite::SqliteRow) -> DbTournamentStanding {
+    DbTournamentStanding {
+        user_id: row.get("user_id"),
+        total_score: row.get("total_score"),
+        games_played: row.get("games_played"),
     }
 }
 
+fn tournament_result_from_row(row: &sqlx::sqlite::SqliteRow) -> DbTournamentResult {
+    DbTournamentResult {
+        tournament_id: row.get("tournament_id"),
+        user_id: row.get("user_id"),
+        rank: row.get("rank"),
+        total_score: row.get("total_score"),
+        games_played: row.get("games_played"),
+    }
+}
+
+fn practice_game_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbPracticeGame> {
+    Ok(DbPracticeGame {
+        id: row.get("id"),
+        seed: row.get::<String, _>("seed").parse()?,
+        board_data: row.get("board_data"),
+        threshold_score: row.get("threshold_score"),
+        optimal_score: row.get("optimal_score"),
+        created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?
+            .with_timezone(&Utc),
+        expires_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("expires_at"))?
+            .with_timezone(&Utc),
+    })
+}
+
+fn user_session_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbUserSession> {
+    Ok(DbUserSession {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        session_hash: row.get("session_hash"),
+        user_agent_hash: row.get("user_agent_hash"),
+        first_seen: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("first_seen"))?
+            .with_timezone(&Utc),
+        last_seen: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("last_seen"))?
+            .with_timezone(&Utc),
+        revoked_at: row
+            .get::<Option<String>, _>("revoked_at")
+            .map(|dt| chrono::DateTime::parse_from_rfc3339(&dt).map(|dt| dt.with_timezone(&Utc)))
+            .transpose()?,
+    })
+}
+
+fn account_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbAccount> {
+    Ok(DbAccount {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        email: row.get("email"),
+        created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?
+            .with_timezone(&Utc),
+    })
+}
+
+fn magic_link_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbMagicLink> {
+    Ok(DbMagicLink {
+        token_hash: row.get("token_hash"),
+        email: row.get("email"),
+        user_id: row.get("user_id"),
+        expires_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("expires_at"))?
+            .with_timezone(&Utc),
+        used_at: row
+            .get::<Option<String>, _>("used_at")
+            .map(|dt| chrono::DateTime::parse_from_rfc3339(&dt).map(|dt| dt.with_timezone(&Utc)))
+            .transpose()?,
+        created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?
+            .with_timezone(&Utc),
+    })
+}
+
+fn passkey_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbPasskey> {
+    Ok(DbPasskey {
+        credential_id: row.get("credential_id"),
+        account_id: row.get("account_id"),
+        public_key: row.get("public_key"),
+        sign_count: row.get("sign_count"),
+        created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?
+            .with_timezone(&Utc),
+        last_used_at: row
+            .get::<Option<String>, _>("last_used_at")
+            .map(|dt| chrono::DateTime::parse_from_rfc3339(&dt).map(|dt| dt.with_timezone(&Utc)))
+            .transpose()?,
+    })
+}
+
+fn admin_token_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbAdminToken> {
+    Ok(DbAdminToken {
+        id: row.get("id"),
+        token_hash: row.get("token_hash"),
+        scope: row.get("scope"),
+        label: row.get("label"),
+        created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?
+            .with_timezone(&Utc),
+        expires_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("expires_at"))?
+            .with_timezone(&Utc),
+        revoked_at: row
+            .get::<Option<String>, _>("revoked_at")
+            .map(|dt| chrono::DateTime::parse_from_rfc3339(&dt).map(|dt| dt.with_timezone(&Utc)))
+            .transpose()?,
+    })
+}
+
+fn game_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbGame> {
+    Ok(DbGame {
+        id: row.get("id"),
+        date: row.get("date"),
+        board_data: row.get("board_data"),
+        threshold_score: row.get("threshold_score"),
+        sequence_number: row.get("sequence_number"),
+        generation_version: row.get("generation_version"),
+        difficulty: row.get("difficulty"),
+        theme: row.get("theme"),
+        scoring_scheme_version: row.get("scoring_scheme_version"),
+        bonus_rules: row.get("bonus_rules"),
+        game_rules: row.get("game_rules"),
+        mode: row.get("mode"),
+        time_limit_seconds: row.get("time_limit_seconds"),
+        language: row.get("language"),
+        revision: row.get("revision"),
+        deleted_at: row
+            .get::<Option<String>, _>("deleted_at")
+            .map(|dt| chrono::DateTime::parse_from_rfc3339(&dt).map(|dt| dt.with_timezone(&Utc)))
+            .transpose()?,
+        completed: row.get::<i32, _>("completed") != 0,
+        completed_at: row
+            .get::<Option<String>, _>("completed_at")
+            .map(|dt| chrono::DateTime::parse_from_rfc3339(&dt).map(|dt| dt.with_timezone(&Utc)))
+            .transpose()?,
+        created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?
+            .with_timezone(&Utc),
+    })
+}
+
+fn final_game_stats_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbFinalGameStats> {
+    Ok(DbFinalGameStats {
+        game_id: row.get("game_id"),
+        total_players: row.get("total_players"),
+        average_score: row.get("average_score"),
+        highest_score: row.get("highest_score"),
+        frozen_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("frozen_at"))?
+            .with_timezone(&Utc),
+    })
+}
+
+fn game_stats_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbGameStats> {
+    Ok(DbGameStats {
+        game_id: row.get("game_id"),
+        players: row.get("players"),
+        completed_players: row.get("completed_players"),
+        median_score: row.get("median_score"),
+        optimal_score: row.get("optimal_score"),
+        completion_rate: row.get("completion_rate"),
+        threshold_hit_rate: row.get("threshold_hit_rate"),
+        computed_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("computed_at"))?
+            .with_timezone(&Utc),
+    })
+}
+
+fn notification_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbNotification> {
+    Ok(DbNotification {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        kind: row.get("kind"),
+        game_id: row.get("game_id"),
+        message: row.get("message"),
+        created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?
+            .with_timezone(&Utc),
+        read_at: row
+            .get::<Option<String>, _>("read_at")
+            .map(|dt| chrono::DateTime::parse_from_rfc3339(&dt).map(|dt| dt.with_timezone(&Utc)))
+            .transpose()?,
+    })
+}
+
+fn hint_usage_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<DbHintUsage> {
+    Ok(DbHintUsage {
+        user_id: row.get("user_id"),
+        game_id: row.get("game_id"),
+        word: row.get("word"),
+        level: row.get("level"),
+        created_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("created_at"))?
+            .with_timezone(&Utc),
+        updated_at: chrono::DateTime::parse_from_rfc3339(&row.get::<String, _>("updated_at"))?
+            .with_timezone(&Utc),
+    })
+}
+
 #[cfg(all(test, feature = "database-tests"))]
 mod tests {
     use sqlx::{Pool, Sqlite};
 
     use super::*;
+    use crate::test_utils;
 
     fn create_test_board_data() -> String {
         r#"{"rows":[{"tiles":[{"letter":"t","points":1,"is_wildcard":false,"row":0,"col":0},{"letter":"e","points":1,"is_wildcard":false,"row":0,"col":1},{"letter":"s","points":1,"is_wildcard":false,"row":0,"col":2},{"letter":"t","points":1,"is_wildcard":false,"row":0,"col":3}]}]}"#.to_string()
@@ -575,7 +2867,7 @@ mod tests {
             date: "2025-06-08".to_string(),
             board_data: create_test_board_data(),
             threshold_score: 40,
-            sequence_number: 1,
+            ..test_utils::create_new_test_game()
         };
 
         let (created_game, _) = repo
@@ -613,19 +2905,21 @@ mod tests {
                 date: "2025-06-08".to_string(),
                 board_data: create_test_board_data(),
                 threshold_score: 40,
-                sequence_number: 1,
+                ..test_utils::create_new_test_game()
             },
             NewGame {
                 date: "2025-06-07".to_string(),
                 board_data: create_test_board_data(),
                 threshold_score: 35,
                 sequence_number: 2,
+                ..test_utils::create_new_test_game()
             },
             NewGame {
                 date: "2025-06-06".to_string(),
                 board_data: create_test_board_data(),
                 threshold_score: 45,
                 sequence_number: 5,
+                ..test_utils::create_new_test_game()
             },
         ];
 
@@ -673,7 +2967,7 @@ mod tests {
             date: "2025-06-08".to_string(),
             board_data: create_test_board_data(),
             threshold_score: 40,
-            sequence_number: 1,
+            ..test_utils::create_new_test_game()
         };
         repo.create_game_with_answers(new_game, vec![], None)
             .await
@@ -689,6 +2983,7 @@ mod tests {
             board_data: create_test_board_data(),
             threshold_score: 35,
             sequence_number: 5,
+            ..test_utils::create_new_test_game()
         };
         repo.create_game_with_answers(new_game, vec![], None)
             .await
@@ -712,7 +3007,7 @@ mod tests {
             date: "2025-06-08".to_string(),
             board_data: create_test_board_data(),
             threshold_score: 40,
-            sequence_number: 1,
+            ..test_utils::create_new_test_game()
         };
         repo.create_game_with_answers(new_game, vec![], None)
             .await
@@ -740,7 +3035,7 @@ mod tests {
             date: "2025-06-08".to_string(),
             board_data: create_test_board_data(),
             threshold_score: 40,
-            sequence_number: 1,
+            ..test_utils::create_new_test_game()
         };
         let (created_game, _) = repo
             .create_game_with_answers(new_game, vec![], None)
@@ -755,4 +3050,416 @@ mod tests {
         assert_eq!(game.date, "2025-06-08");
         assert_eq!(game.sequence_number, 1);
     }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_create_games_with_answers_is_atomic(pool: Pool<Sqlite>) {
+        let repo = SqliteRepository::new(pool);
+
+        let batch = |date: &str, sequence_number: i32| NewGameWithAnswers {
+            game: NewGame {
+                date: date.to_string(),
+                board_data: create_test_board_data(),
+                threshold_score: 40,
+                sequence_number,
+                ..test_utils::create_new_test_game()
+            },
+            answers: vec![NewGameAnswer {
+                game_id: String::new(),
+                word: "test".to_string(),
+                path_stats: None,
+            }],
+            optimal_solution: None,
+            density: None,
+            quality: None,
+        };
+
+        let created = repo
+            .create_games_with_answers(vec![batch("2025-06-08", 1), batch("2025-06-09", 2)])
+            .await
+            .unwrap();
+        assert_eq!(created.len(), 2);
+        assert_eq!(
+            repo.get_game_words(&created[1].id).await.unwrap(),
+            vec!["test".to_string()]
+        );
+
+        // The second game reuses a sequence number, so the first must not be kept either
+        let result = repo
+            .create_games_with_answers(vec![batch("2025-06-10", 3), batch("2025-06-11", 2)])
+            .await;
+        assert!(result.is_err());
+        assert!(!repo.game_exists_for_date("2025-06-10").await.unwrap());
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_replaced_game_keeps_its_entries(pool: Pool<Sqlite>) {
+        let repo = SqliteRepository::new(pool);
+        let board = |threshold_score: i32| NewGameWithAnswers {
+            game: NewGame {
+                date: "2025-06-08".to_string(),
+                board_data: create_test_board_data(),
+                threshold_score,
+                ..test_utils::create_new_test_game()
+            },
+            answers: vec![],
+            optimal_solution: None,
+            density: None,
+            quality: None,
+        };
+        let original = repo
+            .create_games_with_answers(vec![board(40)])
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        let user = repo
+            .create_user(NewUser {
+                cookie_token: "player".to_string(),
+            })
+            .await
+            .unwrap();
+        repo.create_or_update_game_entry(NewGameEntry {
+            user_id: user.id.clone(),
+            game_id: original.id.clone(),
+            answers_data: r#"[{"word":"test","score":4}]"#.to_string(),
+            total_score: 4,
+            completed: true,
+            suspicion_score: 0,
+            exclude: false,
+            request_id: None,
+        })
+        .await
+        .unwrap();
+
+        // The replacement takes the date and number, whatever it was staged with
+        let mut staged = board(30);
+        staged.game.date = "2025-06-09".to_string();
+        staged.game.sequence_number = 0;
+        let replacement = repo
+            .replace_game(&original.id, staged)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(replacement.revision, 2);
+        assert_eq!(replacement.date, "2025-06-08");
+        assert_eq!(replacement.sequence_number, 1);
+        assert_eq!(
+            repo.get_game_by_date("2025-06-08")
+                .await
+                .unwrap()
+                .unwrap()
+                .id,
+            replacement.id
+        );
+        assert_eq!(
+            repo.get_game_by_sequence_number(1)
+                .await
+                .unwrap()
+                .unwrap()
+                .id,
+            replacement.id
+        );
+
+        // The old revision and the score played on it are kept
+        let replaced = repo.get_game_by_id(&original.id).await.unwrap().unwrap();
+        assert!(replaced.is_replaced());
+        let entry = repo
+            .get_game_entry(&user.id, &original.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.total_score, 4);
+
+        assert!(repo
+            .replace_game(&original.id, board(30))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_record_hint_usage_never_lowers_level(pool: Pool<Sqlite>) {
+        let repo = SqliteRepository::new(pool);
+
+        let user = repo
+            .create_user(NewUser {
+                cookie_token: "hint-cookie".to_string(),
+            })
+            .await
+            .unwrap();
+        let new_game = NewGame {
+            date: "2025-06-08".to_string(),
+            board_data: create_test_board_data(),
+            threshold_score: 40,
+            ..test_utils::create_new_test_game()
+        };
+        let (game, _) = repo
+            .create_game_with_answers(new_game, vec![], None)
+            .await
+            .unwrap();
+
+        let usage = |word: &str, level| NewHintUsage {
+            user_id: user.id.clone(),
+            game_id: game.id.clone(),
+            word: word.to_string(),
+            level,
+        };
+
+        repo.record_hint_usage(usage("test", 1)).await.unwrap();
+        let raised = repo.record_hint_usage(usage("test", 3)).await.unwrap();
+        assert_eq!(raised.level, 3);
+        let replayed = repo.record_hint_usage(usage("test", 2)).await.unwrap();
+        assert_eq!(replayed.level, 3);
+        repo.record_hint_usage(usage("sett", 1)).await.unwrap();
+
+        let usages = repo.get_hint_usage(&user.id, &game.id).await.unwrap();
+        let levels: Vec<(&str, i32)> = usages
+            .iter()
+            .map(|usage| (usage.word.as_str(), usage.level))
+            .collect();
+        assert_eq!(levels, vec![("test", 3), ("sett", 1)]);
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_retried_entry_write_changes_nothing(pool: Pool<Sqlite>) {
+        let repo = SqliteRepository::new(pool);
+        let user = repo
+            .create_user(NewUser {
+                cookie_token: "retry-cookie".to_string(),
+            })
+            .await
+            .unwrap();
+        let new_game = NewGame {
+            date: "2025-06-09".to_string(),
+            board_data: create_test_board_data(),
+            threshold_score: 40,
+            ..test_utils::create_new_test_game()
+        };
+        let (game, _) = repo
+            .create_game_with_answers(new_game, vec![], None)
+            .await
+            .unwrap();
+
+        let write = |total_score, request_id: &str| {
+            let entry = NewGameEntry {
+                user_id: user.id.clone(),
+                game_id: game.id.clone(),
+                answers_data: "[]".to_string(),
+                total_score,
+                completed: false,
+                suspicion_score: 0,
+                exclude: false,
+                request_id: Some(request_id.to_string()),
+            };
+            let event = NewOutboxEvent {
+                event_type: "entry_saved".to_string(),
+                payload: "{}".to_string(),
+            };
+            repo.create_or_update_game_entry_with_events(entry, vec![event])
+        };
+
+        write(10, "first").await.unwrap();
+        let retried = write(10, "first").await.unwrap();
+        assert_eq!(retried.total_score, 10);
+        let pending = repo.get_pending_outbox_events(10, 10).await.unwrap();
+        assert_eq!(pending.len(), 1);
+
+        let next = write(20, "second").await.unwrap();
+        assert_eq!(next.total_score, 20);
+        assert_eq!(next.request_id.as_deref(), Some("second"));
+        let pending = repo.get_pending_outbox_events(10, 10).await.unwrap();
+        assert_eq!(pending.len(), 2);
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_entry_answers_are_written_through(pool: Pool<Sqlite>) {
+        let repo = SqliteRepository::new(pool.clone());
+        let new_game = NewGame {
+            date: "2025-06-10".to_string(),
+            board_data: create_test_board_data(),
+            threshold_score: 40,
+            ..test_utils::create_new_test_game()
+        };
+        let (game, _) = repo
+            .create_game_with_answers(new_game, vec![], None)
+            .await
+            .unwrap();
+
+        let mut users = Vec::new();
+        for (cookie, answers_data) in [
+            (
+                "first",
+                r#"{"version":2,"payload":{"answers":[{"word":"test","score":4,"traced_path":null},{"word":"sett","score":4,"traced_path":null}]}}"#,
+            ),
+            ("second", r#"[{"word":"test","score":4}]"#),
+        ] {
+            let user = repo
+                .create_user(NewUser {
+                    cookie_token: cookie.to_string(),
+                })
+                .await
+                .unwrap();
+            repo.create_or_update_game_entry(NewGameEntry {
+                user_id: user.id.clone(),
+                game_id: game.id.clone(),
+                answers_data: answers_data.to_string(),
+                total_score: 8,
+                completed: true,
+                suspicion_score: 0,
+                exclude: false,
+                request_id: None,
+            })
+            .await
+            .unwrap();
+            users.push(user);
+        }
+
+        let counts = |repo: SqliteRepository| {
+            let game_id = game.id.clone();
+            async move {
+                repo.get_word_counts(&game_id)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|count| (count.word, count.players))
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(
+            counts(repo.clone()).await,
+            vec![("test".to_string(), 2), ("sett".to_string(), 1)]
+        );
+
+        // Rewriting an entry replaces its answers rather than adding to them
+        repo.create_or_update_game_entry(NewGameEntry {
+            user_id: users[0].id.clone(),
+            game_id: game.id.clone(),
+            answers_data: r#"[{"word":"tset","score":4}]"#.to_string(),
+            total_score: 4,
+            completed: true,
+            suspicion_score: 0,
+            exclude: false,
+            request_id: None,
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            counts(repo.clone()).await,
+            vec![("test".to_string(), 1), ("tset".to_string(), 1)]
+        );
+
+        repo.delete_user(&users[1].id).await.unwrap();
+        assert_eq!(counts(repo.clone()).await, vec![("tset".to_string(), 1)]);
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_lists_are_filtered_and_paged(pool: Pool<Sqlite>) {
+        let repo = SqliteRepository::new(pool);
+        let mut games = Vec::new();
+        for (i, (date, language)) in [
+            ("2025-06-01", "en"),
+            ("2025-06-02", "en"),
+            ("2025-06-02", "es"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let new_game = NewGame {
+                date: date.to_string(),
+                board_data: create_test_board_data(),
+                threshold_score: 40,
+                sequence_number: i as i32 + 1,
+                language: language.to_string(),
+                ..test_utils::create_new_test_game()
+            };
+            let (game, _) = repo
+                .create_game_with_answers(new_game, vec![], None)
+                .await
+                .unwrap();
+            games.push(game);
+        }
+
+        let english = GameFilter {
+            language: Some(Language::English),
+            ..GameFilter::default()
+        };
+        let page = repo
+            .list_games(&english, PageRequest::new(1, 1))
+            .await
+            .unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.items[0].id, games[1].id);
+        let page = repo
+            .list_games(
+                &GameFilter {
+                    to_date: Some("2025-06-01".to_string()),
+                    ..GameFilter::default()
+                },
+                PageRequest::new(1, 10),
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].id, games[0].id);
+
+        for cookie in ["first", "second", "third"] {
+            let user = repo
+                .create_user(NewUser {
+                    cookie_token: cookie.to_string(),
+                })
+                .await
+                .unwrap();
+            repo.create_or_update_game_entry(NewGameEntry {
+                user_id: user.id,
+                game_id: games[0].id.clone(),
+                answers_data: "[]".to_string(),
+                total_score: 0,
+                completed: false,
+                suspicion_score: 0,
+                exclude: false,
+                request_id: None,
+            })
+            .await
+            .unwrap();
+        }
+        let entries = repo
+            .list_entries_for_game(&games[0].id, PageRequest::new(2, 2))
+            .await
+            .unwrap();
+        assert_eq!((entries.items.len(), entries.total), (1, 3));
+
+        let users = repo.list_users(PageRequest::new(1, 2)).await.unwrap();
+        assert_eq!((users.items.len(), users.total), (2, 3));
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_expired_practice_games_are_hidden_and_deleted(pool: Pool<Sqlite>) {
+        let repo = SqliteRepository::new(pool);
+
+        let practice_game = |seed, expires_at| NewPracticeGame {
+            seed,
+            board_data: create_test_board_data(),
+            threshold_score: 40,
+            optimal_score: 60,
+            expires_at,
+        };
+        let live = repo
+            .create_practice_game(practice_game(
+                u64::MAX,
+                Utc::now() + chrono::Duration::hours(1),
+            ))
+            .await
+            .unwrap();
+        let expired = repo
+            .create_practice_game(practice_game(7, Utc::now() - chrono::Duration::hours(1)))
+            .await
+            .unwrap();
+
+        let fetched = repo.get_practice_game(&live.id).await.unwrap().unwrap();
+        assert_eq!(fetched.seed, u64::MAX);
+        assert!(repo.get_practice_game(&expired.id).await.unwrap().is_none());
+
+        assert_eq!(repo.delete_expired_practice_games().await.unwrap(), 1);
+        assert!(repo.get_practice_game(&live.id).await.unwrap().is_some());
+    }
 }
@@ -1,13 +1,38 @@
+use chrono::{DateTime, Utc};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
 use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use tracing::debug;
+
+use crate::logging::LogSampler;
+
+/// Legacy answers are read on every request for an old entry, so only some
+/// reads are logged
+static LEGACY_ANSWER_LOGS: LogSampler = LogSampler::every(100);
 
 /// Stable database types for long-term storage compatibility.
 /// These types should remain backwards compatible and only evolve carefully.
 ///
-/// Version 1.0 - Initial stable schema
+/// Version 1.0 - Initial stable schema. Answers may also carry the path
+/// they were traced along; older data simply has none.
+///
+/// Version 2 - Answers are wrapped in a `{version, payload}` envelope, and a
+/// traced path lists its wildcard choices by the wildcard's index on the
+/// board instead of fixed first and second fields. Older versions are
+/// migrated to it as they're read; they're never written.
+pub const ANSWERS_VERSION: u32 = 2;
 
+/// What every answers column written since version 2 holds. The payload is
+/// parsed only once the version says what it is.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DbAnswersEnvelope {
+    pub version: u32,
+    pub payload: serde_json::Value,
+}
+
+/// A player's answers in the current version, the envelope's payload
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DbStoredAnswers {
-    pub version: String,
     pub answers: Vec<DbAnswer>,
 }
 
@@ -15,6 +40,49 @@ pub struct DbStoredAnswers {
 pub struct DbAnswer {
     pub word: String,
     pub score: i32,
+    /// Not named `path`, which legacy answers used for a bare position list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub traced_path: Option<DbTracedPath>,
+}
+
+/// The tiles an answer was traced through, in order, and the letter each
+/// wildcard on it stood for
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DbTracedPath {
+    pub positions: Vec<DbPosition>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wildcards: Vec<DbWildcardChoice>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct DbWildcardChoice {
+    /// Which of the board's wildcards, counting from 0 in reading order
+    pub wildcard: u8,
+    pub letter: char,
+}
+
+/// Version 1.0 answers, read only to be migrated
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct DbStoredAnswersV1 {
+    pub version: String,
+    pub answers: Vec<DbAnswerV1>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct DbAnswerV1 {
+    pub word: String,
+    pub score: i32,
+    #[serde(default)]
+    pub traced_path: Option<DbTracedPathV1>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct DbTracedPathV1 {
+    pub positions: Vec<DbPosition>,
+    #[serde(default)]
+    pub first_wildcard: Option<char>,
+    #[serde(default)]
+    pub second_wildcard: Option<char>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -23,41 +91,130 @@ pub struct DbPosition {
     pub col: i32,
 }
 
+/// A game's answers after compaction, stored as deflate-compressed JSON in
+/// games.answers_blob in place of its game_answers2 rows
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DbCompactedAnswers {
+    pub version: String,
+    pub answers: Vec<DbCompactedAnswer>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DbCompactedAnswer {
+    pub word: String,
+    pub path_count: Option<i32>,
+    pub requires_wildcard: Option<bool>,
+    pub created_at: DateTime<Utc>,
+}
+
 impl DbStoredAnswers {
     pub fn new(answers: Vec<DbAnswer>) -> Self {
-        Self {
-            version: "1.0".to_string(),
-            answers,
-        }
+        Self { answers }
     }
 
-    /// Serialize to JSON string for database storage
+    /// Serialize to JSON string for database storage, in the current version's
+    /// envelope
     pub fn to_json(&self) -> Result<String, serde_json::Error> {
-        serde_json::to_string(self)
+        serde_json::to_string(&DbAnswersEnvelope {
+            version: ANSWERS_VERSION,
+            payload: serde_json::to_value(self)?,
+        })
     }
 
-    /// Deserialize from JSON string with version compatibility
+    /// Deserialize from JSON string written by any version, migrating older
+    /// ones to the current version
     pub fn from_json(json: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        // Try to parse as current version first
-        match serde_json::from_str::<DbStoredAnswers>(json) {
-            Ok(stored) => {
-                // Validate version compatibility
-                match stored.version.as_str() {
-                    "1.0" => Ok(stored),
-                    version => Err(format!("Unsupported version: {version}").into()),
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| format!("Failed to parse answers data: {e}"))?;
+
+        // Version 2 onwards
+        if let Ok(envelope) = serde_json::from_value::<DbAnswersEnvelope>(value.clone()) {
+            return match envelope.version {
+                2 => Ok(serde_json::from_value(envelope.payload)?),
+                version => Err(format!("Unsupported version: {version}").into()),
+            };
+        }
+
+        // Version 1.0, or the bare list of answers stored before versioning
+        let stored = match value {
+            serde_json::Value::Array(_) => {
+                if LEGACY_ANSWER_LOGS.sample() {
+                    debug!("Reading legacy answer data as the versioned format");
                 }
-            }
-            Err(_) => {
-                // Fallback: try to parse as legacy format (direct Vec<DbAnswer>)
-                // This maintains compatibility with data stored before versioning
-                match serde_json::from_str::<Vec<DbAnswer>>(json) {
-                    Ok(answers) => {
-                        println!("Migrating legacy answer data to versioned format");
-                        Ok(DbStoredAnswers::new(answers))
-                    }
-                    Err(e) => Err(format!("Failed to parse answers data: {e}").into()),
+                DbStoredAnswersV1 {
+                    version: "1.0".to_string(),
+                    answers: serde_json::from_value(value)
+                        .map_err(|e| format!("Failed to parse answers data: {e}"))?,
                 }
             }
+            value => serde_json::from_value::<DbStoredAnswersV1>(value)
+                .map_err(|e| format!("Failed to parse answers data: {e}"))?,
+        };
+        match stored.version.as_str() {
+            "1.0" => Ok(stored.into()),
+            version => Err(format!("Unsupported version: {version}").into()),
+        }
+    }
+}
+
+impl From<DbStoredAnswersV1> for DbStoredAnswers {
+    fn from(v1: DbStoredAnswersV1) -> Self {
+        Self::new(
+            v1.answers
+                .into_iter()
+                .map(|answer| DbAnswer {
+                    word: answer.word,
+                    score: answer.score,
+                    traced_path: answer.traced_path.map(DbTracedPath::from),
+                })
+                .collect(),
+        )
+    }
+}
+
+impl From<DbTracedPathV1> for DbTracedPath {
+    fn from(v1: DbTracedPathV1) -> Self {
+        let wildcards = [v1.first_wildcard, v1.second_wildcard]
+            .into_iter()
+            .zip(0..)
+            .filter_map(|(letter, wildcard)| {
+                Some(DbWildcardChoice {
+                    wildcard,
+                    letter: letter?,
+                })
+            })
+            .collect();
+        Self {
+            positions: v1.positions,
+            wildcards,
+        }
+    }
+}
+
+impl DbCompactedAnswers {
+    pub fn new(answers: Vec<DbCompactedAnswer>) -> Self {
+        Self {
+            version: "1.0".to_string(),
+            answers,
+        }
+    }
+
+    /// Serialize and compress for storage in games.answers_blob
+    pub fn compress(&self) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(&serde_json::to_vec(self)?)?;
+        Ok(encoder.finish()?)
+    }
+
+    /// Decompress and deserialize a blob written by `compress`
+    pub fn decompress(blob: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut json = Vec::new();
+        DeflateDecoder::new(blob).read_to_end(&mut json)?;
+        let compacted: DbCompactedAnswers = serde_json::from_slice(&json)?;
+
+        match compacted.version.as_str() {
+            "1.0" => Ok(compacted),
+            version => Err(format!("Unsupported version: {version}").into()),
         }
     }
 }
@@ -77,14 +234,15 @@ mod tests {
         let answers = vec![DbAnswer {
             word: "test".to_string(),
             score: 10,
+            traced_path: None,
         }];
 
         let stored = DbStoredAnswers::new(answers.clone());
         let json = stored.to_json().unwrap();
+        assert!(json.starts_with(r#"{"version":2,"payload":{"#));
         let deserialized = DbStoredAnswers::from_json(&json).unwrap();
 
         assert_eq!(stored, deserialized);
-        assert_eq!(deserialized.version, "1.0");
         assert_eq!(deserialized.answers, answers);
     }
 
@@ -95,20 +253,79 @@ mod tests {
             r#"[{"word":"test","score":10,"path":[{"row":0,"col":0}],"wildcard_constraints":{}}]"#;
 
         let result = DbStoredAnswers::from_json(legacy_json).unwrap();
-        assert_eq!(result.version, "1.0");
         assert_eq!(result.answers.len(), 1);
         assert_eq!(result.answers[0].word, "test");
     }
 
+    #[test]
+    fn test_compacted_answers_round_trip() {
+        let compacted = DbCompactedAnswers::new(vec![
+            DbCompactedAnswer {
+                word: "test".to_string(),
+                path_count: Some(2),
+                requires_wildcard: Some(false),
+                created_at: Utc::now(),
+            },
+            DbCompactedAnswer {
+                word: "stop".to_string(),
+                path_count: None,
+                requires_wildcard: None,
+                created_at: Utc::now(),
+            },
+        ]);
+
+        let blob = compacted.compress().unwrap();
+        assert_eq!(DbCompactedAnswers::decompress(&blob).unwrap(), compacted);
+        assert!(DbCompactedAnswers::decompress(b"not deflate").is_err());
+    }
+
+    #[test]
+    fn test_version_1_paths_are_migrated() {
+        let v1_json = r#"{"version":"1.0","answers":[
+            {"word":"dog","score":4,"traced_path":{"positions":[{"row":0,"col":0}],"second_wildcard":"o"}},
+            {"word":"cat","score":3,"traced_path":{"positions":[],"first_wildcard":"c","second_wildcard":"t"}},
+            {"word":"hen","score":3}
+        ]}"#;
+
+        let migrated = DbStoredAnswers::from_json(v1_json).unwrap();
+        let wildcards: Vec<Vec<DbWildcardChoice>> = migrated
+            .answers
+            .iter()
+            .map(|answer| {
+                answer
+                    .traced_path
+                    .as_ref()
+                    .map_or_else(Vec::new, |path| path.wildcards.clone())
+            })
+            .collect();
+        let choice = |wildcard, letter| DbWildcardChoice { wildcard, letter };
+        assert_eq!(
+            wildcards,
+            vec![
+                vec![choice(1, 'o')],
+                vec![choice(0, 'c'), choice(1, 't')],
+                vec![]
+            ]
+        );
+
+        // Written back, it's the current version and reads the same
+        let rewritten = migrated.to_json().unwrap();
+        assert_eq!(DbStoredAnswers::from_json(&rewritten).unwrap(), migrated);
+    }
+
     #[test]
     fn test_version_validation() {
-        let future_version_json = r#"{"version":"2.0","answers":[]}"#;
-        let result = DbStoredAnswers::from_json(future_version_json);
-
-        assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("Unsupported version"));
+        for future_version_json in [
+            r#"{"version":"2.0","answers":[]}"#,
+            r#"{"version":3,"payload":{"answers":[]}}"#,
+        ] {
+            let result = DbStoredAnswers::from_json(future_version_json);
+
+            assert!(result.is_err());
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains("Unsupported version"));
+        }
     }
 }
@@ -0,0 +1,446 @@
+use anyhow::Context;
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::HeaderMap,
+    response::{Extension, Json},
+    routing::post,
+    Router,
+};
+use chrono::Utc;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::{env, time::Duration};
+use tracing::warn;
+
+use crate::api_error::ApiError;
+use crate::db::Repository;
+use crate::http_api::{authenticate_user, ApiState};
+use crate::security::{session::SessionInfo, user_cookie::UserIdentity, utils::hash_token};
+use crate::social::content::{PuzzleContent, DEFAULT_ANNOUNCEMENT_TEMPLATE};
+use crate::social::discord::{
+    decode_hex, verify_interaction, Interaction, InteractionResponse,
+    INTERACTION_APPLICATION_COMMAND, INTERACTION_PING, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+};
+
+/// Letters link codes are made of, leaving out ones easily misread for each
+/// other. There are 32, so every random byte maps to one evenly.
+const LINK_CODE_ALPHABET: &[u8; 32] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const LINK_CODE_LENGTH: usize = 8;
+
+#[derive(Clone, Debug)]
+pub struct DiscordConfig {
+    /// The application's Ed25519 public key. Interactions are only answered
+    /// when it is set.
+    pub public_key: Option<Vec<u8>>,
+    /// Site linked from replies; the puzzle path is appended
+    pub base_url: String,
+    /// How long a link code can be entered for
+    pub link_code_ttl: Duration,
+}
+
+impl Default for DiscordConfig {
+    fn default() -> Self {
+        Self {
+            public_key: None,
+            base_url: "https://pathfinder.prof".to_string(),
+            link_code_ttl: Duration::from_secs(10 * 60),
+        }
+    }
+}
+
+impl DiscordConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(public_key) = env::var("DISCORD_PUBLIC_KEY") {
+            match decode_hex(public_key.trim()) {
+                Some(key) => config.public_key = Some(key),
+                None => warn!("DISCORD_PUBLIC_KEY is not hex, not answering Discord interactions"),
+            }
+        }
+
+        // Replies link to the same site as the announcements
+        if let Ok(base_url) = env::var("ANNOUNCEMENT_BASE_URL") {
+            config.base_url = base_url.trim_end_matches('/').to_string();
+        }
+
+        config
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct LinkDiscordRequest {
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+    /// The code the bot's link command replied with
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiDiscordLink {
+    pub discord_user_id: String,
+}
+
+/// Routes for Discord: the interactions endpoint Discord forwards commands
+/// to, and the one players link their Discord account with
+pub fn discord_router<R: Repository + Clone + Send + Sync + 'static>() -> Router<ApiState<R>> {
+    Router::new()
+        .route("/api/discord/interactions", post(handle_interaction))
+        .route("/api/discord/link", post(link_discord_account))
+}
+
+/// Answer an interaction from Discord. Commands:
+/// `puzzle` posts today's announcement to the channel, `link` privately
+/// gives the member a code to link their player with, and `score` privately
+/// tells a linked member their score and rank today.
+async fn handle_interaction<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<InteractionResponse>, ApiError> {
+    let Some(public_key) = state.discord_config.public_key.as_deref() else {
+        return Err(ApiError::not_found("Discord interactions"));
+    };
+
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+    let (Some(signature), Some(timestamp)) = (header(SIGNATURE_HEADER), header(TIMESTAMP_HEADER))
+    else {
+        return Err(ApiError::unauthorized("Missing request signature"));
+    };
+    if !verify_interaction(public_key, timestamp, &body, signature) {
+        return Err(ApiError::unauthorized("Invalid request signature"));
+    }
+
+    let interaction: Interaction = serde_json::from_slice(&body)
+        .map_err(|e| ApiError::validation(format!("Invalid interaction: {e}")))?;
+
+    match interaction.kind {
+        INTERACTION_PING => Ok(Json(InteractionResponse::pong())),
+        INTERACTION_APPLICATION_COMMAND => {
+            let Some(discord_user_id) = interaction.user_id() else {
+                return Err(ApiError::validation("Interaction has no user"));
+            };
+            let response = match interaction.command() {
+                Some("puzzle") => puzzle_reply(&state).await?,
+                Some("link") => link_reply(&state, discord_user_id).await?,
+                Some("score") => score_reply(&state, discord_user_id).await?,
+                _ => InteractionResponse::private_message("Unknown command"),
+            };
+            Ok(Json(response))
+        }
+        kind => Err(ApiError::validation(format!(
+            "Unsupported interaction type {kind}"
+        ))),
+    }
+}
+
+fn today() -> String {
+    Utc::now().format("%Y-%m-%d").to_string()
+}
+
+/// Today's announcement, for everyone in the channel
+async fn puzzle_reply<R: Repository>(state: &ApiState<R>) -> Result<InteractionResponse, ApiError> {
+    let game = state
+        .repository
+        .get_game_by_date(&today())
+        .await
+        .context("Failed to get today's game")?;
+
+    Ok(match game {
+        Some(game) => InteractionResponse::message(
+            PuzzleContent::new(&game, &state.discord_config.base_url)
+                .render(DEFAULT_ANNOUNCEMENT_TEMPLATE),
+        ),
+        None => InteractionResponse::private_message("Today's puzzle isn't out yet."),
+    })
+}
+
+/// A new code for the member to enter on the site, replacing any they were
+/// given before
+async fn link_reply<R: Repository>(
+    state: &ApiState<R>,
+    discord_user_id: &str,
+) -> Result<InteractionResponse, ApiError> {
+    let code = generate_link_code();
+    let ttl = chrono::Duration::from_std(state.discord_config.link_code_ttl)
+        .context("Invalid link code lifetime")?;
+    state
+        .repository
+        .start_discord_link(discord_user_id, &hash_token(&code), Utc::now() + ttl)
+        .await
+        .context("Failed to store Discord link code")?;
+
+    Ok(InteractionResponse::private_message(format!(
+        "Enter the code {code} in Pathfinder at {} within {} minutes to link your account.",
+        state.discord_config.base_url,
+        state.discord_config.link_code_ttl.as_secs() / 60
+    )))
+}
+
+/// The linked member's score and rank on today's game
+async fn score_reply<R: Repository>(
+    state: &ApiState<R>,
+    discord_user_id: &str,
+) -> Result<InteractionResponse, ApiError> {
+    let Some(user_id) = state
+        .repository
+        .get_discord_linked_user(discord_user_id)
+        .await
+        .context("Failed to get linked player")?
+    else {
+        return Ok(InteractionResponse::private_message(
+            "Your Discord account isn't linked to Pathfinder yet. Use /link to link it.",
+        ));
+    };
+
+    let Some(game) = state
+        .repository
+        .get_game_by_date(&today())
+        .await
+        .context("Failed to get today's game")?
+    else {
+        return Ok(InteractionResponse::private_message(
+            "Today's puzzle isn't out yet.",
+        ));
+    };
+
+    let entry = state
+        .repository
+        .get_game_entry(&user_id, &game.id)
+        .await
+        .context("Failed to get game entry")?;
+    let Some(entry) = entry.filter(|entry| entry.completed) else {
+        return Ok(InteractionResponse::private_message(format!(
+            "You haven't finished Pathfinder #{} yet.",
+            game.sequence_number
+        )));
+    };
+
+    let (total_players, user_rank, ..) = state
+        .repository
+        .get_game_stats(&game.id, entry.total_score)
+        .await
+        .context("Failed to get game stats")?;
+    Ok(InteractionResponse::private_message(format!(
+        "Pathfinder #{}: {} points, ranked {} of {}.",
+        game.sequence_number, entry.total_score, user_rank, total_players
+    )))
+}
+
+/// Link the caller's player to the Discord user a code was given to
+async fn link_discord_account<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+    Json(request): Json<LinkDiscordRequest>,
+) -> Result<Json<ApiDiscordLink>, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        request.user_id.as_ref(),
+        request.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+
+    // Codes are shown in capitals but easy to type in lower case
+    let code = request.code.trim().to_ascii_uppercase();
+    let discord_user_id = state
+        .repository
+        .complete_discord_link(&hash_token(&code), &user.id)
+        .await
+        .context("Failed to link Discord account")?
+        .ok_or_else(|| ApiError::not_found("Link code"))?;
+
+    Ok(Json(ApiDiscordLink { discord_user_id }))
+}
+
+/// generate_link_code returns a short random code that is easy to type
+fn generate_link_code() -> String {
+    let mut bytes = [0u8; LINK_CODE_LENGTH];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .expect("Failed to generate random bytes");
+    bytes
+        .iter()
+        .map(|b| LINK_CODE_ALPHABET[(*b as usize) % LINK_CODE_ALPHABET.len()] as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+    use tower::util::ServiceExt;
+
+    use crate::db::models::{NewGameEntry, NewUser};
+    use crate::db::InMemoryRepository;
+    use crate::http_api::create_secure_router;
+    use crate::security::SecurityConfig;
+    use crate::test_utils::{create_new_test_game, create_test_game_engine, create_test_request};
+
+    struct DiscordApp {
+        state: ApiState<InMemoryRepository>,
+        app: Router,
+        key_pair: Ed25519KeyPair,
+    }
+
+    fn setup_discord_app() -> DiscordApp {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let (game_engine, _temp_file) = create_test_game_engine();
+        let state = ApiState::new(InMemoryRepository::new(), game_engine).with_discord_config(
+            DiscordConfig {
+                public_key: Some(key_pair.public_key().as_ref().to_vec()),
+                ..DiscordConfig::default()
+            },
+        );
+        let app = create_secure_router(state.clone(), SecurityConfig::default());
+        DiscordApp {
+            state,
+            app,
+            key_pair,
+        }
+    }
+
+    impl DiscordApp {
+        /// Sends an interaction signed with the application's key
+        async fn interact(&self, body: &str) -> (StatusCode, serde_json::Value) {
+            let timestamp = "1700000000";
+            let signature: String = self
+                .key_pair
+                .sign(format!("{timestamp}{body}").as_bytes())
+                .as_ref()
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect();
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri("/api/discord/interactions")
+                .header("content-type", "application/json")
+                .header(SIGNATURE_HEADER, signature)
+                .header(TIMESTAMP_HEADER, timestamp)
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            let response = self.app.clone().oneshot(request).await.unwrap();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            (status, serde_json::from_slice(&body).unwrap_or_default())
+        }
+
+        /// The reply to a command used by Discord user 42
+        async fn command(&self, name: &str) -> String {
+            let body = serde_json::json!({
+                "type": 2,
+                "data": {"name": name},
+                "member": {"user": {"id": "42"}},
+            });
+            let (status, response) = self.interact(&body.to_string()).await;
+            assert_eq!(status, StatusCode::OK);
+            response["data"]["content"].as_str().unwrap().to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interactions_must_be_signed() {
+        let discord = setup_discord_app();
+        let (status, response) = discord.interact(r#"{"type":1}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response, serde_json::json!({"type": 1}));
+
+        let mut request = create_test_request(
+            Method::POST,
+            "/api/discord/interactions",
+            Some(r#"{"type":1}"#),
+        );
+        let response = discord.app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        request = create_test_request(
+            Method::POST,
+            "/api/discord/interactions",
+            Some(r#"{"type":1}"#),
+        );
+        let headers = request.headers_mut();
+        headers.insert(SIGNATURE_HEADER, "00".repeat(64).parse().unwrap());
+        headers.insert(TIMESTAMP_HEADER, "1700000000".parse().unwrap());
+        let response = discord.app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_link_then_fetch_score_and_rank() {
+        let discord = setup_discord_app();
+        let repository = &discord.state.repository;
+        let mut new_game = create_new_test_game();
+        new_game.date = today();
+        let (game, _) = repository
+            .create_game_with_answers(new_game, vec![], None)
+            .await
+            .unwrap();
+
+        assert!(discord.command("score").await.contains("isn't linked"));
+
+        let reply = discord.command("link").await;
+        let code = reply
+            .split_whitespace()
+            .nth(3)
+            .expect("reply has a code")
+            .to_ascii_lowercase();
+        let user = repository
+            .create_user(NewUser {
+                cookie_token: "discord-cookie".to_string(),
+            })
+            .await
+            .unwrap();
+        let link = |code: String| {
+            let body = serde_json::json!({
+                "user_id": user.id,
+                "cookie_token": user.cookie_token,
+                "code": code,
+            })
+            .to_string();
+            let app = discord.app.clone();
+            async move {
+                app.oneshot(create_test_request(
+                    Method::POST,
+                    "/api/discord/link",
+                    Some(&body),
+                ))
+                .await
+                .unwrap()
+                .status()
+            }
+        };
+        assert_eq!(link("WRONGCOD".to_string()).await, StatusCode::NOT_FOUND);
+        assert_eq!(link(code.clone()).await, StatusCode::OK);
+        // Codes only work once
+        assert_eq!(link(code).await, StatusCode::NOT_FOUND);
+
+        assert!(discord.command("score").await.contains("haven't finished"));
+
+        repository
+            .create_or_update_game_entry(NewGameEntry {
+                user_id: user.id.clone(),
+                game_id: game.id.clone(),
+                answers_data: "[]".to_string(),
+                total_score: 37,
+                completed: true,
+                suspicion_score: 0,
+                exclude: false,
+                request_id: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            discord.command("score").await,
+            "Pathfinder #1: 37 points, ranked 1 of 1."
+        );
+        assert!(discord.command("puzzle").await.contains("Pathfinder #1"));
+    }
+}
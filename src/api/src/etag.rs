@@ -0,0 +1,113 @@
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use base64::{engine::general_purpose, Engine as _};
+
+/// Games and their paths never change once generated, but browsers check
+/// again after a day in case a game was regenerated
+pub const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=86400";
+
+/// For responses whose content depends on when they are asked for, like
+/// today's game: always revalidate, but a matching ETag still saves the body
+pub const REVALIDATE_CACHE_CONTROL: &str = "no-cache";
+
+/// ETag is a strong entity tag for a response, a hash of whatever identifies
+/// its content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ETag(String);
+
+impl ETag {
+    pub fn of(parts: &[&[u8]]) -> Self {
+        let mut context = ring::digest::Context::new(&ring::digest::SHA256);
+        for part in parts {
+            // Length-prefixed so ("ab", "c") and ("a", "bc") differ
+            context.update(&(part.len() as u64).to_le_bytes());
+            context.update(part);
+        }
+        let digest = context.finish();
+        // 128 bits is plenty to tell versions of one resource apart
+        Self(format!(
+            "\"{}\"",
+            general_purpose::URL_SAFE_NO_PAD.encode(&digest.as_ref()[..16])
+        ))
+    }
+
+    /// matches reports whether the request's If-None-Match names this tag,
+    /// so the client's copy is current
+    pub fn matches(&self, headers: &HeaderMap) -> bool {
+        headers
+            .get_all(header::IF_NONE_MATCH)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag.trim_start_matches("W/") == self.0)
+    }
+
+    /// respond sends 304 Not Modified when the client's copy is current and
+    /// `body` otherwise, tagged and with `cache_control`
+    pub fn respond(
+        &self,
+        headers: &HeaderMap,
+        cache_control: &'static str,
+        body: impl IntoResponse,
+    ) -> Response {
+        let mut response = if self.matches(headers) {
+            StatusCode::NOT_MODIFIED.into_response()
+        } else {
+            body.into_response()
+        };
+        let response_headers = response.headers_mut();
+        if let Ok(etag) = HeaderValue::from_str(&self.0) {
+            response_headers.insert(header::ETAG, etag);
+        }
+        response_headers.insert(
+            header::CACHE_CONTROL,
+            HeaderValue::from_static(cache_control),
+        );
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn if_none_match(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_etag_matches_listed_and_weak_tags() {
+        let etag = ETag::of(&[b"game", b"board"]);
+        let tag = etag.0.clone();
+
+        assert!(etag.matches(&if_none_match(&tag)));
+        assert!(etag.matches(&if_none_match(&format!("\"other\", W/{tag}"))));
+        assert!(etag.matches(&if_none_match("*")));
+        assert!(!etag.matches(&if_none_match("\"other\"")));
+        assert!(!etag.matches(&HeaderMap::new()));
+
+        assert_ne!(etag, ETag::of(&[b"gam", b"eboard"]));
+    }
+
+    #[test]
+    fn test_respond_sends_not_modified_without_body() {
+        let etag = ETag::of(&[b"game"]);
+
+        let response = etag.respond(&HeaderMap::new(), IMMUTABLE_CACHE_CONTROL, "body");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::ETAG], etag.0.as_str());
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            IMMUTABLE_CACHE_CONTROL
+        );
+
+        let response = etag.respond(&if_none_match(&etag.0), IMMUTABLE_CACHE_CONTROL, "body");
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag.0.as_str());
+    }
+}
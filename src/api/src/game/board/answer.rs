@@ -13,15 +13,28 @@ pub struct Answer {
 }
 
 impl Answer {
-    /// score gets an approximate score for this Answer. it is approximate
-    /// because the Answer contains multiple possible paths to form the word,
-    /// and each path can potentially score differently.
+    /// path_count is the number of distinct paths that spell this word
+    pub fn path_count(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// requires_wildcard reports whether every path uses at least one
+    /// wildcard tile, i.e. the word can't be spelled from letter tiles alone
+    pub fn requires_wildcard(&self) -> bool {
+        !self.paths.is_empty()
+            && self
+                .paths
+                .iter()
+                .all(|path| path.tiles.iter().any(|tile| tile.is_wildcard))
+    }
+
+    /// score is the most any of the word's paths scores, since a player
+    /// traces whichever scores best. Paths come in search order, and with
+    /// multiplier tiles a later one can be worth several times the first.
+    /// In a list of answers the wildcards the other words need can leave a
+    /// word only its lower scoring paths.
     pub fn score(&self) -> i32 {
-        if let Some(path) = self.paths.first() {
-            path.tiles.iter().map(|tile| tile.points).sum()
-        } else {
-            0
-        }
+        self.paths.iter().map(Path::points).max().unwrap_or(0)
     }
 }
 
@@ -36,6 +49,7 @@ mod tests {
     use super::*;
     use crate::game::board::{
         constraints::PathConstraintSet,
+        modifier::TileModifier,
         path::{GameTile, Path},
     };
 
@@ -48,6 +62,7 @@ mod tests {
             letter: letter.to_string(),
             points,
             is_wildcard: false,
+            modifier: None,
         }
     }
 
@@ -58,6 +73,7 @@ mod tests {
             letter: "*".to_string(),
             points,
             is_wildcard: true,
+            modifier: None,
         }
     }
 
@@ -92,6 +108,33 @@ mod tests {
         assert_eq!(answer.score(), 4);
     }
 
+    #[test]
+    fn test_answer_score_is_its_best_path() {
+        let plain = create_test_path(vec![
+            create_test_tile(0, 0, 'c', 2),
+            create_test_tile(0, 1, 'a', 1),
+            create_test_tile(0, 2, 't', 1),
+        ]);
+        let mut tripled = create_test_tile(1, 2, 't', 1);
+        tripled.modifier = Some(TileModifier::TripleWord);
+        let through_multiplier = create_test_path(vec![
+            create_test_tile(0, 0, 'c', 2),
+            create_test_tile(0, 1, 'a', 1),
+            tripled,
+        ]);
+
+        let answer = Answer {
+            word: "cat".to_string(),
+            paths: vec![plain, through_multiplier],
+            constraints_set: AnswerGroupConstraintSet {
+                path_constraint_sets: vec![],
+            },
+        };
+
+        // The second path triples the word: (2 + 1 + 1) * 3
+        assert_eq!(answer.score(), 12);
+    }
+
     #[test]
     fn test_answer_score_empty_paths() {
         let answer = Answer {
@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::game::board::constraints::{AnswerGroupConstraintSet, PathConstraintSet};
+
+/// The letters one way of playing a word needs the wildcards to be. A
+/// wildcard the path doesn't go through is left out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct WildcardLetters {
+    pub first: Option<char>,
+    pub second: Option<char>,
+}
+
+impl From<PathConstraintSet> for WildcardLetters {
+    fn from(constraints: PathConstraintSet) -> Self {
+        let (first, second) = match constraints {
+            PathConstraintSet::Unconstrainted => (None, None),
+            PathConstraintSet::FirstDecided(first) => (Some(first), None),
+            PathConstraintSet::SecondDecided(second) => (None, Some(second)),
+            PathConstraintSet::BothDecided(first, second) => (Some(first), Some(second)),
+        };
+        Self { first, second }
+    }
+}
+
+impl fmt::Display for WildcardLetters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.first, self.second) {
+            (Some(first), Some(second)) => write!(f, "the wildcards as {first} and {second}"),
+            (Some(first), None) => write!(f, "the first wildcard as {first}"),
+            (None, Some(second)) => write!(f, "the second wildcard as {second}"),
+            (None, None) => write!(f, "no wildcard"),
+        }
+    }
+}
+
+/// What a word in a conflict needs from the wildcards: any one of `options`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WildcardDemand {
+    pub word: String,
+    pub options: Vec<WildcardLetters>,
+}
+
+impl fmt::Display for WildcardDemand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let options: Vec<String> = self.options.iter().map(|o| o.to_string()).collect();
+        write!(f, "'{}' needs {}", self.word, options.join(" or "))
+    }
+}
+
+/// ConstraintConflict explains why answers can't all be played together:
+/// a minimal set of them that still can't, and the wildcard letters each of
+/// those needs. Removing any one of `words` resolves this conflict.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstraintConflict {
+    pub words: Vec<String>,
+    pub demands: Vec<WildcardDemand>,
+}
+
+impl ConstraintConflict {
+    /// explain finds a minimal subset of `answers` that can't agree on the
+    /// wildcards, removing each word in turn and keeping it out whenever
+    /// the rest still conflict. No word of it can be dropped, though another
+    /// subset may conflict with fewer words. Answers that don't conflict at
+    /// all are all returned.
+    pub fn explain<'a>(
+        answers: impl IntoIterator<Item = (&'a str, &'a AnswerGroupConstraintSet)>,
+    ) -> Self {
+        let mut conflicting: Vec<(&str, &AnswerGroupConstraintSet)> = answers.into_iter().collect();
+        let mut i = 0;
+        while i < conflicting.len() {
+            let removed = conflicting.remove(i);
+            if !conflicts(conflicting.iter().map(|(_, constraints)| *constraints)) {
+                conflicting.insert(i, removed);
+                i += 1;
+            }
+        }
+
+        Self {
+            words: conflicting
+                .iter()
+                .map(|(word, _)| word.to_string())
+                .collect(),
+            demands: conflicting
+                .iter()
+                .map(|(word, constraints)| WildcardDemand {
+                    word: word.to_string(),
+                    options: constraints
+                        .path_constraint_sets
+                        .iter()
+                        .map(|&constraints| WildcardLetters::from(constraints))
+                        .collect::<BTreeSet<_>>()
+                        .into_iter()
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for ConstraintConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let words: Vec<String> = self.words.iter().map(|word| format!("'{word}'")).collect();
+        let demands: Vec<String> = self.demands.iter().map(|d| d.to_string()).collect();
+        write!(
+            f,
+            "Answers {} have conflicting wildcard constraints: {}",
+            words.join(", "),
+            demands.join("; ")
+        )
+    }
+}
+
+/// Whether no choice of wildcard letters lets every one of `sets` be played
+fn conflicts<'a>(sets: impl Iterator<Item = &'a AnswerGroupConstraintSet>) -> bool {
+    let mut agreed: Option<AnswerGroupConstraintSet> = None;
+    for set in sets {
+        agreed = match agreed {
+            None => Some(set.clone()),
+            Some(agreed) => match agreed.intersection(set.clone()) {
+                Ok(agreed) => Some(agreed),
+                Err(_) => return true,
+            },
+        };
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(sets: &[PathConstraintSet]) -> AnswerGroupConstraintSet {
+        AnswerGroupConstraintSet::from(sets.to_vec())
+    }
+
+    #[test]
+    fn test_explain_finds_a_minimal_conflict() {
+        let free = options(&[PathConstraintSet::Unconstrainted]);
+        let cat = options(&[PathConstraintSet::FirstDecided('a')]);
+        let either = options(&[
+            PathConstraintSet::FirstDecided('a'),
+            PathConstraintSet::SecondDecided('e'),
+        ]);
+        let cot = options(&[PathConstraintSet::FirstDecided('o')]);
+        let set = options(&[PathConstraintSet::SecondDecided('x')]);
+
+        // 'set' only conflicts with 'either' once 'cot' has ruled out its
+        // first option, so all three are needed while 'tea' is not
+        let conflict = ConstraintConflict::explain([
+            ("tea", &free),
+            ("either", &either),
+            ("cot", &cot),
+            ("set", &set),
+        ]);
+        assert_eq!(conflict.words, ["either", "cot", "set"]);
+        assert_eq!(
+            conflict.demands[0].options,
+            [
+                WildcardLetters {
+                    first: None,
+                    second: Some('e')
+                },
+                WildcardLetters {
+                    first: Some('a'),
+                    second: None
+                },
+            ]
+        );
+        assert_eq!(
+            conflict.to_string(),
+            "Answers 'either', 'cot', 'set' have conflicting wildcard constraints: \
+             'either' needs the second wildcard as e or the first wildcard as a; \
+             'cot' needs the first wildcard as o; 'set' needs the second wildcard as x"
+        );
+
+        // A pair that conflicts on its own is found among the rest
+        let conflict = ConstraintConflict::explain([("cat", &cat), ("tea", &free), ("cot", &cot)]);
+        assert_eq!(conflict.words, ["cat", "cot"]);
+    }
+}
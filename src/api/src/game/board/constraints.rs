@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 
 use crate::game::board::answer::Answer;
 
@@ -6,7 +6,7 @@ use crate::game::board::answer::Answer;
 pub struct UnsatisfiableConstraint;
 
 /// PathConstraintSet represents the constraints imposed upon all wildcard tiles on the board for a particular Path
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum PathConstraintSet {
     // Unconstrainted means that the wildcard tile is unused and therefore could represent any letter
     Unconstrainted,
@@ -177,6 +177,55 @@ impl AnswerGroupConstraintSet {
         let contraint_sets = answers.iter().map(|m| m.constraints_set.clone()).collect();
         AnswerGroupConstraintSet::merge_all(contraint_sets).is_ok()
     }
+
+    /// first_wildcard is what the first wildcard could be under any of the constraint sets
+    pub fn first_wildcard(&self) -> WildcardOptions {
+        self.wildcard(|constraints| match constraints {
+            PathConstraintSet::FirstDecided(first) | PathConstraintSet::BothDecided(first, _) => {
+                Some(first)
+            }
+            _ => None,
+        })
+    }
+
+    /// second_wildcard is what the second wildcard could be under any of the constraint sets
+    pub fn second_wildcard(&self) -> WildcardOptions {
+        self.wildcard(|constraints| match constraints {
+            PathConstraintSet::SecondDecided(second)
+            | PathConstraintSet::BothDecided(_, second) => Some(second),
+            _ => None,
+        })
+    }
+
+    fn wildcard(&self, letter: impl Fn(PathConstraintSet) -> Option<char>) -> WildcardOptions {
+        let mut letters = BTreeSet::new();
+        for &constraints in &self.path_constraint_sets {
+            match letter(constraints) {
+                Some(letter) => letters.insert(letter),
+                None => return WildcardOptions::Any,
+            };
+        }
+        WildcardOptions::Letters(letters)
+    }
+}
+
+/// WildcardOptions is what one wildcard could still be for a group of answers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WildcardOptions {
+    /// Some way of playing the answers leaves the wildcard free
+    Any,
+    /// Every way of playing the answers makes the wildcard one of these
+    Letters(BTreeSet<char>),
+}
+
+impl WildcardOptions {
+    /// locked is the one letter the wildcard must be, if there's only one
+    pub fn locked(&self) -> Option<char> {
+        match self {
+            WildcardOptions::Letters(letters) if letters.len() == 1 => letters.first().copied(),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1273,4 +1322,28 @@ mod tests {
         assert!(constraint_set.contains(&PathConstraintSet::BothDecided('t', 'e')));
         assert!(constraint_set.contains(&PathConstraintSet::BothDecided('a', 'e')));
     }
+
+    #[test]
+    fn test_wildcard_options() {
+        // The same answers as above agree the second wildcard is e
+        let board = create_test_board("eadux*ysta*tnhrv");
+        let answers = ["day", "days", "year", "data"]
+            .iter()
+            .map(|a| board.paths_for(a))
+            .collect();
+        let constraints = AnswerGroupConstraintSet::try_from(&answers).unwrap();
+        assert_eq!(
+            constraints.first_wildcard(),
+            WildcardOptions::Letters(BTreeSet::from(['a', 't']))
+        );
+        assert_eq!(constraints.first_wildcard().locked(), None);
+        assert_eq!(constraints.second_wildcard().locked(), Some('e'));
+
+        let free = AnswerGroupConstraintSet::from(vec![
+            PathConstraintSet::FirstDecided('a'),
+            PathConstraintSet::Unconstrainted,
+        ]);
+        assert_eq!(free.first_wildcard(), WildcardOptions::Any);
+        assert_eq!(free.second_wildcard(), WildcardOptions::Any);
+    }
 }
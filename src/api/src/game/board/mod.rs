@@ -3,21 +3,30 @@ use crate::game::{
         answer::Answer,
         constraints::{AnswerGroupConstraintSet, PathConstraintSet},
     },
-    directions,
+    directions::Adjacency,
+    error::GameError,
+    letters,
+    scoring::ScoringScheme,
 };
 use core::fmt;
 use std::collections::{HashSet, VecDeque};
+use std::str::FromStr;
 
 pub mod answer;
+pub mod conflict;
 pub mod constraints;
+pub mod modifier;
 pub mod path;
 
+use modifier::TileModifier;
 use path::GameTile;
 
 // Native Rust types (replacing protobuf)
 #[derive(Debug, Clone, PartialEq)]
 pub struct Board {
     pub rows: Vec<Row>,
+    /// Which cells a path can step to from each cell
+    pub adjacency: Adjacency,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -32,6 +41,7 @@ pub struct Tile {
     pub is_wildcard: bool,
     pub row: i32,
     pub col: i32,
+    pub modifier: Option<TileModifier>,
 }
 
 impl Tile {
@@ -43,7 +53,9 @@ impl Tile {
         self.is_wildcard && !self.is_first_wildcard()
     }
 
-    fn as_constraint(&self, c: char) -> PathConstraintSet {
+    /// as_constraint returns the constraint placed on the wildcards by using
+    /// this tile as the letter `c`
+    pub(crate) fn as_constraint(&self, c: char) -> PathConstraintSet {
         if self.is_first_wildcard() {
             PathConstraintSet::FirstDecided(c)
         } else if self.is_second_wildcard() {
@@ -70,6 +82,67 @@ impl fmt::Display for Board {
     }
 }
 
+/// Parse a board from its 16 letters row by row, with `*` for wildcards and
+/// a tile showing several letters in parentheses, e.g. `tmitc*(qu)tsa*iinal`.
+/// Whitespace is ignored so rows can be split up.
+impl FromStr for Board {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_ascii_lowercase());
+        let mut letters: Vec<String> = Vec::with_capacity(16);
+        while let Some(c) = chars.next() {
+            if c != '(' {
+                letters.push(c.to_string());
+                continue;
+            }
+            let mut letter = String::new();
+            loop {
+                match chars.next() {
+                    Some(')') => break,
+                    Some(c) => letter.push(c),
+                    None => return Err(format!("Board tile '({letter}' has no closing ')'")),
+                }
+            }
+            if letter.len() < 2 || !letter.chars().all(|c| c.is_ascii_lowercase()) {
+                return Err(format!(
+                    "Board tile '({letter})' must be two or more letters a-z"
+                ));
+            }
+            letters.push(letter);
+        }
+        if letters.len() != 16 {
+            return Err(format!(
+                "Board must have 16 letters, found {}",
+                letters.len()
+            ));
+        }
+        if letters.iter().filter(|letter| *letter == "*").count() > 2 {
+            return Err("Board can have at most two wildcards".to_string());
+        }
+
+        let mut board = Board::new();
+        for (index, letter) in letters.iter().enumerate() {
+            let (row, col) = (index / 4, index % 4);
+            match letter.as_str() {
+                "*" => board.set_tile(row, col, '*', 0, true),
+                _ if letter.chars().all(|c| c.is_ascii_lowercase()) => board.set_tile_letters(
+                    row,
+                    col,
+                    letter,
+                    crate::game::scoring::points_for_tile(letter),
+                    false,
+                ),
+                _ => return Err(format!("Board letter '{letter}' is not a-z or *")),
+            }
+        }
+        Ok(board)
+    }
+}
+
 // Convert protobuf Tile to GameTile for internal use
 impl From<&Tile> for GameTile {
     fn from(tile: &Tile) -> Self {
@@ -79,6 +152,7 @@ impl From<&Tile> for GameTile {
             is_wildcard: tile.is_wildcard,
             row: tile.row,
             col: tile.col,
+            modifier: tile.modifier,
         }
     }
 }
@@ -90,6 +164,18 @@ impl Default for Board {
 }
 
 impl Board {
+    /// score_with gives every tile the points `scheme` sets for its letter.
+    /// Boards parsed from letters start out with the standard points.
+    pub fn score_with(&mut self, scheme: &ScoringScheme) {
+        for tile in self.rows.iter_mut().flat_map(|row| &mut row.tiles) {
+            tile.points = if tile.is_wildcard {
+                scheme.wildcard_points
+            } else {
+                scheme.points_for_tile(&tile.letter)
+            };
+        }
+    }
+
     pub fn new() -> Self {
         Self {
             rows: (0..4)
@@ -101,13 +187,27 @@ impl Board {
                             is_wildcard: false,
                             row: row_idx,
                             col: col_idx,
+                            modifier: None,
                         })
                         .collect(),
                 })
                 .collect(),
+            adjacency: Adjacency::default(),
         }
     }
 
+    /// Play this board with a different adjacency, e.g. for a variant
+    pub fn with_adjacency(mut self, adjacency: Adjacency) -> Self {
+        self.adjacency = adjacency;
+        self
+    }
+
+    /// neighbors returns the cells a path can step to from (row, col)
+    pub fn neighbors(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.rows.first().map(|row| row.tiles.len()).unwrap_or(0);
+        self.adjacency.neighbors(row, col, self.rows.len(), width)
+    }
+
     pub fn set_tile(
         &mut self,
         row: usize,
@@ -115,18 +215,46 @@ impl Board {
         letter: char,
         points: i32,
         is_wildcard: bool,
+    ) {
+        self.set_tile_letters(row, col, &letter.to_string(), points, is_wildcard);
+    }
+
+    /// set_tile_letters is set_tile for tiles that may show more than one
+    /// letter, e.g. `qu`
+    pub fn set_tile_letters(
+        &mut self,
+        row: usize,
+        col: usize,
+        letters: &str,
+        points: i32,
+        is_wildcard: bool,
     ) {
         if row < 4 && col < 4 {
             self.rows[row].tiles[col] = Tile {
-                letter: letter.to_string(),
+                letter: letters.to_string(),
                 points,
                 is_wildcard,
                 row: row as i32,
                 col: col as i32,
+                modifier: None,
             };
         }
     }
 
+    /// set_modifier puts `modifier` on the tile at (row, col), or clears it.
+    /// Wildcards are left without one.
+    pub fn set_modifier(&mut self, row: usize, col: usize, modifier: Option<TileModifier>) {
+        if let Some(tile) = self
+            .rows
+            .get_mut(row)
+            .and_then(|row| row.tiles.get_mut(col))
+        {
+            if !tile.is_wildcard {
+                tile.modifier = modifier;
+            }
+        }
+    }
+
     pub fn get_tile(&self, row: usize, col: usize) -> &Tile {
         &self.rows[row].tiles[col]
     }
@@ -167,19 +295,27 @@ impl Board {
         visited: &mut HashSet<(usize, usize)>,
     ) -> Vec<path::Path> {
         let mut result = vec![];
-        let current_word_char = word.chars().next();
-        if visited.contains(&(row_number, column_number)) || current_word_char.is_none() {
+        if visited.contains(&(row_number, column_number)) || word.is_empty() {
             return result;
         }
-        let current_char = current_word_char.unwrap();
         let current_location = &self.rows[row_number].tiles[column_number];
-        let current_location_letter = current_location.letter.chars().next();
 
-        if current_location_letter != Some(current_char) && !current_location.is_wildcard {
-            return result;
-        }
+        // A tile covers as many letters of the word as it shows, e.g. two
+        // for a `qu` tile, and a wildcard stands for any one letter
+        let (current_char, covered) = if current_location.is_wildcard {
+            match letters::first_letter(word) {
+                Some(letter) => letter,
+                None => return result,
+            }
+        } else {
+            match letters::match_tile(word, &current_location.letter) {
+                Some(covered) => (word.chars().next().unwrap_or_default(), covered),
+                None => return result,
+            }
+        };
+        let rest = &word[covered..];
 
-        if word.len() == 1 {
+        if rest.is_empty() {
             let mut tiles = VecDeque::new();
             tiles.push_back(GameTile::from(current_location));
             let path = path::Path {
@@ -191,21 +327,11 @@ impl Board {
         }
 
         visited.insert((row_number, column_number));
-        for direction in directions::DIRECTIONS {
-            let next_row_number = row_number.checked_add_signed(direction.0);
-            let next_column_number = column_number.checked_add_signed(direction.1);
-            if next_row_number.is_none()
-                || next_column_number.is_none()
-                || next_row_number.unwrap() >= self.rows.len()
-                || next_column_number.unwrap() >= self.rows[next_row_number.unwrap()].tiles.len()
-            {
-                continue;
-            }
-
+        for (next_row_number, next_column_number) in self.neighbors(row_number, column_number) {
             let paths = self.paths_for_word_from_position(
-                &word[1..],
-                next_row_number.unwrap(),
-                next_column_number.unwrap(),
+                rest,
+                next_row_number,
+                next_column_number,
                 visited,
             );
 
@@ -225,12 +351,15 @@ impl Board {
     }
 
     /// Get all paths for each answer
-    pub fn get_answers_with_all_paths(&self, answers: Vec<String>) -> Result<Vec<Answer>, String> {
+    pub fn get_answers_with_all_paths(
+        &self,
+        answers: Vec<String>,
+    ) -> Result<Vec<Answer>, GameError> {
         let mut answers_with_all_paths = Vec::new();
-        for word in &answers {
-            let answer = self.new_answer(word);
+        for word in answers {
+            let answer = self.new_answer(&word);
             if answer.paths.is_empty() {
-                return Err(format!("Word '{word}' has no possible path on board"));
+                return Err(GameError::NotOnBoard { word });
             }
             answers_with_all_paths.push(answer);
         }
@@ -248,6 +377,44 @@ mod tests {
         test_utils::create_test_board("abcdabcdab*dappd")
     }
 
+    #[test]
+    fn test_board_from_str() {
+        let board: Board = "TMIT c*ot sa*i inal".parse().unwrap();
+        assert_eq!(
+            board.to_string(),
+            test_utils::create_test_board("tmitc*otsa*iinal").to_string()
+        );
+        assert_eq!(
+            board.get_tile(0, 0).points,
+            crate::game::scoring::points_for_letter('t')
+        );
+        assert!(board.get_tile(1, 1).is_first_wildcard());
+        assert!(board.get_tile(2, 2).is_second_wildcard());
+
+        assert!("tmitc*ot".parse::<Board>().is_err());
+        assert!("tmitc*otsa*i*nal".parse::<Board>().is_err());
+        assert!("tmitc*otsa*iina1".parse::<Board>().is_err());
+    }
+
+    #[test]
+    fn test_paths_through_multi_letter_tiles() {
+        // qu i t s
+        // a  e o t
+        // s  a n i
+        // i  n a l
+        let board: Board = "(qu)itsaeotsaniinal".parse().unwrap();
+
+        // The qu tile covers both letters, and only together
+        let answer = board.paths_for("quit");
+        assert_eq!(answer.paths.len(), 1);
+        assert_eq!(answer.paths[0].tiles.len(), 3);
+        assert!(board.paths_for("qit").paths.is_empty());
+        assert!(board.paths_for("uit").paths.is_empty());
+
+        // Words that aren't whole letters on the board never match
+        assert!(board.paths_for("qu\u{308}it").paths.is_empty());
+    }
+
     #[test]
     fn test_basic_path_finding() {
         let board = test_board();
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+
+/// TileModifier multiplies what a tile is worth to the words traced through
+/// it: letter modifiers multiply the tile's own points, word modifiers the
+/// whole word's. Wildcards never carry one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TileModifier {
+    #[serde(rename = "DL")]
+    DoubleLetter,
+    #[serde(rename = "TL")]
+    TripleLetter,
+    #[serde(rename = "DW")]
+    DoubleWord,
+    #[serde(rename = "TW")]
+    TripleWord,
+}
+
+impl TileModifier {
+    pub fn letter_multiplier(self) -> i32 {
+        match self {
+            TileModifier::DoubleLetter => 2,
+            TileModifier::TripleLetter => 3,
+            TileModifier::DoubleWord | TileModifier::TripleWord => 1,
+        }
+    }
+
+    pub fn word_multiplier(self) -> i32 {
+        match self {
+            TileModifier::DoubleWord => 2,
+            TileModifier::TripleWord => 3,
+            TileModifier::DoubleLetter | TileModifier::TripleLetter => 1,
+        }
+    }
+}
+
+/// How many tiles of each modifier a generated board gets. None by default,
+/// which leaves boards exactly as they were before modifiers existed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ModifierCounts {
+    pub double_letter: usize,
+    pub triple_letter: usize,
+    pub double_word: usize,
+    pub triple_word: usize,
+}
+
+impl ModifierCounts {
+    /// from_env reads BOARD_DOUBLE_LETTERS, BOARD_TRIPLE_LETTERS,
+    /// BOARD_DOUBLE_WORDS and BOARD_TRIPLE_WORDS
+    pub fn from_env() -> Self {
+        let count = |name: &str| {
+            env::var(name)
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(0)
+        };
+        Self {
+            double_letter: count("BOARD_DOUBLE_LETTERS"),
+            triple_letter: count("BOARD_TRIPLE_LETTERS"),
+            double_word: count("BOARD_DOUBLE_WORDS"),
+            triple_word: count("BOARD_TRIPLE_WORDS"),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
+    pub fn total(&self) -> usize {
+        self.double_letter + self.triple_letter + self.double_word + self.triple_word
+    }
+
+    /// Every modifier to place, word modifiers first
+    pub fn modifiers(&self) -> impl Iterator<Item = TileModifier> {
+        [
+            (TileModifier::TripleWord, self.triple_word),
+            (TileModifier::DoubleWord, self.double_word),
+            (TileModifier::TripleLetter, self.triple_letter),
+            (TileModifier::DoubleLetter, self.double_letter),
+        ]
+        .into_iter()
+        .flat_map(|(modifier, count)| std::iter::repeat_n(modifier, count))
+    }
+}
@@ -2,6 +2,7 @@ use std::collections::VecDeque;
 use std::fmt::Debug;
 
 use super::constraints;
+use super::modifier::TileModifier;
 
 // Since we're using protobuf, we'll work with the generated Tile type
 // and create a compatibility layer
@@ -12,6 +13,7 @@ pub struct GameTile {
     pub is_wildcard: bool,
     pub row: i32,
     pub col: i32,
+    pub modifier: Option<TileModifier>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -19,3 +21,23 @@ pub struct Path {
     pub tiles: VecDeque<GameTile>,
     pub constraints: constraints::PathConstraintSet,
 }
+
+impl Path {
+    /// points is what the path scores: each tile's points times its letter
+    /// multiplier, with the sum multiplied by every word multiplier passed
+    /// through
+    pub fn points(&self) -> i32 {
+        let mut word_multiplier = 1;
+        let mut points = 0;
+        for tile in &self.tiles {
+            match tile.modifier {
+                Some(modifier) => {
+                    points += tile.points * modifier.letter_multiplier();
+                    word_multiplier *= modifier.word_multiplier();
+                }
+                None => points += tile.points,
+            }
+        }
+        points * word_multiplier
+    }
+}
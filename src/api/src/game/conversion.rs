@@ -1,9 +1,158 @@
-use crate::game::board::{Board, Row, Tile};
+use crate::game::board::{modifier::TileModifier, Board, Row, Tile};
+use crate::game::directions::Adjacency;
+use crate::game::scoring::points_for_tile;
 use serde::{Deserialize, Serialize};
 
+/// Board codes are the board's 16 letters row by row with `*` for wildcards,
+/// e.g. `tmitc*otsa*iinal`, and tiles showing several letters in parentheses,
+/// e.g. `(qu)`, followed by whichever of these sections the board
+/// needs, in this order:
+///
+/// - `.` and each tile's points as a base-36 digit, unless every tile has
+///   the standard points
+/// - `~` and each tile's modifier: `d`/`t` for double/triple letter,
+///   `D`/`T` for double/triple word, `-` for none
+/// - `@4` for a board played with four-way adjacency
+///
+/// A board has exactly one code, so codes can identify boards when they're
+/// shared.
+const POINTS_SECTION: char = '.';
+const MODIFIERS_SECTION: char = '~';
+const ADJACENCY_SECTION: char = '@';
+const FOUR_WAY_CODE: &str = "4";
+
+impl Board {
+    /// from_code parses a code written by `to_code`. Letters may be any case.
+    pub fn from_code(code: &str) -> Result<Board, String> {
+        let (code, adjacency) = match code.split_once(ADJACENCY_SECTION) {
+            Some((code, FOUR_WAY_CODE)) => (code, Adjacency::FourWay),
+            Some((_, adjacency)) => return Err(format!("Unknown adjacency '{adjacency}'")),
+            None => (code, Adjacency::default()),
+        };
+        let (code, modifiers) = match code.split_once(MODIFIERS_SECTION) {
+            Some((code, modifiers)) => (code, Some(modifiers)),
+            None => (code, None),
+        };
+        let (letters, points) = match code.split_once(POINTS_SECTION) {
+            Some((letters, points)) => (letters, Some(points)),
+            None => (code, None),
+        };
+        if letters.chars().any(char::is_whitespace) {
+            return Err("Board codes can't contain whitespace".to_string());
+        }
+
+        let mut board: Board = letters.parse()?;
+        if let Some(points) = points {
+            let points = section(points, "points")?;
+            for (tile, digit) in board.tiles_mut().zip(points) {
+                tile.points = digit
+                    .to_digit(36)
+                    .ok_or_else(|| format!("Points '{digit}' are not a base-36 digit"))?
+                    as i32;
+            }
+        }
+        if let Some(modifiers) = modifiers {
+            let modifiers = section(modifiers, "modifiers")?;
+            for (tile, code) in board.tiles_mut().zip(modifiers) {
+                let modifier = match code {
+                    '-' => None,
+                    'd' => Some(TileModifier::DoubleLetter),
+                    't' => Some(TileModifier::TripleLetter),
+                    'D' => Some(TileModifier::DoubleWord),
+                    'T' => Some(TileModifier::TripleWord),
+                    _ => return Err(format!("Unknown modifier '{code}'")),
+                };
+                if modifier.is_some() && tile.is_wildcard {
+                    return Err("Wildcards can't have a modifier".to_string());
+                }
+                tile.modifier = modifier;
+            }
+        }
+        Ok(board.with_adjacency(adjacency))
+    }
+
+    /// to_code writes the board's code. Boards with tiles that aren't letters
+    /// a to z or with points outside 0 to 35 have no code.
+    pub fn to_code(&self) -> Result<String, String> {
+        let tiles: Vec<&Tile> = self.rows.iter().flat_map(|row| &row.tiles).collect();
+        if tiles.len() != 16 {
+            return Err(format!("Board must have 16 tiles, found {}", tiles.len()));
+        }
+
+        let mut code = String::with_capacity(16);
+        for tile in &tiles {
+            match tile.letter.chars().collect::<Vec<_>>()[..] {
+                _ if tile.is_wildcard => code.push('*'),
+                [letter] if letter.is_ascii_lowercase() => code.push(letter),
+                [_, _, ..] if tile.letter.chars().all(|c| c.is_ascii_lowercase()) => {
+                    code.push('(');
+                    code.push_str(&tile.letter);
+                    code.push(')');
+                }
+                _ => return Err(format!("Tile '{}' has no code", tile.letter)),
+            }
+        }
+
+        let standard_points = tiles.iter().all(|tile| {
+            let standard = if tile.is_wildcard {
+                0
+            } else {
+                points_for_tile(&tile.letter)
+            };
+            tile.points == standard
+        });
+        if !standard_points {
+            code.push(POINTS_SECTION);
+            for tile in &tiles {
+                let digit = u32::try_from(tile.points)
+                    .ok()
+                    .and_then(|points| char::from_digit(points, 36))
+                    .ok_or_else(|| format!("Points {} have no code", tile.points))?;
+                code.push(digit);
+            }
+        }
+
+        if tiles.iter().any(|tile| tile.modifier.is_some()) {
+            code.push(MODIFIERS_SECTION);
+            code.extend(tiles.iter().map(|tile| match tile.modifier {
+                None => '-',
+                Some(TileModifier::DoubleLetter) => 'd',
+                Some(TileModifier::TripleLetter) => 't',
+                Some(TileModifier::DoubleWord) => 'D',
+                Some(TileModifier::TripleWord) => 'T',
+            }));
+        }
+
+        if self.adjacency == Adjacency::FourWay {
+            code.push(ADJACENCY_SECTION);
+            code.push_str(FOUR_WAY_CODE);
+        }
+        Ok(code)
+    }
+
+    fn tiles_mut(&mut self) -> impl Iterator<Item = &mut Tile> {
+        self.rows.iter_mut().flat_map(|row| &mut row.tiles)
+    }
+}
+
+/// section splits a per-tile section of a board code into one char per tile
+fn section(section: &str, name: &str) -> Result<Vec<char>, String> {
+    let chars: Vec<char> = section.chars().collect();
+    if chars.len() != 16 {
+        return Err(format!(
+            "Board {name} must have 16 tiles, found {}",
+            chars.len()
+        ));
+    }
+    Ok(chars)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SerializableBoard {
     pub rows: Vec<SerializableRow>,
+    /// Left out for standard boards so stored boards are unchanged
+    #[serde(default, skip_serializing_if = "Adjacency::is_default")]
+    pub adjacency: Adjacency,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +167,9 @@ pub struct SerializableTile {
     pub is_wildcard: bool,
     pub row: i32,
     pub col: i32,
+    /// Left out for plain tiles so stored boards are unchanged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modifier: Option<TileModifier>,
 }
 
 impl From<&Board> for SerializableBoard {
@@ -36,10 +188,12 @@ impl From<&Board> for SerializableBoard {
                             is_wildcard: tile.is_wildcard,
                             row: tile.row,
                             col: tile.col,
+                            modifier: tile.modifier,
                         })
                         .collect(),
                 })
                 .collect(),
+            adjacency: board.adjacency,
         }
     }
 }
@@ -60,10 +214,103 @@ impl From<SerializableBoard> for Board {
                             is_wildcard: tile.is_wildcard,
                             row: tile.row,
                             col: tile.col,
+                            modifier: tile.modifier,
                         })
                         .collect(),
                 })
                 .collect(),
+            adjacency: board.adjacency,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_test_board;
+
+    #[test]
+    fn test_adjacency_round_trips_and_is_omitted_by_default() {
+        let board = create_test_board("abcdefghijklmnop");
+        let json = serde_json::to_string(&SerializableBoard::from(&board)).unwrap();
+        assert!(!json.contains("adjacency"));
+
+        let board = board.with_adjacency(Adjacency::FourWay);
+        let json = serde_json::to_string(&SerializableBoard::from(&board)).unwrap();
+        assert!(json.contains(r#""adjacency":"four_way""#));
+        let restored: SerializableBoard = serde_json::from_str(&json).unwrap();
+        assert_eq!(Board::from(restored), board);
+    }
+
+    #[test]
+    fn test_board_codes_round_trip() {
+        let board = Board::from_code("tmitc*otsa*iinal").unwrap();
+        assert_eq!(board, "tmitc*otsa*iinal".parse::<Board>().unwrap());
+        assert_eq!(board.to_code().unwrap(), "tmitc*otsa*iinal");
+        assert_eq!(
+            Board::from_code("TMITC*OTSA*IINAL")
+                .unwrap()
+                .to_code()
+                .unwrap(),
+            "tmitc*otsa*iinal"
+        );
+
+        let mut board = board.with_adjacency(Adjacency::FourWay);
+        board.set_modifier(0, 1, Some(TileModifier::DoubleWord));
+        board.set_modifier(3, 3, Some(TileModifier::TripleLetter));
+        board.rows[0].tiles[0].points = 12;
+        let code = board.to_code().unwrap();
+        assert_eq!(code, "tmitc*otsa*iinal.c311201111011112~-D-------------t@4");
+        assert_eq!(Board::from_code(&code).unwrap(), board);
+    }
+
+    #[test]
+    fn test_invalid_board_codes_are_rejected() {
+        for code in [
+            "tmitc*otsa*iina",
+            "tmitc otsa*iinal",
+            "tmitc*otsa*iinal.313",
+            "tmitc*otsa*iinal.3131041113041!24",
+            "tmitc*otsa*iinal~-----D----------",
+            "tmitc*otsa*iinal~x---------------",
+            "tmitc*otsa*iinal@6",
+        ] {
+            assert!(Board::from_code(code).is_err(), "{code} should be rejected");
+        }
+
+        // A tile outside a to z can't be written as a code
+        let mut board = create_test_board("abcdefghijklmnop");
+        board.rows[0].tiles[0].letter = "é".to_string();
+        assert!(board.to_code().is_err());
+    }
+
+    #[test]
+    fn test_multi_letter_tiles_round_trip() {
+        let board = Board::from_code("(QU)itc*otsa*ii(ll)nal").unwrap();
+        assert_eq!(board.get_tile(0, 0).letter, "qu");
+        assert_eq!(board.get_tile(0, 0).points, points_for_tile("q"));
+        assert_eq!(board.get_tile(3, 0).letter, "ll");
+        assert_eq!(board.to_code().unwrap(), "(qu)itc*otsa*ii(ll)nal");
+
+        for code in [
+            "(qu itc*otsa*iinal",
+            "(q)itc*otsa*iinal",
+            "(*u)itc*otsa*iinal",
+        ] {
+            assert!(Board::from_code(code).is_err(), "{code} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_modifiers_round_trip_and_are_omitted_when_absent() {
+        let mut board = create_test_board("abcdefghijklmnop");
+        let json = serde_json::to_string(&SerializableBoard::from(&board)).unwrap();
+        assert!(!json.contains("modifier"));
+
+        board.set_modifier(0, 1, Some(TileModifier::DoubleWord));
+        let json = serde_json::to_string(&SerializableBoard::from(&board)).unwrap();
+        assert!(json.contains(r#""modifier":"DW""#));
+        let restored: SerializableBoard = serde_json::from_str(&json).unwrap();
+        assert_eq!(Board::from(restored), board);
+    }
+}
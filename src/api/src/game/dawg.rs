@@ -0,0 +1,730 @@
+use anyhow::{anyhow, Result};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+};
+use tracing::{info, warn};
+
+use super::trie::letter_bit;
+
+/// Magic bytes at the start of a serialized dawg file
+const MAGIC: &[u8; 8] = b"PFDAWG02";
+/// Where the checksum of the wordlist a dawg was built from is kept
+const CHECKSUM_OFFSET: usize = 16;
+/// magic + root edge list index + word count + source checksum
+const HEADER_LEN: usize = 20;
+
+// Every edge is packed into a u32:
+//   bits 0-4   letter ('a' = 0)
+//   bit  5     last edge in its node's edge list
+//   bit  6     the target node ends a word
+//   bits 7-31  index of the target node's first edge (0 = no children)
+const LETTER_MASK: u32 = 0x1f;
+const LAST_EDGE: u32 = 1 << 5;
+const TARGET_FINAL: u32 = 1 << 6;
+const TARGET_SHIFT: u32 = 7;
+const MAX_EDGES: usize = 1 << (32 - TARGET_SHIFT);
+
+/// Dawg is a minimized directed acyclic word graph: a trie in which identical
+/// suffixes are shared. Nodes are stored as lists of packed edges in a single
+/// flat buffer, which can be serialized to disk and loaded back (or memory
+/// mapped with the `mmap` feature) without rebuilding.
+///
+/// Only words made of the letters a-z are stored; anything else is skipped
+/// when building, since it can't be played on a board anyway.
+pub struct Dawg {
+    storage: Storage,
+    root: u32,
+    word_count: u32,
+}
+
+enum Storage {
+    Owned(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+impl Storage {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Storage::Owned(bytes) => bytes,
+            #[cfg(feature = "mmap")]
+            Storage::Mapped(map) => map,
+        }
+    }
+}
+
+/// DawgNode is a cheap handle to a node in a Dawg, reached by following the
+/// letters of some prefix from the root
+#[derive(Clone, Copy)]
+pub struct DawgNode<'a> {
+    dawg: &'a Dawg,
+    first_edge: u32,
+    finish: bool,
+}
+
+impl<'a> DawgNode<'a> {
+    fn edges(&self) -> impl Iterator<Item = u32> + 'a {
+        let dawg = self.dawg;
+        let mut index = self.first_edge as usize;
+        let mut done = index == 0;
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            let edge = dawg.edge(index);
+            done = edge & LAST_EDGE != 0;
+            index += 1;
+            Some(edge)
+        })
+    }
+
+    /// child returns the node for words continuing with `c`
+    pub fn child(&self, c: char) -> Option<DawgNode<'a>> {
+        let bit = letter_bit(c);
+        if bit == 0 {
+            return None;
+        }
+        let letter = bit.trailing_zeros();
+
+        self.edges()
+            .find(|edge| edge & LETTER_MASK == letter)
+            .map(|edge| DawgNode {
+                dawg: self.dawg,
+                first_edge: edge >> TARGET_SHIFT,
+                finish: edge & TARGET_FINAL != 0,
+            })
+    }
+
+    /// node returns the node reached by following `prefix`, or None if no word
+    /// starts with it
+    pub fn node(&self, prefix: &str) -> Option<DawgNode<'a>> {
+        prefix.chars().try_fold(*self, |node, c| node.child(c))
+    }
+
+    /// is_word reports whether the prefix leading to this node is a complete word
+    pub fn is_word(&self) -> bool {
+        self.finish
+    }
+
+    /// child_letters returns the set of letters that can follow this node as a
+    /// bitmask (see `letter_bit`)
+    pub fn child_letters(&self) -> u32 {
+        self.edges()
+            .fold(0, |mask, edge| mask | (1 << (edge & LETTER_MASK)))
+    }
+}
+
+impl Dawg {
+    /// build creates a minimized dawg from any collection of words. Words are
+    /// sorted and deduplicated first, as the construction requires.
+    pub fn build<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut words: Vec<String> = words
+            .into_iter()
+            .map(|w| w.as_ref().trim().to_string())
+            .filter(|w| !w.is_empty() && w.bytes().all(|b| b.is_ascii_lowercase()))
+            .collect();
+        words.sort_unstable();
+        words.dedup();
+
+        let mut builder = Builder::new();
+        for word in &words {
+            builder.insert(word.as_bytes());
+        }
+        builder.finish()
+    }
+
+    /// from_bytes loads a dawg previously produced by `to_bytes`
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        let (root, word_count) = validate(&bytes)?;
+        Ok(Self {
+            storage: Storage::Owned(bytes),
+            root,
+            word_count,
+        })
+    }
+
+    /// open loads a serialized dawg from disk. With the `mmap` feature the file
+    /// is memory mapped instead of read, so its pages are shared between
+    /// processes and only faulted in as lookups touch them.
+    pub fn open(path: &Path) -> Result<Self> {
+        #[cfg(feature = "mmap")]
+        {
+            let file = File::open(path)?;
+            // Safety: the file is opened read-only and is treated as immutable
+            // for the lifetime of the map; it is validated before any lookup.
+            let map = unsafe { memmap2::Mmap::map(&file)? };
+            let (root, word_count) = validate(&map)?;
+            Ok(Self {
+                storage: Storage::Mapped(map),
+                root,
+                word_count,
+            })
+        }
+
+        #[cfg(not(feature = "mmap"))]
+        {
+            Self::from_bytes(std::fs::read(path)?)
+        }
+    }
+
+    /// to_bytes serializes the dawg so it can be written to disk and reloaded with
+    /// `from_bytes` or `open`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.storage.bytes().to_vec()
+    }
+
+    /// with_source_checksum records the `wordlist_checksum` of the wordlist the
+    /// dawg was built from, so a serialized copy can be told apart from one
+    /// built from another version of the list
+    pub fn with_source_checksum(self, checksum: u32) -> Self {
+        let mut bytes = self.to_bytes();
+        bytes[CHECKSUM_OFFSET..HEADER_LEN].copy_from_slice(&checksum.to_le_bytes());
+        Self {
+            storage: Storage::Owned(bytes),
+            root: self.root,
+            word_count: self.word_count,
+        }
+    }
+
+    /// source_checksum returns the checksum recorded by `with_source_checksum`,
+    /// or None for a dawg built without one
+    pub fn source_checksum(&self) -> Option<u32> {
+        header_checksum(self.storage.bytes())
+    }
+
+    /// is_dawg_file reports whether the file at `path` starts with the dawg magic
+    pub fn is_dawg_file(path: &Path) -> bool {
+        let mut magic = [0u8; MAGIC.len()];
+        File::open(path)
+            .and_then(|mut file| file.read_exact(&mut magic))
+            .map(|_| &magic == MAGIC)
+            .unwrap_or(false)
+    }
+
+    /// read_source_checksum reads the source checksum from the header of the
+    /// dawg file at `path` without loading the rest of it. None when the file
+    /// isn't a dawg or has no checksum.
+    pub fn read_source_checksum(path: &Path) -> Option<u32> {
+        let mut header = [0u8; HEADER_LEN];
+        File::open(path)
+            .and_then(|mut file| file.read_exact(&mut header))
+            .ok()
+            .filter(|_| &header[..MAGIC.len()] == MAGIC)
+            .and_then(|_| header_checksum(&header))
+    }
+
+    pub fn root(&self) -> DawgNode<'_> {
+        DawgNode {
+            dawg: self,
+            first_edge: self.root,
+            finish: false,
+        }
+    }
+
+    pub fn search(&self, word: &str) -> bool {
+        self.root()
+            .node(&word.to_lowercase())
+            .map(|node| node.is_word())
+            .unwrap_or(false)
+    }
+
+    pub fn has_prefix(&self, prefix: &str) -> bool {
+        self.root().node(prefix).is_some()
+    }
+
+    /// len returns the number of words in the dawg
+    pub fn len(&self) -> usize {
+        self.word_count as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.word_count == 0
+    }
+
+    /// words returns every word in the dawg in sorted order, e.g. to rebuild
+    /// it with more words
+    pub fn words(&self) -> Vec<String> {
+        fn collect(node: DawgNode<'_>, prefix: &mut String, words: &mut Vec<String>) {
+            if node.is_word() {
+                words.push(prefix.clone());
+            }
+            for letter in 'a'..='z' {
+                if let Some(child) = node.child(letter) {
+                    prefix.push(letter);
+                    collect(child, prefix, words);
+                    prefix.pop();
+                }
+            }
+        }
+
+        let mut words = Vec::with_capacity(self.len());
+        collect(self.root(), &mut String::new(), &mut words);
+        words
+    }
+
+    /// size_in_bytes returns the size of the serialized representation, which is
+    /// also all the memory the dawg's lookups touch
+    pub fn size_in_bytes(&self) -> usize {
+        self.storage.bytes().len()
+    }
+
+    fn edge(&self, index: usize) -> u32 {
+        let offset = HEADER_LEN + index * 4;
+        let bytes = &self.storage.bytes()[offset..offset + 4];
+        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    }
+}
+
+/// Checksums are never 0, which marks a dawg built without one
+fn header_checksum(header: &[u8]) -> Option<u32> {
+    let bytes = &header[CHECKSUM_OFFSET..HEADER_LEN];
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])).filter(|&c| c != 0)
+}
+
+/// wordlist_checksum returns the checksum of the wordlist file at `path`, as
+/// recorded in dawgs built from it
+pub fn wordlist_checksum(path: &Path) -> Result<u32> {
+    let bytes = std::fs::read(path)?;
+    // 0 is reserved for "no checksum"
+    Ok(crc32fast::hash(&bytes).max(1))
+}
+
+/// validate checks a serialized dawg's header and that every edge points at an
+/// edge list inside the buffer, so lookups can never read out of bounds.
+/// Returns the root index and word count.
+fn validate(bytes: &[u8]) -> Result<(u32, u32)> {
+    if bytes.len() < HEADER_LEN || &bytes[..MAGIC.len()] != MAGIC {
+        return Err(anyhow!("Not a dawg file"));
+    }
+    if !(bytes.len() - HEADER_LEN).is_multiple_of(4) {
+        return Err(anyhow!("Truncated dawg file"));
+    }
+
+    let read_u32 = |offset: usize| {
+        u32::from_le_bytes([
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ])
+    };
+    let edge_count = (bytes.len() - HEADER_LEN) / 4;
+    let root = read_u32(8);
+    let word_count = read_u32(12);
+
+    if root as usize >= edge_count.max(1) {
+        return Err(anyhow!("Corrupt dawg file: root out of range"));
+    }
+    for index in 0..edge_count {
+        let target = (read_u32(HEADER_LEN + index * 4) >> TARGET_SHIFT) as usize;
+        if target >= edge_count {
+            return Err(anyhow!("Corrupt dawg file: edge {index} out of range"));
+        }
+    }
+    // Every edge list must end before the buffer does
+    if edge_count > 1 && read_u32(bytes.len() - 4) & LAST_EDGE == 0 {
+        return Err(anyhow!("Corrupt dawg file: unterminated edge list"));
+    }
+
+    Ok((root, word_count))
+}
+
+/// Builder implements incremental construction of a minimal acyclic automaton
+/// from sorted input (Daciuk et al. 2000): after each word, the part of the
+/// previous word's path that can no longer change is merged into a register of
+/// unique nodes.
+struct Builder {
+    nodes: Vec<BuildNode>,
+    register: HashMap<BuildNode, usize>,
+    /// (parent, letter, child) for the path of the most recently inserted word
+    unchecked: Vec<(usize, u8, usize)>,
+    previous: Vec<u8>,
+    word_count: u32,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+struct BuildNode {
+    edges: Vec<(u8, usize)>,
+    finish: bool,
+}
+
+impl Builder {
+    fn new() -> Self {
+        Self {
+            nodes: vec![BuildNode::default()],
+            register: HashMap::new(),
+            unchecked: Vec::new(),
+            previous: Vec::new(),
+            word_count: 0,
+        }
+    }
+
+    fn insert(&mut self, word: &[u8]) {
+        let common = word
+            .iter()
+            .zip(&self.previous)
+            .take_while(|(a, b)| a == b)
+            .count();
+        self.minimize(common);
+
+        let mut node = self
+            .unchecked
+            .last()
+            .map(|(_, _, child)| *child)
+            .unwrap_or(0);
+        for &letter in &word[common..] {
+            let child = self.nodes.len();
+            self.nodes.push(BuildNode::default());
+            self.nodes[node].edges.push((letter - b'a', child));
+            self.unchecked.push((node, letter - b'a', child));
+            node = child;
+        }
+
+        self.nodes[node].finish = true;
+        self.word_count += 1;
+        self.previous = word.to_vec();
+    }
+
+    fn minimize(&mut self, down_to: usize) {
+        while self.unchecked.len() > down_to {
+            let (parent, letter, child) = self.unchecked.pop().unwrap();
+            let key = self.nodes[child].clone();
+            match self.register.get(&key) {
+                Some(&existing) => {
+                    // An identical node already exists; point the parent at it
+                    let edge = self.nodes[parent].edges.last_mut().unwrap();
+                    debug_assert_eq!(edge.0, letter);
+                    edge.1 = existing;
+                }
+                None => {
+                    self.register.insert(key, child);
+                }
+            }
+        }
+    }
+
+    fn finish(mut self) -> Dawg {
+        self.minimize(0);
+
+        // Lay out each reachable node's edges contiguously. Index 0 is reserved
+        // so that a target of 0 can mean "no children".
+        let mut edges: Vec<u32> = vec![0];
+        let mut offsets: HashMap<usize, u32> = HashMap::new();
+        let root = self.layout(0, &mut edges, &mut offsets);
+        assert!(edges.len() <= MAX_EDGES, "dictionary too large for a dawg");
+
+        let mut bytes = Vec::with_capacity(HEADER_LEN + edges.len() * 4);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&root.to_le_bytes());
+        bytes.extend_from_slice(&self.word_count.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        for edge in edges {
+            bytes.extend_from_slice(&edge.to_le_bytes());
+        }
+
+        Dawg {
+            storage: Storage::Owned(bytes),
+            root,
+            word_count: self.word_count,
+        }
+    }
+
+    /// layout writes the edge lists of `node` and everything below it, returning
+    /// the index of `node`'s first edge
+    fn layout(&self, node: usize, edges: &mut Vec<u32>, offsets: &mut HashMap<usize, u32>) -> u32 {
+        if self.nodes[node].edges.is_empty() {
+            return 0;
+        }
+        if let Some(&offset) = offsets.get(&node) {
+            return offset;
+        }
+
+        // Children first, so their offsets are known when packing our edges
+        let packed: Vec<u32> = self.nodes[node]
+            .edges
+            .iter()
+            .map(|&(letter, child)| {
+                let target = self.layout(child, edges, offsets);
+                let mut edge = letter as u32 | (target << TARGET_SHIFT);
+                if self.nodes[child].finish {
+                    edge |= TARGET_FINAL;
+                }
+                edge
+            })
+            .collect();
+
+        let offset = edges.len() as u32;
+        let last = packed.len() - 1;
+        edges.extend(packed.into_iter().enumerate().map(|(i, edge)| {
+            if i == last {
+                edge | LAST_EDGE
+            } else {
+                edge
+            }
+        }));
+        offsets.insert(node, offset);
+        offset
+    }
+}
+
+/// dictionary_path returns the dictionary binaries should load: a prebuilt
+/// `wordlist.dawg` (see the build-dawg binary) when it was built from the
+/// `wordlist` next to it, otherwise the plain `wordlist`, which is compiled
+/// into a dawg at startup
+pub fn dictionary_path() -> PathBuf {
+    resolve_dictionary(Path::new("wordlist.dawg"), Path::new("wordlist"))
+}
+
+/// The dictionary of another language, `wordlist.<code>`, or its prebuilt
+/// `wordlist.<code>.dawg`
+pub fn language_dictionary_path(code: &str) -> PathBuf {
+    resolve_dictionary(
+        Path::new(&format!("wordlist.{code}.dawg")),
+        Path::new(&format!("wordlist.{code}")),
+    )
+}
+
+/// resolve_dictionary picks between a prebuilt dawg and the wordlist it should
+/// have been built from. The dawg wins only if its recorded checksum matches
+/// the wordlist, or if there is no wordlist to check it against.
+fn resolve_dictionary(prebuilt: &Path, source: &Path) -> PathBuf {
+    if !source.exists() {
+        return prebuilt.to_path_buf();
+    }
+    if !prebuilt.exists() {
+        return source.to_path_buf();
+    }
+
+    let expected = match wordlist_checksum(source) {
+        Ok(checksum) => checksum,
+        Err(e) => {
+            warn!("Failed to checksum {}: {}", source.display(), e);
+            return prebuilt.to_path_buf();
+        }
+    };
+    match Dawg::read_source_checksum(prebuilt) {
+        Some(checksum) if checksum == expected => {
+            info!(
+                "Loading {} built from {} ({:08x})",
+                prebuilt.display(),
+                source.display(),
+                checksum
+            );
+            prebuilt.to_path_buf()
+        }
+        recorded => {
+            warn!(
+                "{} was not built from the current {} (checksum {:08x?}, expected {:08x}); building from the wordlist instead",
+                prebuilt.display(),
+                source.display(),
+                recorded,
+                expected
+            );
+            source.to_path_buf()
+        }
+    }
+}
+
+impl From<Vec<&str>> for Dawg {
+    fn from(words: Vec<&str>) -> Self {
+        Dawg::build(words)
+    }
+}
+
+impl From<Vec<String>> for Dawg {
+    fn from(words: Vec<String>) -> Self {
+        Dawg::build(words)
+    }
+}
+
+impl From<String> for Dawg {
+    fn from(text: String) -> Self {
+        Dawg::build(text.lines())
+    }
+}
+
+impl From<PathBuf> for Dawg {
+    /// Loads a serialized dawg if the file is one, otherwise builds a dawg from
+    /// a plain wordlist with one word per line
+    fn from(value: PathBuf) -> Self {
+        if Dawg::is_dawg_file(&value) {
+            return Dawg::open(&value).expect("failed to load dawg");
+        }
+
+        let file = File::open(value).expect("file does not exist");
+        let buf = BufReader::new(file);
+        Dawg::build(buf.lines().map(|l| l.expect("failed to parse line")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Trie;
+
+    const WORDS: [&str; 9] = [
+        "tap", "taps", "tops", "top", "stop", "stops", "happy", "app", "apple",
+    ];
+
+    #[test]
+    fn test_search_and_prefix() {
+        let dawg = Dawg::from(WORDS.to_vec());
+        assert_eq!(dawg.len(), 9);
+
+        for word in WORDS {
+            assert!(dawg.search(word), "{word} should be found");
+        }
+        assert!(dawg.search("HAPPY"));
+        assert!(!dawg.search("ta"));
+        assert!(!dawg.search("tapss"));
+        assert!(!dawg.search(""));
+
+        assert!(dawg.has_prefix(""));
+        assert!(dawg.has_prefix("sto"));
+        assert!(dawg.has_prefix("apple"));
+        assert!(!dawg.has_prefix("apples"));
+        assert!(!dawg.has_prefix("x"));
+    }
+
+    #[test]
+    fn test_shares_suffixes() {
+        // "taps"/"tops"/"stops" end in the same "ps" node
+        let dawg = Dawg::from(vec!["taps", "tops", "stops"]);
+        let taps = dawg.root().node("ta").unwrap();
+        let tops = dawg.root().node("sto").unwrap();
+        assert_eq!(
+            taps.child('p').unwrap().first_edge,
+            tops.child('p').unwrap().first_edge
+        );
+    }
+
+    #[test]
+    fn test_node_and_child_letters() {
+        let dawg = Dawg::from(WORDS.to_vec());
+        let to = dawg.root().node("to").unwrap();
+        assert!(!to.is_word());
+        assert_eq!(to.child_letters(), letter_bit('p'));
+        assert!(to.child('p').unwrap().is_word());
+        assert!(to.child('x').is_none());
+        assert!(to.child('*').is_none());
+    }
+
+    #[test]
+    fn test_words_round_trip() {
+        let dawg = Dawg::from(WORDS.to_vec());
+        let mut expected = WORDS.to_vec();
+        expected.sort();
+        assert_eq!(dawg.words(), expected);
+    }
+
+    #[test]
+    fn test_skips_unplayable_words() {
+        let dawg = Dawg::from(vec!["ok", "don't", "Caps", "b2b", ""]);
+        assert_eq!(dawg.len(), 1);
+        assert!(dawg.search("ok"));
+    }
+
+    #[test]
+    fn test_empty_dawg() {
+        let dawg = Dawg::from(Vec::<String>::new());
+        assert!(dawg.is_empty());
+        assert!(!dawg.search("a"));
+        assert!(dawg.has_prefix(""));
+
+        let reloaded = Dawg::from_bytes(dawg.to_bytes()).unwrap();
+        assert!(reloaded.is_empty());
+    }
+
+    #[test]
+    fn test_round_trip_through_file() {
+        let dawg = Dawg::from(WORDS.to_vec());
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), dawg.to_bytes()).unwrap();
+
+        assert!(Dawg::is_dawg_file(file.path()));
+        let loaded = Dawg::from(file.path().to_path_buf());
+        assert_eq!(loaded.len(), dawg.len());
+        for word in WORDS {
+            assert!(loaded.search(word));
+        }
+        assert!(!loaded.search("tapss"));
+    }
+
+    #[test]
+    fn test_rejects_corrupt_bytes() {
+        assert!(Dawg::from_bytes(b"not a dawg".to_vec()).is_err());
+
+        let mut bytes = Dawg::from(WORDS.to_vec()).to_bytes();
+        bytes.pop();
+        assert!(Dawg::from_bytes(bytes).is_err());
+
+        let mut bytes = Dawg::from(WORDS.to_vec()).to_bytes();
+        let last = bytes.len() - 4;
+        bytes[last..].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(Dawg::from_bytes(bytes).is_err());
+    }
+
+    #[test]
+    fn test_source_checksum_is_kept_in_the_header() {
+        let dawg = Dawg::from(WORDS.to_vec());
+        assert_eq!(dawg.source_checksum(), None);
+
+        let dawg = dawg.with_source_checksum(0xfeed);
+        assert_eq!(dawg.source_checksum(), Some(0xfeed));
+        assert!(dawg.search("apple"));
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), dawg.to_bytes()).unwrap();
+        assert_eq!(Dawg::read_source_checksum(file.path()), Some(0xfeed));
+        assert_eq!(
+            Dawg::open(file.path()).unwrap().source_checksum(),
+            Some(0xfeed)
+        );
+    }
+
+    #[test]
+    fn test_resolve_dictionary_checks_the_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let prebuilt = dir.path().join("wordlist.dawg");
+        let source = dir.path().join("wordlist");
+
+        std::fs::write(&source, WORDS.join("\n")).unwrap();
+        assert_eq!(resolve_dictionary(&prebuilt, &source), source);
+
+        let checksum = wordlist_checksum(&source).unwrap();
+        let dawg = Dawg::from(WORDS.to_vec()).with_source_checksum(checksum);
+        std::fs::write(&prebuilt, dawg.to_bytes()).unwrap();
+        assert_eq!(resolve_dictionary(&prebuilt, &source), prebuilt);
+
+        // Editing the wordlist leaves the dawg stale
+        std::fs::write(&source, "zebra").unwrap();
+        assert_eq!(resolve_dictionary(&prebuilt, &source), source);
+
+        // A dawg with no checksum can't be trusted over the wordlist
+        std::fs::write(&prebuilt, Dawg::from(WORDS.to_vec()).to_bytes()).unwrap();
+        assert_eq!(resolve_dictionary(&prebuilt, &source), source);
+
+        std::fs::remove_file(&source).unwrap();
+        assert_eq!(resolve_dictionary(&prebuilt, &source), prebuilt);
+    }
+
+    #[test]
+    fn test_matches_trie_on_wordlist() {
+        let trie = Trie::from(PathBuf::from("test_wordlist"));
+        let dawg = Dawg::from(PathBuf::from("test_wordlist"));
+
+        let text = std::fs::read_to_string("test_wordlist").unwrap();
+        for word in text.lines() {
+            assert_eq!(dawg.search(word), trie.search(word), "{word}");
+            let prefix = &word[..word.len() / 2];
+            assert_eq!(dawg.has_prefix(prefix), trie.has_prefix(prefix), "{prefix}");
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::{board::answer::Answer, Board};
+
+/// BoardDensity summarises how rich a board's solution space is. It is
+/// recorded when a game is generated so it can later be compared with how
+/// players fared on the board.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BoardDensity {
+    pub valid_words: i32,
+    /// Valid words per tile on the board
+    pub words_per_tile: f64,
+    pub average_word_length: f64,
+    /// Share of valid words, 0 to 1, that can only be spelled with a wildcard
+    pub wildcard_reliance: f64,
+}
+
+impl BoardDensity {
+    /// analyze computes the density of `board` from every valid answer on it
+    pub fn analyze(board: &Board, answers: &[Answer]) -> Self {
+        let tiles: usize = board.rows.iter().map(|row| row.tiles.len()).sum();
+        if answers.is_empty() || tiles == 0 {
+            return Self::default();
+        }
+
+        let words = answers.len() as f64;
+        let letters: usize = answers
+            .iter()
+            .map(|answer| answer.word.chars().count())
+            .sum();
+        let wildcard_words = answers
+            .iter()
+            .filter(|answer| answer.requires_wildcard())
+            .count();
+
+        Self {
+            valid_words: answers.len() as i32,
+            words_per_tile: words / tiles as f64,
+            average_word_length: letters as f64 / words,
+            wildcard_reliance: wildcard_words as f64 / words,
+        }
+    }
+}
+
+/// correlation returns the Pearson correlation coefficient of paired samples,
+/// or None when there are fewer than two pairs or either side never varies
+pub fn correlation(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < 2 {
+        return None;
+    }
+
+    let n = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let (mut covariance, mut variance_x, mut variance_y) = (0.0, 0.0, 0.0);
+    for (x, y) in pairs {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance_x += (x - mean_x).powi(2);
+        variance_y += (y - mean_y).powi(2);
+    }
+
+    if variance_x == 0.0 || variance_y == 0.0 {
+        return None;
+    }
+    Some(covariance / (variance_x * variance_y).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils;
+
+    #[test]
+    fn test_analyze_board_density() {
+        // c*xx
+        // atxx
+        let board = test_utils::create_test_board("c*xxatxxxxxxxxxx");
+        let answers: Vec<Answer> = ["cat", "cot", "at"]
+            .iter()
+            .map(|word| board.paths_for(word))
+            .collect();
+
+        let density = BoardDensity::analyze(&board, &answers);
+        assert_eq!(density.valid_words, 3);
+        assert_eq!(density.words_per_tile, 3.0 / 16.0);
+        assert_eq!(density.average_word_length, 8.0 / 3.0);
+        // Only cot needs the wildcard; cat and at can use the real a
+        assert_eq!(density.wildcard_reliance, 1.0 / 3.0);
+
+        assert_eq!(BoardDensity::analyze(&board, &[]), BoardDensity::default());
+    }
+
+    #[test]
+    fn test_correlation() {
+        assert_eq!(
+            correlation(&[(1.0, 2.0), (2.0, 4.0), (3.0, 6.0)]),
+            Some(1.0)
+        );
+        assert_eq!(
+            correlation(&[(1.0, 3.0), (2.0, 2.0), (3.0, 1.0)]),
+            Some(-1.0)
+        );
+        assert_eq!(correlation(&[(1.0, 1.0), (2.0, 1.0)]), None);
+        assert_eq!(correlation(&[(1.0, 1.0)]), None);
+    }
+}
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+use crate::game::density::BoardDensity;
+
+/// Difficulty is the label a board's difficulty score falls into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+/// Scores at or above these are Medium and Hard respectively, set so that
+/// boards from the generator split into roughly equal thirds
+const MEDIUM_FROM: f64 = 52.0;
+const HARD_FROM: f64 = 63.0;
+
+impl Difficulty {
+    /// The band a difficulty score from `rate` falls into
+    pub fn from_score(score: f64) -> Self {
+        if score >= HARD_FROM {
+            Difficulty::Hard
+        } else if score >= MEDIUM_FROM {
+            Difficulty::Medium
+        } else {
+            Difficulty::Easy
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(format!("unknown difficulty: {s}")),
+        }
+    }
+}
+
+/// rate scores how hard a board is, from 0 (easiest) to 100 (hardest).
+/// Fewer valid words and a lower optimal score leave players less to find;
+/// longer words and words that only exist through a wildcard are harder to
+/// spot. The ranges each metric is scaled over are those seen on boards from
+/// the generator with the shipped wordlist.
+pub fn rate(density: &BoardDensity, optimal_score: i32) -> f64 {
+    // Word counts spread over orders of magnitude, so they are compared on a
+    // log scale
+    let sparsity = 1.0
+        - scale(
+            (density.valid_words.max(1) as f64).ln(),
+            1500f64.ln(),
+            7500f64.ln(),
+        );
+    let scarcity = 1.0 - scale(optimal_score as f64, 40.0, 70.0);
+    let length = scale(density.average_word_length, 4.2, 5.2);
+    // Nearly every word on a generated board needs a wildcard, so only the
+    // last few percent tell boards apart
+    let leverage = scale(density.wildcard_reliance, 0.95, 1.0);
+
+    100.0 * (0.35 * sparsity + 0.35 * scarcity + 0.2 * length + 0.1 * leverage)
+}
+
+/// scale maps `value` from `low..=high` onto 0..=1, clamping outside it
+fn scale(value: f64, low: f64, high: f64) -> f64 {
+    ((value - low) / (high - low)).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn density(valid_words: i32, average_word_length: f64, wildcard_reliance: f64) -> BoardDensity {
+        BoardDensity {
+            valid_words,
+            words_per_tile: valid_words as f64 / 16.0,
+            average_word_length,
+            wildcard_reliance,
+        }
+    }
+
+    #[test]
+    fn test_rate_orders_boards() {
+        let rich = rate(&density(7500, 4.2, 0.95), 70);
+        let typical = rate(&density(3000, 4.8, 0.98), 50);
+        let sparse = rate(&density(1500, 5.2, 1.0), 40);
+
+        assert!(rich.abs() < 1e-9);
+        assert!((sparse - 100.0).abs() < 1e-9);
+        assert!(rich < typical && typical < sparse);
+        assert_eq!(Difficulty::from_score(rich), Difficulty::Easy);
+        assert_eq!(Difficulty::from_score(sparse), Difficulty::Hard);
+    }
+
+    #[test]
+    fn test_difficulty_labels_round_trip() {
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+            assert_eq!(difficulty.as_str().parse(), Ok(difficulty));
+            assert_eq!(
+                serde_json::to_string(&difficulty).unwrap(),
+                format!("\"{difficulty}\"")
+            );
+        }
+        assert!("brutal".parse::<Difficulty>().is_err());
+    }
+}
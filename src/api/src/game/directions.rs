@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 pub const DIRECTIONS: [(isize, isize); 8] = [
     (-1, 0),  // up
     (1, 0),   // down
@@ -8,3 +10,68 @@ pub const DIRECTIONS: [(isize, isize); 8] = [
     (1, -1),  // down-left
     (1, 1),   // down-right
 ];
+
+/// Adjacency decides which cells a path can step to from a cell. Every path
+/// search goes through `neighbors`, so a board's adjacency is the only thing
+/// a variant needs to change; layouts whose offsets depend on the cell, like
+/// hex grids, fit behind the same method.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Adjacency {
+    /// Orthogonal and diagonal neighbours, the standard game
+    #[default]
+    EightWay,
+    /// Orthogonal neighbours only
+    FourWay,
+}
+
+impl Adjacency {
+    /// directions returns the (row, column) offsets to adjacent cells, in the
+    /// order paths are searched
+    pub fn directions(self) -> &'static [(isize, isize)] {
+        match self {
+            Adjacency::EightWay => &DIRECTIONS,
+            Adjacency::FourWay => &DIRECTIONS[..4],
+        }
+    }
+
+    /// neighbors returns the cells adjacent to (row, col) that lie on a board
+    /// `height` rows by `width` columns
+    pub fn neighbors(
+        self,
+        row: usize,
+        col: usize,
+        height: usize,
+        width: usize,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        self.directions().iter().filter_map(move |(dr, dc)| {
+            let r = row.checked_add_signed(*dr)?;
+            let c = col.checked_add_signed(*dc)?;
+            (r < height && c < width).then_some((r, c))
+        })
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbors_respect_bounds() {
+        let corner: Vec<_> = Adjacency::EightWay.neighbors(0, 0, 4, 4).collect();
+        assert_eq!(corner, vec![(1, 0), (0, 1), (1, 1)]);
+        assert_eq!(Adjacency::EightWay.neighbors(1, 1, 4, 4).count(), 8);
+        assert_eq!(Adjacency::EightWay.neighbors(3, 3, 4, 4).count(), 3);
+    }
+
+    #[test]
+    fn test_four_way_skips_diagonals() {
+        let middle: Vec<_> = Adjacency::FourWay.neighbors(1, 1, 4, 4).collect();
+        assert_eq!(middle, vec![(0, 1), (2, 1), (1, 2), (1, 0)]);
+        assert_eq!(Adjacency::FourWay.neighbors(0, 0, 4, 4).count(), 2);
+    }
+}
@@ -0,0 +1,78 @@
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::game::board::conflict::ConstraintConflict;
+use crate::game::input_guard::UnsupportedInput;
+
+/// GameError is why answers can't be accepted or scored on a board. It is
+/// serialized, tagged by `kind`, as the details of the validation error
+/// clients receive, so they can tell which word to fix without reading the
+/// message.
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GameError {
+    /// A word that is empty once sanitized
+    #[error("Words can't be empty")]
+    EmptyInput,
+    /// A word the dictionary can't contain, e.g. one in another alphabet
+    #[error("Word '{word}' is not supported: {reason}")]
+    Unsupported {
+        word: String,
+        reason: UnsupportedInput,
+    },
+    /// A word shorter than the game allows
+    #[error("Word '{word}' is shorter than {min_word_len} letters")]
+    TooShort { word: String, min_word_len: usize },
+    /// More answers than the game allows in an entry
+    #[error("At most {max_answers} words can be played in this game")]
+    TooManyAnswers { max_answers: usize },
+    /// A word played again in a game that doesn't allow repeats
+    #[error("Word '{word}' has already been played")]
+    Repeated { word: String },
+    #[error("Word '{word}' is not in the dictionary")]
+    NotInDictionary { word: String },
+    /// A word with no path on the board, or a traced path that doesn't
+    /// spell it
+    #[error("Word '{word}' cannot be formed on this board")]
+    NotOnBoard { word: String },
+    /// Words that can't all be played together, as they need the
+    /// wildcards to be different letters
+    #[error("{0}")]
+    ConstraintConflict(ConstraintConflict),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::board::constraints::{AnswerGroupConstraintSet, PathConstraintSet};
+
+    #[test]
+    fn test_errors_explain_themselves() {
+        let cat = AnswerGroupConstraintSet::from(vec![PathConstraintSet::FirstDecided('a')]);
+        let cot = AnswerGroupConstraintSet::from(vec![PathConstraintSet::FirstDecided('o')]);
+        let error = GameError::ConstraintConflict(ConstraintConflict::explain([
+            ("cat", &cat),
+            ("cot", &cot),
+        ]));
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "kind": "constraint_conflict",
+                "words": ["cat", "cot"],
+                "demands": [
+                    { "word": "cat", "options": [{ "first": "a", "second": null }] },
+                    { "word": "cot", "options": [{ "first": "o", "second": null }] },
+                ],
+            })
+        );
+
+        let error = GameError::Unsupported {
+            word: "héllo".to_string(),
+            reason: UnsupportedInput::Language,
+        };
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({ "kind": "unsupported", "word": "héllo", "reason": "language" })
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::game::{Board, BoardGenerator};
+
+/// Boards sampled from the generator to estimate how often it places a
+/// wildcard on each cell
+const WILDCARD_SAMPLE_BOARDS: usize = 4096;
+
+/// TileFairness compares the tiles of a set of boards with what the
+/// generator is configured to produce. Drift is the total variation distance
+/// between the observed and expected distributions: 0 when they match, 1
+/// when they share nothing.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TileFairness {
+    pub boards: usize,
+    /// Letter tiles only, alphabetical
+    pub letters: Vec<TileShare>,
+    /// Letter tiles by point value, lowest first
+    pub points: Vec<TileShare>,
+    /// Share of boards with a wildcard on each cell that ever has one
+    pub wildcard_positions: Vec<WildcardShare>,
+    pub letter_drift: f64,
+    pub points_drift: f64,
+    pub wildcard_drift: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TileShare {
+    /// The letter or point value
+    pub value: String,
+    pub count: usize,
+    pub observed: f64,
+    pub expected: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WildcardShare {
+    pub row: i32,
+    pub col: i32,
+    pub count: usize,
+    pub observed: f64,
+    pub expected: f64,
+}
+
+/// Expected is the distribution of tiles the generator is configured with
+pub struct Expected {
+    letters: BTreeMap<char, f64>,
+    points: BTreeMap<i32, f64>,
+    wildcards: BTreeMap<(i32, i32), f64>,
+}
+
+impl Expected {
+    /// The distributions `generator` draws from. Letter and point shares
+    /// come straight from its frequencies; wildcard placement is sampled
+    /// with a fixed seed, so the estimate is the same on every run.
+    pub fn from_generator(generator: &BoardGenerator) -> Self {
+        let frequencies = generator.letter_frequencies();
+        let total: f64 = frequencies.iter().map(|(_, frequency)| frequency).sum();
+
+        let mut letters = BTreeMap::new();
+        let mut points = BTreeMap::new();
+        for (letter, frequency) in frequencies {
+            letters.insert(letter, frequency / total);
+            *points
+                .entry(generator.scoring_scheme().points_for(letter))
+                .or_insert(0.0) += frequency / total;
+        }
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let mut wildcards = BTreeMap::new();
+        for _ in 0..WILDCARD_SAMPLE_BOARDS {
+            for (row, col) in wildcard_cells(&generator.generate_board(&mut rng)) {
+                *wildcards.entry((row, col)).or_insert(0.0) += 1.0;
+            }
+        }
+        for share in wildcards.values_mut() {
+            *share /= WILDCARD_SAMPLE_BOARDS as f64;
+        }
+
+        Self {
+            letters,
+            points,
+            wildcards,
+        }
+    }
+
+    /// analyze compares the tiles of `boards` with the expected distributions
+    pub fn analyze<'a>(&self, boards: impl IntoIterator<Item = &'a Board>) -> TileFairness {
+        let mut board_count = 0;
+        let mut letters: BTreeMap<char, usize> = BTreeMap::new();
+        let mut points: BTreeMap<i32, usize> = BTreeMap::new();
+        let mut wildcards: BTreeMap<(i32, i32), usize> = BTreeMap::new();
+        for board in boards {
+            board_count += 1;
+            for tile in board.rows.iter().flat_map(|row| &row.tiles) {
+                if tile.is_wildcard {
+                    *wildcards.entry((tile.row, tile.col)).or_insert(0) += 1;
+                } else if let Some(letter) = tile.letter.chars().next() {
+                    *letters.entry(letter).or_insert(0) += 1;
+                    *points.entry(tile.points).or_insert(0) += 1;
+                }
+            }
+        }
+        if board_count == 0 {
+            return TileFairness::default();
+        }
+
+        let letter_tiles: usize = letters.values().sum();
+        let (letters, letter_drift) = compare(&letters, &self.letters, letter_tiles);
+        let (points, points_drift) = compare(&points, &self.points, letter_tiles);
+        // Each board has two wildcards, so per-board rates sum to 2 and the
+        // distance is halved to stay between 0 and 1
+        let (wildcards, wildcard_drift) = compare(&wildcards, &self.wildcards, board_count);
+
+        TileFairness {
+            boards: board_count,
+            letters: letters
+                .into_iter()
+                .map(|(letter, share)| share.into_tile_share(letter.to_string()))
+                .collect(),
+            points: points
+                .into_iter()
+                .map(|(points, share)| share.into_tile_share(points.to_string()))
+                .collect(),
+            wildcard_positions: wildcards
+                .into_iter()
+                .map(|((row, col), share)| WildcardShare {
+                    row,
+                    col,
+                    count: share.count,
+                    observed: share.observed,
+                    expected: share.expected,
+                })
+                .collect(),
+            letter_drift,
+            points_drift,
+            wildcard_drift: wildcard_drift / 2.0,
+        }
+    }
+}
+
+struct Share {
+    count: usize,
+    observed: f64,
+    expected: f64,
+}
+
+impl Share {
+    fn into_tile_share(self, value: String) -> TileShare {
+        TileShare {
+            value,
+            count: self.count,
+            observed: self.observed,
+            expected: self.expected,
+        }
+    }
+}
+
+/// compare lines up observed counts out of `total` with expected shares,
+/// covering every key either side has, and returns the total variation
+/// distance between them
+fn compare<K: Ord + Copy>(
+    counts: &BTreeMap<K, usize>,
+    expected: &BTreeMap<K, f64>,
+    total: usize,
+) -> (BTreeMap<K, Share>, f64) {
+    let mut shares = BTreeMap::new();
+    for key in counts.keys().chain(expected.keys()) {
+        let count = counts.get(key).copied().unwrap_or(0);
+        shares.insert(
+            *key,
+            Share {
+                count,
+                observed: count as f64 / total.max(1) as f64,
+                expected: expected.get(key).copied().unwrap_or(0.0),
+            },
+        );
+    }
+    let distance = shares
+        .values()
+        .map(|share| (share.observed - share.expected).abs())
+        .sum::<f64>()
+        / 2.0;
+    (shares, distance)
+}
+
+fn wildcard_cells(board: &Board) -> impl Iterator<Item = (i32, i32)> + '_ {
+    board
+        .rows
+        .iter()
+        .flat_map(|row| &row.tiles)
+        .filter(|tile| tile.is_wildcard)
+        .map(|tile| (tile.row, tile.col))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_boards_match_expected() {
+        let generator = BoardGenerator::new();
+        let expected = Expected::from_generator(&generator);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let boards: Vec<Board> = (0..2000)
+            .map(|_| generator.generate_board(&mut rng))
+            .collect();
+
+        let fairness = expected.analyze(&boards);
+        assert_eq!(fairness.boards, 2000);
+        assert_eq!(fairness.letters.len(), 26);
+        assert_eq!(fairness.wildcard_positions.len(), 6);
+        assert!(fairness.letter_drift < 0.05, "{}", fairness.letter_drift);
+        assert!(fairness.points_drift < 0.05, "{}", fairness.points_drift);
+        assert!(
+            fairness.wildcard_drift < 0.05,
+            "{}",
+            fairness.wildcard_drift
+        );
+    }
+
+    #[test]
+    fn test_biased_boards_drift() {
+        let expected = Expected::from_generator(&BoardGenerator::new());
+        let board: Board = "eeee*eeeee*eeeee".parse().unwrap();
+        let fairness = expected.analyze([&board, &board]);
+
+        let e = fairness.letters.iter().find(|share| share.value == "e");
+        assert_eq!(
+            e.map(|share| (share.count, share.observed)),
+            Some((28, 1.0))
+        );
+        assert!(fairness.letter_drift > 0.8);
+        // Wildcards at (1,0) and (2,2) are a placement the generator makes
+        assert!(fairness.wildcard_drift > 0.3 && fairness.wildcard_drift < 1.0);
+
+        assert_eq!(expected.analyze([]), TileFairness::default());
+    }
+}
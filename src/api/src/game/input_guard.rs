@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A path visits each of the 16 tiles at most once, so no word can be longer
+pub const MAX_WORD_LENGTH: usize = 16;
+
+/// Why a word was turned away before being looked up in the dictionary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnsupportedInput {
+    /// Letters outside the English alphabet, e.g. accented or Cyrillic letters
+    Language,
+    /// Digits, punctuation, whitespace or symbols
+    Characters,
+    /// Longer than any word that fits on the board
+    Length,
+}
+
+impl fmt::Display for UnsupportedInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnsupportedInput::Language => write!(f, "Only English words are supported"),
+            UnsupportedInput::Characters => write!(f, "Words may only contain the letters A-Z"),
+            UnsupportedInput::Length => {
+                write!(f, "Words can be at most {MAX_WORD_LENGTH} letters long")
+            }
+        }
+    }
+}
+
+/// screen_word checks a word is something the dictionary could contain at
+/// all, so pasted sentences and other languages get a specific error rather
+/// than "not in the dictionary"
+pub fn screen_word(word: &str) -> Result<(), UnsupportedInput> {
+    if word
+        .chars()
+        .any(|c| c.is_alphabetic() && !c.is_ascii_alphabetic())
+    {
+        return Err(UnsupportedInput::Language);
+    }
+    if !word.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(UnsupportedInput::Characters);
+    }
+    if word.len() > MAX_WORD_LENGTH {
+        return Err(UnsupportedInput::Length);
+    }
+    Ok(())
+}
+
+/// How many words have been turned away for each reason since startup
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RejectionCounts {
+    pub language: u64,
+    pub characters: u64,
+    pub length: u64,
+}
+
+/// Running counts of rejected words, shared by every clone of the engine
+#[derive(Debug, Default)]
+pub struct InputRejections {
+    language: AtomicU64,
+    characters: AtomicU64,
+    length: AtomicU64,
+}
+
+impl InputRejections {
+    pub fn record(&self, reason: UnsupportedInput) {
+        let counter = match reason {
+            UnsupportedInput::Language => &self.language,
+            UnsupportedInput::Characters => &self.characters,
+            UnsupportedInput::Length => &self.length,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn counts(&self) -> RejectionCounts {
+        RejectionCounts {
+            language: self.language.load(Ordering::Relaxed),
+            characters: self.characters.load(Ordering::Relaxed),
+            length: self.length.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screen_word() {
+        assert_eq!(screen_word("pathfinder"), Ok(()));
+        assert_eq!(screen_word("TEST"), Ok(()));
+        assert_eq!(screen_word("café"), Err(UnsupportedInput::Language));
+        assert_eq!(screen_word("привет"), Err(UnsupportedInput::Language));
+        assert_eq!(screen_word("単語"), Err(UnsupportedInput::Language));
+        assert_eq!(
+            screen_word("c'est la vie"),
+            Err(UnsupportedInput::Characters)
+        );
+        assert_eq!(screen_word("abc123"), Err(UnsupportedInput::Characters));
+        assert_eq!(
+            screen_word("antidisestablishmentarianism"),
+            Err(UnsupportedInput::Length)
+        );
+    }
+
+    #[test]
+    fn test_rejections_are_counted_by_reason() {
+        let rejections = InputRejections::default();
+        rejections.record(UnsupportedInput::Language);
+        rejections.record(UnsupportedInput::Language);
+        rejections.record(UnsupportedInput::Length);
+
+        assert_eq!(
+            rejections.counts(),
+            RejectionCounts {
+                language: 2,
+                characters: 0,
+                length: 1,
+            }
+        );
+    }
+}
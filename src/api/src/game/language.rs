@@ -0,0 +1,333 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use tracing::{info, warn};
+
+use crate::game::dawg::{dictionary_path, language_dictionary_path};
+use crate::game::letters;
+use crate::game::scoring::ScoringScheme;
+use crate::game::GameEngine;
+
+/// Language is the language a board is generated and played in. English is
+/// the game's original language; daily puzzles in the others are generated
+/// alongside it, with their own puzzle numbers.
+///
+/// Boards only have tiles a to z, so the other languages' wordlists are
+/// written without diacritics: accents are dropped, Spanish ñ is n, and the
+/// German umlauts and ß are spelled out as ae, oe, ue and ss.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "es")]
+    Spanish,
+    #[serde(rename = "de")]
+    German,
+}
+
+/// The first version the built-in scoring schemes of languages besides
+/// English are numbered from, well clear of the versions SCORING_SCHEMES_PATH
+/// defines
+const LANGUAGE_SCHEME_VERSIONS: i32 = 1000;
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::English, Language::Spanish, Language::German];
+
+    /// The ISO 639-1 code the language is stored and requested by
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+            Language::German => "de",
+        }
+    }
+
+    /// How often each letter is drawn onto the language's boards, from
+    /// letter frequencies in running text with diacritics folded away
+    pub fn letter_frequencies(&self) -> &'static [(char, f64)] {
+        match self {
+            Language::English => &ENGLISH_FREQUENCIES,
+            Language::Spanish => &SPANISH_FREQUENCIES,
+            Language::German => &GERMAN_FREQUENCIES,
+        }
+    }
+
+    /// The version of the built-in scoring scheme boards in the language are
+    /// scored with. English uses the standard scheme.
+    pub fn scoring_scheme_version(&self) -> i32 {
+        match self {
+            Language::English => crate::game::scoring::STANDARD_SCORING_SCHEME_VERSION,
+            Language::Spanish => LANGUAGE_SCHEME_VERSIONS + 1,
+            Language::German => LANGUAGE_SCHEME_VERSIONS + 2,
+        }
+    }
+
+    /// The language whose built-in scoring scheme has `version`, if any
+    /// besides English does
+    pub fn with_scoring_scheme_version(version: i32) -> Option<Language> {
+        Self::ALL
+            .into_iter()
+            .filter(|language| *language != Language::English)
+            .find(|language| language.scoring_scheme_version() == version)
+    }
+
+    /// The built-in scheme the language's boards are scored with. Letters
+    /// score by rarity in the language, as the standard scheme does for
+    /// English.
+    pub fn scoring_scheme(&self) -> ScoringScheme {
+        match self {
+            Language::English => ScoringScheme::standard(),
+            _ => ScoringScheme::from_frequencies(
+                self.scoring_scheme_version(),
+                self.letter_frequencies(),
+            ),
+        }
+    }
+
+    /// fold_word spells a normalized word the way the language's wordlist
+    /// writes it, in the board's a-z alphabet, so players can type words
+    /// with their accents. English words are left as they are, so letters
+    /// outside the alphabet are still turned away as unsupported.
+    pub fn fold_word(&self, word: &str) -> String {
+        match self {
+            Language::English => word.to_string(),
+            Language::Spanish => letters::strip_marks(word),
+            Language::German => letters::strip_marks(
+                &word
+                    .replace('ä', "ae")
+                    .replace('ö', "oe")
+                    .replace('ü', "ue")
+                    .replace('ß', "ss"),
+            ),
+        }
+    }
+
+    /// The dictionary the language's boards are solved and checked against:
+    /// `wordlist` for English and `wordlist.<code>` for the others, each
+    /// with an optional prebuilt `.dawg`
+    pub fn dictionary_path(&self) -> PathBuf {
+        match self {
+            Language::English => dictionary_path(),
+            _ => language_dictionary_path(self.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Language::ALL
+            .into_iter()
+            .find(|language| language.as_str() == s.to_lowercase())
+            .ok_or_else(|| format!("unknown language: {s}"))
+    }
+}
+
+/// LanguageEngines holds a GameEngine for each language besides English that
+/// puzzles are generated in. English always uses the deployment's main
+/// engine.
+#[derive(Clone, Default)]
+pub struct LanguageEngines {
+    engines: BTreeMap<Language, GameEngine>,
+}
+
+impl LanguageEngines {
+    /// Adds the engine for its language, replacing any it already had
+    pub fn with_engine(mut self, engine: GameEngine) -> Self {
+        self.engines.insert(engine.language(), engine);
+        self
+    }
+
+    pub fn get(&self, language: Language) -> Option<&GameEngine> {
+        self.engines.get(&language)
+    }
+
+    /// The languages besides English with an engine, in a fixed order
+    pub fn languages(&self) -> impl Iterator<Item = Language> + '_ {
+        self.engines.keys().copied()
+    }
+
+    /// from_env loads an engine for each language GAME_LANGUAGES lists, e.g.
+    /// `es,de`, from the language's wordlist. Each shares `english`'s board
+    /// and generation settings. Languages whose wordlist is missing are
+    /// skipped with a warning.
+    pub fn from_env(english: &GameEngine) -> Self {
+        let mut engines = Self::default();
+        let Ok(codes) = env::var("GAME_LANGUAGES") else {
+            return engines;
+        };
+
+        for code in codes.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+            let language = match code.parse::<Language>() {
+                Ok(Language::English) => continue,
+                Ok(language) => language,
+                Err(e) => {
+                    warn!("Ignoring GAME_LANGUAGES entry: {}", e);
+                    continue;
+                }
+            };
+            let path = language.dictionary_path();
+            if !path.exists() {
+                warn!(
+                    "No {} puzzles: its wordlist {} doesn't exist",
+                    language,
+                    path.display()
+                );
+                continue;
+            }
+            info!("Loading {} wordlist from {}", language, path.display());
+            engines = engines.with_engine(english.for_language(language, path));
+        }
+        engines
+    }
+}
+
+static ENGLISH_FREQUENCIES: [(char, f64); 26] = [
+    ('a', 0.078),
+    ('b', 0.02),
+    ('c', 0.04),
+    ('d', 0.038),
+    ('e', 0.11),
+    ('f', 0.014),
+    ('g', 0.03),
+    ('h', 0.023),
+    ('i', 0.086),
+    ('j', 0.0021),
+    ('k', 0.0097),
+    ('l', 0.053),
+    ('m', 0.027),
+    ('n', 0.072),
+    ('o', 0.061),
+    ('p', 0.028),
+    ('q', 0.0019),
+    ('r', 0.073),
+    ('s', 0.087),
+    ('t', 0.067),
+    ('u', 0.033),
+    ('v', 0.01),
+    ('w', 0.0091),
+    ('x', 0.0027),
+    ('y', 0.016),
+    ('z', 0.0044),
+];
+
+static SPANISH_FREQUENCIES: [(char, f64); 26] = [
+    ('a', 0.125),
+    ('b', 0.014),
+    ('c', 0.047),
+    ('d', 0.059),
+    ('e', 0.137),
+    ('f', 0.0069),
+    ('g', 0.01),
+    ('h', 0.007),
+    ('i', 0.063),
+    ('j', 0.0044),
+    ('k', 0.0002),
+    ('l', 0.05),
+    ('m', 0.032),
+    ('n', 0.07),
+    ('o', 0.087),
+    ('p', 0.025),
+    ('q', 0.0088),
+    ('r', 0.069),
+    ('s', 0.08),
+    ('t', 0.046),
+    ('u', 0.039),
+    ('v', 0.009),
+    ('w', 0.0002),
+    ('x', 0.0022),
+    ('y', 0.009),
+    ('z', 0.0052),
+];
+
+static GERMAN_FREQUENCIES: [(char, f64); 26] = [
+    ('a', 0.065),
+    ('b', 0.019),
+    ('c', 0.031),
+    ('d', 0.051),
+    ('e', 0.174),
+    ('f', 0.017),
+    ('g', 0.03),
+    ('h', 0.048),
+    ('i', 0.076),
+    ('j', 0.0027),
+    ('k', 0.012),
+    ('l', 0.034),
+    ('m', 0.025),
+    ('n', 0.098),
+    ('o', 0.025),
+    ('p', 0.0079),
+    ('q', 0.0002),
+    ('r', 0.07),
+    ('s', 0.073),
+    ('t', 0.062),
+    ('u', 0.044),
+    ('v', 0.0067),
+    ('w', 0.019),
+    ('x', 0.0003),
+    ('y', 0.0004),
+    ('z', 0.011),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_codes_round_trip() {
+        for language in Language::ALL {
+            assert_eq!(language.as_str().parse::<Language>(), Ok(language));
+            assert_eq!(
+                serde_json::to_string(&language).unwrap(),
+                format!("\"{language}\"")
+            );
+        }
+        assert_eq!("ES".parse::<Language>(), Ok(Language::Spanish));
+        assert!("fr".parse::<Language>().is_err());
+    }
+
+    #[test]
+    fn test_each_language_scores_every_letter() {
+        for language in Language::ALL {
+            let scheme = language.scoring_scheme();
+            assert_eq!(scheme.version, language.scoring_scheme_version());
+            assert_eq!(language.letter_frequencies().len(), 26);
+            for letter in 'a'..='z' {
+                assert!(scheme.points_for(letter) >= 1, "{language} {letter}");
+            }
+        }
+        // K is common in German and all but absent from Spanish
+        assert!(
+            Language::Spanish.scoring_scheme().points_for('k')
+                > Language::German.scoring_scheme().points_for('k')
+        );
+        assert_eq!(
+            Language::with_scoring_scheme_version(Language::German.scoring_scheme_version()),
+            Some(Language::German)
+        );
+        assert_eq!(Language::with_scoring_scheme_version(1), None);
+    }
+
+    #[test]
+    fn test_words_fold_to_the_board_alphabet() {
+        assert_eq!(Language::Spanish.fold_word("niño"), "nino");
+        assert_eq!(Language::Spanish.fold_word("acción"), "accion");
+        assert_eq!(Language::German.fold_word("grüße"), "gruesse");
+        assert_eq!(Language::German.fold_word("café"), "cafe");
+        assert_eq!(Language::English.fold_word("café"), "café");
+    }
+}
@@ -0,0 +1,120 @@
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
+
+/// normalize puts a word as typed into the form words are compared in:
+/// surrounding whitespace trimmed, composed (NFC) so an accented letter is
+/// the same however it was entered, and case folded.
+pub fn normalize(word: &str) -> String {
+    let mut folded = String::with_capacity(word.len());
+    for c in word.trim().nfc() {
+        match full_case_fold(c) {
+            Some(fold) => folded.push_str(fold),
+            None => folded.extend(c.to_lowercase()),
+        }
+    }
+    // Lowercasing can decompose, e.g. İ becomes i and a combining dot
+    folded.nfc().collect()
+}
+
+/// The letters whose full case folding isn't their lowercase form, so
+/// e.g. STRASSE, straße and Straße all fold to strasse
+fn full_case_fold(c: char) -> Option<&'static str> {
+    match c {
+        'ß' | 'ẞ' => Some("ss"),
+        'ſ' => Some("s"),
+        'ς' => Some("σ"),
+        'ﬀ' => Some("ff"),
+        'ﬁ' => Some("fi"),
+        'ﬂ' => Some("fl"),
+        'ﬃ' => Some("ffi"),
+        'ﬄ' => Some("ffl"),
+        'ﬅ' | 'ﬆ' => Some("st"),
+        _ => None,
+    }
+}
+
+/// strip_marks drops the accents and other combining marks from a word's
+/// letters, e.g. niño becomes nino. Letters that are distinct rather than
+/// accented, such as ø, are kept.
+pub fn strip_marks(word: &str) -> String {
+    word.nfd()
+        .filter(|&c| !is_combining_mark(c))
+        .nfc()
+        .collect()
+}
+
+/// The number of letters in a word, counting each user-perceived character
+/// (grapheme cluster) once however many code points spell it
+pub fn letter_count(word: &str) -> usize {
+    word.graphemes(true).count()
+}
+
+/// match_tile returns how many bytes of `word` a tile showing `letter`
+/// covers when the word begins with it, e.g. 2 for a `qu` tile and `quit`.
+/// The tile must cover whole letters, so an `n` tile doesn't match the
+/// start of a word spelled with n and a combining tilde.
+pub fn match_tile(word: &str, letter: &str) -> Option<usize> {
+    if letter.is_empty() || !word.starts_with(letter) {
+        return None;
+    }
+    let covered = letter.len();
+    is_letter_boundary(word, covered).then_some(covered)
+}
+
+/// first_letter returns the first letter of `word` when it is a single code
+/// point, which is all a wildcard can stand for, along with its length in
+/// bytes
+pub fn first_letter(word: &str) -> Option<(char, usize)> {
+    let letter = word.graphemes(true).next()?;
+    let mut chars = letter.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some((c, letter.len())),
+        _ => None,
+    }
+}
+
+fn is_letter_boundary(word: &str, index: usize) -> bool {
+    GraphemeCursor::new(index, word.len(), true)
+        .is_boundary(word, 0)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_composes_and_folds_case() {
+        assert_eq!(normalize("  Pathfinder "), "pathfinder");
+        // e and a combining acute accent compose to é
+        assert_eq!(normalize("CAFE\u{301}"), "café");
+        assert_eq!(normalize("café"), normalize("cafe\u{301}"));
+        assert_eq!(normalize("Straße"), "strasse");
+        assert_eq!(normalize("STRASSE"), "strasse");
+        assert_eq!(normalize("ﬁsh"), "fish");
+    }
+
+    #[test]
+    fn test_strip_marks() {
+        assert_eq!(strip_marks("niño"), "nino");
+        assert_eq!(strip_marks("canción"), "cancion");
+        assert_eq!(strip_marks("über"), "uber");
+        assert_eq!(strip_marks("smørrebrød"), "smørrebrød");
+    }
+
+    #[test]
+    fn test_letters_are_graphemes() {
+        assert_eq!(letter_count("niño"), 4);
+        assert_eq!(letter_count("nin\u{303}o"), 4);
+
+        assert_eq!(match_tile("quit", "qu"), Some(2));
+        assert_eq!(match_tile("quit", "q"), Some(1));
+        assert_eq!(match_tile("qit", "qu"), None);
+        assert_eq!(match_tile("n\u{303}o", "n"), None);
+        assert_eq!(match_tile("ño", "ñ"), Some('ñ'.len_utf8()));
+
+        assert_eq!(first_letter("ño"), Some(('ñ', 2)));
+        assert_eq!(first_letter("n\u{303}o"), None);
+        assert_eq!(first_letter(""), None);
+    }
+}
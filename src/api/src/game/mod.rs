@@ -1,17 +1,54 @@
 pub mod board;
 pub mod conversion;
+pub mod dawg;
+pub mod density;
+pub mod difficulty;
 pub mod directions;
+pub mod error;
+pub mod fairness;
+pub mod input_guard;
+pub mod language;
+pub mod letters;
+pub mod mode;
+pub mod neighbors;
+pub mod optimizer;
+pub mod quality;
+pub mod rules;
 pub mod scoring;
+pub mod scoring_session;
+pub mod solver;
+pub mod theme;
 pub mod trie;
+pub mod weakness;
 
+use board::modifier::ModifierCounts;
 pub use board::Board;
+pub use dawg::Dawg;
+pub use error::GameError;
+use language::Language;
+use quality::{BoardQuality, BoardRejection};
+use rules::GameRules;
+use scoring::{BonusRules, ScoringScheme, ScoringSchemes};
 use std::cmp::Reverse;
-use std::collections::HashMap;
+use std::collections::HashSet;
+use theme::Theme;
 pub use trie::Trie;
 
+/// The tile a generator that makes qu tiles puts down in place of a q
+pub const QU_TILE: &str = "qu";
+
+/// qu_tiles_from_env reads BOARD_QU_TILES, which makes generated boards show
+/// `qu` on one tile wherever they'd show a q, as classic Boggle does
+pub fn qu_tiles_from_env() -> bool {
+    std::env::var("BOARD_QU_TILES").is_ok_and(|value| value.to_lowercase() == "true")
+}
+
 // BoardGenerator for game generation
 pub struct BoardGenerator {
     letter_frequencies: std::collections::HashMap<char, f64>,
+    scoring_scheme: ScoringScheme,
+    modifier_counts: ModifierCounts,
+    qu_tiles: bool,
 }
 
 impl Default for BoardGenerator {
@@ -22,35 +59,41 @@ impl Default for BoardGenerator {
 
 impl BoardGenerator {
     pub fn new() -> Self {
-        let mut letter_frequencies = std::collections::HashMap::new();
-        letter_frequencies.insert('a', 0.078);
-        letter_frequencies.insert('b', 0.02);
-        letter_frequencies.insert('c', 0.04);
-        letter_frequencies.insert('d', 0.038);
-        letter_frequencies.insert('e', 0.11);
-        letter_frequencies.insert('f', 0.014);
-        letter_frequencies.insert('g', 0.03);
-        letter_frequencies.insert('h', 0.023);
-        letter_frequencies.insert('i', 0.086);
-        letter_frequencies.insert('j', 0.0021);
-        letter_frequencies.insert('k', 0.0097);
-        letter_frequencies.insert('l', 0.053);
-        letter_frequencies.insert('m', 0.027);
-        letter_frequencies.insert('n', 0.072);
-        letter_frequencies.insert('o', 0.061);
-        letter_frequencies.insert('p', 0.028);
-        letter_frequencies.insert('q', 0.0019);
-        letter_frequencies.insert('r', 0.073);
-        letter_frequencies.insert('s', 0.087);
-        letter_frequencies.insert('t', 0.067);
-        letter_frequencies.insert('u', 0.033);
-        letter_frequencies.insert('v', 0.01);
-        letter_frequencies.insert('w', 0.0091);
-        letter_frequencies.insert('x', 0.0027);
-        letter_frequencies.insert('y', 0.016);
-        letter_frequencies.insert('z', 0.0044);
-
-        Self { letter_frequencies }
+        Self::for_language(Language::English)
+    }
+
+    /// Draws letters as often as they appear in `language` and scores them
+    /// with its built-in scheme
+    pub fn for_language(language: Language) -> Self {
+        Self {
+            letter_frequencies: language.letter_frequencies().iter().copied().collect(),
+            scoring_scheme: language.scoring_scheme(),
+            modifier_counts: ModifierCounts::default(),
+            qu_tiles: false,
+        }
+    }
+
+    /// Draws a `qu` tile wherever a q would be drawn
+    pub fn with_qu_tiles(mut self, qu_tiles: bool) -> Self {
+        self.qu_tiles = qu_tiles;
+        self
+    }
+
+    /// Puts `counts` of each multiplier on generated boards
+    pub fn with_modifier_counts(mut self, counts: ModifierCounts) -> Self {
+        self.modifier_counts = counts;
+        self
+    }
+
+    /// Gives generated tiles the points `scheme` sets instead of the
+    /// standard ones
+    pub fn with_scoring_scheme(mut self, scheme: ScoringScheme) -> Self {
+        self.scoring_scheme = scheme;
+        self
+    }
+
+    pub fn scoring_scheme(&self) -> &ScoringScheme {
+        &self.scoring_scheme
     }
 
     pub fn generate_board<R: rand::Rng>(&self, rng: &mut R) -> Board {
@@ -67,47 +110,150 @@ impl BoardGenerator {
             for col in 0..4 {
                 // Choose random letter based on frequency
                 let letter = self.weighted_choice(&letters, &weights, rng);
-                let points = crate::game::scoring::points_for_letter(letter);
+                self.set_drawn_tile(&mut board, row, col, letter);
+            }
+        }
+
+        self.place_wildcards(&mut board, rng);
+        self.place_modifiers(&mut board, rng);
+
+        board
+    }
+
+    /// The relative frequency each letter is drawn with, alphabetical
+    pub fn letter_frequencies(&self) -> Vec<(char, f64)> {
+        let mut frequencies: Vec<(char, f64)> = self
+            .letter_frequencies
+            .iter()
+            .map(|(&letter, &frequency)| (letter, frequency))
+            .collect();
+        frequencies.sort_by_key(|(letter, _)| *letter);
+        frequencies
+    }
+
+    /// generate_themed_board generates a board guaranteed to contain `theme`:
+    /// a theme word is spelled along a random path, theme letters are
+    /// scattered, and the rest of the tiles are drawn by frequency as usual.
+    /// The wildcards go where they always do. None when the theme can't fit.
+    pub fn generate_themed_board<R: rand::Rng>(&self, rng: &mut R, theme: &Theme) -> Option<Board> {
+        use rand::seq::SliceRandom;
+
+        let mut board = Board::new();
+        self.place_wildcards(&mut board, rng);
+        let mut open = [[true; 4]; 4];
+        for tile in board.rows.iter().flat_map(|row| &row.tiles) {
+            if tile.is_wildcard {
+                open[tile.row as usize][tile.col as usize] = false;
+            }
+        }
 
-                board.set_tile(row, col, letter, points, false);
+        let placed: Vec<((usize, usize), char)> = match theme {
+            Theme::Word(word) => {
+                let path = theme::random_path(rng, &open, word.chars().count())?;
+                path.into_iter().zip(word.chars()).collect()
+            }
+            Theme::Letters(letters) => {
+                let mut cells: Vec<(usize, usize)> = (0..4)
+                    .flat_map(|row| (0..4).map(move |col| (row, col)))
+                    .filter(|&(row, col)| open[row][col])
+                    .collect();
+                if letters.chars().count() > cells.len() {
+                    return None;
+                }
+                cells.shuffle(rng);
+                cells.into_iter().zip(letters.chars()).collect()
             }
+        };
+        for ((row, col), letter) in placed {
+            let points = self.scoring_scheme.points_for(letter);
+            board.set_tile(row, col, letter, points, false);
+            open[row][col] = false;
+        }
+
+        let mut letters: Vec<char> = self.letter_frequencies.keys().cloned().collect();
+        letters.sort();
+        let weights: Vec<f64> = self.get_letter_weights(&letters);
+        let unfilled: Vec<(usize, usize)> = (0..4)
+            .flat_map(|row| (0..4).map(move |col| (row, col)))
+            .filter(|&(row, col)| open[row][col])
+            .collect();
+        for (row, col) in unfilled {
+            let letter = self.weighted_choice(&letters, &weights, rng);
+            self.set_drawn_tile(&mut board, row, col, letter);
+        }
+        self.place_modifiers(&mut board, rng);
+
+        Some(board)
+    }
+
+    /// set_drawn_tile puts a letter drawn by frequency on (row, col), as a
+    /// `qu` tile when it's a q and the generator makes them
+    fn set_drawn_tile(&self, board: &mut Board, row: usize, col: usize, letter: char) {
+        if letter == 'q' && self.qu_tiles {
+            let points = self.scoring_scheme.points_for_tile(QU_TILE);
+            board.set_tile_letters(row, col, QU_TILE, points, false);
+        } else {
+            let points = self.scoring_scheme.points_for(letter);
+            board.set_tile(row, col, letter, points, false);
         }
+    }
+
+    /// place_modifiers scatters the configured multipliers over letter tiles,
+    /// one per tile. Without any configured the generator draws nothing
+    /// more, so boards for a seed come out as they always have.
+    fn place_modifiers<R: rand::Rng>(&self, board: &mut Board, rng: &mut R) {
+        use rand::seq::SliceRandom;
 
+        if self.modifier_counts.is_empty() {
+            return;
+        }
+        let mut cells: Vec<(usize, usize)> = board
+            .rows
+            .iter()
+            .flat_map(|row| &row.tiles)
+            .filter(|tile| !tile.is_wildcard)
+            .map(|tile| (tile.row as usize, tile.col as usize))
+            .collect();
+        cells.shuffle(rng);
+        for ((row, col), modifier) in cells.into_iter().zip(self.modifier_counts.modifiers()) {
+            board.set_modifier(row, col, Some(modifier));
+        }
+    }
+
+    fn place_wildcards<R: rand::Rng>(&self, board: &mut Board, rng: &mut R) {
         // N.B. for wildcard generation, deciding 'first' or 'second' wildcard is based on both indices being < 2.
         // see `fn is_first_wildcard`
 
         // set one wildcard to be one of the center squares, and the other one to be either a diagonal center square OR one of the edge squares adjacent to that square.
         if rng.gen_bool(0.5) {
-            board.set_tile(1, 1, '*', 0, true);
+            board.set_tile(1, 1, '*', self.scoring_scheme.wildcard_points, true);
             if rng.gen_bool(0.5) {
                 // in here we will move the other wildcard to an edge
 
                 // decide which edge
                 if rng.gen_bool(0.5) {
-                    board.set_tile(3, 2, '*', 0, true);
+                    board.set_tile(3, 2, '*', self.scoring_scheme.wildcard_points, true);
                 } else {
-                    board.set_tile(2, 3, '*', 0, true);
+                    board.set_tile(2, 3, '*', self.scoring_scheme.wildcard_points, true);
                 }
             } else {
-                board.set_tile(2, 2, '*', 0, true);
+                board.set_tile(2, 2, '*', self.scoring_scheme.wildcard_points, true);
             }
         } else {
-            board.set_tile(2, 2, '*', 0, true);
+            board.set_tile(2, 2, '*', self.scoring_scheme.wildcard_points, true);
             if rng.gen_bool(0.5) {
                 // in here we will move the other wildcard to an edge
 
                 // decide which edge
                 if rng.gen_bool(0.5) {
-                    board.set_tile(1, 0, '*', 0, true);
+                    board.set_tile(1, 0, '*', self.scoring_scheme.wildcard_points, true);
                 } else {
-                    board.set_tile(0, 1, '*', 0, true);
+                    board.set_tile(0, 1, '*', self.scoring_scheme.wildcard_points, true);
                 }
             } else {
-                board.set_tile(1, 1, '*', 0, true);
+                board.set_tile(1, 1, '*', self.scoring_scheme.wildcard_points, true);
             }
         }
-
-        board
     }
 
     fn weighted_choice<R: rand::Rng>(
@@ -132,55 +278,385 @@ impl BoardGenerator {
 
 use anyhow::Result;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
 
-use crate::game::board::constraints::AnswerGroupConstraintSet;
+use crate::game::board::conflict::ConstraintConflict;
+use crate::game::board::constraints::{AnswerGroupConstraintSet, PathConstraintSet};
+use crate::game::input_guard::{screen_word, InputRejections, RejectionCounts, UnsupportedInput};
+use crate::game::optimizer::BranchAndBound;
 use crate::game::scoring::ScoreSheet;
+use crate::game::scoring_session::ScoringSession;
+use crate::game::solver::Solver;
 use crate::http_api::ApiAnswer;
+use crate::wordlist::{
+    DisplayCasing, FrequencyCorpus, SpellingVariants, VariantUsage, VariantWords,
+};
 
 #[derive(Debug, Clone)]
 pub struct OptimizationMetadata {
     pub total_score: i32,
     pub individual_scores: Vec<i32>,
     pub word_count: usize,
+    /// False when the time budget ran out before the search finished, so a
+    /// higher scoring set may exist
+    pub complete: bool,
+}
+
+/// The best a player's answers can finish on with more words
+#[derive(Debug, Clone)]
+pub struct BestFinish {
+    /// What the answers score now
+    pub current_score: i32,
+    /// The answers' score plus the best words' under the same wildcard
+    /// letters
+    pub total_score: i32,
+    /// The words to add, fewer than asked for when no more fit
+    pub words: Vec<board::answer::Answer>,
+    /// False when a search ran out of its time budget
+    pub complete: bool,
 }
 
+/// A board that met its threshold, with every valid answer and its best words
+pub type GeneratedBoard = (
+    Board,
+    Vec<board::answer::Answer>,
+    (Vec<board::answer::Answer>, OptimizationMetadata),
+);
+
 /// Main game engine that combines all the game logic components
 #[derive(Clone)]
 pub struct GameEngine {
-    word_trie: Arc<Trie>,
+    word_dawg: Arc<Dawg>,
+    rejections: Arc<InputRejections>,
+    display_casing: Arc<DisplayCasing>,
+    variant_words: Arc<VariantWords>,
+    /// Secondary list a rejected word can be challenged against
+    reference_dawg: Option<Arc<Dawg>>,
+    scoring_schemes: Arc<ScoringSchemes>,
+    /// Multiplier tiles put on new boards
+    modifier_counts: ModifierCounts,
+    /// Bonuses new games are played under
+    bonus_rules: BonusRules,
+    /// Word length and answer limits new games are played under
+    game_rules: GameRules,
+    /// Word counts rarity bonuses are judged against
+    frequency_corpus: Option<Arc<FrequencyCorpus>>,
+    /// Candidate boards generated and solved at once
+    generation_concurrency: usize,
+    /// How long a search for a board's best words may run
+    optimization_budget: Option<Duration>,
+    /// Language boards are generated in and words are checked in
+    language: Language,
+    /// Whether new boards show `qu` on one tile in place of a q
+    qu_tiles: bool,
 }
 
 impl GameEngine {
-    pub fn new<T: Into<Trie>>(trie_source: T) -> Self {
-        let word_trie = Arc::new(trie_source.into());
-        Self { word_trie }
+    pub fn new<T: Into<Dawg>>(dictionary_source: T) -> Self {
+        let word_dawg = Arc::new(dictionary_source.into());
+        Self {
+            word_dawg,
+            rejections: Arc::new(InputRejections::default()),
+            display_casing: Arc::new(DisplayCasing::default()),
+            variant_words: Arc::new(VariantWords::default()),
+            reference_dawg: None,
+            scoring_schemes: Arc::new(ScoringSchemes::default()),
+            modifier_counts: ModifierCounts::default(),
+            bonus_rules: BonusRules::default(),
+            game_rules: GameRules::default(),
+            frequency_corpus: None,
+            generation_concurrency: 1,
+            optimization_budget: None,
+            language: Language::English,
+            qu_tiles: false,
+        }
+    }
+
+    /// for_language makes an engine for another language's dictionary that
+    /// generates boards like this one, with the same multipliers, bonuses,
+    /// rules, concurrency and budget, but draws and scores letters the language's
+    /// way. Spelling variants, display casing, the reference list and the
+    /// frequency corpus are the wordlist's own, so none are carried over.
+    pub fn for_language<T: Into<Dawg>>(&self, language: Language, dictionary_source: T) -> Self {
+        let mut schemes = ScoringSchemes::default();
+        schemes
+            .set_active(language.scoring_scheme_version())
+            .expect("every language has a built-in scheme");
+        Self {
+            language,
+            ..GameEngine::new(dictionary_source)
+                .with_scoring_schemes(schemes)
+                .with_modifier_counts(self.modifier_counts)
+                .with_bonus_rules(self.bonus_rules.clone())
+                .with_game_rules(self.game_rules)
+                .with_generation_concurrency(self.generation_concurrency)
+                .with_optimization_budget(self.optimization_budget)
+                .with_qu_tiles(self.qu_tiles)
+        }
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    /// normalize_word puts a word as a player typed it into the form the
+    /// engine's dictionary spells it: composed, case folded and, outside
+    /// English, with its accents folded into the board's alphabet
+    pub fn normalize_word(&self, word: &str) -> String {
+        self.language.fold_word(&letters::normalize(word))
+    }
+
+    /// sanitize_answer normalizes a submitted answer's word
+    pub fn sanitize_answer(&self, answer: ApiAnswer) -> ApiAnswer {
+        ApiAnswer {
+            word: self.normalize_word(&answer.word),
+            ..answer
+        }
+    }
+
+    /// with_optimization_budget bounds how long finding a board's best words
+    /// may take; when it runs out the best set found so far is used. None
+    /// searches to the end, which keeps generation deterministic.
+    pub fn with_optimization_budget(mut self, budget: Option<Duration>) -> Self {
+        self.optimization_budget = budget;
+        self
+    }
+
+    /// with_generation_concurrency sets how many candidate boards are
+    /// generated and solved at once when looking for one that qualifies.
+    /// Candidates are still judged in order, so this never changes which
+    /// board is picked, only how long picking it takes.
+    pub fn with_generation_concurrency(mut self, concurrency: usize) -> Self {
+        self.generation_concurrency = concurrency.max(1);
+        self
+    }
+
+    pub fn generation_concurrency(&self) -> usize {
+        self.generation_concurrency
+    }
+
+    /// with_modifier_counts sets how many multiplier tiles of each kind new
+    /// boards get
+    pub fn with_modifier_counts(mut self, counts: ModifierCounts) -> Self {
+        self.modifier_counts = counts;
+        self
+    }
+
+    /// with_qu_tiles makes new boards show `qu` on one tile in place of a q
+    pub fn with_qu_tiles(mut self, qu_tiles: bool) -> Self {
+        self.qu_tiles = qu_tiles;
+        self
+    }
+
+    /// with_bonus_rules sets the bonuses new games are generated with
+    pub fn with_bonus_rules(mut self, bonus_rules: BonusRules) -> Self {
+        self.bonus_rules = bonus_rules;
+        self
+    }
+
+    pub fn bonus_rules(&self) -> &BonusRules {
+        &self.bonus_rules
+    }
+
+    /// with_game_rules sets the word length and answer limits new games are
+    /// generated with, which decide the words found on their boards and the
+    /// size of their optimal set
+    pub fn with_game_rules(mut self, game_rules: GameRules) -> Self {
+        self.game_rules = game_rules;
+        self
+    }
+
+    pub fn game_rules(&self) -> &GameRules {
+        &self.game_rules
+    }
+
+    /// with_frequency_corpus sets the corpus that decides which words earn a
+    /// rarity bonus. Without one no word does.
+    pub fn with_frequency_corpus(mut self, corpus: FrequencyCorpus) -> Self {
+        self.frequency_corpus = Some(Arc::new(corpus));
+        self
+    }
+
+    pub fn frequency_corpus(&self) -> Option<&FrequencyCorpus> {
+        self.frequency_corpus.as_deref()
+    }
+
+    /// with_scoring_schemes sets the schemes games can be scored with; new
+    /// boards get the active one's points
+    pub fn with_scoring_schemes(mut self, schemes: ScoringSchemes) -> Self {
+        self.scoring_schemes = Arc::new(schemes);
+        self
+    }
+
+    pub fn scoring_schemes(&self) -> &ScoringSchemes {
+        &self.scoring_schemes
+    }
+
+    /// with_reference_words sets the secondary list, typically a larger
+    /// lexicon than the wordlist, that players can challenge a rejected word
+    /// against. Without one every challenge fails.
+    pub fn with_reference_words(mut self, words: Vec<String>) -> Self {
+        self.reference_dawg = (!words.is_empty()).then(|| Arc::new(Dawg::build(words)));
+        self
+    }
+
+    /// in_reference_list reports whether a challenge of `word` succeeds
+    pub fn in_reference_list(&self, word: &str) -> bool {
+        self.reference_dawg
+            .as_ref()
+            .is_some_and(|reference| reference.search(word))
+    }
+
+    pub fn with_display_casing(mut self, display_casing: DisplayCasing) -> Self {
+        self.display_casing = Arc::new(display_casing);
+        self
+    }
+
+    /// with_spelling_variants adds the spellings in `variants` the dictionary
+    /// is missing, rebuilding it so they can be found on boards as well as
+    /// accepted in answers. The word each was added for is kept, see
+    /// `variant_source`.
+    pub fn with_spelling_variants(mut self, variants: &SpellingVariants) -> Self {
+        let added = variants.expand(|word| self.word_dawg.search(word));
+        if !added.is_empty() {
+            let mut words = self.word_dawg.words();
+            words.extend(added.words().map(str::to_string));
+            self.word_dawg = Arc::new(Dawg::build(words));
+            info!("Added {} spelling variants to the dictionary", added.len());
+        }
+        self.variant_words = Arc::new(added);
+        self
+    }
+
+    /// variant_source returns the dictionary word `word` was added as a
+    /// spelling variant of, or None if the dictionary has it in its own right
+    pub fn variant_source(&self, word: &str) -> Option<&str> {
+        self.variant_words.source(word)
+    }
+
+    /// How often each added spelling variant has been accepted in an answer
+    /// since startup
+    pub fn variant_usage(&self) -> Vec<VariantUsage> {
+        self.variant_words.usage()
+    }
+
+    /// display_form is how responses should show `word`
+    pub fn display_form(&self, word: &str) -> String {
+        self.display_casing.display(word).to_string()
+    }
+
+    /// screen_word rejects words the dictionary can't contain, such as ones
+    /// in another alphabet, before they reach the dictionary. Rejections are
+    /// counted by reason.
+    pub fn screen_word(&self, word: &str) -> Result<(), UnsupportedInput> {
+        screen_word(word).inspect_err(|reason| {
+            info!("Rejected unsupported word ({:?}): {}", reason, word);
+            self.rejections.record(*reason);
+        })
+    }
+
+    /// How many words screen_word has rejected for each reason since startup
+    pub fn rejection_counts(&self) -> RejectionCounts {
+        self.rejections.counts()
     }
 
     pub fn validate_api_answer_group(
         &self,
         board: &Board,
         answers: Vec<ApiAnswer>,
-    ) -> Result<(), String> {
+    ) -> Result<(), GameError> {
+        self.validate_api_answer_group_accepting(board, answers, &HashSet::new())
+    }
+
+    /// validate_api_answer_group_accepting validates answers like
+    /// validate_api_answer_group, but also accepts the words in `challenged`,
+    /// which the player has successfully challenged for this entry
+    pub fn validate_api_answer_group_accepting(
+        &self,
+        board: &Board,
+        answers: Vec<ApiAnswer>,
+        challenged: &HashSet<String>,
+    ) -> Result<(), GameError> {
         // Sanitize input
-        let mut sanitized_answers: Vec<ApiAnswer> = Vec::new();
-        for answer in answers {
-            sanitized_answers.push(answer.clone().sanitize());
-        }
-        self.validate_answer_group(
+        let sanitized_answers: Vec<ApiAnswer> = answers
+            .into_iter()
+            .map(|answer| self.sanitize_answer(answer))
+            .collect();
+        self.validate_answer_group_accepting(
             board,
             sanitized_answers
                 .iter()
                 .map(|m| m.word.to_string())
                 .collect(),
-        )
+            challenged,
+        )?;
+        self.validate_traced_paths(board, &sanitized_answers)?;
+
+        for answer in &sanitized_answers {
+            self.variant_words.record_accepted(&answer.word);
+        }
+        Ok(())
+    }
+
+    /// validate_traced_paths checks that each traced path is one of the paths
+    /// the board has for its word, wildcard letters included, and that the
+    /// paths agree on what each wildcard is
+    fn validate_traced_paths(&self, board: &Board, answers: &[ApiAnswer]) -> Result<(), GameError> {
+        let mut wildcards = PathConstraintSet::Unconstrainted;
+        let mut traced_constraints = Vec::new();
+        for answer in answers {
+            let Some(traced) = &answer.path else {
+                continue;
+            };
+
+            let constraints = PathConstraintSet::from(traced.constraints);
+            let on_board = board.paths_for(&answer.word).paths.iter().any(|path| {
+                path.constraints == constraints
+                    && path.tiles.len() == traced.positions.len()
+                    && path
+                        .tiles
+                        .iter()
+                        .zip(&traced.positions)
+                        .all(|(tile, position)| {
+                            tile.row == position.row && tile.col == position.col
+                        })
+            });
+            if !on_board {
+                return Err(GameError::NotOnBoard {
+                    word: answer.word.clone(),
+                });
+            }
+
+            traced_constraints.push((
+                answer.word.as_str(),
+                AnswerGroupConstraintSet::from(vec![constraints]),
+            ));
+            wildcards = wildcards.merge(constraints).map_err(|_| {
+                GameError::ConstraintConflict(ConstraintConflict::explain(
+                    traced_constraints
+                        .iter()
+                        .map(|(word, constraints)| (*word, constraints)),
+                ))
+            })?;
+        }
+        Ok(())
+    }
+
+    #[cfg(test)]
+    fn validate_answer_group(&self, board: &Board, answers: Vec<String>) -> Result<(), GameError> {
+        self.validate_answer_group_accepting(board, answers, &HashSet::new())
     }
 
-    fn validate_answer_group(&self, board: &Board, answers: Vec<String>) -> Result<(), String> {
+    fn validate_answer_group_accepting(
+        &self,
+        board: &Board,
+        answers: Vec<String>,
+        challenged: &HashSet<String>,
+    ) -> Result<(), GameError> {
         // First validate that all words exist in the dictionary
         for answer in &answers {
-            if !self.is_valid_word_in_dictionary(answer) {
-                return Err(format!("Word '{answer}' is not in the dictionary"));
-            }
+            self.check_answer_word(answer, challenged)?;
         }
 
         // need a step here where we check a word actually has >1 paths, unless maybe is_valid_set is already handling that for us
@@ -188,88 +664,105 @@ impl GameEngine {
         // Get all paths for each word
         let answers_with_all_paths = board.get_answers_with_all_paths(answers)?;
 
-        // Ensure constraints can be satisfied
-        if AnswerGroupConstraintSet::is_valid_set(answers_with_all_paths) {
+        // Ensure constraints can be satisfied, explaining which words clash
+        // when they can't
+        if AnswerGroupConstraintSet::is_valid_set(answers_with_all_paths.clone()) {
             Ok(())
         } else {
-            Err("Some answers have conflicting wildcard constraints".to_string())
+            Err(GameError::ConstraintConflict(ConstraintConflict::explain(
+                answers_with_all_paths
+                    .iter()
+                    .map(|answer| (answer.word.as_str(), &answer.constraints_set)),
+            )))
         }
     }
 
-    pub fn is_valid_word_in_dictionary(&self, word: &str) -> bool {
-        self.word_trie.search(word)
-    }
-
-    /// score_answer_group finds all the possible AnswerGroupConstraintSets, calculates the scores for all words based on each set of constraints, and returns the HashMap of answer -> score for the highest total scoring paths that can coexist based on constraints. It returns an error if the answers cannot coexist based on constraints.
-    pub fn score_answer_group(
+    /// resolve_constraints merges what `words` need of the wildcards on
+    /// `board` into every way the wildcards can be played with all of them,
+    /// in order. Words need only be on the board, not in the dictionary.
+    pub fn resolve_constraints(
         &self,
         board: &Board,
-        answers: Vec<String>,
-    ) -> Result<ScoreSheet, String> {
+        words: Vec<String>,
+    ) -> Result<AnswerGroupConstraintSet, GameError> {
+        for word in &words {
+            self.check_word(word)?;
+        }
+        let answers = board.get_answers_with_all_paths(words)?;
         if answers.is_empty() {
-            return Ok(ScoreSheet::new());
+            return Ok(AnswerGroupConstraintSet::from(vec![
+                PathConstraintSet::Unconstrainted,
+            ]));
         }
 
-        // Find all possible paths for each answer
-        let mut answer_objects = Vec::new();
-        for word in answers {
-            let answer = self.find_word_paths(board, &word);
-            if answer.paths.is_empty() {
-                return Err(format!("Word '{word}' cannot be formed on this board"));
-            }
-            answer_objects.push(answer);
-        }
+        let mut resolved = AnswerGroupConstraintSet::merge_all(
+            answers
+                .iter()
+                .map(|answer| answer.constraints_set.clone())
+                .collect(),
+        )
+        .map_err(|_| {
+            GameError::ConstraintConflict(ConstraintConflict::explain(
+                answers
+                    .iter()
+                    .map(|answer| (answer.word.as_str(), &answer.constraints_set)),
+            ))
+        })?;
+        resolved.path_constraint_sets.sort();
+        Ok(resolved)
+    }
 
-        let valid_constraint_set =
-            if let Ok(v) = AnswerGroupConstraintSet::try_from(&answer_objects) {
-                v
-            } else {
-                return Err(
-                    "Answers cannot coexist due to conflicting wildcard constraints".to_string(),
-                );
-            };
+    /// check_word rejects empty words and ones screen_word turns away
+    fn check_word(&self, word: &str) -> Result<(), GameError> {
+        if word.is_empty() {
+            return Err(GameError::EmptyInput);
+        }
+        self.screen_word(word)
+            .map_err(|reason| GameError::Unsupported {
+                word: word.to_string(),
+                reason,
+            })
+    }
 
-        // For each valid path constraint set, calculate the maximum possible score
-        let mut max_total_score = 0u32;
-        let mut best_scores_by_word = HashMap::new();
-
-        for path_constraint in &valid_constraint_set.path_constraint_sets {
-            let mut total_score = 0u32;
-            let mut scores_by_word = HashMap::new();
-
-            // For each answer, find the best scoring path that satisfies this constraint
-            for answer_obj in &answer_objects {
-                let mut best_path_score = 0;
-
-                // Check all paths for this answer to find the one that works with current constraints
-                for path in &answer_obj.paths {
-                    // Check if this path's constraints are compatible with the current path_constraint
-                    if path.constraints.merge(*path_constraint).is_ok() {
-                        let path_score: u32 = path
-                            .tiles
-                            .iter()
-                            .map(|tile| tile.points)
-                            .sum::<i32>()
-                            .try_into()
-                            .unwrap();
-                        best_path_score = best_path_score.max(path_score);
-                    }
-                }
+    /// check_answer_word rejects a word that couldn't be an answer wherever
+    /// it is on the board: one check_word turns away, or one missing from the
+    /// dictionary that wasn't `challenged` in
+    pub fn check_answer_word(
+        &self,
+        word: &str,
+        challenged: &HashSet<String>,
+    ) -> Result<(), GameError> {
+        self.check_word(word)?;
+        if !self.is_valid_word_in_dictionary(word) && !challenged.contains(word) {
+            return Err(GameError::NotInDictionary {
+                word: word.to_string(),
+            });
+        }
+        Ok(())
+    }
 
-                // Record this answer's best score and add to total (ensuring non-negative)
-                let word_score = best_path_score;
-                scores_by_word.insert(answer_obj.word.clone(), word_score);
-                total_score += word_score;
-            }
+    pub fn is_valid_word_in_dictionary(&self, word: &str) -> bool {
+        self.word_dawg.search(word)
+    }
 
-            // If this constraint set gives us a better total score, use it
-            if total_score > max_total_score {
-                max_total_score = total_score;
-                best_scores_by_word = scores_by_word;
-            }
-        }
+    /// score_answer_group finds all the possible AnswerGroupConstraintSets, calculates the scores for all words based on each set of constraints, and returns the HashMap of answer -> score for the highest total scoring paths that can coexist based on constraints. It returns an error if the answers cannot coexist based on constraints. Words earn the bonuses in `bonus_rules` on top of their tiles.
+    pub fn score_answer_group(
+        &self,
+        board: &Board,
+        answers: Vec<String>,
+        bonus_rules: &BonusRules,
+    ) -> Result<ScoreSheet, GameError> {
+        self.scoring_session(board, bonus_rules).score(answers)
+    }
 
-        Ok(ScoreSheet::from(best_scores_by_word))
+    /// scoring_session starts a session for scoring a list of answers on
+    /// `board` as it changes, e.g. a player's entry across saves
+    pub fn scoring_session(&self, board: &Board, bonus_rules: &BonusRules) -> ScoringSession {
+        ScoringSession::new(
+            board.clone(),
+            bonus_rules.clone(),
+            self.frequency_corpus.clone(),
+        )
     }
 
     pub fn find_word_paths(&self, board: &Board, word: &str) -> board::answer::Answer {
@@ -280,205 +773,120 @@ impl GameEngine {
         &self,
         board: &Board,
         word: &str,
-    ) -> Result<board::answer::Answer, String> {
+    ) -> Result<board::answer::Answer, GameError> {
+        self.check_word(word)?;
+
         // First check if the word is in our dictionary
         if !self.is_valid_word_in_dictionary(word) {
-            return Err(format!("Word '{word}' not found in dictionary"));
+            return Err(GameError::NotInDictionary {
+                word: word.to_string(),
+            });
         }
 
         // Find all possible paths for this word on the board
         let answer = self.find_word_paths(board, word);
 
         if answer.paths.is_empty() {
-            return Err(format!("Word '{word}' cannot be formed on this board"));
+            return Err(GameError::NotOnBoard {
+                word: word.to_string(),
+            });
         }
 
         Ok(answer)
     }
 
     pub async fn find_all_valid_words(&self, board: &Board) -> Result<Vec<board::answer::Answer>> {
-        let mut valid_answers = Vec::new();
-
-        // Generate all possible words from the board using DFS
-        let mut found_words = std::collections::HashSet::new();
-
-        // Start from each position on the board
-        for row in 0..4 {
-            for col in 0..4 {
-                let mut visited = std::collections::HashSet::new();
-                self.find_words_from_position(
-                    board,
-                    row,
-                    col,
-                    String::new(),
-                    &mut visited,
-                    &mut found_words,
-                );
-            }
-        }
-
-        // Validate found words against our dictionary and create answers
-        for word in found_words {
-            if word.len() >= 3 && self.is_valid_word_in_dictionary(&word) {
-                if let Ok(answer) = self.validate_answer(board, &word) {
-                    if !answer.paths.is_empty() {
-                        valid_answers.push(answer);
-                    }
-                }
-            }
-        }
-
-        Ok(valid_answers)
-    }
-
-    fn find_words_from_position(
-        &self,
-        board: &Board,
-        row: usize,
-        col: usize,
-        current_word: String,
-        visited: &mut std::collections::HashSet<(usize, usize)>,
-        found_words: &mut std::collections::HashSet<String>,
-    ) {
-        // Don't exceed reasonable word length
-        if current_word.len() > 16 {
-            return;
-        }
-
-        // Mark current position as visited
-        visited.insert((row, col));
-
-        // Get current tile
-        let tile = board.get_tile(row, col);
-
-        if tile.is_wildcard {
-            // For wildcards, try all possible letters
-            for letter in 'a'..='z' {
-                let mut new_word = current_word.clone();
-                new_word.push(letter);
-
-                // Early termination: if this prefix can't lead to any valid words, skip
-                if !self.word_trie.has_prefix(&new_word) {
-                    continue;
-                }
-
-                // If word is long enough and valid, add it to found words
-                if new_word.len() >= 3 && self.word_trie.search(&new_word) {
-                    found_words.insert(new_word.clone());
-                }
-
-                // Explore adjacent positions with this wildcard letter choice
-                self.explore_adjacent_positions(board, row, col, new_word, visited, found_words);
-            }
-        } else {
-            // For regular tiles, add the letter
-            let mut new_word = current_word;
-            new_word.push_str(&tile.letter);
-
-            // Early termination: if this prefix can't lead to any valid words, stop
-            if !self.word_trie.has_prefix(&new_word) {
-                visited.remove(&(row, col));
-                return;
-            }
-
-            // If word is long enough and valid, add it to found words
-            if new_word.len() >= 3 && self.word_trie.search(&new_word) {
-                found_words.insert(new_word.clone());
-            }
-
-            // Explore adjacent positions
-            self.explore_adjacent_positions(board, row, col, new_word, visited, found_words);
-        }
-
-        // Unmark position for other paths
-        visited.remove(&(row, col));
-    }
-
-    fn explore_adjacent_positions(
-        &self,
-        board: &Board,
-        row: usize,
-        col: usize,
-        current_word: String,
-        visited: &mut std::collections::HashSet<(usize, usize)>,
-        found_words: &mut std::collections::HashSet<String>,
-    ) {
-        let directions = [
-            (-1, -1),
-            (-1, 0),
-            (-1, 1),
-            (0, -1),
-            (0, 1),
-            (1, -1),
-            (1, 0),
-            (1, 1),
-        ];
-
-        for (dr, dc) in directions {
-            let new_row = row as i32 + dr;
-            let new_col = col as i32 + dc;
-
-            if (0..4).contains(&new_row) && (0..4).contains(&new_col) {
-                let new_row = new_row as usize;
-                let new_col = new_col as usize;
-
-                if !visited.contains(&(new_row, new_col)) {
-                    self.find_words_from_position(
-                        board,
-                        new_row,
-                        new_col,
-                        current_word.clone(),
-                        visited,
-                        found_words,
-                    );
-                }
-            }
-        }
+        Ok(Solver::new(board)
+            .with_min_word_length(self.game_rules.min_word_len)
+            .solve(&self.word_dawg))
     }
 
     pub async fn find_best_n_words(
         &self,
         board: &Board,
+        bonus_rules: &BonusRules,
         n: usize,
     ) -> Result<(Vec<board::answer::Answer>, OptimizationMetadata)> {
         let all_answers = self.find_all_valid_words(board).await?;
-        let result = self.find_best_n_words_from_answers(&all_answers, n)?;
+        let result = self.find_best_n_words_from_answers(&all_answers, bonus_rules, n)?;
         Ok(result)
     }
 
-    /// Try to generate a valid board that meets the threshold score
-    pub async fn try_generate_valid_board<R: rand::Rng>(
+    /// Try to generate a valid board that meets the threshold score, themed
+    /// when `theme` is given. This solves the board on the calling thread, so
+    /// it can run on any of rayon's.
+    pub fn try_generate_valid_board<R: rand::Rng>(
         &self,
         rng: &mut R,
         threshold_score: i32,
-    ) -> Result<(
-        crate::game::board::Board,
-        Vec<crate::game::board::answer::Answer>,
-        (Vec<board::answer::Answer>, OptimizationMetadata),
-    )> {
-        let board_generator = BoardGenerator::new();
-        let board = board_generator.generate_board(rng);
-
-        // Find optimal set of 5 words instead of just checking top 5 individually
-        let all_valid_answers = self.find_all_valid_words(&board).await?;
-        let (optimal_words, metadata) =
-            self.find_best_n_words_from_answers(&all_valid_answers, 5)?;
+        theme: Option<&Theme>,
+    ) -> Result<GeneratedBoard> {
+        let board_generator = BoardGenerator::for_language(self.language)
+            .with_scoring_scheme(self.scoring_schemes.active().clone())
+            .with_modifier_counts(self.modifier_counts)
+            .with_qu_tiles(self.qu_tiles);
+        let board = match theme {
+            Some(theme) => board_generator
+                .generate_themed_board(rng, theme)
+                .ok_or_else(|| anyhow::anyhow!("Theme {} doesn't fit on the board", theme))?,
+            None => board_generator.generate_board(rng),
+        };
+
+        // Find the optimal set of words an entry holds instead of just checking
+        // the top ones individually
+        let all_valid_answers = Solver::new(&board)
+            .with_min_word_length(self.game_rules.min_word_len)
+            .solve(&self.word_dawg);
+        let (optimal_words, metadata) = self.find_best_n_words_from_answers(
+            &all_valid_answers,
+            &self.bonus_rules,
+            self.game_rules.max_answers,
+        )?;
 
         if metadata.total_score >= threshold_score {
             Ok((board, all_valid_answers, (optimal_words, metadata)))
         } else {
-            anyhow::bail!(
-                "Board quality insufficient: optimal 5 words sum to {} (threshold: {})",
-                metadata.total_score,
-                threshold_score
-            );
+            // Say what else is wrong with the board so retries can be tuned
+            Err(BoardRejection {
+                optimal_score: metadata.total_score,
+                threshold_score,
+                quality: BoardQuality::analyze(&board, &all_valid_answers),
+            }
+            .into())
         }
     }
 
+    /// find_best_n_words_from_answers finds the n of `answers` that score the
+    /// most together, each word earning the bonuses in `bonus_rules` as a
+    /// player's would
     pub fn find_best_n_words_from_answers(
         &self,
         answers: &[board::answer::Answer],
+        bonus_rules: &BonusRules,
+        n: usize,
+    ) -> Result<(Vec<board::answer::Answer>, OptimizationMetadata)> {
+        let deadline = self
+            .optimization_budget
+            .map(|budget| Instant::now() + budget);
+        let found = self.find_best_n_words_before(answers, bonus_rules, n, deadline)?;
+        if !found.1.complete {
+            warn!(
+                "Best words search ran out of its {:?} budget; using the best set found",
+                self.optimization_budget.unwrap_or_default()
+            );
+        }
+        Ok(found)
+    }
+
+    /// find_best_n_words_before finds the best n of `answers` as
+    /// find_best_n_words_from_answers does, settling for the best set found
+    /// by `deadline`
+    fn find_best_n_words_before(
+        &self,
+        answers: &[board::answer::Answer],
+        bonus_rules: &BonusRules,
         n: usize,
+        deadline: Option<Instant>,
     ) -> Result<(Vec<board::answer::Answer>, OptimizationMetadata)> {
         if answers.is_empty() {
             return Ok((
@@ -487,6 +895,7 @@ impl GameEngine {
                     total_score: 0,
                     individual_scores: vec![],
                     word_count: 0,
+                    complete: true,
                 },
             ));
         }
@@ -498,160 +907,130 @@ impl GameEngine {
                     total_score: 0,
                     individual_scores: vec![],
                     word_count: 0,
+                    complete: true,
                 },
             ));
         }
 
-        // Phase 1: Sort answers by descending score
+        // Phase 1: Sort answers by descending score, bonuses included
+        let score = |a: &board::answer::Answer| {
+            bonus_rules.answer_points(a, self.frequency_corpus.as_deref())
+        };
         let mut sorted_answers = answers.to_owned();
-        sorted_answers.sort_by_key(|a| Reverse(a.score()));
+        sorted_answers.sort_by_cached_key(|a| Reverse(score(a)));
+        let scores: Vec<i32> = sorted_answers.iter().map(score).collect();
 
-        // Phase 2: Try greedy approach first (fast path)
+        // Phase 2: Greedy selection, which the search has to beat
         let greedy_result = self.greedy_selection(&sorted_answers, n)?;
 
-        // Phase 3: If we didn't get the requested number, try backtracking approach
-        let final_result = if greedy_result.len() < n && greedy_result.len() < sorted_answers.len()
-        {
-            let backtrack_result = self.backtrack_selection(&sorted_answers, n)?;
-            // Choose the better result (more words, or same words with higher score)
-            if backtrack_result.len() > greedy_result.len()
-                || (backtrack_result.len() == greedy_result.len()
-                    && backtrack_result.iter().map(|a| a.score()).sum::<i32>()
-                        > greedy_result.iter().map(|a| a.score()).sum::<i32>())
-            {
-                backtrack_result
-            } else {
-                greedy_result
-            }
-        } else {
-            greedy_result
+        // Phase 3: Branch and bound for the best n compatible words, falling
+        // back to greedy when no full set beats it or the budget runs out
+        let mut search = BranchAndBound::new(&sorted_answers, &scores, n).with_deadline(deadline);
+        if greedy_result.len() == n {
+            search = search.with_incumbent(greedy_result.iter().map(score).sum());
+        }
+        let selection = search.run();
+        let final_result = match selection.indices {
+            Some(indices) => indices
+                .into_iter()
+                .map(|i| sorted_answers[i].clone())
+                .collect(),
+            None => greedy_result,
         };
 
         // Create metadata
-        let individual_scores: Vec<i32> = final_result.iter().map(|a| a.score()).collect();
+        let individual_scores: Vec<i32> = final_result.iter().map(score).collect();
         let total_score = individual_scores.iter().sum();
 
         let metadata = OptimizationMetadata {
             total_score,
             individual_scores,
             word_count: final_result.len(),
+            complete: selection.complete,
         };
 
         Ok((final_result, metadata))
     }
 
-    fn greedy_selection(
+    /// best_finish finds the highest total `words` can reach with up to `n`
+    /// more of `candidates`. Under each constraint set the words all agree on,
+    /// the candidates are narrowed to their paths that fit it and the best n
+    /// found with find_best_n_words_from_answers; a set another one loosens
+    /// never does better, so it is skipped. Added words earn the bonuses in
+    /// `bonus_rules` as played ones do. The searches settle for the best
+    /// finish found by `deadline`, which isn't then complete.
+    pub fn best_finish(
         &self,
-        sorted_answers: &[board::answer::Answer],
+        board: &Board,
+        bonus_rules: &BonusRules,
+        words: Vec<String>,
+        candidates: &[board::answer::Answer],
         n: usize,
-    ) -> Result<Vec<board::answer::Answer>> {
-        let mut selected_answers = Vec::new();
-        let mut used_indices = std::collections::HashSet::new();
-
-        for (i, answer) in sorted_answers.iter().enumerate() {
-            if selected_answers.len() >= n {
-                break;
-            }
-
-            if used_indices.contains(&i) {
+        deadline: Option<Instant>,
+    ) -> Result<BestFinish, GameError> {
+        let mut session = self.scoring_session(board, bonus_rules);
+        let current_score = session.score(words.clone())?.total_score() as i32;
+        let sheets = session.sheets();
+
+        let mut best = BestFinish {
+            current_score,
+            total_score: current_score,
+            words: vec![],
+            complete: true,
+        };
+        for (constraint, sheet) in &sheets {
+            let loosened = sheets.iter().any(|(other, _)| {
+                other != constraint && other.merge(*constraint) == Ok(*constraint)
+            });
+            if loosened {
                 continue;
             }
 
-            let candidate = answer;
-
-            // Check if this candidate is compatible with current selection
-            if self.is_compatible_with_selection(candidate, &selected_answers)? {
-                selected_answers.push(candidate.clone());
-                used_indices.insert(i);
+            let narrowed: Vec<board::answer::Answer> = candidates
+                .iter()
+                .filter(|candidate| !words.contains(&candidate.word))
+                .filter_map(|candidate| narrow_answer(candidate, *constraint))
+                .collect();
+            let (found, metadata) = self
+                .find_best_n_words_before(&narrowed, bonus_rules, n, deadline)
+                .expect("finding the best words doesn't fail");
+            best.complete &= metadata.complete;
+            let total_score = sheet.total_score() as i32 + metadata.total_score;
+            if total_score > best.total_score {
+                best.total_score = total_score;
+                best.words = found;
             }
         }
-
-        Ok(selected_answers)
+        Ok(best)
     }
 
-    fn backtrack_selection(
+    fn greedy_selection(
         &self,
         sorted_answers: &[board::answer::Answer],
         n: usize,
     ) -> Result<Vec<board::answer::Answer>> {
-        let mut best_combination = Vec::new();
-        let mut best_score = 0;
-
-        // Try all possible combinations using backtracking
-        let mut current_combination = Vec::new();
+        let mut selected_answers = Vec::new();
         let mut used_indices = std::collections::HashSet::new();
 
-        self.backtrack_helper(
-            sorted_answers,
-            n,
-            0,
-            &mut current_combination,
-            &mut used_indices,
-            &mut best_combination,
-            &mut best_score,
-        )?;
-
-        Ok(best_combination)
-    }
-
-    #[allow(clippy::too_many_arguments)]
-    fn backtrack_helper(
-        &self,
-        sorted_answers: &[board::answer::Answer],
-        target_n: usize,
-        start_index: usize,
-        current_combination: &mut Vec<board::answer::Answer>,
-        used_indices: &mut std::collections::HashSet<usize>,
-        best_combination: &mut Vec<board::answer::Answer>,
-        best_score: &mut i32,
-    ) -> Result<()> {
-        // If we have enough words, check if this is the best combination so far
-        if current_combination.len() == target_n {
-            let current_score: i32 = current_combination.iter().map(|a| a.score()).sum();
-            if current_score > *best_score {
-                *best_score = current_score;
-                *best_combination = current_combination.clone();
+        for (i, answer) in sorted_answers.iter().enumerate() {
+            if selected_answers.len() >= n {
+                break;
             }
-            return Ok(());
-        }
 
-        // Early termination: if we can't possibly beat the best score even with remaining highest-scoring words
-        if current_combination.len() + (sorted_answers.len() - start_index) < target_n {
-            return Ok(());
-        }
-
-        // Try each remaining word
-        for i in start_index..sorted_answers.len() {
             if used_indices.contains(&i) {
                 continue;
             }
 
-            let candidate = &sorted_answers[i];
+            let candidate = answer;
 
             // Check if this candidate is compatible with current selection
-            if self.is_compatible_with_selection(candidate, current_combination)? {
-                // Add to current combination
-                current_combination.push(candidate.clone());
+            if self.is_compatible_with_selection(candidate, &selected_answers)? {
+                selected_answers.push(candidate.clone());
                 used_indices.insert(i);
-
-                // Recursive call
-                self.backtrack_helper(
-                    sorted_answers,
-                    target_n,
-                    i + 1,
-                    current_combination,
-                    used_indices,
-                    best_combination,
-                    best_score,
-                )?;
-
-                // Backtrack
-                current_combination.pop();
-                used_indices.remove(&i);
             }
         }
 
-        Ok(())
+        Ok(selected_answers)
     }
 
     fn is_compatible_with_selection(
@@ -672,6 +1051,40 @@ impl GameEngine {
     }
 }
 
+/// narrow_answer keeps the paths of `answer` that fit `constraint`, each
+/// taking on the letters it fixes, best scoring first so `score` gives the
+/// best of them. None when no path fits.
+fn narrow_answer(
+    answer: &board::answer::Answer,
+    constraint: PathConstraintSet,
+) -> Option<board::answer::Answer> {
+    let mut paths: Vec<board::path::Path> = answer
+        .paths
+        .iter()
+        .filter_map(|path| {
+            let constraints = path.constraints.merge(constraint).ok()?;
+            Some(board::path::Path {
+                constraints,
+                ..path.clone()
+            })
+        })
+        .collect();
+    if paths.is_empty() {
+        return None;
+    }
+    paths.sort_by_key(|path| Reverse(path.points()));
+    Some(board::answer::Answer {
+        word: answer.word.clone(),
+        constraints_set: AnswerGroupConstraintSet::from(
+            paths
+                .iter()
+                .map(|path| path.constraints)
+                .collect::<Vec<_>>(),
+        ),
+        paths,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use std::cmp::Reverse;
@@ -679,6 +1092,7 @@ mod tests {
     use crate::test_utils;
 
     use super::*;
+    use board::modifier::TileModifier;
     use rand::SeedableRng;
     fn create_test_wordlist() -> Vec<&'static str> {
         vec![
@@ -764,6 +1178,101 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_board_generator_places_modifiers_on_letters() {
+        let plain = BoardGenerator::new().generate_board(&mut rand::rngs::StdRng::seed_from_u64(7));
+        let counts = ModifierCounts {
+            double_letter: 2,
+            triple_letter: 1,
+            double_word: 1,
+            triple_word: 1,
+        };
+        let board = BoardGenerator::new()
+            .with_modifier_counts(counts)
+            .generate_board(&mut rand::rngs::StdRng::seed_from_u64(7));
+
+        let tiles: Vec<_> = board.rows.iter().flat_map(|row| &row.tiles).collect();
+        let modified: Vec<_> = tiles
+            .iter()
+            .filter(|tile| tile.modifier.is_some())
+            .collect();
+        assert_eq!(modified.len(), counts.total());
+        assert!(modified.iter().all(|tile| !tile.is_wildcard));
+        let count = |modifier| {
+            modified
+                .iter()
+                .filter(|tile| tile.modifier == Some(modifier))
+                .count()
+        };
+        assert_eq!(count(TileModifier::DoubleLetter), 2);
+        assert_eq!(count(TileModifier::TripleWord), 1);
+
+        // Only the modifiers differ from the board generated without them
+        for (tile, plain) in tiles
+            .iter()
+            .zip(plain.rows.iter().flat_map(|row| &row.tiles))
+        {
+            assert_eq!(tile.letter, plain.letter);
+            assert!(plain.modifier.is_none());
+        }
+    }
+
+    #[test]
+    fn test_board_generator_draws_qu_tiles() {
+        let generator = BoardGenerator::new().with_qu_tiles(true);
+        let mut qu_tiles = 0;
+        for seed in 0..500 {
+            let plain =
+                BoardGenerator::new().generate_board(&mut rand::rngs::StdRng::seed_from_u64(seed));
+            let board = generator.generate_board(&mut rand::rngs::StdRng::seed_from_u64(seed));
+            for (tile, plain) in board
+                .rows
+                .iter()
+                .flat_map(|row| &row.tiles)
+                .zip(plain.rows.iter().flat_map(|row| &row.tiles))
+            {
+                if plain.letter == "q" {
+                    assert_eq!(tile.letter, QU_TILE);
+                    qu_tiles += 1;
+                } else {
+                    assert_eq!(tile.letter, plain.letter);
+                }
+                // The u comes free with the q
+                assert_eq!(tile.points, plain.points);
+            }
+        }
+        assert!(qu_tiles > 0);
+
+        // A word through the tile scores it once
+        let board: Board = "(qu)itsaeotsaniinal".parse().unwrap();
+        let quit = &board.paths_for("quit").paths[0];
+        let points = |letter| scoring::points_for_letter(letter);
+        assert_eq!(quit.points(), points('q') + points('i') + points('t'));
+    }
+
+    #[test]
+    fn test_paths_score_their_modifiers() {
+        let mut board = create_test_board();
+        // c(2) a(1) t(1)
+        let cat = |board: &Board| board.paths_for("cat").paths[0].points();
+        assert_eq!(cat(&board), 4);
+
+        board.set_modifier(0, 0, Some(TileModifier::TripleLetter));
+        assert_eq!(cat(&board), 8);
+        board.set_modifier(0, 2, Some(TileModifier::DoubleWord));
+        assert_eq!(cat(&board), 16);
+
+        // Wildcards can't carry a modifier
+        board.set_modifier(2, 0, Some(TileModifier::TripleWord));
+        assert!(board.get_tile(2, 0).modifier.is_none());
+
+        let engine = GameEngine::new(vec!["cat".to_string()]);
+        let scores = engine
+            .score_answer_group(&board, vec!["cat".to_string()], &BonusRules::default())
+            .unwrap();
+        assert_eq!(scores.total_score(), 16);
+    }
+
     #[test]
     fn test_board_generator_weighted_choice() {
         let generator = BoardGenerator::new();
@@ -1000,7 +1509,11 @@ mod tests {
 
         for test_case in test_cases {
             let engine = GameEngine::new(test_case.words);
-            let result = engine.score_answer_group(&test_case.board, test_case.answers);
+            let result = engine.score_answer_group(
+                &test_case.board,
+                test_case.answers,
+                &BonusRules::default(),
+            );
 
             match (&result, &test_case.expected_result) {
                 (Ok(actual_scores), ExpectedResult::Success { expected_scores }) => {
@@ -1024,7 +1537,7 @@ mod tests {
                     }
                 }
                 (Err(actual_error), ExpectedResult::Error { error_fragment }) => {
-                    assert!(actual_error.contains(error_fragment),
+                    assert!(actual_error.to_string().contains(error_fragment),
                         "Test case '{}': Error message mismatch. Expected to contain '{}', got '{}'. Description: {}",
                         test_case.name, error_fragment, actual_error, test_case.description);
                 }
@@ -1052,7 +1565,7 @@ mod tests {
 
         // Test with valid words that exist on the board
         let answers = vec!["cat".to_string(), "dog".to_string()];
-        let result = engine.score_answer_group(&board, answers.clone());
+        let result = engine.score_answer_group(&board, answers.clone(), &BonusRules::default());
 
         assert!(result.is_ok());
         let scores = result.unwrap();
@@ -1068,11 +1581,60 @@ mod tests {
         }
 
         // Test with empty input
-        let empty_result = engine.score_answer_group(&board, vec![]);
+        let empty_result = engine.score_answer_group(&board, vec![], &BonusRules::default());
         assert!(empty_result.is_ok());
         assert_eq!(empty_result.unwrap().map.len(), 0);
     }
 
+    #[test]
+    fn test_score_answer_group_applies_bonuses() {
+        let engine = GameEngine::new(vec![
+            "cat".to_string(),
+            "ate".to_string(),
+            "oxen".to_string(),
+        ])
+        .with_frequency_corpus(FrequencyCorpus::parse("cat 50\noxen 50\n").unwrap());
+        // c a t e
+        // o * p l
+        // r s * n
+        // d g h k
+        let mut board = Board::new();
+        for (row, letters) in ["cate", "o*pl", "rs*n", "dghk"].iter().enumerate() {
+            for (col, letter) in letters.chars().enumerate() {
+                let points = if letter == '*' { 0 } else { 1 };
+                board.set_tile(row, col, letter, points, letter == '*');
+            }
+        }
+        let answers = vec!["cat".to_string(), "ate".to_string(), "oxen".to_string()];
+        let rules = BonusRules {
+            long_word_length: 3,
+            long_word_bonus: 5,
+            wildcard_multiplier: 2,
+            rare_word_count: 10,
+            rarity_bonus: 3,
+        };
+
+        let plain = engine
+            .score_answer_group(&board, answers.clone(), &BonusRules::default())
+            .unwrap();
+        assert_eq!(plain.map["oxen"], 2);
+        assert_eq!(plain.bonus_score(), 0);
+
+        let scores = engine.score_answer_group(&board, answers, &rules).unwrap();
+        // Nothing for a common short word
+        assert_eq!(scores.map["cat"], 3);
+        assert!(!scores.bonuses.contains_key("cat"));
+        // "ate" isn't in the corpus, so it's rare
+        assert_eq!(scores.map["ate"], 6);
+        assert_eq!(scores.bonuses["ate"], 3);
+        // "oxen" has to go through both wildcards, which doubles it, and it
+        // earns the length bonus too
+        assert_eq!(scores.map["oxen"], 9);
+        assert_eq!(scores.bonuses["oxen"], 7);
+        assert_eq!(scores.total_score(), 18);
+        assert_eq!(scores.bonus_score(), 10);
+    }
+
     #[tokio::test]
     async fn test_game_engine_find_word_paths() {
         let words = create_test_wordlist();
@@ -1112,8 +1674,17 @@ mod tests {
 
         // Test invalid word (not in dictionary)
         let result = engine.validate_answer(&board, "xyz");
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("not found in dictionary"));
+        assert_eq!(
+            result.unwrap_err(),
+            GameError::NotInDictionary {
+                word: "xyz".to_string()
+            }
+        );
+
+        assert_eq!(
+            engine.validate_answer(&board, "").unwrap_err(),
+            GameError::EmptyInput
+        );
     }
 
     #[tokio::test]
@@ -1124,10 +1695,12 @@ mod tests {
 
         // Test word that exists in dictionary but can't be formed on board
         let result = engine.validate_answer(&board, "game");
-        assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .contains("cannot be formed on this board"));
+        assert_eq!(
+            result.unwrap_err(),
+            GameError::NotOnBoard {
+                word: "game".to_string()
+            }
+        );
     }
 
     #[tokio::test]
@@ -1152,6 +1725,35 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_find_all_valid_words_prunes_without_losing_wildcard_words() {
+        // a b c d
+        // e * g h
+        // i j * l
+        // m n o p
+        let mut board = crate::test_utils::create_test_board("abcdefghijklmnop");
+        for (row, col) in [(1, 1), (2, 2)] {
+            let tile = &mut board.rows[row].tiles[col];
+            tile.is_wildcard = true;
+            tile.letter = "*".to_string();
+        }
+
+        // "cab", "bag" and "ajo" each need a wildcard to bridge otherwise
+        // unconnected tiles, "pond" needs both; "zzz" can't be built because
+        // z only comes from wildcards and there are only two of them
+        let engine = GameEngine::new(vec!["cab", "bag", "ajo", "zzz", "mnop", "pond"]);
+        let mut words: Vec<String> = engine
+            .find_all_valid_words(&board)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|answer| answer.word)
+            .collect();
+        words.sort();
+
+        assert_eq!(words, vec!["ajo", "bag", "cab", "mnop", "pond"]);
+    }
+
     #[test]
     fn test_find_words_from_position_length_limit() {
         // This is harder to test without access to private methods, so we'll test behavior indirectly
@@ -1309,6 +1911,123 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_spelling_variants_are_accepted_and_tracked() {
+        let variants = SpellingVariants::parse("grey gray\ncolour color\n").unwrap();
+        let engine = GameEngine::new(vec!["grey", "rye"]).with_spelling_variants(&variants);
+        assert!(engine.is_valid_word_in_dictionary("gray"));
+        assert!(!engine.is_valid_word_in_dictionary("color"));
+        assert_eq!(engine.variant_source("gray"), Some("grey"));
+        assert_eq!(engine.variant_source("grey"), None);
+
+        // gray is also found when solving the board
+        let board = test_utils::create_test_board("grayxxxxxxxxxxxx");
+        let result = engine.validate_api_answer_group(
+            &board,
+            vec![ApiAnswer {
+                word: "gray".to_string(),
+                score: 0,
+                path: None,
+            }],
+        );
+        assert!(result.is_ok(), "{result:?}");
+        assert_eq!(
+            engine.variant_usage(),
+            vec![VariantUsage {
+                variant: "gray".to_string(),
+                source: "grey".to_string(),
+                accepted: 1,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_traced_paths() {
+        use crate::game::board::conflict::WildcardLetters;
+        use crate::http_api::{ApiAnswerPath, ApiPathConstraintSet, ApiPosition};
+
+        let engine = GameEngine::new(vec!["cat", "cot"]);
+        // c*xx
+        // atxx
+        let board = test_utils::create_test_board("c*xxatxxxxxxxxxx");
+        let traced = |word: &str, positions: &[(i32, i32)], constraints| ApiAnswer {
+            word: word.to_string(),
+            score: 0,
+            path: Some(ApiAnswerPath {
+                positions: positions
+                    .iter()
+                    .map(|&(row, col)| ApiPosition { row, col })
+                    .collect(),
+                constraints,
+            }),
+        };
+        let cot = traced(
+            "cot",
+            &[(0, 0), (0, 1), (1, 1)],
+            ApiPathConstraintSet::FirstDecided('o'),
+        );
+
+        let result = engine.validate_api_answer_group(
+            &board,
+            vec![
+                cot.clone(),
+                traced(
+                    "cat",
+                    &[(0, 0), (1, 0), (1, 1)],
+                    ApiPathConstraintSet::Unconstrainted,
+                ),
+            ],
+        );
+        assert!(result.is_ok(), "{result:?}");
+
+        // Tracing cat through the wildcard conflicts with cot
+        let result = engine.validate_api_answer_group(
+            &board,
+            vec![
+                cot.clone(),
+                traced(
+                    "cat",
+                    &[(0, 0), (0, 1), (1, 1)],
+                    ApiPathConstraintSet::FirstDecided('a'),
+                ),
+            ],
+        );
+        let Err(GameError::ConstraintConflict(conflict)) = result else {
+            panic!("expected a conflict, got {result:?}");
+        };
+        assert_eq!(conflict.words, ["cot", "cat"]);
+        assert_eq!(
+            conflict.demands[1].options,
+            [WildcardLetters {
+                first: Some('a'),
+                second: None
+            }]
+        );
+
+        // Positions that don't spell the word, or a wildcard letter that
+        // doesn't fit the path, are rejected
+        for answer in [
+            traced(
+                "cot",
+                &[(0, 0), (1, 0), (1, 1)],
+                ApiPathConstraintSet::Unconstrainted,
+            ),
+            traced(
+                "cot",
+                &[(0, 0), (0, 1), (1, 1)],
+                ApiPathConstraintSet::FirstDecided('a'),
+            ),
+        ] {
+            let result = engine.validate_api_answer_group(&board, vec![answer]);
+            assert_eq!(
+                result.unwrap_err(),
+                GameError::NotOnBoard {
+                    word: "cot".to_string()
+                }
+            );
+        }
+    }
+
     #[tokio::test]
     async fn test_find_best_n_words() {
         let words = create_test_wordlist();
@@ -1316,7 +2035,9 @@ mod tests {
         let board = create_test_board();
 
         // Test finding best 5 words
-        let result = engine.find_best_n_words(&board, 5).await;
+        let result = engine
+            .find_best_n_words(&board, &BonusRules::default(), 5)
+            .await;
         assert!(result.is_ok(), "Should be able to find best 5 words");
 
         let (best_words, metadata) = result.unwrap();
@@ -1381,7 +2102,9 @@ mod tests {
 
         // Test finding different numbers of words
         for n in 1..=10 {
-            let result = engine.find_best_n_words(&board, n).await;
+            let result = engine
+                .find_best_n_words(&board, &BonusRules::default(), n)
+                .await;
             assert!(result.is_ok(), "Should be able to find best {n} words");
 
             let (best_words, metadata) = result.unwrap();
@@ -1462,7 +2185,9 @@ mod tests {
         board.set_tile(3, 3, 'j', 5, false);
 
         // Find optimal words - should handle wildcard conflicts properly
-        let result = engine.find_best_n_words(&board, 5).await;
+        let result = engine
+            .find_best_n_words(&board, &BonusRules::default(), 5)
+            .await;
         assert!(
             result.is_ok(),
             "Should handle wildcard conflicts and find valid words"
@@ -1533,7 +2258,9 @@ mod tests {
         board.set_tile(3, 3, 'k', 3, false);
 
         // Find optimal 5 words
-        let result = engine.find_best_n_words(&board, 5).await;
+        let result = engine
+            .find_best_n_words(&board, &BonusRules::default(), 5)
+            .await;
         assert!(
             result.is_ok(),
             "Should find optimal combination even if it means skipping top word"
@@ -1616,7 +2343,9 @@ mod tests {
 
         // Test different numbers of words to see backtracking behavior
         for n in 1..=8 {
-            let result = engine.find_best_n_words(&board, n).await;
+            let result = engine
+                .find_best_n_words(&board, &BonusRules::default(), n)
+                .await;
             assert!(result.is_ok(), "Should handle backtracking for n={n}");
 
             let (best_words, metadata) = result.unwrap();
@@ -1689,7 +2418,9 @@ mod tests {
         ];
 
         for (n, description) in test_cases {
-            let result = engine.find_best_n_words(&board, n).await;
+            let result = engine
+                .find_best_n_words(&board, &BonusRules::default(), n)
+                .await;
             assert!(result.is_ok(), "Should handle {description} scenario");
 
             let (best_words, metadata) = result.unwrap();
@@ -1774,7 +2505,9 @@ mod tests {
 
         // Test the optimization with different numbers of words
         for n in 1..=10 {
-            let result = engine.find_best_n_words(&board, n).await;
+            let result = engine
+                .find_best_n_words(&board, &BonusRules::default(), n)
+                .await;
             assert!(result.is_ok(), "Should find optimal selection for n={n}");
 
             let (best_words, metadata) = result.unwrap();
@@ -1803,7 +2536,9 @@ mod tests {
         }
 
         // Also test the scenario where we get more words than requested
-        let result = engine.find_best_n_words(&board, 100).await;
+        let result = engine
+            .find_best_n_words(&board, &BonusRules::default(), 100)
+            .await;
         assert!(
             result.is_ok(),
             "Should handle request for more words than available"
@@ -1861,7 +2596,9 @@ mod tests {
         board.set_tile(3, 3, 'j', 5, false);
 
         // Test with n=2 where backtracking might find a better combination
-        let result = engine.find_best_n_words(&board, 2).await;
+        let result = engine
+            .find_best_n_words(&board, &BonusRules::default(), 2)
+            .await;
         assert!(result.is_ok(), "Should find optimal combination");
 
         let (best_words, metadata) = result.unwrap();
@@ -1911,6 +2648,143 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_game_rules_decide_the_words_found() {
+        let board = test_utils::create_test_board("catsx*xxxx*xxxxx");
+        let words = |answers: Vec<board::answer::Answer>| {
+            answers.into_iter().map(|a| a.word).collect::<Vec<_>>()
+        };
+
+        let engine = GameEngine::new(vec!["cat", "cats", "cot"]);
+        let answers = engine.find_all_valid_words(&board).await.unwrap();
+        assert_eq!(words(answers), vec!["cat", "cats", "cot"]);
+
+        let engine = engine.with_game_rules(GameRules {
+            min_word_len: 4,
+            ..GameRules::default()
+        });
+        let answers = engine.find_all_valid_words(&board).await.unwrap();
+        assert_eq!(words(answers), vec!["cats"]);
+    }
+
+    #[tokio::test]
+    async fn test_best_finish_keeps_to_the_answers_wildcards() {
+        let engine = GameEngine::new(vec!["cats", "cot", "cut"]);
+        // c a t s
+        // x * x x
+        // x x * x
+        let board = test_utils::create_test_board("catsx*xxxx*xxxxx");
+        let candidates = engine.find_all_valid_words(&board).await.unwrap();
+        let best_words = |finish: &BestFinish| {
+            let mut words: Vec<String> = finish.words.iter().map(|a| a.word.clone()).collect();
+            words.sort();
+            words
+        };
+
+        let finish = engine
+            .best_finish(&board, &BonusRules::default(), vec![], &candidates, 2, None)
+            .unwrap();
+        assert_eq!(finish.current_score, 0);
+        assert_eq!(finish.total_score, 7);
+        // cats with either of cot or cut
+        assert_eq!(best_words(&finish).len(), 2);
+        assert_eq!(best_words(&finish)[0], "cats");
+        assert!(finish.complete);
+
+        // Past its deadline the search settles for what it has
+        let finish = engine
+            .best_finish(
+                &board,
+                &BonusRules::default(),
+                vec![],
+                &candidates,
+                2,
+                Some(Instant::now()),
+            )
+            .unwrap();
+        assert!(!finish.complete);
+
+        // Once cot has the first wildcard as o, cut no longer fits
+        let finish = engine
+            .best_finish(
+                &board,
+                &BonusRules::default(),
+                vec!["cot".to_string()],
+                &candidates,
+                2,
+                None,
+            )
+            .unwrap();
+        assert_eq!(finish.current_score, 3);
+        assert_eq!(finish.total_score, 7);
+        assert_eq!(best_words(&finish), vec!["cats"]);
+
+        let err = engine
+            .best_finish(
+                &board,
+                &BonusRules::default(),
+                vec!["cot".to_string(), "cut".to_string()],
+                &candidates,
+                2,
+                None,
+            )
+            .unwrap_err();
+        assert!(matches!(err, GameError::ConstraintConflict(_)));
+    }
+
+    #[tokio::test]
+    async fn test_best_words_earn_their_bonuses() {
+        let engine = GameEngine::new(vec!["cats", "jab"])
+            .with_frequency_corpus(FrequencyCorpus::parse("cats 50\n").unwrap());
+        // c a t s
+        // j a b x
+        let board = test_utils::create_test_board("catsjabxxxxxxxxx");
+        let candidates = engine.find_all_valid_words(&board).await.unwrap();
+        let best_word = |bonus_rules: &BonusRules| {
+            let (words, metadata) = engine
+                .find_best_n_words_from_answers(&candidates, bonus_rules, 1)
+                .unwrap();
+            (words[0].word.clone(), metadata.total_score)
+        };
+
+        assert_eq!(best_word(&BonusRules::default()), ("cats".to_string(), 4));
+
+        // jab is rare enough for its bonus to put it ahead
+        let bonus_rules = BonusRules {
+            rare_word_count: 10,
+            rarity_bonus: 5,
+            ..BonusRules::default()
+        };
+        assert_eq!(best_word(&bonus_rules), ("jab".to_string(), 8));
+
+        let finish = engine
+            .best_finish(&board, &bonus_rules, vec![], &candidates, 1, None)
+            .unwrap();
+        assert_eq!(finish.total_score, 8);
+    }
+
+    #[tokio::test]
+    async fn test_spent_budget_falls_back_to_greedy() {
+        let engine = GameEngine::new(create_test_wordlist());
+        let board = create_test_board();
+        let mut answers = engine.find_all_valid_words(&board).await.unwrap();
+        answers.sort_by_key(|a| Reverse(a.score()));
+        let greedy = engine.greedy_selection(&answers, 3).unwrap();
+
+        let (words, metadata) = engine
+            .clone()
+            .with_optimization_budget(Some(Duration::ZERO))
+            .find_best_n_words_from_answers(&answers, &BonusRules::default(), 3)
+            .unwrap();
+        assert!(!metadata.complete);
+        assert_eq!(words, greedy);
+
+        let (_, metadata) = engine
+            .find_best_n_words_from_answers(&answers, &BonusRules::default(), 3)
+            .unwrap();
+        assert!(metadata.complete);
+    }
+
     #[tokio::test]
     async fn test_force_constraint_conflict_resolution() {
         // Create a scenario that definitely requires constraint conflict resolution
@@ -1953,7 +2827,9 @@ mod tests {
 
         // Test with increasing values of n to stress the constraint system
         for n in 1..=5 {
-            let result = engine.find_best_n_words(&board, n).await;
+            let result = engine
+                .find_best_n_words(&board, &BonusRules::default(), n)
+                .await;
             assert!(
                 result.is_ok(),
                 "Should resolve constraint conflicts for n={n}"
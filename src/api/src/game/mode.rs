@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// GameMode is the kind of game a board is played as. Each mode has its own
+/// board and sequence of puzzle numbers per date.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GameMode {
+    /// The daily puzzle, played at any pace until the day ends
+    #[default]
+    Daily,
+    /// A separate board per date that must be submitted within a time limit
+    /// of the player first opening it
+    Blitz,
+}
+
+impl GameMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GameMode::Daily => "daily",
+            GameMode::Blitz => "blitz",
+        }
+    }
+}
+
+impl fmt::Display for GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for GameMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "daily" => Ok(GameMode::Daily),
+            "blitz" => Ok(GameMode::Blitz),
+            _ => Err(format!("unknown game mode: {s}")),
+        }
+    }
+}
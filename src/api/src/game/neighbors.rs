@@ -0,0 +1,139 @@
+use super::board::Board;
+use super::trie::letter_bit;
+
+/// Every letter a wildcard tile can stand in for
+pub const ALL_LETTERS: u32 = (1 << 26) - 1;
+
+/// NeighborLetters precomputes, for every cell of a board, the letters that
+/// cell can supply and the cells adjacent to it under the board's adjacency. The word search
+/// uses it to check whether any continuation of a prefix is reachable from the
+/// current cell before descending into it.
+///
+/// Visited cells are tracked as a bitmask with bit `row * width + col` set
+/// for each visited cell, which covers boards of up to 32 cells.
+pub struct NeighborLetters {
+    width: usize,
+    cell_letters: Vec<u32>,
+    neighbors: Vec<Vec<(usize, usize)>>,
+    neighbor_letters: Vec<u32>,
+}
+
+impl NeighborLetters {
+    pub fn new(board: &Board) -> Self {
+        let height = board.rows.len();
+        let width = board.rows.first().map(|row| row.tiles.len()).unwrap_or(0);
+        debug_assert!(width * height <= 32, "visited bitmask holds 32 cells");
+
+        let cell_letters: Vec<u32> = board
+            .rows
+            .iter()
+            .flat_map(|row| row.tiles.iter())
+            .map(|tile| {
+                if tile.is_wildcard {
+                    ALL_LETTERS
+                } else {
+                    tile.letter.chars().next().map(letter_bit).unwrap_or(0)
+                }
+            })
+            .collect();
+
+        let mut neighbors = Vec::with_capacity(width * height);
+        let mut neighbor_letters = Vec::with_capacity(width * height);
+        for row in 0..height {
+            for col in 0..width {
+                let adjacent: Vec<(usize, usize)> = board.neighbors(row, col).collect();
+
+                neighbor_letters.push(
+                    adjacent
+                        .iter()
+                        .fold(0, |mask, (r, c)| mask | cell_letters[r * width + c]),
+                );
+                neighbors.push(adjacent);
+            }
+        }
+
+        Self {
+            width,
+            cell_letters,
+            neighbors,
+            neighbor_letters,
+        }
+    }
+
+    /// letters returns the letters the cell can supply (all of them for a wildcard)
+    pub fn letters(&self, row: usize, col: usize) -> u32 {
+        self.cell_letters[row * self.width + col]
+    }
+
+    /// cell_bit returns the bit representing (row, col) in a visited bitmask
+    pub fn cell_bit(&self, row: usize, col: usize) -> u32 {
+        1 << (row * self.width + col)
+    }
+
+    /// neighbors returns the in-bounds cells adjacent to (row, col)
+    pub fn neighbors(&self, row: usize, col: usize) -> &[(usize, usize)] {
+        &self.neighbors[row * self.width + col]
+    }
+
+    /// reachable_letters returns the letters that some unvisited neighbor of
+    /// (row, col) can supply
+    pub fn reachable_letters(&self, row: usize, col: usize, visited: u32) -> u32 {
+        let index = row * self.width + col;
+        if visited == 0 {
+            return self.neighbor_letters[index];
+        }
+
+        self.neighbors[index]
+            .iter()
+            .filter(|(r, c)| visited & self.cell_bit(*r, *c) == 0)
+            .fold(0, |mask, (r, c)| mask | self.letters(*r, *c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_test_board;
+
+    #[test]
+    fn test_neighbors_respect_bounds() {
+        let board = create_test_board("abcdefghijklmnop");
+        let neighbors = NeighborLetters::new(&board);
+
+        assert_eq!(neighbors.neighbors(0, 0).len(), 3);
+        assert_eq!(neighbors.neighbors(0, 1).len(), 5);
+        assert_eq!(neighbors.neighbors(1, 1).len(), 8);
+        assert_eq!(neighbors.neighbors(3, 3).len(), 3);
+    }
+
+    #[test]
+    fn test_reachable_letters_excludes_visited() {
+        // a b c d
+        // e f g h
+        // i j k l
+        // m n o p
+        let board = create_test_board("abcdefghijklmnop");
+        let neighbors = NeighborLetters::new(&board);
+
+        assert_eq!(
+            neighbors.reachable_letters(0, 0, 0),
+            letter_bit('b') | letter_bit('e') | letter_bit('f')
+        );
+
+        let visited = neighbors.cell_bit(0, 0) | neighbors.cell_bit(0, 1);
+        assert_eq!(
+            neighbors.reachable_letters(0, 0, visited),
+            letter_bit('e') | letter_bit('f')
+        );
+    }
+
+    #[test]
+    fn test_wildcard_supplies_every_letter() {
+        let mut board = create_test_board("abcdefghijklmnop");
+        board.rows[0].tiles[1].is_wildcard = true;
+        let neighbors = NeighborLetters::new(&board);
+
+        assert_eq!(neighbors.letters(0, 1), ALL_LETTERS);
+        assert_eq!(neighbors.reachable_letters(0, 0, 0), ALL_LETTERS);
+    }
+}
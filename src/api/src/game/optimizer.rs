@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+use std::env;
+use std::time::{Duration, Instant};
+
+use super::board::{
+    answer::Answer,
+    constraints::{AnswerGroupConstraintSet, PathConstraintSet},
+};
+
+/// Constraint states remembered in one search. Past this the search carries
+/// on without remembering more, so memory stays bounded on wordy boards.
+const MEMO_LIMIT: usize = 1 << 20;
+
+/// Nodes visited between looks at the clock
+const DEADLINE_CHECK_INTERVAL: u64 = 1024;
+
+/// The outcome of a search
+pub(crate) struct Selection {
+    /// Indices of the chosen answers in ascending order, or None when no set
+    /// of n compatible answers beats the incumbent
+    pub indices: Option<Vec<usize>>,
+    /// False when the deadline stopped the search, so a better set may exist
+    pub complete: bool,
+}
+
+/// BranchAndBound finds the n answers with the highest total score that can
+/// all be formed together, each answer scoring what `scores` gives it at the
+/// same index. Answers must be sorted by descending score, which
+/// makes the next k answers from any position the best any k can do; a branch
+/// whose score plus that bound can't beat the best set found so far is cut.
+/// Branches that reach the same position with the same wildcard constraints
+/// and the same number of answers still to choose have the same futures, so
+/// only the first to get there with the higher score is explored.
+pub(crate) struct BranchAndBound<'a> {
+    answers: &'a [Answer],
+    scores: &'a [i32],
+    n: usize,
+    /// prefix[i] is the total score of answers[..i]
+    prefix: Vec<i32>,
+    deadline: Option<Instant>,
+    /// Highest score seen on reaching (position, answers still wanted,
+    /// constraints)
+    memo: HashMap<(usize, usize, Vec<PathConstraintSet>), i32>,
+    current: Vec<usize>,
+    best_score: Option<i32>,
+    best: Option<Vec<usize>>,
+    nodes: u64,
+    timed_out: bool,
+}
+
+impl<'a> BranchAndBound<'a> {
+    pub(crate) fn new(sorted_answers: &'a [Answer], scores: &'a [i32], n: usize) -> Self {
+        let mut prefix = Vec::with_capacity(scores.len() + 1);
+        prefix.push(0);
+        for score in scores {
+            prefix.push(prefix.last().unwrap() + score);
+        }
+        Self {
+            answers: sorted_answers,
+            scores,
+            n,
+            prefix,
+            deadline: None,
+            memo: HashMap::new(),
+            current: Vec::with_capacity(n),
+            best_score: None,
+            best: None,
+            nodes: 0,
+            timed_out: false,
+        }
+    }
+
+    /// with_incumbent starts the search from a set already known to score
+    /// `score`, so only strictly better sets are returned
+    pub(crate) fn with_incumbent(mut self, score: i32) -> Self {
+        self.best_score = Some(score);
+        self
+    }
+
+    /// with_deadline stops the search at `deadline`, keeping the best set
+    /// found by then
+    pub(crate) fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    pub(crate) fn run(mut self) -> Selection {
+        if self.n > 0 {
+            self.search(0, None, 0);
+        }
+        Selection {
+            indices: self.best,
+            complete: !self.timed_out,
+        }
+    }
+
+    fn search(&mut self, start: usize, agreed: Option<AnswerGroupConstraintSet>, score: i32) {
+        if self.current.len() == self.n {
+            if self.best_score.is_none_or(|best| score > best) {
+                self.best_score = Some(score);
+                self.best = Some(self.current.clone());
+            }
+            return;
+        }
+
+        if self.nodes.is_multiple_of(DEADLINE_CHECK_INTERVAL)
+            && self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            self.timed_out = true;
+        }
+        self.nodes += 1;
+        if self.timed_out {
+            return;
+        }
+
+        let wanted = self.n - self.current.len();
+        if let Some(agreed) = &agreed {
+            let key = (start, wanted, agreed.path_constraint_sets.clone());
+            let full = self.memo.len() >= MEMO_LIMIT;
+            match self.memo.get_mut(&key) {
+                Some(seen) if *seen >= score => return,
+                Some(seen) => *seen = score,
+                None if !full => {
+                    self.memo.insert(key, score);
+                }
+                None => {}
+            }
+        }
+
+        for i in start..self.answers.len() {
+            if self.answers.len() - i < wanted {
+                break;
+            }
+            // The bound only shrinks from here on, so nothing later can win
+            let bound = self.prefix[(i + wanted).min(self.answers.len())] - self.prefix[i];
+            if self.best_score.is_some_and(|best| score + bound <= best) {
+                break;
+            }
+
+            let constraints = self.answers[i].constraints_set.clone();
+            let next = match &agreed {
+                None => Some(constraints),
+                Some(agreed) => agreed.intersection(constraints).ok(),
+            };
+            let Some(next) = next else {
+                continue;
+            };
+
+            self.current.push(i);
+            self.search(i + 1, Some(canonical(next)), score + self.scores[i]);
+            self.current.pop();
+            if self.timed_out {
+                return;
+            }
+        }
+    }
+}
+
+/// Sort and dedup a constraint set so equal states compare equal
+fn canonical(mut set: AnswerGroupConstraintSet) -> AnswerGroupConstraintSet {
+    set.path_constraint_sets.sort_unstable();
+    set.path_constraint_sets.dedup();
+    set
+}
+
+/// optimization_budget_from_env reads OPTIMIZATION_BUDGET_MS, how long the
+/// search for a board's best words may run before settling for the best set
+/// found so far. Unset, the search always runs to the end.
+pub fn optimization_budget_from_env() -> Option<Duration> {
+    env::var("OPTIMIZATION_BUDGET_MS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .map(Duration::from_millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{solver::Solver, BoardGenerator, Dawg};
+    use rand::SeedableRng;
+    use std::cmp::Reverse;
+
+    /// Every set of n compatible answers, scored, by brute force
+    fn brute_force_best(answers: &[Answer], n: usize) -> Option<i32> {
+        fn walk(
+            answers: &[Answer],
+            n: usize,
+            start: usize,
+            chosen: &mut Vec<Answer>,
+        ) -> Option<i32> {
+            if chosen.len() == n {
+                return Some(chosen.iter().map(|a| a.score()).sum());
+            }
+            let mut best = None;
+            for i in start..answers.len() {
+                chosen.push(answers[i].clone());
+                if AnswerGroupConstraintSet::is_valid_set(chosen.clone()) {
+                    best = best.max(walk(answers, n, i + 1, chosen));
+                }
+                chosen.pop();
+            }
+            best
+        }
+        walk(answers, n, 0, &mut Vec::new())
+    }
+
+    fn scores(answers: &[Answer]) -> Vec<i32> {
+        answers.iter().map(Answer::score).collect()
+    }
+
+    fn sorted_answers(seed: u64) -> Vec<Answer> {
+        const WORDS: [&str; 24] = [
+            "are", "area", "ate", "eat", "ear", "era", "net", "nest", "rat", "rate", "sea", "seat",
+            "set", "star", "stare", "tar", "tea", "tear", "ten", "tone", "one", "note", "rest",
+            "nose",
+        ];
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        let board = BoardGenerator::new().generate_board(&mut rng);
+        let mut answers = Solver::new(&board).solve(&Dawg::from(WORDS.to_vec()));
+        answers.sort_by_key(|a| Reverse(a.score()));
+        answers
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        for seed in 0..8 {
+            let answers = sorted_answers(seed);
+            for n in 1..=4 {
+                let selection = BranchAndBound::new(&answers, &scores(&answers), n).run();
+                assert!(selection.complete);
+                let found = selection.indices.map(|indices| {
+                    let chosen: Vec<Answer> = indices.iter().map(|&i| answers[i].clone()).collect();
+                    assert!(AnswerGroupConstraintSet::is_valid_set(chosen.clone()));
+                    chosen.iter().map(|a| a.score()).sum::<i32>()
+                });
+                assert_eq!(found, brute_force_best(&answers, n), "seed {seed} n {n}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_brute_force_when_sets_of_different_sizes_agree() {
+        // Every word can be traced around the wildcards as well as through
+        // them, so sets of one, two and three answers reach the same
+        // positions with the same constraints
+        const WORDS: [&str; 8] = ["rat", "star", "sat", "set", "tea", "eat", "seat", "east"];
+        let board = crate::test_utils::create_test_board("rats*teae*stsate");
+        let mut answers = Solver::new(&board).solve(&Dawg::from(WORDS.to_vec()));
+        answers.sort_by_key(|a| Reverse(a.score()));
+
+        for n in 1..=4 {
+            let selection = BranchAndBound::new(&answers, &scores(&answers), n).run();
+            let found = selection
+                .indices
+                .map(|indices| indices.iter().map(|&i| answers[i].score()).sum::<i32>());
+            assert_eq!(found, brute_force_best(&answers, n), "n {n}");
+        }
+    }
+
+    #[test]
+    fn test_incumbent_is_only_replaced_by_better() {
+        let answers = sorted_answers(1);
+        let best = brute_force_best(&answers, 3).unwrap();
+
+        let tied = BranchAndBound::new(&answers, &scores(&answers), 3)
+            .with_incumbent(best)
+            .run();
+        assert!(tied.indices.is_none());
+
+        let selection = BranchAndBound::new(&answers, &scores(&answers), 3)
+            .with_incumbent(best - 1)
+            .run();
+        assert!(selection.indices.is_some());
+    }
+
+    #[test]
+    fn test_passed_deadline_stops_the_search() {
+        let answers = sorted_answers(2);
+        let selection = BranchAndBound::new(&answers, &scores(&answers), 3)
+            .with_deadline(Some(Instant::now()))
+            .run();
+        assert!(!selection.complete);
+        assert!(selection.indices.is_none());
+
+        let selection = BranchAndBound::new(&answers, &scores(&answers), 3)
+            .with_deadline(Some(Instant::now() + Duration::from_secs(60)))
+            .run();
+        assert!(selection.complete);
+    }
+}
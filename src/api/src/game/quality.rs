@@ -0,0 +1,273 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::game::{board::answer::Answer, Board};
+
+/// Words at least this many letters long are the long words a board needs
+pub const LONG_WORD_LENGTH: usize = 6;
+
+/// Boards with fewer long words than this have little to reward searching
+const MIN_LONG_WORDS: i32 = 3;
+
+/// Boards whose letter tiles are outside this share of vowels are lopsided:
+/// too few and little can be spelled, too many and words run short
+const MIN_VOWEL_SHARE: f64 = 0.2;
+const MAX_VOWEL_SHARE: f64 = 0.6;
+
+const VOWELS: [char; 5] = ['a', 'e', 'i', 'o', 'u'];
+
+/// A reason a board plays badly, whatever its best words score
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityIssue {
+    /// Fewer than MIN_LONG_WORDS words of LONG_WORD_LENGTH letters or more
+    FewLongWords,
+    /// A corner tile no valid word passes through
+    DeadCorners,
+    /// A wildcard no valid word passes through
+    UnusedWildcards,
+    TooFewVowels,
+    TooManyVowels,
+}
+
+impl QualityIssue {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QualityIssue::FewLongWords => "few_long_words",
+            QualityIssue::DeadCorners => "dead_corners",
+            QualityIssue::UnusedWildcards => "unused_wildcards",
+            QualityIssue::TooFewVowels => "too_few_vowels",
+            QualityIssue::TooManyVowels => "too_many_vowels",
+        }
+    }
+}
+
+impl fmt::Display for QualityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for QualityIssue {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "few_long_words" => Ok(QualityIssue::FewLongWords),
+            "dead_corners" => Ok(QualityIssue::DeadCorners),
+            "unused_wildcards" => Ok(QualityIssue::UnusedWildcards),
+            "too_few_vowels" => Ok(QualityIssue::TooFewVowels),
+            "too_many_vowels" => Ok(QualityIssue::TooManyVowels),
+            _ => Err(format!("unknown quality issue: {s}")),
+        }
+    }
+}
+
+/// BoardQuality measures what makes a board good to play beyond the score of
+/// its best words, and names what's wrong with it. It is recorded when a
+/// game is generated so generation can be tuned against it later.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BoardQuality {
+    /// Valid words of LONG_WORD_LENGTH letters or more
+    pub long_words: i32,
+    /// Letter tiles no valid word passes through
+    pub dead_tiles: i32,
+    /// Corner tiles no valid word passes through
+    pub dead_corners: i32,
+    /// Wildcards no valid word passes through
+    pub unused_wildcards: i32,
+    /// Share of the letter tiles, 0 to 1, that are vowels
+    pub vowel_share: f64,
+    pub issues: Vec<QualityIssue>,
+}
+
+impl BoardQuality {
+    /// analyze measures `board` from every valid answer on it
+    pub fn analyze(board: &Board, answers: &[Answer]) -> Self {
+        let used: HashSet<(i32, i32)> = answers
+            .iter()
+            .flat_map(|answer| &answer.paths)
+            .flat_map(|path| &path.tiles)
+            .map(|tile| (tile.row, tile.col))
+            .collect();
+        let last_row = board.rows.len() as i32 - 1;
+        let last_col = board.rows.first().map_or(0, |row| row.tiles.len()) as i32 - 1;
+
+        let mut quality = Self {
+            long_words: answers
+                .iter()
+                .filter(|answer| answer.word.chars().count() >= LONG_WORD_LENGTH)
+                .count() as i32,
+            ..Self::default()
+        };
+        let (mut letter_tiles, mut vowels) = (0, 0);
+        for tile in board.rows.iter().flat_map(|row| &row.tiles) {
+            let dead = !used.contains(&(tile.row, tile.col));
+            let corner =
+                (tile.row == 0 || tile.row == last_row) && (tile.col == 0 || tile.col == last_col);
+            if dead && corner {
+                quality.dead_corners += 1;
+            }
+            if tile.is_wildcard {
+                quality.unused_wildcards += dead as i32;
+                continue;
+            }
+            quality.dead_tiles += dead as i32;
+            letter_tiles += 1;
+            // A tile showing several letters, e.g. qu, sounds like its first
+            if tile.letter.starts_with(VOWELS) {
+                vowels += 1;
+            }
+        }
+        if letter_tiles > 0 {
+            quality.vowel_share = vowels as f64 / letter_tiles as f64;
+        }
+
+        quality.issues = [
+            (
+                quality.long_words < MIN_LONG_WORDS,
+                QualityIssue::FewLongWords,
+            ),
+            (quality.dead_corners > 0, QualityIssue::DeadCorners),
+            (quality.unused_wildcards > 0, QualityIssue::UnusedWildcards),
+            (
+                quality.vowel_share < MIN_VOWEL_SHARE,
+                QualityIssue::TooFewVowels,
+            ),
+            (
+                quality.vowel_share > MAX_VOWEL_SHARE,
+                QualityIssue::TooManyVowels,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(found, issue)| found.then_some(issue))
+        .collect();
+        quality
+    }
+
+    pub fn has_issues(&self) -> bool {
+        !self.issues.is_empty()
+    }
+}
+
+/// BoardRejection is why a generated board was turned down: its best words
+/// missed the threshold, along with what else is wrong with it
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardRejection {
+    pub optimal_score: i32,
+    pub threshold_score: i32,
+    pub quality: BoardQuality,
+}
+
+impl fmt::Display for BoardRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Board quality insufficient: optimal 5 words sum to {} (threshold: {})",
+            self.optimal_score, self.threshold_score
+        )?;
+        if self.quality.has_issues() {
+            write!(f, "; {}", join_issues(&self.quality.issues))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for BoardRejection {}
+
+/// join_issues lists issues for logs and storage, e.g.
+/// `few_long_words,dead_corners`
+pub fn join_issues(issues: &[QualityIssue]) -> String {
+    issues
+        .iter()
+        .map(QualityIssue::as_str)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// split_issues reads issues written by `join_issues`, skipping any this
+/// build doesn't know
+pub fn split_issues(issues: &str) -> Vec<QualityIssue> {
+    issues
+        .split(',')
+        .filter_map(|issue| issue.parse().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::solver::Solver;
+    use crate::game::Dawg;
+    use crate::test_utils::create_test_board;
+
+    #[test]
+    fn test_analyze_names_what_is_wrong() {
+        // c a t s
+        // a * e t
+        // t e a s
+        // s t a c
+        let board = create_test_board("catsa*etteasstac");
+        let dictionary = Dawg::from(vec!["cat", "cats", "eat", "tea"]);
+        let answers = Solver::new(&board).solve(&dictionary);
+
+        // Every tile is on the path of some word, but none is long
+        let quality = BoardQuality::analyze(&board, &answers);
+        assert_eq!(quality.long_words, 0);
+        assert_eq!(quality.dead_tiles, 0);
+        assert_eq!(quality.vowel_share, 6.0 / 15.0);
+        assert_eq!(quality.issues, [QualityIssue::FewLongWords]);
+
+        // With no words the corners and the wildcard go unused
+        let quality = BoardQuality::analyze(&board, &[]);
+        assert_eq!(quality.dead_tiles, 15);
+        assert_eq!(quality.dead_corners, 4);
+        assert_eq!(quality.unused_wildcards, 1);
+        assert_eq!(
+            quality.issues,
+            [
+                QualityIssue::FewLongWords,
+                QualityIssue::DeadCorners,
+                QualityIssue::UnusedWildcards
+            ]
+        );
+
+        // Measured against the same paths, only the letters differ
+        let vowels = create_test_board("aeioua*eioueaioa");
+        assert_eq!(
+            BoardQuality::analyze(&vowels, &answers).issues,
+            [QualityIssue::FewLongWords, QualityIssue::TooManyVowels]
+        );
+    }
+
+    #[test]
+    fn test_issues_round_trip() {
+        let issues = [QualityIssue::FewLongWords, QualityIssue::TooManyVowels];
+        let joined = join_issues(&issues);
+        assert_eq!(joined, "few_long_words,too_many_vowels");
+        assert_eq!(split_issues(&joined), issues);
+        assert_eq!(split_issues(""), []);
+        assert_eq!(
+            split_issues("few_long_words,gone"),
+            [QualityIssue::FewLongWords]
+        );
+    }
+
+    #[test]
+    fn test_rejections_explain_themselves() {
+        let rejection = BoardRejection {
+            optimal_score: 20,
+            threshold_score: 40,
+            quality: BoardQuality {
+                issues: vec![QualityIssue::TooFewVowels],
+                ..BoardQuality::default()
+            },
+        };
+        assert_eq!(
+            rejection.to_string(),
+            "Board quality insufficient: optimal 5 words sum to 20 (threshold: 40); too_few_vowels"
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+use std::env;
+
+use serde::{Deserialize, Serialize};
+
+use super::error::GameError;
+use super::letters;
+use super::solver::MIN_WORD_LENGTH;
+
+/// How many words an entry holds unless its game says otherwise
+pub const DEFAULT_MAX_ANSWERS: usize = 5;
+
+/// GameRules are which lists of answers a game takes: how short a word may
+/// be, how many words make an entry and whether a word may be played twice.
+/// Each game stores the rules it was generated with, so a special event
+/// puzzle can be played differently; games stored without any use the
+/// defaults every daily game has been played under.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct GameRules {
+    /// Fewest letters an answer can have
+    pub min_word_len: usize,
+    /// Most answers an entry can hold, and how many make the optimal set
+    pub max_answers: usize,
+    /// Whether the same word can be played more than once
+    pub allow_repeats: bool,
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        Self {
+            min_word_len: MIN_WORD_LENGTH,
+            max_answers: DEFAULT_MAX_ANSWERS,
+            allow_repeats: false,
+        }
+    }
+}
+
+impl GameRules {
+    /// from_env reads GAME_MIN_WORD_LEN, GAME_MAX_ANSWERS and
+    /// GAME_ALLOW_REPEATS, keeping the default for any that aren't set
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            env::var(name).ok().and_then(|value| value.parse().ok())
+        }
+
+        let defaults = Self::default();
+        Self {
+            min_word_len: var("GAME_MIN_WORD_LEN")
+                .filter(|&len| len > 0)
+                .unwrap_or(defaults.min_word_len),
+            max_answers: var("GAME_MAX_ANSWERS")
+                .filter(|&max| max > 0)
+                .unwrap_or(defaults.max_answers),
+            allow_repeats: var("GAME_ALLOW_REPEATS").unwrap_or(defaults.allow_repeats),
+        }
+    }
+
+    /// Whether these are the rules of a game stored without any
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// check_answers rejects a list of answers the rules don't allow: too
+    /// many of them, a word too short, or a word played twice when repeats
+    /// aren't allowed
+    pub fn check_answers<'a>(
+        &self,
+        words: impl IntoIterator<Item = &'a str>,
+    ) -> Result<(), GameError> {
+        let mut seen = HashSet::new();
+        for (i, word) in words.into_iter().enumerate() {
+            if i >= self.max_answers {
+                return Err(GameError::TooManyAnswers {
+                    max_answers: self.max_answers,
+                });
+            }
+            if letters::letter_count(word) < self.min_word_len {
+                return Err(GameError::TooShort {
+                    word: word.to_string(),
+                    min_word_len: self.min_word_len,
+                });
+            }
+            if !self.allow_repeats && !seen.insert(word) {
+                return Err(GameError::Repeated {
+                    word: word.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_answers() {
+        let rules = GameRules::default();
+        assert!(rules.check_answers(["cat", "dog", "tea"]).is_ok());
+        assert_eq!(
+            rules.check_answers(["cat", "at"]),
+            Err(GameError::TooShort {
+                word: "at".to_string(),
+                min_word_len: 3,
+            })
+        );
+        assert_eq!(
+            rules.check_answers(["cat", "dog", "cat"]),
+            Err(GameError::Repeated {
+                word: "cat".to_string()
+            })
+        );
+        assert_eq!(
+            rules.check_answers(["one", "two", "six", "ten", "tea", "cat"]),
+            Err(GameError::TooManyAnswers { max_answers: 5 })
+        );
+
+        let event = GameRules {
+            min_word_len: 2,
+            max_answers: 8,
+            allow_repeats: true,
+        };
+        assert!(event
+            .check_answers(["at", "cat", "cat", "one", "two", "six", "ten", "tea"])
+            .is_ok());
+    }
+
+    #[test]
+    fn test_stored_rules_fill_in_defaults() {
+        let rules: GameRules = serde_json::from_str(r#"{"max_answers":8}"#).unwrap();
+        assert_eq!(
+            rules,
+            GameRules {
+                max_answers: 8,
+                ..GameRules::default()
+            }
+        );
+        assert!(GameRules::default().is_default());
+        assert!(!rules.is_default());
+    }
+}
@@ -1,5 +1,14 @@
+use anyhow::{anyhow, bail, Context, Result};
 use phf::phf_map;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::path::Path;
+use tracing::warn;
+
+use crate::game::board::answer::Answer;
+use crate::game::language::Language;
+use crate::wordlist::FrequencyCorpus;
 
 static LETTER_FREQUENCIES: phf::Map<char, f64> = phf_map! {
     'a' => 0.078,
@@ -39,8 +48,378 @@ pub fn points_for_letter(letter: char) -> i32 {
     ((e_freq / letter_freq).log2().floor() as i32) + 1
 }
 
+/// points_for_tile is what a tile showing `letters` is worth with the
+/// standard points. A tile showing several letters, e.g. `qu`, is still one
+/// tile, so it's worth its best letter once rather than each letter.
+pub fn points_for_tile(letters: &str) -> i32 {
+    letters.chars().map(points_for_letter).max().unwrap_or(0)
+}
+
+/// Id of the built-in scoring scheme every board was scored with before
+/// schemes were versioned
+pub const STANDARD_SCORING_SCHEME: &str = "standard";
+pub const STANDARD_SCORING_SCHEME_VERSION: i32 = 1;
+
+/// Where further scoring schemes are defined unless SCORING_SCHEMES_PATH says
+/// otherwise
+pub const SCORING_SCHEMES_PATH: &str = "scoring.toml";
+
+/// ScoringScheme is the table of points a board's letters are worth. Each
+/// game stores the version its tiles were scored with, and a version's table
+/// never changes once games use it, so rebalancing means adding a version.
+/// Clients fetch a game's scheme by id to show point values without
+/// hardcoding them.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScoringScheme {
+    pub id: String,
+    pub version: i32,
+    pub letter_points: BTreeMap<char, i32>,
+    pub wildcard_points: i32,
+}
+
+impl ScoringScheme {
+    /// Points by rarity: each halving of a letter's frequency relative to
+    /// E is worth another point
+    pub fn standard() -> Self {
+        Self {
+            id: STANDARD_SCORING_SCHEME.to_string(),
+            version: STANDARD_SCORING_SCHEME_VERSION,
+            letter_points: LETTER_FREQUENCIES
+                .keys()
+                .map(|&letter| (letter, points_for_letter(letter)))
+                .collect(),
+            wildcard_points: 0,
+        }
+    }
+
+    /// Points by rarity in another language's letter frequencies, each
+    /// halving relative to its most common letter worth another point
+    pub fn from_frequencies(version: i32, frequencies: &[(char, f64)]) -> Self {
+        let most_common = frequencies
+            .iter()
+            .map(|&(_, frequency)| frequency)
+            .fold(0.0, f64::max);
+        Self {
+            id: Self::id_for(version),
+            version,
+            letter_points: frequencies
+                .iter()
+                .map(|&(letter, frequency)| {
+                    (
+                        letter,
+                        ((most_common / frequency).log2().floor() as i32) + 1,
+                    )
+                })
+                .collect(),
+            wildcard_points: 0,
+        }
+    }
+
+    /// The id clients fetch the scheme with `version` by
+    pub fn id_for(version: i32) -> String {
+        if version == STANDARD_SCORING_SCHEME_VERSION {
+            STANDARD_SCORING_SCHEME.to_string()
+        } else if let Some(language) = Language::with_scoring_scheme_version(version) {
+            format!("{STANDARD_SCORING_SCHEME}-{language}")
+        } else {
+            format!("v{version}")
+        }
+    }
+
+    /// The points a tile showing `letter` is worth, with `*` for wildcards
+    pub fn points_for(&self, letter: char) -> i32 {
+        if letter == '*' {
+            return self.wildcard_points;
+        }
+        self.letter_points
+            .get(&letter.to_ascii_lowercase())
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// points_for_tile is what a tile showing `letters` is worth: its best
+    /// letter, counted once however many letters it shows
+    pub fn points_for_tile(&self, letters: &str) -> i32 {
+        letters
+            .chars()
+            .map(|letter| self.points_for(letter))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// ScoringSchemes holds every scheme this deployment knows, by version, and
+/// which one new boards are scored with. The standard scheme and each other
+/// language's are built in; later ones are read from a TOML file with a
+/// table per version:
+///
+/// ```toml
+/// active = 2
+///
+/// [2]
+/// wildcard = 0
+/// a = 1
+/// b = 4
+/// # ... every letter from a to z
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScoringSchemes {
+    schemes: BTreeMap<i32, ScoringScheme>,
+    active: i32,
+}
+
+impl Default for ScoringSchemes {
+    fn default() -> Self {
+        Self {
+            active: STANDARD_SCORING_SCHEME_VERSION,
+            schemes: Language::ALL
+                .into_iter()
+                .map(|language| {
+                    let scheme = language.scoring_scheme();
+                    (scheme.version, scheme)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl ScoringSchemes {
+    /// parse reads the schemes defined in `text` alongside the built-in ones.
+    /// Only the top-level `active` key and integer `letter = points` entries
+    /// are understood, which is all the file needs.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut schemes = Self::default();
+        let mut active = None;
+        let mut current: Option<ScoringScheme> = None;
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let context = || format!("line {}: {}", number + 1, line);
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                if let Some(scheme) = current.take() {
+                    schemes.add(scheme)?;
+                }
+                let version: i32 = name.trim().trim_matches('"').parse().with_context(|| {
+                    format!("scheme tables are named by version ({})", context())
+                })?;
+                current = Some(ScoringScheme {
+                    id: ScoringScheme::id_for(version),
+                    version,
+                    letter_points: BTreeMap::new(),
+                    wildcard_points: 0,
+                });
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| anyhow!("expected key = value ({})", context()))?;
+            let key = key.trim().trim_matches('"');
+            let value: i32 = value
+                .trim()
+                .parse()
+                .with_context(|| format!("points must be whole numbers ({})", context()))?;
+
+            match (&mut current, key) {
+                (None, "active") => active = Some(value),
+                (Some(scheme), "wildcard") => scheme.wildcard_points = value,
+                (Some(scheme), key)
+                    if key.len() == 1 && key.chars().all(|c| c.is_ascii_lowercase()) =>
+                {
+                    scheme
+                        .letter_points
+                        .insert(key.chars().next().unwrap(), value);
+                }
+                _ => bail!("unknown key '{}' ({})", key, context()),
+            }
+        }
+        if let Some(scheme) = current {
+            schemes.add(scheme)?;
+        }
+
+        if let Some(active) = active {
+            schemes.set_active(active)?;
+        }
+        Ok(schemes)
+    }
+
+    fn add(&mut self, scheme: ScoringScheme) -> Result<()> {
+        if self.schemes.contains_key(&scheme.version) {
+            bail!("scheme version {} is defined twice", scheme.version);
+        }
+        if let Some(missing) = ('a'..='z').find(|letter| !scheme.letter_points.contains_key(letter))
+        {
+            bail!(
+                "scheme version {} has no points for '{}'",
+                scheme.version,
+                missing
+            );
+        }
+        self.schemes.insert(scheme.version, scheme);
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid scoring schemes in {}", path.display()))
+    }
+
+    /// from_env loads SCORING_SCHEMES_PATH and makes SCORING_SCHEME_VERSION
+    /// active when it is set. A missing or invalid file leaves only the
+    /// standard scheme, so boards keep being scored the way they always were.
+    pub fn from_env() -> Self {
+        let path = env::var("SCORING_SCHEMES_PATH").unwrap_or(SCORING_SCHEMES_PATH.to_string());
+        let path = Path::new(&path);
+        let mut schemes = if path.exists() {
+            Self::load(path).unwrap_or_else(|e| {
+                warn!("Ignoring scoring schemes: {:#}", e);
+                Self::default()
+            })
+        } else {
+            Self::default()
+        };
+
+        if let Ok(version) = env::var("SCORING_SCHEME_VERSION") {
+            let set = version
+                .parse()
+                .map_err(|_| anyhow!("'{}' is not a version", version))
+                .and_then(|version| schemes.set_active(version));
+            if let Err(e) = set {
+                warn!("Ignoring SCORING_SCHEME_VERSION: {}", e);
+            }
+        }
+        schemes
+    }
+
+    pub fn set_active(&mut self, version: i32) -> Result<()> {
+        if !self.schemes.contains_key(&version) {
+            bail!("no scoring scheme has version {}", version);
+        }
+        self.active = version;
+        Ok(())
+    }
+
+    /// The scheme new boards are scored with
+    pub fn active(&self) -> &ScoringScheme {
+        &self.schemes[&self.active]
+    }
+
+    /// The scheme with `version`, None for versions this deployment doesn't
+    /// define
+    pub fn get(&self, version: i32) -> Option<&ScoringScheme> {
+        self.schemes.get(&version)
+    }
+
+    pub fn by_id(&self, id: &str) -> Option<&ScoringScheme> {
+        self.schemes.values().find(|scheme| scheme.id == id)
+    }
+}
+
+/// BonusRules are the points a word can earn beyond its tiles. Each game
+/// stores the rules it was played under, so changing them only affects
+/// games generated afterwards. Every bonus is off by default.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct BonusRules {
+    /// Words with more letters than this earn `long_word_bonus`
+    pub long_word_length: usize,
+    pub long_word_bonus: u32,
+    /// A word traced through both of the board's wildcards has its tile
+    /// points multiplied by this; 0 and 1 leave them alone
+    pub wildcard_multiplier: u32,
+    /// Words the frequency corpus has seen fewer than this many times earn
+    /// `rarity_bonus`
+    pub rare_word_count: u64,
+    pub rarity_bonus: u32,
+}
+
+impl BonusRules {
+    /// from_env reads SCORE_LONG_WORD_BONUS (for words over
+    /// SCORE_LONG_WORD_LENGTH letters, 6 unless set),
+    /// SCORE_WILDCARD_MULTIPLIER, and SCORE_RARITY_BONUS (for words seen fewer
+    /// than SCORE_RARE_WORD_COUNT times, 100 unless set)
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            env::var(name).ok().and_then(|value| value.parse().ok())
+        }
+
+        let mut rules = Self::default();
+        if let Some(bonus) = var("SCORE_LONG_WORD_BONUS") {
+            rules.long_word_bonus = bonus;
+            rules.long_word_length = var("SCORE_LONG_WORD_LENGTH").unwrap_or(6);
+        }
+        if let Some(multiplier) = var("SCORE_WILDCARD_MULTIPLIER") {
+            rules.wildcard_multiplier = multiplier;
+        }
+        if let Some(bonus) = var("SCORE_RARITY_BONUS") {
+            rules.rarity_bonus = bonus;
+            rules.rare_word_count = var("SCORE_RARE_WORD_COUNT").unwrap_or(100);
+        }
+        rules
+    }
+
+    /// Whether no bonus can ever apply, as for every game played before
+    /// bonuses existed
+    pub fn is_empty(&self) -> bool {
+        self.long_word_bonus == 0 && self.wildcard_multiplier <= 1 && self.rarity_bonus == 0
+    }
+
+    /// Points for a path worth `tile_points` that passes through
+    /// `wildcards_used` wildcards
+    pub fn path_points(&self, tile_points: u32, wildcards_used: usize) -> u32 {
+        if wildcards_used >= 2 && self.wildcard_multiplier > 1 {
+            tile_points * self.wildcard_multiplier
+        } else {
+            tile_points
+        }
+    }
+
+    /// Bonus `word` earns whichever path it takes. Without a corpus no word
+    /// counts as rare.
+    pub fn word_bonus(&self, word: &str, corpus: Option<&FrequencyCorpus>) -> u32 {
+        let mut bonus = 0;
+        if self.long_word_bonus > 0 && word.chars().count() > self.long_word_length {
+            bonus += self.long_word_bonus;
+        }
+        if let Some(corpus) = corpus.filter(|_| self.rarity_bonus > 0) {
+            if corpus.count(word) < self.rare_word_count {
+                bonus += self.rarity_bonus;
+            }
+        }
+        bonus
+    }
+
+    /// Most points `answer` can earn under these rules, over whichever of its
+    /// paths scores best, bonuses included
+    pub fn answer_points(&self, answer: &Answer, corpus: Option<&FrequencyCorpus>) -> i32 {
+        let path_points = answer
+            .paths
+            .iter()
+            .map(|path| {
+                let tile_points = path.points().try_into().unwrap_or(0);
+                let wildcards_used = path.tiles.iter().filter(|tile| tile.is_wildcard).count();
+                self.path_points(tile_points, wildcards_used)
+            })
+            .max()
+            .unwrap_or(0);
+        (path_points + self.word_bonus(&answer.word, corpus)) as i32
+    }
+}
+
 pub struct ScoreSheet {
+    /// Each word's points, bonuses included
     pub map: HashMap<String, u32>,
+    /// The part of each word's points that came from bonuses, for words that
+    /// earned any
+    pub bonuses: HashMap<String, u32>,
+    /// How many times beyond the first each repeated word was played, in
+    /// games that allow repeats. Every play scores the word's points.
+    pub repeats: HashMap<String, u32>,
 }
 
 impl Default for ScoreSheet {
@@ -53,17 +432,36 @@ impl ScoreSheet {
     pub fn new() -> Self {
         Self {
             map: HashMap::new(),
+            bonuses: HashMap::new(),
+            repeats: HashMap::new(),
         }
     }
 
     pub fn total_score(&self) -> u32 {
-        self.map.values().sum()
+        self.played(&self.map)
+    }
+
+    /// How much of the total came from bonuses
+    pub fn bonus_score(&self) -> u32 {
+        self.played(&self.bonuses)
+    }
+
+    /// Sum each word's points over every time it was played
+    fn played(&self, points: &HashMap<String, u32>) -> u32 {
+        points
+            .iter()
+            .map(|(word, points)| points * (1 + self.repeats.get(word).unwrap_or(&0)))
+            .sum()
     }
 }
 
 impl From<HashMap<String, u32>> for ScoreSheet {
     fn from(map: HashMap<String, u32>) -> Self {
-        Self { map }
+        Self {
+            map,
+            bonuses: HashMap::new(),
+            repeats: HashMap::new(),
+        }
     }
 }
 
@@ -105,6 +503,45 @@ mod tests {
         assert_eq!(unknown_points, expected);
     }
 
+    #[test]
+    fn test_standard_scheme_matches_tile_points() {
+        let schemes = ScoringSchemes::default();
+        let scheme = schemes.by_id(STANDARD_SCORING_SCHEME).unwrap();
+        assert_eq!(scheme.version, STANDARD_SCORING_SCHEME_VERSION);
+        assert_eq!(scheme.letter_points.len(), 26);
+        for (&letter, &points) in &scheme.letter_points {
+            assert_eq!(points, points_for_letter(letter));
+            assert_eq!(scheme.points_for(letter), points);
+        }
+        assert_eq!(scheme.points_for('*'), 0);
+        assert_eq!(schemes.active(), scheme);
+        assert!(schemes.by_id("unknown").is_none());
+    }
+
+    #[test]
+    fn test_schemes_are_read_from_toml() {
+        let letters: String = ('a'..='z')
+            .map(|letter| format!("{letter} = 2\n"))
+            .collect();
+        let text = format!("# Rebalanced\nactive = 2\n\n[2]\nwildcard = 1\n{letters}");
+        let schemes = ScoringSchemes::parse(&text).unwrap();
+
+        let rebalanced = schemes.active();
+        assert_eq!(rebalanced.version, 2);
+        assert_eq!(rebalanced.id, "v2");
+        assert_eq!(rebalanced.points_for('Q'), 2);
+        assert_eq!(rebalanced.points_for('*'), 1);
+        assert_eq!(schemes.by_id("v2"), Some(rebalanced));
+        // Games scored with the standard scheme keep it
+        assert_eq!(schemes.get(1), Some(&ScoringScheme::standard()));
+
+        // The standard scheme can't be redefined, and a scheme must cover
+        // every letter
+        assert!(ScoringSchemes::parse(&format!("[1]\n{letters}")).is_err());
+        assert!(ScoringSchemes::parse("[2]\na = 1\n").is_err());
+        assert!(ScoringSchemes::parse("active = 3\n").is_err());
+    }
+
     #[test]
     fn test_points_for_letter_case_insensitive() {
         let lowercase_points = points_for_letter('a');
@@ -133,4 +570,33 @@ mod tests {
         assert!(*q_freq < *a_freq);
         assert!(*z_freq < *a_freq);
     }
+
+    #[test]
+    fn test_bonus_rules() {
+        let none = BonusRules::default();
+        assert!(none.is_empty());
+        assert_eq!(none.path_points(10, 2), 10);
+        assert_eq!(none.word_bonus("stomping", None), 0);
+
+        let rules = BonusRules {
+            long_word_length: 6,
+            long_word_bonus: 5,
+            wildcard_multiplier: 2,
+            rare_word_count: 10,
+            rarity_bonus: 3,
+        };
+        assert!(!rules.is_empty());
+        assert_eq!(rules.path_points(10, 1), 10);
+        assert_eq!(rules.path_points(10, 2), 20);
+
+        let corpus = FrequencyCorpus::parse("stomping 50\nsettle 2\n").unwrap();
+        assert_eq!(rules.word_bonus("stomping", Some(&corpus)), 5);
+        assert_eq!(rules.word_bonus("settle", Some(&corpus)), 3);
+        assert_eq!(rules.word_bonus("zyzzyvas", Some(&corpus)), 8);
+        assert_eq!(rules.word_bonus("zyzzyvas", None), 5);
+
+        // Games stored before bonuses existed have none
+        let stored: BonusRules = serde_json::from_str("{}").unwrap();
+        assert_eq!(stored, none);
+    }
 }
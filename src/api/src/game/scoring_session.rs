@@ -0,0 +1,493 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use super::board::conflict::ConstraintConflict;
+use super::board::{
+    answer::Answer,
+    constraints::{AnswerGroupConstraintSet, PathConstraintSet},
+    Board,
+};
+use super::error::GameError;
+use super::scoring::{BonusRules, ScoreSheet};
+use crate::wordlist::FrequencyCorpus;
+
+/// ScoringSession scores one player's answers on one board as the list grows.
+/// Each word's paths are found once, and the wildcard constraints the answers
+/// agree on are kept for every prefix of the list, so adding a word costs one
+/// path search and one intersection however many words came before it. A
+/// list that changes further back only redoes the words from the first
+/// change on. Paths are only kept for the words in the list, so a session
+/// stays the size of the list however many words come and go.
+///
+/// It gives the same scores as `GameEngine::score_answer_group`, which scores
+/// through a session of its own.
+#[derive(Clone)]
+pub struct ScoringSession {
+    board: Board,
+    bonus_rules: BonusRules,
+    corpus: Option<Arc<FrequencyCorpus>>,
+    answers: Vec<SessionAnswer>,
+    /// Paths of each word in the list
+    paths: HashMap<String, Answer>,
+}
+
+/// What adding a word would do to a list of answers
+pub struct ScorePreview {
+    /// The scores of the list as it stands
+    pub before: ScoreSheet,
+    /// The scores with the word added, or why the wildcards can't be chosen
+    /// for them all
+    pub after: Result<ScoreSheet, ConstraintConflict>,
+}
+
+#[derive(Clone)]
+struct SessionAnswer {
+    word: String,
+    /// The constraint sets under which this answer and every one before it
+    /// can all be formed
+    agreed: Vec<PathConstraintSet>,
+    /// (points, tile points) of the word's best path under each constraint
+    /// set it has been scored against
+    best: HashMap<PathConstraintSet, (u32, u32)>,
+}
+
+impl ScoringSession {
+    pub(crate) fn new(
+        board: Board,
+        bonus_rules: BonusRules,
+        corpus: Option<Arc<FrequencyCorpus>>,
+    ) -> Self {
+        Self {
+            board,
+            bonus_rules,
+            corpus,
+            answers: Vec::new(),
+            paths: HashMap::new(),
+        }
+    }
+
+    /// is_for reports whether the session scores `board` under `bonus_rules`,
+    /// so a kept session can be checked against the game it's reused for
+    pub fn is_for(&self, board: &Board, bonus_rules: &BonusRules) -> bool {
+        self.board == *board && self.bonus_rules == *bonus_rules
+    }
+
+    /// weight is how many words' paths the session holds, to bound the
+    /// sessions kept for players by what they hold
+    pub fn weight(&self) -> u32 {
+        self.paths.len().try_into().unwrap_or(u32::MAX)
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.answers.iter().map(|answer| answer.word.as_str())
+    }
+
+    /// score sets the answers to `words` and scores them. Only the words after
+    /// the longest prefix shared with the previous list are worked out again.
+    /// After an error the session holds some prefix of `words`, which the next
+    /// call carries on from.
+    pub fn score(&mut self, words: Vec<String>) -> Result<ScoreSheet, GameError> {
+        let result = self.set_words(words);
+        self.forget_stale_paths();
+        result.map(|()| self.score_sheet())
+    }
+
+    fn set_words(&mut self, words: Vec<String>) -> Result<(), GameError> {
+        let kept = self
+            .answers
+            .iter()
+            .zip(&words)
+            .take_while(|(answer, word)| answer.word == **word)
+            .count();
+        self.answers.truncate(kept);
+
+        let added = words.into_iter().skip(kept).collect::<Vec<_>>();
+        // Every new word must be formable before any constraints are merged
+        for word in &added {
+            let paths = self
+                .paths
+                .entry(word.clone())
+                .or_insert_with(|| self.board.paths_for(word));
+            if paths.paths.is_empty() {
+                return Err(GameError::NotOnBoard { word: word.clone() });
+            }
+        }
+        for word in added {
+            self.push(word)?;
+        }
+        Ok(())
+    }
+
+    /// preview scores `words`, then `words` with `candidate` added, which
+    /// only works out the candidate on top of the list. A candidate the
+    /// wildcards can't be chosen for alongside the list is a conflict in the
+    /// preview rather than an error, and the session is left on `words`.
+    pub fn preview(
+        &mut self,
+        words: Vec<String>,
+        candidate: String,
+    ) -> Result<ScorePreview, GameError> {
+        let before = self.score(words)?;
+        let after = self.score_with(candidate);
+        self.forget_stale_paths();
+        let after = match after {
+            Ok(sheet) => Ok(sheet),
+            Err(GameError::ConstraintConflict(conflict)) => Err(conflict),
+            Err(error) => return Err(error),
+        };
+        Ok(ScorePreview { before, after })
+    }
+
+    /// Score the list with `candidate` on the end, then take it off again
+    fn score_with(&mut self, candidate: String) -> Result<ScoreSheet, GameError> {
+        let paths = self
+            .paths
+            .entry(candidate.clone())
+            .or_insert_with(|| self.board.paths_for(&candidate));
+        if paths.paths.is_empty() {
+            return Err(GameError::NotOnBoard { word: candidate });
+        }
+        self.push(candidate)?;
+        let sheet = self.score_sheet();
+        self.answers.pop();
+        Ok(sheet)
+    }
+
+    /// Drop the paths of words no longer in the list
+    fn forget_stale_paths(&mut self) {
+        let words: HashSet<&str> = self.answers.iter().map(|a| a.word.as_str()).collect();
+        self.paths.retain(|word, _| words.contains(word.as_str()));
+    }
+
+    fn push(&mut self, word: String) -> Result<(), GameError> {
+        let constraints = &self.paths[&word].constraints_set;
+        let agreed = match self.answers.last() {
+            None => dedup(constraints.path_constraint_sets.clone()),
+            Some(previous) => {
+                let previous = AnswerGroupConstraintSet::from(previous.agreed.clone());
+                previous
+                    .intersection(constraints.clone())
+                    .map(|set| dedup(set.path_constraint_sets))
+                    .map_err(|_| {
+                        GameError::ConstraintConflict(ConstraintConflict::explain(
+                            self.words()
+                                .chain([word.as_str()])
+                                .map(|word| (word, &self.paths[word].constraints_set)),
+                        ))
+                    })?
+            }
+        };
+
+        self.answers.push(SessionAnswer {
+            word,
+            agreed,
+            best: HashMap::new(),
+        });
+        Ok(())
+    }
+
+    /// For each constraint set all the answers agree on, total every word's
+    /// best path under it and keep the highest total
+    fn score_sheet(&mut self) -> ScoreSheet {
+        let mut max_total_score = 0u32;
+        let mut sheet = ScoreSheet::new();
+        for (_, candidate) in self.sheets() {
+            let total_score = candidate.total_score();
+            if total_score > max_total_score {
+                max_total_score = total_score;
+                sheet = candidate;
+            }
+        }
+        sheet
+    }
+
+    /// sheets scores the answers under each constraint set they all agree
+    /// on. With no answers there is one empty sheet, under no constraints.
+    pub fn sheets(&mut self) -> Vec<(PathConstraintSet, ScoreSheet)> {
+        let Some(last) = self.answers.last() else {
+            return vec![(PathConstraintSet::Unconstrainted, ScoreSheet::new())];
+        };
+        last.agreed
+            .clone()
+            .into_iter()
+            .map(|path_constraint| (path_constraint, self.sheet_under(path_constraint)))
+            .collect()
+    }
+
+    /// Every word's best path under `path_constraint`
+    fn sheet_under(&mut self, path_constraint: PathConstraintSet) -> ScoreSheet {
+        let mut sheet = ScoreSheet::new();
+        for answer in &mut self.answers {
+            // A word played again scores the same as it did the first time
+            if sheet.map.contains_key(&answer.word) {
+                *sheet.repeats.entry(answer.word.clone()).or_default() += 1;
+                continue;
+            }
+            let (best_path_score, best_tile_score) =
+                *answer.best.entry(path_constraint).or_insert_with(|| {
+                    best_path(
+                        &self.paths[&answer.word],
+                        path_constraint,
+                        &self.bonus_rules,
+                    )
+                });
+
+            let word_score = best_path_score
+                + self
+                    .bonus_rules
+                    .word_bonus(&answer.word, self.corpus.as_deref());
+            if word_score > best_tile_score {
+                sheet
+                    .bonuses
+                    .insert(answer.word.clone(), word_score - best_tile_score);
+            }
+            sheet.map.insert(answer.word.clone(), word_score);
+        }
+        sheet
+    }
+}
+
+/// The (points, tile points) of the highest scoring of `answer`'s paths that
+/// can be formed under `constraint`
+fn best_path(
+    answer: &Answer,
+    constraint: PathConstraintSet,
+    bonus_rules: &BonusRules,
+) -> (u32, u32) {
+    let mut best_path_score = 0;
+    let mut best_tile_score = 0;
+    for path in &answer.paths {
+        if path.constraints.merge(constraint).is_ok() {
+            let tile_score: u32 = path.points().try_into().unwrap();
+            let wildcards_used = path.tiles.iter().filter(|tile| tile.is_wildcard).count();
+            let path_score = bonus_rules.path_points(tile_score, wildcards_used);
+            if path_score > best_path_score {
+                best_path_score = path_score;
+                best_tile_score = tile_score;
+            }
+        }
+    }
+    (best_path_score, best_tile_score)
+}
+
+/// Drop repeated constraint sets, which would otherwise multiply with every
+/// answer intersected
+fn dedup(sets: Vec<PathConstraintSet>) -> Vec<PathConstraintSet> {
+    let mut seen = HashSet::new();
+    sets.into_iter().filter(|set| seen.insert(*set)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{solver::Solver, BoardGenerator, Dawg};
+    use crate::test_utils::create_test_board;
+    use rand::SeedableRng;
+
+    fn session(board: &Board) -> ScoringSession {
+        ScoringSession::new(board.clone(), BonusRules::default(), None)
+    }
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|word| word.to_string()).collect()
+    }
+
+    /// Score every prefix of `list` in a fresh session, as score_answer_group
+    /// does
+    fn scratch_totals(board: &Board, list: &[String]) -> Vec<Result<u32, GameError>> {
+        (1..=list.len())
+            .map(|len| {
+                session(board)
+                    .score(list[..len].to_vec())
+                    .map(|sheet| sheet.total_score())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_growing_list_matches_scoring_from_scratch() {
+        const WORDS: [&str; 20] = [
+            "are", "area", "ate", "eat", "ear", "era", "net", "nest", "rat", "rate", "sea", "seat",
+            "set", "star", "stare", "tar", "tea", "tear", "ten", "tone",
+        ];
+        let dictionary = Dawg::from(WORDS.to_vec());
+
+        for seed in 0..10 {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+            let board = BoardGenerator::new().generate_board(&mut rng);
+            let found: Vec<String> = Solver::new(&board)
+                .solve(&dictionary)
+                .into_iter()
+                .map(|answer| answer.word)
+                .collect();
+
+            let mut incremental = session(&board);
+            let totals: Vec<Result<u32, GameError>> = (1..=found.len())
+                .map(|len| {
+                    incremental
+                        .score(found[..len].to_vec())
+                        .map(|sheet| sheet.total_score())
+                })
+                .collect();
+            // The session stops short at the first conflict, as scratch does
+            let expected = scratch_totals(&board, &found);
+            for (len, (got, want)) in totals.iter().zip(&expected).enumerate() {
+                match want {
+                    Ok(want) => assert_eq!(got.as_ref().ok(), Some(want), "seed {seed} len {len}"),
+                    Err(_) => break,
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_changed_list_rescored_from_the_change() {
+        // c a t s
+        // a * e t
+        // t e a s
+        // s t a c
+        let board = create_test_board("catsa*etteasstac");
+        let mut scoring = session(&board);
+
+        let full = scoring.score(words(&["cat", "eat", "tea"])).unwrap();
+        let dropped = scoring.score(words(&["cat", "tea"])).unwrap();
+        assert_eq!(scoring.words().collect::<Vec<_>>(), vec!["cat", "tea"]);
+        assert_eq!(
+            dropped.total_score(),
+            session(&board)
+                .score(words(&["cat", "tea"]))
+                .unwrap()
+                .total_score()
+        );
+        assert!(dropped.total_score() < full.total_score());
+
+        let back = scoring.score(words(&["cat", "tea", "eat"])).unwrap();
+        assert_eq!(back.total_score(), full.total_score());
+
+        assert!(scoring.score(vec![]).unwrap().map.is_empty());
+    }
+
+    #[test]
+    fn test_unformable_word_is_reported_first() {
+        let board = create_test_board("catsa*etteasstac");
+        let mut scoring = session(&board);
+
+        let err = scoring.score(words(&["cat", "zzz"])).err().unwrap();
+        assert_eq!(
+            err,
+            GameError::NotOnBoard {
+                word: "zzz".to_string()
+            }
+        );
+        assert!(scoring.score(words(&["cat", "tea"])).is_ok());
+        assert_eq!(scoring.words().collect::<Vec<_>>(), vec!["cat", "tea"]);
+    }
+
+    #[test]
+    fn test_preview_shows_words_losing_points() {
+        // c a t s
+        // x * x x
+        // x x * x
+        // cats doubles through both wildcards, until cot takes them as o and t
+        let board = create_test_board("catsx*xxxx*xxxxx");
+        let rules = BonusRules {
+            wildcard_multiplier: 2,
+            ..BonusRules::default()
+        };
+        let mut scoring = ScoringSession::new(board.clone(), rules.clone(), None);
+
+        let preview = scoring
+            .preview(words(&["cats"]), "cot".to_string())
+            .unwrap();
+        assert_eq!(preview.before.map["cats"], 8);
+        let after = preview.after.unwrap();
+        assert_eq!(after.map["cats"], 4);
+        assert_eq!(after.map["cot"], 6);
+        assert_eq!(scoring.words().collect::<Vec<_>>(), vec!["cats"]);
+
+        // cut needs the first wildcard to be u as well
+        let preview = scoring
+            .preview(words(&["cats", "cot"]), "cut".to_string())
+            .unwrap();
+        assert_eq!(preview.before.total_score(), 10);
+        assert_eq!(preview.after.err().unwrap().words, vec!["cot", "cut"]);
+        assert_eq!(scoring.words().collect::<Vec<_>>(), vec!["cats", "cot"]);
+
+        let err = scoring
+            .preview(words(&["cats"]), "zzz".to_string())
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            GameError::NotOnBoard {
+                word: "zzz".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_preview_leaves_the_list_as_it_was() {
+        let board = create_test_board("catsa*etteasstac");
+        let mut scoring = session(&board);
+        let list = words(&["cat", "tea"]);
+        let scored = scoring.score(list.clone()).unwrap();
+
+        let preview = scoring.preview(list.clone(), "eat".to_string()).unwrap();
+        assert_eq!(preview.before.total_score(), scored.total_score());
+        assert!(preview.after.is_ok());
+        assert_eq!(scoring.words().collect::<Vec<_>>(), vec!["cat", "tea"]);
+        assert!(!scoring.paths.contains_key("eat"));
+
+        // The list's scores are still worked out, so scoring it again finds
+        // them rather than searching the board, here swapped for a blank one
+        assert!(scoring.answers.iter().all(|answer| !answer.best.is_empty()));
+        scoring.board = create_test_board("xxxxxxxxxxxxxxxx");
+        let again = scoring.score(list).unwrap();
+        assert_eq!(again.total_score(), scored.total_score());
+    }
+
+    #[test]
+    fn test_dropped_words_paths_are_forgotten() {
+        let board = create_test_board("catsa*etteasstac");
+        let mut scoring = session(&board);
+
+        scoring.score(words(&["cat", "eat", "tea"])).unwrap();
+        scoring.score(words(&["cat", "tea"])).unwrap();
+        assert_eq!(scoring.paths.len(), 2);
+        assert!(!scoring.paths.contains_key("eat"));
+
+        assert!(scoring.score(words(&["cat", "zzz"])).is_err());
+        assert_eq!(
+            scoring.paths.keys().map(String::as_str).collect::<Vec<_>>(),
+            vec!["cat"]
+        );
+    }
+
+    #[test]
+    fn test_repeated_words_score_every_time() {
+        let board = create_test_board("catsa*etteasstac");
+        let mut scoring = session(&board);
+        let once = scoring.score(words(&["cat", "tea"])).unwrap();
+
+        let twice = scoring.score(words(&["cat", "tea", "cat"])).unwrap();
+        assert_eq!(twice.map["cat"], once.map["cat"]);
+        assert_eq!(twice.repeats["cat"], 1);
+        assert_eq!(twice.total_score(), once.total_score() + once.map["cat"]);
+    }
+
+    #[test]
+    fn test_is_for_its_board_and_rules() {
+        let board = create_test_board("catsa*etteasstac");
+        let scoring = session(&board);
+        assert!(scoring.is_for(&board, &BonusRules::default()));
+        assert!(!scoring.is_for(
+            &create_test_board("tacsa*etteasstac"),
+            &BonusRules::default()
+        ));
+        let rules = BonusRules {
+            long_word_bonus: 2,
+            ..BonusRules::default()
+        };
+        assert!(!scoring.is_for(&board, &rules));
+    }
+}
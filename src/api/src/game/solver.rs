@@ -0,0 +1,298 @@
+use rayon::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+use super::board::{
+    answer::Answer,
+    constraints::{AnswerGroupConstraintSet, PathConstraintSet},
+    path::{GameTile, Path},
+    Board,
+};
+use super::dawg::{Dawg, DawgNode};
+use super::neighbors::NeighborLetters;
+
+/// Shortest word that counts as an answer unless a game's rules say otherwise
+pub const MIN_WORD_LENGTH: usize = 3;
+
+/// Solver finds every dictionary word on a board in a single depth-first
+/// search. It carries the dawg node for the current prefix, so dead prefixes
+/// are never extended, along with the cells and wildcard constraints of the
+/// current path, so each word's paths are collected as the search reaches
+/// them instead of being searched for again per word.
+///
+/// The searches from each starting cell are independent, so they run in
+/// parallel and are merged in cell order afterwards. Paths for each word come
+/// out in the same order `Board::paths_for` produces them: by starting cell,
+/// then by direction at each step.
+pub struct Solver<'a> {
+    board: &'a Board,
+    neighbors: NeighborLetters,
+    min_word_length: usize,
+}
+
+/// Mutable state threaded through the search
+struct Search {
+    word: String,
+    cells: Vec<(usize, usize)>,
+    visited: u32,
+    found: HashMap<String, Vec<Path>>,
+}
+
+impl<'a> Solver<'a> {
+    pub fn new(board: &'a Board) -> Self {
+        Self {
+            board,
+            neighbors: NeighborLetters::new(board),
+            min_word_length: MIN_WORD_LENGTH,
+        }
+    }
+
+    /// with_min_word_length sets the fewest letters a word found needs
+    pub fn with_min_word_length(mut self, min_word_length: usize) -> Self {
+        self.min_word_length = min_word_length;
+        self
+    }
+
+    /// solve returns an Answer, with every path, for each word in `dictionary`
+    /// that can be formed on the board, sorted by word
+    pub fn solve(&self, dictionary: &Dawg) -> Vec<Answer> {
+        let starts: Vec<(usize, usize)> = self
+            .board
+            .rows
+            .iter()
+            .enumerate()
+            .flat_map(|(row, tiles)| (0..tiles.tiles.len()).map(move |col| (row, col)))
+            .collect();
+
+        let found_from_each_start: Vec<HashMap<String, Vec<Path>>> = starts
+            .into_par_iter()
+            .map(|(row, col)| {
+                let mut search = Search {
+                    word: String::new(),
+                    cells: Vec::new(),
+                    visited: 0,
+                    found: HashMap::new(),
+                };
+                self.visit(
+                    row,
+                    col,
+                    dictionary.root(),
+                    PathConstraintSet::Unconstrainted,
+                    &mut search,
+                );
+                search.found
+            })
+            .collect();
+
+        let mut found: HashMap<String, Vec<Path>> = HashMap::new();
+        for found_from_start in found_from_each_start {
+            for (word, paths) in found_from_start {
+                found.entry(word).or_default().extend(paths);
+            }
+        }
+
+        let mut answers: Vec<Answer> = found
+            .into_iter()
+            .map(|(word, paths)| {
+                let constraints_set = AnswerGroupConstraintSet::from(
+                    paths
+                        .iter()
+                        .map(|path| path.constraints)
+                        .collect::<Vec<PathConstraintSet>>(),
+                );
+                Answer {
+                    word,
+                    paths,
+                    constraints_set,
+                }
+            })
+            .collect();
+        answers.sort_by(|a, b| a.word.cmp(&b.word));
+        answers
+    }
+
+    /// Step onto (row, col) from a path spelling `search.word`, whose dawg node
+    /// is `node` and whose wildcard constraints are `constraints`
+    fn visit(
+        &self,
+        row: usize,
+        col: usize,
+        node: DawgNode,
+        constraints: PathConstraintSet,
+        search: &mut Search,
+    ) {
+        let tile = self.board.get_tile(row, col);
+
+        // Only letters this cell can supply that also continue the prefix
+        let mut candidates = self.neighbors.letters(row, col) & node.child_letters();
+        if candidates == 0 {
+            return;
+        }
+
+        search.visited |= self.neighbors.cell_bit(row, col);
+        search.cells.push((row, col));
+
+        // Letters an unvisited neighbor could supply after this tile. Any dawg
+        // branch that can only continue with other letters is a dead end here.
+        let reachable = self.neighbors.reachable_letters(row, col, search.visited);
+
+        while candidates != 0 {
+            let letter = (b'a' + candidates.trailing_zeros() as u8) as char;
+            candidates &= candidates - 1;
+
+            // A tile showing several letters, e.g. `qu`, spells the rest of
+            // them after its first
+            let rest = if tile.is_wildcard {
+                ""
+            } else {
+                tile.letter.get(letter.len_utf8()..).unwrap_or_default()
+            };
+            let Some(child) = node
+                .child(letter)
+                .and_then(|child| rest.chars().try_fold(child, |node, c| node.child(c)))
+            else {
+                continue;
+            };
+            let Ok(constraints) = constraints.merge(tile.as_constraint(letter)) else {
+                continue;
+            };
+
+            search.word.push(letter);
+            search.word.push_str(rest);
+
+            if search.word.len() >= self.min_word_length && child.is_word() {
+                self.record(constraints, search);
+            }
+
+            // Negative lookahead: skip the whole subtree if no neighbor can continue it
+            let next_letters = child.child_letters();
+            if next_letters & reachable != 0 {
+                for &(next_row, next_col) in self.neighbors.neighbors(row, col) {
+                    if search.visited & self.neighbors.cell_bit(next_row, next_col) == 0
+                        && self.neighbors.letters(next_row, next_col) & next_letters != 0
+                    {
+                        self.visit(next_row, next_col, child, constraints, search);
+                    }
+                }
+            }
+
+            search
+                .word
+                .truncate(search.word.len() - letter.len_utf8() - rest.len());
+        }
+
+        search.cells.pop();
+        search.visited &= !self.neighbors.cell_bit(row, col);
+    }
+
+    /// Record the current path as one way of forming the current word
+    fn record(&self, constraints: PathConstraintSet, search: &mut Search) {
+        let tiles: VecDeque<GameTile> = search
+            .cells
+            .iter()
+            .map(|&(row, col)| GameTile::from(self.board.get_tile(row, col)))
+            .collect();
+
+        search
+            .found
+            .entry(search.word.clone())
+            .or_default()
+            .push(Path { tiles, constraints });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{directions::Adjacency, BoardGenerator};
+    use crate::test_utils::create_test_board;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_solve_finds_words_with_all_paths() {
+        // c a t s
+        // a * e t
+        // t e a s
+        // s t a c
+        let board = create_test_board("catsa*etteasstac");
+        let dictionary = Dawg::from(vec!["cat", "cats", "eat", "tea", "zzz", "at"]);
+
+        let answers = Solver::new(&board).solve(&dictionary);
+        let words: Vec<&str> = answers.iter().map(|a| a.word.as_str()).collect();
+        assert_eq!(words, vec!["cat", "cats", "eat", "tea"]);
+
+        for answer in &answers {
+            assert_eq!(answer, &board.paths_for(&answer.word));
+        }
+    }
+
+    #[test]
+    fn test_solve_matches_paths_for_on_generated_boards() {
+        const WORDS: [&str; 27] = [
+            "are", "area", "ate", "eat", "ear", "era", "net", "nest", "rat", "rate", "sea", "seat",
+            "set", "sit", "site", "star", "stare", "tar", "tea", "tear", "ten", "tie", "tin",
+            "tine", "toe", "ton", "tone",
+        ];
+        let dictionary = Dawg::from(WORDS.to_vec());
+
+        for seed in 0..20 {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+            let board = BoardGenerator::new().generate_board(&mut rng);
+            let answers = Solver::new(&board).solve(&dictionary);
+
+            for word in WORDS {
+                let expected = board.paths_for(word);
+                match answers.iter().find(|answer| answer.word == word) {
+                    Some(answer) => assert_eq!(answer, &expected, "seed {seed}"),
+                    None => assert!(expected.paths.is_empty(), "seed {seed} missed {word}"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_spells_multi_letter_tiles_in_full() {
+        // qu i t s
+        // a  e o t
+        // s  a n i
+        // i  n a l
+        let board: Board = "(qu)itsaeotsaniinal".parse().unwrap();
+        let dictionary = Dawg::from(vec!["quit", "quits", "qit", "suit", "its", "tin"]);
+
+        let answers = Solver::new(&board).solve(&dictionary);
+        let words: Vec<&str> = answers.iter().map(|a| a.word.as_str()).collect();
+        assert_eq!(words, vec!["its", "quit", "quits", "tin"]);
+        for answer in &answers {
+            assert_eq!(answer, &board.paths_for(&answer.word));
+        }
+    }
+
+    #[test]
+    fn test_solve_follows_board_adjacency() {
+        // c a t s
+        // a * e t
+        // t e a s
+        // s t a c
+        let board = create_test_board("catsa*etteasstac").with_adjacency(Adjacency::FourWay);
+        let dictionary = Dawg::from(vec!["cat", "cats", "eat", "tea"]);
+
+        let answers = Solver::new(&board).solve(&dictionary);
+        for answer in &answers {
+            assert_eq!(answer, &board.paths_for(&answer.word));
+        }
+
+        // Every step is orthogonal, so some eight-way paths are lost
+        for path in answers.iter().flat_map(|answer| &answer.paths) {
+            for (from, to) in path.tiles.iter().zip(path.tiles.iter().skip(1)) {
+                assert_eq!((from.row - to.row).abs() + (from.col - to.col).abs(), 1);
+            }
+        }
+        let eight_way = board.clone().with_adjacency(Adjacency::EightWay);
+        let path_count = |board: &Board| -> usize {
+            ["cat", "cats", "eat", "tea"]
+                .iter()
+                .map(|word| board.paths_for(word).paths.len())
+                .sum()
+        };
+        assert!(path_count(&board) < path_count(&eight_way));
+    }
+}
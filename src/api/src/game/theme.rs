@@ -0,0 +1,255 @@
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+use std::path::Path;
+use std::{env, fmt, fs, str::FromStr};
+use tracing::warn;
+
+use crate::game::directions::Adjacency;
+
+/// Where the schedule of themed dates is kept unless THEME_SCHEDULE_PATH says
+/// otherwise
+pub const THEME_SCHEDULE_PATH: &str = "themes.txt";
+
+/// Cells a theme can use: the whole board but its two wildcards
+const OPEN_CELLS: usize = 14;
+
+/// Theme is what a themed board is guaranteed to contain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Theme {
+    /// A word spelled along a path on the board, e.g. a holiday word
+    Word(String),
+    /// Letters that each appear somewhere on the board
+    Letters(String),
+}
+
+/// Parse a theme spec: `word:snow` or just `snow` for a word, `letters:xmas`
+/// for a set of letters
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let spec = s.trim().to_lowercase();
+        let (kind, value) = spec.split_once(':').unwrap_or(("word", &spec));
+        if value.is_empty() || !value.chars().all(|c| c.is_ascii_lowercase()) {
+            return Err(format!("theme must be letters a-z only: {s}"));
+        }
+
+        match kind {
+            "word" if value.len() < 2 => Err(format!("theme word is too short: {s}")),
+            "word" if value.len() > OPEN_CELLS => Err(format!("theme word is too long: {s}")),
+            "word" => Ok(Theme::Word(value.to_string())),
+            "letters" => {
+                let mut letters: Vec<char> = value.chars().collect();
+                letters.sort_unstable();
+                letters.dedup();
+                if letters.len() > OPEN_CELLS {
+                    return Err(format!("theme has too many letters: {s}"));
+                }
+                Ok(Theme::Letters(letters.into_iter().collect()))
+            }
+            _ => Err(format!("unknown theme kind: {kind}")),
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Theme::Word(word) => write!(f, "word:{word}"),
+            Theme::Letters(letters) => write!(f, "letters:{letters}"),
+        }
+    }
+}
+
+/// ThemeSchedule holds the theme for each themed date
+#[derive(Debug, Clone, Default)]
+pub struct ThemeSchedule {
+    themes: HashMap<String, Theme>,
+}
+
+impl ThemeSchedule {
+    /// parse reads one `YYYY-MM-DD spec` pair per line, e.g.
+    /// `2025-12-25 word:snow`. Blank lines and `#` comments are skipped.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut themes = HashMap::new();
+        for line in text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        {
+            let (date, spec) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| anyhow!("Theme line is not `date theme`: {line}"))?;
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| anyhow!("Theme line has a bad date: {line}"))?;
+            let theme = spec.parse::<Theme>().map_err(|e| anyhow!(e))?;
+            themes.insert(date.to_string(), theme);
+        }
+        Ok(Self { themes })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// load_or_default loads the schedule at `path`, falling back to no
+    /// themes when the file is missing or invalid
+    pub fn load_or_default(path: &Path) -> Self {
+        if !path.exists() {
+            return Self::default();
+        }
+        Self::load(path).unwrap_or_else(|e| {
+            warn!("Ignoring theme schedule in {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    /// The schedule at THEME_SCHEDULE_PATH, or `themes.txt` by default
+    pub fn from_env() -> Self {
+        let path = env::var("THEME_SCHEDULE_PATH").unwrap_or(THEME_SCHEDULE_PATH.to_string());
+        Self::load_or_default(Path::new(&path))
+    }
+
+    pub fn get(&self, date: &str) -> Option<&Theme> {
+        self.themes.get(date)
+    }
+}
+
+/// random_path finds a path of `length` distinct adjacent cells through the
+/// `open` cells of a 4x4 board, trying starts and steps in random order.
+/// None when no such path exists.
+pub(crate) fn random_path<R: rand::Rng>(
+    rng: &mut R,
+    open: &[[bool; 4]; 4],
+    length: usize,
+) -> Option<Vec<(usize, usize)>> {
+    let mut starts: Vec<(usize, usize)> = (0..4)
+        .flat_map(|row| (0..4).map(move |col| (row, col)))
+        .filter(|&(row, col)| open[row][col])
+        .collect();
+    starts.shuffle(rng);
+
+    let mut path = Vec::with_capacity(length);
+    for start in starts {
+        path.push(start);
+        if extend_path(rng, open, length, &mut path) {
+            return Some(path);
+        }
+        path.pop();
+    }
+    None
+}
+
+fn extend_path<R: rand::Rng>(
+    rng: &mut R,
+    open: &[[bool; 4]; 4],
+    length: usize,
+    path: &mut Vec<(usize, usize)>,
+) -> bool {
+    if path.len() >= length {
+        return true;
+    }
+
+    let (row, col) = path[path.len() - 1];
+    let mut steps: Vec<(usize, usize)> = Adjacency::EightWay
+        .neighbors(row, col, 4, 4)
+        .filter(|&(r, c)| open[r][c] && !path.contains(&(r, c)))
+        .collect();
+    steps.shuffle(rng);
+
+    for step in steps {
+        path.push(step);
+        if extend_path(rng, open, length, path) {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::BoardGenerator;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_parse_theme_specs() {
+        assert_eq!("Snow".parse(), Ok(Theme::Word("snow".to_string())));
+        assert_eq!("word:snow".parse(), Ok(Theme::Word("snow".to_string())));
+        assert_eq!(
+            "letters:xmasx".parse(),
+            Ok(Theme::Letters("amsx".to_string()))
+        );
+        assert_eq!(
+            Theme::Letters("amsx".to_string()).to_string(),
+            "letters:amsx"
+        );
+        assert!("x-ray".parse::<Theme>().is_err());
+        assert!("a".parse::<Theme>().is_err());
+        assert!("word:abcdefghijklmno".parse::<Theme>().is_err());
+        assert!("colour:red".parse::<Theme>().is_err());
+
+        let schedule =
+            ThemeSchedule::parse("# holidays\n2025-12-25 word:snow\n2025-10-31 letters:boo\n")
+                .unwrap();
+        assert_eq!(
+            schedule.get("2025-12-25"),
+            Some(&Theme::Word("snow".to_string()))
+        );
+        assert_eq!(schedule.get("2025-12-26"), None);
+        assert!(ThemeSchedule::parse("christmas word:snow\n").is_err());
+        assert!(ThemeSchedule::parse("2025-12-25\n").is_err());
+    }
+
+    #[test]
+    fn test_themed_boards_contain_theme() {
+        let generator = BoardGenerator::new();
+        for seed in 0..50 {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+            let theme = Theme::Word("holidays".to_string());
+            let board = generator.generate_themed_board(&mut rng, &theme).unwrap();
+            assert!(!board.paths_for("holidays").paths.is_empty());
+            assert_eq!(
+                board
+                    .rows
+                    .iter()
+                    .flat_map(|row| &row.tiles)
+                    .filter(|tile| tile.is_wildcard)
+                    .count(),
+                2
+            );
+
+            let theme = Theme::Letters("jqxz".to_string());
+            let board = generator.generate_themed_board(&mut rng, &theme).unwrap();
+            let letters: String = board.to_string();
+            assert!("JQXZ".chars().all(|letter| letters.contains(letter)));
+        }
+
+        // The same seed always gives the same board
+        let theme = Theme::Word("snow".to_string());
+        let board = |seed| {
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+            generator.generate_themed_board(&mut rng, &theme).unwrap()
+        };
+        assert_eq!(board(3), board(3));
+    }
+
+    #[test]
+    fn test_random_path_respects_open_cells() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let mut open = [[true; 4]; 4];
+        let path = random_path(&mut rng, &open, 16).unwrap();
+        assert_eq!(path.len(), 16);
+
+        // Only the top row is open, so at most 4 cells can be visited
+        open = [[true; 4], [false; 4], [false; 4], [false; 4]];
+        assert!(random_path(&mut rng, &open, 4)
+            .unwrap()
+            .iter()
+            .all(|&(row, _)| row == 0));
+        assert_eq!(random_path(&mut rng, &open, 5), None);
+    }
+}
@@ -74,6 +74,43 @@ impl Trie {
     pub fn has_prefix(&self, prefix: &str) -> bool {
         self.ihas_prefix(&mut prefix.chars())
     }
+
+    /// node returns the subtrie reached by following `prefix`, or None if no
+    /// word starts with it
+    pub fn node(&self, prefix: &str) -> Option<&Trie> {
+        prefix.chars().try_fold(self, |node, c| node.child(c))
+    }
+
+    /// child returns the subtrie for words continuing with `c`
+    pub fn child(&self, c: char) -> Option<&Trie> {
+        self.next
+            .iter()
+            .find(|(ch, _)| *ch == c)
+            .map(|(_, child)| child.as_ref())
+    }
+
+    /// is_word reports whether the prefix leading to this node is a complete word
+    pub fn is_word(&self) -> bool {
+        self.finish
+    }
+
+    /// child_letters returns the set of letters that can follow this node as a
+    /// bitmask (see `letter_bit`)
+    pub fn child_letters(&self) -> u32 {
+        self.next
+            .iter()
+            .fold(0, |mask, (c, _)| mask | letter_bit(*c))
+    }
+}
+
+/// letter_bit maps 'a'..='z' to bits 0..26 so sets of letters can be stored
+/// and intersected as a u32. Other characters map to the empty set.
+pub fn letter_bit(c: char) -> u32 {
+    if c.is_ascii_lowercase() {
+        1 << (c as u32 - 'a' as u32)
+    } else {
+        0
+    }
 }
 
 impl From<Vec<&str>> for Trie {
@@ -173,6 +210,23 @@ mod tests {
         assert!(t.has_prefix("happy"));
     }
 
+    #[test]
+    fn test_node_and_child_letters() {
+        let t = Trie::from(vec!["app", "apple", "apt", "bee"]);
+
+        let ap = t.node("ap").unwrap();
+        assert!(!ap.is_word());
+        assert_eq!(ap.child_letters(), letter_bit('p') | letter_bit('t'));
+
+        let app = t.node("app").unwrap();
+        assert!(app.is_word());
+        assert_eq!(app.child_letters(), letter_bit('l'));
+
+        assert!(t.node("apple").unwrap().child_letters() == 0);
+        assert!(t.node("bz").is_none());
+        assert_eq!(t.node("").unwrap(), &t);
+    }
+
     #[test]
     fn test_from_string() {
         let wordlist = "apple\nbanana\ncherry\nhappy".to_string();
@@ -0,0 +1,223 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::game::{board::answer::Answer, Board};
+
+/// Players averaging fewer letters per word than this are told to practice
+/// longer words
+const SHORT_WORD_LENGTH: f64 = 4.5;
+
+/// Players whose words need a wildcard less often than this are told to
+/// practice with wildcards
+const RARE_WILDCARD_SHARE: f64 = 0.15;
+
+/// Players whose words cover less of the board than this, on average, are
+/// told to practice spreading their words out
+const LOW_TILE_COVERAGE: f64 = 0.6;
+
+/// Fewest games a profile needs before it suggests anything
+pub const MIN_PROFILE_GAMES: usize = 3;
+
+/// A habit worth practising against. Each is also a focus for a practice
+/// board that exercises it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Weakness {
+    /// Settling for short words; practised on boards whose best words are long
+    ShortWords,
+    /// Avoiding wildcards; practised on boards whose best words need them
+    RareWildcards,
+    /// Words bunched in one corner; practised on boards whose best words
+    /// spread across the grid
+    LowCoverage,
+}
+
+impl Weakness {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Weakness::ShortWords => "short_words",
+            Weakness::RareWildcards => "rare_wildcards",
+            Weakness::LowCoverage => "low_coverage",
+        }
+    }
+
+    /// board_fit rates how well a board exercises this weakness from its
+    /// best words; higher is better
+    pub fn board_fit(&self, board: &Board, optimal_words: &[Answer]) -> f64 {
+        let measures = WordMeasures::of(board, optimal_words);
+        match self {
+            Weakness::ShortWords => measures.average_word_length,
+            Weakness::RareWildcards => measures.wildcard_share,
+            Weakness::LowCoverage => measures.tile_coverage,
+        }
+    }
+}
+
+impl fmt::Display for Weakness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// What a set of words on one board looks like
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct WordMeasures {
+    average_word_length: f64,
+    /// Share of words, 0 to 1, that can only be spelled with a wildcard
+    wildcard_share: f64,
+    /// Share of the board's tiles, 0 to 1, on the path of some word that
+    /// uses the fewest wildcards
+    tile_coverage: f64,
+}
+
+impl WordMeasures {
+    fn of(board: &Board, answers: &[Answer]) -> Self {
+        let tiles: usize = board.rows.iter().map(|row| row.tiles.len()).sum();
+        let answers: Vec<&Answer> = answers.iter().filter(|a| !a.paths.is_empty()).collect();
+        if answers.is_empty() || tiles == 0 {
+            return Self::default();
+        }
+
+        let words = answers.len() as f64;
+        let letters: usize = answers.iter().map(|a| a.word.chars().count()).sum();
+        let wildcard_words = answers.iter().filter(|a| a.requires_wildcard()).count();
+        let covered: HashSet<(i32, i32)> = answers
+            .iter()
+            .filter_map(|answer| {
+                answer
+                    .paths
+                    .iter()
+                    .min_by_key(|path| path.tiles.iter().filter(|tile| tile.is_wildcard).count())
+            })
+            .flat_map(|path| path.tiles.iter().map(|tile| (tile.row, tile.col)))
+            .collect();
+
+        Self {
+            average_word_length: letters as f64 / words,
+            wildcard_share: wildcard_words as f64 / words,
+            tile_coverage: covered.len() as f64 / tiles as f64,
+        }
+    }
+}
+
+/// PlayProfile summarises how a player has played their recent games
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayProfile {
+    pub games: usize,
+    pub average_word_length: f64,
+    pub wildcard_share: f64,
+    /// Mean share of each board's tiles their words covered
+    pub tile_coverage: f64,
+}
+
+impl PlayProfile {
+    /// measure builds a profile from each board a player completed and their
+    /// words on it, as found by the engine
+    pub fn measure(games: &[(Board, Vec<Answer>)]) -> Self {
+        let measures: Vec<WordMeasures> = games
+            .iter()
+            .filter(|(_, answers)| answers.iter().any(|a| !a.paths.is_empty()))
+            .map(|(board, answers)| WordMeasures::of(board, answers))
+            .collect();
+        if measures.is_empty() {
+            return Self::default();
+        }
+
+        let n = measures.len() as f64;
+        let mean = |f: fn(&WordMeasures) -> f64| measures.iter().map(f).sum::<f64>() / n;
+        Self {
+            games: measures.len(),
+            average_word_length: mean(|m| m.average_word_length),
+            wildcard_share: mean(|m| m.wildcard_share),
+            tile_coverage: mean(|m| m.tile_coverage),
+        }
+    }
+
+    /// weaknesses lists what the player should practice, most pronounced
+    /// first. Profiles of fewer than MIN_PROFILE_GAMES games suggest nothing.
+    pub fn weaknesses(&self) -> Vec<Weakness> {
+        if self.games < MIN_PROFILE_GAMES {
+            return Vec::new();
+        }
+
+        // How far short of each threshold the player falls, relative to it
+        let mut shortfalls: Vec<(Weakness, f64)> = [
+            (
+                Weakness::ShortWords,
+                self.average_word_length,
+                SHORT_WORD_LENGTH,
+            ),
+            (
+                Weakness::RareWildcards,
+                self.wildcard_share,
+                RARE_WILDCARD_SHARE,
+            ),
+            (Weakness::LowCoverage, self.tile_coverage, LOW_TILE_COVERAGE),
+        ]
+        .into_iter()
+        .filter(|(_, value, threshold)| value < threshold)
+        .map(|(weakness, value, threshold)| (weakness, (threshold - value) / threshold))
+        .collect();
+
+        shortfalls.sort_by(|a, b| b.1.total_cmp(&a.1));
+        shortfalls
+            .into_iter()
+            .map(|(weakness, _)| weakness)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_test_board;
+
+    fn answers(board: &Board, words: &[&str]) -> Vec<Answer> {
+        words.iter().map(|word| board.paths_for(word)).collect()
+    }
+
+    #[test]
+    fn test_profile_finds_short_bunched_words() {
+        // c*xx
+        // atxx
+        // xxxx
+        // xxxx
+        let board = create_test_board("c*xxatxxxxxxxxxx");
+        let games: Vec<(Board, Vec<Answer>)> = (0..3)
+            .map(|_| (board.clone(), answers(&board, &["cat", "at"])))
+            .collect();
+
+        let profile = PlayProfile::measure(&games);
+        assert_eq!(profile.games, 3);
+        assert_eq!(profile.average_word_length, 2.5);
+        assert_eq!(profile.tile_coverage, 3.0 / 16.0);
+        assert_eq!(
+            profile.weaknesses(),
+            vec![
+                Weakness::RareWildcards,
+                Weakness::LowCoverage,
+                Weakness::ShortWords
+            ]
+        );
+
+        // Too few games to judge
+        assert!(PlayProfile::measure(&games[..2]).weaknesses().is_empty());
+    }
+
+    #[test]
+    fn test_board_fit_prefers_boards_exercising_the_weakness() {
+        let board = create_test_board("c*xxatxxxxxxxxxx");
+        let plain = answers(&board, &["cat"]);
+        let wild = answers(&board, &["cot"]);
+
+        assert!(
+            Weakness::RareWildcards.board_fit(&board, &wild)
+                > Weakness::RareWildcards.board_fit(&board, &plain)
+        );
+        assert_eq!(
+            Weakness::ShortWords.board_fit(&board, &plain),
+            Weakness::ShortWords.board_fit(&board, &wild)
+        );
+    }
+}
@@ -0,0 +1,549 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use moka::future::Cache;
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+    sync::Arc,
+    time::Duration,
+};
+use tracing::{info, warn};
+
+use crate::db::{
+    models::{DbGame, NewGameWithAnswers},
+    Repository,
+};
+use crate::game::theme::Theme;
+use crate::game_generator::GameGenerator;
+use crate::social::Post;
+
+/// Most games that can be staged in a single batch
+pub const MAX_BATCH_SIZE: usize = 31;
+
+/// How long a staged batch waits to be published before it is dropped
+const STAGED_BATCH_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug)]
+pub enum BatchError {
+    /// The requested dates can't be staged (bad format, duplicates, past dates, too many)
+    InvalidRequest(String),
+    /// Games already exist for these dates
+    DateConflict(Vec<String>),
+    /// No staged batch with the given id (never staged, already published, or expired)
+    NotFound,
+    Internal(anyhow::Error),
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::InvalidRequest(message) => write!(f, "{message}"),
+            BatchError::DateConflict(dates) => {
+                write!(f, "games already exist for {}", dates.join(", "))
+            }
+            BatchError::NotFound => write!(f, "staged batch not found"),
+            BatchError::Internal(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+impl From<anyhow::Error> for BatchError {
+    fn from(e: anyhow::Error) -> Self {
+        BatchError::Internal(e)
+    }
+}
+
+/// A set of generated games held in memory until they are published together
+#[derive(Debug)]
+pub struct StagedBatch {
+    pub id: String,
+    pub name: Option<String>,
+    /// Staged games in date order. Sequence numbers are assigned on publish.
+    pub games: Vec<NewGameWithAnswers>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The games created by publishing a staged batch
+#[derive(Debug)]
+pub struct PublishedBatch {
+    pub name: Option<String>,
+    pub games: Vec<DbGame>,
+}
+
+/// BatchPublisher stages batches of games, such as a themed week, and
+/// publishes each batch in a single transaction so either every game in it
+/// goes live or none do
+pub struct BatchPublisher<R: Repository, P: Post> {
+    repository: R,
+    game_generator: GameGenerator<R>,
+    staged: Cache<String, Arc<StagedBatch>>,
+    poster: Option<Arc<P>>,
+}
+
+impl<R: Repository + Clone, P: Post> Clone for BatchPublisher<R, P> {
+    fn clone(&self) -> Self {
+        Self {
+            repository: self.repository.clone(),
+            game_generator: self.game_generator.clone(),
+            staged: self.staged.clone(),
+            poster: self.poster.clone(),
+        }
+    }
+}
+
+impl<R, P> BatchPublisher<R, P>
+where
+    R: Repository + Clone + Send + Sync + 'static,
+    P: Post + Send + Sync + 'static,
+{
+    pub fn new(repository: R, game_generator: GameGenerator<R>) -> Self {
+        let staged = Cache::builder()
+            .max_capacity(10)
+            .time_to_live(STAGED_BATCH_TTL)
+            .build();
+
+        Self {
+            repository,
+            game_generator,
+            staged,
+            poster: None,
+        }
+    }
+
+    /// Sets the poster used to announce published batches
+    pub fn with_poster(mut self, poster: P) -> Self {
+        self.poster = Some(Arc::new(poster));
+        self
+    }
+
+    /// Sets the generator games are staged with
+    pub fn with_game_generator(mut self, game_generator: GameGenerator<R>) -> Self {
+        self.game_generator = game_generator;
+        self
+    }
+
+    /// Validates the dates and generates a game for each one without writing
+    /// anything to the database. `themes` maps dates in the batch to theme
+    /// specs, taking the place of any theme scheduled for those dates.
+    pub async fn stage(
+        &self,
+        name: Option<String>,
+        dates: &[String],
+        themes: &HashMap<String, String>,
+    ) -> Result<Arc<StagedBatch>, BatchError> {
+        let dates = validate_batch_dates(dates, Utc::now().date_naive())?;
+        let themes = validate_batch_themes(themes, &dates)?;
+        self.check_date_conflicts(&dates).await?;
+
+        let mut games = Vec::with_capacity(dates.len());
+        for date in &dates {
+            games.push(
+                self.game_generator
+                    .stage_game_for_date(date, themes.get(date))
+                    .await?,
+            );
+        }
+
+        let batch = Arc::new(StagedBatch {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            games,
+            created_at: Utc::now(),
+        });
+        self.staged.insert(batch.id.clone(), batch.clone()).await;
+
+        info!(
+            "Staged batch {} with {} games ({} to {})",
+            batch.id,
+            dates.len(),
+            dates[0],
+            dates[dates.len() - 1]
+        );
+        Ok(batch)
+    }
+
+    /// Returns a staged batch that hasn't been published or discarded yet
+    pub async fn get(&self, batch_id: &str) -> Option<Arc<StagedBatch>> {
+        self.staged.get(batch_id).await
+    }
+
+    /// Drops a staged batch. Returns false if there was nothing to drop.
+    pub async fn discard(&self, batch_id: &str) -> bool {
+        self.staged.remove(batch_id).await.is_some()
+    }
+
+    /// Writes every game in a staged batch in one transaction. Dates are
+    /// re-checked first because games may have been generated for them since
+    /// the batch was staged; on any failure nothing is written and the batch
+    /// stays staged.
+    pub async fn publish(&self, batch_id: &str) -> Result<PublishedBatch, BatchError> {
+        let batch = self
+            .staged
+            .get(batch_id)
+            .await
+            .ok_or(BatchError::NotFound)?;
+
+        let dates: Vec<String> = batch.games.iter().map(|g| g.game.date.clone()).collect();
+        self.check_date_conflicts(&dates).await?;
+
+        let first_sequence_number = self.repository.get_next_sequence_number().await?;
+        let games = batch
+            .games
+            .iter()
+            .cloned()
+            .zip(first_sequence_number..)
+            .map(|(mut staged, sequence_number)| {
+                staged.game.sequence_number = sequence_number;
+                staged
+            })
+            .collect();
+
+        let games = self.repository.create_games_with_answers(games).await?;
+        self.staged.invalidate(batch_id).await;
+
+        info!("Published batch {} with {} games", batch_id, games.len());
+        Ok(PublishedBatch {
+            name: batch.name.clone(),
+            games,
+        })
+    }
+
+    /// Posts one announcement covering the whole batch. Returns whether a post
+    /// was made.
+    pub async fn announce(&self, batch: &PublishedBatch) -> bool {
+        let Some(poster) = &self.poster else {
+            return false;
+        };
+
+        match poster
+            .post(announcement_message(batch.name.as_deref(), &batch.games))
+            .await
+        {
+            Ok(()) => true,
+            Err(e) => {
+                warn!("Failed to announce published batch: {}", e);
+                false
+            }
+        }
+    }
+
+    async fn check_date_conflicts(&self, dates: &[String]) -> Result<(), BatchError> {
+        let mut conflicts = Vec::new();
+        for date in dates {
+            if self.repository.game_exists_for_date(date).await? {
+                conflicts.push(date.clone());
+            }
+        }
+
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(BatchError::DateConflict(conflicts))
+        }
+    }
+}
+
+/// validate_batch_dates checks that dates are well formed, unique, not in the
+/// past and within the batch size limit, and returns them sorted
+fn validate_batch_dates(dates: &[String], today: NaiveDate) -> Result<Vec<String>, BatchError> {
+    if dates.is_empty() {
+        return Err(BatchError::InvalidRequest(
+            "a batch needs at least one date".to_string(),
+        ));
+    }
+    if dates.len() > MAX_BATCH_SIZE {
+        return Err(BatchError::InvalidRequest(format!(
+            "a batch can contain at most {MAX_BATCH_SIZE} games"
+        )));
+    }
+
+    let mut parsed = BTreeSet::new();
+    for date in dates {
+        let day = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| BatchError::InvalidRequest(format!("invalid date: {date}")))?;
+        if day < today {
+            return Err(BatchError::InvalidRequest(format!(
+                "date is in the past: {date}"
+            )));
+        }
+        if !parsed.insert(day) {
+            return Err(BatchError::InvalidRequest(format!(
+                "duplicate date: {date}"
+            )));
+        }
+    }
+
+    Ok(parsed
+        .into_iter()
+        .map(|day| day.format("%Y-%m-%d").to_string())
+        .collect())
+}
+
+/// validate_batch_themes parses each theme spec, checking it is for one of
+/// the batch's validated `dates`
+fn validate_batch_themes(
+    themes: &HashMap<String, String>,
+    dates: &[String],
+) -> Result<HashMap<String, Theme>, BatchError> {
+    themes
+        .iter()
+        .map(|(date, spec)| {
+            if !dates.contains(date) {
+                return Err(BatchError::InvalidRequest(format!(
+                    "theme is for a date outside the batch: {date}"
+                )));
+            }
+            let theme = spec
+                .parse()
+                .map_err(|e| BatchError::InvalidRequest(format!("{date}: {e}")))?;
+            Ok((date.clone(), theme))
+        })
+        .collect()
+}
+
+/// announcement_message builds the single post announcing a published batch
+fn announcement_message(name: Option<&str>, games: &[DbGame]) -> String {
+    let (Some(first), Some(last)) = (games.first(), games.last()) else {
+        return String::new();
+    };
+
+    let lineup = if games.len() == 1 {
+        format!(
+            "Pathfinder #{} for {} is ready to play!",
+            first.sequence_number, first.date
+        )
+    } else {
+        format!(
+            "Pathfinder #{}-#{} are ready, running {} through {}!",
+            first.sequence_number, last.sequence_number, first.date, last.date
+        )
+    };
+
+    match name {
+        Some(name) => format!("{name}: {lineup}"),
+        None => lineup,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::NewGame;
+    use crate::test_utils::create_new_test_game;
+
+    fn dates(values: &[&str]) -> Vec<String> {
+        values.iter().map(|d| d.to_string()).collect()
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 12, 1).unwrap()
+    }
+
+    #[test]
+    fn test_validate_batch_dates_sorts() {
+        let validated =
+            validate_batch_dates(&dates(&["2025-12-03", "2025-12-01", "2025-12-02"]), today())
+                .unwrap();
+        assert_eq!(
+            validated,
+            dates(&["2025-12-01", "2025-12-02", "2025-12-03"])
+        );
+    }
+
+    #[test]
+    fn test_validate_batch_dates_rejects_bad_input() {
+        for bad in [
+            dates(&[]),
+            dates(&["2025-12-02", "2025-12-02"]),
+            dates(&["12/02/2025"]),
+            dates(&["2025-11-30"]),
+        ] {
+            assert!(matches!(
+                validate_batch_dates(&bad, today()),
+                Err(BatchError::InvalidRequest(_))
+            ));
+        }
+
+        let too_many: Vec<String> = (0..=MAX_BATCH_SIZE as u64)
+            .map(|offset| {
+                (today() + chrono::Days::new(offset))
+                    .format("%Y-%m-%d")
+                    .to_string()
+            })
+            .collect();
+        assert!(validate_batch_dates(&too_many, today()).is_err());
+    }
+
+    #[test]
+    fn test_validate_batch_themes() {
+        let batch = dates(&["2025-12-24", "2025-12-25"]);
+        let themes = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(date, spec)| (date.to_string(), spec.to_string()))
+                .collect()
+        };
+
+        let validated =
+            validate_batch_themes(&themes(&[("2025-12-25", "word:snow")]), &batch).unwrap();
+        assert_eq!(
+            validated.get("2025-12-25"),
+            Some(&Theme::Word("snow".to_string()))
+        );
+        for bad in [
+            themes(&[("2025-12-26", "word:snow")]),
+            themes(&[("2025-12-25", "word:x-ray")]),
+        ] {
+            assert!(matches!(
+                validate_batch_themes(&bad, &batch),
+                Err(BatchError::InvalidRequest(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_announcement_message() {
+        let first = DbGame::new(NewGame {
+            date: "2025-12-20".to_string(),
+            sequence_number: 12,
+            ..create_new_test_game()
+        });
+        assert_eq!(
+            announcement_message(None, std::slice::from_ref(&first)),
+            "Pathfinder #12 for 2025-12-20 is ready to play!"
+        );
+
+        let last = DbGame::new(NewGame {
+            date: "2025-12-26".to_string(),
+            sequence_number: 18,
+            ..create_new_test_game()
+        });
+        assert_eq!(
+            announcement_message(Some("Holiday week"), &[first, last]),
+            "Holiday week: Pathfinder #12-#18 are ready, running 2025-12-20 through 2025-12-26!"
+        );
+    }
+
+    #[cfg(feature = "database-tests")]
+    mod database {
+        use super::super::*;
+        use crate::db::{models::NewGameAnswer, SqliteRepository};
+        use crate::test_utils::{create_new_test_game, create_test_game_engine};
+        use std::sync::Mutex;
+
+        #[derive(Default)]
+        struct RecordingPoster {
+            messages: Mutex<Vec<String>>,
+        }
+
+        impl Post for RecordingPoster {
+            async fn post(&self, message: String) -> anyhow::Result<()> {
+                self.messages.lock().unwrap().push(message);
+                Ok(())
+            }
+        }
+
+        fn publisher(
+            pool: sqlx::Pool<sqlx::Sqlite>,
+        ) -> BatchPublisher<SqliteRepository, RecordingPoster> {
+            let repository = SqliteRepository::new(pool);
+            let (game_engine, _wordlist) = create_test_game_engine();
+            let game_generator = GameGenerator::new(repository.clone(), game_engine);
+            BatchPublisher::new(repository, game_generator).with_poster(RecordingPoster::default())
+        }
+
+        /// Stage a batch directly, skipping board generation which the test
+        /// wordlist is too small for
+        async fn stage_test_batch(
+            publisher: &BatchPublisher<SqliteRepository, RecordingPoster>,
+            dates: &[&str],
+        ) -> String {
+            let games = dates
+                .iter()
+                .map(|date| {
+                    let mut game = create_new_test_game();
+                    game.date = date.to_string();
+                    game.sequence_number = 0;
+                    NewGameWithAnswers {
+                        game,
+                        answers: vec![NewGameAnswer {
+                            game_id: String::new(),
+                            word: "test".to_string(),
+                            path_stats: None,
+                        }],
+                        optimal_solution: None,
+                        density: None,
+                        quality: None,
+                    }
+                })
+                .collect();
+
+            let batch = Arc::new(StagedBatch {
+                id: "batch".to_string(),
+                name: Some("Theme week".to_string()),
+                games,
+                created_at: Utc::now(),
+            });
+            publisher.staged.insert(batch.id.clone(), batch).await;
+            "batch".to_string()
+        }
+
+        #[sqlx::test(migrations = "migrations/sqlite")]
+        async fn test_publish_creates_every_game(pool: sqlx::Pool<sqlx::Sqlite>) {
+            let publisher = publisher(pool);
+            let batch_id =
+                stage_test_batch(&publisher, &["2030-01-01", "2030-01-02", "2030-01-03"]).await;
+
+            let published = publisher.publish(&batch_id).await.unwrap();
+            let sequence_numbers: Vec<i32> =
+                published.games.iter().map(|g| g.sequence_number).collect();
+            assert_eq!(sequence_numbers, vec![1, 2, 3]);
+
+            for game in &published.games {
+                let words = publisher.repository.get_game_words(&game.id).await.unwrap();
+                assert_eq!(words, vec!["test".to_string()]);
+            }
+
+            // A published batch can't be published twice
+            assert!(matches!(
+                publisher.publish(&batch_id).await,
+                Err(BatchError::NotFound)
+            ));
+
+            assert!(publisher.announce(&published).await);
+            let messages = publisher.poster.as_ref().unwrap().messages.lock().unwrap();
+            assert_eq!(messages.len(), 1);
+            assert!(messages[0].starts_with("Theme week: Pathfinder #1-#3"));
+        }
+
+        #[sqlx::test(migrations = "migrations/sqlite")]
+        async fn test_publish_is_all_or_nothing_on_conflict(pool: sqlx::Pool<sqlx::Sqlite>) {
+            let publisher = publisher(pool);
+            let batch_id = stage_test_batch(&publisher, &["2030-01-01", "2030-01-02"]).await;
+
+            // Another game lands on one of the staged dates after staging
+            let mut existing = create_new_test_game();
+            existing.date = "2030-01-02".to_string();
+            publisher
+                .repository
+                .create_game_with_answers(existing, vec![], None)
+                .await
+                .unwrap();
+
+            match publisher.publish(&batch_id).await {
+                Err(BatchError::DateConflict(dates)) => {
+                    assert_eq!(dates, vec!["2030-01-02".to_string()])
+                }
+                other => panic!("expected a date conflict, got {other:?}"),
+            }
+
+            assert!(!publisher
+                .repository
+                .game_exists_for_date("2030-01-01")
+                .await
+                .unwrap());
+            assert!(publisher.get(&batch_id).await.is_some());
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use moka::future::Cache;
+use std::time::Duration;
+
+use crate::db::models::DbGame;
+use crate::game::language::Language;
+use crate::http_api::ApiGame;
+
+/// Games kept at once, about three months' worth
+const MAX_GAMES: u64 = 100;
+/// Games rarely change once they exist, so this is only a backstop for a
+/// change nothing invalidated
+const GAME_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+const GAME_TTI: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Another way a game is looked up besides its id. Each language numbers
+/// and dates its games separately.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum GameAlias {
+    Date(Language, String),
+    Sequence(Language, i32),
+}
+
+impl GameAlias {
+    fn of(game: &ApiGame) -> [Self; 2] {
+        [
+            GameAlias::Date(game.language, game.date.clone()),
+            GameAlias::Sequence(game.language, game.sequence_number),
+        ]
+    }
+}
+
+/// Tags select the cached games to invalidate. A game matching any of them is
+/// dropped along with every alias pointing at it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GameTags {
+    pub game_id: Option<String>,
+    pub date: Option<String>,
+    pub sequence_number: Option<i32>,
+    /// The language `date` and `sequence_number` are in
+    pub language: Language,
+}
+
+impl GameTags {
+    pub fn of(game: &ApiGame) -> Self {
+        Self {
+            game_id: Some(game.id.clone()),
+            date: Some(game.date.clone()),
+            sequence_number: Some(game.sequence_number),
+            language: game.language,
+        }
+    }
+
+    pub fn of_db_game(game: &DbGame) -> Self {
+        Self {
+            game_id: Some(game.id.clone()),
+            date: Some(game.date.clone()),
+            sequence_number: Some(game.sequence_number),
+            language: game.game_language(),
+        }
+    }
+}
+
+/// GameCache holds each game once under its id, with its date and sequence
+/// number as aliases to that id. Because an alias is only followed to a game
+/// that still carries it, invalidating a game by any one tag is enough to
+/// stop it being served under the others.
+#[derive(Clone)]
+pub struct GameCache {
+    games: Cache<String, ApiGame>,
+    aliases: Cache<GameAlias, String>,
+}
+
+impl Default for GameCache {
+    fn default() -> Self {
+        Self {
+            games: Cache::builder()
+                .max_capacity(MAX_GAMES)
+                .time_to_live(GAME_TTL)
+                .time_to_idle(GAME_TTI)
+                .build(),
+            // Two aliases per game
+            aliases: Cache::builder()
+                .max_capacity(MAX_GAMES * 2)
+                .time_to_live(GAME_TTL)
+                .build(),
+        }
+    }
+}
+
+impl GameCache {
+    pub async fn get(&self, alias: &GameAlias) -> Option<ApiGame> {
+        let game_id = self.aliases.get(alias).await?;
+        self.games
+            .get(&game_id)
+            .await
+            .filter(|game| matches_alias(game, alias))
+    }
+
+    /// insert caches `game`, first dropping any other game it shares an id,
+    /// date or sequence number with. Returns how many were dropped.
+    pub async fn insert(&self, game: ApiGame) -> u64 {
+        let displaced = self.invalidate(&GameTags::of(&game)).await;
+        for alias in GameAlias::of(&game) {
+            self.aliases.insert(alias, game.id.clone()).await;
+        }
+        self.games.insert(game.id.clone(), game).await;
+        displaced
+    }
+
+    /// invalidate drops every cached game matching `tags`, returning how many
+    /// were dropped
+    pub async fn invalidate(&self, tags: &GameTags) -> u64 {
+        let mut game_ids: Vec<String> = tags.game_id.iter().cloned().collect();
+        let aliases = tags
+            .date
+            .clone()
+            .map(|date| GameAlias::Date(tags.language, date))
+            .into_iter()
+            .chain(
+                tags.sequence_number
+                    .map(|sequence_number| GameAlias::Sequence(tags.language, sequence_number)),
+            );
+        for alias in aliases {
+            if let Some(game_id) = self.aliases.remove(&alias).await {
+                game_ids.push(game_id);
+            }
+        }
+
+        let mut invalidated = 0;
+        for game_id in game_ids {
+            let Some(game) = self.games.remove(&game_id).await else {
+                continue;
+            };
+            invalidated += 1;
+            // Its other aliases may already point at a newer game
+            for alias in GameAlias::of(&game) {
+                if self.aliases.get(&alias).await.as_ref() == Some(&game.id) {
+                    self.aliases.invalidate(&alias).await;
+                }
+            }
+        }
+        invalidated
+    }
+
+    /// invalidate_all drops every game, returning how many were held
+    pub fn invalidate_all(&self) -> u64 {
+        let held = self.games.entry_count();
+        self.games.invalidate_all();
+        self.aliases.invalidate_all();
+        held
+    }
+
+    pub fn entry_count(&self) -> u64 {
+        self.games.entry_count()
+    }
+}
+
+fn matches_alias(game: &ApiGame, alias: &GameAlias) -> bool {
+    match alias {
+        GameAlias::Date(language, date) => game.language == *language && game.date == *date,
+        GameAlias::Sequence(language, sequence_number) => {
+            game.language == *language && game.sequence_number == *sequence_number
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::scoring::BonusRules;
+    use crate::http_api::ApiBoard;
+
+    fn game(id: &str, date: &str, sequence_number: i32) -> ApiGame {
+        ApiGame {
+            id: id.to_string(),
+            date: date.to_string(),
+            board: ApiBoard { tiles: vec![] },
+            threshold_score: 40,
+            sequence_number,
+            difficulty: None,
+            target_difficulty: None,
+            scoring_profile: "standard".to_string(),
+            bonus_rules: BonusRules::default(),
+            rules: Default::default(),
+            mode: Default::default(),
+            time_limit_seconds: None,
+            language: Language::English,
+            revision: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalidating_one_tag_drops_every_alias() {
+        let cache = GameCache::default();
+        cache.insert(game("a", "2025-01-01", 1)).await;
+        cache.insert(game("b", "2025-01-02", 2)).await;
+
+        assert_eq!(
+            cache
+                .invalidate(&GameTags {
+                    game_id: Some("a".to_string()),
+                    ..GameTags::default()
+                })
+                .await,
+            1
+        );
+        assert!(cache
+            .get(&GameAlias::Date(
+                Language::English,
+                "2025-01-01".to_string()
+            ))
+            .await
+            .is_none());
+        assert!(cache
+            .get(&GameAlias::Sequence(Language::English, 1))
+            .await
+            .is_none());
+
+        assert_eq!(
+            cache
+                .invalidate(&GameTags {
+                    sequence_number: Some(2),
+                    ..GameTags::default()
+                })
+                .await,
+            1
+        );
+        assert!(cache
+            .get(&GameAlias::Date(
+                Language::English,
+                "2025-01-02".to_string()
+            ))
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_regenerated_game_replaces_the_old_one() {
+        let cache = GameCache::default();
+        cache.insert(game("old", "2025-01-01", 1)).await;
+        assert_eq!(cache.insert(game("new", "2025-01-01", 1)).await, 1);
+
+        let by_date = cache
+            .get(&GameAlias::Date(
+                Language::English,
+                "2025-01-01".to_string(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(by_date.id, "new");
+        assert_eq!(
+            cache
+                .get(&GameAlias::Sequence(Language::English, 1))
+                .await
+                .unwrap()
+                .id,
+            "new"
+        );
+
+        // Dropping the old game's id leaves the new one alone
+        assert_eq!(
+            cache
+                .invalidate(&GameTags {
+                    game_id: Some("old".to_string()),
+                    ..GameTags::default()
+                })
+                .await,
+            0
+        );
+        assert!(cache
+            .get(&GameAlias::Sequence(Language::English, 1))
+            .await
+            .is_some());
+    }
+}
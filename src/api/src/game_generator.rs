@@ -1,30 +1,253 @@
+use crate::audit::{AuditActor, AuditLogger};
+use crate::calibration::{load_calibration, CalibrationConfig};
 use crate::db::{
-    models::{NewGame, NewGameAnswer, NewOptimalSolution},
+    models::{
+        DbGame, DbPracticeGame, NewGame, NewGameAnswer, NewGameWithAnswers, NewOptimalSolution,
+        NewPracticeGame, WordPathStats,
+    },
     Repository,
 };
-use crate::game::GameEngine;
+use crate::game::{
+    board::answer::Answer,
+    density::BoardDensity,
+    difficulty::{self, Difficulty},
+    language::{Language, LanguageEngines},
+    mode::GameMode,
+    quality::{join_issues, BoardQuality, BoardRejection, QualityIssue},
+    theme::{Theme, ThemeSchedule},
+    weakness::Weakness,
+    Board, GameEngine,
+};
+use crate::metrics::Metrics;
 use anyhow::Result;
-use chrono::{Duration, Utc};
+use chrono::{Datelike, Duration, NaiveDate, Utc};
 use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::env;
+use std::sync::Arc;
+use std::time::Instant;
 use tracing::{error, info, warn};
 
+/// Version of the generation rules new games are made with. Bump it whenever
+/// a change would give a different board for the same date, such as new
+/// thresholds or attempt counts, letter frequencies or a new wordlist, and
+/// keep the old rules in `GenerationConfig::for_version` so games made under
+/// every version can still be regenerated exactly.
+pub const CURRENT_GENERATION_VERSION: i32 = 4;
+
+/// GenerationConfig is everything besides the date that decides a daily
+/// board. Together with the date it fixes every seed that is tried, so the
+/// board never depends on the database or on the machine generating it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GenerationConfig {
+    pub version: i32,
+    /// Score the best five words of a board must reach, unless the week
+    /// curve sets one for the day
+    pub threshold_score: i32,
+    /// How many times the threshold is cut by 25% when no board reaches it
+    pub max_threshold_reductions: u32,
+    /// Boards tried at each threshold
+    pub attempts_per_threshold: u32,
+    /// How hard each day of the week should be. When set, every board at a
+    /// threshold is tried and the first in the day's band wins, falling back
+    /// to the first that qualified at all.
+    pub week_curve: Option<WeekCurve>,
+    /// Whether boards the quality analyzer finds issues with are passed over
+    /// for one without, falling back to the first that qualified as with the
+    /// week curve
+    pub avoid_board_issues: bool,
+}
+
+/// WeekCurve shapes difficulty across the week, one target per weekday
+/// starting on Monday
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeekCurve {
+    pub days: [DayTarget; 7],
+    /// Points the threshold is moved by for each band a board lands away
+    /// from its day's target: raised when the board is easier than wanted,
+    /// lowered when it is harder. 0 publishes the threshold unchanged.
+    pub feedback_step: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DayTarget {
+    pub threshold_score: i32,
+    pub difficulty: Difficulty,
+}
+
+impl WeekCurve {
+    fn new(days: [(i32, Difficulty); 7], feedback_step: i32) -> Self {
+        Self {
+            days: days.map(|(threshold_score, difficulty)| DayTarget {
+                threshold_score,
+                difficulty,
+            }),
+            feedback_step,
+        }
+    }
+
+    pub fn day(&self, date: NaiveDate) -> DayTarget {
+        self.days[date.weekday().num_days_from_monday() as usize]
+    }
+
+    /// The threshold to publish for a board rated `rated` on a day aiming
+    /// for `target`. It is only raised up to 90% of what the board's best
+    /// words can score, so beating it never takes the exact optimal play.
+    pub fn adjust_threshold(
+        &self,
+        threshold_score: i32,
+        target: Difficulty,
+        rated: Difficulty,
+        optimal_score: i32,
+    ) -> i32 {
+        let bands_easier = target as i32 - rated as i32;
+        let ceiling = threshold_score.max(optimal_score * 9 / 10);
+        (threshold_score + bands_easier * self.feedback_step)
+            .min(ceiling)
+            .max(1)
+    }
+}
+
+impl Default for GenerationConfig {
+    fn default() -> Self {
+        Self::for_version(CURRENT_GENERATION_VERSION).expect("current version has a config")
+    }
+}
+
+impl GenerationConfig {
+    /// The rules games of `version` were generated with, None for versions
+    /// this build doesn't know
+    pub fn for_version(version: i32) -> Option<Self> {
+        match version {
+            1 => Some(Self {
+                version,
+                threshold_score: 40,
+                // Only allow one 25% reduction (40 -> 30)
+                max_threshold_reductions: 1,
+                attempts_per_threshold: 5,
+                week_curve: None,
+                avoid_board_issues: false,
+            }),
+            // Easier early in the week, building to the hardest boards on
+            // the weekend
+            2 => Some(Self {
+                version,
+                week_curve: Some(WeekCurve::new(
+                    [
+                        (40, Difficulty::Easy),
+                        (40, Difficulty::Easy),
+                        (40, Difficulty::Medium),
+                        (40, Difficulty::Medium),
+                        (40, Difficulty::Hard),
+                        (40, Difficulty::Hard),
+                        (40, Difficulty::Medium),
+                    ],
+                    0,
+                )),
+                ..Self::for_version(1)?
+            }),
+            // The threshold climbs with the difficulty from an easy Monday to
+            // a hard Sunday, and boards that miss their band move it
+            3 => Some(Self {
+                version,
+                week_curve: Some(WeekCurve::new(
+                    [
+                        (30, Difficulty::Easy),
+                        (35, Difficulty::Easy),
+                        (40, Difficulty::Medium),
+                        (40, Difficulty::Medium),
+                        (45, Difficulty::Medium),
+                        (50, Difficulty::Hard),
+                        (50, Difficulty::Hard),
+                    ],
+                    5,
+                )),
+                ..Self::for_version(1)?
+            }),
+            // Version 3's week, preferring boards without quality issues
+            4 => Some(Self {
+                version,
+                avoid_board_issues: true,
+                ..Self::for_version(3)?
+            }),
+            _ => None,
+        }
+    }
+
+    /// The difficulty to aim for on `date`, if any
+    pub fn target_for(&self, date: NaiveDate) -> Option<Difficulty> {
+        self.week_curve.map(|curve| curve.day(date).difficulty)
+    }
+
+    /// The threshold generation starts from on `date`
+    pub fn threshold_for(&self, date: NaiveDate) -> i32 {
+        self.week_curve
+            .map(|curve| curve.day(date).threshold_score)
+            .unwrap_or(self.threshold_score)
+    }
+}
+
 #[derive(Clone)]
 pub struct GameGenerator<R: Repository> {
     repository: R,
     game_engine: GameEngine,
+    languages: LanguageEngines,
+    themes: Arc<ThemeSchedule>,
+    metrics: Metrics,
+    calibration: CalibrationConfig,
+    audit: AuditLogger<R>,
 }
 
 impl<R: Repository> GameGenerator<R> {
-    pub fn new(repository: R, game_engine: GameEngine) -> Self {
+    pub fn new(repository: R, game_engine: GameEngine) -> Self
+    where
+        R: Clone,
+    {
         Self {
+            audit: AuditLogger::new(repository.clone()),
             repository,
             game_engine,
+            languages: LanguageEngines::default(),
+            themes: Arc::new(ThemeSchedule::default()),
+            metrics: Metrics::default(),
+            calibration: CalibrationConfig::default(),
         }
     }
 
-    /// Generate games for the past week and next 3 days if they don't already exist
-    pub async fn generate_missing_games(&self) -> Result<()> {
+    /// Records how long each board takes to generate
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Also generates daily games in each of these languages besides English
+    pub fn with_languages(mut self, languages: LanguageEngines) -> Self {
+        self.languages = languages;
+        self
+    }
+
+    /// Sets the themes scheduled for particular dates
+    pub fn with_themes(mut self, themes: ThemeSchedule) -> Self {
+        self.themes = Arc::new(themes);
+        self
+    }
+
+    /// Calibrates new English daily games' thresholds against how players
+    /// fared on recent ones
+    pub fn with_calibration(mut self, calibration: CalibrationConfig) -> Self {
+        self.calibration = calibration;
+        self
+    }
+
+    /// Generate games for the past week and next 3 days if they don't already exist.
+    /// Returns the games that were newly generated. Every date is tried even
+    /// when one fails, but any failure fails the whole pass, after the other
+    /// dates' games are stored, so a retry only has the failed dates left.
+    pub async fn generate_missing_games(&self) -> Result<Vec<DbGame>> {
         let today = Utc::now().date_naive();
+        let mut generated = Vec::new();
+        let mut failed = Vec::new();
 
         // Generate games for the past 7 days, in case this is the first launch or the app has had downtime.
         for days_back in 1..=7 {
@@ -38,9 +261,11 @@ impl<R: Repository> GameGenerator<R> {
                             "Generated game for past date: {} with ID: {}",
                             date_str, game.id
                         );
+                        generated.push(game);
                     }
                     Err(e) => {
                         error!("Failed to generate game for past date {}: {}", date_str, e);
+                        failed.push(date_str);
                     }
                 }
             } else {
@@ -57,9 +282,11 @@ impl<R: Repository> GameGenerator<R> {
                 match self.generate_game_for_date(&date_str).await {
                     Ok(game) => {
                         info!("Generated game for date: {} with ID: {}", date_str, game.id);
+                        generated.push(game);
                     }
                     Err(e) => {
                         error!("Failed to generate game for date {}: {}", date_str, e);
+                        failed.push(date_str);
                     }
                 }
             } else {
@@ -67,128 +294,719 @@ impl<R: Repository> GameGenerator<R> {
             }
         }
 
-        Ok(())
+        self.generate_missing_language_games(today, &mut generated, &mut failed)
+            .await?;
+        if !failed.is_empty() {
+            anyhow::bail!(
+                "Failed to generate {} of the missing games ({}); generated {}",
+                failed.len(),
+                failed.join(", "),
+                generated.len()
+            );
+        }
+        Ok(generated)
     }
 
-    /// Generate a single game for a specific date
-    pub async fn generate_game_for_date(&self, date: &str) -> Result<crate::db::models::DbGame> {
-        let mut threshold_score = 40;
-        let max_threshold_reductions = 1; // Only allow one 25% reduction (40 -> 30)
-
-        for reduction_attempt in 0..=max_threshold_reductions {
-            for generation_attempt in 1..=5 {
-                let seed = create_seed(date, reduction_attempt, generation_attempt);
-                let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    /// Generate the same days' games in each language besides English that
+    /// has an engine, adding them to `generated` and the dates that failed to
+    /// `failed`
+    async fn generate_missing_language_games(
+        &self,
+        today: NaiveDate,
+        generated: &mut Vec<DbGame>,
+        failed: &mut Vec<String>,
+    ) -> Result<()> {
+        for language in self.languages.languages() {
+            for days in -7..=3 {
+                let date_str = (today + Duration::days(days))
+                    .format("%Y-%m-%d")
+                    .to_string();
+                if self
+                    .repository
+                    .get_game_by_date_in_language(&date_str, language)
+                    .await?
+                    .is_some()
+                {
+                    continue;
+                }
 
                 match self
-                    .game_engine
-                    .try_generate_valid_board(&mut rng, threshold_score)
+                    .generate_game_for_date_in_language(&date_str, language)
                     .await
                 {
-                    Ok((board, valid_answers, (optimal_words, optimal_metadata))) => {
-                        // Convert board to JSON for storage
-                        let serializable_board =
-                            crate::game::conversion::SerializableBoard::from(&board);
-                        let board_data = serde_json::to_string(&serializable_board)?;
-
-                        let sequence_number = self.repository.get_next_sequence_number().await?;
-                        let new_game = NewGame {
-                            date: date.to_string(),
-                            board_data,
-                            threshold_score,
-                            sequence_number,
-                        };
+                    Ok(game) => {
+                        info!(
+                            "Generated {} game for date: {} with ID: {}",
+                            language, date_str, game.id
+                        );
+                        generated.push(game);
+                    }
+                    Err(e) => {
+                        error!(
+                            "Failed to generate {} game for date {}: {}",
+                            language, date_str, e
+                        );
+                        failed.push(format!("{language} {date_str}"));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
 
-                        // Prepare game answers entries BEFORE creating the game
-                        let mut game_answers = Vec::new();
-                        // Use a temporary game_id that will be replaced by the actual ID
-                        let temp_game_id = uuid::Uuid::new_v4().to_string();
+    /// Generate a single game for a specific date
+    pub async fn generate_game_for_date(&self, date: &str) -> Result<DbGame> {
+        let mut staged = self.stage_game_for_date(date, None).await?;
+        staged.game.sequence_number = self.repository.get_next_sequence_number().await?;
 
-                        for answer in &valid_answers {
-                            game_answers.push(NewGameAnswer {
-                                game_id: temp_game_id.clone(), // Will be replaced in the atomic create
-                                word: answer.word.clone(),
-                            });
-                        }
+        // Create game, answers and density atomically
+        let game = self
+            .repository
+            .create_games_with_answers(vec![staged])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("no game was created for {}", date))?;
+        self.record_generated(&game).await;
+        Ok(game)
+    }
 
-                        // Prepare optimal solution data
-                        let optimal_words_and_scores: Vec<(String, i32)> = optimal_words
-                            .iter()
-                            .map(|answer| (answer.word.clone(), answer.score()))
-                            .collect();
-                        let optimal_solution_json =
-                            serde_json::to_string(&optimal_words_and_scores)?;
-
-                        let optimal_solution = NewOptimalSolution {
-                            game_id: temp_game_id.clone(), // Will be replaced in the atomic create
-                            words_and_scores: optimal_solution_json,
-                            total_score: optimal_metadata.total_score,
-                        };
+    /// Generate and store the daily game for a date in a language. Boards in
+    /// languages besides English are drawn with the language's letter
+    /// frequencies, scored with its scheme, numbered in their own sequence
+    /// and never themed, since themes' words are English.
+    pub async fn generate_game_for_date_in_language(
+        &self,
+        date: &str,
+        language: Language,
+    ) -> Result<DbGame> {
+        if language == Language::English {
+            return self.generate_game_for_date(date).await;
+        }
+        let engine = self
+            .languages
+            .get(language)
+            .ok_or_else(|| anyhow::anyhow!("no {} wordlist is loaded", language))?;
+
+        let config = GenerationConfig::default();
+        let started = Instant::now();
+        let generated = generate_daily_board_with(engine, date, config, None).await?;
+        self.metrics.record_generation("daily", started.elapsed());
+
+        let mut staged = self.stage_generated(engine, date, config, generated, None)?;
+        staged.game.sequence_number = self
+            .repository
+            .get_next_sequence_number_in_language(language)
+            .await?;
+
+        let game = self
+            .repository
+            .create_games_with_answers(vec![staged])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("no {} game was created for {}", language, date))?;
+        self.record_generated(&game).await;
+        Ok(game)
+    }
+
+    /// Generate and store the blitz game for a date, which players must
+    /// submit within `time_limit_seconds` of opening. Blitz boards are drawn
+    /// from their own seeds, so they never repeat the date's daily board, and
+    /// are numbered in their own sequence.
+    pub async fn generate_blitz_game_for_date(
+        &self,
+        date: &str,
+        time_limit_seconds: i32,
+    ) -> Result<DbGame> {
+        let day = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| anyhow::anyhow!("invalid game date: {}", date))?;
+        let config = GenerationConfig::default();
+        let started = Instant::now();
+        let generated = generate_qualifying_board(
+            &self.game_engine,
+            config,
+            None,
+            None,
+            &format!("blitz {date}"),
+            |reduction_attempt, generation_attempt| {
+                create_seed(day, reduction_attempt, generation_attempt) + BLITZ_SEED_OFFSET
+            },
+        )
+        .await?;
+        self.metrics.record_generation("blitz", started.elapsed());
+
+        let mut staged = self.stage_generated(&self.game_engine, date, config, generated, None)?;
+        staged.game.mode = GameMode::Blitz.to_string();
+        staged.game.time_limit_seconds = Some(time_limit_seconds);
+        staged.game.sequence_number = self
+            .repository
+            .get_next_sequence_number_in_mode(GameMode::Blitz)
+            .await?;
+
+        let game = self
+            .repository
+            .create_games_with_answers(vec![staged])
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("no blitz game was created for {}", date))?;
+        self.record_generated(&game).await;
+        Ok(game)
+    }
+
+    /// Generate the next revision of a daily game's board and store it in
+    /// the game's place, for when a published board turns out to be broken.
+    /// The new board keeps the game's date, language, number and theme. The
+    /// replaced game is kept along with the entries played on it, but is no
+    /// longer served for its date. Returns None if the game was already
+    /// replaced.
+    pub async fn replace_game(&self, game: &DbGame) -> Result<Option<DbGame>> {
+        if game.game_mode() != GameMode::Daily {
+            anyhow::bail!("only daily games can be replaced, not {} games", game.mode);
+        }
+        let language = game.game_language();
+        let engine = match language {
+            Language::English => &self.game_engine,
+            _ => self
+                .languages
+                .get(language)
+                .ok_or_else(|| anyhow::anyhow!("no {} wordlist is loaded", language))?,
+        };
+        let theme = game
+            .theme
+            .as_deref()
+            .map(str::parse::<Theme>)
+            .transpose()
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        let config = GenerationConfig::default();
+        let started = Instant::now();
+        let mut generated = generate_daily_board_revision(
+            engine,
+            &game.date,
+            config,
+            theme.as_ref(),
+            game.revision + 1,
+        )
+        .await?;
+        self.metrics.record_generation("daily", started.elapsed());
+        if language == Language::English {
+            self.calibrate_threshold(&game.date, &mut generated).await;
+        }
+
+        let staged = self.stage_generated(engine, &game.date, config, generated, theme.as_ref())?;
+        self.repository.replace_game(&game.id, staged).await
+    }
 
-                        // Create game and answers atomically
-                        let (game, _created_answers) = self
-                            .repository
-                            .create_game_with_answers(
-                                new_game,
-                                game_answers,
-                                Some(optimal_solution),
-                            )
-                            .await?;
+    /// Record a generated game in the audit log, so a disputed board can be
+    /// traced to when and under which rules it was made
+    async fn record_generated(&self, game: &DbGame) {
+        self.audit
+            .record(
+                &AuditActor::System,
+                "game.generate",
+                Some(&game.id),
+                None,
+                serde_json::to_value(game).ok(),
+            )
+            .await;
+    }
+
+    /// Generate the board, answers and optimal solution for a date without
+    /// writing anything to the database. The returned game has no sequence
+    /// number yet; callers assign one when they persist it. `theme` takes
+    /// the place of any theme scheduled for the date.
+    pub async fn stage_game_for_date(
+        &self,
+        date: &str,
+        theme: Option<&Theme>,
+    ) -> Result<NewGameWithAnswers> {
+        let config = GenerationConfig::default();
+        let theme = theme.or_else(|| self.themes.get(date));
+        let started = Instant::now();
+        let mut generated =
+            generate_daily_board_with(&self.game_engine, date, config, theme).await?;
+        self.metrics.record_generation("daily", started.elapsed());
+        self.calibrate_threshold(date, &mut generated).await;
+        self.stage_generated(&self.game_engine, date, config, generated, theme)
+    }
+
+    /// Move a board's published threshold to where recent games say it
+    /// should be, when calibration is on. Only the threshold changes, so the
+    /// board is still the one its date and generation version give. Stats
+    /// that can't be read leave the threshold as generated.
+    async fn calibrate_threshold(&self, date: &str, generated: &mut QualifyingBoard) {
+        let calibration = match load_calibration(&self.repository, &self.calibration).await {
+            Ok(Some(calibration)) => calibration,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Not calibrating the threshold for {}: {:#}", date, e);
+                return;
+            }
+        };
+        let threshold_score =
+            calibration.adjust_threshold(generated.threshold_score, generated.optimal_score);
+        if threshold_score != generated.threshold_score {
+            info!(
+                "Calibrating threshold for {} from {} to {} as players recently found {:.0}% of the optimal score over {} games",
+                date,
+                generated.threshold_score,
+                threshold_score,
+                calibration.median_share * 100.0,
+                calibration.games
+            );
+            generated.threshold_score = threshold_score;
+        }
+    }
+
+    fn stage_generated(
+        &self,
+        engine: &GameEngine,
+        date: &str,
+        config: GenerationConfig,
+        generated: QualifyingBoard,
+        theme: Option<&Theme>,
+    ) -> Result<NewGameWithAnswers> {
+        // Games without bonuses store none, like those from before bonuses
+        let bonus_rules = engine.bonus_rules();
+        let bonus_rules = (!bonus_rules.is_empty())
+            .then(|| serde_json::to_string(bonus_rules))
+            .transpose()?;
+        // Likewise games under the default rules store none
+        let game_rules = engine.game_rules();
+        let game_rules = (!game_rules.is_default())
+            .then(|| serde_json::to_string(game_rules))
+            .transpose()?;
+
+        let new_game = NewGame {
+            date: date.to_string(),
+            board_data: generated.board_data()?,
+            threshold_score: generated.threshold_score,
+            sequence_number: 0,
+            generation_version: config.version,
+            difficulty: Some(generated.difficulty().to_string()),
+            theme: theme.map(Theme::to_string),
+            scoring_scheme_version: engine.scoring_schemes().active().version,
+            bonus_rules,
+            game_rules,
+            mode: GameMode::Daily.to_string(),
+            time_limit_seconds: None,
+            language: engine.language().to_string(),
+        };
+
+        // Use a temporary game_id that will be replaced by the actual ID
+        let temp_game_id = uuid::Uuid::new_v4().to_string();
+
+        let game_answers = generated
+            .answers
+            .iter()
+            .map(|answer| NewGameAnswer {
+                game_id: temp_game_id.clone(), // Will be replaced in the atomic create
+                word: answer.word.clone(),
+                path_stats: Some(WordPathStats::from(answer)),
+            })
+            .collect();
 
+        // Prepare optimal solution data
+        let optimal_words_and_scores: Vec<(String, i32)> = generated
+            .optimal_words
+            .iter()
+            .map(|answer| {
+                let score = engine
+                    .bonus_rules()
+                    .answer_points(answer, engine.frequency_corpus());
+                (answer.word.clone(), score)
+            })
+            .collect();
+        let optimal_solution_json = serde_json::to_string(&optimal_words_and_scores)?;
+
+        let optimal_solution = NewOptimalSolution {
+            game_id: temp_game_id.clone(), // Will be replaced in the atomic create
+            words_and_scores: optimal_solution_json,
+            total_score: generated.optimal_score,
+        };
+
+        Ok(NewGameWithAnswers {
+            game: new_game,
+            answers: game_answers,
+            optimal_solution: Some(optimal_solution),
+            density: Some(generated.density),
+            quality: Some(generated.quality.clone()),
+        })
+    }
+
+    /// Generate and store a practice game that expires after `ttl`. The same
+    /// seed always produces the same board; without one a random seed is
+    /// picked and returned on the game so the board can be shared.
+    pub async fn generate_practice_game(
+        &self,
+        seed: Option<u64>,
+        ttl: Duration,
+    ) -> Result<DbPracticeGame> {
+        let seed = seed.unwrap_or_else(random_practice_seed);
+        let started = Instant::now();
+        let generated = self.practice_board(seed).await?;
+        self.metrics
+            .record_generation("practice", started.elapsed());
+
+        self.store_practice_game(seed, generated, ttl).await
+    }
+
+    /// Generate and store a practice game that exercises `focus`. Boards are
+    /// generated from PRACTICE_FOCUS_CANDIDATES consecutive seeds starting at
+    /// `seed` and the one whose best words suit the focus most is kept, so
+    /// the returned game's seed still reproduces its board.
+    pub async fn generate_focused_practice_game(
+        &self,
+        focus: Weakness,
+        seed: Option<u64>,
+        ttl: Duration,
+    ) -> Result<DbPracticeGame> {
+        let first_seed = seed.unwrap_or_else(random_practice_seed);
+        let started = Instant::now();
+        let mut best: Option<(f64, u64, QualifyingBoard)> = None;
+        for candidate in 0..PRACTICE_FOCUS_CANDIDATES {
+            // Practice seeds step through attempts from the seed upwards, so
+            // candidates are spaced so that no two share an attempt
+            let seed = first_seed.wrapping_add(candidate * PRACTICE_SEED_SPACING);
+            let generated = match self.practice_board(seed).await {
+                Ok(generated) => generated,
+                Err(e) => {
+                    warn!("Skipping {} practice candidate {}: {}", focus, seed, e);
+                    continue;
+                }
+            };
+            let fit = focus.board_fit(&generated.board, &generated.optimal_words);
+            if best.as_ref().is_none_or(|(best_fit, _, _)| fit > *best_fit) {
+                best = Some((fit, seed, generated));
+            }
+        }
+        self.metrics
+            .record_generation("practice_focus", started.elapsed());
+
+        let (fit, seed, generated) = best.ok_or_else(|| {
+            anyhow::anyhow!("Could not generate a {focus} practice game from seed {first_seed}")
+        })?;
+        info!(
+            "Picked practice seed {} for {} with fit {:.2}",
+            seed, focus, fit
+        );
+        self.store_practice_game(seed, generated, ttl).await
+    }
+
+    async fn practice_board(&self, seed: u64) -> Result<QualifyingBoard> {
+        generate_qualifying_board(
+            &self.game_engine,
+            GenerationConfig::default(),
+            None,
+            None,
+            &format!("practice seed {seed}"),
+            |reduction_attempt, generation_attempt| {
+                create_practice_seed(seed, reduction_attempt, generation_attempt)
+            },
+        )
+        .await
+    }
+
+    async fn store_practice_game(
+        &self,
+        seed: u64,
+        generated: QualifyingBoard,
+        ttl: Duration,
+    ) -> Result<DbPracticeGame> {
+        let new_game = NewPracticeGame {
+            seed,
+            board_data: generated.board_data()?,
+            threshold_score: generated.threshold_score,
+            optimal_score: generated.optimal_score,
+            expires_at: Utc::now() + ttl,
+        };
+
+        self.repository.create_practice_game(new_game).await
+    }
+}
+
+/// Generate the daily board for a date under the current generation rules
+pub async fn generate_daily_board(game_engine: &GameEngine, date: &str) -> Result<QualifyingBoard> {
+    generate_daily_board_with(game_engine, date, GenerationConfig::default(), None).await
+}
+
+/// Generate the daily board for a date. The board is a pure function of the
+/// date, `config` and the dictionary, so every deployment generates the same
+/// board and a stored game can be regenerated under its recorded version and
+/// theme.
+pub async fn generate_daily_board_with(
+    game_engine: &GameEngine,
+    date: &str,
+    config: GenerationConfig,
+    theme: Option<&Theme>,
+) -> Result<QualifyingBoard> {
+    generate_daily_board_revision(game_engine, date, config, theme, 1).await
+}
+
+/// Generate a revision of the daily board for a date. Revision 1 is the
+/// date's board; each later one is drawn from its own seeds, so replacing a
+/// board gives a different one that is still the same on every deployment.
+pub async fn generate_daily_board_revision(
+    game_engine: &GameEngine,
+    date: &str,
+    config: GenerationConfig,
+    theme: Option<&Theme>,
+    revision: i32,
+) -> Result<QualifyingBoard> {
+    let revision_offset = (revision.max(1) - 1) as u64 * REVISION_SEED_OFFSET;
+    let day = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("invalid game date: {}", date))?;
+    let target = config.target_for(day);
+    let mut generated = generate_qualifying_board(
+        game_engine,
+        GenerationConfig {
+            threshold_score: config.threshold_for(day),
+            ..config
+        },
+        target,
+        theme,
+        date,
+        |reduction_attempt, generation_attempt| {
+            create_seed(day, reduction_attempt, generation_attempt) + revision_offset
+        },
+    )
+    .await?;
+
+    // Feed the board's rating back into its threshold when it missed the
+    // day's band
+    if let (Some(curve), Some(target)) = (config.week_curve, target) {
+        let threshold_score = curve.adjust_threshold(
+            generated.threshold_score,
+            target,
+            generated.difficulty(),
+            generated.optimal_score,
+        );
+        if threshold_score != generated.threshold_score {
+            info!(
+                "Moving threshold for {} from {} to {} as its board is {} rather than {}",
+                date,
+                generated.threshold_score,
+                threshold_score,
+                generated.difficulty(),
+                target
+            );
+            generated.threshold_score = threshold_score;
+        }
+    }
+    Ok(generated)
+}
+
+/// generation_concurrency_from_env reads GENERATION_CONCURRENCY, the number
+/// of candidate boards generated at once, defaulting to one per CPU
+pub fn generation_concurrency_from_env() -> usize {
+    env::var("GENERATION_CONCURRENCY")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|cpus| cpus.get())
+                .unwrap_or(1)
+        })
+}
+
+/// Generate boards until one's best five words reach the score threshold,
+/// relaxing the threshold as `config` allows if none do. With a `target`,
+/// a board in that difficulty band is preferred over the first to qualify,
+/// and with a `theme` every board contains it. `seed_for` gives the rng seed for each (reduction attempt, generation
+/// attempt); `label` names the game in logs.
+///
+/// The engine's generation concurrency sets how many attempts are generated
+/// and solved at once. Their outcomes are looked at in attempt order, so the
+/// board picked is the same however many run together.
+async fn generate_qualifying_board(
+    game_engine: &GameEngine,
+    config: GenerationConfig,
+    target: Option<Difficulty>,
+    theme: Option<&Theme>,
+    label: &str,
+    seed_for: impl Fn(u32, u32) -> u64 + Sync,
+) -> Result<QualifyingBoard> {
+    let mut threshold_score = config.threshold_score;
+    let max_threshold_reductions = config.max_threshold_reductions;
+    let attempts: Vec<u32> = (1..=config.attempts_per_threshold).collect();
+
+    for reduction_attempt in 0..=max_threshold_reductions {
+        let mut first_qualifying: Option<QualifyingBoard> = None;
+        // What was wrong with the boards turned down at this threshold
+        let mut rejected_issues: BTreeMap<QualityIssue, usize> = BTreeMap::new();
+        for batch in attempts.chunks(game_engine.generation_concurrency()) {
+            let outcomes: Vec<_> = batch
+                .par_iter()
+                .map(|&generation_attempt| {
+                    let seed = seed_for(reduction_attempt, generation_attempt);
+                    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+                    game_engine.try_generate_valid_board(&mut rng, threshold_score, theme)
+                })
+                .collect();
+
+            for (&generation_attempt, outcome) in batch.iter().zip(outcomes) {
+                match outcome {
+                    Ok((board, valid_answers, (optimal_words, optimal_metadata))) => {
                         info!(
-                            "Successfully generated game for {} after {} attempts with threshold {} and {} valid answers",
-                            date, generation_attempt, threshold_score, valid_answers.len()
-                        );
-                        return Ok(game);
+                        "Successfully generated game for {} after {} attempts with threshold {} and {} valid answers",
+                        label, generation_attempt, threshold_score, valid_answers.len()
+                    );
+                        let density = BoardDensity::analyze(&board, &valid_answers);
+                        let quality = BoardQuality::analyze(&board, &valid_answers);
+                        let qualifying = QualifyingBoard {
+                            board,
+                            threshold_score,
+                            answers: valid_answers,
+                            optimal_words,
+                            optimal_score: optimal_metadata.total_score,
+                            density,
+                            quality,
+                        };
+                        match target {
+                            Some(target) if qualifying.difficulty() != target => {
+                                info!(
+                                    "Board for {} is {} rather than {}, trying for another",
+                                    label,
+                                    qualifying.difficulty(),
+                                    target
+                                );
+                                first_qualifying.get_or_insert(qualifying);
+                            }
+                            _ if config.avoid_board_issues && qualifying.quality.has_issues() => {
+                                info!(
+                                    "Board for {} has {}, trying for another",
+                                    label,
+                                    join_issues(&qualifying.quality.issues)
+                                );
+                                first_qualifying.get_or_insert(qualifying);
+                            }
+                            _ => return Ok(qualifying),
+                        }
                     }
                     Err(e) => {
+                        if let Some(rejection) = e.downcast_ref::<BoardRejection>() {
+                            for &issue in &rejection.quality.issues {
+                                *rejected_issues.entry(issue).or_default() += 1;
+                            }
+                        }
                         warn!(
-                            "Generation attempt {} failed for date {} with threshold {}: {}",
-                            generation_attempt, date, threshold_score, e
+                            "Generation attempt {} failed for {} with threshold {}: {}",
+                            generation_attempt, label, threshold_score, e
                         );
                     }
                 }
             }
+        }
 
-            // Reduce threshold by 25% and try again
-            if reduction_attempt < max_threshold_reductions {
-                threshold_score = (threshold_score as f32 * 0.75) as i32;
-                info!(
-                    "Reducing threshold score to {} for date {} and retrying",
-                    threshold_score, date
-                );
-            }
+        if let Some(qualifying) = first_qualifying {
+            info!(
+                "No better board for {}, using the first to qualify: {} with issues [{}]",
+                label,
+                qualifying.difficulty(),
+                join_issues(&qualifying.quality.issues)
+            );
+            return Ok(qualifying);
         }
 
-        error!(
-            "Failed to generate valid game for date {} after all attempts",
-            date
-        );
-        anyhow::bail!("Could not generate valid game for date: {}", date);
+        // Reduce threshold by 25% and try again
+        if reduction_attempt < max_threshold_reductions {
+            threshold_score = (threshold_score as f32 * 0.75) as i32;
+            let issues: Vec<String> = rejected_issues
+                .iter()
+                .map(|(issue, count)| format!("{issue} x{count}"))
+                .collect();
+            info!(
+                "Reducing threshold score to {} for {} and retrying; boards turned down had [{}]",
+                threshold_score,
+                label,
+                issues.join(", ")
+            );
+        }
+    }
+
+    error!(
+        "Failed to generate valid game for {} after all attempts",
+        label
+    );
+    anyhow::bail!("Could not generate valid game for {}", label);
+}
+
+/// A generated board whose best words met the score threshold
+pub struct QualifyingBoard {
+    pub board: Board,
+    pub threshold_score: i32,
+    pub answers: Vec<Answer>,
+    pub optimal_words: Vec<Answer>,
+    pub optimal_score: i32,
+    pub density: BoardDensity,
+    pub quality: BoardQuality,
+}
+
+impl QualifyingBoard {
+    /// How hard the board is, from 0 to 100; see `difficulty::rate`
+    pub fn difficulty_score(&self) -> f64 {
+        difficulty::rate(&self.density, self.optimal_score)
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        Difficulty::from_score(self.difficulty_score())
     }
+
+    /// The board serialized for storage
+    fn board_data(&self) -> Result<String> {
+        let serializable_board = crate::game::conversion::SerializableBoard::from(&self.board);
+        Ok(serde_json::to_string(&serializable_board)?)
+    }
+}
+
+/// Largest random practice seed, kept within the integers JavaScript can
+/// represent exactly so the frontend can share it
+const MAX_RANDOM_PRACTICE_SEED: u64 = (1 << 53) - 1;
+
+/// How many boards a focused practice game is picked from
+const PRACTICE_FOCUS_CANDIDATES: u64 = 4;
+
+/// Gap between the seeds of focused practice candidates, more than the
+/// attempts `create_practice_seed` steps through for one seed
+const PRACTICE_SEED_SPACING: u64 = 64;
+
+fn random_practice_seed() -> u64 {
+    rand::thread_rng().gen_range(0..=MAX_RANDOM_PRACTICE_SEED)
 }
 
+/// Seed for each practice attempt. The first attempt uses the practice seed
+/// itself, later ones step away from it deterministically.
+fn create_practice_seed(seed: u64, reduction_attempt: u32, generation_attempt: u32) -> u64 {
+    let attempt = reduction_attempt * 5 + generation_attempt - 1;
+    seed.wrapping_add(attempt as u64)
+}
+
+/// Added to a date's seeds for its blitz board. Daily seeds stay below 1e11,
+/// so the two never meet.
+const BLITZ_SEED_OFFSET: u64 = 1 << 40;
+
+/// Added to a date's daily seeds once for each revision after the first,
+/// clear of both the daily and blitz seeds
+const REVISION_SEED_OFFSET: u64 = 1 << 41;
+
 /// Create a deterministic seed based on date and attempt numbers
-fn create_seed(date: &str, reduction_attempt: u32, generation_attempt: u32) -> u64 {
+fn create_seed(date: NaiveDate, reduction_attempt: u32, generation_attempt: u32) -> u64 {
     // order so that lowest digits are first to avoid passing 2**32 - otherwise the game will break in the year 4000
     // this assumes we will not make reduction attempt go beyond 3
     let seed_string: String = format!("{reduction_attempt}{generation_attempt}{date}")
         .chars()
-        .filter(|c| c.is_numeric())
+        .filter(|c| c.is_ascii_digit())
         .collect();
-    seed_string.parse::<u64>().unwrap_or_else(|_| {
-        warn!(
-            "falling back to random seed for game generation: could not parse {} to u64",
-            seed_string
-        );
-        rand::thread_rng().gen_range(0..u64::MAX)
-    })
+    // A few attempt digits and an 8 digit date are far from overflowing, so
+    // the seed never has to fall back to randomness
+    seed_string
+        .parse::<u64>()
+        .expect("attempt numbers and a date fit in a u64")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::game::GameEngine;
+    use crate::game::{scoring::ScoringSchemes, GameEngine};
     use chrono::NaiveDate;
     use rand_seeder::Seeder;
     use std::io::Write;
@@ -244,6 +1062,14 @@ mod tests {
         assert_eq!(seed1, seed2);
     }
 
+    #[test]
+    fn test_create_practice_seed_starts_at_seed() {
+        assert_eq!(create_practice_seed(1234, 0, 1), 1234);
+        assert_eq!(create_practice_seed(1234, 1, 5), 1243);
+        assert_eq!(create_practice_seed(u64::MAX, 0, 2), 0);
+        assert!(random_practice_seed() <= MAX_RANDOM_PRACTICE_SEED);
+    }
+
     #[test]
     fn test_create_seed_different_dates() {
         let seed1 = create_deterministic_seed("2023-12-01", 0, 1);
@@ -280,7 +1106,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_seed_deterministic() {
-        let seed = create_seed("2025-04-04", 0, 2);
+        let seed = create_seed(NaiveDate::from_ymd_opt(2025, 4, 4).unwrap(), 0, 2);
         let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
         assert!(!rng.gen_bool(0.5));
         assert!(!rng.gen_bool(0.5));
@@ -335,6 +1161,229 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_generation_config_versions() {
+        let current = GenerationConfig::default();
+        assert_eq!(current.version, CURRENT_GENERATION_VERSION);
+        assert_eq!(
+            GenerationConfig::for_version(CURRENT_GENERATION_VERSION),
+            Some(current)
+        );
+
+        // Version 1 boards took the first qualifying board on every day
+        let v1 = GenerationConfig::for_version(1).unwrap();
+        let monday = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        assert_eq!(v1.target_for(monday), None);
+
+        let v2 = GenerationConfig::for_version(2).unwrap();
+        assert_eq!(v2.threshold_score, v1.threshold_score);
+        assert_eq!(v2.target_for(monday), Some(Difficulty::Easy));
+        assert_eq!(
+            v2.target_for(monday + Duration::days(5)),
+            Some(Difficulty::Hard)
+        );
+
+        // Version 3 builds up to the hardest board and threshold on Sunday
+        let v3 = GenerationConfig::for_version(3).unwrap();
+        let week: Vec<NaiveDate> = (0..7).map(|day| monday + Duration::days(day)).collect();
+        assert_eq!(v3.target_for(week[0]), Some(Difficulty::Easy));
+        assert_eq!(v3.target_for(week[6]), Some(Difficulty::Hard));
+        assert!(week
+            .windows(2)
+            .all(|days| v3.threshold_for(days[0]) <= v3.threshold_for(days[1])));
+        assert!(v3.threshold_for(week[0]) < v3.threshold_for(week[6]));
+        assert_eq!(v1.threshold_for(week[6]), v1.threshold_score);
+
+        // Version 4 keeps version 3's week but passes over flawed boards
+        let v4 = GenerationConfig::for_version(4).unwrap();
+        assert_eq!(v4.week_curve, v3.week_curve);
+        assert!(v4.avoid_board_issues && !v3.avoid_board_issues);
+        assert_eq!(GenerationConfig::for_version(0), None);
+        assert_eq!(
+            GenerationConfig::for_version(CURRENT_GENERATION_VERSION + 1),
+            None
+        );
+    }
+
+    #[test]
+    fn test_week_curve_feeds_rating_back_into_threshold() {
+        let curve = GenerationConfig::for_version(3)
+            .unwrap()
+            .week_curve
+            .unwrap();
+        // On target the threshold stands
+        assert_eq!(
+            curve.adjust_threshold(40, Difficulty::Medium, Difficulty::Medium, 60),
+            40
+        );
+        // An easy board on a hard day is made harder to beat, but not so far
+        // that only the optimal words reach it
+        assert_eq!(
+            curve.adjust_threshold(40, Difficulty::Hard, Difficulty::Easy, 60),
+            50
+        );
+        assert_eq!(
+            curve.adjust_threshold(40, Difficulty::Hard, Difficulty::Easy, 48),
+            43
+        );
+        // A hard board on an easy day is made easier to beat
+        assert_eq!(
+            curve.adjust_threshold(30, Difficulty::Easy, Difficulty::Medium, 60),
+            25
+        );
+
+        let flat = GenerationConfig::for_version(2)
+            .unwrap()
+            .week_curve
+            .unwrap();
+        assert_eq!(
+            flat.adjust_threshold(40, Difficulty::Hard, Difficulty::Easy, 60),
+            40
+        );
+    }
+
+    #[tokio::test]
+    async fn test_daily_board_is_pure_function_of_date_and_config() {
+        let (game_engine, _temp_file) = create_test_game_generator_without_db().await;
+        // The test wordlist is too small to reach the real thresholds
+        let config = GenerationConfig {
+            threshold_score: 0,
+            week_curve: None,
+            ..GenerationConfig::default()
+        };
+
+        let first = generate_daily_board_with(&game_engine, "2025-03-01", config, None)
+            .await
+            .unwrap();
+        let again = generate_daily_board_with(&game_engine, "2025-03-01", config, None)
+            .await
+            .unwrap();
+        let next_day = generate_daily_board_with(&game_engine, "2025-03-02", config, None)
+            .await
+            .unwrap();
+        assert_eq!(first.board_data().unwrap(), again.board_data().unwrap());
+        assert_ne!(first.board_data().unwrap(), next_day.board_data().unwrap());
+
+        assert!(
+            generate_daily_board_with(&game_engine, "March 1st", config, None)
+                .await
+                .is_err()
+        );
+
+        // A theme changes the board but it is still fixed by the date
+        let theme = Theme::Word("board".to_string());
+        let themed = generate_daily_board_with(&game_engine, "2025-03-01", config, Some(&theme))
+            .await
+            .unwrap();
+        assert!(themed.answers.iter().any(|answer| answer.word == "board"));
+        let themed_again =
+            generate_daily_board_with(&game_engine, "2025-03-01", config, Some(&theme))
+                .await
+                .unwrap();
+        assert_eq!(
+            themed.board_data().unwrap(),
+            themed_again.board_data().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_boards_with_issues_are_passed_over_when_avoided() {
+        let (game_engine, _temp_file) = create_test_game_generator_without_db().await;
+        let config = GenerationConfig {
+            threshold_score: 0,
+            week_curve: None,
+            avoid_board_issues: false,
+            ..GenerationConfig::default()
+        };
+        let avoiding = GenerationConfig {
+            avoid_board_issues: true,
+            ..config
+        };
+
+        for date in ["2025-03-01", "2025-03-02", "2025-03-03"] {
+            let first = generate_daily_board_with(&game_engine, date, config, None)
+                .await
+                .unwrap();
+            assert_eq!(
+                first.quality,
+                BoardQuality::analyze(&first.board, &first.answers)
+            );
+
+            // Either a board without issues, or the first to qualify when
+            // every board has some
+            let picked = generate_daily_board_with(&game_engine, date, avoiding, None)
+                .await
+                .unwrap();
+            if !first.quality.has_issues() || picked.quality.has_issues() {
+                assert_eq!(picked.board, first.board, "{date}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_does_not_change_the_board() {
+        let (game_engine, _temp_file) = create_test_game_generator_without_db().await;
+        // Aiming every day at hard boards makes most attempts miss the band,
+        // so the pick depends on judging every attempt in order
+        let config = GenerationConfig {
+            week_curve: Some(WeekCurve::new([(0, Difficulty::Hard); 7], 0)),
+            ..GenerationConfig::default()
+        };
+
+        for date in ["2025-03-01", "2025-03-02", "2025-03-03"] {
+            let serial = generate_daily_board_with(&game_engine, date, config, None)
+                .await
+                .unwrap();
+            let concurrent = generate_daily_board_with(
+                &game_engine.clone().with_generation_concurrency(3),
+                date,
+                config,
+                None,
+            )
+            .await
+            .unwrap();
+            assert_eq!(
+                serial.board_data().unwrap(),
+                concurrent.board_data().unwrap(),
+                "{date}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_boards_are_scored_with_the_active_scheme() {
+        let (game_engine, _temp_file) = create_test_game_generator_without_db().await;
+        let letters: String = ('a'..='z')
+            .map(|letter| format!("{letter} = 5\n"))
+            .collect();
+        let schemes =
+            ScoringSchemes::parse(&format!("active = 2\n[2]\nwildcard = 1\n{letters}")).unwrap();
+        let rebalanced = game_engine.clone().with_scoring_schemes(schemes);
+        let config = GenerationConfig {
+            threshold_score: 0,
+            week_curve: None,
+            ..GenerationConfig::default()
+        };
+
+        let standard = generate_daily_board_with(&game_engine, "2025-03-01", config, None)
+            .await
+            .unwrap();
+        let generated = generate_daily_board_with(&rebalanced, "2025-03-01", config, None)
+            .await
+            .unwrap();
+        for tile in generated.board.rows.iter().flat_map(|row| &row.tiles) {
+            assert_eq!(tile.points, if tile.is_wildcard { 1 } else { 5 });
+        }
+
+        // Rescoring the standard board reproduces the rebalanced one, and
+        // the standard scheme takes it back
+        let mut rescored = standard.board.clone();
+        rescored.score_with(rebalanced.scoring_schemes().active());
+        assert_eq!(rescored, generated.board);
+        rescored.score_with(game_engine.scoring_schemes().active());
+        assert_eq!(rescored, standard.board);
+    }
+
     #[test]
     fn test_threshold_reduction_logic() {
         // Test the threshold reduction algorithm used in generate_game_for_date
@@ -0,0 +1,364 @@
+//! The game API over gRPC, generated from proto/pathfinder.proto. It serves
+//! the same ApiState as the HTTP API, on its own port set by GRPC_PORT.
+
+use anyhow::Context;
+use axum::{extract::State, Json};
+use chrono::Utc;
+use std::future::Future;
+use tokio::net::TcpListener;
+use tonic::{
+    transport::{server::TcpIncoming, Server},
+    Request, Response, Status,
+};
+use tracing::error;
+
+use crate::api_error::ApiError;
+use crate::db::Repository;
+use crate::game::input_guard::UnsupportedInput;
+use crate::game::language::Language;
+use crate::http_api::{
+    authenticate_credentials, completed_response, future_puzzle, is_date_in_future,
+    load_game_for_date, resolve_today, update_game_entry, validate_answer, ApiAnswer,
+    ApiAnswerPath, ApiGame, ApiGameStats, ApiPathConstraintSet, ApiPosition, ApiState,
+    UpdateGameEntryRequest, ValidateRequest,
+};
+use crate::security::user_cookie::UserIdentity;
+
+pub mod proto {
+    tonic::include_proto!("pathfinder.v1");
+}
+
+use proto::pathfinder_server::{Pathfinder, PathfinderServer};
+
+/// GrpcApi answers the Pathfinder service with the HTTP API's handlers
+pub struct GrpcApi<R: Repository> {
+    state: ApiState<R>,
+}
+
+impl<R: Repository + Clone + Send + Sync + 'static> GrpcApi<R> {
+    pub fn new(state: ApiState<R>) -> Self {
+        Self { state }
+    }
+
+    pub fn into_service(self) -> PathfinderServer<Self> {
+        PathfinderServer::new(self)
+    }
+}
+
+#[tonic::async_trait]
+impl<R: Repository + Clone + Send + Sync + 'static> Pathfinder for GrpcApi<R> {
+    async fn get_game(
+        &self,
+        request: Request<proto::GetGameRequest>,
+    ) -> Result<Response<proto::Game>, Status> {
+        let request = request.into_inner();
+        let date = if request.date.is_empty() {
+            let tz = (!request.tz.is_empty()).then_some(request.tz.as_str());
+            resolve_today(tz, Utc::now())
+                .ok_or_else(|| ApiError::validation(format!("Unknown timezone: {}", request.tz)))?
+                .format("%Y-%m-%d")
+                .to_string()
+        } else if is_date_in_future(&request.date) {
+            return Err(future_puzzle().into());
+        } else {
+            request.date
+        };
+
+        let game = load_game_for_date(&self.state, &date, Language::English).await?;
+        Ok(Response::new(game.into()))
+    }
+
+    async fn validate_answer(
+        &self,
+        request: Request<proto::ValidateAnswerRequest>,
+    ) -> Result<Response<proto::ValidateAnswerResponse>, Status> {
+        let request = request.into_inner();
+        let request = ValidateRequest {
+            word: request.word,
+            previous_answers: answers_from_proto(request.previous_answers)?,
+            game_id: request.game_id,
+        };
+
+        let Json(response) = validate_answer(State(self.state.clone()), Json(request)).await?;
+        Ok(Response::new(proto::ValidateAnswerResponse {
+            is_valid: response.is_valid,
+            score: response.score,
+            path: response.path.into_iter().map(Into::into).collect(),
+            wildcard_constraints: response.wildcard_constraints,
+            error_message: response.error_message,
+            error_code: response
+                .error_code
+                .map_or(proto::UnsupportedInput::Unspecified, Into::into)
+                .into(),
+        }))
+    }
+
+    async fn submit_entry(
+        &self,
+        request: Request<proto::SubmitEntryRequest>,
+    ) -> Result<Response<proto::SubmitEntryResponse>, Status> {
+        let request = request.into_inner();
+        let request = UpdateGameEntryRequest {
+            user_id: request.user_id,
+            cookie_token: request.cookie_token,
+            answers: answers_from_proto(request.answers)?,
+            game_id: request.game_id,
+            completed: request.completed,
+            start_token: None,
+            request_id: None,
+        };
+
+        let identity = UserIdentity::from_credentials(self.state.user_cookies.clone());
+        let Json(response) =
+            update_game_entry(State(self.state.clone()), None, identity, Json(request)).await?;
+        Ok(Response::new(proto::SubmitEntryResponse {
+            user_id: response.user_id,
+            total_score: response.total_score,
+            stats: Some(response.stats.into()),
+            off_board: response.off_board,
+        }))
+    }
+
+    async fn get_stats(
+        &self,
+        request: Request<proto::GetStatsRequest>,
+    ) -> Result<Response<proto::GameStats>, Status> {
+        let request = request.into_inner();
+        let user = authenticate_credentials(
+            &self.state,
+            Some(&request.user_id),
+            Some(&request.cookie_token),
+        )
+        .await?;
+
+        let entry = self
+            .state
+            .repository
+            .get_game_entry(&user.id, &request.game_id)
+            .await
+            .context("Failed to get game entry")
+            .map_err(ApiError::from)?
+            .filter(|entry| entry.completed)
+            .ok_or_else(|| ApiError::not_found("Completed game entry"))?;
+
+        let response = completed_response(
+            &self.state,
+            user.id,
+            &request.game_id,
+            entry.total_score,
+            entry.off_board,
+        )
+        .await;
+        Ok(Response::new(response.stats.into()))
+    }
+}
+
+/// serve answers gRPC requests on `listener` until `signal` resolves
+pub async fn serve<R, F>(
+    listener: TcpListener,
+    state: ApiState<R>,
+    signal: F,
+) -> Result<(), tonic::transport::Error>
+where
+    R: Repository + Clone + Send + Sync + 'static,
+    F: Future<Output = ()>,
+{
+    let incoming =
+        TcpIncoming::from_listener(listener, true, None).expect("listener is already bound");
+    Server::builder()
+        .add_service(GrpcApi::new(state).into_service())
+        .serve_with_incoming_shutdown(incoming, signal)
+        .await
+}
+
+impl From<ApiError> for Status {
+    fn from(e: ApiError) -> Self {
+        let message = e.to_string();
+        match e {
+            ApiError::NotFound(_) => Status::not_found(message),
+            ApiError::Validation { .. } => Status::invalid_argument(message),
+            ApiError::Unauthorized(_) => Status::unauthenticated(message),
+            ApiError::Forbidden(_) => Status::permission_denied(message),
+            ApiError::Conflict { .. } => Status::failed_precondition(message),
+            ApiError::Internal(e) => {
+                error!("gRPC request failed: {e:#}");
+                Status::internal(message)
+            }
+        }
+    }
+}
+
+fn answers_from_proto(answers: Vec<proto::Answer>) -> Result<Vec<ApiAnswer>, ApiError> {
+    answers
+        .into_iter()
+        .map(|answer| {
+            Ok(ApiAnswer {
+                word: answer.word,
+                score: answer.score,
+                path: answer.path.map(TryInto::try_into).transpose()?,
+            })
+        })
+        .collect()
+}
+
+impl TryFrom<proto::AnswerPath> for ApiAnswerPath {
+    type Error = ApiError;
+
+    fn try_from(path: proto::AnswerPath) -> Result<Self, ApiError> {
+        let constraints = path.constraints.unwrap_or_default();
+        let first = wildcard_letter(constraints.first_wildcard)?;
+        let second = wildcard_letter(constraints.second_wildcard)?;
+        Ok(ApiAnswerPath {
+            positions: path
+                .positions
+                .into_iter()
+                .map(|p| ApiPosition {
+                    row: p.row,
+                    col: p.col,
+                })
+                .collect(),
+            constraints: match (first, second) {
+                (None, None) => ApiPathConstraintSet::Unconstrainted,
+                (Some(first), None) => ApiPathConstraintSet::FirstDecided(first),
+                (None, Some(second)) => ApiPathConstraintSet::SecondDecided(second),
+                (Some(first), Some(second)) => ApiPathConstraintSet::BothDecided(first, second),
+            },
+        })
+    }
+}
+
+/// The single letter chosen for a wildcard, if one was
+fn wildcard_letter(letter: Option<String>) -> Result<Option<char>, ApiError> {
+    let Some(letter) = letter else {
+        return Ok(None);
+    };
+    let mut chars = letter.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(Some(c)),
+        _ => Err(ApiError::validation(format!(
+            "Wildcard letter '{letter}' must be a single letter"
+        ))),
+    }
+}
+
+impl From<ApiGame> for proto::Game {
+    fn from(game: ApiGame) -> Self {
+        proto::Game {
+            id: game.id,
+            date: game.date,
+            board: Some(proto::Board {
+                rows: game
+                    .board
+                    .tiles
+                    .into_iter()
+                    .map(|row| proto::TileRow {
+                        tiles: row
+                            .into_iter()
+                            .map(|tile| proto::Tile {
+                                letter: tile.letter,
+                                points: tile.points,
+                                is_wildcard: tile.is_wildcard,
+                                row: tile.row,
+                                col: tile.col,
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            }),
+            threshold_score: game.threshold_score,
+            sequence_number: game.sequence_number,
+        }
+    }
+}
+
+impl From<ApiPosition> for proto::Position {
+    fn from(position: ApiPosition) -> Self {
+        proto::Position {
+            row: position.row,
+            col: position.col,
+        }
+    }
+}
+
+impl From<ApiGameStats> for proto::GameStats {
+    fn from(stats: ApiGameStats) -> Self {
+        proto::GameStats {
+            total_players: stats.total_players,
+            user_rank: stats.user_rank,
+            percentile: stats.percentile,
+            average_score: stats.average_score,
+            highest_score: stats.highest_score,
+        }
+    }
+}
+
+impl From<UnsupportedInput> for proto::UnsupportedInput {
+    fn from(reason: UnsupportedInput) -> Self {
+        match reason {
+            UnsupportedInput::Language => proto::UnsupportedInput::Language,
+            UnsupportedInput::Characters => proto::UnsupportedInput::Characters,
+            UnsupportedInput::Length => proto::UnsupportedInput::Length,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{create_new_test_game, setup_memory_app};
+    use proto::pathfinder_client::PathfinderClient;
+
+    #[tokio::test]
+    async fn test_grpc_serves_the_game_and_validates_words() {
+        let (state, _app) = setup_memory_app();
+        state
+            .repository
+            .create_game_with_answers(create_new_test_game(), vec![], None)
+            .await
+            .unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve(listener, state, async {
+            let _ = stopped.await;
+        }));
+
+        let mut client = PathfinderClient::connect(format!("http://{addr}"))
+            .await
+            .unwrap();
+        let game = client
+            .get_game(proto::GetGameRequest {
+                date: "2024-01-01".to_string(),
+                tz: String::new(),
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(game.date, "2024-01-01");
+        assert_eq!(game.board.unwrap().rows.len(), 4);
+
+        let validated = client
+            .validate_answer(proto::ValidateAnswerRequest {
+                word: "test".to_string(),
+                previous_answers: vec![],
+                game_id: None,
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(validated.is_valid);
+
+        let future = client
+            .get_game(proto::GetGameRequest {
+                date: "2999-01-01".to_string(),
+                tz: String::new(),
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(future.code(), tonic::Code::InvalidArgument);
+
+        stop.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+}
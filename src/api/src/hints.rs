@@ -0,0 +1,192 @@
+use std::{collections::HashSet, env};
+
+use crate::db::models::DbHintUsage;
+use crate::game::board::answer::Answer;
+
+/// Hints revealed for a single word, in order: its first letter, its length,
+/// the tile it starts on, then the first half of a path that spells it
+pub const MAX_HINT_LEVEL: i32 = 4;
+
+#[derive(Clone, Debug)]
+pub struct HintConfig {
+    /// Total hints a player may reveal per game, across all words
+    pub budget: i32,
+    /// Points taken off the stored score for every hint revealed; 0 disables the penalty
+    pub score_penalty: i32,
+}
+
+impl Default for HintConfig {
+    fn default() -> Self {
+        Self {
+            budget: 2 * MAX_HINT_LEVEL,
+            score_penalty: 0,
+        }
+    }
+}
+
+impl HintConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(budget) = env::var("HINT_BUDGET") {
+            if let Ok(value) = budget.parse::<i32>() {
+                config.budget = value.max(0);
+            }
+        }
+
+        if let Ok(penalty) = env::var("HINT_SCORE_PENALTY") {
+            if let Ok(value) = penalty.parse::<i32>() {
+                config.score_penalty = value.max(0);
+            }
+        }
+
+        config
+    }
+
+    /// Score after taking off the penalty for `hints_used` hints, never below zero
+    pub fn apply_penalty(&self, total_score: i32, hints_used: i32) -> i32 {
+        (total_score - self.score_penalty * hints_used).max(0)
+    }
+}
+
+/// A hint about one word. Fields beyond `first_letter` are only filled in
+/// once the player has revealed enough hints for that word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    pub level: i32,
+    pub first_letter: char,
+    pub length: Option<usize>,
+    pub origin: Option<(i32, i32)>,
+    pub partial_path: Option<Vec<(i32, i32)>>,
+}
+
+impl Hint {
+    /// Reveal `level` hints about `answer`. Tile hints follow the path with
+    /// the fewest wildcards, since those point at the letters actually on the
+    /// board. Returns None if the answer can't be formed on the board.
+    pub fn reveal(answer: &Answer, level: i32) -> Option<Self> {
+        let first_letter = answer.word.chars().next()?;
+        let positions: Vec<(i32, i32)> = answer
+            .paths
+            .iter()
+            .min_by_key(|path| path.tiles.iter().filter(|tile| tile.is_wildcard).count())?
+            .tiles
+            .iter()
+            .map(|tile| (tile.row, tile.col))
+            .collect();
+        let level = level.clamp(1, MAX_HINT_LEVEL);
+
+        Some(Self {
+            level,
+            first_letter,
+            length: (level >= 2).then_some(answer.word.len()),
+            origin: (level >= 3).then_some(positions[0]),
+            partial_path: (level >= 4).then(|| positions[..positions.len().div_ceil(2)].to_vec()),
+        })
+    }
+}
+
+/// Total hints revealed in a game, including for words since found
+pub fn hints_used(usage: &[DbHintUsage]) -> i32 {
+    usage.iter().map(|usage| usage.level).sum()
+}
+
+/// Pick the word and level of the next hint to reveal. A word that already
+/// has hints keeps getting them until it is fully revealed or found; after
+/// that the shortest unfound word is hinted next.
+pub fn next_hint(
+    words: &[String],
+    found: &HashSet<String>,
+    usage: &[DbHintUsage],
+) -> Option<(String, i32)> {
+    if let Some(in_progress) = usage
+        .iter()
+        .find(|usage| usage.level < MAX_HINT_LEVEL && !found.contains(&usage.word))
+    {
+        return Some((in_progress.word.clone(), in_progress.level + 1));
+    }
+
+    let hinted: HashSet<&str> = usage.iter().map(|usage| usage.word.as_str()).collect();
+    words
+        .iter()
+        .filter(|word| !found.contains(*word) && !hinted.contains(word.as_str()))
+        .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+        .map(|word| (word.clone(), 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_default_test_board;
+
+    fn usage(word: &str, level: i32) -> DbHintUsage {
+        DbHintUsage::new(
+            "user".to_string(),
+            "game".to_string(),
+            word.to_string(),
+            level,
+        )
+    }
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn test_reveal_is_progressive() {
+        // t e s t
+        // h * n g
+        // a r * a
+        // s t o p
+        let answer = create_default_test_board().paths_for("stop");
+
+        let first = Hint::reveal(&answer, 1).unwrap();
+        assert_eq!(first.first_letter, 's');
+        assert_eq!(first.length, None);
+        assert_eq!(first.origin, None);
+
+        let last = Hint::reveal(&answer, MAX_HINT_LEVEL).unwrap();
+        assert_eq!(last.length, Some(4));
+        assert_eq!(last.origin, Some((3, 0)));
+        assert_eq!(last.partial_path, Some(vec![(3, 0), (3, 1)]));
+    }
+
+    #[test]
+    fn test_next_hint_continues_word_in_progress() {
+        let all = words(&["stop", "test", "the"]);
+        let found = HashSet::new();
+
+        assert_eq!(next_hint(&all, &found, &[]), Some(("the".to_string(), 1)));
+        assert_eq!(
+            next_hint(&all, &found, &[usage("test", 2)]),
+            Some(("test".to_string(), 3))
+        );
+    }
+
+    #[test]
+    fn test_next_hint_skips_found_and_fully_hinted_words() {
+        let all = words(&["stop", "test", "the"]);
+        let found: HashSet<String> = ["the".to_string()].into();
+
+        let usage = [usage("test", MAX_HINT_LEVEL)];
+        assert_eq!(
+            next_hint(&all, &found, &usage),
+            Some(("stop".to_string(), 1))
+        );
+
+        let found: HashSet<String> = ["the".to_string(), "stop".to_string()].into();
+        assert_eq!(next_hint(&all, &found, &usage), None);
+        assert_eq!(hints_used(&usage), MAX_HINT_LEVEL);
+    }
+
+    #[test]
+    fn test_apply_penalty_floors_at_zero() {
+        let config = HintConfig {
+            score_penalty: 3,
+            ..Default::default()
+        };
+        assert_eq!(config.apply_penalty(20, 2), 14);
+        assert_eq!(config.apply_penalty(5, 4), 0);
+        assert_eq!(HintConfig::default().apply_penalty(20, 8), 20);
+    }
+}
@@ -0,0 +1,595 @@
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::header,
+    response::{Extension, IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::NaiveDate;
+use futures_util::stream;
+use serde::{Deserialize, Serialize};
+use tracing::{error, warn};
+
+use crate::api_error::ApiError;
+use crate::db::{conversions::AnswerStorage, models::DbHistoryEntry, Repository};
+use crate::hints;
+use crate::http_api::{
+    authenticate_user, parse_board, parse_bonus_rules, score_submitted_answers, ApiState,
+};
+use crate::security::{session::SessionInfo, user_cookie::UserIdentity};
+use anyhow::Context;
+
+/// Entries fetched from the repository per chunk of the export
+const EXPORT_PAGE_SIZE: i32 = 100;
+
+const CSV_HEADER: &str = "date,sequence_number,words,score,completed,percentile,streak\n";
+
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct HistoryExportQuery {
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct VerifyQuery {
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+}
+
+/// A completed game whose stored score doesn't match a recomputation
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ApiScoreMismatch {
+    pub game_id: String,
+    pub date: String,
+    pub sequence_number: i32,
+    pub stored_score: i32,
+    /// None when the stored answers could not be rescored at all
+    pub recomputed_score: Option<i32>,
+    /// Why the answers could not be rescored
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ApiScoreVerification {
+    pub games_checked: i32,
+    pub mismatches: Vec<ApiScoreMismatch>,
+}
+
+/// One played game in an export
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ApiHistoryRow {
+    pub date: String,
+    pub sequence_number: i32,
+    pub words: Vec<String>,
+    pub score: i32,
+    pub completed: bool,
+    /// None for unfinished games and games finished after their stats froze
+    pub percentile: Option<f64>,
+    /// Days in a row, ending with this game, that the user completed the
+    /// daily game; 0 if this one wasn't completed
+    pub streak: i32,
+}
+
+/// Streak follows consecutive days of completed games as history is read
+/// oldest first
+#[derive(Default)]
+pub(crate) struct Streak {
+    last_completed: Option<NaiveDate>,
+    length: i32,
+}
+
+impl Streak {
+    pub(crate) fn record(&mut self, date: &str, completed: bool) -> i32 {
+        let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+            return 0;
+        };
+        if !completed {
+            return 0;
+        }
+
+        self.length = match self.last_completed {
+            Some(last) if date.pred_opt() == Some(last) => self.length + 1,
+            _ => 1,
+        };
+        self.last_completed = Some(date);
+        self.length
+    }
+}
+
+impl ApiHistoryRow {
+    fn from_entry(entry: DbHistoryEntry, streak: &mut Streak) -> Self {
+        let words = AnswerStorage::deserialize_to_api_answers(&entry.answers_data)
+            .map(|answers| answers.into_iter().map(|answer| answer.word).collect())
+            .unwrap_or_else(|e| {
+                warn!("Unreadable answers exporting game {}: {}", entry.game_id, e);
+                Vec::new()
+            });
+
+        ApiHistoryRow {
+            streak: streak.record(&entry.date, entry.completed),
+            date: entry.date,
+            sequence_number: entry.sequence_number,
+            words,
+            score: entry.total_score,
+            completed: entry.completed,
+            percentile: entry.percentile,
+        }
+    }
+
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{}\n",
+            self.date,
+            self.sequence_number,
+            csv_field(&self.words.join(" ")),
+            self.score,
+            self.completed,
+            self.percentile
+                .map(|percentile| format!("{percentile:.1}"))
+                .unwrap_or_default(),
+            self.streak
+        )
+    }
+}
+
+/// Quote a CSV field if it contains anything that would break the row
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Routes for exporting a user's play history
+pub fn history_router<R: Repository + Clone + Send + Sync + 'static>() -> Router<ApiState<R>> {
+    Router::new()
+        .route("/api/user/history/export", get(export_history))
+        .route("/api/user/verify", get(verify_scores))
+}
+
+/// Where an export has got to
+struct ExportCursor<R> {
+    repository: R,
+    user_id: String,
+    format: ExportFormat,
+    after_date: Option<String>,
+    streak: Streak,
+    started: bool,
+    done: bool,
+}
+
+impl<R: Repository> ExportCursor<R> {
+    /// The next chunk of the export, or None once it is complete
+    async fn next_chunk(&mut self) -> Option<anyhow::Result<String>> {
+        if self.done {
+            return None;
+        }
+
+        let page = match self
+            .repository
+            .get_user_history(&self.user_id, self.after_date.as_deref(), EXPORT_PAGE_SIZE)
+            .await
+        {
+            Ok(page) => page,
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e));
+            }
+        };
+        self.done = page.len() < EXPORT_PAGE_SIZE as usize;
+        self.after_date = page.last().map(|entry| entry.date.clone());
+
+        let mut chunk = String::new();
+        for entry in page {
+            let row = ApiHistoryRow::from_entry(entry, &mut self.streak);
+            match self.format {
+                ExportFormat::Csv => {
+                    if !self.started {
+                        chunk.push_str(CSV_HEADER);
+                    }
+                    chunk.push_str(&row.to_csv());
+                }
+                ExportFormat::Json => {
+                    chunk.push_str(if self.started { ",\n" } else { "[\n" });
+                    match serde_json::to_string(&row) {
+                        Ok(json) => chunk.push_str(&json),
+                        Err(e) => return Some(Err(e.into())),
+                    }
+                }
+            }
+            self.started = true;
+        }
+
+        if self.done {
+            match (self.format, self.started) {
+                (ExportFormat::Csv, false) => chunk.push_str(CSV_HEADER),
+                (ExportFormat::Json, false) => chunk.push_str("[]\n"),
+                (ExportFormat::Json, true) => chunk.push_str("\n]\n"),
+                (ExportFormat::Csv, true) => {}
+            }
+        }
+        Some(Ok(chunk))
+    }
+}
+
+/// Stream every game the user has played, oldest first, as a CSV
+/// spreadsheet or a JSON array. History is read a page at a time so long
+/// histories never have to be held in memory.
+async fn export_history<R: Repository + Clone + Send + Sync + 'static>(
+    Query(query): Query<HistoryExportQuery>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+) -> Result<Response, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        query.user_id.as_ref(),
+        query.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+
+    let cursor = ExportCursor {
+        repository: state.repository.clone(),
+        user_id: user.id,
+        format: query.format,
+        after_date: None,
+        streak: Streak::default(),
+        started: false,
+        done: false,
+    };
+    let chunks = stream::unfold(cursor, |mut cursor| async move {
+        let chunk = cursor.next_chunk().await?.inspect_err(|e| {
+            error!("History export for user {} failed: {}", cursor.user_id, e);
+        });
+        Some((chunk, cursor))
+    });
+
+    let (content_type, filename) = match query.format {
+        ExportFormat::Csv => ("text/csv; charset=utf-8", "pathfinder-history.csv"),
+        ExportFormat::Json => ("application/json", "pathfinder-history.json"),
+    };
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        Body::from_stream(chunks),
+    )
+        .into_response())
+}
+
+/// Rescore every game the user has completed from their stored answers and
+/// report any whose stored total disagrees. Boards keep the tile points they
+/// were generated with, so games are rescored under the scheme they were
+/// played with rather than whatever is active now.
+async fn verify_scores<R: Repository + Clone + Send + Sync + 'static>(
+    Query(query): Query<VerifyQuery>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+) -> Result<Json<ApiScoreVerification>, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        query.user_id.as_ref(),
+        query.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+
+    let mut verification = ApiScoreVerification {
+        games_checked: 0,
+        mismatches: Vec::new(),
+    };
+    let mut after_date: Option<String> = None;
+    loop {
+        let page = state
+            .repository
+            .get_user_history(&user.id, after_date.as_deref(), EXPORT_PAGE_SIZE)
+            .await
+            .context("Failed to get user history")?;
+        let done = page.len() < EXPORT_PAGE_SIZE as usize;
+        after_date = page.last().map(|entry| entry.date.clone());
+
+        for entry in page.into_iter().filter(|entry| entry.completed) {
+            verification.games_checked += 1;
+            let recomputed = recompute_score(&state, &user.id, &entry).await?;
+            let mismatch = match recomputed {
+                Ok(score) if score == entry.total_score => continue,
+                Ok(score) => (Some(score), None),
+                Err(reason) => (None, Some(reason)),
+            };
+            warn!(
+                "Stored score {} for user {} on game {} doesn't verify: {:?}",
+                entry.total_score, user.id, entry.game_id, mismatch
+            );
+            verification.mismatches.push(ApiScoreMismatch {
+                game_id: entry.game_id,
+                date: entry.date,
+                sequence_number: entry.sequence_number,
+                stored_score: entry.total_score,
+                recomputed_score: mismatch.0,
+                reason: mismatch.1,
+            });
+        }
+
+        if done {
+            break;
+        }
+    }
+
+    Ok(Json(verification))
+}
+
+/// The score an entry's stored answers earn today, hint penalty included.
+/// The inner error explains why the answers couldn't be rescored; the outer
+/// one is a failure to read what's needed to try.
+async fn recompute_score<R: Repository>(
+    state: &ApiState<R>,
+    user_id: &str,
+    entry: &DbHistoryEntry,
+) -> Result<Result<i32, String>, ApiError> {
+    let Some(game) = state
+        .repository
+        .get_game_by_id(&entry.game_id)
+        .await
+        .context("Failed to get game")?
+    else {
+        return Ok(Err("The game no longer exists".to_string()));
+    };
+    let board = parse_board(&game)?;
+    let bonus_rules = parse_bonus_rules(&game)?;
+    let answers = match AnswerStorage::deserialize_to_api_answers(&entry.answers_data) {
+        Ok(answers) => answers,
+        Err(e) => return Ok(Err(format!("Stored answers are unreadable: {e}"))),
+    };
+    let score_sheet = match score_submitted_answers(
+        state.engine_for(game.game_language()),
+        &board,
+        &answers,
+        &bonus_rules,
+    ) {
+        Ok(score_sheet) => score_sheet,
+        Err(reason) => return Ok(Err(reason.to_string())),
+    };
+
+    let mut total_score = i32::try_from(score_sheet.total_score()).unwrap_or(i32::MAX);
+    if state.hint_config.score_penalty > 0 {
+        let usage = state
+            .repository
+            .get_hint_usage(user_id, &entry.game_id)
+            .await
+            .context("Failed to get hint usage")?;
+        total_score = state
+            .hint_config
+            .apply_penalty(total_score, hints::hints_used(&usage));
+    }
+    Ok(Ok(total_score))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_streak_counts_consecutive_completed_days() {
+        let mut streak = Streak::default();
+        assert_eq!(streak.record("2025-06-01", true), 1);
+        assert_eq!(streak.record("2025-06-02", true), 2);
+        assert_eq!(streak.record("2025-06-03", false), 0);
+        assert_eq!(streak.record("2025-06-04", true), 1);
+        assert_eq!(streak.record("2025-06-06", true), 1);
+        assert_eq!(streak.record("2025-06-07", true), 2);
+    }
+
+    #[test]
+    fn test_csv_row_quotes_fields() {
+        assert_eq!(csv_field("cat dog"), "cat dog");
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+
+        let row = ApiHistoryRow {
+            date: "2025-06-01".to_string(),
+            sequence_number: 7,
+            words: vec!["cat".to_string(), "dog".to_string()],
+            score: 12,
+            completed: true,
+            percentile: Some(87.5),
+            streak: 3,
+        };
+        assert_eq!(row.to_csv(), "2025-06-01,7,cat dog,12,true,87.5,3\n");
+    }
+
+    #[tokio::test]
+    async fn test_verify_reports_scores_that_dont_recompute() {
+        use crate::db::models::{NewGameEntry, NewUser};
+        use crate::game::scoring::BonusRules;
+        use crate::http_api::ApiAnswer;
+        use crate::test_utils::{create_new_test_game, create_test_request, setup_memory_app};
+        use axum::http::{Method, StatusCode};
+        use tower::util::ServiceExt;
+
+        let (state, app) = setup_memory_app();
+        let repo = &state.repository;
+        let user = repo
+            .create_user(NewUser {
+                cookie_token: "player".to_string(),
+            })
+            .await
+            .unwrap();
+        let answers: Vec<ApiAnswer> = ["stop", "test"]
+            .into_iter()
+            .map(|word| ApiAnswer {
+                word: word.to_string(),
+                score: 0,
+                path: None,
+            })
+            .collect();
+        let answers_data = AnswerStorage::serialize_api_answers(&answers).unwrap();
+
+        // The first game's total is honest, the second's was inflated and the
+        // third's answers can't be read back
+        let mut expected = 0;
+        for (day, answers_data) in [answers_data.clone(), answers_data, "not json".to_string()]
+            .into_iter()
+            .enumerate()
+        {
+            let mut new_game = create_new_test_game();
+            new_game.date = format!("2025-06-0{}", day + 1);
+            new_game.sequence_number = day as i32 + 1;
+            let (game, _) = repo
+                .create_game_with_answers(new_game, vec![], None)
+                .await
+                .unwrap();
+            if day == 0 {
+                let board = parse_board(&game).unwrap();
+                expected = score_submitted_answers(
+                    &state.game_engine,
+                    &board,
+                    &answers,
+                    &BonusRules::default(),
+                )
+                .unwrap()
+                .total_score() as i32;
+            }
+            repo.create_or_update_game_entry(NewGameEntry {
+                user_id: user.id.clone(),
+                game_id: game.id,
+                answers_data,
+                total_score: if day == 1 { expected + 5 } else { expected },
+                completed: true,
+                suspicion_score: 0,
+                exclude: false,
+                request_id: None,
+            })
+            .await
+            .unwrap();
+        }
+
+        let uri = format!("/api/user/verify?user_id={}&cookie_token=player", user.id);
+        let response = app
+            .oneshot(create_test_request(Method::GET, &uri, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let verification: ApiScoreVerification = serde_json::from_slice(&body).unwrap();
+        assert_eq!(verification.games_checked, 3);
+        assert_eq!(verification.mismatches.len(), 2);
+        assert_eq!(verification.mismatches[0].date, "2025-06-02");
+        assert_eq!(verification.mismatches[0].stored_score, expected + 5);
+        assert_eq!(verification.mismatches[0].recomputed_score, Some(expected));
+        assert_eq!(verification.mismatches[1].date, "2025-06-03");
+        assert_eq!(verification.mismatches[1].recomputed_score, None);
+        assert!(verification.mismatches[1].reason.is_some());
+    }
+
+    #[cfg(feature = "database-tests")]
+    mod database {
+        use super::super::*;
+        use axum::http::{Method, StatusCode};
+        use tower::util::ServiceExt;
+
+        use crate::db::models::{NewGameEntry, NewUser};
+        use crate::test_utils::{create_new_test_game, create_test_request, setup_app};
+
+        #[sqlx::test(migrations = "migrations/sqlite")]
+        async fn test_export_pages_through_history(pool: sqlx::Pool<sqlx::Sqlite>) {
+            let (state, app) = setup_app(pool).await;
+            let repo = &state.repository;
+            let user = repo
+                .create_user(NewUser {
+                    cookie_token: "player".to_string(),
+                })
+                .await
+                .unwrap();
+
+            // More games than fit in one page, every one completed but the last
+            let games = EXPORT_PAGE_SIZE + 5;
+            let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+            for day in 0..games {
+                let mut new_game = create_new_test_game();
+                new_game.date = (start + chrono::Duration::days(day as i64))
+                    .format("%Y-%m-%d")
+                    .to_string();
+                new_game.sequence_number = day + 1;
+                let (game, _) = repo
+                    .create_game_with_answers(new_game, vec![], None)
+                    .await
+                    .unwrap();
+                repo.create_or_update_game_entry(NewGameEntry {
+                    user_id: user.id.clone(),
+                    game_id: game.id,
+                    answers_data: "[]".to_string(),
+                    total_score: 10,
+                    completed: day < games - 1,
+                    suspicion_score: 0,
+                    exclude: false,
+                    request_id: None,
+                })
+                .await
+                .unwrap();
+            }
+
+            let uri = format!(
+                "/api/user/history/export?format=json&user_id={}&cookie_token=player",
+                user.id
+            );
+            let response = app
+                .clone()
+                .oneshot(create_test_request(Method::GET, &uri, None))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let rows: Vec<ApiHistoryRow> = serde_json::from_slice(&body).unwrap();
+            assert_eq!(rows.len(), games as usize);
+            assert_eq!(rows[0].date, "2024-01-01");
+            assert_eq!(rows[0].percentile, Some(100.0));
+            assert_eq!(rows[games as usize - 2].streak, games - 1);
+            assert_eq!(rows[games as usize - 1].streak, 0);
+            assert_eq!(rows[games as usize - 1].percentile, None);
+
+            let uri = format!(
+                "/api/user/history/export?user_id={}&cookie_token=player",
+                user.id
+            );
+            let response = app
+                .oneshot(create_test_request(Method::GET, &uri, None))
+                .await
+                .unwrap();
+            assert_eq!(
+                response.headers()[header::CONTENT_TYPE],
+                "text/csv; charset=utf-8"
+            );
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let csv = String::from_utf8(body.to_vec()).unwrap();
+            let lines: Vec<&str> = csv.lines().collect();
+            assert_eq!(lines.len(), games as usize + 1);
+            assert_eq!(lines[0], CSV_HEADER.trim_end());
+            assert_eq!(lines[1], "2024-01-01,1,,10,true,100.0,1");
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::{collections::HashSet, env, time::Duration};
+
+use crate::wordlist::curate::FrequencyCorpus;
+
+/// Fewest words a submission needs before the share of them that are rare
+/// means anything
+const MIN_WORDS_FOR_RARITY: usize = 5;
+
+const FAST_WEIGHT: i32 = 20;
+const NEAR_OPTIMAL_WEIGHT: i32 = 20;
+/// Extra weight for a near-optimal set found quickly, which alone is what
+/// a solver pasting its output looks like
+const FAST_AND_NEAR_OPTIMAL_WEIGHT: i32 = 30;
+const RARE_WORDS_WEIGHT: i32 = 30;
+
+pub const MAX_SUSPICION_SCORE: i32 = 100;
+
+#[derive(Clone, Debug)]
+pub struct IntegrityConfig {
+    /// Completions sooner than this after the entry was first saved count as fast
+    pub min_plausible_time: Duration,
+    /// Share of the optimal words a submission must contain to count as near-optimal
+    pub near_optimal_fraction: f64,
+    /// Words seen fewer times than this in the frequency corpus are rare
+    pub rare_word_count: u64,
+    /// Share of a submission's words that can be rare before it stands out
+    pub max_rare_fraction: f64,
+    /// Entries scoring at least this are kept out of stats and leaderboards
+    pub exclude_threshold: i32,
+}
+
+impl Default for IntegrityConfig {
+    fn default() -> Self {
+        Self {
+            min_plausible_time: Duration::from_secs(60),
+            near_optimal_fraction: 0.8,
+            rare_word_count: 100,
+            max_rare_fraction: 0.5,
+            exclude_threshold: 70,
+        }
+    }
+}
+
+impl IntegrityConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(seconds) = env::var("INTEGRITY_MIN_PLAUSIBLE_SECONDS") {
+            if let Ok(value) = seconds.parse::<u64>() {
+                config.min_plausible_time = Duration::from_secs(value);
+            }
+        }
+
+        if let Ok(fraction) = env::var("INTEGRITY_NEAR_OPTIMAL_FRACTION") {
+            if let Ok(value) = fraction.parse::<f64>() {
+                config.near_optimal_fraction = value.clamp(0.0, 1.0);
+            }
+        }
+
+        if let Ok(count) = env::var("INTEGRITY_RARE_WORD_COUNT") {
+            if let Ok(value) = count.parse::<u64>() {
+                config.rare_word_count = value;
+            }
+        }
+
+        if let Ok(fraction) = env::var("INTEGRITY_MAX_RARE_FRACTION") {
+            if let Ok(value) = fraction.parse::<f64>() {
+                config.max_rare_fraction = value.clamp(0.0, 1.0);
+            }
+        }
+
+        if let Ok(threshold) = env::var("INTEGRITY_EXCLUDE_THRESHOLD") {
+            if let Ok(value) = threshold.parse::<i32>() {
+                config.exclude_threshold = value;
+            }
+        }
+
+        config
+    }
+
+    /// Weigh up a completed submission. `solve_time` runs from the entry
+    /// first being saved to this submission; `optimal_words` are the game's
+    /// best answers.
+    pub fn assess(
+        &self,
+        solve_time: Duration,
+        words: &[&str],
+        optimal_words: &[&str],
+        corpus: Option<&FrequencyCorpus>,
+    ) -> Assessment {
+        let submitted: HashSet<&str> = words.iter().copied().collect();
+        let optimal_found = optimal_words
+            .iter()
+            .filter(|word| submitted.contains(*word))
+            .count();
+        let rare_words = corpus.map_or(0, |corpus| {
+            submitted
+                .iter()
+                .filter(|word| corpus.count(word) < self.rare_word_count)
+                .count()
+        });
+
+        let fast = solve_time < self.min_plausible_time;
+        let near_optimal = !optimal_words.is_empty()
+            && optimal_found as f64 >= self.near_optimal_fraction * optimal_words.len() as f64;
+        let mostly_rare = submitted.len() >= MIN_WORDS_FOR_RARITY
+            && rare_words as f64 > self.max_rare_fraction * submitted.len() as f64;
+
+        let mut score = 0;
+        if fast {
+            score += FAST_WEIGHT;
+        }
+        if near_optimal {
+            score += NEAR_OPTIMAL_WEIGHT;
+        }
+        if fast && near_optimal {
+            score += FAST_AND_NEAR_OPTIMAL_WEIGHT;
+        }
+        if mostly_rare {
+            score += RARE_WORDS_WEIGHT;
+        }
+
+        Assessment {
+            suspicion_score: score.min(MAX_SUSPICION_SCORE),
+            fast,
+            near_optimal,
+            mostly_rare,
+        }
+    }
+
+    pub fn excludes(&self, assessment: &Assessment) -> bool {
+        assessment.suspicion_score >= self.exclude_threshold
+    }
+}
+
+/// What the integrity checks made of one submission
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Assessment {
+    /// 0 for nothing unusual, up to MAX_SUSPICION_SCORE
+    pub suspicion_score: i32,
+    pub fast: bool,
+    pub near_optimal: bool,
+    pub mostly_rare: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPTIMAL: [&str; 5] = ["stone", "notes", "tones", "onset", "seton"];
+
+    #[test]
+    fn test_fast_near_optimal_submissions_are_excluded() {
+        let config = IntegrityConfig::default();
+
+        let pasted = config.assess(Duration::from_secs(5), &OPTIMAL, &OPTIMAL, None);
+        assert!(pasted.fast && pasted.near_optimal);
+        assert_eq!(pasted.suspicion_score, 70);
+        assert!(config.excludes(&pasted));
+
+        // Either on its own is what a quick or a strong player looks like
+        let quick = config.assess(Duration::from_secs(5), &["stone", "one"], &OPTIMAL, None);
+        assert_eq!(quick.suspicion_score, 20);
+        assert!(!config.excludes(&quick));
+        let strong = config.assess(Duration::from_secs(600), &OPTIMAL, &OPTIMAL, None);
+        assert_eq!(strong.suspicion_score, 20);
+        assert!(!config.excludes(&strong));
+    }
+
+    #[test]
+    fn test_mostly_rare_words_add_suspicion() {
+        let config = IntegrityConfig::default();
+        let corpus = FrequencyCorpus::parse("stone 5000\nnotes 4000\none 9000\n").unwrap();
+        let slow = Duration::from_secs(600);
+
+        // Three of five are rare
+        let words = ["stone", "notes", "tones", "onset", "seton"];
+        let assessment = config.assess(slow, &words, &[], Some(&corpus));
+        assert!(assessment.mostly_rare);
+        assert_eq!(assessment.suspicion_score, 30);
+
+        let words = ["stone", "notes", "one", "onset", "seton"];
+        assert!(!config.assess(slow, &words, &[], Some(&corpus)).mostly_rare);
+        // Too few words to judge, and no corpus to judge them by
+        assert!(
+            !config
+                .assess(slow, &["tones", "seton"], &[], Some(&corpus))
+                .mostly_rare
+        );
+        assert!(!config.assess(slow, &OPTIMAL, &[], None).mostly_rare);
+    }
+}
@@ -1,10 +1,53 @@
+pub mod account;
+pub mod account_api;
+pub mod admin_api;
+pub mod api_error;
+pub mod audit;
+pub mod backup;
+pub mod blitz_api;
+pub mod board_preview;
+pub mod calibration;
+pub mod custom_game_api;
 pub mod db;
+pub mod discord_api;
+pub mod etag;
 pub mod game;
+pub mod game_batch;
+pub mod game_cache;
 pub mod game_generator;
+pub mod grpc_api;
+pub mod hints;
+pub mod history_api;
 pub mod http_api;
+pub mod integrity;
+pub mod live_api;
+pub mod logging;
 pub mod memory_profiler;
+pub mod metrics;
+pub mod notification_api;
+pub mod notifications;
+pub mod notifier;
+pub mod outbox;
+pub mod paths_cache;
+pub mod practice_api;
+pub mod progress_api;
+pub mod raster;
+pub mod scheduler;
 pub mod security;
+pub mod server;
+pub mod share_card;
+pub mod shutdown;
 pub mod social;
+pub mod speed_api;
+pub mod stats_freeze;
+pub mod tile_fairness;
+pub mod tournament_api;
+pub mod user_data_api;
+pub mod user_stats_api;
+pub mod webhook_api;
+pub mod word_popularity;
+pub mod wordlist;
 
 #[cfg(test)]
+#[cfg_attr(not(feature = "database-tests"), allow(dead_code))]
 mod test_utils;
@@ -0,0 +1,488 @@
+use anyhow::Context;
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
+    routing::get,
+    Router,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use futures_util::stream;
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    convert::Infallible,
+    sync::{
+        atomic::{AtomicI32, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::api_error::ApiError;
+use crate::db::{models::DbPlayerCounts, Repository};
+use crate::http_api::ApiState;
+use crate::stats_freeze::latest_freezable_date;
+
+/// How long counts are kept before being reloaded from the database. Reloading
+/// corrects any drift from writes the counters didn't see.
+const LIVE_COUNTS_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// How often a live ticker checks for new counts
+const LIVE_TICK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Words kept per game for the spectator feed
+const LIVE_FEED_WORDS: usize = 50;
+
+/// How long a game's feed is kept once nothing is submitted or watched
+const LIVE_FEED_TTI: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApiLiveCounts {
+    /// Players who have started the game but not submitted
+    pub in_progress: i32,
+    pub completed: i32,
+}
+
+impl From<DbPlayerCounts> for ApiLiveCounts {
+    fn from(counts: DbPlayerCounts) -> Self {
+        Self {
+            in_progress: counts.in_progress,
+            completed: counts.completed,
+        }
+    }
+}
+
+/// A submitted word as spectators see it. Until the game's day has ended
+/// everywhere only its length and score are shown.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApiLiveWord {
+    /// Increases with each word submitted for the game
+    pub id: u64,
+    pub length: usize,
+    pub score: i32,
+    pub word: Option<String>,
+    pub submitted_at: DateTime<Utc>,
+}
+
+/// A game's player counts with the words submitted for it most recently
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApiLiveGame {
+    #[serde(flatten)]
+    pub counts: ApiLiveCounts,
+    /// Oldest first
+    pub words: Vec<ApiLiveWord>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct LiveQuery {
+    /// Only words with a greater id, for clients polling the feed
+    pub since: Option<u64>,
+}
+
+#[derive(Default)]
+struct PlayerCounters {
+    in_progress: AtomicI32,
+    completed: AtomicI32,
+}
+
+/// LiveCounts keeps approximate player counts per game. Counts are loaded
+/// from the database the first time a game is asked about and then kept up
+/// to date as entries are written, so reading them is cheap enough to poll.
+#[derive(Clone)]
+pub struct LiveCounts {
+    games: Cache<String, Arc<PlayerCounters>>,
+}
+
+impl Default for LiveCounts {
+    fn default() -> Self {
+        Self {
+            games: Cache::builder()
+                .max_capacity(100)
+                .time_to_live(LIVE_COUNTS_TTL)
+                .build(),
+        }
+    }
+}
+
+impl LiveCounts {
+    /// Counts for a game, or None if there is no such game
+    pub async fn get<R: Repository>(
+        &self,
+        repository: &R,
+        game_id: &str,
+    ) -> anyhow::Result<Option<DbPlayerCounts>> {
+        if let Some(counters) = self.games.get(game_id).await {
+            return Ok(Some(DbPlayerCounts {
+                in_progress: counters.in_progress.load(Ordering::Relaxed),
+                completed: counters.completed.load(Ordering::Relaxed),
+            }));
+        }
+
+        if repository.get_game_by_id(game_id).await?.is_none() {
+            return Ok(None);
+        }
+
+        let counts = repository.get_player_counts(game_id).await?;
+        self.games
+            .insert(
+                game_id.to_string(),
+                Arc::new(PlayerCounters {
+                    in_progress: AtomicI32::new(counts.in_progress),
+                    completed: AtomicI32::new(counts.completed),
+                }),
+            )
+            .await;
+        Ok(Some(counts))
+    }
+
+    /// Record a written entry. `started` is whether the entry is new and
+    /// `completed` whether it was submitted. Games nobody is watching aren't
+    /// tracked; their counts are loaded fresh when first asked for.
+    pub async fn record_entry(&self, game_id: &str, started: bool, completed: bool) {
+        let Some(counters) = self.games.get(game_id).await else {
+            return;
+        };
+
+        if started {
+            counters.in_progress.fetch_add(1, Ordering::Relaxed);
+        }
+        if completed {
+            counters.in_progress.fetch_sub(1, Ordering::Relaxed);
+            counters.completed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LiveWord {
+    id: u64,
+    word: String,
+    score: i32,
+    submitted_at: DateTime<Utc>,
+}
+
+impl LiveWord {
+    fn to_api(&self, revealed: bool) -> ApiLiveWord {
+        ApiLiveWord {
+            id: self.id,
+            length: crate::game::letters::letter_count(&self.word),
+            score: self.score,
+            word: revealed.then(|| self.word.clone()),
+            submitted_at: self.submitted_at,
+        }
+    }
+}
+
+#[derive(Default)]
+struct FeedLog {
+    /// The game's date, known once a word is submitted
+    date: Option<String>,
+    last_id: u64,
+    words: VecDeque<LiveWord>,
+}
+
+struct GameFeed {
+    log: Mutex<FeedLog>,
+    sender: broadcast::Sender<ApiLiveWord>,
+}
+
+/// LiveFeed keeps the words most recently submitted for each game and
+/// broadcasts new ones to anyone watching. Like the counts it is this
+/// process's own view and starts empty when the process does.
+#[derive(Clone)]
+pub struct LiveFeed {
+    games: Cache<String, Arc<GameFeed>>,
+}
+
+impl Default for LiveFeed {
+    fn default() -> Self {
+        Self {
+            games: Cache::builder()
+                .max_capacity(100)
+                .time_to_idle(LIVE_FEED_TTI)
+                .build(),
+        }
+    }
+}
+
+impl LiveFeed {
+    async fn game(&self, game_id: &str) -> Arc<GameFeed> {
+        self.games
+            .get_with(game_id.to_string(), async {
+                Arc::new(GameFeed {
+                    log: Mutex::default(),
+                    sender: broadcast::channel(LIVE_FEED_WORDS).0,
+                })
+            })
+            .await
+    }
+
+    /// Record the scored words of an entry submitted for the game played on
+    /// `date`, and send them to the game's watchers
+    pub async fn record_submission(
+        &self,
+        game_id: &str,
+        date: &str,
+        words: impl IntoIterator<Item = (String, i32)>,
+    ) {
+        let feed = self.game(game_id).await;
+        let revealed = has_ended(date);
+        let submitted_at = Utc::now();
+        let mut log = feed.log.lock().unwrap_or_else(|e| e.into_inner());
+        log.date = Some(date.to_string());
+        for (word, score) in words {
+            log.last_id += 1;
+            let word = LiveWord {
+                id: log.last_id,
+                word,
+                score,
+                submitted_at,
+            };
+            // There may be nobody watching
+            let _ = feed.sender.send(word.to_api(revealed));
+            log.words.push_back(word);
+            if log.words.len() > LIVE_FEED_WORDS {
+                log.words.pop_front();
+            }
+        }
+    }
+
+    /// A game's recent words with ids after `since`, oldest first. A `since`
+    /// past every id is from before the feed started over, so all its words
+    /// are returned.
+    pub async fn recent(&self, game_id: &str, since: Option<u64>) -> Vec<ApiLiveWord> {
+        let Some(feed) = self.games.get(game_id).await else {
+            return Vec::new();
+        };
+        let log = feed.log.lock().unwrap_or_else(|e| e.into_inner());
+        let since = since.filter(|&since| since <= log.last_id).unwrap_or(0);
+        let revealed = log.date.as_deref().is_some_and(has_ended);
+        log.words
+            .iter()
+            .filter(|word| word.id > since)
+            .map(|word| word.to_api(revealed))
+            .collect()
+    }
+
+    /// Words submitted for a game from now on
+    pub async fn subscribe(&self, game_id: &str) -> broadcast::Receiver<ApiLiveWord> {
+        self.game(game_id).await.sender.subscribe()
+    }
+}
+
+/// Whether the day of the game played on `date` has ended everywhere, so
+/// its words no longer give anything away
+fn has_ended(date: &str) -> bool {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .is_ok_and(|date| date <= latest_freezable_date(Utc::now(), chrono::Duration::zero()))
+}
+
+/// Routes for live player counts and the spectator feed of submitted words.
+/// Clients that accept `text/event-stream` get a `counts` event whenever the
+/// counts change and a `word` event for each word submitted instead of a
+/// single JSON response; others can poll with `since`.
+pub fn live_router<R: Repository + Clone + Send + Sync + 'static>() -> Router<ApiState<R>> {
+    Router::new().route("/api/game/:game_id/live", get(get_live_game))
+}
+
+async fn get_live_game<R: Repository + Clone + Send + Sync + 'static>(
+    Path(game_id): Path<String>,
+    Query(query): Query<LiveQuery>,
+    State(state): State<ApiState<R>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let counts = load_live_counts(&state, &game_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Game"))?;
+
+    let wants_stream = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/event-stream"));
+    if !wants_stream {
+        let words = state.live_feed.recent(&game_id, query.since).await;
+        return Ok(Json(ApiLiveGame { counts, words }).into_response());
+    }
+
+    let ticks = tokio::time::interval(LIVE_TICK_INTERVAL);
+    let words = state.live_feed.subscribe(&game_id).await;
+    let events = stream::unfold((ticks, words, None), move |(mut ticks, mut words, last)| {
+        let state = state.clone();
+        let game_id = game_id.clone();
+        async move {
+            loop {
+                tokio::select! {
+                    _ = ticks.tick() => {
+                        let counts = load_live_counts(&state, &game_id).await.ok()??;
+                        if last != Some(counts) {
+                            let event = Event::default().event("counts").json_data(counts).ok()?;
+                            return Some((Ok::<_, Infallible>(event), (ticks, words, Some(counts))));
+                        }
+                    }
+                    word = words.recv() => match word {
+                        Ok(word) => {
+                            let event = Event::default()
+                                .event("word")
+                                .id(word.id.to_string())
+                                .json_data(&word)
+                                .ok()?;
+                            return Some((Ok(event), (ticks, words, last)));
+                        }
+                        // A watcher too slow to keep up misses some words
+                        Err(RecvError::Lagged(_)) => {}
+                        // The game's feed was dropped from the cache, so
+                        // follow the one that replaces it
+                        Err(RecvError::Closed) => words = state.live_feed.subscribe(&game_id).await,
+                    },
+                }
+            }
+        }
+    });
+
+    Ok(Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response())
+}
+
+async fn load_live_counts<R: Repository>(
+    state: &ApiState<R>,
+    game_id: &str,
+) -> Result<Option<ApiLiveCounts>, ApiError> {
+    let counts = state
+        .live_counts
+        .get(&state.repository, game_id)
+        .await
+        .with_context(|| format!("Failed to load live counts for game {game_id}"))?;
+    Ok(counts.map(ApiLiveCounts::from))
+}
+
+#[cfg(all(test, feature = "database-tests"))]
+mod tests {
+    use super::*;
+    use axum::http::{Method, StatusCode};
+    use tower::util::ServiceExt;
+
+    use crate::http_api::{ApiAnswer, UpdateGameEntryRequest};
+    use crate::test_utils::{create_new_test_game, create_test_request, setup_app};
+
+    async fn live_counts(app: &Router, game_id: &str) -> ApiLiveCounts {
+        let uri = format!("/api/game/{game_id}/live");
+        let request = create_test_request(Method::GET, &uri, None);
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_live_counts_follow_entry_writes(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let (state, app) = setup_app(pool).await;
+        let (game, _) = state
+            .repository
+            .create_game_with_answers(create_new_test_game(), vec![], None)
+            .await
+            .unwrap();
+
+        let idle = ApiLiveCounts {
+            in_progress: 0,
+            completed: 0,
+        };
+        assert_eq!(live_counts(&app, &game.id).await, idle);
+
+        let mut entry = UpdateGameEntryRequest {
+            user_id: None,
+            cookie_token: None,
+            answers: vec![ApiAnswer {
+                word: "test".to_string(),
+                score: 0,
+                path: None,
+            }],
+            game_id: game.id.clone(),
+            completed: false,
+            start_token: None,
+            request_id: None,
+        };
+        let uri = format!("/api/game-entry/{}", game.id);
+        let body = serde_json::to_string(&entry).unwrap();
+        let request = create_test_request(Method::POST, &uri, Some(&body));
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            live_counts(&app, &game.id).await,
+            ApiLiveCounts {
+                in_progress: 1,
+                completed: 0,
+            }
+        );
+
+        // A second player submits straight away
+        entry.completed = true;
+        let body = serde_json::to_string(&entry).unwrap();
+        let request = create_test_request(Method::POST, &uri, Some(&body));
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let expected = ApiLiveCounts {
+            in_progress: 1,
+            completed: 1,
+        };
+        assert_eq!(live_counts(&app, &game.id).await, expected);
+
+        // The cached counts agree with the database
+        assert_eq!(
+            ApiLiveCounts::from(state.repository.get_player_counts(&game.id).await.unwrap()),
+            expected
+        );
+
+        // The submitted word is in the feed, spelled out since 2024's games are over
+        let uri = format!("/api/game/{}/live", game.id);
+        let request = create_test_request(Method::GET, &uri, None);
+        let response = app.clone().oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let live: ApiLiveGame = serde_json::from_slice(&body).unwrap();
+        assert_eq!(live.words.len(), 1);
+        assert_eq!(live.words[0].word.as_deref(), Some("test"));
+
+        let request = create_test_request(Method::GET, "/api/game/missing/live", None);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_live_feed_hides_words_until_the_day_ends() {
+        let feed = LiveFeed::default();
+        let mut watcher = feed.subscribe("today").await;
+        feed.record_submission(
+            "today",
+            "2999-01-01",
+            [("cat".to_string(), 5), ("tests".to_string(), 9)],
+        )
+        .await;
+        feed.record_submission("past", "2024-01-01", [("cat".to_string(), 5)])
+            .await;
+
+        let sent = watcher.recv().await.unwrap();
+        assert_eq!((sent.id, sent.length, sent.score), (1, 3, 5));
+        assert_eq!(sent.word, None);
+
+        let recent = feed.recent("today", Some(1)).await;
+        assert_eq!(recent.len(), 1);
+        assert_eq!((recent[0].id, recent[0].length), (2, 5));
+        // A since from before the feed started over returns every word
+        assert_eq!(feed.recent("today", Some(100)).await.len(), 2);
+        assert_eq!(
+            feed.recent("past", None).await[0].word.as_deref(),
+            Some("cat")
+        );
+        assert!(feed.recent("missing", None).await.is_empty());
+    }
+}
@@ -0,0 +1,48 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// LogSampler lets one in every `every` calls through, for debug logs on
+/// paths that every request takes. Keep one in a static per log line.
+pub struct LogSampler {
+    every: u64,
+    calls: AtomicU64,
+}
+
+impl LogSampler {
+    pub const fn every(every: u64) -> Self {
+        Self {
+            every,
+            calls: AtomicU64::new(0),
+        }
+    }
+
+    /// sample reports whether this call should be logged. The first call
+    /// always is.
+    pub fn sample(&self) -> bool {
+        self.calls
+            .fetch_add(1, Ordering::Relaxed)
+            .is_multiple_of(self.every.max(1))
+    }
+}
+
+/// without_query drops a URL's query string and fragment, where clients
+/// pass user ids and cookie tokens, so the rest can be logged
+pub fn without_query(url: &str) -> &str {
+    url.split(['?', '#']).next().unwrap_or(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampler_logs_one_in_every() {
+        static SAMPLER: LogSampler = LogSampler::every(3);
+        let logged: Vec<bool> = (0..7).map(|_| SAMPLER.sample()).collect();
+        assert_eq!(logged, vec![true, false, false, true, false, false, true]);
+
+        assert_eq!(
+            without_query("https://example.com/play?cookie_token=secret#top"),
+            "https://example.com/play"
+        );
+    }
+}
@@ -1,12 +1,271 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use clap::{Arg, ArgMatches, Command};
 use dotenvy::dotenv;
-use std::{env, time::Duration};
-use tracing::info;
+use sqlx::{PgPool, SqlitePool};
+use std::{
+    collections::HashSet,
+    env,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{info, warn};
 
-use pathfinder::db::{setup_database, SqliteRepository};
-use pathfinder::game::GameEngine;
-use pathfinder::memory_profiler::MemoryProfiler;
-use pathfinder::security::SecurityConfig;
+use pathfinder::account::Accounts;
+use pathfinder::backup::{self, s3::S3Storage, BackupConfig, SqliteBackups};
+use pathfinder::blitz_api::BlitzConfig;
+use pathfinder::board_preview::PreviewCache;
+use pathfinder::calibration::CalibrationConfig;
+use pathfinder::custom_game_api::CustomGameConfig;
+use pathfinder::db::{
+    memory_backend_unsupported, setup_database, setup_postgres_database, DatabaseBackend,
+    InMemoryRepository, MeteredRepository, PgRepository, Repository, SqliteConfig,
+    SqliteRepository,
+};
+use pathfinder::discord_api::DiscordConfig;
+use pathfinder::game::{
+    board::modifier::ModifierCounts,
+    dawg::dictionary_path,
+    language::LanguageEngines,
+    optimizer::optimization_budget_from_env,
+    qu_tiles_from_env,
+    rules::GameRules,
+    scoring::{BonusRules, ScoringSchemes},
+    theme::{Theme, ThemeSchedule},
+    Board, GameEngine,
+};
+use pathfinder::game_generator::{
+    generate_daily_board_with, generation_concurrency_from_env, GameGenerator, GenerationConfig,
+};
+use pathfinder::hints::HintConfig;
+use pathfinder::integrity::IntegrityConfig;
+use pathfinder::memory_profiler::{MemoryConfig, MemoryHistory, MemoryProfiler};
+use pathfinder::metrics::Metrics;
+use pathfinder::notifier::{HttpDeliverer, NotifierConfig, WebhookNotifier};
+use pathfinder::outbox::{EventWebhook, OutboxConfig};
+use pathfinder::paths_cache::PathsCache;
+use pathfinder::progress_api::ProgressConfig;
+use pathfinder::scheduler::{GenerationScheduler, SchedulerConfig};
+use pathfinder::security::{start_token::StartTokens, user_cookie::UserCookies, SecurityConfig};
+use pathfinder::shutdown::{shutdown_signal, BackgroundTasks, ShutdownConfig};
+use pathfinder::social::{
+    announcer::{AnnouncerConfig, DailyAnnouncer},
+    fanout::SocialPoster,
+};
+use pathfinder::speed_api::SpeedConfig;
+use pathfinder::tile_fairness::FairnessConfig;
+use pathfinder::wordlist::{
+    formats::read_source, load_wordlist, reference_words_from_env, replay_all, Curator,
+    DefinitionApi, DefinitionLookup, Definitions, DenyList, DisplayCasing, FrequencyCorpus,
+    ReplayReport, SpellingVariants, WordlistDiff, WordlistFormat, DEFINITIONS_PATH,
+    DISPLAY_CASING_PATH,
+};
+
+/// The command line: serving the API is the default, the other subcommands
+/// run the game engine on its own, without the HTTP server or a database
+fn cli() -> Command {
+    let board = Arg::new("board")
+        .long("board")
+        .required(true)
+        .value_name("LETTERS")
+        .value_parser(|s: &str| s.parse::<Board>())
+        .help(
+            "The 16 letters of the board row by row, with * for wildcards, e.g. tmitc*otsa*iinal",
+        );
+    let scoring_scheme = Arg::new("scoring-scheme")
+        .long("scoring-scheme")
+        .value_name("VERSION")
+        .value_parser(clap::value_parser!(i32))
+        .help(
+            "Version of the scoring scheme tiles are worth points by, defaults to the active one",
+        );
+
+    Command::new("api-server")
+        .about("Pathfinder word game server and board tools")
+        .subcommand(Command::new("serve").about("Run the HTTP API (the default)"))
+        .subcommand(
+            Command::new("generate")
+                .about("Generate the daily board for a date without storing it")
+                .arg(
+                    Arg::new("date")
+                        .long("date")
+                        .required(true)
+                        .value_name("YYYY-MM-DD")
+                        .value_parser(|s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d")),
+                )
+                .arg(
+                    Arg::new("generation-version")
+                        .long("generation-version")
+                        .value_name("VERSION")
+                        .value_parser(|s: &str| {
+                            s.parse::<i32>()
+                                .ok()
+                                .and_then(GenerationConfig::for_version)
+                                .ok_or_else(|| format!("unknown generation version {s}"))
+                        })
+                        .help("Regenerate the board with the rules of an earlier version, defaults to the current one"),
+                )
+                .arg(
+                    Arg::new("theme")
+                        .long("theme")
+                        .value_name("SPEC")
+                        .value_parser(|s: &str| s.parse::<Theme>())
+                        .help("Theme the board must contain, e.g. word:snow or letters:xmas, defaults to the date's scheduled theme"),
+                )
+                .arg(scoring_scheme.clone()),
+        )
+        .subcommand(
+            Command::new("solve")
+                .about("List every word on a board with its score, and the best words")
+                .arg(board.clone())
+                .arg(scoring_scheme.clone())
+                .arg(
+                    Arg::new("best")
+                        .long("best")
+                        .value_name("N")
+                        .default_value("5")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("How many words the optimal set has"),
+                ),
+        )
+        .subcommand(
+            Command::new("validate")
+                .about("Check that a group of words can all be played on a board and score them")
+                .arg(board)
+                .arg(scoring_scheme)
+                .arg(
+                    Arg::new("words")
+                        .long("words")
+                        .required(true)
+                        .value_name("WORD,WORD,...")
+                        .value_delimiter(','),
+                ),
+        )
+        .subcommand(
+            Command::new("wordlist")
+                .about("Build and check the wordlist")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("build")
+                        .about("Rebuild the wordlist from raw dictionaries")
+                        .arg(
+                            Arg::new("sources")
+                                .required(true)
+                                .num_args(1..)
+                                .value_name("SOURCE")
+                                .value_parser(clap::value_parser!(PathBuf)),
+                        )
+                        .arg(
+                            Arg::new("format")
+                                .long("format")
+                                .value_name("plain|annotated")
+                                .value_parser(|s: &str| s.parse::<WordlistFormat>())
+                                .help("Line format of the sources, detected from each unless given"),
+                        )
+                        .arg(
+                            Arg::new("frequencies")
+                                .long("frequencies")
+                                .value_name("CORPUS")
+                                .value_parser(clap::value_parser!(PathBuf))
+                                .help("Frequency corpus of `word count` lines words must occur in"),
+                        )
+                        .arg(
+                            Arg::new("min-frequency")
+                                .long("min-frequency")
+                                .value_name("COUNT")
+                                .default_value("1")
+                                .value_parser(clap::value_parser!(u64))
+                                .help("How often a word must occur in the corpus"),
+                        )
+                        .arg(
+                            Arg::new("deny")
+                                .long("deny")
+                                .value_name("LIST")
+                                .value_parser(clap::value_parser!(PathBuf)),
+                        )
+                        .arg(
+                            Arg::new("output")
+                                .long("output")
+                                .value_name("WORDLIST")
+                                .default_value("wordlist")
+                                .value_parser(clap::value_parser!(PathBuf)),
+                        ),
+                )
+                .subcommand(
+                    Command::new("replay")
+                        .about("Check every stored game against the current engine and dictionary")
+                        .arg(
+                            Arg::new("limit")
+                                .long("limit")
+                                .value_name("GAMES")
+                                .value_parser(clap::value_parser!(usize))
+                                .help("Only replay the most recent games"),
+                        )
+                        .arg(
+                            Arg::new("json")
+                                .long("json")
+                                .value_name("REPORT")
+                                .value_parser(clap::value_parser!(PathBuf))
+                                .help("Also write the report to a JSON file"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("definitions")
+                        .about("Build the offline definitions from WordNet data files")
+                        .arg(
+                            Arg::new("data-files")
+                                .required(true)
+                                .num_args(1..)
+                                .value_name("DATA_FILE")
+                                .value_parser(clap::value_parser!(PathBuf))
+                                .help("WordNet's data.noun, data.verb, data.adj and data.adv"),
+                        )
+                        .arg(
+                            Arg::new("wordlist")
+                                .long("wordlist")
+                                .value_name("WORDLIST")
+                                .default_value("wordlist")
+                                .value_parser(clap::value_parser!(PathBuf))
+                                .help("Only words in this list are defined"),
+                        )
+                        .arg(
+                            Arg::new("output")
+                                .long("output")
+                                .value_name("DEFINITIONS")
+                                .default_value(DEFINITIONS_PATH)
+                                .value_parser(clap::value_parser!(PathBuf)),
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("backup")
+                .about("Back up the SQLite database now")
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .value_name("FILE")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("Write the backup here instead of the backup directory and any S3 bucket"),
+                ),
+        )
+        .subcommand(
+            Command::new("restore")
+                .about("Replace the SQLite database with a backup; stop the server first")
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .required(true)
+                        .value_name("FILE|s3://BUCKET/KEY"),
+                )
+                .arg(
+                    Arg::new("database")
+                        .long("database")
+                        .value_name("FILE")
+                        .value_parser(clap::value_parser!(PathBuf))
+                        .help("Database to replace, defaults to the configured one"),
+                ),
+        )
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -18,31 +277,410 @@ async fn main() -> Result<()> {
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .init();
 
+    let matches = cli().get_matches();
+    match matches.subcommand() {
+        Some(("generate", args)) => {
+            let date = args.get_one::<NaiveDate>("date").expect("date is required");
+            let config = args
+                .get_one::<GenerationConfig>("generation-version")
+                .copied()
+                .unwrap_or_default();
+            let date_key = date.format("%Y-%m-%d").to_string();
+            let theme = args
+                .get_one::<Theme>("theme")
+                .or(ThemeSchedule::from_env().get(&date_key))
+                .cloned();
+            let game_engine = load_scored_game_engine(args)?;
+            generate(&game_engine, *date, config, theme).await
+        }
+        Some(("solve", args)) => {
+            let game_engine = load_scored_game_engine(args)?;
+            let mut board = args
+                .get_one::<Board>("board")
+                .expect("board is required")
+                .clone();
+            board.score_with(game_engine.scoring_schemes().active());
+            let best = *args.get_one::<usize>("best").expect("best has a default");
+            solve(&game_engine, &board, best).await
+        }
+        Some(("validate", args)) => {
+            let game_engine = load_scored_game_engine(args)?;
+            let mut board = args
+                .get_one::<Board>("board")
+                .expect("board is required")
+                .clone();
+            board.score_with(game_engine.scoring_schemes().active());
+            let words: Vec<String> = args
+                .get_many::<String>("words")
+                .expect("words are required")
+                .map(|word| word.trim().to_lowercase())
+                .filter(|word| !word.is_empty())
+                .collect();
+            validate(&game_engine, &board, words)
+        }
+        Some(("wordlist", args)) => match args.subcommand() {
+            Some(("build", args)) => build_wordlist(args),
+            Some(("replay", args)) => {
+                let limit = args.get_one::<usize>("limit").copied();
+                replay_answers(limit, args.get_one::<PathBuf>("json")).await
+            }
+            Some(("definitions", args)) => build_definitions(args),
+            _ => unreachable!("wordlist requires a subcommand"),
+        },
+        Some(("backup", args)) => backup_database(args.get_one::<PathBuf>("to")).await,
+        Some(("restore", args)) => {
+            let from = args.get_one::<String>("from").expect("from is required");
+            restore_database(from, args.get_one::<PathBuf>("database").cloned()).await
+        }
+        _ => serve().await,
+    }
+}
+
+fn load_game_engine() -> GameEngine {
+    let game_engine = GameEngine::new(dictionary_path())
+        .with_spelling_variants(&SpellingVariants::from_env())
+        .with_display_casing(DisplayCasing::load_or_default(Path::new(
+            DISPLAY_CASING_PATH,
+        )))
+        .with_reference_words(reference_words_from_env())
+        .with_scoring_schemes(ScoringSchemes::from_env())
+        .with_bonus_rules(BonusRules::from_env())
+        .with_game_rules(GameRules::from_env())
+        .with_modifier_counts(ModifierCounts::from_env())
+        .with_qu_tiles(qu_tiles_from_env())
+        .with_generation_concurrency(generation_concurrency_from_env())
+        .with_optimization_budget(optimization_budget_from_env());
+    match FrequencyCorpus::from_env() {
+        Some(corpus) => game_engine.with_frequency_corpus(corpus),
+        None => game_engine,
+    }
+}
+
+/// load_scored_game_engine loads the engine with the scoring scheme chosen by
+/// a subcommand's --scoring-scheme active
+fn load_scored_game_engine(args: &ArgMatches) -> Result<GameEngine> {
+    let game_engine = load_game_engine();
+    let Some(&version) = args.get_one::<i32>("scoring-scheme") else {
+        return Ok(game_engine);
+    };
+    let mut schemes = game_engine.scoring_schemes().clone();
+    schemes.set_active(version)?;
+    Ok(game_engine.with_scoring_schemes(schemes))
+}
+
+/// generate prints the board the generator creates for `date` under
+/// `config`. Boards only depend on the date, the generation version, the
+/// theme and the scoring scheme, so this is the board any server stores for
+/// that day.
+async fn generate(
+    game_engine: &GameEngine,
+    date: NaiveDate,
+    config: GenerationConfig,
+    theme: Option<Theme>,
+) -> Result<()> {
+    let target = config.target_for(date);
+    let date = date.format("%Y-%m-%d").to_string();
+    let generated = generate_daily_board_with(game_engine, &date, config, theme.as_ref()).await?;
+
+    println!(
+        "Board for {date} (generation version {}, scoring scheme {}):",
+        config.version,
+        game_engine.scoring_schemes().active().version
+    );
+    if let Some(theme) = &theme {
+        println!("Theme: {theme}");
+    }
+    print!("{}", generated.board);
+    println!("Threshold: {}", generated.threshold_score);
+    println!("Valid words: {}", generated.answers.len());
+    println!(
+        "Difficulty: {} ({:.0})",
+        generated.difficulty(),
+        generated.difficulty_score()
+    );
+    if let Some(target) = target {
+        println!("Target difficulty: {target}");
+    }
+    println!("Optimal words ({} points):", generated.optimal_score);
+    for answer in &generated.optimal_words {
+        println!(
+            "  {:>3}  {}",
+            game_engine
+                .bonus_rules()
+                .answer_points(answer, game_engine.frequency_corpus()),
+            game_engine.display_form(&answer.word)
+        );
+    }
+    Ok(())
+}
+
+/// solve prints every word on the board, highest scoring first, then the
+/// best `best` words that can be played together
+async fn solve(game_engine: &GameEngine, board: &Board, best: usize) -> Result<()> {
+    let mut answers = game_engine.find_all_valid_words(board).await?;
+    answers.sort_by(|a, b| b.score().cmp(&a.score()).then_with(|| a.word.cmp(&b.word)));
+
+    print!("{board}");
+    println!("{} valid words:", answers.len());
+    for answer in &answers {
+        println!(
+            "  {:>3}  {}",
+            answer.score(),
+            game_engine.display_form(&answer.word)
+        );
+    }
+
+    let (optimal_words, metadata) =
+        game_engine.find_best_n_words_from_answers(&answers, game_engine.bonus_rules(), best)?;
+    println!("Optimal {best} words ({} points):", metadata.total_score);
+    for (answer, score) in optimal_words.iter().zip(&metadata.individual_scores) {
+        println!("  {:>3}  {}", score, game_engine.display_form(&answer.word));
+    }
+    Ok(())
+}
+
+/// validate checks the words the way a submitted entry is checked: each must
+/// be in the dictionary and on the board, and all of them must agree on the
+/// wildcard letters. It prints the best score the group can reach.
+fn validate(game_engine: &GameEngine, board: &Board, words: Vec<String>) -> Result<()> {
+    for word in &words {
+        game_engine.validate_answer(board, word)?;
+    }
+    let scores = game_engine.score_answer_group(board, words.clone(), game_engine.bonus_rules())?;
+
+    print!("{board}");
+    for word in &words {
+        println!(
+            "  {:>3}  {}",
+            scores.map.get(word).copied().unwrap_or(0),
+            game_engine.display_form(word)
+        );
+    }
+    println!("Valid, {} points", scores.total_score());
+    Ok(())
+}
+
+/// build_wordlist rebuilds the wordlist from raw dictionaries. The output
+/// only depends on the inputs, so the list can be regenerated reproducibly.
+fn build_wordlist(args: &ArgMatches) -> Result<()> {
+    let output = args
+        .get_one::<PathBuf>("output")
+        .expect("output has a default");
+    let mut curator = Curator::new();
+    if let Some(path) = args.get_one::<PathBuf>("frequencies") {
+        info!("Loading frequency corpus from {}", path.display());
+        let min_frequency = *args
+            .get_one::<u64>("min-frequency")
+            .expect("min-frequency has a default");
+        curator = curator.with_corpus(FrequencyCorpus::load(path)?, min_frequency);
+    }
+    if let Some(path) = args.get_one::<PathBuf>("deny") {
+        info!("Loading deny list from {}", path.display());
+        curator = curator.with_deny_list(DenyList::load(path)?);
+    }
+
+    let mut texts = Vec::new();
+    for source in args
+        .get_many::<PathBuf>("sources")
+        .expect("sources are required")
+    {
+        info!("Reading dictionary {}", source.display());
+        texts.push(read_source(source)?);
+    }
+    let format = args.get_one::<WordlistFormat>("format").copied();
+    let raw_words = texts.iter().flat_map(|text| {
+        let format = format.unwrap_or_else(|| WordlistFormat::detect(text));
+        format.extract_words(text)
+    });
+    let (words, report) = curator.curate(raw_words);
+    println!("{report}");
+
+    if output.exists() {
+        let current = load_wordlist(output, Some(WordlistFormat::Plain))?;
+        let diff = WordlistDiff::between(&current, &words);
+        println!(
+            "Compared with {}: {} added, {} removed, {} unchanged",
+            output.display(),
+            diff.added.len(),
+            diff.removed.len(),
+            diff.unchanged
+        );
+    }
+
+    std::fs::write(output, words.join("\n") + "\n")?;
+    println!("Wrote {} words to {}", words.len(), output.display());
+    Ok(())
+}
+
+/// replay_answers replays every stored game's answers through the engine and
+/// dictionary as they are now, to catch words that would change before
+/// shipping an engine or dictionary change. Exits with status 1 when anything
+/// differs.
+async fn replay_answers(limit: Option<usize>, json: Option<&PathBuf>) -> Result<()> {
+    let game_engine =
+        GameEngine::new(dictionary_path()).with_spelling_variants(&SpellingVariants::from_env());
+    let database_backend = DatabaseBackend::from_env()?;
+    let database_url = database_backend.database_url()?;
+
+    let report: ReplayReport = match database_backend {
+        DatabaseBackend::Sqlite => {
+            let pool = SqlitePool::connect(&database_url).await?;
+            replay_all(&SqliteRepository::new(pool), &game_engine, limit).await?
+        }
+        DatabaseBackend::Postgres => {
+            let pool = PgPool::connect(&database_url).await?;
+            replay_all(&PgRepository::new(pool), &game_engine, limit).await?
+        }
+        DatabaseBackend::Memory => {
+            return Err(memory_backend_unsupported("api-server wordlist replay"))
+        }
+    };
+    print!("{report}");
+
+    if let Some(path) = json {
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+        println!("Wrote the report to {}", path.display());
+    }
+    if !report.is_clean() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// build_definitions builds the offline definitions from WordNet's data
+/// files, keeping only words in the wordlist
+fn build_definitions(args: &ArgMatches) -> Result<()> {
+    let wordlist = args
+        .get_one::<PathBuf>("wordlist")
+        .expect("wordlist has a default");
+    let output = args
+        .get_one::<PathBuf>("output")
+        .expect("output has a default");
+    let words: HashSet<String> = load_wordlist(wordlist, Some(WordlistFormat::Plain))?
+        .into_iter()
+        .collect();
+
+    let mut texts = Vec::new();
+    for path in args
+        .get_many::<PathBuf>("data-files")
+        .expect("data files are required")
+    {
+        info!("Reading WordNet data {}", path.display());
+        texts.push(read_source(path)?);
+    }
+    let definitions = Definitions::from_wordnet(texts.iter().map(String::as_str), |word| {
+        words.contains(word)
+    });
+
+    std::fs::write(output, definitions.to_text())?;
+    println!(
+        "Wrote definitions of {} of {} words to {}",
+        definitions.len(),
+        words.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// sqlite_database is the SQLite database file the server is configured to
+/// use
+fn sqlite_database(command: &str) -> Result<PathBuf> {
+    match DatabaseBackend::from_env()? {
+        DatabaseBackend::Sqlite => backup::sqlite_path(&DatabaseBackend::Sqlite.database_url()?),
+        DatabaseBackend::Postgres => Err(anyhow!(
+            "{command} only supports SQLite; back up Postgres with pg_dump"
+        )),
+        DatabaseBackend::Memory => Err(memory_backend_unsupported(command)),
+    }
+}
+
+/// backup_database takes a backup of the SQLite database now, into the
+/// backup directory (and any configured S3 bucket) unless a file is given
+async fn backup_database(to: Option<&PathBuf>) -> Result<()> {
+    let database = sqlite_database("api-server backup")?;
+    let pool = SqlitePool::connect(&format!("sqlite://{}", database.display())).await?;
+
+    match to {
+        Some(path) => {
+            backup::backup_to(&pool, path).await?;
+            backup::verify(path).await?;
+            println!("Backed up {} to {}", database.display(), path.display());
+        }
+        None => {
+            let mut backups = SqliteBackups::new(pool, BackupConfig::from_env());
+            if let Some(storage) = S3Storage::from_env() {
+                backups = backups.with_storage(storage);
+            }
+            let path = backups.run().await?;
+            println!("Backed up {} to {}", database.display(), path.display());
+        }
+    }
+    Ok(())
+}
+
+/// restore_database replaces the SQLite database with a backup, downloading
+/// it first when it is an s3:// url. Stop the server before restoring.
+async fn restore_database(from: &str, database: Option<PathBuf>) -> Result<()> {
+    let database = match database {
+        Some(database) => database,
+        None => sqlite_database("api-server restore")?,
+    };
+
+    let (backup, downloaded) = match from.strip_prefix("s3://") {
+        Some(location) => {
+            let (bucket, key) = location
+                .split_once('/')
+                .ok_or_else(|| anyhow!("--from s3:// urls look like s3://bucket/key"))?;
+            let storage = S3Storage::from_env().ok_or_else(|| {
+                anyhow!("BACKUP_S3_BUCKET and credentials must be set to restore from S3")
+            })?;
+            info!("Downloading {} from {}", key, bucket);
+            let body = storage.get(Some(bucket), key).await?;
+            let mut path = database.clone().into_os_string();
+            path.push(".download");
+            let path = PathBuf::from(path);
+            std::fs::write(&path, body)?;
+            (path, true)
+        }
+        None => (PathBuf::from(from), false),
+    };
+
+    let restored = backup::restore(&backup, &database).await;
+    if downloaded {
+        let _ = std::fs::remove_file(&backup);
+    }
+    if let Some(replaced) = restored? {
+        println!("Moved the previous database to {}", replaced.display());
+    }
+    println!("Restored {} from {}", database.display(), from);
+    Ok(())
+}
+
+/// serve loads the server configuration and database and runs the HTTP API
+async fn serve() -> Result<()> {
     info!("Starting word game backend server");
 
     // Initialize memory profiler
-    let mut memory_profiler = MemoryProfiler::new();
+    let mut memory_profiler =
+        MemoryProfiler::new().with_history(MemoryHistory::new(MemoryConfig::from_env()));
     memory_profiler.log_memory("startup");
 
-    let sqlite_database_url =
-        env::var("SQLITE_DATABASE_URL").unwrap_or_else(|_| "sqlite://pathfinder.db".to_string());
+    let database_backend = DatabaseBackend::from_env()?;
+    let database_url = database_backend.database_url()?;
 
     let server_host = env::var("SERVER_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
     let http_port = env::var("HTTP_PORT")
         .unwrap_or_else(|_| "3001".to_string())
         .parse::<u16>()
         .expect("Invalid HTTP_PORT");
-
-    // Setup database
-    info!("Setting up database connection");
-    let pool = setup_database(&sqlite_database_url).await?;
-    // let _postgres_repository = PgRepository::new(pool.0);
-    let sqlite_repository = SqliteRepository::new(pool);
-    memory_profiler.log_memory("after_database_setup");
+    // The gRPC API is only served when it is given a port
+    let grpc_port = env::var("GRPC_PORT")
+        .ok()
+        .map(|port| port.parse::<u16>().expect("Invalid GRPC_PORT"));
 
     // Setup game engine
     info!("Initializing game engine");
-    let game_engine = GameEngine::new(std::path::PathBuf::from("wordlist"));
+    let game_engine = load_game_engine();
     memory_profiler.log_memory("after_game_engine_init");
 
     // Setup security configuration
@@ -50,45 +688,244 @@ async fn main() -> Result<()> {
     let security_config = SecurityConfig::from_env();
     memory_profiler.log_memory("after_security_config");
 
+    let http_addr = format!("{server_host}:{http_port}");
+    let grpc_addr = grpc_port.map(|port| format!("{server_host}:{port}"));
+
+    // Setup database
+    info!(
+        "Setting up database connection ({:?} backend)",
+        database_backend
+    );
+    match database_backend {
+        DatabaseBackend::Sqlite => {
+            let pools = setup_database(&database_url, &SqliteConfig::from_env()).await?;
+            let backup_config = BackupConfig::from_env();
+            let _backup_job = if backup_config.enabled {
+                info!(
+                    "Backing up the database to {} every {:?}",
+                    backup_config.directory.display(),
+                    backup_config.interval
+                );
+                let mut backups = SqliteBackups::new(pools.reader.clone(), backup_config);
+                if let Some(storage) = S3Storage::from_env() {
+                    backups = backups.with_storage(storage);
+                }
+                Some(backups.start())
+            } else {
+                None
+            };
+            let repository = pools.repository();
+            serve_with(
+                repository,
+                game_engine,
+                security_config,
+                memory_profiler,
+                http_addr,
+                grpc_addr,
+            )
+            .await
+        }
+        DatabaseBackend::Postgres => {
+            let pool = setup_postgres_database(&database_url).await?;
+            let repository = PgRepository::new(pool);
+            serve_with(
+                repository,
+                game_engine,
+                security_config,
+                memory_profiler,
+                http_addr,
+                grpc_addr,
+            )
+            .await
+        }
+        DatabaseBackend::Memory => {
+            // Nothing is kept across restarts, so games only exist once the
+            // scheduler has generated them
+            let repository = InMemoryRepository::new();
+            serve_with(
+                repository,
+                game_engine,
+                security_config,
+                memory_profiler,
+                http_addr,
+                grpc_addr,
+            )
+            .await
+        }
+    }
+}
+
+/// serve_with starts background generation, the HTTP API and, given an
+/// address, the gRPC API on top of whichever repository backend was selected
+/// at startup
+async fn serve_with<R: Repository + Clone + Send + Sync + 'static>(
+    repository: R,
+    game_engine: GameEngine,
+    security_config: SecurityConfig,
+    mut memory_profiler: MemoryProfiler,
+    http_addr: String,
+    grpc_addr: Option<String>,
+) -> Result<()> {
+    memory_profiler.log_memory("after_database_setup");
+
+    // Every repository call is timed for /metrics
+    let metrics = Metrics::default();
+    let repository = MeteredRepository::new(repository, metrics.clone());
+
+    // Daily games in other languages are generated alongside English
+    let languages = LanguageEngines::from_env(&game_engine);
+    memory_profiler.log_memory("after_language_engines_init");
+
+    // Setup background game generation
+    let background_tasks = BackgroundTasks::default();
+    let scheduler_config = SchedulerConfig::from_env();
+    let generation_scheduler = if scheduler_config.enabled {
+        info!("Starting game generation scheduler");
+        let game_generator = GameGenerator::new(repository.clone(), game_engine.clone())
+            .with_themes(ThemeSchedule::from_env())
+            .with_languages(languages.clone())
+            .with_calibration(CalibrationConfig::from_env())
+            .with_metrics(metrics.clone());
+        let mut scheduler: GenerationScheduler<_, SocialPoster> =
+            GenerationScheduler::new(game_generator, scheduler_config)
+                .with_tasks(background_tasks.clone());
+        if let Some(poster) = SocialPoster::from_env().await {
+            scheduler = scheduler.with_announcer(DailyAnnouncer::new(
+                repository.clone(),
+                poster,
+                AnnouncerConfig::from_env(),
+            ));
+        }
+        let notifier_config = NotifierConfig::from_env();
+        scheduler = scheduler.with_notifier(WebhookNotifier::new(
+            repository.clone(),
+            HttpDeliverer::new(notifier_config.timeout),
+            notifier_config,
+        ));
+        Some(scheduler.start().await?)
+    } else {
+        None
+    };
+
     // Setup HTTP API
     info!("Creating API state");
-    let api_state =
-        pathfinder::http_api::ApiState::new(sqlite_repository.clone(), game_engine.clone());
+    let mut api_state = pathfinder::http_api::ApiState::new(repository, game_engine)
+        .with_hint_config(HintConfig::from_env())
+        .with_paths_cache(PathsCache::from_env())
+        .with_preview_cache(PreviewCache::from_env())
+        .with_accounts(Accounts::from_env())
+        .with_speed_config(SpeedConfig::from_env())
+        .with_blitz_config(BlitzConfig::from_env())
+        .with_discord_config(DiscordConfig::from_env())
+        .with_integrity_config(IntegrityConfig::from_env())
+        .with_progress_config(ProgressConfig::from_env())
+        .with_custom_game_config(CustomGameConfig::from_env())
+        .with_themes(ThemeSchedule::from_env())
+        .with_languages(languages)
+        .with_start_tokens(StartTokens::from_env())
+        .with_user_cookies(UserCookies::from_env().with_max_age(security_config.cookie_max_age))
+        .with_metrics(metrics.clone())
+        .with_memory_history(memory_profiler.history())
+        .with_definitions(
+            DefinitionLookup::new(Definitions::from_env()).with_fallback(DefinitionApi::from_env()),
+        );
+    // Published game batches are announced through the admin API
+    if let Some(poster) = SocialPoster::from_env().await {
+        api_state = api_state.with_poster(poster);
+    }
+    // Entry writes' side effects are delivered from the outbox in the background
+    api_state = api_state.with_outbox_config(OutboxConfig::from_env());
+    if let Some(webhook) = EventWebhook::from_env() {
+        api_state = api_state.with_outbox_handler(Arc::new(webhook));
+    }
+    let outbox_worker = api_state.outbox.clone().start();
+    let _tile_fairness_job = api_state
+        .tile_fairness
+        .clone()
+        .start(api_state.repository.clone(), FairnessConfig::from_env());
+    api_state
+        .restore_revoked_sessions(security_config.cookie_max_age)
+        .await?;
     memory_profiler.log_memory("after_api_state");
 
+    // Kept for shutdown, once the router owns the state
+    let readiness = api_state.readiness.clone();
+    let outbox = api_state.outbox.clone();
+    let repository = api_state.repository.clone();
+
+    // The gRPC API shares the HTTP API's state and stops on the same signal
+    if let Some(grpc_addr) = grpc_addr {
+        info!("Starting gRPC API server on {}", grpc_addr);
+        let listener = tokio::net::TcpListener::bind(&grpc_addr).await?;
+        let grpc_server = pathfinder::grpc_api::serve(
+            listener,
+            api_state.clone(),
+            shutdown_signal(readiness.clone()),
+        );
+        tokio::spawn(async move {
+            if let Err(e) = grpc_server.await {
+                warn!("gRPC server error: {}", e);
+            }
+        });
+    }
+
     info!("Creating secure router");
-    let http_router = pathfinder::http_api::create_secure_router(api_state, security_config);
+    let http_router =
+        pathfinder::http_api::create_secure_router(api_state, security_config.clone());
     memory_profiler.log_memory("after_secure_router_creation");
 
-    let http_addr = format!("{server_host}:{http_port}");
-
     info!("Starting HTTP API server on {}", http_addr);
     memory_profiler.log_memory("after_http_setup");
 
-    // Start HTTP server
-    let http_server = axum::serve(
-        tokio::net::TcpListener::bind(&http_addr).await?,
+    // Start HTTP server. On SIGINT or SIGTERM it stops accepting connections
+    // and returns once in-flight requests finish.
+    let listener = tokio::net::TcpListener::bind(&http_addr).await?;
+    let http_server = pathfinder::server::serve(
+        listener,
         http_router,
+        &security_config,
+        shutdown_signal(readiness),
     );
 
     memory_profiler.log_memory("after_full_startup");
 
-    // Start 10-second memory monitoring
-    let monitoring_duration = Duration::from_secs(3);
-    let monitoring_interval = Duration::from_secs(1);
-
-    info!("Starting 10-second memory monitoring");
-    tokio::spawn(async move {
-        memory_profiler
-            .monitor_for_duration(monitoring_duration, monitoring_interval)
-            .await;
-        info!("Memory monitoring completed");
-    });
+    // Keep sampling memory for /api/admin/memory and the gauges on /metrics
+    tokio::spawn(memory_profiler.run(metrics));
 
     // Run HTTP server
     http_server
         .await
         .map_err(|e| anyhow::anyhow!("HTTP server error: {}", e))?;
 
+    // Let a generation run that has started store and announce its games,
+    // and deliver the side effects of the last entries written, before the
+    // database connections are closed
+    info!("HTTP server stopped, finishing background work");
+    let shutdown_config = ShutdownConfig::from_env();
+    outbox_worker.abort();
+    let drain = async {
+        if let Some(mut scheduler) = generation_scheduler {
+            if let Err(e) = scheduler.shutdown().await {
+                warn!("Failed to stop the generation scheduler: {}", e);
+            }
+        }
+        background_tasks.wait_idle().await;
+        match outbox.process_pending().await {
+            Ok(processed) => info!("Processed {} outbox events", processed),
+            Err(e) => warn!("Failed to process outbox events: {:#}", e),
+        }
+    };
+    if tokio::time::timeout(shutdown_config.drain_timeout, drain)
+        .await
+        .is_err()
+    {
+        warn!(
+            "Background work didn't finish within {:?}, stopping anyway",
+            shutdown_config.drain_timeout
+        );
+    }
+
+    repository.close().await;
+    info!("Shutdown complete");
     Ok(())
 }
@@ -1,11 +1,112 @@
-use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::{
+    collections::VecDeque,
+    env,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 use sysinfo::{Pid, System};
 use tracing::{info, warn};
 
+use crate::metrics::Metrics;
+
+#[derive(Clone, Debug)]
+pub struct MemoryConfig {
+    /// How often memory is sampled once the server is up
+    pub interval: Duration,
+    /// How many samples are kept; older ones are dropped
+    pub capacity: usize,
+}
+
+impl Default for MemoryConfig {
+    fn default() -> Self {
+        // A day of samples at the default interval
+        Self {
+            interval: Duration::from_secs(60),
+            capacity: 24 * 60,
+        }
+    }
+}
+
+impl MemoryConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(secs) = env::var("MEMORY_SAMPLE_INTERVAL_SECS") {
+            if let Ok(value) = secs.parse::<u64>() {
+                config.interval = Duration::from_secs(value.max(1));
+            }
+        }
+
+        if let Ok(capacity) = env::var("MEMORY_SAMPLE_CAPACITY") {
+            if let Ok(value) = capacity.parse::<usize>() {
+                config.capacity = value.max(1);
+            }
+        }
+
+        config
+    }
+}
+
+/// One reading of the process's memory
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct MemorySample {
+    pub taken_at: DateTime<Utc>,
+    pub resident_bytes: u64,
+    pub virtual_bytes: u64,
+    /// The startup step this was logged at, for samples taken during startup
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<String>,
+}
+
+/// MemoryHistory keeps the most recent samples in a ring buffer so memory
+/// growth can be looked at after the fact without an external scraper
+#[derive(Clone)]
+pub struct MemoryHistory {
+    samples: Arc<Mutex<VecDeque<MemorySample>>>,
+    config: MemoryConfig,
+}
+
+impl Default for MemoryHistory {
+    fn default() -> Self {
+        Self::new(MemoryConfig::default())
+    }
+}
+
+impl MemoryHistory {
+    pub fn new(config: MemoryConfig) -> Self {
+        Self {
+            samples: Arc::new(Mutex::new(VecDeque::with_capacity(config.capacity))),
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &MemoryConfig {
+        &self.config
+    }
+
+    pub fn record(&self, sample: MemorySample) {
+        let mut samples = self.samples.lock().unwrap();
+        if samples.len() == self.config.capacity {
+            samples.pop_front();
+        }
+        samples.push_back(sample);
+    }
+
+    /// recent returns up to `limit` of the latest samples, oldest first
+    pub fn recent(&self, limit: usize) -> Vec<MemorySample> {
+        let samples = self.samples.lock().unwrap();
+        let skip = samples.len().saturating_sub(limit);
+        samples.iter().skip(skip).cloned().collect()
+    }
+}
+
 pub struct MemoryProfiler {
     system: System,
     pid: Pid,
     start_time: Instant,
+    history: MemoryHistory,
 }
 
 impl Default for MemoryProfiler {
@@ -24,15 +125,41 @@ impl MemoryProfiler {
             system,
             pid,
             start_time: Instant::now(),
+            history: MemoryHistory::default(),
         }
     }
 
-    pub fn log_memory(&mut self, checkpoint: &str) {
+    /// Records samples into `history` rather than a history of its own
+    pub fn with_history(mut self, history: MemoryHistory) -> Self {
+        self.history = history;
+        self
+    }
+
+    pub fn history(&self) -> MemoryHistory {
+        self.history.clone()
+    }
+
+    /// sample returns the process's resident and virtual memory in bytes
+    pub fn sample(&mut self) -> Option<(u64, u64)> {
         self.system.refresh_process(self.pid);
+        self.system
+            .process(self.pid)
+            .map(|process| (process.memory(), process.virtual_memory()))
+    }
+
+    fn record(&mut self, checkpoint: Option<&str>) -> Option<(u64, u64)> {
+        let (resident_bytes, virtual_bytes) = self.sample()?;
+        self.history.record(MemorySample {
+            taken_at: Utc::now(),
+            resident_bytes,
+            virtual_bytes,
+            checkpoint: checkpoint.map(str::to_string),
+        });
+        Some((resident_bytes, virtual_bytes))
+    }
 
-        if let Some(process) = self.system.process(self.pid) {
-            let memory_bytes = process.memory();
-            let virtual_memory_bytes = process.virtual_memory();
+    pub fn log_memory(&mut self, checkpoint: &str) {
+        if let Some((memory_bytes, virtual_memory_bytes)) = self.record(Some(checkpoint)) {
             let elapsed = self.start_time.elapsed();
 
             info!(
@@ -50,18 +177,62 @@ impl MemoryProfiler {
         }
     }
 
-    pub async fn monitor_for_duration(&mut self, duration: Duration, interval: Duration) {
-        let start = Instant::now();
-        let mut next_log = start;
-
-        while start.elapsed() < duration {
-            if Instant::now() >= next_log {
-                let elapsed = start.elapsed();
-                self.log_memory(&format!("monitor_{}s", elapsed.as_secs()));
-                next_log = Instant::now() + interval;
+    /// run samples memory at the configured interval for as long as the
+    /// process runs, keeping the samples and setting the memory gauges in
+    /// `metrics`
+    pub async fn run(mut self, metrics: Metrics) {
+        let mut ticker = tokio::time::interval(self.history.config.interval);
+        loop {
+            ticker.tick().await;
+            if let Some((memory_bytes, virtual_memory_bytes)) = self.record(None) {
+                metrics.set_memory(memory_bytes, virtual_memory_bytes);
             }
+        }
+    }
+}
 
-            tokio::time::sleep(Duration::from_millis(100)).await;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(resident_bytes: u64) -> MemorySample {
+        MemorySample {
+            taken_at: Utc::now(),
+            resident_bytes,
+            virtual_bytes: resident_bytes * 2,
+            checkpoint: None,
         }
     }
+
+    #[test]
+    fn test_history_keeps_the_latest_samples() {
+        let history = MemoryHistory::new(MemoryConfig {
+            capacity: 3,
+            ..MemoryConfig::default()
+        });
+        for resident_bytes in 1..=5 {
+            history.record(sample(resident_bytes));
+        }
+
+        let kept: Vec<u64> = history
+            .recent(10)
+            .iter()
+            .map(|s| s.resident_bytes)
+            .collect();
+        assert_eq!(kept, vec![3, 4, 5]);
+
+        let latest: Vec<u64> = history.recent(2).iter().map(|s| s.resident_bytes).collect();
+        assert_eq!(latest, vec![4, 5]);
+    }
+
+    #[test]
+    fn test_checkpoints_are_recorded() {
+        let mut profiler = MemoryProfiler::new();
+        profiler.log_memory("startup");
+
+        let samples = profiler.history().recent(1);
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].checkpoint.as_deref(), Some("startup"));
+        assert!(samples[0].resident_bytes > 0);
+    }
 }
@@ -0,0 +1,459 @@
+use axum::{
+    body::Body,
+    extract::MatchedPath,
+    http::{Request, StatusCode},
+    response::{IntoResponse, Response},
+};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt::Write;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::{Layer, Service};
+
+/// Upper bounds, in seconds, of the buckets every duration histogram uses.
+/// They run from a cached read to a slow board generation.
+const DURATION_BUCKETS: [f64; 14] = [
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// Route label for requests no route matched, which the static frontend serves
+const STATIC_ROUTE: &str = "static";
+
+/// Metrics collects request, database, cache, generation and memory
+/// measurements and renders them in the Prometheus text format. Clones share
+/// the same measurements.
+#[derive(Clone, Default)]
+pub struct Metrics {
+    inner: Arc<Registry>,
+}
+
+#[derive(Default)]
+struct Registry {
+    /// Responses by method, route and status
+    requests: Mutex<BTreeMap<(String, String, u16), u64>>,
+    /// Request durations by method and route
+    request_durations: Mutex<BTreeMap<(String, String), Histogram>>,
+    /// Repository call durations by operation
+    queries: Mutex<BTreeMap<&'static str, Histogram>>,
+    /// Board generation durations by kind of game
+    generations: Mutex<BTreeMap<&'static str, Histogram>>,
+    /// Lookups and sizes by cache
+    caches: Mutex<BTreeMap<&'static str, CacheStats>>,
+    resident_memory_bytes: AtomicU64,
+    virtual_memory_bytes: AtomicU64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct CacheStats {
+    hits: u64,
+    misses: u64,
+    /// Entries dropped on purpose rather than evicted
+    invalidations: u64,
+    entries: u64,
+    /// Weighted size, for caches that weigh their entries in bytes
+    bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Observations per bucket, not cumulative; the last is +Inf
+    buckets: [u64; DURATION_BUCKETS.len() + 1],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        let bucket = DURATION_BUCKETS
+            .iter()
+            .position(|bound| seconds <= *bound)
+            .unwrap_or(DURATION_BUCKETS.len());
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.sum += seconds;
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let separator = if labels.is_empty() { "" } else { "," };
+        let mut cumulative = 0;
+        for (bound, count) in DURATION_BUCKETS.iter().zip(self.buckets) {
+            cumulative += count;
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{{labels}{separator}le=\"{bound}\"}} {cumulative}"
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{name}_bucket{{{labels}{separator}le=\"+Inf\"}} {}",
+            self.count
+        );
+        let _ = writeln!(out, "{name}_sum{{{labels}}} {}", self.sum);
+        let _ = writeln!(out, "{name}_count{{{labels}}} {}", self.count);
+    }
+}
+
+impl Metrics {
+    pub fn record_request(&self, method: &str, route: &str, status: u16, elapsed: Duration) {
+        *lock(&self.inner.requests)
+            .entry((method.to_string(), route.to_string(), status))
+            .or_default() += 1;
+        lock(&self.inner.request_durations)
+            .entry((method.to_string(), route.to_string()))
+            .or_default()
+            .observe(elapsed);
+    }
+
+    /// record_query records how long a repository operation took
+    pub fn record_query(&self, operation: &'static str, elapsed: Duration) {
+        lock(&self.inner.queries)
+            .entry(operation)
+            .or_default()
+            .observe(elapsed);
+    }
+
+    /// record_generation records how long generating a board took, by kind
+    /// of game such as `daily` or `practice`
+    pub fn record_generation(&self, kind: &'static str, elapsed: Duration) {
+        lock(&self.inner.generations)
+            .entry(kind)
+            .or_default()
+            .observe(elapsed);
+    }
+
+    /// record_cache counts a lookup in the named cache
+    pub fn record_cache(&self, cache: &'static str, hit: bool) {
+        let mut caches = lock(&self.inner.caches);
+        let stats = caches.entry(cache).or_default();
+        if hit {
+            stats.hits += 1;
+        } else {
+            stats.misses += 1;
+        }
+    }
+
+    /// record_cache_invalidations counts entries deliberately dropped from
+    /// the named cache, e.g. after a game changed under it
+    pub fn record_cache_invalidations(&self, cache: &'static str, count: u64) {
+        lock(&self.inner.caches)
+            .entry(cache)
+            .or_default()
+            .invalidations += count;
+    }
+
+    /// set_cache_size reports how many entries the named cache holds and,
+    /// for caches weighed in bytes, their total size
+    pub fn set_cache_size(&self, cache: &'static str, entries: u64, bytes: Option<u64>) {
+        let mut caches = lock(&self.inner.caches);
+        let stats = caches.entry(cache).or_default();
+        stats.entries = entries;
+        stats.bytes = bytes;
+    }
+
+    pub fn set_memory(&self, resident_bytes: u64, virtual_bytes: u64) {
+        self.inner
+            .resident_memory_bytes
+            .store(resident_bytes, Ordering::Relaxed);
+        self.inner
+            .virtual_memory_bytes
+            .store(virtual_bytes, Ordering::Relaxed);
+    }
+
+    /// render returns every metric in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "pathfinder_http_requests_total",
+            "counter",
+            "HTTP responses by method, route and status",
+        );
+        for ((method, route, status), count) in lock(&self.inner.requests).iter() {
+            let _ = writeln!(
+                out,
+                "pathfinder_http_requests_total{{method=\"{}\",route=\"{}\",status=\"{status}\"}} {count}",
+                escape(method),
+                escape(route)
+            );
+        }
+
+        header(
+            &mut out,
+            "pathfinder_http_request_duration_seconds",
+            "histogram",
+            "Time to respond to HTTP requests",
+        );
+        for ((method, route), histogram) in lock(&self.inner.request_durations).iter() {
+            histogram.render(
+                &mut out,
+                "pathfinder_http_request_duration_seconds",
+                &format!("method=\"{}\",route=\"{}\"", escape(method), escape(route)),
+            );
+        }
+
+        header(
+            &mut out,
+            "pathfinder_db_query_duration_seconds",
+            "histogram",
+            "Time taken by repository operations",
+        );
+        for (operation, histogram) in lock(&self.inner.queries).iter() {
+            histogram.render(
+                &mut out,
+                "pathfinder_db_query_duration_seconds",
+                &format!("operation=\"{operation}\""),
+            );
+        }
+
+        header(
+            &mut out,
+            "pathfinder_game_generation_duration_seconds",
+            "histogram",
+            "Time taken to generate a board",
+        );
+        for (kind, histogram) in lock(&self.inner.generations).iter() {
+            histogram.render(
+                &mut out,
+                "pathfinder_game_generation_duration_seconds",
+                &format!("kind=\"{kind}\""),
+            );
+        }
+
+        let caches = lock(&self.inner.caches);
+        header(
+            &mut out,
+            "pathfinder_cache_requests_total",
+            "counter",
+            "Cache lookups by cache and result",
+        );
+        for (cache, stats) in caches.iter() {
+            for (result, count) in [("hit", stats.hits), ("miss", stats.misses)] {
+                let _ = writeln!(
+                    out,
+                    "pathfinder_cache_requests_total{{cache=\"{cache}\",result=\"{result}\"}} {count}"
+                );
+            }
+        }
+        header(
+            &mut out,
+            "pathfinder_cache_invalidations_total",
+            "counter",
+            "Entries invalidated by cache",
+        );
+        for (cache, stats) in caches.iter() {
+            let _ = writeln!(
+                out,
+                "pathfinder_cache_invalidations_total{{cache=\"{cache}\"}} {}",
+                stats.invalidations
+            );
+        }
+        header(
+            &mut out,
+            "pathfinder_cache_entries",
+            "gauge",
+            "Entries held by each cache",
+        );
+        for (cache, stats) in caches.iter() {
+            let _ = writeln!(
+                out,
+                "pathfinder_cache_entries{{cache=\"{cache}\"}} {}",
+                stats.entries
+            );
+        }
+        header(
+            &mut out,
+            "pathfinder_cache_size_bytes",
+            "gauge",
+            "Weighted size of the caches that are bounded in bytes",
+        );
+        for (cache, stats) in caches.iter() {
+            if let Some(bytes) = stats.bytes {
+                let _ = writeln!(
+                    out,
+                    "pathfinder_cache_size_bytes{{cache=\"{cache}\"}} {bytes}"
+                );
+            }
+        }
+        drop(caches);
+
+        for (name, help, gauge) in [
+            (
+                "process_resident_memory_bytes",
+                "Resident memory size in bytes",
+                &self.inner.resident_memory_bytes,
+            ),
+            (
+                "process_virtual_memory_bytes",
+                "Virtual memory size in bytes",
+                &self.inner.virtual_memory_bytes,
+            ),
+        ] {
+            header(&mut out, name, "gauge", help);
+            let _ = writeln!(out, "{name} {}", gauge.load(Ordering::Relaxed));
+        }
+
+        out
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    // A panic while recording leaves the measurements usable
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn escape(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Serves the metrics to a Prometheus scraper
+pub fn metrics_response(metrics: &Metrics) -> Response {
+    (
+        StatusCode::OK,
+        [("content-type", "text/plain; version=0.0.4")],
+        metrics.render(),
+    )
+        .into_response()
+}
+
+/// MetricsLayer counts responses and times requests by method and route.
+/// Routes are labelled by their pattern, e.g. `/api/game/:game_id/words`, so
+/// the number of series doesn't grow with the ids requested.
+#[derive(Clone)]
+pub struct MetricsLayer {
+    metrics: Metrics,
+}
+
+impl MetricsLayer {
+    pub fn new(metrics: Metrics) -> Self {
+        Self { metrics }
+    }
+}
+
+impl<S> Layer<S> for MetricsLayer {
+    type Service = MetricsMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MetricsMiddleware {
+            inner,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MetricsMiddleware<S> {
+    inner: S,
+    metrics: Metrics,
+}
+
+impl<S> Service<Request<Body>> for MetricsMiddleware<S>
+where
+    S: Service<Request<Body>, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let metrics = self.metrics.clone();
+        let mut inner = self.inner.clone();
+        let method = request.method().to_string();
+        let route = request
+            .extensions()
+            .get::<MatchedPath>()
+            .map(|path| path.as_str().to_string())
+            .unwrap_or_else(|| STATIC_ROUTE.to_string());
+
+        Box::pin(async move {
+            let started = Instant::now();
+            let response = inner.call(request).await?;
+            metrics.record_request(
+                &method,
+                &route,
+                response.status().as_u16(),
+                started.elapsed(),
+            );
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histograms_are_cumulative() {
+        let metrics = Metrics::default();
+        metrics.record_query("get_game_by_date", Duration::from_millis(3));
+        metrics.record_query("get_game_by_date", Duration::from_millis(40));
+        metrics.record_query("get_game_by_date", Duration::from_secs(60));
+
+        let rendered = metrics.render();
+        let series = |le: &str| {
+            format!(
+                "pathfinder_db_query_duration_seconds_bucket{{operation=\"get_game_by_date\",le=\"{le}\"}}"
+            )
+        };
+        assert!(rendered.contains(&format!("{} 0\n", series("0.001"))));
+        assert!(rendered.contains(&format!("{} 1\n", series("0.005"))));
+        assert!(rendered.contains(&format!("{} 2\n", series("0.05"))));
+        assert!(rendered.contains(&format!("{} 2\n", series("30"))));
+        assert!(rendered.contains(&format!("{} 3\n", series("+Inf"))));
+        assert!(rendered.contains(
+            "pathfinder_db_query_duration_seconds_count{operation=\"get_game_by_date\"} 3\n"
+        ));
+    }
+
+    #[test]
+    fn test_counters_and_gauges() {
+        let metrics = Metrics::default();
+        metrics.record_request("GET", "/api/game/today", 200, Duration::from_millis(2));
+        metrics.record_request("GET", "/api/game/today", 200, Duration::from_millis(2));
+        metrics.record_request("GET", "/api/game/today", 404, Duration::from_millis(2));
+        metrics.record_cache("game", true);
+        metrics.record_cache("game", false);
+        metrics.record_cache("game", true);
+        metrics.set_cache_size("paths", 3, Some(2048));
+        metrics.record_cache_invalidations("game", 2);
+        metrics.set_memory(1024, 4096);
+
+        let rendered = metrics.render();
+        assert!(rendered.contains(
+            "pathfinder_http_requests_total{method=\"GET\",route=\"/api/game/today\",status=\"200\"} 2\n"
+        ));
+        assert!(rendered.contains(
+            "pathfinder_http_requests_total{method=\"GET\",route=\"/api/game/today\",status=\"404\"} 1\n"
+        ));
+        assert!(
+            rendered.contains("pathfinder_cache_requests_total{cache=\"game\",result=\"hit\"} 2\n")
+        );
+        assert!(rendered
+            .contains("pathfinder_cache_requests_total{cache=\"game\",result=\"miss\"} 1\n"));
+        assert!(rendered.contains("pathfinder_cache_size_bytes{cache=\"paths\"} 2048\n"));
+        assert!(rendered.contains("pathfinder_cache_invalidations_total{cache=\"game\"} 2\n"));
+        assert!(!rendered.contains("pathfinder_cache_size_bytes{cache=\"game\"}"));
+        assert!(rendered.contains("process_resident_memory_bytes 1024\n"));
+        assert!(rendered.contains("# TYPE process_virtual_memory_bytes gauge\n"));
+    }
+}
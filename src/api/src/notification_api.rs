@@ -0,0 +1,265 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{Extension, Json},
+    routing::{get, post},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::account_api::AccountQuery;
+use crate::api_error::ApiError;
+use crate::db::{
+    models::{DbNotification, DbNotificationPreferences},
+    Repository,
+};
+use crate::http_api::{authenticate_user, ApiState};
+use crate::security::{session::SessionInfo, user_cookie::UserIdentity};
+
+/// Most notifications listed at once
+const NOTIFICATION_LIMIT: i32 = 50;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiNotification {
+    pub id: String,
+    pub kind: String,
+    pub game_id: Option<String>,
+    pub message: String,
+    pub created_at: String,
+    pub read: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiNotifications {
+    /// Newest first
+    pub notifications: Vec<ApiNotification>,
+    pub unread: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ApiNotificationPreferences {
+    /// Hear where you finished once a game's stats are frozen
+    pub final_rank: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdatePreferencesRequest {
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+    pub final_rank: bool,
+}
+
+/// Routes under /api/notifications: a user's inbox and the notifications
+/// they've opted in to
+pub fn notification_router<R: Repository + Clone + Send + Sync + 'static>() -> Router<ApiState<R>> {
+    Router::new()
+        .route("/api/notifications", get(get_notifications))
+        .route("/api/notifications/read", post(mark_read))
+        .route(
+            "/api/notifications/preferences",
+            get(get_preferences).put(update_preferences),
+        )
+}
+
+impl From<DbNotification> for ApiNotification {
+    fn from(notification: DbNotification) -> Self {
+        ApiNotification {
+            id: notification.id,
+            kind: notification.kind,
+            game_id: notification.game_id,
+            message: notification.message,
+            created_at: notification.created_at.to_rfc3339(),
+            read: notification.read_at.is_some(),
+        }
+    }
+}
+
+impl From<DbNotificationPreferences> for ApiNotificationPreferences {
+    fn from(preferences: DbNotificationPreferences) -> Self {
+        ApiNotificationPreferences {
+            final_rank: preferences.final_rank,
+        }
+    }
+}
+
+fn internal_error(e: anyhow::Error) -> ApiError {
+    ApiError::Internal(e.context("Notification operation failed"))
+}
+
+async fn get_notifications<R: Repository + Clone + Send + Sync + 'static>(
+    Query(query): Query<AccountQuery>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+) -> Result<Json<ApiNotifications>, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        query.user_id.as_ref(),
+        query.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+
+    let notifications: Vec<ApiNotification> = state
+        .repository
+        .get_notifications(&user.id, NOTIFICATION_LIMIT)
+        .await
+        .map_err(internal_error)?
+        .into_iter()
+        .map(ApiNotification::from)
+        .collect();
+    let unread = notifications.iter().filter(|n| !n.read).count();
+
+    Ok(Json(ApiNotifications {
+        notifications,
+        unread,
+    }))
+}
+
+async fn mark_read<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+    Json(request): Json<AccountQuery>,
+) -> Result<StatusCode, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        request.user_id.as_ref(),
+        request.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+
+    state
+        .repository
+        .mark_notifications_read(&user.id)
+        .await
+        .map_err(internal_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn get_preferences<R: Repository + Clone + Send + Sync + 'static>(
+    Query(query): Query<AccountQuery>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+) -> Result<Json<ApiNotificationPreferences>, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        query.user_id.as_ref(),
+        query.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+
+    let preferences = state
+        .repository
+        .get_notification_preferences(&user.id)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(preferences.into()))
+}
+
+async fn update_preferences<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+    Json(request): Json<UpdatePreferencesRequest>,
+) -> Result<Json<ApiNotificationPreferences>, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        request.user_id.as_ref(),
+        request.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+
+    let preferences = DbNotificationPreferences {
+        user_id: user.id,
+        final_rank: request.final_rank,
+    };
+    state
+        .repository
+        .set_notification_preferences(preferences.clone())
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(preferences.into()))
+}
+
+#[cfg(all(test, feature = "database-tests"))]
+mod tests {
+    use super::*;
+    use axum::http::Method;
+    use tower::util::ServiceExt;
+
+    use crate::db::models::NewNotification;
+    use crate::test_utils::{create_test_request, setup_app};
+
+    async fn send(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<&str>,
+    ) -> (StatusCode, serde_json::Value) {
+        let request = create_test_request(method, uri, body);
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_preferences_and_inbox(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let (state, app) = setup_app(pool).await;
+        let (_, user) = send(&app, Method::POST, "/api/user", None).await;
+        let user_id = user["user_id"].as_str().unwrap();
+        let cookie_token = user["cookie_token"].as_str().unwrap();
+        let credentials = format!("user_id={user_id}&cookie_token={cookie_token}");
+
+        let uri = format!("/api/notifications/preferences?{credentials}");
+        let (status, preferences) = send(&app, Method::GET, &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(preferences["final_rank"], false);
+
+        let body = serde_json::json!({"user_id": user_id, "cookie_token": cookie_token, "final_rank": true}).to_string();
+        let (status, _) = send(
+            &app,
+            Method::PUT,
+            "/api/notifications/preferences",
+            Some(&body),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, preferences) = send(&app, Method::GET, &uri, None).await;
+        assert_eq!(preferences["final_rank"], true);
+
+        state
+            .repository
+            .create_notification(NewNotification {
+                user_id: user_id.to_string(),
+                kind: "final_rank".to_string(),
+                game_id: None,
+                message: "You finished #1 of 1".to_string(),
+            })
+            .await
+            .unwrap();
+        let uri = format!("/api/notifications?{credentials}");
+        let (status, inbox) = send(&app, Method::GET, &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(inbox["unread"], 1);
+        assert_eq!(inbox["notifications"][0]["message"], "You finished #1 of 1");
+
+        let body =
+            serde_json::json!({"user_id": user_id, "cookie_token": cookie_token}).to_string();
+        let (status, _) = send(&app, Method::POST, "/api/notifications/read", Some(&body)).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, inbox) = send(&app, Method::GET, &uri, None).await;
+        assert_eq!(inbox["unread"], 0);
+    }
+}
@@ -0,0 +1,164 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::account::mailer::Mailer;
+use crate::db::{
+    models::{DbNotification, NewNotification},
+    Repository,
+};
+
+/// The kinds of notification a user can opt in to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// A player's final rank on a game once its stats are frozen
+    FinalRank,
+}
+
+impl NotificationKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NotificationKind::FinalRank => "final_rank",
+        }
+    }
+}
+
+/// Notifier delivers notifications. Every notification goes to the user's
+/// inbox, and is also emailed if the user has confirmed an address for their
+/// account and a mailer is set.
+#[derive(Clone)]
+pub struct Notifier<R: Repository> {
+    repository: R,
+    mailer: Option<Arc<dyn Mailer>>,
+}
+
+impl<R: Repository + Send + Sync> Notifier<R> {
+    pub fn new(repository: R) -> Self {
+        Self {
+            repository,
+            mailer: None,
+        }
+    }
+
+    /// Also email notifications to users with an account email
+    pub fn with_mailer(mut self, mailer: Arc<dyn Mailer>) -> Self {
+        self.mailer = Some(mailer);
+        self
+    }
+
+    /// Deliver a notification. Only storing it can fail; an email that can't
+    /// be sent is logged, since the notification is already in the inbox.
+    pub async fn notify(
+        &self,
+        user_id: &str,
+        kind: NotificationKind,
+        game_id: Option<&str>,
+        subject: &str,
+        message: String,
+    ) -> Result<DbNotification> {
+        let notification = self
+            .repository
+            .create_notification(NewNotification {
+                user_id: user_id.to_string(),
+                kind: kind.as_str().to_string(),
+                game_id: game_id.map(str::to_string),
+                message,
+            })
+            .await?;
+
+        if let Some(mailer) = &self.mailer {
+            if let Err(e) = self.email(mailer.as_ref(), &notification, subject).await {
+                warn!("Failed to email notification {}: {:#}", notification.id, e);
+            }
+        }
+
+        Ok(notification)
+    }
+
+    async fn email(
+        &self,
+        mailer: &dyn Mailer,
+        notification: &DbNotification,
+        subject: &str,
+    ) -> Result<()> {
+        let email = self
+            .repository
+            .get_account_by_user(&notification.user_id)
+            .await?
+            .and_then(|account| account.email);
+        match email {
+            Some(email) => mailer.send(&email, subject, &notification.message).await,
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "database-tests"))]
+mod tests {
+    use super::*;
+    use axum::async_trait;
+    use std::sync::Mutex;
+
+    use crate::db::{models::NewUser, SqliteRepository};
+
+    #[derive(Default)]
+    struct CapturingMailer {
+        sent: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Mailer for CapturingMailer {
+        async fn send(&self, to: &str, _subject: &str, _body: &str) -> Result<()> {
+            self.sent.lock().unwrap().push(to.to_string());
+            Ok(())
+        }
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_notify_emails_only_users_with_an_address(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let repository = SqliteRepository::new(pool);
+        let mailer = Arc::new(CapturingMailer::default());
+        let notifier = Notifier::new(repository.clone()).with_mailer(mailer.clone());
+
+        let mut user_ids = Vec::new();
+        for cookie_token in ["with-email", "anonymous"] {
+            let user = repository
+                .create_user(NewUser {
+                    cookie_token: cookie_token.to_string(),
+                })
+                .await
+                .unwrap();
+            user_ids.push(user.id);
+        }
+        let account = repository
+            .get_or_create_account(&user_ids[0])
+            .await
+            .unwrap();
+        repository
+            .set_account_email(&account.id, "player@example.com")
+            .await
+            .unwrap();
+
+        for user_id in &user_ids {
+            notifier
+                .notify(
+                    user_id,
+                    NotificationKind::FinalRank,
+                    None,
+                    "Final results",
+                    "You placed 1st".to_string(),
+                )
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(*mailer.sent.lock().unwrap(), vec!["player@example.com"]);
+        for user_id in &user_ids {
+            let inbox = repository.get_notifications(user_id, 10).await.unwrap();
+            assert_eq!(inbox.len(), 1);
+            assert_eq!(inbox[0].kind, "final_rank");
+        }
+    }
+}
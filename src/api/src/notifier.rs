@@ -0,0 +1,501 @@
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use chrono::Utc;
+use futures_util::future::join_all;
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use std::{env, fmt, future::Future, str::FromStr, time::Duration};
+use tracing::{info, warn};
+
+use crate::db::{
+    models::{DbGame, DbWebhookSubscription},
+    Repository,
+};
+use crate::scheduler::backoff_delay;
+use crate::social::content::{PuzzleContent, DEFAULT_ANNOUNCEMENT_TEMPLATE};
+
+/// Header carrying the delivery's signature, `sha256=` and the base64url
+/// HMAC-SHA256 of `{timestamp}.{body}` keyed with the subscription's secret
+pub const SIGNATURE_HEADER: &str = "X-Pathfinder-Signature";
+/// Header carrying the unix time the delivery was signed at, so receivers can
+/// turn away replays of old deliveries
+pub const TIMESTAMP_HEADER: &str = "X-Pathfinder-Timestamp";
+
+#[derive(Clone, Debug)]
+pub struct NotifierConfig {
+    /// Site deliveries link to; the puzzle path is appended
+    pub base_url: String,
+    /// How long to wait for a subscriber to answer one delivery
+    pub timeout: Duration,
+    /// How many times a failed delivery is retried before giving up
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled for every subsequent retry
+    pub retry_backoff: Duration,
+}
+
+impl Default for NotifierConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "https://pathfinder.prof".to_string(),
+            timeout: Duration::from_secs(10),
+            max_retries: 3,
+            retry_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl NotifierConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        // Deliveries link to the same site as the social announcements
+        if let Ok(base_url) = env::var("ANNOUNCEMENT_BASE_URL") {
+            config.base_url = base_url.trim_end_matches('/').to_string();
+        }
+
+        if let Ok(timeout) = env::var("WEBHOOK_TIMEOUT_SECONDS") {
+            if let Ok(seconds) = timeout.parse::<u64>() {
+                config.timeout = Duration::from_secs(seconds);
+            }
+        }
+
+        if let Ok(retries) = env::var("WEBHOOK_MAX_RETRIES") {
+            if let Ok(value) = retries.parse::<u32>() {
+                config.max_retries = value;
+            }
+        }
+
+        if let Ok(backoff) = env::var("WEBHOOK_RETRY_BACKOFF") {
+            if let Ok(seconds) = backoff.parse::<u64>() {
+                config.retry_backoff = Duration::from_secs(seconds);
+            }
+        }
+
+        config
+    }
+}
+
+/// WebhookKind decides what a subscription is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    /// A Discord channel webhook, sent the announcement as a message
+    Discord,
+    /// Any other URL, sent the game as JSON
+    Generic,
+}
+
+impl WebhookKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookKind::Discord => "discord",
+            WebhookKind::Generic => "generic",
+        }
+    }
+}
+
+impl fmt::Display for WebhookKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WebhookKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "discord" => Ok(WebhookKind::Discord),
+            "generic" => Ok(WebhookKind::Generic),
+            _ => Err(format!("unknown webhook kind: {s}")),
+        }
+    }
+}
+
+/// The JSON generic subscribers are sent for each new game
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct GamePublishedEvent {
+    /// Always `game.published`
+    pub event: String,
+    pub game_id: String,
+    pub date: String,
+    pub sequence_number: i32,
+    pub threshold_score: i32,
+    pub url: String,
+}
+
+#[derive(Serialize)]
+struct DiscordMessage {
+    content: String,
+}
+
+/// One signed POST to a subscriber
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookRequest {
+    pub url: String,
+    /// JSON body
+    pub body: String,
+    pub timestamp: i64,
+    pub signature: String,
+}
+
+/// Trait for sending webhook requests
+pub trait Deliver {
+    /// Sends the request, failing unless the subscriber accepts it
+    fn deliver(&self, request: &WebhookRequest) -> impl Future<Output = Result<()>> + Send;
+}
+
+/// HttpDeliverer POSTs webhook requests over HTTP
+pub struct HttpDeliverer {
+    client: reqwest::Client,
+}
+
+impl HttpDeliverer {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(timeout)
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Deliver for HttpDeliverer {
+    async fn deliver(&self, request: &WebhookRequest) -> Result<()> {
+        let response = self
+            .client
+            .post(&request.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(TIMESTAMP_HEADER, request.timestamp.to_string())
+            .header(SIGNATURE_HEADER, &request.signature)
+            .body(request.body.clone())
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to reach webhook: {}", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(anyhow!("Webhook answered {}", status));
+        }
+        Ok(())
+    }
+}
+
+/// sign returns the signature of a delivery body sent at `timestamp`
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, format!("{timestamp}.{body}").as_bytes());
+    format!(
+        "sha256={}",
+        general_purpose::URL_SAFE_NO_PAD.encode(tag.as_ref())
+    )
+}
+
+/// WebhookNotifier tells every subscribed service when a day's game goes
+/// live. Like announcements, each delivery is claimed in the database before
+/// it is sent, so a game reaches each subscription at most once, and a
+/// claim is released when every attempt fails so the next run tries again.
+pub struct WebhookNotifier<R: Repository, D: Deliver = HttpDeliverer> {
+    repository: R,
+    deliverer: D,
+    config: NotifierConfig,
+}
+
+impl<R, D> WebhookNotifier<R, D>
+where
+    R: Repository + Send + Sync,
+    D: Deliver + Send + Sync,
+{
+    pub fn new(repository: R, deliverer: D, config: NotifierConfig) -> Self {
+        Self {
+            repository,
+            deliverer,
+            config,
+        }
+    }
+
+    /// Notifies subscribers of the game for a date, if there is one. Returns
+    /// how many deliveries were made.
+    pub async fn notify_date(&self, date: &str) -> Result<usize> {
+        match self.repository.get_game_by_date(date).await? {
+            Some(game) => self.notify(&game).await,
+            None => {
+                info!("No game for {} to send to webhooks", date);
+                Ok(0)
+            }
+        }
+    }
+
+    /// Sends a game to every subscription that hasn't had it, all at once.
+    /// A subscriber that can't be reached doesn't hold up the others; its
+    /// failure is logged. Returns how many deliveries were made.
+    pub async fn notify(&self, game: &DbGame) -> Result<usize> {
+        let subscriptions = self
+            .repository
+            .get_webhook_subscriptions()
+            .await
+            .context("failed to load webhook subscriptions")?;
+
+        let outcomes = join_all(
+            subscriptions
+                .iter()
+                .map(|subscription| self.deliver_to(subscription, game)),
+        )
+        .await;
+
+        let mut delivered = 0;
+        for (subscription, outcome) in subscriptions.iter().zip(outcomes) {
+            match outcome {
+                Ok(true) => delivered += 1,
+                Ok(false) => {}
+                Err(e) => warn!("Webhook {} failed: {:#}", subscription.id, e),
+            }
+        }
+        if delivered > 0 {
+            info!(
+                "Sent game {} to {} webhooks",
+                game.sequence_number, delivered
+            );
+        }
+        Ok(delivered)
+    }
+
+    /// Delivers a game to one subscription unless it already has been.
+    /// Returns whether a delivery was made.
+    async fn deliver_to(
+        &self,
+        subscription: &DbWebhookSubscription,
+        game: &DbGame,
+    ) -> Result<bool> {
+        if !self
+            .repository
+            .claim_webhook_delivery(&subscription.id, &game.id)
+            .await?
+        {
+            return Ok(false);
+        }
+
+        let body = self.body(subscription, game)?;
+        let mut attempt = 0;
+        loop {
+            // Signed afresh per attempt, so retries carry a current timestamp
+            let timestamp = Utc::now().timestamp();
+            let request = WebhookRequest {
+                url: subscription.url.clone(),
+                signature: sign(&subscription.secret, timestamp, &body),
+                body: body.clone(),
+                timestamp,
+            };
+            match self.deliverer.deliver(&request).await {
+                Ok(()) => return Ok(true),
+                Err(e) if attempt < self.config.max_retries => {
+                    let delay = backoff_delay(self.config.retry_backoff, attempt);
+                    warn!(
+                        "Webhook {} failed: {}; retrying in {:?}",
+                        subscription.id, e, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    self.repository
+                        .release_webhook_delivery(&subscription.id, &game.id)
+                        .await
+                        .context("failed to release webhook delivery claim")?;
+                    return Err(e.context(format!(
+                        "failed to send game {} to webhook",
+                        game.sequence_number
+                    )));
+                }
+            }
+        }
+    }
+
+    /// What a subscription is sent for a game
+    fn body(&self, subscription: &DbWebhookSubscription, game: &DbGame) -> Result<String> {
+        let content = PuzzleContent::new(game, &self.config.base_url);
+        let kind = subscription
+            .kind
+            .parse::<WebhookKind>()
+            .map_err(|e| anyhow!(e))?;
+        Ok(match kind {
+            WebhookKind::Discord => serde_json::to_string(&DiscordMessage {
+                content: content.render(DEFAULT_ANNOUNCEMENT_TEMPLATE),
+            })?,
+            WebhookKind::Generic => serde_json::to_string(&GamePublishedEvent {
+                event: "game.published".to_string(),
+                game_id: game.id.clone(),
+                date: game.date.clone(),
+                sequence_number: game.sequence_number,
+                threshold_score: game.threshold_score,
+                url: content.url,
+            })?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        models::{NewGameWithAnswers, NewWebhookSubscription},
+        InMemoryRepository,
+    };
+    use crate::test_utils::create_new_test_game;
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex,
+    };
+
+    /// Fails the first `failures` deliveries to `flaky_url`, then records
+    /// every delivery made
+    #[derive(Default)]
+    struct FlakyDeliverer {
+        flaky_url: String,
+        failures: AtomicU32,
+        requests: Mutex<Vec<WebhookRequest>>,
+    }
+
+    impl Deliver for FlakyDeliverer {
+        async fn deliver(&self, request: &WebhookRequest) -> Result<()> {
+            if request.url == self.flaky_url
+                && self
+                    .failures
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                    .is_ok()
+            {
+                anyhow::bail!("service unavailable");
+            }
+            self.requests.lock().unwrap().push(request.clone());
+            Ok(())
+        }
+    }
+
+    fn config(max_retries: u32) -> NotifierConfig {
+        NotifierConfig {
+            max_retries,
+            retry_backoff: Duration::ZERO,
+            ..Default::default()
+        }
+    }
+
+    async fn subscribe(repository: &InMemoryRepository, kind: WebhookKind, url: &str) {
+        repository
+            .create_webhook_subscription(NewWebhookSubscription {
+                kind: kind.to_string(),
+                url: url.to_string(),
+                secret: format!("secret for {url}"),
+            })
+            .await
+            .unwrap();
+    }
+
+    async fn game(repository: &InMemoryRepository) -> DbGame {
+        repository
+            .create_games_with_answers(vec![NewGameWithAnswers {
+                game: create_new_test_game(),
+                answers: vec![],
+                optimal_solution: None,
+                density: None,
+                quality: None,
+            }])
+            .await
+            .unwrap()
+            .pop()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_signature_covers_timestamp_and_body() {
+        let signature = sign("secret", 1700000000, "{}");
+        let tag = general_purpose::URL_SAFE_NO_PAD
+            .decode(signature.strip_prefix("sha256=").unwrap())
+            .unwrap();
+        let key = hmac::Key::new(hmac::HMAC_SHA256, b"secret");
+        assert!(hmac::verify(&key, b"1700000000.{}", &tag).is_ok());
+
+        assert_ne!(signature, sign("secret", 1700000001, "{}"));
+        assert_ne!(signature, sign("other", 1700000000, "{}"));
+    }
+
+    #[tokio::test]
+    async fn test_notify_retries_and_never_double_sends() {
+        let repository = InMemoryRepository::new();
+        subscribe(
+            &repository,
+            WebhookKind::Generic,
+            "https://example.com/hook",
+        )
+        .await;
+        subscribe(
+            &repository,
+            WebhookKind::Discord,
+            "https://discord.com/api/webhooks/1/abc",
+        )
+        .await;
+        let game = game(&repository).await;
+
+        let deliverer = FlakyDeliverer {
+            flaky_url: "https://example.com/hook".to_string(),
+            failures: AtomicU32::new(2),
+            ..Default::default()
+        };
+        let notifier = WebhookNotifier::new(repository.clone(), deliverer, config(2));
+        assert_eq!(notifier.notify(&game).await.unwrap(), 2);
+        // Both already have the game
+        assert_eq!(notifier.notify(&game).await.unwrap(), 0);
+
+        let requests = notifier.deliverer.requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 2);
+        let generic = requests
+            .iter()
+            .find(|request| request.url == "https://example.com/hook")
+            .unwrap();
+        let event: GamePublishedEvent = serde_json::from_str(&generic.body).unwrap();
+        assert_eq!(event.event, "game.published");
+        assert_eq!(event.game_id, game.id);
+        assert_eq!(event.url, "https://pathfinder.prof/puzzle/1");
+        assert_eq!(
+            generic.signature,
+            sign(
+                "secret for https://example.com/hook",
+                generic.timestamp,
+                &generic.body
+            )
+        );
+
+        let discord = requests
+            .iter()
+            .find(|request| request.url.starts_with("https://discord.com"))
+            .unwrap();
+        let message: serde_json::Value = serde_json::from_str(&discord.body).unwrap();
+        assert!(message["content"]
+            .as_str()
+            .unwrap()
+            .starts_with("Pathfinder #1 is live!"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_delivery_is_retried_next_run() {
+        let repository = InMemoryRepository::new();
+        subscribe(
+            &repository,
+            WebhookKind::Generic,
+            "https://example.com/hook",
+        )
+        .await;
+        let game = game(&repository).await;
+
+        let deliverer = FlakyDeliverer {
+            flaky_url: "https://example.com/hook".to_string(),
+            failures: AtomicU32::new(2),
+            ..Default::default()
+        };
+        let notifier = WebhookNotifier::new(repository.clone(), deliverer, config(0));
+        // Out of retries, so the claim is released for the next run
+        assert_eq!(notifier.notify(&game).await.unwrap(), 0);
+        assert_eq!(notifier.notify(&game).await.unwrap(), 0);
+        assert_eq!(notifier.notify(&game).await.unwrap(), 1);
+    }
+}
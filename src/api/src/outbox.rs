@@ -0,0 +1,460 @@
+use anyhow::{anyhow, Result};
+use axum::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::{env, sync::Arc, time::Duration};
+use tokio::{sync::Notify, task::JoinHandle};
+use tracing::{error, info, warn};
+
+use crate::db::{
+    models::{DbOutboxEvent, NewOutboxEvent},
+    Repository,
+};
+use crate::user_stats_api::UserStats;
+
+/// Events written to the outbox alongside the change that caused them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutboxEvent {
+    /// A user's entry for a game was written. `started` is whether the entry
+    /// is new and `completed` whether it was submitted.
+    EntrySaved {
+        user_id: String,
+        game_id: String,
+        total_score: i32,
+        started: bool,
+        completed: bool,
+    },
+}
+
+impl OutboxEvent {
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            OutboxEvent::EntrySaved { .. } => "entry_saved",
+        }
+    }
+
+    /// The event as it is stored in the outbox
+    pub fn to_new(&self) -> Result<NewOutboxEvent> {
+        Ok(NewOutboxEvent {
+            event_type: self.event_type().to_string(),
+            payload: serde_json::to_string(self)?,
+        })
+    }
+}
+
+/// OutboxHandler carries out one kind of side effect for outbox events.
+/// Events are delivered at least once: a handler may see an event again if
+/// it or a later handler failed, so handlers must tolerate repeats.
+#[async_trait]
+pub trait OutboxHandler: Send + Sync {
+    async fn handle(&self, event_id: &str, event: &OutboxEvent) -> Result<()>;
+}
+
+/// Drops a user's cached historical stats once they complete a game
+pub struct UserStatsHandler {
+    user_stats: UserStats,
+}
+
+impl UserStatsHandler {
+    pub fn new(user_stats: UserStats) -> Self {
+        Self { user_stats }
+    }
+}
+
+#[async_trait]
+impl OutboxHandler for UserStatsHandler {
+    async fn handle(&self, _event_id: &str, event: &OutboxEvent) -> Result<()> {
+        match event {
+            OutboxEvent::EntrySaved {
+                user_id,
+                completed: true,
+                ..
+            } => self.user_stats.invalidate(user_id).await,
+            OutboxEvent::EntrySaved { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+/// EventWebhook posts every outbox event as JSON to an external service
+pub struct EventWebhook {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+}
+
+#[derive(Serialize)]
+struct OutgoingEvent<'a> {
+    /// Stable across retries, so receivers can ignore repeats
+    id: &'a str,
+    #[serde(flatten)]
+    event: &'a OutboxEvent,
+}
+
+impl EventWebhook {
+    pub fn new(url: String, token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            token,
+        }
+    }
+
+    /// Creates a webhook from `EVENT_WEBHOOK_URL` and the optional
+    /// `EVENT_WEBHOOK_TOKEN`. Returns None when the URL is missing.
+    pub fn from_env() -> Option<Self> {
+        let url = env::var("EVENT_WEBHOOK_URL").ok()?;
+        let token = env::var("EVENT_WEBHOOK_TOKEN").ok();
+        Some(Self::new(url, token))
+    }
+}
+
+#[async_trait]
+impl OutboxHandler for EventWebhook {
+    async fn handle(&self, event_id: &str, event: &OutboxEvent) -> Result<()> {
+        let mut request = self.client.post(&self.url).json(&OutgoingEvent {
+            id: event_id,
+            event,
+        });
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("event webhook returned {}", response.status()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OutboxConfig {
+    /// How often pending events are retried when nothing wakes the worker
+    pub poll_interval: Duration,
+    /// Events fetched per pass
+    pub batch_size: i32,
+    /// Failed deliveries after which an event is left alone
+    pub max_attempts: i32,
+    /// How long processed events are kept before they are deleted
+    pub retention: Duration,
+}
+
+impl Default for OutboxConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(30),
+            batch_size: 100,
+            max_attempts: 10,
+            retention: Duration::from_secs(7 * 24 * 60 * 60),
+        }
+    }
+}
+
+impl OutboxConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(interval) = env::var("OUTBOX_POLL_INTERVAL") {
+            if let Ok(seconds) = interval.parse::<u64>() {
+                config.poll_interval = Duration::from_secs(seconds);
+            }
+        }
+
+        if let Ok(attempts) = env::var("OUTBOX_MAX_ATTEMPTS") {
+            if let Ok(value) = attempts.parse::<i32>() {
+                config.max_attempts = value;
+            }
+        }
+
+        if let Ok(days) = env::var("OUTBOX_RETENTION_DAYS") {
+            if let Ok(value) = days.parse::<u64>() {
+                config.retention = Duration::from_secs(value * 24 * 60 * 60);
+            }
+        }
+
+        config
+    }
+}
+
+/// Outbox delivers the events written alongside entries to its handlers,
+/// in the order they were written. Because an event is only stored if its
+/// write commits, and only marked processed once every handler succeeds, a
+/// crash can delay side effects but never lose or invent them.
+#[derive(Clone)]
+pub struct Outbox<R: Repository> {
+    repository: R,
+    handlers: Vec<Arc<dyn OutboxHandler>>,
+    config: OutboxConfig,
+    wake: Arc<Notify>,
+}
+
+impl<R: Repository> Outbox<R> {
+    pub fn new(repository: R) -> Self {
+        Self {
+            repository,
+            handlers: Vec::new(),
+            config: OutboxConfig::default(),
+            wake: Arc::new(Notify::new()),
+        }
+    }
+
+    pub fn with_config(mut self, config: OutboxConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Adds a handler, run after the handlers already added
+    pub fn with_handler(mut self, handler: Arc<dyn OutboxHandler>) -> Self {
+        self.handlers.push(handler);
+        self
+    }
+
+    /// Ask the worker to process pending events now rather than at its next poll
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+}
+
+impl<R: Repository + Clone + Send + Sync + 'static> Outbox<R> {
+    /// Deliver pending events, returning how many were processed. An event
+    /// whose delivery fails is counted as an attempt and retried on a later
+    /// pass.
+    pub async fn process_pending(&self) -> Result<usize> {
+        let mut processed = 0;
+        loop {
+            let events = self
+                .repository
+                .get_pending_outbox_events(self.config.max_attempts, self.config.batch_size)
+                .await?;
+            let fetched = events.len();
+
+            let mut failed = 0;
+            for event in events {
+                match self.deliver(&event).await {
+                    Ok(()) => {
+                        self.repository
+                            .mark_outbox_event_processed(&event.id)
+                            .await?;
+                        processed += 1;
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Outbox event {} ({}) failed on attempt {}: {:#}",
+                            event.id,
+                            event.event_type,
+                            event.attempts + 1,
+                            e
+                        );
+                        self.repository
+                            .record_outbox_event_failure(&event.id, &format!("{e:#}"))
+                            .await?;
+                        failed += 1;
+                    }
+                }
+            }
+
+            // Failed events stay pending, so stop once a pass makes no
+            // progress instead of retrying them straight away
+            if fetched < self.config.batch_size as usize || failed == fetched {
+                return Ok(processed);
+            }
+        }
+    }
+
+    async fn deliver(&self, event: &DbOutboxEvent) -> Result<()> {
+        let decoded: OutboxEvent = serde_json::from_str(&event.payload)?;
+        for handler in &self.handlers {
+            handler.handle(&event.id, &decoded).await?;
+        }
+        Ok(())
+    }
+
+    /// Starts processing events in the background, whenever the outbox is
+    /// woken and at least every poll interval. Events left pending by a
+    /// previous run are picked up straight away.
+    pub fn start(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            info!("Starting outbox worker");
+            loop {
+                match self.process_pending().await {
+                    Ok(0) => {}
+                    Ok(processed) => info!("Processed {} outbox events", processed),
+                    Err(e) => error!("Failed to process outbox events: {:#}", e),
+                }
+
+                let cutoff = Utc::now()
+                    - chrono::Duration::from_std(self.config.retention)
+                        .unwrap_or(chrono::Duration::days(7));
+                if let Err(e) = self.repository.delete_processed_outbox_events(cutoff).await {
+                    error!("Failed to delete processed outbox events: {:#}", e);
+                }
+
+                tokio::select! {
+                    _ = self.wake.notified() => {}
+                    _ = tokio::time::sleep(self.config.poll_interval) => {}
+                }
+            }
+        })
+    }
+}
+
+#[cfg(all(test, feature = "database-tests"))]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    use crate::db::{
+        models::{NewGameEntry, NewUser},
+        SqliteRepository,
+    };
+    use crate::test_utils::create_new_test_game;
+
+    /// Records the events it sees, failing the first `failures` of them
+    #[derive(Default)]
+    struct RecordingHandler {
+        failures: AtomicUsize,
+        seen: Mutex<Vec<OutboxEvent>>,
+    }
+
+    #[async_trait]
+    impl OutboxHandler for RecordingHandler {
+        async fn handle(&self, _event_id: &str, event: &OutboxEvent) -> Result<()> {
+            if self
+                .failures
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+                .is_ok()
+            {
+                return Err(anyhow!("receiver unavailable"));
+            }
+            self.seen.lock().unwrap().push(event.clone());
+            Ok(())
+        }
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_outbox_delivers_events_written_with_entries(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let repo = SqliteRepository::new(pool);
+        let user = repo
+            .create_user(NewUser {
+                cookie_token: "player".to_string(),
+            })
+            .await
+            .unwrap();
+        let (game, _) = repo
+            .create_game_with_answers(create_new_test_game(), vec![], None)
+            .await
+            .unwrap();
+
+        let event = OutboxEvent::EntrySaved {
+            user_id: user.id.clone(),
+            game_id: game.id.clone(),
+            total_score: 12,
+            started: true,
+            completed: true,
+        };
+        repo.create_or_update_game_entry_with_events(
+            NewGameEntry {
+                user_id: user.id.clone(),
+                game_id: game.id.clone(),
+                answers_data: "[]".to_string(),
+                total_score: 12,
+                completed: true,
+                suspicion_score: 0,
+                exclude: false,
+                request_id: None,
+            },
+            vec![event.to_new().unwrap()],
+        )
+        .await
+        .unwrap();
+
+        let handler = Arc::new(RecordingHandler {
+            failures: AtomicUsize::new(1),
+            ..Default::default()
+        });
+        let outbox = Outbox::new(repo.clone()).with_handler(handler.clone());
+
+        // A failed delivery leaves the event pending for the next pass
+        assert_eq!(outbox.process_pending().await.unwrap(), 0);
+        let pending = repo.get_pending_outbox_events(10, 10).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].attempts, 1);
+
+        assert_eq!(outbox.process_pending().await.unwrap(), 1);
+        assert_eq!(*handler.seen.lock().unwrap(), vec![event]);
+        assert!(repo
+            .get_pending_outbox_events(10, 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Nothing is delivered twice once processed
+        assert_eq!(outbox.process_pending().await.unwrap(), 0);
+        assert_eq!(handler.seen.lock().unwrap().len(), 1);
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_outbox_stops_retrying_after_max_attempts(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let repo = SqliteRepository::new(pool);
+        let user = repo
+            .create_user(NewUser {
+                cookie_token: "player".to_string(),
+            })
+            .await
+            .unwrap();
+        let (game, _) = repo
+            .create_game_with_answers(create_new_test_game(), vec![], None)
+            .await
+            .unwrap();
+        let event = OutboxEvent::EntrySaved {
+            user_id: user.id.clone(),
+            game_id: game.id.clone(),
+            total_score: 0,
+            started: true,
+            completed: false,
+        };
+        repo.create_or_update_game_entry_with_events(
+            NewGameEntry {
+                user_id: user.id,
+                game_id: game.id,
+                answers_data: "[]".to_string(),
+                total_score: 0,
+                completed: false,
+                suspicion_score: 0,
+                exclude: false,
+                request_id: None,
+            },
+            vec![event.to_new().unwrap()],
+        )
+        .await
+        .unwrap();
+
+        let handler = Arc::new(RecordingHandler {
+            failures: AtomicUsize::new(usize::MAX),
+            ..Default::default()
+        });
+        let outbox = Outbox::new(repo.clone())
+            .with_config(OutboxConfig {
+                max_attempts: 2,
+                ..Default::default()
+            })
+            .with_handler(handler);
+
+        outbox.process_pending().await.unwrap();
+        outbox.process_pending().await.unwrap();
+        assert!(repo
+            .get_pending_outbox_events(2, 10)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            repo.get_pending_outbox_events(3, 10).await.unwrap().len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use axum::body::Bytes;
+use moka::future::Cache;
+use serde::Serialize;
+use std::env;
+
+/// Default budget for cached path responses. A board's paths serialize to a
+/// few hundred KB, so this keeps a few months of games well inside the VM's
+/// memory.
+pub const DEFAULT_PATHS_CACHE_BYTES: u64 = 32 * 1024 * 1024;
+
+/// What a cached path response is for
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub enum PathsKey {
+    /// Every word's paths on a game's board, by game id
+    Game(String),
+    /// One word's paths, by game id and word
+    Word(String, String),
+}
+
+/// PathsCache holds path responses as the JSON sent to clients, since a
+/// game's paths never change once it exists. Entries are weighed by their
+/// serialized size and the least recently used are evicted once the cache
+/// holds `max_bytes`. It is kept apart from the game cache so large path
+/// responses can't push out games.
+#[derive(Clone)]
+pub struct PathsCache {
+    cache: Cache<PathsKey, Bytes>,
+    max_bytes: u64,
+}
+
+impl Default for PathsCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_PATHS_CACHE_BYTES)
+    }
+}
+
+impl PathsCache {
+    pub fn new(max_bytes: u64) -> Self {
+        let cache = Cache::builder()
+            .max_capacity(max_bytes)
+            .weigher(|_key, json: &Bytes| json.len().try_into().unwrap_or(u32::MAX))
+            .support_invalidation_closures()
+            .build();
+        Self { cache, max_bytes }
+    }
+
+    pub fn from_env() -> Self {
+        env::var("PATHS_CACHE_MAX_BYTES")
+            .ok()
+            .and_then(|bytes| bytes.parse().ok())
+            .map(Self::new)
+            .unwrap_or_default()
+    }
+
+    pub async fn get(&self, key: &PathsKey) -> Option<Bytes> {
+        self.cache.get(key).await
+    }
+
+    /// insert serializes `paths` and caches the JSON, returning it to be sent
+    pub async fn insert<T: Serialize>(
+        &self,
+        key: PathsKey,
+        paths: &T,
+    ) -> serde_json::Result<Bytes> {
+        let json = Bytes::from(serde_json::to_vec(paths)?);
+        self.cache.insert(key, json.clone()).await;
+        Ok(json)
+    }
+
+    pub fn invalidate_all(&self) {
+        self.cache.invalidate_all();
+    }
+
+    /// invalidate_game drops every response for one game's board
+    pub fn invalidate_game(&self, game_id: &str) {
+        let game_id = game_id.to_string();
+        // Only fails when the cache is built without invalidation closures
+        let _ = self.cache.invalidate_entries_if(move |key, _| match key {
+            PathsKey::Game(id) | PathsKey::Word(id, _) => *id == game_id,
+        });
+    }
+
+    pub fn entry_count(&self) -> u64 {
+        self.cache.entry_count()
+    }
+
+    /// Total serialized size of the cached responses, in bytes
+    pub fn weighted_size(&self) -> u64 {
+        self.cache.weighted_size()
+    }
+
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cache_stays_within_its_byte_budget() {
+        let cache = PathsCache::new(1_000);
+        let paths = vec!["x".repeat(300)];
+
+        for game in 0..10 {
+            let json = cache
+                .insert(PathsKey::Game(game.to_string()), &paths)
+                .await
+                .unwrap();
+            assert_eq!(json, serde_json::to_vec(&paths).unwrap());
+        }
+        cache.cache.run_pending_tasks().await;
+
+        assert!(cache.weighted_size() <= cache.max_bytes());
+        assert!(cache.entry_count() < 10);
+        assert!(cache.entry_count() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_game_and_word_entries_are_separate() {
+        let cache = PathsCache::default();
+        cache
+            .insert(PathsKey::Game("game".to_string()), &["all"])
+            .await
+            .unwrap();
+
+        assert!(cache
+            .get(&PathsKey::Game("game".to_string()))
+            .await
+            .is_some());
+        assert!(cache
+            .get(&PathsKey::Word("game".to_string(), "all".to_string()))
+            .await
+            .is_none());
+    }
+}
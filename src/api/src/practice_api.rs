@@ -0,0 +1,472 @@
+use anyhow::Context;
+use axum::{
+    extract::{Path, Query, State},
+    response::{Extension, Json},
+    routing::{get, post},
+    Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::api_error::ApiError;
+use crate::db::{conversions::AnswerStorage, models::DbPracticeGame, Repository};
+use crate::game::conversion::SerializableBoard;
+use crate::game::scoring::BonusRules;
+use crate::game::weakness::{PlayProfile, Weakness};
+use crate::http_api::{
+    authenticate_user, load_game, parse_api_board, parse_board, ApiAnswer, ApiBoard, ApiState,
+};
+use crate::security::{session::SessionInfo, user_cookie::UserIdentity};
+
+/// How long a practice board stays playable after it is generated
+pub const PRACTICE_GAME_TTL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+/// Recommendations look at games completed within this many days
+const RECOMMENDATION_WINDOW_DAYS: i64 = 60;
+
+/// At most this many of the games in the window are looked at
+const RECOMMENDATION_GAMES: i32 = 30;
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct NewPracticeRequest {
+    /// Seed to generate the board from; the same seed always gives the same board
+    pub seed: Option<u64>,
+    /// Generate a board that exercises this weakness. The board is picked
+    /// from several seeded candidates, starting at `seed` when one is given.
+    pub focus: Option<Weakness>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RecommendationsQuery {
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+}
+
+/// What a player should practice, judged from their recent games
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApiPracticeRecommendations {
+    pub profile: PlayProfile,
+    /// Most pronounced weakness first; empty until enough games are played
+    pub recommendations: Vec<ApiPracticeRecommendation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApiPracticeRecommendation {
+    /// Pass as `focus` to /api/practice/new for a board that exercises it
+    pub focus: Weakness,
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiPracticeGame {
+    pub id: String,
+    pub seed: u64,
+    pub board: ApiBoard,
+    /// The board's code, which /api/board/decode reads back, for sharing it
+    pub board_code: String,
+    pub threshold_score: i32,
+    pub optimal_score: i32,
+    pub expires_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PracticeSubmitRequest {
+    pub answers: Vec<ApiAnswer>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PracticeSubmitResponse {
+    pub total_score: i32,
+    pub threshold_score: i32,
+    pub optimal_score: i32,
+}
+
+/// Routes under /api/practice. Practice games have no date or sequence
+/// number and submissions are scored without being stored, so they never
+/// show up in daily stats.
+pub fn practice_router<R: Repository + Clone + Send + Sync + 'static>() -> Router<ApiState<R>> {
+    Router::new()
+        .route("/api/practice/new", post(new_practice_game))
+        .route(
+            "/api/practice/recommendations",
+            get(get_practice_recommendations),
+        )
+        .route("/api/practice/:practice_id", get(get_practice_game))
+        .route(
+            "/api/practice/:practice_id/submit",
+            post(submit_practice_game),
+        )
+}
+
+impl TryFrom<&DbPracticeGame> for ApiPracticeGame {
+    type Error = ApiError;
+
+    fn try_from(game: &DbPracticeGame) -> Result<Self, Self::Error> {
+        let board: SerializableBoard = serde_json::from_str(&game.board_data)
+            .with_context(|| format!("Failed to parse board of practice game {}", game.id))?;
+        let board_code = crate::game::Board::from(board)
+            .to_code()
+            .map_err(|e| anyhow::anyhow!("Practice game {} has no board code: {e}", game.id))?;
+        Ok(ApiPracticeGame {
+            id: game.id.clone(),
+            seed: game.seed,
+            board: parse_api_board(&game.board_data)?,
+            board_code,
+            threshold_score: game.threshold_score,
+            optimal_score: game.optimal_score,
+            expires_at: game.expires_at.to_rfc3339(),
+        })
+    }
+}
+
+async fn new_practice_game<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    Json(request): Json<NewPracticeRequest>,
+) -> Result<Json<ApiPracticeGame>, ApiError> {
+    // Clear out expired boards as new ones are made; a failure here only
+    // leaves rows that are already hidden from reads
+    match state.repository.delete_expired_practice_games().await {
+        Ok(0) => {}
+        Ok(deleted) => info!("Deleted {} expired practice games", deleted),
+        Err(e) => warn!("Failed to delete expired practice games: {}", e),
+    }
+
+    let ttl = chrono::Duration::from_std(PRACTICE_GAME_TTL)
+        .context("Practice game lifetime is out of range")?;
+    let game = match request.focus {
+        Some(focus) => state
+            .game_generator
+            .generate_focused_practice_game(focus, request.seed, ttl)
+            .await
+            .with_context(|| format!("Failed to generate {focus} practice game"))?,
+        None => state
+            .game_generator
+            .generate_practice_game(request.seed, ttl)
+            .await
+            .context("Failed to generate practice game")?,
+    };
+
+    state
+        .practice_cache
+        .insert(game.id.clone(), game.clone())
+        .await;
+
+    ApiPracticeGame::try_from(&game).map(Json)
+}
+
+async fn get_practice_game<R: Repository + Clone + Send + Sync + 'static>(
+    Path(practice_id): Path<String>,
+    State(state): State<ApiState<R>>,
+) -> Result<Json<ApiPracticeGame>, ApiError> {
+    let game = load_practice_game(&state, &practice_id).await?;
+    ApiPracticeGame::try_from(&game).map(Json)
+}
+
+async fn submit_practice_game<R: Repository + Clone + Send + Sync + 'static>(
+    Path(practice_id): Path<String>,
+    State(state): State<ApiState<R>>,
+    Json(request): Json<PracticeSubmitRequest>,
+) -> Result<Json<PracticeSubmitResponse>, ApiError> {
+    let game = load_practice_game(&state, &practice_id).await?;
+
+    let serializable_board: SerializableBoard = serde_json::from_str(&game.board_data)
+        .with_context(|| format!("Failed to parse board of practice game {}", game.id))?;
+    let board: crate::game::Board = serializable_board.into();
+
+    if let Err(error) = state
+        .game_engine
+        .validate_api_answer_group(&board, request.answers.clone())
+    {
+        info!("Practice answer validation failed: {error}");
+        return Err(error.into());
+    }
+
+    let answers = request
+        .answers
+        .into_iter()
+        .map(|answer| answer.sanitize().word)
+        .collect();
+    let score_sheet = state
+        .game_engine
+        // Practice scores are measured against the solver's optimal score,
+        // which counts tiles alone, so bonuses don't apply
+        .score_answer_group(&board, answers, &BonusRules::default())
+        .map_err(|error| {
+            info!("Practice answer scoring failed: {error}");
+            ApiError::from(error)
+        })?;
+
+    Ok(Json(PracticeSubmitResponse {
+        total_score: score_sheet
+            .total_score()
+            .try_into()
+            .context("Practice score is out of range")?,
+        threshold_score: game.threshold_score,
+        optimal_score: game.optimal_score,
+    }))
+}
+
+async fn get_practice_recommendations<R: Repository + Clone + Send + Sync + 'static>(
+    Query(query): Query<RecommendationsQuery>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+) -> Result<Json<ApiPracticeRecommendations>, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        query.user_id.as_ref(),
+        query.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+
+    let profile = play_profile(&state, &user.id).await?;
+    let recommendations = profile
+        .weaknesses()
+        .into_iter()
+        .map(|focus| ApiPracticeRecommendation {
+            focus,
+            reason: recommendation_reason(focus, &profile),
+        })
+        .collect();
+
+    Ok(Json(ApiPracticeRecommendations {
+        profile,
+        recommendations,
+    }))
+}
+
+/// Measure how a user played the games they completed recently, finding
+/// their words' paths on each board with the engine
+async fn play_profile<R: Repository>(
+    state: &ApiState<R>,
+    user_id: &str,
+) -> Result<PlayProfile, ApiError> {
+    let since = (Utc::now() - chrono::Duration::days(RECOMMENDATION_WINDOW_DAYS))
+        .format("%Y-%m-%d")
+        .to_string();
+    let history = state
+        .repository
+        .get_user_history(user_id, Some(&since), RECOMMENDATION_GAMES)
+        .await
+        .context("Failed to get user history")?;
+
+    let mut games = Vec::new();
+    for entry in history.into_iter().filter(|entry| entry.completed) {
+        let answers = match AnswerStorage::deserialize_to_api_answers(&entry.answers_data) {
+            Ok(answers) => answers,
+            Err(e) => {
+                warn!(
+                    "Skipping unreadable answers for game {}: {}",
+                    entry.game_id, e
+                );
+                continue;
+            }
+        };
+        let board = parse_board(&load_game(state, &entry.game_id).await?)?;
+        let answers = answers
+            .iter()
+            .map(|answer| {
+                state
+                    .game_engine
+                    .find_word_paths(&board, &answer.word.to_lowercase())
+            })
+            .collect();
+        games.push((board, answers));
+    }
+
+    Ok(PlayProfile::measure(&games))
+}
+
+fn recommendation_reason(focus: Weakness, profile: &PlayProfile) -> String {
+    match focus {
+        Weakness::ShortWords => format!(
+            "Your words average {:.1} letters. This board's best words are long.",
+            profile.average_word_length
+        ),
+        Weakness::RareWildcards => format!(
+            "Only {:.0}% of your words need a wildcard. This board's best words use them.",
+            profile.wildcard_share * 100.0
+        ),
+        Weakness::LowCoverage => format!(
+            "Your words cover {:.0}% of the board. This board's best words reach every corner.",
+            profile.tile_coverage * 100.0
+        ),
+    }
+}
+
+/// Load an unexpired practice game from cache or the database
+async fn load_practice_game<R: Repository>(
+    state: &ApiState<R>,
+    practice_id: &str,
+) -> Result<DbPracticeGame, ApiError> {
+    let not_found = || ApiError::NotFound("Practice game not found or expired".to_string());
+    if let Some(cached_game) = state.practice_cache.get(practice_id).await {
+        if cached_game.expires_at > Utc::now() {
+            return Ok(cached_game);
+        }
+        state.practice_cache.invalidate(practice_id).await;
+        return Err(not_found());
+    }
+
+    let game = state
+        .repository
+        .get_practice_game(practice_id)
+        .await
+        .context("Failed to get practice game")?
+        .ok_or_else(not_found)?;
+
+    state
+        .practice_cache
+        .insert(practice_id.to_string(), game.clone())
+        .await;
+
+    Ok(game)
+}
+
+#[cfg(all(test, feature = "database-tests"))]
+mod tests {
+    use super::*;
+    use axum::http::{Method, StatusCode};
+    use tower::util::ServiceExt;
+
+    use crate::db::models::NewPracticeGame;
+    use crate::test_utils::{create_test_board_data, create_test_request, setup_app};
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_practice_game_can_be_played_without_stats(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let (state, app) = setup_app(pool).await;
+
+        let game = state
+            .repository
+            .create_practice_game(NewPracticeGame {
+                seed: 42,
+                board_data: create_test_board_data(),
+                threshold_score: 10,
+                optimal_score: 30,
+                expires_at: Utc::now() + chrono::Duration::hours(1),
+            })
+            .await
+            .unwrap();
+
+        let uri = format!("/api/practice/{}", game.id);
+        let request = create_test_request(Method::GET, &uri, None);
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let practice: ApiPracticeGame = serde_json::from_slice(&body).unwrap();
+        assert_eq!(practice.seed, 42);
+        assert_eq!(practice.board.tiles.len(), 4);
+        // Every test tile is worth 1 point, which isn't standard
+        assert_eq!(practice.board_code, "testh*ngar*astop.1111111111111111");
+
+        let uri = format!("/api/practice/{}/submit", game.id);
+        let body = r#"{"answers": [{"word": "TEST", "score": 0}, {"word": "stop", "score": 0}]}"#;
+        let request = create_test_request(Method::POST, &uri, Some(body));
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let submitted: PracticeSubmitResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(submitted.total_score, 8);
+        assert_eq!(submitted.optimal_score, 30);
+
+        let body = r#"{"answers": [{"word": "zzz", "score": 0}]}"#;
+        let request = create_test_request(Method::POST, &uri, Some(body));
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let request = create_test_request(Method::GET, "/api/practice/missing", None);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_recommendations_follow_recent_games(pool: sqlx::Pool<sqlx::Sqlite>) {
+        use crate::db::models::{NewGameEntry, NewUser};
+        use crate::test_utils::create_new_test_game;
+
+        let (state, app) = setup_app(pool).await;
+        let repo = &state.repository;
+        let player = repo
+            .create_user(NewUser {
+                cookie_token: "player".to_string(),
+            })
+            .await
+            .unwrap();
+        let uri = format!(
+            "/api/practice/recommendations?user_id={}&cookie_token=player",
+            player.id
+        );
+
+        let request = create_test_request(
+            Method::GET,
+            &format!("/api/practice/recommendations?user_id={}", player.id),
+            None,
+        );
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // testh*ngar*astop: test and stop are short, keep off the wildcards
+        // and leave the middle rows untouched
+        for days_ago in 1..=3 {
+            let mut new_game = create_new_test_game();
+            new_game.sequence_number = days_ago as i32;
+            new_game.date = (Utc::now() - chrono::Duration::days(days_ago))
+                .format("%Y-%m-%d")
+                .to_string();
+            let (game, _) = repo
+                .create_game_with_answers(new_game, vec![], None)
+                .await
+                .unwrap();
+            let answers: Vec<ApiAnswer> = ["test", "stop"]
+                .iter()
+                .map(|word| ApiAnswer {
+                    word: word.to_string(),
+                    score: 0,
+                    path: None,
+                })
+                .collect();
+            repo.create_or_update_game_entry(NewGameEntry {
+                user_id: player.id.clone(),
+                game_id: game.id,
+                answers_data: AnswerStorage::serialize_api_answers(&answers).unwrap(),
+                total_score: 20,
+                completed: true,
+                suspicion_score: 0,
+                exclude: false,
+                request_id: None,
+            })
+            .await
+            .unwrap();
+        }
+
+        let request = create_test_request(Method::GET, &uri, None);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let recommended: ApiPracticeRecommendations = serde_json::from_slice(&body).unwrap();
+        assert_eq!(recommended.profile.games, 3);
+        assert_eq!(recommended.profile.tile_coverage, 0.5);
+        assert_eq!(
+            recommended
+                .recommendations
+                .iter()
+                .map(|r| r.focus)
+                .collect::<Vec<_>>(),
+            vec![
+                Weakness::RareWildcards,
+                Weakness::LowCoverage,
+                Weakness::ShortWords
+            ]
+        );
+        assert!(recommended.recommendations[1].reason.contains("50%"));
+    }
+}
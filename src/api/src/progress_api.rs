@@ -0,0 +1,298 @@
+use anyhow::Context;
+use axum::{
+    extract::{Path, Query, State},
+    response::{Extension, Json},
+    routing::get,
+    Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, env};
+
+use crate::api_error::ApiError;
+use crate::db::{conversions::AnswerStorage, models::DbEntryRevision, Repository};
+use crate::http_api::{authenticate_user, load_game, ApiAnswer, ApiState};
+use crate::security::{session::SessionInfo, user_cookie::UserIdentity};
+
+#[derive(Clone, Debug)]
+pub struct ProgressConfig {
+    /// Autosaved revisions kept per player and game; older ones are dropped
+    pub max_revisions: usize,
+}
+
+impl Default for ProgressConfig {
+    fn default() -> Self {
+        Self { max_revisions: 20 }
+    }
+}
+
+impl ProgressConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(max_revisions) = env::var("PROGRESS_MAX_REVISIONS") {
+            if let Ok(value) = max_revisions.parse::<usize>() {
+                config.max_revisions = value.max(1);
+            }
+        }
+
+        config
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SaveProgressRequest {
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+    /// Every answer found so far, not just the ones since the last save
+    pub answers: Vec<ApiAnswer>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ApiProgressRevision {
+    pub answers: Vec<ApiAnswer>,
+    pub saved_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiProgress {
+    /// Newest first
+    pub revisions: Vec<ApiProgressRevision>,
+}
+
+impl TryFrom<DbEntryRevision> for ApiProgressRevision {
+    type Error = ApiError;
+
+    fn try_from(revision: DbEntryRevision) -> Result<Self, Self::Error> {
+        let answers = AnswerStorage::deserialize_to_api_answers(&revision.answers_data)
+            .map_err(|e| anyhow::anyhow!("Failed to parse revision {}: {e}", revision.id))?;
+        Ok(ApiProgressRevision {
+            answers,
+            saved_at: revision.created_at,
+        })
+    }
+}
+
+impl ApiProgress {
+    pub fn from_revisions(revisions: Vec<DbEntryRevision>) -> Result<Self, ApiError> {
+        Ok(ApiProgress {
+            revisions: revisions
+                .into_iter()
+                .map(ApiProgressRevision::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Routes for autosaving an entry in progress and reading the saves back
+pub fn progress_router<R: Repository + Clone + Send + Sync + 'static>() -> Router<ApiState<R>> {
+    Router::new().route(
+        "/api/game-entry/:game_id/progress",
+        get(get_progress).patch(save_progress),
+    )
+}
+
+/// Autosave the answers found so far as a new revision. This is only a
+/// snapshot for recovery: the entry itself, its score and stats are left to
+/// POST /api/game-entry/:game_id.
+async fn save_progress<R: Repository + Clone + Send + Sync + 'static>(
+    Path(game_id): Path<String>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+    Json(request): Json<SaveProgressRequest>,
+) -> Result<Json<ApiProgressRevision>, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        request.user_id.as_ref(),
+        request.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+    let game = load_game(&state, &game_id).await?;
+
+    let entry = state
+        .repository
+        .get_game_entry(&user.id, &game.id)
+        .await
+        .context("Failed to get game entry")?;
+    if entry.is_some_and(|entry| entry.completed) {
+        return Err(ApiError::conflict("This game has already been submitted"));
+    }
+
+    // Saving the same answers again, e.g. from an autosave timer, keeps the
+    // revision already stored rather than pushing older ones out
+    let latest = state
+        .repository
+        .get_entry_revisions(&user.id, &game.id)
+        .await
+        .context("Failed to get entry revisions")?
+        .into_iter()
+        .next()
+        .map(ApiProgressRevision::try_from)
+        .transpose()?;
+    if let Some(latest) = latest.filter(|latest| latest.answers == request.answers) {
+        return Ok(Json(latest));
+    }
+
+    let answers_data = AnswerStorage::serialize_api_answers(&request.answers)
+        .context("Failed to serialize answers")?;
+    let revision = state
+        .repository
+        .save_entry_revision(
+            &user.id,
+            &game.id,
+            &answers_data,
+            state.progress_config.max_revisions,
+        )
+        .await
+        .context("Failed to save entry revision")?;
+
+    Ok(Json(ApiProgressRevision {
+        answers: request.answers,
+        saved_at: revision.created_at,
+    }))
+}
+
+/// The caller's autosaved revisions for a game, newest first
+async fn get_progress<R: Repository + Clone + Send + Sync + 'static>(
+    Path(game_id): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+) -> Result<Json<ApiProgress>, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        params.get("user_id"),
+        params.get("cookie_token"),
+        session.as_deref(),
+    )
+    .await?;
+    let game = load_game(&state, &game_id).await?;
+
+    let revisions = state
+        .repository
+        .get_entry_revisions(&user.id, &game.id)
+        .await
+        .context("Failed to get entry revisions")?;
+    ApiProgress::from_revisions(revisions).map(Json)
+}
+
+#[cfg(all(test, feature = "database-tests"))]
+mod tests {
+    use super::*;
+    use axum::http::{Method, StatusCode};
+    use tower::util::ServiceExt;
+
+    use crate::db::models::{NewGameEntry, NewUser};
+    use crate::test_utils::{create_new_test_game, create_test_request, setup_app};
+
+    fn answers(words: &[&str]) -> Vec<ApiAnswer> {
+        words
+            .iter()
+            .map(|word| ApiAnswer {
+                word: word.to_string(),
+                score: 4,
+                path: None,
+            })
+            .collect()
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_progress_keeps_the_newest_revisions(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let (state, _) = setup_app(pool).await;
+        let state = state.with_progress_config(ProgressConfig { max_revisions: 2 });
+        let app = crate::http_api::create_secure_router(
+            state.clone(),
+            crate::security::SecurityConfig::default(),
+        );
+        let repo = &state.repository;
+        let (game, _) = repo
+            .create_game_with_answers(create_new_test_game(), vec![], None)
+            .await
+            .unwrap();
+        let user = repo
+            .create_user(NewUser {
+                cookie_token: "autosaver".to_string(),
+            })
+            .await
+            .unwrap();
+        let uri = format!("/api/game-entry/{}/progress", game.id);
+        let save = |words: &[&str]| {
+            let body = serde_json::to_string(&SaveProgressRequest {
+                user_id: Some(user.id.clone()),
+                cookie_token: Some(user.cookie_token.clone()),
+                answers: answers(words),
+            })
+            .unwrap();
+            create_test_request(Method::PATCH, &uri, Some(&body))
+        };
+
+        // Saving unchanged answers doesn't add a revision
+        for words in [
+            &["stop"][..],
+            &["stop"],
+            &["stop", "test"],
+            &["stop", "test", "post"],
+        ] {
+            let response = app.clone().oneshot(save(words)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let query_uri = format!(
+            "{uri}?user_id={}&cookie_token={}",
+            user.id, user.cookie_token
+        );
+        let response = app
+            .clone()
+            .oneshot(create_test_request(Method::GET, &query_uri, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let progress: ApiProgress = serde_json::from_slice(&body).unwrap();
+        let saved: Vec<Vec<ApiAnswer>> = progress
+            .revisions
+            .into_iter()
+            .map(|revision| revision.answers)
+            .collect();
+        assert_eq!(
+            saved,
+            vec![
+                answers(&["stop", "test", "post"]),
+                answers(&["stop", "test"])
+            ]
+        );
+
+        // Once the entry is submitted there's nothing left to autosave
+        repo.create_or_update_game_entry(NewGameEntry {
+            user_id: user.id.clone(),
+            game_id: game.id.clone(),
+            answers_data: "[]".to_string(),
+            total_score: 10,
+            completed: true,
+            suspicion_score: 0,
+            exclude: false,
+            request_id: None,
+        })
+        .await
+        .unwrap();
+        let response = app.clone().oneshot(save(&["stop"])).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let response = app
+            .oneshot(create_test_request(
+                Method::GET,
+                &format!("{uri}?user_id=nobody&cookie_token=none"),
+                None,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
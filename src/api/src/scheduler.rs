@@ -0,0 +1,272 @@
+use anyhow::Result;
+use chrono::Utc;
+use std::{env, future::Future, sync::Arc, time::Duration};
+use tokio_cron_scheduler::{Job, JobScheduler};
+use tracing::{error, info, warn};
+
+use crate::db::{models::DbGame, Repository};
+use crate::game_generator::GameGenerator;
+use crate::notifier::WebhookNotifier;
+use crate::shutdown::BackgroundTasks;
+use crate::social::{announcer::DailyAnnouncer, Post};
+
+#[derive(Clone, Debug)]
+pub struct SchedulerConfig {
+    /// Whether the API server should run the generation scheduler in-process
+    pub enabled: bool,
+    /// Cron expression (with seconds) describing when generation runs
+    pub schedule: String,
+    /// Whether to run a generation pass immediately when the scheduler starts
+    pub run_on_startup: bool,
+    /// How many times a failed generation pass is retried before giving up until the next tick
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled for every subsequent retry
+    pub retry_backoff: Duration,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            schedule: "0 30 1 * * *".to_string(), // 1:30 AM UTC daily
+            run_on_startup: true,
+            max_retries: 3,
+            retry_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl SchedulerConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(enabled) = env::var("GENERATION_SCHEDULER_ENABLED") {
+            config.enabled = enabled.to_lowercase() == "true";
+        }
+
+        if let Ok(schedule) = env::var("GENERATION_SCHEDULE") {
+            config.schedule = schedule;
+        }
+
+        if let Ok(on_startup) = env::var("GENERATION_RUN_ON_STARTUP") {
+            config.run_on_startup = on_startup.to_lowercase() == "true";
+        }
+
+        if let Ok(retries) = env::var("GENERATION_MAX_RETRIES") {
+            if let Ok(value) = retries.parse::<u32>() {
+                config.max_retries = value;
+            }
+        }
+
+        if let Ok(backoff) = env::var("GENERATION_RETRY_BACKOFF") {
+            if let Ok(seconds) = backoff.parse::<u64>() {
+                config.retry_backoff = Duration::from_secs(seconds);
+            }
+        }
+
+        config
+    }
+}
+
+/// GenerationScheduler periodically pre-generates upcoming games and announces
+/// the day's game through an optional announcer and webhook notifier
+pub struct GenerationScheduler<R: Repository, P: Post> {
+    game_generator: GameGenerator<R>,
+    announcer: Option<DailyAnnouncer<R, P>>,
+    notifier: Option<WebhookNotifier<R>>,
+    config: SchedulerConfig,
+    tasks: BackgroundTasks,
+}
+
+impl<R, P> GenerationScheduler<R, P>
+where
+    R: Repository + Clone + Send + Sync + 'static,
+    P: Post + Send + Sync + 'static,
+{
+    pub fn new(game_generator: GameGenerator<R>, config: SchedulerConfig) -> Self {
+        Self {
+            game_generator,
+            announcer: None,
+            notifier: None,
+            config,
+            tasks: BackgroundTasks::default(),
+        }
+    }
+
+    /// Tracks each run in `tasks`, so shutdown can wait for a run to store
+    /// and announce its games
+    pub fn with_tasks(mut self, tasks: BackgroundTasks) -> Self {
+        self.tasks = tasks;
+        self
+    }
+
+    /// Sets the announcer used to post each day's game once it is live
+    pub fn with_announcer(mut self, announcer: DailyAnnouncer<R, P>) -> Self {
+        self.announcer = Some(announcer);
+        self
+    }
+
+    /// Sets the notifier used to send each day's game to subscribed webhooks
+    pub fn with_notifier(mut self, notifier: WebhookNotifier<R>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Starts the scheduler in the background. The returned JobScheduler must
+    /// be kept alive for as long as scheduled generation should keep running.
+    pub async fn start(self) -> Result<JobScheduler> {
+        let schedule = self.config.schedule.clone();
+        let run_on_startup = self.config.run_on_startup;
+        let this = Arc::new(self);
+
+        if run_on_startup {
+            let startup = this.clone();
+            tokio::spawn(async move {
+                info!("Running initial game generation on startup");
+                startup.run().await;
+            });
+        }
+
+        let sched = JobScheduler::new().await?;
+        sched
+            .add(Job::new_async(schedule.as_str(), move |_uuid, _l| {
+                let this = this.clone();
+                Box::pin(async move {
+                    info!("Running scheduled game generation");
+                    this.run().await;
+                })
+            })?)
+            .await?;
+        sched.start().await?;
+
+        info!(
+            "Game generation scheduler started with schedule '{}'",
+            schedule
+        );
+        Ok(sched)
+    }
+
+    /// Runs one generation pass with retries, then announces today's game and
+    /// sends it to webhooks
+    pub async fn run(&self) {
+        let _running = self.tasks.track();
+        match self.generate_with_retry().await {
+            Ok(games) => info!("Game generation completed, {} new games", games.len()),
+            Err(e) => error!("Game generation failed after all retries: {}", e),
+        }
+
+        // Announce even if generation failed, since today's game may already exist
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        if let Some(announcer) = &self.announcer {
+            if let Err(e) = announcer.announce_date(&today).await {
+                warn!("Failed to announce game for {}: {:#}", today, e);
+            }
+        }
+        if let Some(notifier) = &self.notifier {
+            if let Err(e) = notifier.notify_date(&today).await {
+                warn!("Failed to send game for {} to webhooks: {:#}", today, e);
+            }
+        }
+    }
+
+    async fn generate_with_retry(&self) -> Result<Vec<DbGame>> {
+        retry_with_backoff(self.config.max_retries, self.config.retry_backoff, || {
+            self.game_generator.generate_missing_games()
+        })
+        .await
+    }
+}
+
+/// retry_with_backoff runs `attempt` until it succeeds, retrying up to
+/// `max_retries` times with exponential backoff from `backoff`
+pub(crate) async fn retry_with_backoff<T, F, Fut>(
+    max_retries: u32,
+    backoff: Duration,
+    mut attempt: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut retries = 0;
+    loop {
+        match attempt().await {
+            Ok(value) => return Ok(value),
+            Err(e) if retries < max_retries => {
+                let delay = backoff_delay(backoff, retries);
+                warn!(
+                    "Game generation attempt {} failed: {}; retrying in {:?}",
+                    retries + 1,
+                    e,
+                    delay
+                );
+                tokio::time::sleep(delay).await;
+                retries += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// backoff_delay returns the exponential backoff delay for a zero-based retry attempt
+pub(crate) fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(2u32.saturating_pow(attempt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_backoff_delay_doubles() {
+        let base = Duration::from_secs(30);
+        assert_eq!(backoff_delay(base, 0), Duration::from_secs(30));
+        assert_eq!(backoff_delay(base, 1), Duration::from_secs(60));
+        assert_eq!(backoff_delay(base, 3), Duration::from_secs(240));
+    }
+
+    #[test]
+    fn test_backoff_delay_saturates() {
+        let delay = backoff_delay(Duration::from_secs(30), 64);
+        assert!(delay >= Duration::from_secs(30));
+    }
+
+    #[tokio::test]
+    async fn test_failed_generation_is_retried() {
+        let attempts = AtomicU32::new(0);
+        let generate = || async {
+            match attempts.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(anyhow::anyhow!("Failed to generate 1 of the missing games")),
+                _ => Ok(vec!["2025-03-01"]),
+            }
+        };
+
+        let generated = retry_with_backoff(3, Duration::from_millis(1), generate)
+            .await
+            .unwrap();
+        assert_eq!(generated, vec!["2025-03-01"]);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_generation_gives_up_after_the_last_retry() {
+        let attempts = AtomicU32::new(0);
+        let generate = || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err::<(), _>(anyhow::anyhow!("Failed to generate 1 of the missing games"))
+        };
+
+        assert!(retry_with_backoff(2, Duration::from_millis(1), generate)
+            .await
+            .is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_default_schedule_is_valid_cron() {
+        let config = SchedulerConfig::default();
+        assert!(!config.enabled);
+        assert!(Job::new_async(config.schedule.as_str(), |_uuid, _l| Box::pin(async {})).is_ok());
+    }
+}
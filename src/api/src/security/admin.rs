@@ -0,0 +1,450 @@
+use axum::{
+    async_trait,
+    http::{header, HeaderMap, StatusCode},
+    response::Response,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use tracing::{error, warn};
+
+use crate::db::{models::DbAdminToken, Repository};
+use crate::security::{
+    policy::RoutePolicies,
+    utils::{constant_time_eq, hash_token},
+    SecurityConfig,
+};
+
+/// What an admin token may be used for. A leaked scoped token only exposes
+/// the routes for its scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdminScope {
+    /// Staging and publishing game batches
+    Generate,
+    /// Reading operational stats
+    Stats,
+    /// Clearing caches
+    Cache,
+    /// Everything, including managing tokens. Only the bootstrap token from
+    /// `SecurityConfig::admin_token` has this scope.
+    Superadmin,
+}
+
+impl AdminScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AdminScope::Generate => "generate",
+            AdminScope::Stats => "stats",
+            AdminScope::Cache => "cache",
+            AdminScope::Superadmin => "superadmin",
+        }
+    }
+
+    /// Whether a token with this scope may use a route requiring `required`
+    pub fn allows(&self, required: AdminScope) -> bool {
+        *self == AdminScope::Superadmin || *self == required
+    }
+}
+
+impl fmt::Display for AdminScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for AdminScope {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "generate" => Ok(AdminScope::Generate),
+            "stats" => Ok(AdminScope::Stats),
+            "cache" => Ok(AdminScope::Cache),
+            "superadmin" => Ok(AdminScope::Superadmin),
+            _ => Err(anyhow::anyhow!("unknown admin scope: {s}")),
+        }
+    }
+}
+
+/// The admin token a request was authorized with. It's added to the
+/// request so handlers can record who made a change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdminCaller {
+    pub scope: AdminScope,
+    /// The scoped token's id, or None for the bootstrap token
+    pub token_id: Option<String>,
+}
+
+/// AdminTokenStore looks up scoped admin tokens by the hash of the token
+#[async_trait]
+pub trait AdminTokenStore: Send + Sync {
+    async fn find_admin_token(&self, token_hash: &str) -> anyhow::Result<Option<DbAdminToken>>;
+}
+
+#[async_trait]
+impl<R: Repository + Send + Sync> AdminTokenStore for R {
+    async fn find_admin_token(&self, token_hash: &str) -> anyhow::Result<Option<DbAdminToken>> {
+        self.get_admin_token_by_hash(token_hash).await
+    }
+}
+
+/// AdminAuthLayer guards the routes whose policy requires an admin scope.
+/// Requests must carry either the bootstrap token from
+/// `SecurityConfig::admin_token`, which is allowed every scope, or an
+/// unexpired, unrevoked token from the token store allowed the route's
+/// scope. When neither a bootstrap token nor a store is configured every
+/// admin route responds 404, as though the admin API didn't exist.
+#[derive(Clone)]
+pub struct AdminAuthLayer {
+    config: SecurityConfig,
+    policies: RoutePolicies,
+    tokens: Option<Arc<dyn AdminTokenStore>>,
+}
+
+impl AdminAuthLayer {
+    pub fn new(config: SecurityConfig) -> Self {
+        Self {
+            config,
+            policies: RoutePolicies::default(),
+            tokens: None,
+        }
+    }
+
+    /// Choose which routes require which admin scope from these policies
+    pub fn with_policies(mut self, policies: RoutePolicies) -> Self {
+        self.policies = policies;
+        self
+    }
+
+    /// Also accept scoped tokens from this store
+    pub fn with_tokens(mut self, tokens: Arc<dyn AdminTokenStore>) -> Self {
+        self.tokens = Some(tokens);
+        self
+    }
+}
+
+impl<S> Layer<S> for AdminAuthLayer {
+    type Service = AdminAuthMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AdminAuthMiddleware {
+            inner,
+            config: self.config.clone(),
+            policies: self.policies.clone(),
+            tokens: self.tokens.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AdminAuthMiddleware<S> {
+    inner: S,
+    config: SecurityConfig,
+    policies: RoutePolicies,
+    tokens: Option<Arc<dyn AdminTokenStore>>,
+}
+
+impl<S> Service<axum::http::Request<axum::body::Body>> for AdminAuthMiddleware<S>
+where
+    S: Service<axum::http::Request<axum::body::Body>, Response = Response, Error = Infallible>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: axum::http::Request<axum::body::Body>) -> Self::Future {
+        let config = self.config.clone();
+        let tokens = self.tokens.clone();
+        let mut inner = self.inner.clone();
+        let required = self
+            .policies
+            .policy_for(request.method(), request.uri().path())
+            .admin_scope;
+
+        Box::pin(async move {
+            let Some(required) = required else {
+                return inner.call(request).await;
+            };
+
+            if config.admin_token.is_none() && tokens.is_none() {
+                return Ok(create_admin_error_response(
+                    StatusCode::NOT_FOUND,
+                    "Not found",
+                ));
+            }
+
+            let caller = match bearer_token(request.headers()) {
+                Some(token) => authorize(&config, tokens.as_deref(), token).await,
+                None => Ok(None),
+            };
+
+            match caller {
+                Ok(Some(caller)) if caller.scope.allows(required) => {
+                    request.extensions_mut().insert(caller);
+                    inner.call(request).await
+                }
+                Ok(Some(caller)) => {
+                    warn!(
+                        "Rejected admin request with {} token: {}",
+                        caller.scope,
+                        request.uri().path()
+                    );
+                    Ok(create_admin_error_response(
+                        StatusCode::FORBIDDEN,
+                        &format!("Token is not allowed the {required} scope"),
+                    ))
+                }
+                Ok(None) => {
+                    warn!(
+                        "Rejected admin request without a valid token: {}",
+                        request.uri().path()
+                    );
+                    Ok(create_admin_error_response(
+                        StatusCode::UNAUTHORIZED,
+                        "Missing, invalid or expired admin token",
+                    ))
+                }
+                Err(e) => {
+                    error!("Failed to look up admin token: {}", e);
+                    Ok(create_admin_error_response(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "Could not check admin token",
+                    ))
+                }
+            }
+        })
+    }
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+/// Who a presented token belongs to and the scope it is allowed, or None if
+/// it isn't a usable token
+async fn authorize(
+    config: &SecurityConfig,
+    tokens: Option<&dyn AdminTokenStore>,
+    token: &str,
+) -> anyhow::Result<Option<AdminCaller>> {
+    if let Some(bootstrap) = config.admin_token.as_deref() {
+        if constant_time_eq(token.as_bytes(), bootstrap.as_bytes()) {
+            return Ok(Some(AdminCaller {
+                scope: AdminScope::Superadmin,
+                token_id: None,
+            }));
+        }
+    }
+
+    let Some(tokens) = tokens else {
+        return Ok(None);
+    };
+
+    // Stored tokens are looked up by hash, so an exact match on the hash
+    // doesn't leak anything about the token through timing
+    let Some(stored) = tokens.find_admin_token(&hash_token(token)).await? else {
+        return Ok(None);
+    };
+    if !stored.is_active(Utc::now()) {
+        return Ok(None);
+    }
+
+    // Stored tokens are never superadmin, even if the database says so
+    match stored.scope.parse::<AdminScope>() {
+        Ok(AdminScope::Superadmin) | Err(_) => {
+            warn!(
+                "Ignoring admin token {} with scope {}",
+                stored.id, stored.scope
+            );
+            Ok(None)
+        }
+        Ok(scope) => Ok(Some(AdminCaller {
+            scope,
+            token_id: Some(stored.id),
+        })),
+    }
+}
+
+fn create_admin_error_response(status: StatusCode, message: &str) -> Response {
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(axum::body::Body::from(format!(
+            r#"{{"error": "Admin authentication failed", "message": "{message}"}}"#
+        )))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn call_with_token(config: SecurityConfig, token: Option<&str>) -> StatusCode {
+        let layer = AdminAuthLayer::new(config);
+        let mut service = layer.layer(tower::service_fn(|_| async {
+            Ok(Response::new(axum::body::Body::from("ok")))
+        }));
+
+        let mut builder = Request::builder().uri("/api/admin/batches");
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {token}"));
+        }
+        let request = builder.body(axum::body::Body::empty()).unwrap();
+
+        let response = service.ready().await.unwrap().call(request).await.unwrap();
+        response.status()
+    }
+
+    fn config_with_token() -> SecurityConfig {
+        SecurityConfig {
+            admin_token: Some("secret-admin-token".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_admin_disabled_without_configured_token() {
+        let status = call_with_token(SecurityConfig::default(), Some("anything")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_admin_rejects_missing_or_wrong_token() {
+        assert_eq!(
+            call_with_token(config_with_token(), None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            call_with_token(config_with_token(), Some("wrong")).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_admin_accepts_valid_token() {
+        let status = call_with_token(config_with_token(), Some("secret-admin-token")).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_admin_ignores_routes_without_admin_policy() {
+        let layer = AdminAuthLayer::new(config_with_token());
+        let mut service = layer.layer(tower::service_fn(|_| async {
+            Ok(Response::new(axum::body::Body::from("ok")))
+        }));
+
+        let request = Request::builder()
+            .uri("/api/game/today")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = service.ready().await.unwrap().call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    struct FakeTokens(Vec<DbAdminToken>);
+
+    #[async_trait]
+    impl AdminTokenStore for FakeTokens {
+        async fn find_admin_token(&self, token_hash: &str) -> anyhow::Result<Option<DbAdminToken>> {
+            Ok(self.0.iter().find(|t| t.token_hash == token_hash).cloned())
+        }
+    }
+
+    fn stored_token(token: &str, scope: &str, expires_in: chrono::Duration) -> DbAdminToken {
+        DbAdminToken::new(
+            hash_token(token),
+            scope.to_string(),
+            None,
+            Utc::now() + expires_in,
+        )
+    }
+
+    async fn call_scoped(uri: &str, token: &str) -> StatusCode {
+        let tokens = FakeTokens(vec![
+            stored_token("stats-token", "stats", chrono::Duration::hours(1)),
+            stored_token("expired-token", "stats", chrono::Duration::hours(-1)),
+            stored_token("forged-token", "superadmin", chrono::Duration::hours(1)),
+        ]);
+        let layer = AdminAuthLayer::new(SecurityConfig::default()).with_tokens(Arc::new(tokens));
+        let mut service = layer.layer(tower::service_fn(|_| async {
+            Ok(Response::new(axum::body::Body::from("ok")))
+        }));
+
+        let request = Request::builder()
+            .uri(uri)
+            .header("authorization", format!("Bearer {token}"))
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = service.ready().await.unwrap().call(request).await.unwrap();
+        response.status()
+    }
+
+    #[test]
+    fn test_scope_allows() {
+        assert!(AdminScope::Stats.allows(AdminScope::Stats));
+        assert!(!AdminScope::Stats.allows(AdminScope::Generate));
+        assert!(!AdminScope::Cache.allows(AdminScope::Superadmin));
+        assert!(AdminScope::Superadmin.allows(AdminScope::Cache));
+        assert_eq!("cache".parse::<AdminScope>().unwrap(), AdminScope::Cache);
+        assert!("root".parse::<AdminScope>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scoped_token_only_reaches_its_routes() {
+        assert_eq!(
+            call_scoped("/api/admin/input-rejections", "stats-token").await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            call_scoped("/api/admin/batches/abc", "stats-token").await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            call_scoped("/api/admin/tokens", "stats-token").await,
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expired_and_unknown_tokens_rejected() {
+        assert_eq!(
+            call_scoped("/api/admin/input-rejections", "expired-token").await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            call_scoped("/api/admin/input-rejections", "unknown-token").await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stored_tokens_are_never_superadmin() {
+        assert_eq!(
+            call_scoped("/api/admin/tokens", "forged-token").await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
@@ -1,6 +1,16 @@
 use std::env;
 use std::time::Duration;
 
+/// Where rate limit counts are kept
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RateLimitStoreKind {
+    /// In this process; each instance enforces its own limits
+    #[default]
+    Memory,
+    /// In the database, shared by every instance using it
+    Database,
+}
+
 #[derive(Clone, Debug)]
 pub struct SecurityConfig {
     pub allowed_origins: Vec<String>,
@@ -9,11 +19,26 @@ pub struct SecurityConfig {
     pub rate_limit_read: u32,
     pub rate_limit_write: u32,
     pub rate_limit_window: Duration,
+    pub rate_limit_store: RateLimitStoreKind,
     pub cookie_max_age: Duration,
     pub request_timeout: Duration,
     pub max_request_size: usize,
+    /// How long a client gets to send a request's headers, so slowloris-style
+    /// clients trickling bytes can't hold a connection open
+    pub header_read_timeout: Duration,
+    /// Connections that send and receive nothing for this long are closed.
+    /// Keep it above `request_timeout` so slow handlers aren't cut off.
+    pub idle_connection_timeout: Duration,
+    /// Most connections open at once; further connections are dropped
+    pub max_connections: usize,
+    /// Most connections open at once from one address, 0 for no limit.
+    /// Behind a proxy every connection comes from the proxy's address.
+    pub max_connections_per_ip: usize,
     pub strict_referer: bool,
     pub hsts_max_age: u64,
+    /// Bootstrap superadmin token for /api/admin endpoints, which can also create
+    /// scoped tokens. Without it only scoped tokens already created can be used
+    pub admin_token: Option<String>,
 }
 
 impl Default for SecurityConfig {
@@ -28,11 +53,17 @@ impl Default for SecurityConfig {
             rate_limit_read: 200,
             rate_limit_write: 50,
             rate_limit_window: Duration::from_secs(60),
+            rate_limit_store: RateLimitStoreKind::Memory,
             cookie_max_age: Duration::from_secs(365 * 24 * 60 * 60), // 1 year
             request_timeout: Duration::from_secs(30),
             max_request_size: 1024 * 1024, // 1MB
+            header_read_timeout: Duration::from_secs(10),
+            idle_connection_timeout: Duration::from_secs(60),
+            max_connections: 512,
+            max_connections_per_ip: 32,
             strict_referer: true,
             hsts_max_age: 31536000, // 1 year
+            admin_token: None,
         }
     }
 }
@@ -75,6 +106,14 @@ impl SecurityConfig {
             }
         }
 
+        if let Ok(store) = env::var("RATE_LIMIT_STORE") {
+            match store.trim().to_lowercase().as_str() {
+                "memory" => config.rate_limit_store = RateLimitStoreKind::Memory,
+                "database" => config.rate_limit_store = RateLimitStoreKind::Database,
+                _ => {}
+            }
+        }
+
         if let Ok(max_age) = env::var("COOKIE_MAX_AGE") {
             if let Ok(seconds) = max_age.parse::<u64>() {
                 config.cookie_max_age = Duration::from_secs(seconds);
@@ -93,6 +132,30 @@ impl SecurityConfig {
             }
         }
 
+        if let Ok(timeout) = env::var("HEADER_READ_TIMEOUT") {
+            if let Ok(seconds) = timeout.parse::<u64>() {
+                config.header_read_timeout = Duration::from_secs(seconds);
+            }
+        }
+
+        if let Ok(timeout) = env::var("IDLE_CONNECTION_TIMEOUT") {
+            if let Ok(seconds) = timeout.parse::<u64>() {
+                config.idle_connection_timeout = Duration::from_secs(seconds);
+            }
+        }
+
+        if let Ok(limit) = env::var("MAX_CONNECTIONS") {
+            if let Ok(value) = limit.parse::<usize>() {
+                config.max_connections = value;
+            }
+        }
+
+        if let Ok(limit) = env::var("MAX_CONNECTIONS_PER_IP") {
+            if let Ok(value) = limit.parse::<usize>() {
+                config.max_connections_per_ip = value;
+            }
+        }
+
         if let Ok(strict) = env::var("STRICT_REFERER") {
             config.strict_referer = strict.to_lowercase() == "true";
         }
@@ -103,6 +166,12 @@ impl SecurityConfig {
             }
         }
 
+        if let Ok(token) = env::var("ADMIN_TOKEN") {
+            if !token.trim().is_empty() {
+                config.admin_token = Some(token.trim().to_string());
+            }
+        }
+
         config
     }
 }
@@ -9,8 +9,12 @@ use std::task::{Context, Poll};
 use tower::{Layer, Service};
 use tracing::{debug, warn};
 
+use crate::logging::LogSampler;
 use crate::security::{utils::is_origin_allowed, SecurityConfig};
 
+/// Sampled, as the frontend's own requests all come from an allowed origin
+static ALLOWED_ORIGIN_LOGS: LogSampler = LogSampler::every(100);
+
 #[derive(Clone)]
 pub struct CorsLayer {
     config: SecurityConfig,
@@ -81,7 +85,9 @@ where
                         .body(axum::body::Body::from("CORS: Origin not allowed"))
                         .unwrap());
                 }
-                debug!("CORS: Allowed request from origin: {}", origin_value);
+                if ALLOWED_ORIGIN_LOGS.sample() {
+                    debug!("CORS: Allowed request from origin: {}", origin_value);
+                }
             }
 
             // Process the request
@@ -116,7 +122,7 @@ fn handle_preflight(config: &SecurityConfig, headers: &HeaderMap) -> Response {
     }
 
     // Validate requested method
-    let allowed_methods = ["GET", "POST", "PUT", "DELETE"];
+    let allowed_methods = ["GET", "POST", "PUT", "PATCH", "DELETE"];
     if let Some(method) = requested_method {
         if !allowed_methods.contains(&method) {
             warn!(
@@ -186,7 +192,7 @@ fn add_preflight_headers(config: &SecurityConfig, headers: &mut HeaderMap, origi
 
     headers.insert(
         "access-control-allow-methods",
-        HeaderValue::from_static("GET, POST, PUT, DELETE"),
+        HeaderValue::from_static("GET, POST, PUT, PATCH, DELETE"),
     );
     headers.insert(
         "access-control-allow-headers",
@@ -117,15 +117,17 @@ fn add_security_headers(config: &SecurityConfig, headers: &mut HeaderMap) {
         axum::http::HeaderValue::from_static("none"),
     );
 
-    // Cache-Control: Prevent caching of sensitive responses
-    // Note: This is applied to all responses - you might want to be more selective
-    headers.insert(
-        "cache-control",
-        axum::http::HeaderValue::from_static("no-cache, no-store, must-revalidate"),
-    );
+    // Cache-Control: Prevent caching of sensitive responses, unless the
+    // handler chose a policy for a response that is safe to cache
+    if !headers.contains_key("cache-control") {
+        headers.insert(
+            "cache-control",
+            axum::http::HeaderValue::from_static("no-cache, no-store, must-revalidate"),
+        );
 
-    // Pragma: Legacy cache control
-    headers.insert("pragma", axum::http::HeaderValue::from_static("no-cache"));
+        // Pragma: Legacy cache control
+        headers.insert("pragma", axum::http::HeaderValue::from_static("no-cache"));
+    }
 
     // X-DNS-Prefetch-Control: Controls DNS prefetching
     headers.insert(
@@ -222,4 +224,19 @@ mod tests {
         assert!(headers.contains_key("pragma"));
         assert!(headers.contains_key("x-dns-prefetch-control"));
     }
+
+    #[test]
+    fn test_handler_cache_control_is_kept() {
+        let config = SecurityConfig::default();
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "cache-control",
+            axum::http::HeaderValue::from_static("public, max-age=60"),
+        );
+
+        add_security_headers(&config, &mut headers);
+
+        assert_eq!(headers.get("cache-control").unwrap(), "public, max-age=60");
+        assert!(!headers.contains_key("pragma"));
+    }
 }
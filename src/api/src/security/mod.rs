@@ -1,9 +1,14 @@
+pub mod admin;
 pub mod config;
 pub mod cors;
 pub mod headers;
+pub mod policy;
 pub mod rate_limit;
+pub mod rate_limit_store;
 pub mod referer;
 pub mod session;
+pub mod start_token;
+pub mod user_cookie;
 pub mod utils;
 
 #[cfg(test)]
@@ -0,0 +1,543 @@
+use axum::http::Method;
+use std::sync::Arc;
+
+use crate::security::admin::AdminScope;
+
+/// Which rate limit bucket a request counts against
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RateLimitTier {
+    /// Creating users and sessions
+    Session,
+    Read,
+    Write,
+    /// Not rate limited at all
+    Exempt,
+}
+
+/// RoutePolicy is the set of protections the security layers apply to a
+/// route. Each layer looks the policy up for the request it is handling
+/// rather than deciding from the path itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoutePolicy {
+    pub rate_limit: RateLimitTier,
+    /// Reject cross-site requests whose referer isn't an allowed origin
+    pub check_referer: bool,
+    /// Attach a session to the request, issuing a cookie if there isn't one
+    pub session: bool,
+    /// Require an admin bearer token allowed this scope
+    pub admin_scope: Option<AdminScope>,
+}
+
+impl RoutePolicy {
+    /// Read-only requests from the web client
+    pub const READ: Self = Self {
+        rate_limit: RateLimitTier::Read,
+        check_referer: false,
+        session: true,
+        admin_scope: None,
+    };
+
+    /// State-changing requests from the web client
+    pub const WRITE: Self = Self {
+        rate_limit: RateLimitTier::Write,
+        check_referer: true,
+        session: true,
+        admin_scope: None,
+    };
+
+    /// Requests that create a user, limited separately from other writes
+    pub const SESSION: Self = Self {
+        rate_limit: RateLimitTier::Session,
+        ..Self::WRITE
+    };
+
+    /// Requests from other services, which authenticate with a signature
+    /// rather than cookies and send no referer
+    pub const SIGNED: Self = Self {
+        rate_limit: RateLimitTier::Read,
+        check_referer: false,
+        session: false,
+        admin_scope: None,
+    };
+
+    /// Probes from load balancers and uptime checks
+    pub const HEALTH: Self = Self {
+        rate_limit: RateLimitTier::Exempt,
+        check_referer: false,
+        session: false,
+        admin_scope: None,
+    };
+
+    /// Admin requests authenticate with a bearer token rather than cookies,
+    /// so they aren't exposed to cross-site request forgery
+    pub const fn admin_read(scope: AdminScope) -> Self {
+        Self {
+            rate_limit: RateLimitTier::Read,
+            check_referer: false,
+            session: false,
+            admin_scope: Some(scope),
+        }
+    }
+
+    pub const fn admin_write(scope: AdminScope) -> Self {
+        Self {
+            rate_limit: RateLimitTier::Write,
+            ..Self::admin_read(scope)
+        }
+    }
+
+    /// Policy for a request that matches no route in the table
+    pub fn for_method(method: &Method) -> Self {
+        if matches!(
+            *method,
+            Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+        ) {
+            Self::WRITE
+        } else {
+            Self::READ
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct RouteRule {
+    /// None matches every method
+    method: Option<Method>,
+    pattern: &'static str,
+    policy: RoutePolicy,
+}
+
+/// RoutePolicies maps route patterns to the policy for that route. Patterns
+/// use the same syntax as the router: `:name` matches one path segment and a
+/// trailing `*` matches the rest of the path. Rules are checked in order and
+/// the first match wins; requests matching no rule fall back to
+/// `RoutePolicy::for_method`.
+#[derive(Clone, Debug)]
+pub struct RoutePolicies {
+    rules: Arc<Vec<RouteRule>>,
+}
+
+impl RoutePolicies {
+    /// An empty table, where every request gets the fallback policy
+    pub fn empty() -> Self {
+        Self {
+            rules: Arc::new(Vec::new()),
+        }
+    }
+
+    /// Add a rule for one method, checked after every rule added before it
+    pub fn route(self, method: Method, pattern: &'static str, policy: RoutePolicy) -> Self {
+        self.push(Some(method), pattern, policy)
+    }
+
+    /// Add a rule for every method, checked after every rule added before it
+    pub fn any(self, pattern: &'static str, policy: RoutePolicy) -> Self {
+        self.push(None, pattern, policy)
+    }
+
+    fn push(mut self, method: Option<Method>, pattern: &'static str, policy: RoutePolicy) -> Self {
+        Arc::make_mut(&mut self.rules).push(RouteRule {
+            method,
+            pattern,
+            policy,
+        });
+        self
+    }
+
+    /// Look up the policy for a request
+    pub fn policy_for(&self, method: &Method, path: &str) -> RoutePolicy {
+        self.rules
+            .iter()
+            .find(|rule| {
+                rule.method.as_ref().is_none_or(|m| m == method)
+                    && pattern_matches(rule.pattern, path)
+            })
+            .map(|rule| rule.policy)
+            .unwrap_or_else(|| RoutePolicy::for_method(method))
+    }
+}
+
+impl Default for RoutePolicies {
+    /// The policy for every route the API serves
+    fn default() -> Self {
+        Self::empty()
+            .any("/health", RoutePolicy::HEALTH)
+            .any("/api/health", RoutePolicy::HEALTH)
+            .any("/api/ready", RoutePolicy::HEALTH)
+            .route(Method::GET, "/api/game/today", RoutePolicy::READ)
+            .route(Method::GET, "/api/game/date/:date", RoutePolicy::READ)
+            .route(
+                Method::GET,
+                "/api/game/sequence/:sequence_number",
+                RoutePolicy::READ,
+            )
+            .route(Method::GET, "/api/games/archive", RoutePolicy::READ)
+            .route(Method::GET, "/api/scoring-profile/:id", RoutePolicy::READ)
+            .route(Method::GET, "/api/game/:game_id/words", RoutePolicy::READ)
+            .route(
+                Method::GET,
+                "/api/game/:game_id/word-stats",
+                RoutePolicy::READ,
+            )
+            .route(
+                Method::GET,
+                "/api/game/:game_id/solution",
+                RoutePolicy::READ,
+            )
+            .route(Method::GET, "/api/game/:game_id/paths", RoutePolicy::READ)
+            .route(
+                Method::GET,
+                "/api/game/:game_id/preview.png",
+                RoutePolicy::READ,
+            )
+            .route(
+                Method::GET,
+                "/api/game/:game_id/constraints",
+                RoutePolicy::READ,
+            )
+            .route(
+                Method::POST,
+                "/api/game/:game_id/preview-score",
+                RoutePolicy::WRITE,
+            )
+            // Each request searches the board for the best words left, so it
+            // is limited like creating users
+            .route(
+                Method::POST,
+                "/api/game/:game_id/best-finish",
+                RoutePolicy::SESSION,
+            )
+            .route(Method::GET, "/api/word/:word/definition", RoutePolicy::READ)
+            .route(
+                Method::GET,
+                "/api/game/:game_id/word/:word/paths",
+                RoutePolicy::READ,
+            )
+            .route(Method::GET, "/api/game/:game_id/hints", RoutePolicy::READ)
+            .route(Method::GET, "/api/game/:game_id/live", RoutePolicy::READ)
+            .route(Method::POST, "/api/game/:game_id/hints", RoutePolicy::WRITE)
+            .route(
+                Method::POST,
+                "/api/game/:game_id/challenge",
+                RoutePolicy::WRITE,
+            )
+            .route(Method::POST, "/api/game/:game_id/start", RoutePolicy::WRITE)
+            .route(
+                Method::GET,
+                "/api/game/:game_id/speed-leaderboard",
+                RoutePolicy::READ,
+            )
+            // Opening a blitz board starts the player's clock
+            .route(Method::GET, "/api/blitz/date/:date", RoutePolicy::WRITE)
+            .route(Method::GET, "/api/blitz/stats", RoutePolicy::READ)
+            // Registering makes the server send requests, so it is limited
+            // like creating users
+            .route(Method::POST, "/api/webhooks", RoutePolicy::SESSION)
+            .route(Method::DELETE, "/api/webhooks/:id", RoutePolicy::WRITE)
+            // Discord forwards commands here, signed with the application's key
+            .route(
+                Method::POST,
+                "/api/discord/interactions",
+                RoutePolicy::SIGNED,
+            )
+            .route(Method::POST, "/api/discord/link", RoutePolicy::WRITE)
+            .route(Method::POST, "/api/validate", RoutePolicy::WRITE)
+            .route(Method::POST, "/api/user", RoutePolicy::SESSION)
+            .route(Method::GET, "/api/user/sessions", RoutePolicy::READ)
+            .route(Method::GET, "/api/user/:user_id/stats", RoutePolicy::READ)
+            .route(Method::GET, "/api/user/history/export", RoutePolicy::READ)
+            .route(Method::GET, "/api/user/verify", RoutePolicy::READ)
+            .route(Method::GET, "/api/user/:user_id/export", RoutePolicy::READ)
+            .route(Method::DELETE, "/api/user/:user_id", RoutePolicy::WRITE)
+            .route(
+                Method::DELETE,
+                "/api/user/sessions/:session_id",
+                RoutePolicy::WRITE,
+            )
+            .route(Method::GET, "/api/account", RoutePolicy::READ)
+            // Routes that send email or sign in are limited like creating users
+            .route(Method::POST, "/api/account/email", RoutePolicy::SESSION)
+            .route(Method::POST, "/api/account/login/*", RoutePolicy::SESSION)
+            .route(
+                Method::POST,
+                "/api/account/magic-link",
+                RoutePolicy::SESSION,
+            )
+            .route(Method::POST, "/api/account/merge", RoutePolicy::WRITE)
+            .route(Method::POST, "/api/account/passkeys", RoutePolicy::WRITE)
+            .route(
+                Method::POST,
+                "/api/account/passkeys/options",
+                RoutePolicy::WRITE,
+            )
+            .route(Method::GET, "/api/notifications", RoutePolicy::READ)
+            .route(Method::POST, "/api/notifications/read", RoutePolicy::WRITE)
+            .route(
+                Method::GET,
+                "/api/notifications/preferences",
+                RoutePolicy::READ,
+            )
+            .route(
+                Method::PUT,
+                "/api/notifications/preferences",
+                RoutePolicy::WRITE,
+            )
+            .route(Method::GET, "/api/game-entry/:game_id", RoutePolicy::READ)
+            .route(Method::POST, "/api/game-entry/:game_id", RoutePolicy::WRITE)
+            .route(
+                Method::GET,
+                "/api/game-entry/:game_id/share",
+                RoutePolicy::READ,
+            )
+            .route(
+                Method::GET,
+                "/api/game-entry/:game_id/progress",
+                RoutePolicy::READ,
+            )
+            .route(
+                Method::PATCH,
+                "/api/game-entry/:game_id/progress",
+                RoutePolicy::WRITE,
+            )
+            .route(Method::POST, "/api/practice/new", RoutePolicy::WRITE)
+            .route(
+                Method::GET,
+                "/api/practice/recommendations",
+                RoutePolicy::READ,
+            )
+            .route(Method::GET, "/api/practice/:practice_id", RoutePolicy::READ)
+            .route(
+                Method::POST,
+                "/api/practice/:practice_id/submit",
+                RoutePolicy::WRITE,
+            )
+            .route(Method::POST, "/api/custom-game", RoutePolicy::WRITE)
+            .route(Method::GET, "/api/custom-game/:slug", RoutePolicy::READ)
+            .route(
+                Method::POST,
+                "/api/custom-game/:slug/submit",
+                RoutePolicy::WRITE,
+            )
+            .route(
+                Method::GET,
+                "/api/custom-game/:slug/scores",
+                RoutePolicy::READ,
+            )
+            .route(Method::GET, "/api/tournaments", RoutePolicy::READ)
+            .route(
+                Method::GET,
+                "/api/tournaments/:tournament_id",
+                RoutePolicy::READ,
+            )
+            .route(
+                Method::POST,
+                "/api/tournaments/:tournament_id/join",
+                RoutePolicy::WRITE,
+            )
+            .route(
+                Method::GET,
+                "/api/tournaments/:tournament_id/standings",
+                RoutePolicy::READ,
+            )
+            .route(
+                Method::GET,
+                "/api/admin/batches/:batch_id",
+                RoutePolicy::admin_read(AdminScope::Generate),
+            )
+            .any(
+                "/api/admin/batches",
+                RoutePolicy::admin_write(AdminScope::Generate),
+            )
+            .any(
+                "/api/admin/batches/*",
+                RoutePolicy::admin_write(AdminScope::Generate),
+            )
+            .route(
+                Method::POST,
+                "/api/admin/games/:game_id/replace",
+                RoutePolicy::admin_write(AdminScope::Generate),
+            )
+            .route(
+                Method::POST,
+                "/api/admin/tournaments",
+                RoutePolicy::admin_write(AdminScope::Generate),
+            )
+            .route(
+                Method::GET,
+                "/api/admin/input-rejections",
+                RoutePolicy::admin_read(AdminScope::Stats),
+            )
+            .route(
+                Method::GET,
+                "/api/admin/density-report",
+                RoutePolicy::admin_read(AdminScope::Stats),
+            )
+            .route(
+                Method::GET,
+                "/api/admin/tile-fairness",
+                RoutePolicy::admin_read(AdminScope::Stats),
+            )
+            .route(
+                Method::GET,
+                "/api/admin/memory",
+                RoutePolicy::admin_read(AdminScope::Stats),
+            )
+            .route(
+                Method::GET,
+                "/api/games/upcoming",
+                RoutePolicy::admin_read(AdminScope::Generate),
+            )
+            .route(
+                Method::GET,
+                "/metrics",
+                RoutePolicy::admin_read(AdminScope::Stats),
+            )
+            .any(
+                "/api/admin/cache",
+                RoutePolicy::admin_write(AdminScope::Cache),
+            )
+            .any(
+                "/api/admin/cache/games/:game_id",
+                RoutePolicy::admin_write(AdminScope::Cache),
+            )
+            // Everything else, including managing tokens, is superadmin only
+            .route(
+                Method::GET,
+                "/api/admin/*",
+                RoutePolicy::admin_read(AdminScope::Superadmin),
+            )
+            .any(
+                "/api/admin/*",
+                RoutePolicy::admin_write(AdminScope::Superadmin),
+            )
+    }
+}
+
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let mut pattern_segments = pattern.split('/');
+    let mut path_segments = path.split('/');
+
+    loop {
+        match (pattern_segments.next(), path_segments.next()) {
+            (Some("*"), Some(segment)) => return !segment.is_empty(),
+            (Some(expected), Some(segment)) => {
+                let matched = if expected.starts_with(':') {
+                    !segment.is_empty()
+                } else {
+                    expected == segment
+                };
+                if !matched {
+                    return false;
+                }
+            }
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_matches() {
+        assert!(pattern_matches("/api/game/today", "/api/game/today"));
+        assert!(pattern_matches(
+            "/api/game/:game_id/words",
+            "/api/game/abc/words"
+        ));
+        assert!(!pattern_matches(
+            "/api/game/:game_id/words",
+            "/api/game//words"
+        ));
+        assert!(!pattern_matches(
+            "/api/game/:game_id/words",
+            "/api/game/abc"
+        ));
+        assert!(!pattern_matches("/api/game/today", "/api/game/today/extra"));
+        assert!(pattern_matches(
+            "/api/admin/*",
+            "/api/admin/batches/1/publish"
+        ));
+        assert!(!pattern_matches("/api/admin/*", "/api/admin/"));
+        assert!(!pattern_matches("/api/admin/*", "/api/admin"));
+    }
+
+    #[test]
+    fn test_default_policies() {
+        let policies = RoutePolicies::default();
+
+        assert_eq!(
+            policies.policy_for(&Method::GET, "/health"),
+            RoutePolicy::HEALTH
+        );
+        assert_eq!(
+            policies.policy_for(&Method::POST, "/api/user"),
+            RoutePolicy::SESSION
+        );
+        assert_eq!(
+            policies.policy_for(&Method::GET, "/api/game/date/2025-01-01"),
+            RoutePolicy::READ
+        );
+        assert_eq!(
+            policies.policy_for(&Method::POST, "/api/game-entry/abc"),
+            RoutePolicy::WRITE
+        );
+        assert_eq!(
+            policies.policy_for(&Method::GET, "/api/admin/batches/abc"),
+            RoutePolicy::admin_read(AdminScope::Generate)
+        );
+        assert_eq!(
+            policies.policy_for(&Method::DELETE, "/api/admin/batches/abc"),
+            RoutePolicy::admin_write(AdminScope::Generate)
+        );
+        assert_eq!(
+            policies.policy_for(&Method::POST, "/api/admin/batches/abc/publish"),
+            RoutePolicy::admin_write(AdminScope::Generate)
+        );
+        assert_eq!(
+            policies.policy_for(&Method::GET, "/api/admin/input-rejections"),
+            RoutePolicy::admin_read(AdminScope::Stats)
+        );
+        assert_eq!(
+            policies.policy_for(&Method::DELETE, "/api/admin/cache"),
+            RoutePolicy::admin_write(AdminScope::Cache)
+        );
+        assert_eq!(
+            policies.policy_for(&Method::DELETE, "/api/admin/cache/games/abc"),
+            RoutePolicy::admin_write(AdminScope::Cache)
+        );
+        assert_eq!(
+            policies.policy_for(&Method::POST, "/api/admin/tokens"),
+            RoutePolicy::admin_write(AdminScope::Superadmin)
+        );
+
+        // Anything not in the table is protected according to its method
+        assert_eq!(policies.policy_for(&Method::GET, "/"), RoutePolicy::READ);
+        assert_eq!(
+            policies.policy_for(&Method::PUT, "/api/unknown"),
+            RoutePolicy::WRITE
+        );
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let policies = RoutePolicies::empty()
+            .route(
+                Method::POST,
+                "/api/game/:game_id/hints",
+                RoutePolicy::HEALTH,
+            )
+            .any("/api/game/*", RoutePolicy::SESSION);
+
+        assert_eq!(
+            policies.policy_for(&Method::POST, "/api/game/abc/hints"),
+            RoutePolicy::HEALTH
+        );
+        assert_eq!(
+            policies.policy_for(&Method::GET, "/api/game/abc/hints"),
+            RoutePolicy::SESSION
+        );
+    }
+}
@@ -1,62 +1,58 @@
 use axum::{
     extract::ConnectInfo,
-    http::{HeaderMap, StatusCode, Uri},
+    http::{HeaderMap, StatusCode},
     response::Response,
 };
 use std::{
-    collections::HashMap,
     convert::Infallible,
     future::Future,
     net::{IpAddr, SocketAddr},
     pin::Pin,
-    sync::{Arc, RwLock},
+    sync::Arc,
     task::{Context, Poll},
-    time::{Duration, Instant},
 };
 use tower::{Layer, Service};
-use tracing::{debug, warn};
-
-use crate::security::{utils::extract_client_ip, SecurityConfig};
+use tracing::{debug, error, warn};
+
+use crate::logging::LogSampler;
+use crate::security::{
+    policy::{RateLimitTier, RoutePolicies},
+    rate_limit_store::{MemoryRateLimitStore, RateLimitStore},
+    utils::extract_client_ip,
+    SecurityConfig,
+};
 
-// Simplified rate limiter - we'll implement a basic in-memory HashMap-based solution
-type SimpleRateLimiter = Arc<RwLock<HashMap<IpAddr, (Instant, u32)>>>;
+/// Requests within their limit are only worth logging now and then
+static WITHIN_LIMIT_LOGS: LogSampler = LogSampler::every(100);
 
 #[derive(Clone)]
 pub struct RateLimitLayer {
     config: SecurityConfig,
-    session_limiter: SimpleRateLimiter,
-    read_limiter: SimpleRateLimiter,
-    write_limiter: SimpleRateLimiter,
+    policies: RoutePolicies,
+    store: Arc<dyn RateLimitStore>,
 }
 
 impl RateLimitLayer {
+    /// Rate limit with counts kept in memory, so limits are per instance
     pub fn new(config: SecurityConfig) -> Self {
-        let session_limiter = Arc::new(RwLock::new(HashMap::new()));
-        let read_limiter = Arc::new(RwLock::new(HashMap::new()));
-        let write_limiter = Arc::new(RwLock::new(HashMap::new()));
-
-        // Spawn cleanup task once
-        let session_limiter_clone = session_limiter.clone();
-        let read_limiter_clone = read_limiter.clone();
-        let write_limiter_clone = write_limiter.clone();
-
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(Duration::from_secs(300)); // Cleanup every 5 minutes
-            loop {
-                interval.tick().await;
-                cleanup_old_entries_simple(&session_limiter_clone).await;
-                cleanup_old_entries_simple(&read_limiter_clone).await;
-                cleanup_old_entries_simple(&write_limiter_clone).await;
-            }
-        });
+        Self::with_store(config, Arc::new(MemoryRateLimitStore::new()))
+    }
 
+    /// Rate limit with counts kept in the given store. Instances sharing a
+    /// store share their limits.
+    pub fn with_store(config: SecurityConfig, store: Arc<dyn RateLimitStore>) -> Self {
         Self {
             config,
-            session_limiter,
-            read_limiter,
-            write_limiter,
+            policies: RoutePolicies::default(),
+            store,
         }
     }
+
+    /// Choose each route's rate limit from these policies
+    pub fn with_policies(mut self, policies: RoutePolicies) -> Self {
+        self.policies = policies;
+        self
+    }
 }
 
 impl<S> Layer<S> for RateLimitLayer {
@@ -66,9 +62,8 @@ impl<S> Layer<S> for RateLimitLayer {
         RateLimitMiddleware {
             inner,
             config: self.config.clone(),
-            session_limiter: self.session_limiter.clone(),
-            read_limiter: self.read_limiter.clone(),
-            write_limiter: self.write_limiter.clone(),
+            policies: self.policies.clone(),
+            store: self.store.clone(),
         }
     }
 }
@@ -77,9 +72,8 @@ impl<S> Layer<S> for RateLimitLayer {
 pub struct RateLimitMiddleware<S> {
     inner: S,
     config: SecurityConfig,
-    session_limiter: SimpleRateLimiter,
-    read_limiter: SimpleRateLimiter,
-    write_limiter: SimpleRateLimiter,
+    policies: RoutePolicies,
+    store: Arc<dyn RateLimitStore>,
 }
 
 impl<S> Service<axum::http::Request<axum::body::Body>> for RateLimitMiddleware<S>
@@ -100,9 +94,8 @@ where
 
     fn call(&mut self, request: axum::http::Request<axum::body::Body>) -> Self::Future {
         let config = self.config.clone();
-        let session_limiter = self.session_limiter.clone();
-        let read_limiter = self.read_limiter.clone();
-        let write_limiter = self.write_limiter.clone();
+        let policies = self.policies.clone();
+        let store = self.store.clone();
         let mut inner = self.inner.clone();
 
         Box::pin(async move {
@@ -120,41 +113,33 @@ where
                 })
                 .unwrap_or_else(|| IpAddr::from([127, 0, 0, 1])); // Fallback to localhost
 
-            // Determine rate limit type based on endpoint
-            let (limiter, limit) = match determine_endpoint_type(uri, method.as_str()) {
-                EndpointType::Session => (session_limiter, config.rate_limit_session),
-                EndpointType::Read => (read_limiter, config.rate_limit_read),
-                EndpointType::Write => (write_limiter, config.rate_limit_write),
-                EndpointType::Health => {
+            // Determine rate limit type based on the route's policy
+            let policy = policies.policy_for(method, uri.path());
+            let (bucket, limit) = match policy.rate_limit {
+                RateLimitTier::Session => ("session", config.rate_limit_session),
+                RateLimitTier::Read => ("read", config.rate_limit_read),
+                RateLimitTier::Write => ("write", config.rate_limit_write),
+                RateLimitTier::Exempt => {
                     // Skip rate limiting for health checks
                     let response = inner.call(request).await?;
                     return Ok(response);
                 }
             };
 
-            // Check rate limit
-            let now = Instant::now();
-            let allowed = {
-                let mut limiter_guard = limiter.write().unwrap();
-                let entry = limiter_guard.entry(client_ip).or_insert((now, 0));
-
-                // Reset counter if window has passed
-                if now.duration_since(entry.0) >= config.rate_limit_window {
-                    entry.0 = now;
-                    entry.1 = 0;
-                }
-
-                // Check if under limit
-                if entry.1 < limit {
-                    entry.1 += 1;
+            // Check rate limit. If the store can't be reached, let the
+            // request through rather than take the site down with it.
+            let allowed = match store.hit(bucket, client_ip, config.rate_limit_window).await {
+                Ok(count) => count <= limit,
+                Err(e) => {
+                    error!("Rate limit store failed, allowing request: {}", e);
                     true
-                } else {
-                    false
                 }
             };
 
             if allowed {
-                debug!("Rate limit OK for IP: {}", client_ip);
+                if WITHIN_LIMIT_LOGS.sample() {
+                    debug!("Rate limit OK for IP: {}", client_ip);
+                }
                 let mut response = inner.call(request).await?;
                 add_rate_limit_headers_simple(&config, response.headers_mut(), limit);
                 Ok(response)
@@ -175,36 +160,6 @@ where
     }
 }
 
-#[derive(Debug, PartialEq)]
-enum EndpointType {
-    Session,
-    Read,
-    Write,
-    Health,
-}
-
-fn determine_endpoint_type(uri: &Uri, method: &str) -> EndpointType {
-    let path = uri.path();
-
-    // Health check endpoint
-    if path == "/health" || path == "/api/health" {
-        return EndpointType::Health;
-    }
-
-    // Session endpoints
-    if path == "/api/user" && method == "POST" {
-        return EndpointType::Session;
-    }
-
-    // Write operations - all POST/PUT/DELETE are write operations
-    if matches!(method, "POST" | "PUT" | "DELETE") {
-        return EndpointType::Write;
-    }
-
-    // Default to read operations
-    EndpointType::Read
-}
-
 fn add_rate_limit_headers_simple(config: &SecurityConfig, headers: &mut HeaderMap, limit: u32) {
     let reset_time = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -226,28 +181,6 @@ fn add_rate_limit_headers_simple(config: &SecurityConfig, headers: &mut HeaderMa
     );
 }
 
-async fn cleanup_old_entries_simple(limiter: &SimpleRateLimiter) {
-    let cutoff = Instant::now() - Duration::from_secs(3600); // Remove entries older than 1 hour
-
-    let mut to_remove = Vec::new();
-    {
-        let reader = limiter.read().unwrap();
-        for (ip, (last_seen, _count)) in reader.iter() {
-            if *last_seen < cutoff {
-                to_remove.push(*ip);
-            }
-        }
-    }
-
-    if !to_remove.is_empty() {
-        let mut writer = limiter.write().unwrap();
-        for ip in to_remove {
-            writer.remove(&ip);
-        }
-        debug!("Cleaned up {} old rate limit entries", writer.len());
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -262,31 +195,23 @@ mod tests {
     }
 
     #[test]
-    fn test_determine_endpoint_type() {
-        assert_eq!(
-            determine_endpoint_type(&Uri::from_static("/api/user"), "POST"),
-            EndpointType::Session
-        );
-        assert_eq!(
-            determine_endpoint_type(&Uri::from_static("/api/submit"), "POST"),
-            EndpointType::Write
-        );
-        assert_eq!(
-            determine_endpoint_type(&Uri::from_static("/api/validate"), "POST"),
-            EndpointType::Write
-        );
-        assert_eq!(
-            determine_endpoint_type(&Uri::from_static("/test"), "POST"),
-            EndpointType::Write
-        );
+    fn test_rate_limit_tiers() {
+        let policies = RoutePolicies::default();
+        let tier = |method: Method, path: &str| policies.policy_for(&method, path).rate_limit;
+
+        assert_eq!(tier(Method::POST, "/api/user"), RateLimitTier::Session);
+        assert_eq!(tier(Method::POST, "/api/submit"), RateLimitTier::Write);
+        assert_eq!(tier(Method::POST, "/api/validate"), RateLimitTier::Write);
         assert_eq!(
-            determine_endpoint_type(&Uri::from_static("/api/game/date/2025-01-01"), "GET"),
-            EndpointType::Read
+            tier(Method::POST, "/api/game/abc/best-finish"),
+            RateLimitTier::Session
         );
+        assert_eq!(tier(Method::POST, "/test"), RateLimitTier::Write);
         assert_eq!(
-            determine_endpoint_type(&Uri::from_static("/health"), "GET"),
-            EndpointType::Health
+            tier(Method::GET, "/api/game/date/2025-01-01"),
+            RateLimitTier::Read
         );
+        assert_eq!(tier(Method::GET, "/health"), RateLimitTier::Exempt);
     }
 
     #[tokio::test]
@@ -330,4 +255,40 @@ mod tests {
         let response = service.ready().await.unwrap().call(request).await.unwrap();
         assert_eq!(response.status(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn test_rate_limit_rejects_over_limit() {
+        let config = SecurityConfig {
+            rate_limit_read: 1,
+            ..Default::default()
+        };
+
+        let layer = RateLimitLayer::new(config);
+        let mut service = layer.layer(tower::service_fn(|_| async { Ok(test_service().await) }));
+
+        let request = || {
+            Request::builder()
+                .method(Method::GET)
+                .uri("/api/game/date/2025-01-01")
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        let response = service
+            .ready()
+            .await
+            .unwrap()
+            .call(request())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = service
+            .ready()
+            .await
+            .unwrap()
+            .call(request())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
 }
@@ -0,0 +1,205 @@
+use anyhow::Result;
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc, RwLock,
+    },
+    time::{Duration, Instant},
+};
+use tracing::{debug, warn};
+
+use crate::db::Repository;
+
+/// RateLimitStore keeps the request counts the rate limiter checks. Counts
+/// are per bucket (session, read or write) and client, over a window.
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Count a request, returning how many requests the client has made in
+    /// the bucket during the current window, including this one
+    async fn hit(&self, bucket: &'static str, client: IpAddr, window: Duration) -> Result<u32>;
+}
+
+type Counters = Arc<RwLock<HashMap<(&'static str, IpAddr), (Instant, u32)>>>;
+
+/// MemoryRateLimitStore counts requests in this process only, so each server
+/// instance enforces its own limits
+#[derive(Clone)]
+pub struct MemoryRateLimitStore {
+    counters: Counters,
+}
+
+impl MemoryRateLimitStore {
+    pub fn new() -> Self {
+        let counters: Counters = Arc::new(RwLock::new(HashMap::new()));
+
+        // Spawn cleanup task once
+        let counters_clone = counters.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(300)); // Cleanup every 5 minutes
+            loop {
+                interval.tick().await;
+                cleanup_old_entries(&counters_clone);
+            }
+        });
+
+        Self { counters }
+    }
+}
+
+impl Default for MemoryRateLimitStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryRateLimitStore {
+    async fn hit(&self, bucket: &'static str, client: IpAddr, window: Duration) -> Result<u32> {
+        let now = Instant::now();
+        let mut counters = self.counters.write().unwrap();
+        let entry = counters.entry((bucket, client)).or_insert((now, 0));
+
+        // Reset counter if window has passed
+        if now.duration_since(entry.0) >= window {
+            entry.0 = now;
+            entry.1 = 0;
+        }
+
+        entry.1 = entry.1.saturating_add(1);
+        Ok(entry.1)
+    }
+}
+
+fn cleanup_old_entries(counters: &Counters) {
+    let cutoff = Instant::now() - Duration::from_secs(3600); // Remove entries older than 1 hour
+
+    let mut writer = counters.write().unwrap();
+    writer.retain(|_, (window_start, _)| *window_start >= cutoff);
+    debug!("Rate limit entries after cleanup: {}", writer.len());
+}
+
+/// How often the database store deletes windows that have ended
+const WINDOW_CLEANUP_INTERVAL: Duration = Duration::from_secs(300);
+
+/// RepositoryRateLimitStore counts requests in the database, so every server
+/// instance sharing it enforces the same limits. Windows are fixed and
+/// aligned to the epoch, so all instances agree on when one starts.
+pub struct RepositoryRateLimitStore<R: Repository> {
+    repository: R,
+    /// Unix time of the last cleanup of ended windows
+    last_cleanup: AtomicI64,
+}
+
+impl<R: Repository> RepositoryRateLimitStore<R> {
+    pub fn new(repository: R) -> Self {
+        Self {
+            repository,
+            last_cleanup: AtomicI64::new(0),
+        }
+    }
+
+    async fn cleanup_if_due(&self, now: DateTime<Utc>, window: Duration) {
+        let last = self.last_cleanup.load(Ordering::Relaxed);
+        let due = now.timestamp() - last >= WINDOW_CLEANUP_INTERVAL.as_secs() as i64;
+        // Only one request does the cleanup when it comes due
+        if !due
+            || self
+                .last_cleanup
+                .compare_exchange(last, now.timestamp(), Ordering::Relaxed, Ordering::Relaxed)
+                .is_err()
+        {
+            return;
+        }
+
+        let Ok(window) = chrono::Duration::from_std(window) else {
+            return;
+        };
+        match self
+            .repository
+            .delete_rate_limit_windows_before(now - window)
+            .await
+        {
+            Ok(deleted) => debug!("Deleted {} ended rate limit windows", deleted),
+            Err(e) => warn!("Failed to delete ended rate limit windows: {}", e),
+        }
+    }
+}
+
+#[async_trait]
+impl<R: Repository + Send + Sync> RateLimitStore for RepositoryRateLimitStore<R> {
+    async fn hit(&self, bucket: &'static str, client: IpAddr, window: Duration) -> Result<u32> {
+        let now = Utc::now();
+        let window_start = window_start(now, window);
+        let count = self
+            .repository
+            .increment_rate_limit_window(&format!("{bucket}:{client}"), window_start)
+            .await?;
+
+        self.cleanup_if_due(now, window).await;
+
+        Ok(count.max(0) as u32)
+    }
+}
+
+/// Start of the fixed window containing `now`
+fn window_start(now: DateTime<Utc>, window: Duration) -> DateTime<Utc> {
+    let window_secs = window.as_secs().max(1) as i64;
+    let start = now.timestamp() - now.timestamp().rem_euclid(window_secs);
+    DateTime::from_timestamp(start, 0).unwrap_or(now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_start_is_aligned() {
+        let now = DateTime::parse_from_rfc3339("2025-06-01T12:34:56Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            window_start(now, Duration::from_secs(60)).to_rfc3339(),
+            "2025-06-01T12:34:00+00:00"
+        );
+        assert_eq!(
+            window_start(now, Duration::from_secs(3600)).to_rfc3339(),
+            "2025-06-01T12:00:00+00:00"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_memory_store_counts_per_bucket_and_client() {
+        let store = MemoryRateLimitStore::new();
+        let client = IpAddr::from([10, 0, 0, 1]);
+        let window = Duration::from_secs(60);
+
+        assert_eq!(store.hit("read", client, window).await.unwrap(), 1);
+        assert_eq!(store.hit("read", client, window).await.unwrap(), 2);
+        assert_eq!(store.hit("write", client, window).await.unwrap(), 1);
+        assert_eq!(
+            store
+                .hit("read", IpAddr::from([10, 0, 0, 2]), window)
+                .await
+                .unwrap(),
+            1
+        );
+    }
+
+    #[cfg(feature = "database-tests")]
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_repository_store_is_shared_between_instances(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let repository = crate::db::SqliteRepository::new(pool);
+        let first = RepositoryRateLimitStore::new(repository.clone());
+        let second = RepositoryRateLimitStore::new(repository);
+        let client = IpAddr::from([10, 0, 0, 1]);
+        let window = Duration::from_secs(3600);
+
+        assert_eq!(first.hit("write", client, window).await.unwrap(), 1);
+        assert_eq!(second.hit("write", client, window).await.unwrap(), 2);
+        assert_eq!(second.hit("read", client, window).await.unwrap(), 1);
+    }
+}
@@ -1,5 +1,5 @@
 use axum::{
-    http::{HeaderMap, Method, StatusCode},
+    http::{HeaderMap, StatusCode},
     response::Response,
 };
 use std::convert::Infallible;
@@ -9,16 +9,30 @@ use std::task::{Context, Poll};
 use tower::{Layer, Service};
 use tracing::{debug, warn};
 
-use crate::security::{utils::is_origin_allowed, SecurityConfig};
+use crate::logging::{without_query, LogSampler};
+use crate::security::{policy::RoutePolicies, utils::is_origin_allowed, SecurityConfig};
+
+/// Sampled, as writes from the frontend all pass
+static VALID_REFERER_LOGS: LogSampler = LogSampler::every(100);
 
 #[derive(Clone)]
 pub struct RefererLayer {
     config: SecurityConfig,
+    policies: RoutePolicies,
 }
 
 impl RefererLayer {
     pub fn new(config: SecurityConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            policies: RoutePolicies::default(),
+        }
+    }
+
+    /// Choose which routes check the referer from these policies
+    pub fn with_policies(mut self, policies: RoutePolicies) -> Self {
+        self.policies = policies;
+        self
     }
 }
 
@@ -29,6 +43,7 @@ impl<S> Layer<S> for RefererLayer {
         RefererMiddleware {
             inner,
             config: self.config.clone(),
+            policies: self.policies.clone(),
         }
     }
 }
@@ -37,6 +52,7 @@ impl<S> Layer<S> for RefererLayer {
 pub struct RefererMiddleware<S> {
     inner: S,
     config: SecurityConfig,
+    policies: RoutePolicies,
 }
 
 impl<S> Service<axum::http::Request<axum::body::Body>> for RefererMiddleware<S>
@@ -57,6 +73,7 @@ where
 
     fn call(&mut self, request: axum::http::Request<axum::body::Body>) -> Self::Future {
         let config = self.config.clone();
+        let policies = self.policies.clone();
         let mut inner = self.inner.clone();
 
         Box::pin(async move {
@@ -64,8 +81,8 @@ where
             let headers = request.headers().clone();
             let uri = request.uri().clone();
 
-            // Only check referer for state-changing operations
-            if matches!(method, Method::POST | Method::PUT | Method::DELETE) {
+            // Only check referer where the route's policy asks for it
+            if policies.policy_for(&method, uri.path()).check_referer {
                 if let Err(response) = validate_referer(&config, &headers, &uri) {
                     return Ok(*response);
                 }
@@ -85,12 +102,7 @@ fn validate_referer(
 ) -> Result<(), Box<Response>> {
     let referer = headers.get("referer").and_then(|v| v.to_str().ok());
 
-    // Skip referer validation for specific endpoints if configured
     let path = uri.path();
-    if should_skip_referer_check(path) {
-        debug!("Skipping referer check for path: {}", path);
-        return Ok(());
-    }
 
     match referer {
         Some(referer_value) => {
@@ -115,12 +127,17 @@ fn validate_referer(
                     referer_origin
                 };
                 if is_origin_allowed(&referer_origin, &config.allowed_origins) {
-                    debug!("Referer validation passed for: {}", referer_value);
+                    if VALID_REFERER_LOGS.sample() {
+                        debug!(
+                            "Referer validation passed for: {}",
+                            without_query(referer_value)
+                        );
+                    }
                     Ok(())
                 } else {
                     warn!(
                         "Referer validation failed - invalid referer: {}",
-                        referer_value
+                        without_query(referer_value)
                     );
                     log_suspicious_request(headers, uri, "Invalid referer");
                     Err(Box::new(create_referer_error_response("Invalid referer")))
@@ -128,7 +145,7 @@ fn validate_referer(
             } else {
                 warn!(
                     "Referer validation failed - malformed referer: {}",
-                    referer_value
+                    without_query(referer_value)
                 );
                 log_suspicious_request(headers, uri, "Malformed referer");
                 Err(Box::new(create_referer_error_response("Malformed referer")))
@@ -147,14 +164,6 @@ fn validate_referer(
     }
 }
 
-fn should_skip_referer_check(path: &str) -> bool {
-    // Skip referer check for API endpoints that might be called directly
-    matches!(
-        path,
-        "/health" | "/api/health" | "/metrics" | "/api/metrics"
-    )
-}
-
 fn log_suspicious_request(headers: &HeaderMap, uri: &axum::http::Uri, reason: &str) {
     let user_agent = headers
         .get("user-agent")
@@ -1,24 +1,81 @@
-use axum::response::Response;
+use axum::{
+    http::{header, StatusCode},
+    response::Response,
+};
 use base64::{engine::general_purpose, Engine as _};
-use ring::rand::{SecureRandom, SystemRandom};
+use moka::future::Cache;
 use std::convert::Infallible;
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Duration;
 use tower::{Layer, Service};
 use tower_cookies::{Cookie, CookieManagerLayer, Cookies};
-use tracing::debug;
+use tracing::{debug, warn};
+
+use crate::logging::LogSampler;
+use crate::security::{
+    policy::RoutePolicies,
+    utils::{generate_token, hash_token},
+    SecurityConfig,
+};
+
+/// RevokedSessions holds the hashes of sessions users have revoked. It is
+/// shared between the session layer, which rejects revoked sessions, and the
+/// handlers that revoke them. Entries only need to outlive the session cookie.
+#[derive(Clone)]
+pub struct RevokedSessions {
+    sessions: Cache<String, ()>,
+}
 
-use crate::security::SecurityConfig;
+impl RevokedSessions {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            sessions: Cache::builder().time_to_live(ttl).build(),
+        }
+    }
+
+    pub async fn revoke(&self, session_hash: String) {
+        self.sessions.insert(session_hash, ()).await;
+    }
+
+    pub fn is_revoked(&self, session_hash: &str) -> bool {
+        self.sessions.contains_key(session_hash)
+    }
+}
+
+impl Default for RevokedSessions {
+    fn default() -> Self {
+        Self::new(SecurityConfig::default().cookie_max_age)
+    }
+}
 
 #[derive(Clone)]
 pub struct SessionLayer {
     config: SecurityConfig,
+    policies: RoutePolicies,
+    revoked: Option<RevokedSessions>,
 }
 
 impl SessionLayer {
     pub fn new(config: SecurityConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            policies: RoutePolicies::default(),
+            revoked: None,
+        }
+    }
+
+    /// Choose which routes get a session from these policies
+    pub fn with_policies(mut self, policies: RoutePolicies) -> Self {
+        self.policies = policies;
+        self
+    }
+
+    /// Reject requests whose session has been revoked
+    pub fn with_revocations(mut self, revoked: RevokedSessions) -> Self {
+        self.revoked = Some(revoked);
+        self
     }
 }
 
@@ -29,6 +86,8 @@ impl<S> Layer<S> for SessionLayer {
         SessionMiddleware {
             inner,
             config: self.config.clone(),
+            policies: self.policies.clone(),
+            revoked: self.revoked.clone(),
         }
     }
 }
@@ -37,6 +96,8 @@ impl<S> Layer<S> for SessionLayer {
 pub struct SessionMiddleware<S> {
     inner: S,
     config: SecurityConfig,
+    policies: RoutePolicies,
+    revoked: Option<RevokedSessions>,
 }
 
 impl<S> Service<axum::http::Request<axum::body::Body>> for SessionMiddleware<S>
@@ -57,9 +118,18 @@ where
 
     fn call(&mut self, mut request: axum::http::Request<axum::body::Body>) -> Self::Future {
         let config = self.config.clone();
+        let revoked = self.revoked.clone();
         let mut inner = self.inner.clone();
+        let needs_session = self
+            .policies
+            .policy_for(request.method(), request.uri().path())
+            .session;
 
         Box::pin(async move {
+            if !needs_session {
+                return inner.call(request).await;
+            }
+
             // Extract cookies from request
             let cookies = request.extensions().get::<Cookies>().cloned();
             let session_id = extract_session_id(&cookies);
@@ -67,7 +137,9 @@ where
             let (should_create_session, new_session_id) = match session_id {
                 Some(id) => {
                     if is_valid_session_id(&id) {
-                        debug!("Valid session found: {}", mask_session_id(&id));
+                        if VALID_SESSION_LOGS.sample() {
+                            debug!("Valid session found: {}", mask_session_id(&id));
+                        }
                         (false, id)
                     } else {
                         debug!("Invalid session ID, creating new session");
@@ -80,9 +152,30 @@ where
                 }
             };
 
+            let session_hash = hash_token(&new_session_id);
+            if revoked.is_some_and(|revoked| revoked.is_revoked(&session_hash)) {
+                warn!(
+                    "Rejected revoked session: {}",
+                    mask_session_id(&new_session_id)
+                );
+                // Drop the cookie so the client starts over with a fresh session
+                if let Some(cookies) = cookies {
+                    cookies.remove(Cookie::build(("session_id", "")).path("/api").build());
+                }
+                return Ok(create_revoked_session_response());
+            }
+
+            let user_agent_hash = request
+                .headers()
+                .get(header::USER_AGENT)
+                .and_then(|value| value.to_str().ok())
+                .map(hash_token);
+
             // Add session ID to request extensions for use by handlers
             request.extensions_mut().insert(SessionInfo {
                 session_id: new_session_id.clone(),
+                session_hash,
+                user_agent_hash,
                 is_new: should_create_session,
             });
 
@@ -106,10 +199,24 @@ where
 pub struct SessionInfo {
     #[allow(dead_code)]
     pub session_id: String,
-    #[allow(dead_code)]
+    /// Hash of the session id, safe to store
+    pub session_hash: String,
+    /// Hash of the User-Agent header, to tell a user's devices apart without storing it
+    pub user_agent_hash: Option<String>,
+    /// Created for this request, so the client hasn't sent its cookie before
     pub is_new: bool,
 }
 
+fn create_revoked_session_response() -> Response {
+    Response::builder()
+        .status(StatusCode::UNAUTHORIZED)
+        .header("content-type", "application/json")
+        .body(axum::body::Body::from(
+            r#"{"error": "Session revoked", "message": "This session was signed out"}"#,
+        ))
+        .unwrap()
+}
+
 fn extract_session_id(cookies: &Option<Cookies>) -> Option<String> {
     cookies
         .as_ref()?
@@ -118,11 +225,7 @@ fn extract_session_id(cookies: &Option<Cookies>) -> Option<String> {
 }
 
 fn generate_session_id() -> String {
-    let rng = SystemRandom::new();
-    let mut bytes = [0u8; 32]; // 256 bits of entropy
-    rng.fill(&mut bytes)
-        .expect("Failed to generate random bytes");
-    general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+    generate_token()
 }
 
 fn is_valid_session_id(session_id: &str) -> bool {
@@ -153,6 +256,9 @@ fn set_session_cookie(config: &SecurityConfig, cookies: &Cookies, session_id: &s
     cookies.add(cookie);
 }
 
+/// Most requests carry a valid session; logging each would drown out the rest
+static VALID_SESSION_LOGS: LogSampler = LogSampler::every(100);
+
 fn mask_session_id(session_id: &str) -> String {
     if session_id.len() > 8 {
         format!(
@@ -239,4 +345,54 @@ mod tests {
         let response = service_with_cookies.oneshot(request).await.unwrap();
         assert_eq!(response.status(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn test_session_middleware_rejects_revoked_session() {
+        let session_id = generate_session_id();
+        let revoked = RevokedSessions::default();
+        revoked.revoke(hash_token(&session_id)).await;
+
+        let service_with_cookies = tower::ServiceBuilder::new()
+            .layer(cookie_layer())
+            .layer(SessionLayer::new(SecurityConfig::default()).with_revocations(revoked))
+            .service(tower::service_fn(|_req: Request<axum::body::Body>| async {
+                Ok(test_service().await)
+            }));
+
+        let request = Request::builder()
+            .header("cookie", format!("session_id={session_id}"))
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = service_with_cookies.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Other sessions are unaffected
+        let request = Request::builder()
+            .header("cookie", format!("session_id={}", generate_session_id()))
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = service_with_cookies.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_session_middleware_skips_routes_without_sessions() {
+        let service_with_cookies = tower::ServiceBuilder::new()
+            .layer(cookie_layer())
+            .layer(SessionLayer::new(SecurityConfig::default()))
+            .service(tower::service_fn(
+                |req: Request<axum::body::Body>| async move {
+                    assert!(req.extensions().get::<SessionInfo>().is_none());
+                    Ok(test_service().await)
+                },
+            ));
+
+        let request = Request::builder()
+            .uri("/health")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = service_with_cookies.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get("set-cookie").is_none());
+    }
 }
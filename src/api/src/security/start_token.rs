@@ -0,0 +1,168 @@
+use base64::{engine::general_purpose, Engine as _};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::env;
+use tracing::warn;
+
+/// What a start token binds a submission to: the game the player started,
+/// its date and the session they started it in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StartClaims {
+    pub game_id: String,
+    pub date: String, // YYYY-MM-DD format
+    pub session_hash: Option<String>,
+}
+
+/// Why a submission doesn't match the game its start token was issued for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartMismatch {
+    /// The token was started on a different game or date
+    WrongGame,
+    /// The token was issued to a different session
+    WrongSession,
+    /// The token can't be read or wasn't signed with the current key, e.g.
+    /// it was issued before the server restarted without START_TOKEN_SECRET
+    Unverifiable,
+}
+
+/// StartTokens signs and checks the tokens handed out when a player starts a
+/// game. Without START_TOKEN_SECRET the key is random, so tokens only verify
+/// on the process that issued them.
+#[derive(Clone)]
+pub struct StartTokens {
+    key: hmac::Key,
+}
+
+impl Default for StartTokens {
+    fn default() -> Self {
+        let mut secret = [0u8; 32];
+        SystemRandom::new()
+            .fill(&mut secret)
+            .expect("Failed to generate random bytes");
+        Self::new(&secret)
+    }
+}
+
+impl StartTokens {
+    pub fn new(secret: &[u8]) -> Self {
+        Self {
+            key: hmac::Key::new(hmac::HMAC_SHA256, secret),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        match env::var("START_TOKEN_SECRET") {
+            Ok(secret) if !secret.is_empty() => Self::new(secret.as_bytes()),
+            _ => {
+                warn!("START_TOKEN_SECRET not set; start tokens won't survive a restart");
+                Self::default()
+            }
+        }
+    }
+
+    /// sign returns the claims and their signature, each base64url encoded
+    /// and joined by a dot
+    pub fn sign(&self, claims: &StartClaims) -> String {
+        let payload = general_purpose::URL_SAFE_NO_PAD
+            .encode(serde_json::to_vec(claims).expect("claims always serialize"));
+        let tag = hmac::sign(&self.key, payload.as_bytes());
+        format!(
+            "{payload}.{}",
+            general_purpose::URL_SAFE_NO_PAD.encode(tag.as_ref())
+        )
+    }
+
+    /// check verifies `token` and that it was issued for this game, date and
+    /// session. Sessions are only compared when both the token and the
+    /// caller have one, since a client without cookies starts a new session
+    /// on every request.
+    pub fn check(
+        &self,
+        token: &str,
+        game_id: &str,
+        date: &str,
+        session_hash: Option<&str>,
+    ) -> Result<StartClaims, StartMismatch> {
+        let claims = self.verify(token).ok_or(StartMismatch::Unverifiable)?;
+        if claims.game_id != game_id || claims.date != date {
+            return Err(StartMismatch::WrongGame);
+        }
+        if let (Some(issued_to), Some(session_hash)) = (&claims.session_hash, session_hash) {
+            if issued_to != session_hash {
+                return Err(StartMismatch::WrongSession);
+            }
+        }
+        Ok(claims)
+    }
+
+    fn verify(&self, token: &str) -> Option<StartClaims> {
+        let (payload, tag) = token.split_once('.')?;
+        let tag = general_purpose::URL_SAFE_NO_PAD.decode(tag).ok()?;
+        hmac::verify(&self.key, payload.as_bytes(), &tag).ok()?;
+        let claims = general_purpose::URL_SAFE_NO_PAD.decode(payload).ok()?;
+        serde_json::from_slice(&claims).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(game_id: &str, date: &str) -> StartClaims {
+        StartClaims {
+            game_id: game_id.to_string(),
+            date: date.to_string(),
+            session_hash: Some("session".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_tokens_bind_game_date_and_session() {
+        let tokens = StartTokens::new(b"secret");
+        let token = tokens.sign(&claims("yesterday", "2025-06-07"));
+
+        assert_eq!(
+            tokens.check(&token, "yesterday", "2025-06-07", Some("session")),
+            Ok(claims("yesterday", "2025-06-07"))
+        );
+        assert_eq!(
+            tokens.check(&token, "today", "2025-06-08", Some("session")),
+            Err(StartMismatch::WrongGame)
+        );
+        assert_eq!(
+            tokens.check(&token, "yesterday", "2025-06-07", Some("other")),
+            Err(StartMismatch::WrongSession)
+        );
+        assert!(tokens
+            .check(&token, "yesterday", "2025-06-07", None)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_tampered_or_foreign_tokens_are_unverifiable() {
+        let tokens = StartTokens::new(b"secret");
+        let token = tokens.sign(&claims("yesterday", "2025-06-07"));
+
+        // Swapping in today's claims breaks the signature
+        let (_, tag) = token.split_once('.').unwrap();
+        let today = tokens.sign(&claims("today", "2025-06-08"));
+        let (payload, _) = today.split_once('.').unwrap();
+        let forged = format!("{payload}.{tag}");
+        assert_eq!(
+            tokens.check(&forged, "today", "2025-06-08", Some("session")),
+            Err(StartMismatch::Unverifiable)
+        );
+
+        let other_key = StartTokens::new(b"another secret");
+        assert_eq!(
+            other_key.check(&token, "yesterday", "2025-06-07", Some("session")),
+            Err(StartMismatch::Unverifiable)
+        );
+        assert_eq!(
+            tokens.check("not a token", "yesterday", "2025-06-07", Some("session")),
+            Err(StartMismatch::Unverifiable)
+        );
+    }
+}
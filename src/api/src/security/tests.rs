@@ -1,7 +1,7 @@
 #[cfg(test)]
 #[cfg(feature = "integration-tests")]
 mod integration_tests {
-    use crate::security::SecurityConfig;
+    use crate::security::{config::RateLimitStoreKind, SecurityConfig};
     use axum::{
         body::Body,
         http::{Method, Request, StatusCode},
@@ -18,11 +18,17 @@ mod integration_tests {
             rate_limit_read: 10,
             rate_limit_write: 3,
             rate_limit_window: Duration::from_secs(60),
+            rate_limit_store: RateLimitStoreKind::Memory,
             cookie_max_age: Duration::from_secs(3600),
             request_timeout: Duration::from_secs(30),
             max_request_size: 1024,
+            header_read_timeout: Duration::from_secs(10),
+            idle_connection_timeout: Duration::from_secs(60),
+            max_connections: 16,
+            max_connections_per_ip: 4,
             strict_referer: true,
             hsts_max_age: 31536000,
+            admin_token: None,
         }
     }
 
@@ -0,0 +1,252 @@
+use axum::{
+    async_trait,
+    extract::{FromRef, FromRequestParts},
+    http::request::Parts,
+};
+use base64::{engine::general_purpose, Engine as _};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::env;
+use std::time::Duration;
+use tower_cookies::{Cookie, Cookies};
+use tracing::warn;
+
+use crate::db::models::DbUser;
+use crate::security::SecurityConfig;
+
+/// Name of the cookie that carries the signed user identity
+pub const USER_COOKIE: &str = "pathfinder_user";
+
+/// The user a signed cookie vouches for. The cookie token is kept so a
+/// cookie stops working if the user's token changes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedUser {
+    pub user_id: String,
+    pub cookie_token: String,
+}
+
+/// UserCookies signs and checks the cookie that identifies a user, and
+/// decides whether user_id and cookie_token sent in request bodies and query
+/// strings are still accepted. Without USER_COOKIE_SECRET the key is random,
+/// so cookies only verify on the process that issued them.
+#[derive(Clone)]
+pub struct UserCookies {
+    key: hmac::Key,
+    max_age: Duration,
+    /// Accept user_id and cookie_token from the request when there is no
+    /// signed cookie, for clients that predate it
+    legacy_credentials: bool,
+}
+
+impl Default for UserCookies {
+    fn default() -> Self {
+        let mut secret = [0u8; 32];
+        SystemRandom::new()
+            .fill(&mut secret)
+            .expect("Failed to generate random bytes");
+        Self::new(&secret)
+    }
+}
+
+impl UserCookies {
+    pub fn new(secret: &[u8]) -> Self {
+        Self {
+            key: hmac::Key::new(hmac::HMAC_SHA256, secret),
+            max_age: SecurityConfig::default().cookie_max_age,
+            legacy_credentials: true,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let mut cookies = match env::var("USER_COOKIE_SECRET") {
+            Ok(secret) if !secret.is_empty() => Self::new(secret.as_bytes()),
+            _ => {
+                warn!("USER_COOKIE_SECRET not set; user cookies won't survive a restart");
+                Self::default()
+            }
+        };
+
+        if let Ok(legacy) = env::var("LEGACY_USER_CREDENTIALS") {
+            cookies.legacy_credentials = legacy.to_lowercase() != "false";
+        }
+
+        cookies
+    }
+
+    /// Sets how long the cookie lasts
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Sets whether user_id and cookie_token sent with the request are
+    /// accepted when there is no signed cookie
+    pub fn with_legacy_credentials(mut self, legacy_credentials: bool) -> Self {
+        self.legacy_credentials = legacy_credentials;
+        self
+    }
+
+    pub fn legacy_credentials(&self) -> bool {
+        self.legacy_credentials
+    }
+
+    /// sign returns the user and its signature, each base64url encoded and
+    /// joined by a dot
+    pub fn sign(&self, user: &SignedUser) -> String {
+        let payload = general_purpose::URL_SAFE_NO_PAD
+            .encode(serde_json::to_vec(user).expect("user always serializes"));
+        let tag = hmac::sign(&self.key, payload.as_bytes());
+        format!(
+            "{payload}.{}",
+            general_purpose::URL_SAFE_NO_PAD.encode(tag.as_ref())
+        )
+    }
+
+    /// verify returns the user a cookie value was signed for, or None if it
+    /// can't be read or wasn't signed with this key
+    pub fn verify(&self, value: &str) -> Option<SignedUser> {
+        let (payload, tag) = value.split_once('.')?;
+        let tag = general_purpose::URL_SAFE_NO_PAD.decode(tag).ok()?;
+        hmac::verify(&self.key, payload.as_bytes(), &tag).ok()?;
+        let user = general_purpose::URL_SAFE_NO_PAD.decode(payload).ok()?;
+        serde_json::from_slice(&user).ok()
+    }
+
+    fn cookie(&self, user: &SignedUser) -> Cookie<'static> {
+        let mut cookie = Cookie::new(USER_COOKIE, self.sign(user));
+        cookie.set_http_only(true);
+        cookie.set_secure(true);
+        cookie.set_same_site(tower_cookies::cookie::SameSite::Lax);
+        cookie.set_path("/api");
+        cookie.set_max_age(tower_cookies::cookie::time::Duration::seconds(
+            self.max_age.as_secs() as i64,
+        ));
+        cookie
+    }
+}
+
+/// UserIdentity extracts the user a request's signed cookie names, if it
+/// carries a valid one, and sets the cookie once a handler knows who the
+/// user is. The user it names still has to be looked up, since the user may
+/// have been merged away since the cookie was issued.
+pub struct UserIdentity {
+    signed: Option<SignedUser>,
+    cookies: Option<Cookies>,
+    user_cookies: UserCookies,
+}
+
+impl UserIdentity {
+    /// An identity for callers that can't hold cookies, such as gRPC clients,
+    /// so the credentials they send are always accepted
+    pub fn from_credentials(user_cookies: UserCookies) -> Self {
+        Self {
+            signed: None,
+            cookies: None,
+            user_cookies: user_cookies.with_legacy_credentials(true),
+        }
+    }
+
+    /// The user the request's cookie was signed for
+    pub fn signed(&self) -> Option<&SignedUser> {
+        self.signed.as_ref()
+    }
+
+    /// Whether the handler may fall back to credentials sent with the request
+    pub fn legacy_credentials(&self) -> bool {
+        self.user_cookies.legacy_credentials
+    }
+
+    /// issue sets the signed cookie for `user` on the response, unless the
+    /// request already carried it
+    pub fn issue(&self, user: &DbUser) {
+        let Some(cookies) = &self.cookies else {
+            return;
+        };
+        let signed = SignedUser {
+            user_id: user.id.clone(),
+            cookie_token: user.cookie_token.clone(),
+        };
+        if self.signed.as_ref() != Some(&signed) {
+            cookies.add(self.user_cookies.cookie(&signed));
+        }
+    }
+
+    /// clear removes the signed cookie, once the user it names is gone
+    pub fn clear(&self) {
+        if let Some(cookies) = &self.cookies {
+            let mut cookie = Cookie::from(USER_COOKIE);
+            cookie.set_path("/api");
+            cookies.remove(cookie);
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for UserIdentity
+where
+    UserCookies: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let user_cookies = UserCookies::from_ref(state);
+        let cookies = parts.extensions.get::<Cookies>().cloned();
+        let signed = cookies
+            .as_ref()
+            .and_then(|cookies| cookies.get(USER_COOKIE))
+            .and_then(|cookie| user_cookies.verify(cookie.value()));
+
+        Ok(Self {
+            signed,
+            cookies,
+            user_cookies,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(user_id: &str) -> SignedUser {
+        SignedUser {
+            user_id: user_id.to_string(),
+            cookie_token: "token".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cookies_verify_with_their_key() {
+        let cookies = UserCookies::new(b"secret");
+        let value = cookies.sign(&user("player"));
+
+        assert_eq!(cookies.verify(&value), Some(user("player")));
+        assert_eq!(UserCookies::new(b"another secret").verify(&value), None);
+        assert_eq!(cookies.verify("not a cookie"), None);
+    }
+
+    #[test]
+    fn test_tampered_cookies_dont_verify() {
+        let cookies = UserCookies::new(b"secret");
+        let value = cookies.sign(&user("player"));
+
+        // Swapping in another user's payload breaks the signature
+        let (_, tag) = value.split_once('.').unwrap();
+        let other = cookies.sign(&user("someone else"));
+        let (payload, _) = other.split_once('.').unwrap();
+        assert_eq!(cookies.verify(&format!("{payload}.{tag}")), None);
+    }
+
+    #[test]
+    fn test_cookie_attributes() {
+        let cookie = UserCookies::new(b"secret").cookie(&user("player"));
+
+        assert_eq!(cookie.name(), USER_COOKIE);
+        assert_eq!(cookie.http_only(), Some(true));
+        assert_eq!(cookie.secure(), Some(true));
+        assert_eq!(cookie.path(), Some("/api"));
+    }
+}
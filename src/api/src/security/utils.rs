@@ -1,4 +1,6 @@
 use axum::http::HeaderMap;
+use base64::{engine::general_purpose, Engine as _};
+use ring::rand::{SecureRandom, SystemRandom};
 use std::net::IpAddr;
 use std::str::FromStr;
 
@@ -48,6 +50,32 @@ pub fn is_origin_allowed(origin: &str, allowed_origins: &[String]) -> bool {
     })
 }
 
+/// constant_time_eq compares two secrets without short-circuiting on the first
+/// differing byte, so response timing doesn't leak how much of a guess matched
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// hash_token returns a SHA-256 digest of a secret, so it can be stored or
+/// logged without revealing the secret itself
+pub fn hash_token(token: &str) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, token.as_bytes());
+    general_purpose::URL_SAFE_NO_PAD.encode(digest.as_ref())
+}
+
+/// generate_token returns a random URL-safe secret with 256 bits of entropy
+pub fn generate_token() -> String {
+    let rng = SystemRandom::new();
+    let mut bytes = [0u8; 32];
+    rng.fill(&mut bytes)
+        .expect("Failed to generate random bytes");
+    general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
 #[cfg(test)]
 mod tests {
     use axum::http::HeaderValue;
@@ -96,4 +124,19 @@ mod tests {
         assert!(!is_origin_allowed("https://malicious.com", &allowed));
         assert!(!is_origin_allowed("https://example.com.evil.com", &allowed));
     }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokem"));
+        assert!(!constant_time_eq(b"token", b"token2"));
+        assert!(!constant_time_eq(b"", b"token"));
+    }
+
+    #[test]
+    fn test_hash_token() {
+        assert_eq!(hash_token("session"), hash_token("session"));
+        assert_ne!(hash_token("session"), hash_token("session2"));
+        assert_eq!(hash_token("session").len(), 43);
+    }
 }
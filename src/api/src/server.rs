@@ -0,0 +1,360 @@
+use axum::{extract::ConnectInfo, Router};
+use hyper::{body::Incoming, server::conn::http1, service::service_fn, Request};
+use hyper_util::rt::{TokioIo, TokioTimer};
+use std::{
+    collections::HashMap,
+    future::Future,
+    io,
+    net::IpAddr,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::TcpListener,
+    sync::watch,
+    time::{Instant, Sleep},
+};
+use tower::ServiceExt;
+use tracing::{debug, error, warn};
+
+use crate::security::SecurityConfig;
+
+#[derive(Default)]
+struct OpenConnections {
+    total: usize,
+    by_ip: HashMap<IpAddr, usize>,
+}
+
+/// ConnectionLimits caps how many connections are open at once, in total and
+/// from any one address, so a single client can't use up the VM's sockets
+/// and memory
+#[derive(Clone)]
+pub struct ConnectionLimits {
+    max_total: usize,
+    max_per_ip: usize,
+    open: Arc<Mutex<OpenConnections>>,
+}
+
+/// Held for as long as an admitted connection is open
+pub struct ConnectionGuard {
+    limits: ConnectionLimits,
+    ip: IpAddr,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        let mut open = self.limits.open.lock().unwrap();
+        open.total -= 1;
+        if let Some(count) = open.by_ip.get_mut(&self.ip) {
+            *count -= 1;
+            if *count == 0 {
+                open.by_ip.remove(&self.ip);
+            }
+        }
+    }
+}
+
+impl ConnectionLimits {
+    /// `max_per_ip` of 0 leaves addresses unlimited
+    pub fn new(max_total: usize, max_per_ip: usize) -> Self {
+        Self {
+            max_total,
+            max_per_ip,
+            open: Arc::default(),
+        }
+    }
+
+    /// try_acquire admits a connection from `ip`, or returns None when a limit
+    /// has been reached. It counts as open until the guard is dropped.
+    pub fn try_acquire(&self, ip: IpAddr) -> Option<ConnectionGuard> {
+        let mut open = self.open.lock().unwrap();
+        let from_ip = open.by_ip.get(&ip).copied().unwrap_or(0);
+        if open.total >= self.max_total || (self.max_per_ip > 0 && from_ip >= self.max_per_ip) {
+            return None;
+        }
+        open.total += 1;
+        open.by_ip.insert(ip, from_ip + 1);
+        Some(ConnectionGuard {
+            limits: self.clone(),
+            ip,
+        })
+    }
+
+    pub fn open(&self) -> usize {
+        self.open.lock().unwrap().total
+    }
+}
+
+/// IdleTimeout fails reads and writes on a connection once nothing has been
+/// sent or received for `timeout`, which closes it
+pub struct IdleTimeout<S> {
+    inner: S,
+    timeout: Duration,
+    deadline: Pin<Box<Sleep>>,
+}
+
+impl<S> IdleTimeout<S> {
+    pub fn new(inner: S, timeout: Duration) -> Self {
+        Self {
+            inner,
+            timeout,
+            deadline: Box::pin(tokio::time::sleep(timeout)),
+        }
+    }
+
+    /// track pushes the deadline back when `poll` made progress, and turns a
+    /// pending poll into an error once the deadline has passed
+    fn track<T>(&mut self, cx: &mut Context<'_>, poll: Poll<io::Result<T>>) -> Poll<io::Result<T>> {
+        if poll.is_ready() {
+            let next = Instant::now() + self.timeout;
+            self.deadline.as_mut().reset(next);
+            return poll;
+        }
+        match self.deadline.as_mut().poll(cx) {
+            Poll::Ready(()) => Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "connection idle",
+            ))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for IdleTimeout<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_read(cx, buf);
+        this.track(cx, poll)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for IdleTimeout<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_write(cx, buf);
+        this.track(cx, poll)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_flush(cx);
+        this.track(cx, poll)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+fn is_connection_error(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionReset
+    )
+}
+
+/// serve runs `router` on `listener` until `signal` resolves, then stops
+/// accepting connections and returns once open ones have finished their
+/// requests. Unlike axum::serve it bounds how long clients get to send
+/// headers and stay idle, and how many connections they can hold open, per
+/// `config`.
+pub async fn serve<F>(
+    listener: TcpListener,
+    router: Router,
+    config: &SecurityConfig,
+    signal: F,
+) -> io::Result<()>
+where
+    F: Future<Output = ()> + Send,
+{
+    let limits = ConnectionLimits::new(config.max_connections, config.max_connections_per_ip);
+    let mut http = http1::Builder::new();
+    http.timer(TokioTimer::new())
+        .header_read_timeout(config.header_read_timeout);
+
+    // Dropping `stop` tells connections to finish up; `closed` resolves once
+    // every connection has dropped its receiver
+    let (stop, stop_rx) = watch::channel(());
+    let (closed, closed_rx) = watch::channel(());
+
+    tokio::pin!(signal);
+    loop {
+        let (stream, peer) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) if is_connection_error(&e) => continue,
+                Err(e) => {
+                    error!("Failed to accept connection: {}", e);
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    continue;
+                }
+            },
+            _ = &mut signal => break,
+        };
+
+        let Some(guard) = limits.try_acquire(peer.ip()) else {
+            warn!(
+                "Refusing connection from {}: {} connections open",
+                peer,
+                limits.open()
+            );
+            continue;
+        };
+
+        let io = TokioIo::new(IdleTimeout::new(stream, config.idle_connection_timeout));
+        let router = router.clone();
+        let service = service_fn(move |mut request: Request<Incoming>| {
+            request.extensions_mut().insert(ConnectInfo(peer));
+            router.clone().oneshot(request)
+        });
+        let connection = http.serve_connection(io, service).with_upgrades();
+        let mut stop_rx = stop_rx.clone();
+        let closed_rx = closed_rx.clone();
+
+        tokio::spawn(async move {
+            let _guard = guard;
+            let _closed_rx = closed_rx;
+            tokio::pin!(connection);
+            let result = tokio::select! {
+                result = connection.as_mut() => result,
+                _ = stop_rx.changed() => {
+                    connection.as_mut().graceful_shutdown();
+                    connection.await
+                }
+            };
+            if let Err(e) = result {
+                debug!("Connection from {} closed: {}", peer, e);
+            }
+        });
+    }
+
+    drop(listener);
+    drop(stop_rx);
+    drop(stop);
+    drop(closed_rx);
+    closed.closed().await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use std::net::SocketAddr;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    async fn start(config: SecurityConfig) -> (SocketAddr, watch::Sender<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = Router::new().route("/", get(|| async { "ok" }));
+        let (stop, mut stop_rx) = watch::channel(());
+        tokio::spawn(async move {
+            serve(listener, router, &config, async move {
+                let _ = stop_rx.changed().await;
+            })
+            .await
+        });
+        (addr, stop)
+    }
+
+    /// read_to_close returns what the server sent before closing, or None if
+    /// it kept the connection open past `within`
+    async fn read_to_close(stream: &mut TcpStream, within: Duration) -> Option<String> {
+        let mut received = Vec::new();
+        tokio::time::timeout(within, stream.read_to_end(&mut received))
+            .await
+            .ok()
+            .map(|_| String::from_utf8_lossy(&received).into_owned())
+    }
+
+    #[test]
+    fn test_limits_count_connections_per_address() {
+        let limits = ConnectionLimits::new(3, 2);
+        let first: IpAddr = [10, 0, 0, 1].into();
+        let second: IpAddr = [10, 0, 0, 2].into();
+
+        let a = limits.try_acquire(first).unwrap();
+        let _b = limits.try_acquire(first).unwrap();
+        assert!(limits.try_acquire(first).is_none());
+
+        let _c = limits.try_acquire(second).unwrap();
+        assert!(limits.try_acquire(second).is_none(), "total limit reached");
+
+        drop(a);
+        assert_eq!(limits.open(), 2);
+        assert!(limits.try_acquire(first).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_slow_headers_and_idle_connections_are_closed() {
+        let config = SecurityConfig {
+            header_read_timeout: Duration::from_millis(200),
+            idle_connection_timeout: Duration::from_millis(500),
+            ..Default::default()
+        };
+        let (addr, _stop) = start(config).await;
+
+        // A client that never finishes its headers
+        let mut slow = TcpStream::connect(addr).await.unwrap();
+        slow.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n")
+            .await
+            .unwrap();
+        assert!(read_to_close(&mut slow, Duration::from_secs(2))
+            .await
+            .is_some());
+
+        // A client that sends a request, then sits on the connection
+        let mut idle = TcpStream::connect(addr).await.unwrap();
+        idle.write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let response = read_to_close(&mut idle, Duration::from_secs(2))
+            .await
+            .expect("idle connection should be closed");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+    }
+
+    #[tokio::test]
+    async fn test_connections_over_the_per_address_limit_are_refused() {
+        let config = SecurityConfig {
+            max_connections_per_ip: 1,
+            ..Default::default()
+        };
+        let (addr, stop) = start(config).await;
+
+        let mut first = TcpStream::connect(addr).await.unwrap();
+        first
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = [0; 15];
+        first.read_exact(&mut response).await.unwrap();
+        assert_eq!(&response, b"HTTP/1.1 200 OK");
+
+        let mut second = TcpStream::connect(addr).await.unwrap();
+        assert_eq!(
+            read_to_close(&mut second, Duration::from_secs(2)).await,
+            Some(String::new())
+        );
+
+        // Shutting down lets the open connection finish rather than hang
+        drop(stop);
+        assert!(read_to_close(&mut first, Duration::from_secs(2))
+            .await
+            .is_some());
+    }
+}
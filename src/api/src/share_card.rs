@@ -0,0 +1,235 @@
+use crate::game::{Board, GameEngine};
+use crate::raster::{Canvas, Rgb};
+
+/// A finished game summed up for sharing. The grid shows how many of the
+/// player's words went through each tile, which gives away nothing about
+/// what the words were.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShareCard {
+    pub sequence_number: i32,
+    pub total_score: i32,
+    pub threshold_score: i32,
+    /// Share of players who scored the same or lower, if known
+    pub percentile: Option<f32>,
+    /// Words through each tile, indexed by row then column
+    pub tile_usage: Vec<Vec<u8>>,
+}
+
+impl ShareCard {
+    pub fn new(
+        sequence_number: i32,
+        total_score: i32,
+        threshold_score: i32,
+        percentile: Option<f32>,
+        tile_usage: Vec<Vec<u8>>,
+    ) -> Self {
+        Self {
+            sequence_number,
+            total_score,
+            threshold_score,
+            percentile,
+            tile_usage,
+        }
+    }
+
+    /// The grid as rows of emoji, warmer colours for busier tiles
+    pub fn emoji_grid(&self) -> String {
+        self.tile_usage
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&uses| usage_emoji(uses))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Text to paste into a post, ending with the emoji grid
+    pub fn text(&self) -> String {
+        let mut text = format!(
+            "Pathfinder #{}\n{}/{}",
+            self.sequence_number, self.total_score, self.threshold_score
+        );
+        if self.total_score >= self.threshold_score {
+            text.push_str(" ⭐");
+        }
+        if let Some(percentile) = self.percentile {
+            text.push_str(&format!(
+                "\nBetter than or equal to {:.0}% of players",
+                percentile
+            ));
+        }
+        text.push_str("\n\n");
+        text.push_str(&self.emoji_grid());
+        text
+    }
+
+    /// Draw the card as a PNG: the grid on the left and the puzzle number,
+    /// score, progress towards the threshold and percentile on the right
+    pub fn render_png(&self) -> Vec<u8> {
+        let mut canvas = Canvas::new(CARD_WIDTH, CARD_HEIGHT, BACKGROUND);
+
+        let rows = self.tile_usage.len();
+        let grid_height = rows * TILE_SIZE + rows.saturating_sub(1) * TILE_GAP;
+        let grid_top = (CARD_HEIGHT - grid_height) / 2;
+        for (r, row) in self.tile_usage.iter().enumerate() {
+            for (c, &uses) in row.iter().enumerate() {
+                canvas.fill_rect(
+                    GRID_LEFT + c * (TILE_SIZE + TILE_GAP),
+                    grid_top + r * (TILE_SIZE + TILE_GAP),
+                    TILE_SIZE,
+                    TILE_SIZE,
+                    usage_color(uses),
+                );
+            }
+        }
+
+        canvas.draw_text(
+            TEXT_LEFT,
+            40,
+            8,
+            &format!("#{}", self.sequence_number),
+            MUTED_TEXT,
+        );
+        canvas.draw_text(
+            TEXT_LEFT,
+            110,
+            8,
+            &format!("{}/{}", self.total_score, self.threshold_score),
+            TEXT,
+        );
+
+        let reached = self.total_score >= self.threshold_score;
+        let progress = if self.threshold_score > 0 {
+            (self.total_score.max(0) as f32 / self.threshold_score as f32).min(1.0)
+        } else {
+            1.0
+        };
+        canvas.fill_rect(TEXT_LEFT, 175, BAR_WIDTH, 20, UNUSED_TILE);
+        canvas.fill_rect(
+            TEXT_LEFT,
+            175,
+            (BAR_WIDTH as f32 * progress) as usize,
+            20,
+            if reached { REACHED } else { USED_ONCE },
+        );
+
+        if let Some(percentile) = self.percentile {
+            canvas.draw_text(TEXT_LEFT, 220, 6, &format!("{percentile:.0}%"), MUTED_TEXT);
+        }
+
+        canvas.encode_png()
+    }
+}
+
+/// Count the words through each tile, following the highest scoring path of
+/// each word. Words that can't be formed on the board are skipped.
+pub fn tile_usage(engine: &GameEngine, board: &Board, words: &[String]) -> Vec<Vec<u8>> {
+    let mut usage: Vec<Vec<u8>> = board
+        .rows
+        .iter()
+        .map(|row| vec![0; row.tiles.len()])
+        .collect();
+
+    for word in words {
+        let answer = engine.find_word_paths(board, word);
+        let Some(path) = answer.paths.iter().max_by_key(|path| path.points()) else {
+            continue;
+        };
+        for tile in &path.tiles {
+            if let Some(uses) = usage
+                .get_mut(tile.row as usize)
+                .and_then(|row| row.get_mut(tile.col as usize))
+            {
+                *uses = uses.saturating_add(1);
+            }
+        }
+    }
+
+    usage
+}
+
+fn usage_emoji(uses: u8) -> char {
+    match uses {
+        0 => '⬜',
+        1 => '🟨',
+        2 => '🟧',
+        _ => '🟥',
+    }
+}
+
+const CARD_WIDTH: usize = 600;
+const CARD_HEIGHT: usize = 315;
+const GRID_LEFT: usize = 36;
+const TILE_SIZE: usize = 56;
+const TILE_GAP: usize = 8;
+const TEXT_LEFT: usize = 330;
+const BAR_WIDTH: usize = 230;
+
+const BACKGROUND: Rgb = [17, 24, 39];
+const TEXT: Rgb = [243, 244, 246];
+const MUTED_TEXT: Rgb = [156, 163, 175];
+const UNUSED_TILE: Rgb = [55, 65, 81];
+const USED_ONCE: Rgb = [234, 179, 8];
+const USED_TWICE: Rgb = [249, 115, 22];
+const USED_MORE: Rgb = [239, 68, 68];
+const REACHED: Rgb = [34, 197, 94];
+
+fn usage_color(uses: u8) -> Rgb {
+    match uses {
+        0 => UNUSED_TILE,
+        1 => USED_ONCE,
+        2 => USED_TWICE,
+        _ => USED_MORE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raster::testing::decode_scanlines;
+
+    fn card() -> ShareCard {
+        ShareCard::new(
+            12,
+            42,
+            40,
+            Some(85.4),
+            vec![
+                vec![1, 1, 0, 0],
+                vec![0, 2, 0, 0],
+                vec![0, 3, 1, 0],
+                vec![0, 0, 0, 0],
+            ],
+        )
+    }
+
+    #[test]
+    fn test_share_text() {
+        assert_eq!(
+            card().text(),
+            "Pathfinder #12\n42/40 ⭐\nBetter than or equal to 85% of players\n\n\
+             🟨🟨⬜⬜\n⬜🟧⬜⬜\n⬜🟥🟨⬜\n⬜⬜⬜⬜"
+        );
+    }
+
+    #[test]
+    fn test_tile_usage_follows_word_paths() {
+        let (engine, _wordlist) = crate::test_utils::create_test_game_engine();
+        let board = crate::test_utils::create_default_test_board();
+        let usage = tile_usage(&engine, &board, &["test".to_string(), "zzz".to_string()]);
+
+        assert_eq!(usage.len(), 4);
+        assert_eq!(
+            usage.iter().flatten().map(|&u| u as usize).sum::<usize>(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_render_png() {
+        let raw = decode_scanlines(&card().render_png(), CARD_WIDTH, CARD_HEIGHT);
+        assert_eq!(raw.len(), CARD_HEIGHT * (CARD_WIDTH * 3 + 1));
+    }
+}
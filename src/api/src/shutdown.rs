@@ -0,0 +1,156 @@
+use std::{
+    env,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::Notify;
+use tracing::info;
+
+#[derive(Clone, Debug)]
+pub struct ShutdownConfig {
+    /// How long in-flight requests and background work get to finish once a
+    /// shutdown signal arrives. Keep it under the platform's kill timeout.
+    pub drain_timeout: Duration,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            drain_timeout: Duration::from_secs(25),
+        }
+    }
+}
+
+impl ShutdownConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(secs) = env::var("SHUTDOWN_DRAIN_SECS") {
+            if let Ok(value) = secs.parse::<u64>() {
+                config.drain_timeout = Duration::from_secs(value);
+            }
+        }
+
+        config
+    }
+}
+
+/// Readiness tells load balancers whether to keep sending this instance
+/// traffic. It stops being ready as soon as shutdown starts, so rollouts
+/// move traffic away while in-flight requests finish.
+#[derive(Clone, Default)]
+pub struct Readiness {
+    draining: Arc<AtomicBool>,
+}
+
+impl Readiness {
+    pub fn is_ready(&self) -> bool {
+        !self.draining.load(Ordering::Relaxed)
+    }
+
+    pub fn drain(&self) {
+        self.draining.store(true, Ordering::Relaxed);
+    }
+}
+
+/// BackgroundTasks counts background work that shouldn't be cut off by a
+/// shutdown, such as a generation run that posts the day's game once it is
+/// stored
+#[derive(Clone, Default)]
+pub struct BackgroundTasks {
+    running: Arc<AtomicUsize>,
+    idle: Arc<Notify>,
+}
+
+/// Held for as long as a tracked task runs
+pub struct TaskGuard {
+    tasks: BackgroundTasks,
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        if self.tasks.running.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.tasks.idle.notify_waiters();
+        }
+    }
+}
+
+impl BackgroundTasks {
+    /// track marks a task as running until the returned guard is dropped
+    pub fn track(&self) -> TaskGuard {
+        self.running.fetch_add(1, Ordering::AcqRel);
+        TaskGuard {
+            tasks: self.clone(),
+        }
+    }
+
+    pub fn running(&self) -> usize {
+        self.running.load(Ordering::Acquire)
+    }
+
+    /// wait_idle returns once no tracked task is running
+    pub async fn wait_idle(&self) {
+        loop {
+            let idle = self.idle.notified();
+            if self.running() == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+}
+
+/// shutdown_signal resolves on SIGINT or SIGTERM, after marking `readiness`
+/// as draining
+pub async fn shutdown_signal(readiness: Readiness) {
+    let interrupt = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to listen for SIGINT");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = interrupt => info!("Received SIGINT, shutting down"),
+        _ = terminate => info!("Received SIGTERM, shutting down"),
+    }
+    readiness.drain();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_wait_idle_waits_for_tracked_tasks() {
+        let tasks = BackgroundTasks::default();
+        tasks.wait_idle().await;
+
+        let guard = tasks.track();
+        let waiter = tokio::spawn({
+            let tasks = tasks.clone();
+            async move { tasks.wait_idle().await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!waiter.is_finished());
+
+        drop(guard);
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("wait_idle should return once the task finishes")
+            .unwrap();
+        assert_eq!(tasks.running(), 0);
+    }
+}
@@ -0,0 +1,250 @@
+use anyhow::{Context, Result};
+use std::{env, time::Duration};
+use tracing::{info, warn};
+
+use super::{
+    content::{PuzzleContent, DEFAULT_ANNOUNCEMENT_TEMPLATE},
+    Post,
+};
+use crate::db::{models::DbGame, Repository};
+use crate::scheduler::backoff_delay;
+
+#[derive(Clone, Debug)]
+pub struct AnnouncerConfig {
+    /// Site the announcement links to; the puzzle path is appended
+    pub base_url: String,
+    /// Template the announcement is rendered from, see
+    /// `DEFAULT_ANNOUNCEMENT_TEMPLATE` for its placeholders
+    pub template: String,
+    /// How many times a failed post is retried before giving up
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled for every subsequent retry
+    pub retry_backoff: Duration,
+}
+
+impl Default for AnnouncerConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "https://pathfinder.prof".to_string(),
+            template: DEFAULT_ANNOUNCEMENT_TEMPLATE.to_string(),
+            max_retries: 3,
+            retry_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl AnnouncerConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(base_url) = env::var("ANNOUNCEMENT_BASE_URL") {
+            config.base_url = base_url.trim_end_matches('/').to_string();
+        }
+
+        // Written on one line in the environment, with \n for line breaks
+        if let Ok(template) = env::var("ANNOUNCEMENT_TEMPLATE") {
+            config.template = template.replace("\\n", "\n");
+        }
+
+        if let Ok(retries) = env::var("ANNOUNCEMENT_MAX_RETRIES") {
+            if let Ok(value) = retries.parse::<u32>() {
+                config.max_retries = value;
+            }
+        }
+
+        if let Ok(backoff) = env::var("ANNOUNCEMENT_RETRY_BACKOFF") {
+            if let Ok(seconds) = backoff.parse::<u64>() {
+                config.retry_backoff = Duration::from_secs(seconds);
+            }
+        }
+
+        config
+    }
+}
+
+/// DailyAnnouncer posts a spoiler-free announcement when a day's game goes
+/// live. Each game is claimed in the database before it is posted, so a
+/// restarted or duplicate scheduler never announces the same game twice.
+pub struct DailyAnnouncer<R: Repository, P: Post> {
+    repository: R,
+    poster: P,
+    config: AnnouncerConfig,
+}
+
+impl<R, P> DailyAnnouncer<R, P>
+where
+    R: Repository + Send + Sync,
+    P: Post + Send + Sync,
+{
+    pub fn new(repository: R, poster: P, config: AnnouncerConfig) -> Self {
+        Self {
+            repository,
+            poster,
+            config,
+        }
+    }
+
+    /// Announces the game for a date, if there is one. Returns whether a post
+    /// was made.
+    pub async fn announce_date(&self, date: &str) -> Result<bool> {
+        match self.repository.get_game_by_date(date).await? {
+            Some(game) => self.announce(&game).await,
+            None => {
+                info!("No game for {} to announce", date);
+                Ok(false)
+            }
+        }
+    }
+
+    /// announcement_message builds the post for a game going live. It only
+    /// gives stats about the board, never its letters or words.
+    async fn announcement_message(&self, game: &DbGame) -> String {
+        let mut content = PuzzleContent::new(game, &self.config.base_url);
+        match self.repository.get_game_answers(&game.id).await {
+            Ok(answers) if !answers.is_empty() => {
+                content = content.with_word_count(answers.len());
+            }
+            Ok(_) => {}
+            Err(e) => warn!(
+                "Announcing game {} without its word count: {}",
+                game.sequence_number, e
+            ),
+        }
+        content.render(&self.config.template)
+    }
+
+    /// Announces a game unless it already has been. Returns whether a post
+    /// was made. If every attempt fails the claim is released so the next
+    /// run tries again.
+    pub async fn announce(&self, game: &DbGame) -> Result<bool> {
+        if !self.repository.claim_game_announcement(&game.id).await? {
+            info!("Game {} was already announced", game.sequence_number);
+            return Ok(false);
+        }
+
+        let message = self.announcement_message(game).await;
+        let mut attempt = 0;
+        loop {
+            match self.poster.post(message.clone()).await {
+                Ok(()) => {
+                    info!("Announced game {} ({})", game.sequence_number, game.date);
+                    return Ok(true);
+                }
+                Err(e) if attempt < self.config.max_retries => {
+                    let delay = backoff_delay(self.config.retry_backoff, attempt);
+                    warn!(
+                        "Announcing game {} failed: {}; retrying in {:?}",
+                        game.sequence_number, e, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    self.repository
+                        .release_game_announcement(&game.id)
+                        .await
+                        .context("failed to release announcement claim")?;
+                    return Err(
+                        e.context(format!("failed to announce game {}", game.sequence_number))
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "database-tests"))]
+mod tests {
+    use super::*;
+    use crate::db::{models::NewGameAnswer, SqliteRepository};
+    use crate::test_utils::create_new_test_game;
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex,
+    };
+
+    /// Fails the first `failures` posts, then records the rest
+    #[derive(Default)]
+    struct FlakyPoster {
+        failures: AtomicU32,
+        messages: Mutex<Vec<String>>,
+    }
+
+    impl Post for FlakyPoster {
+        async fn post(&self, message: String) -> Result<()> {
+            if self
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok()
+            {
+                anyhow::bail!("service unavailable");
+            }
+            self.messages.lock().unwrap().push(message);
+            Ok(())
+        }
+    }
+
+    fn config(max_retries: u32) -> AnnouncerConfig {
+        AnnouncerConfig {
+            max_retries,
+            retry_backoff: Duration::ZERO,
+            ..Default::default()
+        }
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_announce_retries_and_never_double_posts(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let repository = SqliteRepository::new(pool);
+        let answers = ["cat", "dog", "tea"]
+            .iter()
+            .map(|word| NewGameAnswer {
+                game_id: String::new(),
+                word: word.to_string(),
+                path_stats: None,
+            })
+            .collect();
+        let (game, _) = repository
+            .create_game_with_answers(create_new_test_game(), answers, None)
+            .await
+            .unwrap();
+
+        let poster = FlakyPoster {
+            failures: AtomicU32::new(1),
+            ..Default::default()
+        };
+        let announcer = DailyAnnouncer::new(repository.clone(), poster, config(1));
+        assert!(announcer.announce_date(&game.date).await.unwrap());
+        assert!(!announcer.announce_date(&game.date).await.unwrap());
+        assert!(!announcer.announce_date("2099-01-01").await.unwrap());
+
+        // A restarted announcer sees the game was already announced
+        let restarted = DailyAnnouncer::new(repository, FlakyPoster::default(), config(1));
+        assert!(!restarted.announce(&game).await.unwrap());
+
+        let messages = announcer.poster.messages.lock().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].starts_with(&format!(
+            "Pathfinder #{} is live! 3 possible words today.",
+            game.sequence_number
+        )));
+        assert!(restarted.poster.messages.lock().unwrap().is_empty());
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_failed_announcement_is_retried_next_run(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let repository = SqliteRepository::new(pool);
+        let (game, _) = repository
+            .create_game_with_answers(create_new_test_game(), vec![], None)
+            .await
+            .unwrap();
+
+        let poster = FlakyPoster {
+            failures: AtomicU32::new(2),
+            ..Default::default()
+        };
+        let announcer = DailyAnnouncer::new(repository, poster, config(1));
+        assert!(announcer.announce(&game).await.is_err());
+        assert!(announcer.announce(&game).await.unwrap());
+        assert_eq!(announcer.poster.messages.lock().unwrap().len(), 1);
+    }
+}
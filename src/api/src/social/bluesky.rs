@@ -1,6 +1,8 @@
 use anyhow::{anyhow, Result};
 use atrium_api::types::string::Datetime;
 use bsky_sdk::BskyAgent;
+use std::env;
+use tracing::{info, warn};
 
 use super::Post;
 
@@ -24,6 +26,35 @@ impl BlueSkyPoster {
         }
     }
 
+    /// Creates a poster from `BLUESKY_HANDLE` and the app password. The password
+    /// is read from `BLUESKY_PASSWORD_FILE` when that is set, and only falls back
+    /// to `BLUESKY_PASSWORD` when it is not. Returns None when either is missing.
+    pub fn from_env() -> Option<Self> {
+        let Ok(handle) = env::var("BLUESKY_HANDLE") else {
+            info!("BlueSky handle not found in environment, skipping post");
+            return None;
+        };
+
+        let password = match env::var("BLUESKY_PASSWORD_FILE") {
+            Ok(path) => match std::fs::read_to_string(&path) {
+                Ok(s) => Some(s.trim().to_string()),
+                Err(e) => {
+                    warn!("Failed to read BLUESKY_PASSWORD_FILE '{}': {}", path, e);
+                    None
+                }
+            },
+            Err(_) => env::var("BLUESKY_PASSWORD").ok(),
+        };
+
+        match password {
+            Some(password) => Some(Self::new(handle).with_password(password)),
+            None => {
+                info!("BlueSky password not found, skipping post");
+                None
+            }
+        }
+    }
+
     /// Sets the app password for authentication
     pub fn with_password(mut self, password: String) -> Self {
         self.password = Some(password);
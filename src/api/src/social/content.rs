@@ -0,0 +1,175 @@
+use crate::db::models::DbGame;
+use crate::game::{conversion::SerializableBoard, Board};
+
+/// The announcement posted when a puzzle goes live. Placeholders:
+/// `{number}` the puzzle number, `{teaser}` a stat about the board,
+/// `{threshold}` the target score, `{board}` the board's silhouette and
+/// `{url}` the link to the puzzle.
+pub const DEFAULT_ANNOUNCEMENT_TEMPLATE: &str = "Pathfinder #{number} is live! {teaser}\nCan you find five words worth {threshold} points?\n\n{board}\n\n{url}";
+
+/// The strictest platform limit, BlueSky's, so one message fits everywhere
+pub const MAX_POST_LENGTH: usize = 300;
+
+const LETTER_TILE: &str = "🟦";
+const WILDCARD_TILE: &str = "⭐";
+
+/// PuzzleContent is what a puzzle announcement can say about a game without
+/// giving anything away. It is rendered into the same text for every
+/// platform.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PuzzleContent {
+    pub sequence_number: i32,
+    pub threshold_score: i32,
+    /// How many valid words the board has, when known
+    pub word_count: Option<usize>,
+    /// The board's shape in emoji, with the wildcards marked but no letters
+    pub silhouette: Option<String>,
+    pub url: String,
+}
+
+impl PuzzleContent {
+    /// Content for a game, linking to it under `base_url`. The silhouette is
+    /// taken from the stored board when it can be read.
+    pub fn new(game: &DbGame, base_url: &str) -> Self {
+        let silhouette = serde_json::from_str::<SerializableBoard>(&game.board_data)
+            .ok()
+            .map(|board| silhouette(&Board::from(board)));
+        Self {
+            sequence_number: game.sequence_number,
+            threshold_score: game.threshold_score,
+            word_count: None,
+            silhouette,
+            url: format!("{}/puzzle/{}", base_url, game.sequence_number),
+        }
+    }
+
+    pub fn with_word_count(mut self, word_count: usize) -> Self {
+        self.word_count = Some(word_count);
+        self
+    }
+
+    /// teaser is a one line stat about the board, empty when there is none
+    pub fn teaser(&self) -> String {
+        match self.word_count {
+            Some(count) => format!("{count} possible words today."),
+            None => String::new(),
+        }
+    }
+
+    /// render fills in `template`. Pieces that are unknown render empty, and
+    /// the blank lines they leave behind are dropped.
+    pub fn render(&self, template: &str) -> String {
+        let filled = template
+            .replace("{number}", &self.sequence_number.to_string())
+            .replace("{threshold}", &self.threshold_score.to_string())
+            .replace("{teaser}", &self.teaser())
+            .replace("{board}", self.silhouette.as_deref().unwrap_or(""))
+            .replace("{url}", &self.url);
+
+        let mut message = String::new();
+        let mut blank = false;
+        for line in filled.lines().map(str::trim_end) {
+            if line.is_empty() {
+                blank = !message.is_empty();
+                continue;
+            }
+            if blank {
+                message.push('\n');
+                blank = false;
+            }
+            if !message.is_empty() {
+                message.push('\n');
+            }
+            message.push_str(line);
+        }
+        message
+    }
+}
+
+/// silhouette draws the board one emoji per tile, marking the wildcards
+pub fn silhouette(board: &Board) -> String {
+    board
+        .rows
+        .iter()
+        .map(|row| {
+            row.tiles
+                .iter()
+                .map(|tile| {
+                    if tile.is_wildcard {
+                        WILDCARD_TILE
+                    } else {
+                        LETTER_TILE
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn game(board_data: String) -> DbGame {
+        DbGame {
+            id: "game".to_string(),
+            date: "2025-06-08".to_string(),
+            board_data,
+            threshold_score: 40,
+            sequence_number: 12,
+            generation_version: 1,
+            difficulty: None,
+            theme: None,
+            scoring_scheme_version: 1,
+            bonus_rules: None,
+            game_rules: None,
+            mode: "daily".to_string(),
+            time_limit_seconds: None,
+            language: "en".to_string(),
+            revision: 1,
+            deleted_at: None,
+            completed: false,
+            completed_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_render_default_template() {
+        let board: Board = "tmitc*otsa*iinal".parse().unwrap();
+        let board_data = serde_json::to_string(&SerializableBoard::from(&board)).unwrap();
+        let content =
+            PuzzleContent::new(&game(board_data), "https://pathfinder.prof").with_word_count(37);
+
+        let message = content.render(DEFAULT_ANNOUNCEMENT_TEMPLATE);
+        assert_eq!(
+            message,
+            "Pathfinder #12 is live! 37 possible words today.\n\
+             Can you find five words worth 40 points?\n\
+             \n\
+             🟦🟦🟦🟦\n🟦⭐🟦🟦\n🟦🟦⭐🟦\n🟦🟦🟦🟦\n\
+             \n\
+             https://pathfinder.prof/puzzle/12"
+        );
+        assert!(message.chars().count() <= MAX_POST_LENGTH);
+    }
+
+    #[test]
+    fn test_render_without_stats() {
+        let content = PuzzleContent::new(&game(String::new()), "https://pathfinder.prof");
+        assert_eq!(content.silhouette, None);
+        assert_eq!(
+            content.render(DEFAULT_ANNOUNCEMENT_TEMPLATE),
+            "Pathfinder #12 is live!\n\
+             Can you find five words worth 40 points?\n\
+             \n\
+             https://pathfinder.prof/puzzle/12"
+        );
+        assert_eq!(
+            content.render("#{number} {teaser}\n{url}"),
+            "#12\nhttps://pathfinder.prof/puzzle/12"
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use anyhow::{anyhow, Result};
+use ring::signature;
+use serde::{Deserialize, Serialize};
+use std::env;
+use tracing::{info, warn};
+
+use super::Post;
+
+/// Discord's REST API, pinned to the version the payloads below follow
+const DISCORD_API_URL: &str = "https://discord.com/api/v10";
+
+/// Longest message Discord accepts
+pub const MAX_MESSAGE_LENGTH: usize = 2000;
+
+/// Headers Discord signs interaction requests with
+pub const SIGNATURE_HEADER: &str = "X-Signature-Ed25519";
+pub const TIMESTAMP_HEADER: &str = "X-Signature-Timestamp";
+
+/// Interaction types Discord sends
+pub const INTERACTION_PING: u8 = 1;
+pub const INTERACTION_APPLICATION_COMMAND: u8 = 2;
+
+/// Interaction response types
+const RESPONSE_PONG: u8 = 1;
+const RESPONSE_CHANNEL_MESSAGE: u8 = 4;
+
+/// Message flag that shows a reply only to the member who asked for it
+const EPHEMERAL: u64 = 1 << 6;
+
+/// Discord poster implementation, posting as a bot user to one channel
+pub struct DiscordPoster {
+    client: reqwest::Client,
+    /// Token of the bot, which must be allowed to send messages in the channel
+    bot_token: String,
+    channel_id: String,
+}
+
+#[derive(Serialize)]
+struct NewMessage {
+    content: String,
+}
+
+impl DiscordPoster {
+    /// Creates a new Discord poster
+    pub fn new(bot_token: String, channel_id: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            bot_token,
+            channel_id,
+        }
+    }
+
+    /// Creates a poster from `DISCORD_CHANNEL_ID` and the bot token. The token
+    /// is read from `DISCORD_BOT_TOKEN_FILE` when that is set, and only falls
+    /// back to `DISCORD_BOT_TOKEN` when it is not. Returns None when either is
+    /// missing.
+    pub fn from_env() -> Option<Self> {
+        let Ok(channel_id) = env::var("DISCORD_CHANNEL_ID") else {
+            info!("Discord channel not found in environment, skipping post");
+            return None;
+        };
+
+        let bot_token = match env::var("DISCORD_BOT_TOKEN_FILE") {
+            Ok(path) => match std::fs::read_to_string(&path) {
+                Ok(s) => Some(s.trim().to_string()),
+                Err(e) => {
+                    warn!("Failed to read DISCORD_BOT_TOKEN_FILE '{}': {}", path, e);
+                    None
+                }
+            },
+            Err(_) => env::var("DISCORD_BOT_TOKEN").ok(),
+        };
+
+        match bot_token {
+            Some(bot_token) => Some(Self::new(bot_token, channel_id)),
+            None => {
+                info!("Discord bot token not found, skipping post");
+                None
+            }
+        }
+    }
+
+    fn messages_url(&self) -> String {
+        format!("{}/channels/{}/messages", DISCORD_API_URL, self.channel_id)
+    }
+
+    /// Sends a message to the channel
+    async fn create_message(&self, content: String) -> Result<()> {
+        let response = self
+            .client
+            .post(self.messages_url())
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!("Bot {}", self.bot_token),
+            )
+            .json(&NewMessage { content })
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to reach Discord: {}", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Failed to create message: {} {}", status, body));
+        }
+
+        Ok(())
+    }
+}
+
+impl Post for DiscordPoster {
+    async fn post(&self, message: String) -> Result<()> {
+        self.create_message(message).await
+    }
+}
+
+/// An interaction Discord forwards when a member uses one of the
+/// application's commands. Only the fields the API answers from are read.
+#[derive(Deserialize, Debug)]
+pub struct Interaction {
+    #[serde(rename = "type")]
+    pub kind: u8,
+    pub data: Option<CommandData>,
+    /// Who used the command, when it was used in a server
+    pub member: Option<Member>,
+    /// Who used the command, when it was used in a direct message
+    pub user: Option<DiscordUser>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CommandData {
+    pub name: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Member {
+    pub user: DiscordUser,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DiscordUser {
+    pub id: String,
+}
+
+impl Interaction {
+    /// The id of the Discord user who sent the interaction
+    pub fn user_id(&self) -> Option<&str> {
+        self.member
+            .as_ref()
+            .map(|member| &member.user)
+            .or(self.user.as_ref())
+            .map(|user| user.id.as_str())
+    }
+
+    /// The name of the command used, for command interactions
+    pub fn command(&self) -> Option<&str> {
+        self.data.as_ref().map(|data| data.name.as_str())
+    }
+}
+
+/// What the API answers an interaction with
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct InteractionResponse {
+    #[serde(rename = "type")]
+    pub kind: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<InteractionMessage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct InteractionMessage {
+    pub content: String,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub flags: u64,
+}
+
+fn is_zero(flags: &u64) -> bool {
+    *flags == 0
+}
+
+impl InteractionResponse {
+    /// The answer to Discord's ping when the endpoint is registered
+    pub fn pong() -> Self {
+        Self {
+            kind: RESPONSE_PONG,
+            data: None,
+        }
+    }
+
+    /// A reply everyone in the channel sees
+    pub fn message(content: impl Into<String>) -> Self {
+        Self::reply(content.into(), 0)
+    }
+
+    /// A reply only the member who used the command sees
+    pub fn private_message(content: impl Into<String>) -> Self {
+        Self::reply(content.into(), EPHEMERAL)
+    }
+
+    fn reply(mut content: String, flags: u64) -> Self {
+        if content.chars().count() > MAX_MESSAGE_LENGTH {
+            content = content.chars().take(MAX_MESSAGE_LENGTH).collect();
+        }
+        Self {
+            kind: RESPONSE_CHANNEL_MESSAGE,
+            data: Some(InteractionMessage { content, flags }),
+        }
+    }
+}
+
+/// verify_interaction checks Discord's Ed25519 signature of an interaction,
+/// made over the timestamp header followed by the raw body. Discord turns
+/// away endpoints that answer unsigned requests.
+pub fn verify_interaction(
+    public_key: &[u8],
+    timestamp: &str,
+    body: &[u8],
+    signature: &str,
+) -> bool {
+    let Some(signature) = decode_hex(signature) else {
+        return false;
+    };
+    let mut message = Vec::with_capacity(timestamp.len() + body.len());
+    message.extend_from_slice(timestamp.as_bytes());
+    message.extend_from_slice(body);
+
+    signature::UnparsedPublicKey::new(&signature::ED25519, public_key)
+        .verify(&message, &signature)
+        .is_ok()
+}
+
+/// decode_hex reads the hex encoding Discord uses for keys and signatures
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::{rand::SystemRandom, signature::KeyPair};
+
+    fn encode_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    fn test_verify_interaction() {
+        let pkcs8 = signature::Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key_pair = signature::Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public_key = key_pair.public_key().as_ref();
+        let body = br#"{"type":1}"#;
+        let signature = encode_hex(key_pair.sign(b"1700000000{\"type\":1}").as_ref());
+
+        assert!(verify_interaction(
+            public_key,
+            "1700000000",
+            body,
+            &signature
+        ));
+        // Replayed with another timestamp, or with the body changed
+        assert!(!verify_interaction(
+            public_key,
+            "1700000001",
+            body,
+            &signature
+        ));
+        assert!(!verify_interaction(
+            public_key,
+            "1700000000",
+            br#"{"type":2}"#,
+            &signature
+        ));
+        assert!(!verify_interaction(
+            public_key,
+            "1700000000",
+            body,
+            "not hex"
+        ));
+    }
+
+    #[test]
+    fn test_interaction_user_from_member_or_direct_message() {
+        let in_server: Interaction = serde_json::from_str(
+            r#"{"type":2,"data":{"name":"score"},"member":{"user":{"id":"42"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(in_server.user_id(), Some("42"));
+        assert_eq!(in_server.command(), Some("score"));
+
+        let direct: Interaction =
+            serde_json::from_str(r#"{"type":2,"data":{"name":"link"},"user":{"id":"7"}}"#).unwrap();
+        assert_eq!(direct.user_id(), Some("7"));
+    }
+
+    #[test]
+    fn test_response_json() {
+        assert_eq!(
+            serde_json::to_value(InteractionResponse::pong()).unwrap(),
+            serde_json::json!({"type": 1})
+        );
+        assert_eq!(
+            serde_json::to_value(InteractionResponse::private_message("hi")).unwrap(),
+            serde_json::json!({"type": 4, "data": {"content": "hi", "flags": 64}})
+        );
+        let long = InteractionResponse::message("x".repeat(MAX_MESSAGE_LENGTH + 1));
+        assert_eq!(
+            long.data.unwrap().content.chars().count(),
+            MAX_MESSAGE_LENGTH
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use anyhow::{anyhow, Result};
+use tracing::{info, warn};
+
+use super::{bluesky::BlueSkyPoster, discord::DiscordPoster, mastodon::MastodonPoster, Post};
+
+/// SocialPoster posts every message to each configured platform at once
+#[derive(Default)]
+pub struct SocialPoster {
+    bluesky: Option<BlueSkyPoster>,
+    mastodon: Option<MastodonPoster>,
+    discord: Option<DiscordPoster>,
+}
+
+/// The outcome of posting one message to every platform
+#[derive(Debug, Default)]
+pub struct PostReport {
+    /// Platforms the message was posted to
+    pub posted: Vec<&'static str>,
+    /// Platforms that failed, with the reason
+    pub failed: Vec<(&'static str, anyhow::Error)>,
+}
+
+impl SocialPoster {
+    /// Sets up every platform with credentials in the environment, logging in
+    /// where needed. Platforms that are missing credentials or reject them are
+    /// left out; returns None when none are left.
+    pub async fn from_env() -> Option<Self> {
+        let mut poster = Self::default();
+
+        if let Some(mut bluesky) = BlueSkyPoster::from_env() {
+            match bluesky.authenticate().await {
+                Ok(()) => poster = poster.with_bluesky(bluesky),
+                Err(e) => warn!("Failed to authenticate with BlueSky: {}", e),
+            }
+        }
+
+        if let Some(mastodon) = MastodonPoster::from_env() {
+            poster = poster.with_mastodon(mastodon);
+        }
+
+        if let Some(discord) = DiscordPoster::from_env() {
+            poster = poster.with_discord(discord);
+        }
+
+        if poster.platforms().is_empty() {
+            None
+        } else {
+            Some(poster)
+        }
+    }
+
+    pub fn with_bluesky(mut self, bluesky: BlueSkyPoster) -> Self {
+        self.bluesky = Some(bluesky);
+        self
+    }
+
+    pub fn with_mastodon(mut self, mastodon: MastodonPoster) -> Self {
+        self.mastodon = Some(mastodon);
+        self
+    }
+
+    pub fn with_discord(mut self, discord: DiscordPoster) -> Self {
+        self.discord = Some(discord);
+        self
+    }
+
+    /// Names of the configured platforms
+    pub fn platforms(&self) -> Vec<&'static str> {
+        let mut platforms = Vec::new();
+        if self.bluesky.is_some() {
+            platforms.push("BlueSky");
+        }
+        if self.mastodon.is_some() {
+            platforms.push("Mastodon");
+        }
+        if self.discord.is_some() {
+            platforms.push("Discord");
+        }
+        platforms
+    }
+
+    /// Posts a message to every configured platform concurrently
+    pub async fn post_to_all(&self, message: String) -> PostReport {
+        let (bluesky, mastodon, discord) = tokio::join!(
+            post_to(self.bluesky.as_ref(), message.clone()),
+            post_to(self.mastodon.as_ref(), message.clone()),
+            post_to(self.discord.as_ref(), message),
+        );
+
+        let mut report = PostReport::default();
+        for (platform, result) in [
+            ("BlueSky", bluesky),
+            ("Mastodon", mastodon),
+            ("Discord", discord),
+        ] {
+            match result {
+                Some(Ok(())) => report.posted.push(platform),
+                Some(Err(e)) => report.failed.push((platform, e)),
+                None => {}
+            }
+        }
+        report
+    }
+}
+
+async fn post_to<P: Post>(poster: Option<&P>, message: String) -> Option<Result<()>> {
+    match poster {
+        Some(poster) => Some(poster.post(message).await),
+        None => None,
+    }
+}
+
+impl PostReport {
+    /// Succeeds when the message reached at least one platform, so a retry
+    /// never double posts to the platforms that already have it. Failures on
+    /// the other platforms are logged.
+    pub fn into_result(self) -> Result<()> {
+        for (platform, e) in &self.failed {
+            warn!("Failed to post to {}: {}", platform, e);
+        }
+
+        if !self.posted.is_empty() {
+            info!("Posted to {}", self.posted.join(", "));
+            return Ok(());
+        }
+
+        match self.failed.len() {
+            0 => Err(anyhow!("No social platforms are configured")),
+            _ => Err(anyhow!(
+                "Failed to post to {}",
+                self.failed
+                    .iter()
+                    .map(|(platform, e)| format!("{platform} ({e})"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+}
+
+impl Post for SocialPoster {
+    async fn post(&self, message: String) -> Result<()> {
+        self.post_to_all(message).await.into_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_succeeds_if_any_platform_posted() {
+        let report = PostReport {
+            posted: vec!["Mastodon"],
+            failed: vec![("BlueSky", anyhow!("rate limited"))],
+        };
+        assert!(report.into_result().is_ok());
+    }
+
+    #[test]
+    fn test_report_lists_every_failure() {
+        let report = PostReport {
+            posted: vec![],
+            failed: vec![
+                ("BlueSky", anyhow!("rate limited")),
+                ("Mastodon", anyhow!("unauthorized")),
+            ],
+        };
+        assert_eq!(
+            report.into_result().unwrap_err().to_string(),
+            "Failed to post to BlueSky (rate limited), Mastodon (unauthorized)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_posting_without_platforms_fails() {
+        let poster = SocialPoster::default();
+        assert!(poster.platforms().is_empty());
+        assert!(poster.post("hello".to_string()).await.is_err());
+    }
+}
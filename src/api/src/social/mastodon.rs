@@ -0,0 +1,111 @@
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::env;
+use tracing::{info, warn};
+
+use super::Post;
+use crate::security::utils::hash_token;
+
+/// Mastodon poster implementation using the REST API of a single instance
+pub struct MastodonPoster {
+    client: reqwest::Client,
+    /// Instance base URL, e.g. https://mastodon.social
+    instance_url: String,
+    /// Access token of an application with the `write:statuses` scope
+    access_token: String,
+}
+
+#[derive(Serialize)]
+struct NewStatus {
+    status: String,
+}
+
+impl MastodonPoster {
+    /// Creates a new Mastodon poster
+    pub fn new(instance_url: String, access_token: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            instance_url: instance_url.trim_end_matches('/').to_string(),
+            access_token,
+        }
+    }
+
+    /// Creates a poster from `MASTODON_INSTANCE_URL` and the access token. The
+    /// token is read from `MASTODON_ACCESS_TOKEN_FILE` when that is set, and only
+    /// falls back to `MASTODON_ACCESS_TOKEN` when it is not. Returns None when
+    /// either is missing.
+    pub fn from_env() -> Option<Self> {
+        let Ok(instance_url) = env::var("MASTODON_INSTANCE_URL") else {
+            info!("Mastodon instance not found in environment, skipping post");
+            return None;
+        };
+
+        let access_token = match env::var("MASTODON_ACCESS_TOKEN_FILE") {
+            Ok(path) => match std::fs::read_to_string(&path) {
+                Ok(s) => Some(s.trim().to_string()),
+                Err(e) => {
+                    warn!(
+                        "Failed to read MASTODON_ACCESS_TOKEN_FILE '{}': {}",
+                        path, e
+                    );
+                    None
+                }
+            },
+            Err(_) => env::var("MASTODON_ACCESS_TOKEN").ok(),
+        };
+
+        match access_token {
+            Some(access_token) => Some(Self::new(instance_url, access_token)),
+            None => {
+                info!("Mastodon access token not found, skipping post");
+                None
+            }
+        }
+    }
+
+    fn statuses_url(&self) -> String {
+        format!("{}/api/v1/statuses", self.instance_url)
+    }
+
+    /// Publishes a status. The idempotency key lets the instance drop a retry
+    /// of a post that actually went through.
+    async fn create_status(&self, text: String) -> Result<()> {
+        let response = self
+            .client
+            .post(self.statuses_url())
+            .bearer_auth(&self.access_token)
+            .header("Idempotency-Key", hash_token(&text))
+            .json(&NewStatus { status: text })
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to reach Mastodon: {}", e))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("Failed to create status: {} {}", status, body));
+        }
+
+        Ok(())
+    }
+}
+
+impl Post for MastodonPoster {
+    async fn post(&self, message: String) -> Result<()> {
+        self.create_status(message).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statuses_url_ignores_trailing_slash() {
+        let poster = MastodonPoster::new("https://mastodon.social/".to_string(), "t".to_string());
+        assert_eq!(
+            poster.statuses_url(),
+            "https://mastodon.social/api/v1/statuses"
+        );
+    }
+}
@@ -1,10 +1,15 @@
+pub mod announcer;
 pub mod bluesky;
+pub mod content;
+pub mod discord;
+pub mod fanout;
+pub mod mastodon;
 
 use anyhow::Result;
+use std::future::Future;
 
 /// Trait for posting messages to social media platforms
-#[allow(async_fn_in_trait)]
 pub trait Post {
     /// Posts a message to the social media platform
-    async fn post(&self, message: String) -> Result<()>;
+    fn post(&self, message: String) -> impl Future<Output = Result<()>> + Send;
 }
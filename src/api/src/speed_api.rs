@@ -0,0 +1,291 @@
+use anyhow::Context;
+use axum::{
+    extract::{Path, Query, State},
+    response::{Extension, Json},
+    routing::{get, post},
+    Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{env, time::Duration};
+
+use crate::api_error::ApiError;
+use crate::db::Repository;
+use crate::http_api::{authenticate_user, load_game, ApiState, HintRequest};
+use crate::security::{session::SessionInfo, start_token::StartClaims, user_cookie::UserIdentity};
+
+const DEFAULT_LEADERBOARD_SIZE: i32 = 10;
+const MAX_LEADERBOARD_SIZE: i32 = 100;
+
+#[derive(Clone, Debug)]
+pub struct SpeedConfig {
+    /// Completions faster than this are assumed to be scripted or solved
+    /// elsewhere first, and are left off the speed leaderboard
+    pub min_plausible_time: Duration,
+}
+
+impl Default for SpeedConfig {
+    fn default() -> Self {
+        Self {
+            min_plausible_time: Duration::from_secs(20),
+        }
+    }
+}
+
+impl SpeedConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(seconds) = env::var("SPEED_MIN_PLAUSIBLE_SECONDS") {
+            if let Ok(value) = seconds.parse::<u64>() {
+                config.min_plausible_time = Duration::from_secs(value);
+            }
+        }
+
+        config
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiGameStart {
+    pub started_at: DateTime<Utc>,
+    /// Sent back with the player's entries for this game, so a tab left on
+    /// another game can't save to this one
+    pub start_token: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct SpeedLeaderboardQuery {
+    pub limit: Option<i32>,
+    /// Identify the caller to mark their own run
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ApiSpeedRun {
+    pub rank: i32,
+    /// Milliseconds from the player starting the game to submitting it
+    pub elapsed_ms: i64,
+    pub total_score: i32,
+    pub is_you: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiSpeedLeaderboard {
+    /// Only completions scoring at least this are ranked: the game's threshold
+    pub score_floor: i32,
+    /// Only completions taking at least this long are ranked
+    pub min_elapsed_ms: i64,
+    pub runs: Vec<ApiSpeedRun>,
+}
+
+/// Routes for timing games and the per-game speed leaderboard, kept apart
+/// from the score stats
+pub fn speed_router<R: Repository + Clone + Send + Sync + 'static>() -> Router<ApiState<R>> {
+    Router::new()
+        .route("/api/game/:game_id/start", post(start_game))
+        .route(
+            "/api/game/:game_id/speed-leaderboard",
+            get(get_speed_leaderboard),
+        )
+}
+
+/// Record that the user has started a game. Only the first start counts, so
+/// calling this again, e.g. on reload, never restarts the clock. Each call
+/// hands out a token bound to the game and the caller's session.
+async fn start_game<R: Repository + Clone + Send + Sync + 'static>(
+    Path(game_id): Path<String>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+    Json(request): Json<HintRequest>,
+) -> Result<Json<ApiGameStart>, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        request.user_id.as_ref(),
+        request.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+    let game = load_game(&state, &game_id).await?;
+
+    let started_at = state
+        .repository
+        .record_game_start(&user.id, &game_id)
+        .await
+        .with_context(|| format!("Failed to record start of game {game_id}"))?;
+    let start_token = state.start_tokens.sign(&StartClaims {
+        game_id: game.id,
+        date: game.date,
+        session_hash: session.map(|session| session.session_hash.clone()),
+    });
+    Ok(Json(ApiGameStart {
+        started_at,
+        start_token,
+    }))
+}
+
+/// The fastest completions of a game that reached its threshold
+async fn get_speed_leaderboard<R: Repository + Clone + Send + Sync + 'static>(
+    Path(game_id): Path<String>,
+    Query(query): Query<SpeedLeaderboardQuery>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+) -> Result<Json<ApiSpeedLeaderboard>, ApiError> {
+    let game = load_game(&state, &game_id).await?;
+    // Anyone can see the leaderboard; identifying yourself only marks your run
+    let caller = match (&query.user_id, &query.cookie_token, &session) {
+        (None, None, None) => None,
+        _ => authenticate_user(
+            &state,
+            &identity,
+            query.user_id.as_ref(),
+            query.cookie_token.as_ref(),
+            session.as_deref(),
+        )
+        .await
+        .ok(),
+    };
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_LEADERBOARD_SIZE)
+        .clamp(1, MAX_LEADERBOARD_SIZE);
+    let min_elapsed_ms = state.speed_config.min_plausible_time.as_millis() as i64;
+    let runs = state
+        .repository
+        .get_speed_runs(&game.id, game.threshold_score, min_elapsed_ms, limit)
+        .await
+        .with_context(|| format!("Failed to load speed runs for game {}", game.id))?;
+
+    Ok(Json(ApiSpeedLeaderboard {
+        score_floor: game.threshold_score,
+        min_elapsed_ms,
+        runs: runs
+            .into_iter()
+            .enumerate()
+            .map(|(index, run)| ApiSpeedRun {
+                rank: index as i32 + 1,
+                elapsed_ms: run.elapsed_ms,
+                total_score: run.total_score,
+                is_you: caller.as_ref().is_some_and(|user| user.id == run.user_id),
+            })
+            .collect(),
+    }))
+}
+
+#[cfg(all(test, feature = "database-tests"))]
+mod tests {
+    use super::*;
+    use axum::http::{Method, StatusCode};
+    use tower::util::ServiceExt;
+
+    use crate::db::models::{DbGame, DbUser, NewGameEntry, NewUser};
+    use crate::test_utils::{create_new_test_game, create_test_request, setup_app};
+
+    async fn leaderboard(app: &Router, uri: &str) -> ApiSpeedLeaderboard {
+        let response = app
+            .clone()
+            .oneshot(create_test_request(Method::GET, uri, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    async fn start(app: &Router, game: &DbGame, user: &DbUser) -> StatusCode {
+        let body = serde_json::to_string(&HintRequest {
+            user_id: Some(user.id.clone()),
+            cookie_token: Some(user.cookie_token.clone()),
+        })
+        .unwrap();
+        let uri = format!("/api/game/{}/start", game.id);
+        app.clone()
+            .oneshot(create_test_request(Method::POST, &uri, Some(&body)))
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_speed_leaderboard_ranks_plausible_completions(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let (state, app) = setup_app(pool.clone()).await;
+        let repo = &state.repository;
+        let (game, _) = repo
+            .create_game_with_answers(create_new_test_game(), vec![], None)
+            .await
+            .unwrap();
+
+        let mut players = Vec::new();
+        for name in ["quick", "steady", "cheat", "low", "unstarted"] {
+            let user = repo
+                .create_user(NewUser {
+                    cookie_token: name.to_string(),
+                })
+                .await
+                .unwrap();
+            if name != "unstarted" {
+                assert_eq!(start(&app, &game, &user).await, StatusCode::OK);
+            }
+            players.push(user);
+        }
+
+        // Backdate the recorded starts to stand in for time spent playing
+        for (user, seconds) in players.iter().zip([60, 300, 2, 30]) {
+            let started_at = Utc::now() - chrono::Duration::seconds(seconds);
+            sqlx::query("UPDATE game_starts SET started_at = ?1 WHERE user_id = ?2")
+                .bind(started_at.to_rfc3339())
+                .bind(&user.id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        // Starting again doesn't restart the clock
+        assert_eq!(start(&app, &game, &players[0]).await, StatusCode::OK);
+
+        for (user, score) in players.iter().zip([150, 120, 150, 50, 200]) {
+            repo.create_or_update_game_entry(NewGameEntry {
+                user_id: user.id.clone(),
+                game_id: game.id.clone(),
+                answers_data: "[]".to_string(),
+                total_score: score,
+                completed: true,
+                suspicion_score: 0,
+                exclude: false,
+                request_id: None,
+            })
+            .await
+            .unwrap();
+        }
+
+        let uri = format!(
+            "/api/game/{}/speed-leaderboard?user_id={}&cookie_token=steady",
+            game.id, players[1].id
+        );
+        let board = leaderboard(&app, &uri).await;
+        assert_eq!(board.score_floor, 100);
+        // Too fast, below the threshold and never started are all left off
+        let ranked: Vec<(i32, i32, bool)> = board
+            .runs
+            .iter()
+            .map(|run| (run.rank, run.total_score, run.is_you))
+            .collect();
+        assert_eq!(ranked, vec![(1, 150, false), (2, 120, true)]);
+        assert!((59_000..=61_000).contains(&board.runs[0].elapsed_ms));
+        assert!((299_000..=301_000).contains(&board.runs[1].elapsed_ms));
+
+        let anonymous = leaderboard(
+            &app,
+            &format!("/api/game/{}/speed-leaderboard?limit=1", game.id),
+        )
+        .await;
+        assert_eq!(anonymous.runs.len(), 1);
+        assert!(!anonymous.runs[0].is_you);
+    }
+}
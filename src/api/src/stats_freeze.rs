@@ -0,0 +1,414 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use std::env;
+use tracing::{info, warn};
+
+use crate::db::{
+    models::{DbFinalGameStats, DbGame},
+    Repository,
+};
+use crate::notifications::{NotificationKind, Notifier};
+
+#[derive(Clone, Debug)]
+pub struct FreezeConfig {
+    /// How long after a game's day has ended everywhere its stats are frozen
+    pub delay: Duration,
+}
+
+impl Default for FreezeConfig {
+    fn default() -> Self {
+        Self {
+            delay: Duration::hours(12),
+        }
+    }
+}
+
+impl FreezeConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(delay) = env::var("STATS_FREEZE_DELAY_HOURS") {
+            if let Ok(hours) = delay.parse::<i64>() {
+                config.delay = Duration::hours(hours.max(0));
+            }
+        }
+
+        config
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FreezeSummary {
+    pub games_frozen: usize,
+    pub notifications_sent: usize,
+    pub tournaments_finalized: usize,
+}
+
+/// The latest date whose game is due to be frozen at `now`. A date's day has
+/// ended everywhere at noon UTC the next day, when it ends at UTC-12.
+pub fn latest_freezable_date(now: DateTime<Utc>, delay: Duration) -> NaiveDate {
+    (now - delay - Duration::hours(12)).date_naive() - Duration::days(1)
+}
+
+/// Freeze the stats of every game that is due, oldest first, and tell the
+/// players who opted in where they finished. Entries submitted after a game
+/// is frozen are flagged off the board and left out of its stats. Then
+/// tournaments whose last game is frozen are finalized, so their winners
+/// are snapshotted from on-board scores only.
+pub async fn freeze_due_games<R: Repository + Send + Sync>(
+    repo: &R,
+    notifier: &Notifier<R>,
+    config: &FreezeConfig,
+    now: DateTime<Utc>,
+) -> Result<FreezeSummary> {
+    let through = latest_freezable_date(now, config.delay)
+        .format("%Y-%m-%d")
+        .to_string();
+    let mut summary = FreezeSummary::default();
+
+    for game in repo.get_unfrozen_games_through(&through).await? {
+        // Another run froze the game first and notifies its players
+        let Some(stats) = repo.freeze_game_stats(&game.id).await? else {
+            continue;
+        };
+        summary.games_frozen += 1;
+        info!(
+            "Froze stats for game {} ({}): {} players",
+            game.id, game.date, stats.total_players
+        );
+
+        summary.notifications_sent += notify_final_ranks(repo, notifier, &game, &stats).await?;
+    }
+
+    for tournament in repo.get_unfinalized_tournaments_through(&through).await? {
+        // Another run finalized the tournament first
+        let Some(results) = repo.finalize_tournament(&tournament.id).await? else {
+            continue;
+        };
+        summary.tournaments_finalized += 1;
+        info!(
+            "Finalized tournament {} ({} to {}): {} players",
+            tournament.id,
+            tournament.start_date,
+            tournament.end_date,
+            results.len()
+        );
+    }
+
+    Ok(summary)
+}
+
+async fn notify_final_ranks<R: Repository + Send + Sync>(
+    repo: &R,
+    notifier: &Notifier<R>,
+    game: &DbGame,
+    stats: &DbFinalGameStats,
+) -> Result<usize> {
+    let subject = format!("Your final rank on Pathfinder #{}", game.sequence_number);
+    let mut sent = 0;
+
+    for rank in repo.get_final_ranks_to_notify(&game.id).await? {
+        let message = format!(
+            "Final results for Pathfinder #{} ({}) are in: you finished #{} of {} with {} points.",
+            game.sequence_number, game.date, rank.rank, stats.total_players, rank.total_score
+        );
+        match notifier
+            .notify(
+                &rank.user_id,
+                NotificationKind::FinalRank,
+                Some(&game.id),
+                &subject,
+                message,
+            )
+            .await
+        {
+            Ok(_) => sent += 1,
+            Err(e) => warn!(
+                "Failed to notify user {} of their final rank: {:#}",
+                rank.user_id, e
+            ),
+        }
+    }
+
+    Ok(sent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latest_freezable_date() {
+        let at = |time: &str| {
+            DateTime::parse_from_rfc3339(time)
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+        let date = |date: &str| NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+
+        // June 1st ends everywhere at noon UTC on June 2nd
+        assert_eq!(
+            latest_freezable_date(at("2025-06-02T12:00:00Z"), Duration::zero()),
+            date("2025-06-01")
+        );
+        assert_eq!(
+            latest_freezable_date(at("2025-06-02T11:59:59Z"), Duration::zero()),
+            date("2025-05-31")
+        );
+        assert_eq!(
+            latest_freezable_date(at("2025-06-02T23:59:59Z"), Duration::hours(12)),
+            date("2025-05-31")
+        );
+        assert_eq!(
+            latest_freezable_date(at("2025-06-03T00:00:00Z"), Duration::hours(12)),
+            date("2025-06-01")
+        );
+    }
+
+    #[cfg(feature = "database-tests")]
+    mod database {
+        use super::super::*;
+        use crate::db::{
+            models::{DbNotificationPreferences, NewGameEntry, NewTournament, NewUser},
+            SqliteRepository,
+        };
+        use crate::test_utils::create_new_test_game;
+
+        async fn add_player(
+            repo: &SqliteRepository,
+            game_id: &str,
+            score: i32,
+            opted_in: bool,
+        ) -> String {
+            let user = repo
+                .create_user(NewUser {
+                    cookie_token: format!("player-{score}"),
+                })
+                .await
+                .unwrap();
+            repo.create_or_update_game_entry(NewGameEntry {
+                user_id: user.id.clone(),
+                game_id: game_id.to_string(),
+                answers_data: "[]".to_string(),
+                total_score: score,
+                completed: true,
+                suspicion_score: 0,
+                exclude: false,
+                request_id: None,
+            })
+            .await
+            .unwrap();
+            if opted_in {
+                repo.set_notification_preferences(DbNotificationPreferences {
+                    user_id: user.id.clone(),
+                    final_rank: true,
+                })
+                .await
+                .unwrap();
+            }
+            user.id
+        }
+
+        #[sqlx::test(migrations = "migrations/sqlite")]
+        async fn test_freeze_notifies_opted_in_players_once(pool: sqlx::Pool<sqlx::Sqlite>) {
+            let repo = SqliteRepository::new(pool);
+            let notifier = Notifier::new(repo.clone());
+            let mut new_game = create_new_test_game();
+            new_game.date = "2025-06-01".to_string();
+            let (game, _) = repo
+                .create_game_with_answers(new_game, vec![], None)
+                .await
+                .unwrap();
+
+            let winner = add_player(&repo, &game.id, 30, true).await;
+            add_player(&repo, &game.id, 20, false).await;
+            let third = add_player(&repo, &game.id, 10, true).await;
+
+            let config = FreezeConfig::default();
+            let too_early = DateTime::parse_from_rfc3339("2025-06-02T23:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc);
+            let summary = freeze_due_games(&repo, &notifier, &config, too_early)
+                .await
+                .unwrap();
+            assert_eq!(summary, FreezeSummary::default());
+
+            let due = DateTime::parse_from_rfc3339("2025-06-03T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc);
+            let summary = freeze_due_games(&repo, &notifier, &config, due)
+                .await
+                .unwrap();
+            assert_eq!(
+                summary,
+                FreezeSummary {
+                    games_frozen: 1,
+                    notifications_sent: 2,
+                    tournaments_finalized: 0,
+                }
+            );
+            let stats = repo.get_final_game_stats(&game.id).await.unwrap().unwrap();
+            assert_eq!(
+                (
+                    stats.total_players,
+                    stats.average_score,
+                    stats.highest_score
+                ),
+                (3, 20, 30)
+            );
+
+            let inbox = repo.get_notifications(&third, 10).await.unwrap();
+            assert_eq!(inbox.len(), 1);
+            assert!(inbox[0].message.contains("#3 of 3"), "{}", inbox[0].message);
+            assert!(repo.get_notifications(&winner, 10).await.unwrap()[0]
+                .message
+                .contains("#1 of 3"));
+
+            // Nothing is frozen or sent twice
+            let summary = freeze_due_games(&repo, &notifier, &config, due)
+                .await
+                .unwrap();
+            assert_eq!(summary, FreezeSummary::default());
+        }
+
+        #[sqlx::test(migrations = "migrations/sqlite")]
+        async fn test_tournaments_finalize_after_their_last_game(pool: sqlx::Pool<sqlx::Sqlite>) {
+            let repo = SqliteRepository::new(pool);
+            let notifier = Notifier::new(repo.clone());
+            let mut games = Vec::new();
+            for (date, sequence_number) in [("2025-06-01", 1), ("2025-06-02", 2)] {
+                let mut new_game = create_new_test_game();
+                new_game.date = date.to_string();
+                new_game.sequence_number = sequence_number;
+                let (game, _) = repo
+                    .create_game_with_answers(new_game, vec![], None)
+                    .await
+                    .unwrap();
+                games.push(game);
+            }
+            let tournament = repo
+                .create_tournament(NewTournament {
+                    name: "June".to_string(),
+                    start_date: "2025-06-01".to_string(),
+                    end_date: "2025-06-02".to_string(),
+                    language: "en".to_string(),
+                })
+                .await
+                .unwrap();
+
+            let steady = add_player(&repo, &games[0].id, 10, false).await;
+            add_player(&repo, &games[1].id, 15, false).await;
+            let steady_entry = |total_score| NewGameEntry {
+                user_id: steady.clone(),
+                game_id: games[1].id.clone(),
+                answers_data: "[]".to_string(),
+                total_score,
+                completed: true,
+                suspicion_score: 0,
+                exclude: false,
+                request_id: None,
+            };
+            repo.create_or_update_game_entry(steady_entry(12))
+                .await
+                .unwrap();
+            let absent = repo
+                .create_user(NewUser {
+                    cookie_token: "absent".to_string(),
+                })
+                .await
+                .unwrap();
+            for user_id in [&steady, &absent.id] {
+                assert!(repo.join_tournament(&tournament.id, user_id).await.unwrap());
+            }
+
+            // Only the first day is over
+            let config = FreezeConfig::default();
+            let first_day_due = DateTime::parse_from_rfc3339("2025-06-03T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc);
+            let summary = freeze_due_games(&repo, &notifier, &config, first_day_due)
+                .await
+                .unwrap();
+            assert_eq!(
+                (summary.games_frozen, summary.tournaments_finalized),
+                (1, 0)
+            );
+            let standings = repo.get_tournament_standings(&tournament.id).await.unwrap();
+            assert_eq!(
+                standings
+                    .iter()
+                    .map(|standing| (standing.total_score, standing.games_played))
+                    .collect::<Vec<_>>(),
+                vec![(22, 2), (0, 0)]
+            );
+
+            let last_day_due = first_day_due + Duration::days(1);
+            let summary = freeze_due_games(&repo, &notifier, &config, last_day_due)
+                .await
+                .unwrap();
+            assert_eq!(
+                (summary.games_frozen, summary.tournaments_finalized),
+                (1, 1)
+            );
+
+            // A late play is off the board and the results are already written
+            repo.create_or_update_game_entry(steady_entry(40))
+                .await
+                .unwrap();
+            let results = repo.get_tournament_results(&tournament.id).await.unwrap();
+            assert_eq!(
+                results
+                    .iter()
+                    .map(|result| (result.user_id.as_deref(), result.rank, result.total_score))
+                    .collect::<Vec<_>>(),
+                vec![
+                    (Some(steady.as_str()), 1, 22),
+                    (Some(absent.id.as_str()), 2, 0)
+                ]
+            );
+            assert!(repo
+                .get_tournament(&tournament.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .is_finalized());
+
+            // Results outlive the players' accounts
+            repo.delete_user(&steady).await.unwrap();
+            let results = repo.get_tournament_results(&tournament.id).await.unwrap();
+            assert_eq!(results[0].user_id, None);
+            assert_eq!(results[0].total_score, 22);
+
+            let summary = freeze_due_games(&repo, &notifier, &config, last_day_due)
+                .await
+                .unwrap();
+            assert_eq!(summary, FreezeSummary::default());
+        }
+
+        #[sqlx::test(migrations = "migrations/sqlite")]
+        async fn test_late_plays_are_off_board(pool: sqlx::Pool<sqlx::Sqlite>) {
+            let repo = SqliteRepository::new(pool);
+            let (game, _) = repo
+                .create_game_with_answers(create_new_test_game(), vec![], None)
+                .await
+                .unwrap();
+            let on_time = add_player(&repo, &game.id, 10, false).await;
+            repo.freeze_game_stats(&game.id).await.unwrap().unwrap();
+
+            let late = add_player(&repo, &game.id, 50, false).await;
+            let late_entry = repo.get_game_entry(&late, &game.id).await.unwrap().unwrap();
+            assert!(late_entry.off_board);
+            let on_time_entry = repo
+                .get_game_entry(&on_time, &game.id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(!on_time_entry.off_board);
+
+            // The late score doesn't change the game's stats
+            let (total_players, _, _, _, highest_score) =
+                repo.get_game_stats(&game.id, 50).await.unwrap();
+            assert_eq!((total_players, highest_score), (1, 10));
+        }
+    }
+}
@@ -1,11 +1,11 @@
 use crate::db::models::NewGame;
+use crate::db::InMemoryRepository;
 #[cfg(feature = "database-tests")]
 use crate::db::SqliteRepository;
 use crate::game::{conversion::SerializableBoard, Board, GameEngine};
-#[cfg(feature = "database-tests")]
-use crate::http_api::ApiState;
+use crate::http_api::{create_secure_router, ApiState};
+use crate::security::SecurityConfig;
 
-#[cfg(feature = "database-tests")]
 use axum::Router;
 
 use tempfile::NamedTempFile;
@@ -19,6 +19,15 @@ pub fn create_new_test_game() -> NewGame {
         board_data: serde_json::to_string(&serializable).unwrap(),
         threshold_score: 100,
         sequence_number: 1,
+        generation_version: 1,
+        difficulty: None,
+        theme: None,
+        scoring_scheme_version: 1,
+        bonus_rules: None,
+        game_rules: None,
+        mode: "daily".to_string(),
+        time_limit_seconds: None,
+        language: "en".to_string(),
     }
 }
 
@@ -68,8 +77,6 @@ pub fn create_default_test_board() -> Board {
 
 #[cfg(feature = "database-tests")]
 pub async fn setup_app(pool: sqlx::Pool<sqlx::Sqlite>) -> (ApiState<SqliteRepository>, Router) {
-    use crate::{db::SqliteRepository, http_api::create_secure_router, security::SecurityConfig};
-
     let repository = SqliteRepository::new(pool);
 
     // Create a test game engine using test_utils
@@ -81,6 +88,17 @@ pub async fn setup_app(pool: sqlx::Pool<sqlx::Sqlite>) -> (ApiState<SqliteReposi
     (state, app)
 }
 
+/// Sets up the app on an empty in-memory repository, for handler tests that
+/// don't need a database
+pub fn setup_memory_app() -> (ApiState<InMemoryRepository>, Router) {
+    let (game_engine, _temp_file) = create_test_game_engine();
+
+    let state = ApiState::new(InMemoryRepository::new(), game_engine);
+    let app = create_secure_router(state.clone(), SecurityConfig::default());
+
+    (state, app)
+}
+
 pub fn create_test_board_data() -> String {
     // Use test_utils board and serialize it
     let board = create_default_test_board();
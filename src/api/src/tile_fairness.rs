@@ -0,0 +1,197 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, env, sync::Arc, time::Duration};
+use tokio::{sync::RwLock, task::JoinHandle};
+use tracing::{error, info, warn};
+
+use crate::db::Repository;
+use crate::game::{
+    conversion::SerializableBoard,
+    fairness::{Expected, TileFairness},
+    Board, BoardGenerator,
+};
+
+#[derive(Clone, Debug)]
+pub struct FairnessConfig {
+    /// How often the report is recomputed from the archive
+    pub refresh_interval: Duration,
+}
+
+impl Default for FairnessConfig {
+    fn default() -> Self {
+        Self {
+            refresh_interval: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+impl FairnessConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(hours) = env::var("TILE_FAIRNESS_REFRESH_HOURS") {
+            if let Ok(value) = hours.parse::<u64>() {
+                config.refresh_interval = Duration::from_secs(value.max(1) * 60 * 60);
+            }
+        }
+
+        config
+    }
+}
+
+/// How the tiles of every generated board compare with the generator's
+/// configured frequencies, overall and for each generation version, so bias
+/// introduced by a change to the generator shows up as drift in its version
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiTileFairnessReport {
+    pub computed_at: DateTime<Utc>,
+    pub all: TileFairness,
+    pub by_generation_version: BTreeMap<i32, TileFairness>,
+    /// Stored boards that couldn't be read and are left out
+    pub unreadable_boards: usize,
+}
+
+/// TileFairnessReports keeps the latest report, since computing one reads
+/// every board in the archive
+#[derive(Clone)]
+pub struct TileFairnessReports {
+    expected: Arc<Expected>,
+    latest: Arc<RwLock<Option<ApiTileFairnessReport>>>,
+}
+
+impl Default for TileFairnessReports {
+    fn default() -> Self {
+        Self {
+            expected: Arc::new(Expected::from_generator(&BoardGenerator::new())),
+            latest: Arc::new(RwLock::new(None)),
+        }
+    }
+}
+
+impl TileFairnessReports {
+    /// The cached report, computing it first if no run has finished yet
+    pub async fn get<R: Repository>(&self, repository: &R) -> Result<ApiTileFairnessReport> {
+        if let Some(report) = self.latest.read().await.clone() {
+            return Ok(report);
+        }
+        self.refresh(repository).await
+    }
+
+    /// Recompute the report from the archive and cache it
+    pub async fn refresh<R: Repository>(&self, repository: &R) -> Result<ApiTileFairnessReport> {
+        let mut all = Vec::new();
+        let mut by_version: BTreeMap<i32, Vec<Board>> = BTreeMap::new();
+        let mut unreadable_boards = 0;
+        for game in repository.get_game_boards().await? {
+            match serde_json::from_str::<SerializableBoard>(&game.board_data) {
+                Ok(board) => {
+                    let board = Board::from(board);
+                    by_version
+                        .entry(game.generation_version)
+                        .or_default()
+                        .push(board.clone());
+                    all.push(board);
+                }
+                Err(e) => {
+                    warn!("Skipping unreadable board for {}: {}", game.date, e);
+                    unreadable_boards += 1;
+                }
+            }
+        }
+
+        let report = ApiTileFairnessReport {
+            computed_at: Utc::now(),
+            all: self.expected.analyze(&all),
+            by_generation_version: by_version
+                .iter()
+                .map(|(version, boards)| (*version, self.expected.analyze(boards)))
+                .collect(),
+            unreadable_boards,
+        };
+        *self.latest.write().await = Some(report.clone());
+        Ok(report)
+    }
+
+    /// Recomputes the report in the background every refresh interval,
+    /// starting straight away
+    pub fn start<R: Repository + Send + Sync + 'static>(
+        self,
+        repository: R,
+        config: FairnessConfig,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(config.refresh_interval);
+            loop {
+                ticks.tick().await;
+                match self.refresh(&repository).await {
+                    Ok(report) => info!(
+                        "Computed tile fairness over {} boards, letter drift {:.3}",
+                        report.all.boards, report.all.letter_drift
+                    ),
+                    Err(e) => error!("Failed to compute tile fairness: {:#}", e),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(all(test, feature = "database-tests"))]
+mod tests {
+    use super::*;
+    use crate::db::{models::NewGame, SqliteRepository};
+    use crate::test_utils::create_new_test_game;
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_report_groups_boards_by_version(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let repo = SqliteRepository::new(pool);
+        let board: Board = "tmitc*otsa*iinal".parse().unwrap();
+        let board_data = serde_json::to_string(&SerializableBoard::from(&board)).unwrap();
+        for (sequence_number, generation_version, board_data) in [
+            (1, 1, board_data.clone()),
+            (2, 2, board_data.clone()),
+            (3, 2, board_data),
+            (4, 2, "not a board".to_string()),
+        ] {
+            repo.create_game_with_answers(
+                NewGame {
+                    date: format!("2025-06-0{sequence_number}"),
+                    board_data,
+                    sequence_number,
+                    generation_version,
+                    ..create_new_test_game()
+                },
+                vec![],
+                None,
+            )
+            .await
+            .unwrap();
+        }
+
+        let reports = TileFairnessReports::default();
+        let report = reports.get(&repo).await.unwrap();
+        assert_eq!(report.all.boards, 3);
+        assert_eq!(report.unreadable_boards, 1);
+        let versions: Vec<(i32, usize)> = report
+            .by_generation_version
+            .iter()
+            .map(|(version, fairness)| (*version, fairness.boards))
+            .collect();
+        assert_eq!(versions, vec![(1, 1), (2, 2)]);
+
+        // Later requests are served from the cache until the next refresh
+        repo.create_game_with_answers(
+            NewGame {
+                date: "2025-06-05".to_string(),
+                sequence_number: 5,
+                ..create_new_test_game()
+            },
+            vec![],
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(reports.get(&repo).await.unwrap().all.boards, 3);
+        assert_eq!(reports.refresh(&repo).await.unwrap().all.boards, 4);
+    }
+}
@@ -0,0 +1,434 @@
+use anyhow::Context;
+use axum::{
+    extract::{Path, Query, State},
+    response::{Extension, Json},
+    routing::{get, post},
+    Router,
+};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::api_error::ApiError;
+use crate::custom_game_api::caller;
+use crate::db::{
+    models::{DbTournament, DbTournamentResult},
+    Repository,
+};
+use crate::game::language::Language;
+use crate::http_api::{authenticate_user, ApiState};
+use crate::security::{session::SessionInfo, user_cookie::UserIdentity};
+use crate::stats_freeze::latest_freezable_date;
+
+const DEFAULT_TOURNAMENTS: i32 = 20;
+const MAX_TOURNAMENTS: i32 = 100;
+const DEFAULT_STANDINGS_SIZE: i32 = 10;
+const MAX_STANDINGS_SIZE: i32 = 100;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApiTournament {
+    pub id: String,
+    pub name: String,
+    /// The first and last dates whose daily games count
+    pub start_date: String,
+    pub end_date: String,
+    pub language: Language,
+    /// Whether its final standings have been written
+    pub finalized: bool,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct TournamentsQuery {
+    pub limit: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct JoinTournamentRequest {
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct JoinTournamentResponse {
+    pub tournament: ApiTournament,
+    /// The player had joined before
+    pub already_joined: bool,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct TournamentStandingsQuery {
+    pub limit: Option<i32>,
+    /// Identify the caller to mark and include their own standing
+    pub user_id: Option<String>,
+    pub cookie_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApiTournamentStanding {
+    /// Players with the same total share a rank
+    pub rank: i32,
+    pub total_score: i32,
+    /// Dates the player completed the daily game on
+    pub games_played: i32,
+    pub is_you: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiTournamentStandings {
+    pub tournament: ApiTournament,
+    pub total_players: i32,
+    /// The top of the final standings once the tournament is finalized, and
+    /// of the standings so far until then
+    pub standings: Vec<ApiTournamentStanding>,
+    /// The caller's standing, wherever they placed, if they joined
+    pub you: Option<ApiTournamentStanding>,
+}
+
+/// Routes under /api/tournaments. Admins define a tournament's dates; each
+/// player who joins has their best score on each of those dates' daily
+/// games added up, and the standings are snapshotted by the stats freezer
+/// once the last date is frozen.
+pub fn tournament_router<R: Repository + Clone + Send + Sync + 'static>() -> Router<ApiState<R>> {
+    Router::new()
+        .route("/api/tournaments", get(list_tournaments))
+        .route("/api/tournaments/:tournament_id", get(get_tournament))
+        .route(
+            "/api/tournaments/:tournament_id/join",
+            post(join_tournament),
+        )
+        .route(
+            "/api/tournaments/:tournament_id/standings",
+            get(get_tournament_standings),
+        )
+}
+
+impl From<&DbTournament> for ApiTournament {
+    fn from(tournament: &DbTournament) -> Self {
+        ApiTournament {
+            id: tournament.id.clone(),
+            name: tournament.name.clone(),
+            start_date: tournament.start_date.clone(),
+            end_date: tournament.end_date.clone(),
+            language: tournament.tournament_language(),
+            finalized: tournament.is_finalized(),
+        }
+    }
+}
+
+/// Tournaments starting latest first
+async fn list_tournaments<R: Repository + Clone + Send + Sync + 'static>(
+    Query(query): Query<TournamentsQuery>,
+    State(state): State<ApiState<R>>,
+) -> Result<Json<Vec<ApiTournament>>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_TOURNAMENTS)
+        .clamp(1, MAX_TOURNAMENTS);
+    let tournaments = state
+        .repository
+        .list_tournaments(limit)
+        .await
+        .context("Failed to list tournaments")?;
+
+    Ok(Json(tournaments.iter().map(ApiTournament::from).collect()))
+}
+
+async fn get_tournament<R: Repository + Clone + Send + Sync + 'static>(
+    Path(tournament_id): Path<String>,
+    State(state): State<ApiState<R>>,
+) -> Result<Json<ApiTournament>, ApiError> {
+    let tournament = load_tournament(&state, &tournament_id).await?;
+    Ok(Json(ApiTournament::from(&tournament)))
+}
+
+/// Join a tournament any time until its last date has ended everywhere.
+/// Scores from dates before joining count too.
+async fn join_tournament<R: Repository + Clone + Send + Sync + 'static>(
+    Path(tournament_id): Path<String>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+    Json(request): Json<JoinTournamentRequest>,
+) -> Result<Json<JoinTournamentResponse>, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        request.user_id.as_ref(),
+        request.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+    let tournament = load_tournament(&state, &tournament_id).await?;
+
+    let last_ended = latest_freezable_date(Utc::now(), Duration::zero())
+        .format("%Y-%m-%d")
+        .to_string();
+    if tournament.is_finalized() || tournament.end_date <= last_ended {
+        return Err(ApiError::conflict("Tournament has ended"));
+    }
+
+    let joined = state
+        .repository
+        .join_tournament(&tournament.id, &user.id)
+        .await
+        .with_context(|| format!("Failed to join tournament {}", tournament.id))?;
+
+    Ok(Json(JoinTournamentResponse {
+        tournament: ApiTournament::from(&tournament),
+        already_joined: !joined,
+    }))
+}
+
+/// A tournament's final standings once it is finalized, or its standings so
+/// far
+async fn get_tournament_standings<R: Repository + Clone + Send + Sync + 'static>(
+    Path(tournament_id): Path<String>,
+    Query(query): Query<TournamentStandingsQuery>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+) -> Result<Json<ApiTournamentStandings>, ApiError> {
+    let tournament = load_tournament(&state, &tournament_id).await?;
+    let viewer = caller(
+        &state,
+        &identity,
+        query.user_id.as_ref(),
+        query.cookie_token.as_ref(),
+        session,
+    )
+    .await;
+
+    let results = if tournament.is_finalized() {
+        state
+            .repository
+            .get_tournament_results(&tournament.id)
+            .await
+            .with_context(|| format!("Failed to load results of tournament {}", tournament.id))?
+    } else {
+        let standings = state
+            .repository
+            .get_tournament_standings(&tournament.id)
+            .await
+            .with_context(|| format!("Failed to load standings of tournament {}", tournament.id))?;
+        DbTournamentResult::from_standings(&tournament.id, &standings)
+    };
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_STANDINGS_SIZE)
+        .clamp(1, MAX_STANDINGS_SIZE);
+    let standing = |result: &DbTournamentResult| ApiTournamentStanding {
+        rank: result.rank,
+        total_score: result.total_score,
+        games_played: result.games_played,
+        is_you: viewer
+            .as_ref()
+            .is_some_and(|user| result.user_id.as_deref() == Some(user.id.as_str())),
+    };
+    let standings: Vec<ApiTournamentStanding> =
+        results.iter().take(limit as usize).map(standing).collect();
+    let you = results
+        .iter()
+        .map(standing)
+        .find(|standing| standing.is_you);
+
+    Ok(Json(ApiTournamentStandings {
+        tournament: ApiTournament::from(&tournament),
+        total_players: results.len() as i32,
+        standings,
+        you,
+    }))
+}
+
+async fn load_tournament<R: Repository>(
+    state: &ApiState<R>,
+    tournament_id: &str,
+) -> Result<DbTournament, ApiError> {
+    state
+        .repository
+        .get_tournament(tournament_id)
+        .await
+        .context("Failed to get tournament")?
+        .ok_or_else(|| ApiError::not_found("Tournament"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{Method, StatusCode};
+    use tower::util::ServiceExt;
+
+    use crate::db::models::{NewGameEntry, NewTournament, NewUser};
+    use crate::test_utils::{create_new_test_game, create_test_request, setup_memory_app};
+
+    async fn call<T: serde::de::DeserializeOwned>(
+        app: &Router,
+        method: Method,
+        uri: &str,
+        body: Option<&str>,
+    ) -> T {
+        let response = app
+            .clone()
+            .oneshot(create_test_request(method, uri, body))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_players_join_and_accumulate_daily_scores() {
+        let (state, app) = setup_memory_app();
+        let repository = &state.repository;
+        let today = Utc::now().date_naive();
+        let date = |days: i64| {
+            (today + Duration::days(days))
+                .format("%Y-%m-%d")
+                .to_string()
+        };
+
+        let tournament = repository
+            .create_tournament(NewTournament {
+                name: "Spring cup".to_string(),
+                start_date: date(-2),
+                end_date: date(5),
+                language: "en".to_string(),
+            })
+            .await
+            .unwrap();
+        let ended = repository
+            .create_tournament(NewTournament {
+                name: "Winter cup".to_string(),
+                start_date: "2025-01-01".to_string(),
+                end_date: "2025-01-07".to_string(),
+                language: "en".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let listed: Vec<ApiTournament> = call(&app, Method::GET, "/api/tournaments", None).await;
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].id, tournament.id);
+
+        // Two games in the tournament and one before it
+        let mut games = Vec::new();
+        for (days, sequence_number) in [(-1, 1), (-3, 2), (0, 3)] {
+            let mut new_game = create_new_test_game();
+            new_game.date = date(days);
+            new_game.sequence_number = sequence_number;
+            let (game, _) = repository
+                .create_game_with_answers(new_game, vec![], None)
+                .await
+                .unwrap();
+            games.push(game);
+        }
+
+        let mut players = Vec::new();
+        for cookie_token in ["first", "second", "third"] {
+            let user = repository
+                .create_user(NewUser {
+                    cookie_token: cookie_token.to_string(),
+                })
+                .await
+                .unwrap();
+            players.push(user);
+        }
+        let play = |user_id: &str, game_id: &str, total_score: i32| NewGameEntry {
+            user_id: user_id.to_string(),
+            game_id: game_id.to_string(),
+            answers_data: "[]".to_string(),
+            total_score,
+            completed: true,
+            suspicion_score: 0,
+            exclude: false,
+            request_id: None,
+        };
+        for entry in [
+            play(&players[0].id, &games[0].id, 20),
+            play(&players[0].id, &games[2].id, 15),
+            // Before the tournament starts
+            play(&players[1].id, &games[1].id, 50),
+            play(&players[1].id, &games[0].id, 35),
+        ] {
+            repository.create_or_update_game_entry(entry).await.unwrap();
+        }
+
+        let join_uri = format!("/api/tournaments/{}/join", tournament.id);
+        for (user, cookie_token) in players.iter().zip(["first", "second", "third"]) {
+            let body = format!(
+                r#"{{"user_id": "{}", "cookie_token": "{cookie_token}"}}"#,
+                user.id
+            );
+            let joined: JoinTournamentResponse =
+                call(&app, Method::POST, &join_uri, Some(&body)).await;
+            assert!(!joined.already_joined);
+        }
+        let body = format!(
+            r#"{{"user_id": "{}", "cookie_token": "first"}}"#,
+            players[0].id
+        );
+        let again: JoinTournamentResponse = call(&app, Method::POST, &join_uri, Some(&body)).await;
+        assert!(again.already_joined);
+
+        let uri = format!("/api/tournaments/{}/join", ended.id);
+        let request = create_test_request(Method::POST, &uri, Some(&body));
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let uri = format!(
+            "/api/tournaments/{}/standings?limit=1&user_id={}&cookie_token=second",
+            tournament.id, players[1].id
+        );
+        let standings: ApiTournamentStandings = call(&app, Method::GET, &uri, None).await;
+        assert!(!standings.tournament.finalized);
+        assert_eq!(standings.total_players, 3);
+        // Tied players are listed in the order they joined
+        assert_eq!(
+            standings.standings,
+            vec![ApiTournamentStanding {
+                rank: 1,
+                total_score: 35,
+                games_played: 2,
+                is_you: false,
+            }]
+        );
+        assert_eq!(
+            standings.you,
+            Some(ApiTournamentStanding {
+                rank: 1,
+                total_score: 35,
+                games_played: 1,
+                is_you: true,
+            })
+        );
+
+        // Finalized standings no longer follow entries
+        repository
+            .finalize_tournament(&tournament.id)
+            .await
+            .unwrap();
+        repository
+            .create_or_update_game_entry(play(&players[2].id, &games[2].id, 90))
+            .await
+            .unwrap();
+        let uri = format!(
+            "/api/tournaments/{}/standings?user_id={}&cookie_token=third",
+            tournament.id, players[2].id
+        );
+        let standings: ApiTournamentStandings = call(&app, Method::GET, &uri, None).await;
+        assert!(standings.tournament.finalized);
+        let totals: Vec<(i32, i32)> = standings
+            .standings
+            .iter()
+            .map(|standing| (standing.rank, standing.total_score))
+            .collect();
+        assert_eq!(totals, vec![(1, 35), (1, 35), (3, 0)]);
+        assert_eq!(standings.you.map(|you| you.rank), Some(3));
+
+        let request = create_test_request(Method::GET, "/api/tournaments/missing", None);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
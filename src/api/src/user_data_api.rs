@@ -0,0 +1,551 @@
+use anyhow::Context;
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{Extension, IntoResponse, Json, Response},
+    routing::{delete, get},
+    Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::account_api::ApiPasskey;
+use crate::api_error::ApiError;
+use crate::audit::AuditActor;
+use crate::db::{
+    conversions::AnswerStorage,
+    models::{DbCustomGameScore, DbUser, DbUserDeletion, DbUserEntry, DbUserSession},
+    Repository,
+};
+use crate::game::mode::GameMode;
+use crate::history_api::Streak;
+use crate::http_api::{authenticate_user, ApiAnswer, ApiState};
+use crate::notification_api::{ApiNotification, ApiNotificationPreferences};
+use crate::security::{session::SessionInfo, user_cookie::UserIdentity};
+use crate::user_stats_api::ApiUserStats;
+
+#[derive(Deserialize, Debug)]
+pub struct UserDataQuery {
+    pub cookie_token: Option<String>,
+}
+
+/// Everything stored about a user, as downloaded from the export
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiUserExport {
+    pub exported_at: String,
+    pub profile: ApiExportProfile,
+    /// Every game the user has an entry for, in any mode, oldest first
+    pub entries: Vec<ApiExportEntry>,
+    pub custom_game_scores: Vec<ApiExportCustomGameScore>,
+    pub stats: ApiUserStats,
+    pub streaks: ApiExportStreaks,
+    pub notifications: Vec<ApiNotification>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiExportProfile {
+    pub user_id: String,
+    pub created_at: String,
+    pub last_seen: String,
+    /// None unless the user confirmed an address for their account
+    pub email: Option<String>,
+    pub passkeys: Vec<ApiPasskey>,
+    pub sessions: Vec<ApiExportSession>,
+    pub notification_preferences: ApiNotificationPreferences,
+    pub discord_user_ids: Vec<String>,
+}
+
+/// A browser session the user played from. Only hashes of the session id
+/// and user agent are ever stored.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiExportSession {
+    pub id: String,
+    pub user_agent_hash: Option<String>,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub revoked_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiExportEntry {
+    pub game_id: String,
+    pub date: String,
+    pub mode: String,
+    pub sequence_number: i32,
+    pub answers: Vec<ApiAnswer>,
+    pub score: i32,
+    pub completed: bool,
+    /// Finished after the game's stats were frozen
+    pub off_board: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiExportCustomGameScore {
+    pub custom_game_id: String,
+    pub answers: Vec<ApiAnswer>,
+    pub score: i32,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Runs of consecutive days the user completed the daily game
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+pub struct ApiExportStreaks {
+    pub longest: i32,
+    /// The run ending with the most recent daily game the user completed
+    pub latest: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ApiUserDeletion {
+    /// Completed entries kept, without their answers, so game stats don't change
+    pub anonymized_entries: u64,
+    pub deleted_entries: u64,
+}
+
+/// Routes for users to download or delete everything stored about them
+pub fn user_data_router<R: Repository + Clone + Send + Sync + 'static>() -> Router<ApiState<R>> {
+    Router::new()
+        .route("/api/user/:user_id/export", get(export_user_data))
+        .route("/api/user/:user_id", delete(delete_user_data))
+}
+
+fn answers(answers_data: &str, what: &str) -> Vec<ApiAnswer> {
+    AnswerStorage::deserialize_to_api_answers(answers_data).unwrap_or_else(|e| {
+        warn!("Unreadable answers exporting {}: {}", what, e);
+        Vec::new()
+    })
+}
+
+impl From<DbUserEntry> for ApiExportEntry {
+    fn from(entry: DbUserEntry) -> Self {
+        ApiExportEntry {
+            answers: answers(&entry.answers_data, &format!("game {}", entry.game_id)),
+            game_id: entry.game_id,
+            date: entry.date,
+            mode: entry.mode,
+            sequence_number: entry.sequence_number,
+            score: entry.total_score,
+            completed: entry.completed,
+            off_board: entry.off_board,
+            created_at: entry.created_at.to_rfc3339(),
+            updated_at: entry.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+impl From<DbCustomGameScore> for ApiExportCustomGameScore {
+    fn from(score: DbCustomGameScore) -> Self {
+        ApiExportCustomGameScore {
+            answers: answers(
+                &score.answers_data,
+                &format!("custom game {}", score.custom_game_id),
+            ),
+            custom_game_id: score.custom_game_id,
+            score: score.total_score,
+            created_at: score.created_at.to_rfc3339(),
+            updated_at: score.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+impl From<DbUserSession> for ApiExportSession {
+    fn from(session: DbUserSession) -> Self {
+        ApiExportSession {
+            id: session.id,
+            user_agent_hash: session.user_agent_hash,
+            first_seen: session.first_seen.to_rfc3339(),
+            last_seen: session.last_seen.to_rfc3339(),
+            revoked_at: session.revoked_at.map(|dt| dt.to_rfc3339()),
+        }
+    }
+}
+
+impl From<DbUserDeletion> for ApiUserDeletion {
+    fn from(deletion: DbUserDeletion) -> Self {
+        ApiUserDeletion {
+            anonymized_entries: deletion.anonymized_entries,
+            deleted_entries: deletion.deleted_entries,
+        }
+    }
+}
+
+/// Streaks over the user's daily entries, which come oldest first
+fn streaks(entries: &[DbUserEntry]) -> ApiExportStreaks {
+    let mut streak = Streak::default();
+    let mut streaks = ApiExportStreaks::default();
+    for entry in entries
+        .iter()
+        .filter(|entry| entry.mode == GameMode::Daily.as_str())
+    {
+        let length = streak.record(&entry.date, entry.completed);
+        if entry.completed {
+            streaks.latest = length;
+        }
+        streaks.longest = streaks.longest.max(length);
+    }
+    streaks
+}
+
+/// Authenticate the request as the user named in the path. Exports and
+/// deletions only ever act on the caller's own data.
+async fn authenticate_owner<R: Repository>(
+    state: &ApiState<R>,
+    identity: &UserIdentity,
+    user_id: &String,
+    cookie_token: Option<&String>,
+    session: Option<&SessionInfo>,
+) -> Result<DbUser, ApiError> {
+    let user = authenticate_user(state, identity, Some(user_id), cookie_token, session).await?;
+    if user.id != *user_id {
+        return Err(ApiError::forbidden("Signed in as a different user"));
+    }
+    Ok(user)
+}
+
+/// Download the user's profile, every entry with its answers, their stats
+/// and streaks as one JSON document
+async fn export_user_data<R: Repository + Clone + Send + Sync + 'static>(
+    Path(user_id): Path<String>,
+    Query(query): Query<UserDataQuery>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+) -> Result<Response, ApiError> {
+    let user = authenticate_owner(
+        &state,
+        &identity,
+        &user_id,
+        query.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+    let repository = &state.repository;
+
+    let account = repository
+        .get_account_by_user(&user.id)
+        .await
+        .context("Failed to get account")?;
+    let passkeys = match &account {
+        Some(account) => repository
+            .get_account_passkeys(&account.id)
+            .await
+            .context("Failed to get passkeys")?,
+        None => Vec::new(),
+    };
+    let sessions = repository
+        .get_user_sessions(&user.id)
+        .await
+        .context("Failed to get sessions")?;
+    let notification_preferences = repository
+        .get_notification_preferences(&user.id)
+        .await
+        .context("Failed to get notification preferences")?;
+    let discord_user_ids = repository
+        .get_linked_discord_users(&user.id)
+        .await
+        .context("Failed to get Discord links")?;
+    let entries = repository
+        .get_user_entries(&user.id)
+        .await
+        .context("Failed to get entries")?;
+    let custom_game_scores = repository
+        .get_user_custom_game_scores(&user.id)
+        .await
+        .context("Failed to get custom game scores")?;
+    let notifications = repository
+        .get_notifications(&user.id, i32::MAX)
+        .await
+        .context("Failed to get notifications")?;
+    let stats = state
+        .user_stats
+        .get(repository, &user.id)
+        .await
+        .with_context(|| format!("Failed to compute stats for user {}", user.id))?;
+
+    let export = ApiUserExport {
+        exported_at: Utc::now().to_rfc3339(),
+        profile: ApiExportProfile {
+            user_id: user.id,
+            created_at: user.created_at.to_rfc3339(),
+            last_seen: user.last_seen.to_rfc3339(),
+            email: account.and_then(|account| account.email),
+            passkeys: passkeys.into_iter().map(ApiPasskey::from).collect(),
+            sessions: sessions.into_iter().map(ApiExportSession::from).collect(),
+            notification_preferences: notification_preferences.into(),
+            discord_user_ids,
+        },
+        streaks: streaks(&entries),
+        entries: entries.into_iter().map(ApiExportEntry::from).collect(),
+        custom_game_scores: custom_game_scores
+            .into_iter()
+            .map(ApiExportCustomGameScore::from)
+            .collect(),
+        stats,
+        notifications: notifications
+            .into_iter()
+            .map(ApiNotification::from)
+            .collect(),
+    };
+
+    Ok((
+        [(
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"pathfinder-data.json\"",
+        )],
+        Json(export),
+    )
+        .into_response())
+}
+
+/// Delete the user and everything stored about them, signing the device out.
+/// Completed entries stay behind anonymously so other players' ranks and
+/// percentiles, and the stats of games not yet frozen, don't shift.
+async fn delete_user_data<R: Repository + Clone + Send + Sync + 'static>(
+    Path(user_id): Path<String>,
+    Query(query): Query<UserDataQuery>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+) -> Result<Json<ApiUserDeletion>, ApiError> {
+    let user = authenticate_owner(
+        &state,
+        &identity,
+        &user_id,
+        query.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+
+    let deletion = state
+        .repository
+        .delete_user(&user.id)
+        .await
+        .with_context(|| format!("Failed to delete user {}", user.id))?;
+    state.user_stats.invalidate(&user.id).await;
+    identity.clear();
+    info!(
+        "Deleted user {} ({} entries anonymized, {} deleted)",
+        user.id, deletion.anonymized_entries, deletion.deleted_entries
+    );
+    state
+        .audit
+        .record(
+            &AuditActor::User(user.id.clone()),
+            "user.delete",
+            Some(&user.id),
+            None,
+            serde_json::to_value(deletion).ok(),
+        )
+        .await;
+
+    Ok(Json(deletion.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn daily(date: &str, completed: bool) -> DbUserEntry {
+        DbUserEntry {
+            game_id: format!("game-{date}"),
+            date: date.to_string(),
+            mode: GameMode::Daily.as_str().to_string(),
+            sequence_number: 1,
+            answers_data: String::new(),
+            total_score: 10,
+            completed,
+            off_board: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_streaks_follow_daily_games() {
+        let mut blitz = daily("2025-06-03", true);
+        blitz.mode = GameMode::Blitz.as_str().to_string();
+        let entries = [
+            daily("2025-06-01", true),
+            daily("2025-06-02", true),
+            blitz,
+            daily("2025-06-03", true),
+            daily("2025-06-05", true),
+            daily("2025-06-06", false),
+        ];
+
+        assert_eq!(
+            streaks(&entries),
+            ApiExportStreaks {
+                longest: 3,
+                latest: 1
+            }
+        );
+        assert_eq!(streaks(&[]), ApiExportStreaks::default());
+    }
+
+    /// Export then delete a player who shares a game with someone else,
+    /// checking the other player's standing survives the deletion
+    async fn check_export_then_delete<R: Repository + Clone + Send + Sync + 'static>(
+        state: ApiState<R>,
+        app: axum::Router,
+    ) {
+        use crate::db::models::{NewGameEntry, NewUser};
+        use crate::test_utils::{create_new_test_game, create_test_request};
+        use axum::http::{Method, StatusCode};
+        use tower::util::ServiceExt;
+
+        let repo = &state.repository;
+        let player = repo
+            .create_user(NewUser {
+                cookie_token: "player".to_string(),
+            })
+            .await
+            .unwrap();
+        let other = repo
+            .create_user(NewUser {
+                cookie_token: "other".to_string(),
+            })
+            .await
+            .unwrap();
+        let account = repo.get_or_create_account(&player.id).await.unwrap();
+        repo.set_account_email(&account.id, "player@example.com")
+            .await
+            .unwrap();
+
+        let answers = vec![ApiAnswer {
+            word: "stop".to_string(),
+            score: 4,
+            path: None,
+        }];
+        let answers_data = AnswerStorage::serialize_api_answers(&answers).unwrap();
+        let mut game_ids = Vec::new();
+        for day in 1..=2 {
+            let mut new_game = create_new_test_game();
+            new_game.date = format!("2025-06-0{day}");
+            new_game.sequence_number = day;
+            let (game, _) = repo
+                .create_game_with_answers(new_game, vec![], None)
+                .await
+                .unwrap();
+            game_ids.push(game.id);
+        }
+        // The player finished the first game and is part way through the second
+        for (user_id, game_id, total_score, completed) in [
+            (&player.id, &game_ids[0], 20, true),
+            (&other.id, &game_ids[0], 10, true),
+            (&player.id, &game_ids[1], 4, false),
+        ] {
+            repo.create_or_update_game_entry(NewGameEntry {
+                user_id: user_id.clone(),
+                game_id: game_id.clone(),
+                answers_data: answers_data.clone(),
+                total_score,
+                completed,
+                suspicion_score: 0,
+                exclude: false,
+                request_id: None,
+            })
+            .await
+            .unwrap();
+        }
+
+        let uri = format!("/api/user/{}/export?cookie_token=player", player.id);
+        let response = app
+            .clone()
+            .oneshot(create_test_request(Method::GET, &uri, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let export: ApiUserExport = serde_json::from_slice(&body).unwrap();
+        assert_eq!(export.profile.user_id, player.id);
+        assert_eq!(export.profile.email.as_deref(), Some("player@example.com"));
+        assert_eq!(export.entries.len(), 2);
+        assert_eq!(export.entries[0].answers, answers);
+        assert_eq!(export.entries[0].score, 20);
+        assert!(!export.entries[1].completed);
+        assert_eq!(export.stats.games_played, 1);
+        assert_eq!(
+            export.streaks,
+            ApiExportStreaks {
+                longest: 1,
+                latest: 1
+            }
+        );
+
+        // Credentials for one user can't be used on another's data
+        let uri = format!("/api/user/{}?cookie_token=other", player.id);
+        let response = app
+            .clone()
+            .oneshot(create_test_request(Method::DELETE, &uri, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let uri = format!("/api/user/{}?cookie_token=player", player.id);
+        let response = app
+            .clone()
+            .oneshot(create_test_request(Method::DELETE, &uri, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let deletion: ApiUserDeletion = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            deletion,
+            ApiUserDeletion {
+                anonymized_entries: 1,
+                deleted_entries: 1
+            }
+        );
+
+        assert!(repo.get_user_by_id(&player.id).await.unwrap().is_none());
+        assert!(repo
+            .get_account_by_email("player@example.com")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(repo.get_user_entries(&player.id).await.unwrap().is_empty());
+        // The finished game still counts the player's score, without their answers
+        let mut scores = repo.get_score_distribution(&game_ids[0]).await.unwrap();
+        scores.sort();
+        assert_eq!(scores, vec![10, 20]);
+        let (total_players, rank, ..) = repo.get_game_stats(&game_ids[0], 10).await.unwrap();
+        assert_eq!((total_players, rank), (2, 2));
+        assert_eq!(
+            repo.get_player_counts(&game_ids[1])
+                .await
+                .unwrap()
+                .in_progress,
+            0
+        );
+
+        let uri = format!("/api/user/{}/export?cookie_token=player", player.id);
+        let response = app
+            .oneshot(create_test_request(Method::GET, &uri, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_export_then_delete() {
+        let (state, app) = crate::test_utils::setup_memory_app();
+        check_export_then_delete(state, app).await;
+    }
+
+    #[cfg(feature = "database-tests")]
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_export_then_delete_in_sqlite(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let (state, app) = crate::test_utils::setup_app(pool).await;
+        check_export_then_delete(state, app).await;
+    }
+}
@@ -0,0 +1,292 @@
+use anyhow::Context;
+use axum::{
+    extract::{Path, Query, State},
+    response::{Extension, Json},
+    routing::get,
+    Router,
+};
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, time::Duration};
+use tracing::warn;
+
+use crate::api_error::ApiError;
+use crate::db::{conversions::AnswerStorage, Repository};
+use crate::http_api::{authenticate_user, ApiState};
+use crate::security::{session::SessionInfo, user_cookie::UserIdentity};
+
+/// How long computed stats are served before being recomputed. A user's own
+/// submissions clear their entry straight away; the wait only delays changes
+/// to their percentiles from other players finishing the same games.
+const USER_STATS_TTL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ApiUserStats {
+    pub games_played: i32,
+    pub average_score: f64,
+    pub best_score: i32,
+    pub average_percentile: f64,
+    /// How many found words there are of each length, shortest first
+    pub word_lengths: Vec<ApiWordLengthCount>,
+    /// Share of completed games where the threshold score was reached, 0 to 1
+    pub threshold_hit_rate: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ApiWordLengthCount {
+    pub length: usize,
+    pub count: usize,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UserStatsQuery {
+    pub cookie_token: Option<String>,
+}
+
+/// UserStats computes a user's historical aggregates and keeps them, since
+/// every computation scans all of the user's entries
+#[derive(Clone)]
+pub struct UserStats {
+    users: Cache<String, ApiUserStats>,
+}
+
+impl Default for UserStats {
+    fn default() -> Self {
+        Self {
+            users: Cache::builder()
+                .max_capacity(10_000)
+                .time_to_live(USER_STATS_TTL)
+                .build(),
+        }
+    }
+}
+
+impl UserStats {
+    pub async fn get<R: Repository>(
+        &self,
+        repository: &R,
+        user_id: &str,
+    ) -> anyhow::Result<ApiUserStats> {
+        if let Some(stats) = self.users.get(user_id).await {
+            return Ok(stats);
+        }
+
+        let stats = compute_user_stats(repository, user_id).await?;
+        self.users.insert(user_id.to_string(), stats.clone()).await;
+        Ok(stats)
+    }
+
+    /// Forget a user's stats after they complete a game or their history
+    /// otherwise changes
+    pub async fn invalidate(&self, user_id: &str) {
+        self.users.invalidate(user_id).await;
+    }
+}
+
+async fn compute_user_stats<R: Repository>(
+    repository: &R,
+    user_id: &str,
+) -> anyhow::Result<ApiUserStats> {
+    let aggregates = repository.get_user_stats(user_id).await?;
+
+    let mut lengths: BTreeMap<usize, usize> = BTreeMap::new();
+    for answers_data in repository.get_completed_answers_data(user_id).await? {
+        match AnswerStorage::deserialize_to_api_answers(&answers_data) {
+            Ok(answers) => {
+                for answer in answers {
+                    *lengths.entry(answer.word.chars().count()).or_default() += 1;
+                }
+            }
+            Err(e) => warn!("Skipping unreadable answers for user {}: {}", user_id, e),
+        }
+    }
+
+    let threshold_hit_rate = if aggregates.games_played > 0 {
+        aggregates.threshold_hits as f64 / aggregates.games_played as f64
+    } else {
+        0.0
+    };
+
+    Ok(ApiUserStats {
+        games_played: aggregates.games_played,
+        average_score: aggregates.average_score.unwrap_or(0.0),
+        best_score: aggregates.best_score.unwrap_or(0),
+        average_percentile: aggregates.average_percentile.unwrap_or(0.0),
+        word_lengths: lengths
+            .into_iter()
+            .map(|(length, count)| ApiWordLengthCount { length, count })
+            .collect(),
+        threshold_hit_rate,
+    })
+}
+
+/// Routes for a user's historical stats
+pub fn user_stats_router<R: Repository + Clone + Send + Sync + 'static>() -> Router<ApiState<R>> {
+    Router::new().route("/api/user/:user_id/stats", get(get_user_stats))
+}
+
+async fn get_user_stats<R: Repository + Clone + Send + Sync + 'static>(
+    Path(user_id): Path<String>,
+    Query(query): Query<UserStatsQuery>,
+    State(state): State<ApiState<R>>,
+    session: Option<Extension<SessionInfo>>,
+    identity: UserIdentity,
+) -> Result<Json<ApiUserStats>, ApiError> {
+    let user = authenticate_user(
+        &state,
+        &identity,
+        Some(&user_id),
+        query.cookie_token.as_ref(),
+        session.as_deref(),
+    )
+    .await?;
+
+    let stats = state
+        .user_stats
+        .get(&state.repository, &user.id)
+        .await
+        .with_context(|| format!("Failed to compute stats for user {}", user.id))?;
+    Ok(Json(stats))
+}
+
+#[cfg(all(test, feature = "database-tests"))]
+mod tests {
+    use super::*;
+    use axum::http::{Method, StatusCode};
+    use tower::util::ServiceExt;
+
+    use crate::db::models::{NewGameEntry, NewUser};
+    use crate::http_api::ApiAnswer;
+    use crate::test_utils::{create_new_test_game, create_test_request, setup_app};
+
+    async fn get_stats(app: &Router, user_id: &str, cookie_token: &str) -> (StatusCode, Vec<u8>) {
+        let uri = format!("/api/user/{user_id}/stats?cookie_token={cookie_token}");
+        let response = app
+            .clone()
+            .oneshot(create_test_request(Method::GET, &uri, None))
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, bytes.to_vec())
+    }
+
+    fn answers(words: &[&str]) -> String {
+        let answers: Vec<ApiAnswer> = words
+            .iter()
+            .map(|word| ApiAnswer {
+                word: word.to_string(),
+                score: 0,
+                path: None,
+            })
+            .collect();
+        AnswerStorage::serialize_api_answers(&answers).unwrap()
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_user_stats_aggregate_history(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let (state, app) = setup_app(pool).await;
+        let repo = &state.repository;
+        let player = repo
+            .create_user(NewUser {
+                cookie_token: "player".to_string(),
+            })
+            .await
+            .unwrap();
+        let rival = repo
+            .create_user(NewUser {
+                cookie_token: "rival".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let (status, body) = get_stats(&app, &player.id, "player").await;
+        assert_eq!(status, StatusCode::OK);
+        let empty: ApiUserStats = serde_json::from_slice(&body).unwrap();
+        assert_eq!(empty.games_played, 0);
+        assert!(empty.word_lengths.is_empty());
+
+        // Two games with a threshold of 40; the player beats the rival on
+        // the first and loses on the second
+        let mut game_ids = Vec::new();
+        for (sequence_number, date) in [(1, "2024-01-01"), (2, "2024-01-02")] {
+            let mut new_game = create_new_test_game();
+            new_game.sequence_number = sequence_number;
+            new_game.date = date.to_string();
+            new_game.threshold_score = 40;
+            let (game, _) = repo
+                .create_game_with_answers(new_game, vec![], None)
+                .await
+                .unwrap();
+            game_ids.push(game.id);
+        }
+        let entries = [
+            (&player.id, &game_ids[0], 50, answers(&["cat", "stop"])),
+            (&rival.id, &game_ids[0], 20, answers(&[])),
+            (&player.id, &game_ids[1], 30, answers(&["dog", "stops"])),
+            (&rival.id, &game_ids[1], 60, answers(&[])),
+        ];
+        for (user_id, game_id, total_score, answers_data) in entries {
+            repo.create_or_update_game_entry(NewGameEntry {
+                user_id: user_id.clone(),
+                game_id: game_id.clone(),
+                answers_data,
+                total_score,
+                completed: true,
+                suspicion_score: 0,
+                exclude: false,
+                request_id: None,
+            })
+            .await
+            .unwrap();
+        }
+
+        // The cached empty stats stand until the user's entry is cleared
+        let (_, body) = get_stats(&app, &player.id, "player").await;
+        let cached: ApiUserStats = serde_json::from_slice(&body).unwrap();
+        assert_eq!(cached, empty);
+
+        state.user_stats.invalidate(&player.id).await;
+        let (_, body) = get_stats(&app, &player.id, "player").await;
+        let stats: ApiUserStats = serde_json::from_slice(&body).unwrap();
+        assert_eq!(stats.games_played, 2);
+        assert_eq!(stats.average_score, 40.0);
+        assert_eq!(stats.best_score, 50);
+        assert_eq!(stats.average_percentile, 75.0);
+        assert_eq!(stats.threshold_hit_rate, 0.5);
+        assert_eq!(
+            stats.word_lengths,
+            vec![
+                ApiWordLengthCount {
+                    length: 3,
+                    count: 2
+                },
+                ApiWordLengthCount {
+                    length: 4,
+                    count: 1
+                },
+                ApiWordLengthCount {
+                    length: 5,
+                    count: 1
+                },
+            ]
+        );
+    }
+
+    #[sqlx::test(migrations = "migrations/sqlite")]
+    async fn test_user_stats_require_the_users_token(pool: sqlx::Pool<sqlx::Sqlite>) {
+        let (state, app) = setup_app(pool).await;
+        let user = state
+            .repository
+            .create_user(NewUser {
+                cookie_token: "secret".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let (status, _) = get_stats(&app, &user.id, "guess").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,229 @@
+use anyhow::Context;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    routing::{delete, post},
+    Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use url::{Host, Url};
+
+use crate::api_error::ApiError;
+use crate::db::{models::NewWebhookSubscription, Repository};
+use crate::http_api::ApiState;
+use crate::notifier::WebhookKind;
+use crate::security::utils::{constant_time_eq, generate_token};
+
+/// Subscriptions the server will hold at once, so registering can't be used
+/// to make it send unbounded requests every day
+const MAX_SUBSCRIPTIONS: usize = 1000;
+
+/// Hosts Discord serves channel webhooks from
+const DISCORD_HOSTS: [&str; 2] = ["discord.com", "discordapp.com"];
+
+#[derive(Deserialize, Debug)]
+pub struct NewWebhookRequest {
+    pub kind: WebhookKind,
+    pub url: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DeleteWebhookRequest {
+    pub secret: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiWebhookSubscription {
+    pub id: String,
+    pub kind: WebhookKind,
+    pub url: String,
+    /// Signs every delivery, and is needed to unsubscribe. It is only ever
+    /// returned here, when the subscription is made.
+    pub secret: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Routes for services to subscribe to new daily games
+pub fn webhook_router<R: Repository + Clone + Send + Sync + 'static>() -> Router<ApiState<R>> {
+    Router::new()
+        .route("/api/webhooks", post(create_webhook))
+        .route("/api/webhooks/:id", delete(delete_webhook))
+}
+
+/// Subscribe a URL to new daily games
+async fn create_webhook<R: Repository + Clone + Send + Sync + 'static>(
+    State(state): State<ApiState<R>>,
+    Json(request): Json<NewWebhookRequest>,
+) -> Result<Json<ApiWebhookSubscription>, ApiError> {
+    let url =
+        validate_webhook_url(request.kind, request.url.trim()).map_err(ApiError::validation)?;
+
+    let subscriptions = state
+        .repository
+        .get_webhook_subscriptions()
+        .await
+        .context("Failed to count webhook subscriptions")?;
+    if subscriptions.len() >= MAX_SUBSCRIPTIONS {
+        return Err(ApiError::conflict("No more webhooks can be registered"));
+    }
+
+    let subscription = state
+        .repository
+        .create_webhook_subscription(NewWebhookSubscription {
+            kind: request.kind.to_string(),
+            url: url.to_string(),
+            secret: generate_token(),
+        })
+        .await
+        .context("Failed to store webhook subscription")?;
+
+    Ok(Json(ApiWebhookSubscription {
+        id: subscription.id,
+        kind: request.kind,
+        url: subscription.url,
+        secret: subscription.secret,
+        created_at: subscription.created_at,
+    }))
+}
+
+/// Unsubscribe, proving ownership with the subscription's secret. A wrong
+/// secret looks the same as an unknown id.
+async fn delete_webhook<R: Repository + Clone + Send + Sync + 'static>(
+    Path(id): Path<String>,
+    State(state): State<ApiState<R>>,
+    Json(request): Json<DeleteWebhookRequest>,
+) -> Result<StatusCode, ApiError> {
+    let subscription = state
+        .repository
+        .get_webhook_subscription(&id)
+        .await
+        .context("Failed to get webhook subscription")?
+        .filter(|subscription| {
+            constant_time_eq(subscription.secret.as_bytes(), request.secret.as_bytes())
+        })
+        .ok_or_else(|| ApiError::not_found("Webhook"))?;
+
+    state
+        .repository
+        .delete_webhook_subscription(&subscription.id)
+        .await
+        .context("Failed to delete webhook subscription")?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Check a URL can be subscribed: https to a public host name, and for
+/// Discord, one of Discord's webhook URLs
+fn validate_webhook_url(kind: WebhookKind, url: &str) -> Result<Url, String> {
+    let url = Url::parse(url).map_err(|e| format!("Invalid webhook URL: {e}"))?;
+    if url.scheme() != "https" {
+        return Err("Webhook URLs must use https".to_string());
+    }
+
+    // Addresses and local names could point deliveries into our own network
+    let host = match url.host() {
+        Some(Host::Domain(host)) => host.to_ascii_lowercase(),
+        _ => return Err("Webhook URLs must use a host name".to_string()),
+    };
+    let host = host.trim_end_matches('.');
+    if host == "localhost"
+        || !host.contains('.')
+        || [".localhost", ".local", ".internal"]
+            .iter()
+            .any(|suffix| host.ends_with(suffix))
+    {
+        return Err("Webhook URLs must point to a public host".to_string());
+    }
+
+    if kind == WebhookKind::Discord
+        && !(DISCORD_HOSTS.contains(&host) && url.path().starts_with("/api/webhooks/"))
+    {
+        return Err("Discord webhooks must be a discord.com/api/webhooks URL".to_string());
+    }
+
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Method;
+    use tower::util::ServiceExt;
+
+    use crate::test_utils::{create_test_request, setup_memory_app};
+
+    #[test]
+    fn test_validate_webhook_url() {
+        let generic = |url| validate_webhook_url(WebhookKind::Generic, url);
+        assert!(generic("https://hooks.example.com/pathfinder").is_ok());
+        assert!(generic("http://hooks.example.com/pathfinder").is_err());
+        assert!(generic("https://127.0.0.1/hook").is_err());
+        assert!(generic("https://[::1]/hook").is_err());
+        assert!(generic("https://localhost/hook").is_err());
+        assert!(generic("https://db.internal/hook").is_err());
+        assert!(generic("https://intranet/hook").is_err());
+        assert!(generic("not a url").is_err());
+
+        let discord = |url| validate_webhook_url(WebhookKind::Discord, url);
+        assert!(discord("https://discord.com/api/webhooks/1/abc").is_ok());
+        assert!(discord("https://discordapp.com/api/webhooks/1/abc").is_ok());
+        assert!(discord("https://discord.com/channels/1").is_err());
+        assert!(discord("https://hooks.example.com/api/webhooks/1/abc").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_and_unsubscribe_with_secret() {
+        let (state, app) = setup_memory_app();
+        let body = r#"{"kind":"discord","url":"https://discord.com/api/webhooks/1/abc"}"#;
+        let response = app
+            .clone()
+            .oneshot(create_test_request(
+                Method::POST,
+                "/api/webhooks",
+                Some(body),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let subscription: ApiWebhookSubscription = serde_json::from_slice(&body).unwrap();
+        assert_eq!(subscription.kind, WebhookKind::Discord);
+
+        let uri = format!("/api/webhooks/{}", subscription.id);
+        let unsubscribe = |secret: &str| {
+            let body = serde_json::json!({ "secret": secret }).to_string();
+            let app = app.clone();
+            let uri = uri.clone();
+            async move {
+                app.oneshot(create_test_request(Method::DELETE, &uri, Some(&body)))
+                    .await
+                    .unwrap()
+                    .status()
+            }
+        };
+        assert_eq!(unsubscribe("wrong").await, StatusCode::NOT_FOUND);
+        assert_eq!(
+            state
+                .repository
+                .get_webhook_subscriptions()
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        assert_eq!(
+            unsubscribe(&subscription.secret).await,
+            StatusCode::NO_CONTENT
+        );
+        assert!(state
+            .repository
+            .get_webhook_subscriptions()
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,97 @@
+use moka::future::Cache;
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use crate::db::{models::DbWordCount, Repository};
+
+/// How long a game's counts are served before being recounted. Counting
+/// aggregates every answer on the game, and players finishing meanwhile only
+/// nudge the percentages.
+const WORD_POPULARITY_TTL: Duration = Duration::from_secs(60);
+
+/// WordPopularity is how many of the players who completed a game found each
+/// word
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WordPopularity {
+    pub players: usize,
+    found: HashMap<String, usize>,
+}
+
+impl WordPopularity {
+    /// `players` completed the game, and `counts` are how many found each
+    /// word
+    pub fn new(players: usize, counts: Vec<DbWordCount>) -> Self {
+        Self {
+            players,
+            found: counts
+                .into_iter()
+                .map(|count| (count.word, count.players.max(0) as usize))
+                .collect(),
+        }
+    }
+
+    /// The percentage, 0 to 100, of players who found `word`
+    pub fn found_by(&self, word: &str) -> f32 {
+        if self.players == 0 {
+            return 0.0;
+        }
+        let found = self.found.get(word).copied().unwrap_or(0);
+        100.0 * found as f32 / self.players as f32
+    }
+}
+
+/// WordPopularityCache keeps each game's counts for a short while, since the
+/// word stats that show them are requested by every player reviewing a game
+#[derive(Clone)]
+pub struct WordPopularityCache {
+    games: Cache<String, Arc<WordPopularity>>,
+}
+
+impl Default for WordPopularityCache {
+    fn default() -> Self {
+        Self {
+            games: Cache::builder()
+                .max_capacity(1000)
+                .time_to_live(WORD_POPULARITY_TTL)
+                .build(),
+        }
+    }
+}
+
+impl WordPopularityCache {
+    pub async fn get<R: Repository>(
+        &self,
+        repository: &R,
+        game_id: &str,
+    ) -> anyhow::Result<Arc<WordPopularity>> {
+        if let Some(popularity) = self.games.get(game_id).await {
+            return Ok(popularity);
+        }
+
+        let players = repository.get_score_distribution(game_id).await?.len();
+        let counts = repository.get_word_counts(game_id).await?;
+        let popularity = Arc::new(WordPopularity::new(players, counts));
+        self.games
+            .insert(game_id.to_string(), popularity.clone())
+            .await;
+        Ok(popularity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_found_by() {
+        let count = |word: &str, players| DbWordCount {
+            word: word.to_string(),
+            players,
+        };
+        let popularity = WordPopularity::new(4, vec![count("stop", 4), count("tops", 1)]);
+
+        assert_eq!(popularity.found_by("stop"), 100.0);
+        assert_eq!(popularity.found_by("tops"), 25.0);
+        assert_eq!(popularity.found_by("post"), 0.0);
+        assert_eq!(WordPopularity::default().found_by("stop"), 0.0);
+    }
+}
@@ -0,0 +1,256 @@
+use anyhow::{anyhow, Result};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::env;
+use std::fmt;
+use std::path::Path;
+use tracing::warn;
+
+use super::formats::{entry_lines, read_source};
+use super::normalize_word;
+
+/// Endings that turn a denied word into another form of it, so a deny list
+/// only has to name the base word
+const INFLECTIONS: [&str; 7] = ["s", "es", "ed", "ing", "er", "ers", "y"];
+
+/// Where the server reads the corpus rarity bonuses are judged against unless
+/// WORD_FREQUENCY_PATH says otherwise
+pub const WORD_FREQUENCY_PATH: &str = "wordlist.frequency";
+
+/// FrequencyCorpus holds how often each word occurs in a reference corpus,
+/// such as a subtitle or ngram frequency list
+#[derive(Debug, Clone, Default)]
+pub struct FrequencyCorpus {
+    counts: HashMap<String, u64>,
+}
+
+impl FrequencyCorpus {
+    /// parse reads one `word count` pair per line, separated by whitespace,
+    /// a comma or a tab. Counts for words that normalize to the same form are
+    /// added together.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut counts = HashMap::new();
+        for line in entry_lines(text) {
+            let mut fields = line
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|field| !field.is_empty());
+            let (Some(raw), Some(count)) = (fields.next(), fields.next()) else {
+                return Err(anyhow!("Frequency line is not `word count`: {line}"));
+            };
+            let count: u64 = count
+                .parse()
+                .map_err(|_| anyhow!("Frequency line has a bad count: {line}"))?;
+            if let Some(word) = normalize_word(raw) {
+                *counts.entry(word).or_insert(0) += count;
+            }
+        }
+        Ok(Self { counts })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::parse(&read_source(path)?)
+    }
+
+    /// from_env loads the corpus at WORD_FREQUENCY_PATH, or None when there
+    /// isn't a readable one there
+    pub fn from_env() -> Option<Self> {
+        let path = env::var("WORD_FREQUENCY_PATH").unwrap_or(WORD_FREQUENCY_PATH.to_string());
+        let path = Path::new(&path);
+        if !path.exists() {
+            return None;
+        }
+        Self::load(path)
+            .inspect_err(|e| warn!("Ignoring word frequencies in {}: {}", path.display(), e))
+            .ok()
+    }
+
+    /// How often a word occurs; 0 for words the corpus never saw
+    pub fn count(&self, word: &str) -> u64 {
+        self.counts.get(word).copied().unwrap_or(0)
+    }
+}
+
+/// DenyList holds offensive words that must never be answers
+#[derive(Debug, Clone, Default)]
+pub struct DenyList {
+    words: HashSet<String>,
+}
+
+impl DenyList {
+    /// parse reads one word per line
+    pub fn parse(text: &str) -> Self {
+        Self {
+            words: entry_lines(text).filter_map(normalize_word).collect(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(Self::parse(&read_source(path)?))
+    }
+
+    /// denies matches a denied word or one of its inflections
+    pub fn denies(&self, word: &str) -> bool {
+        self.words.contains(word)
+            || INFLECTIONS.iter().any(|ending| {
+                word.strip_suffix(ending)
+                    .is_some_and(|base| self.words.contains(base))
+            })
+    }
+}
+
+/// CurationReport counts what happened to the raw words on their way into
+/// the curated list. Every count but `ingested` is of distinct words.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CurationReport {
+    /// raw tokens read from the sources
+    pub ingested: usize,
+    /// tokens that can't be played on the board
+    pub unplayable: usize,
+    pub denied: usize,
+    /// words below the minimum frequency
+    pub rare: usize,
+    pub kept: usize,
+}
+
+impl fmt::Display for CurationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} tokens ingested: {} unplayable, {} denied, {} too rare, {} kept",
+            self.ingested, self.unplayable, self.denied, self.rare, self.kept
+        )
+    }
+}
+
+/// Curator turns raw dictionaries into the wordlist the game engine loads:
+/// playable words only, without offensive words, and optionally only words
+/// common enough in a frequency corpus that players will know them
+#[derive(Debug, Clone, Default)]
+pub struct Curator {
+    corpus: Option<FrequencyCorpus>,
+    min_frequency: u64,
+    deny_list: DenyList,
+}
+
+impl Curator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop words that occur fewer than `min_frequency` times in `corpus`
+    pub fn with_corpus(mut self, corpus: FrequencyCorpus, min_frequency: u64) -> Self {
+        self.corpus = Some(corpus);
+        self.min_frequency = min_frequency;
+        self
+    }
+
+    pub fn with_deny_list(mut self, deny_list: DenyList) -> Self {
+        self.deny_list = deny_list;
+        self
+    }
+
+    /// curate filters the raw words, returning the curated list sorted and
+    /// deduplicated so the same inputs always produce the same file
+    pub fn curate<I, S>(&self, raw_words: I) -> (Vec<String>, CurationReport)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut report = CurationReport::default();
+        let mut playable = BTreeSet::new();
+        let mut unplayable = HashSet::new();
+        for raw in raw_words {
+            report.ingested += 1;
+            match normalize_word(raw.as_ref()) {
+                Some(word) => {
+                    playable.insert(word);
+                }
+                None => {
+                    unplayable.insert(raw.as_ref().trim().to_string());
+                }
+            }
+        }
+        report.unplayable = unplayable.len();
+
+        let mut curated = Vec::with_capacity(playable.len());
+        for word in playable {
+            if self.deny_list.denies(&word) {
+                report.denied += 1;
+            } else if self
+                .corpus
+                .as_ref()
+                .is_some_and(|corpus| corpus.count(&word) < self.min_frequency)
+            {
+                report.rare += 1;
+            } else {
+                curated.push(word);
+            }
+        }
+        report.kept = curated.len();
+
+        (curated, report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frequency_corpus_parses_pairs() {
+        let corpus =
+            FrequencyCorpus::parse("# word count\nthe 5000\nCat,20\nzyzzyva\t1\ncat 5\n").unwrap();
+        assert_eq!(corpus.count("the"), 5000);
+        assert_eq!(corpus.count("cat"), 25);
+        assert_eq!(corpus.count("zyzzyva"), 1);
+        assert_eq!(corpus.count("dog"), 0);
+
+        assert!(FrequencyCorpus::parse("the\n").is_err());
+        assert!(FrequencyCorpus::parse("the lots\n").is_err());
+    }
+
+    #[test]
+    fn test_deny_list_matches_inflections() {
+        let deny_list = DenyList::parse("darn\nHeck\n");
+        assert!(deny_list.denies("darn"));
+        assert!(deny_list.denies("darned"));
+        assert!(deny_list.denies("heckers"));
+        assert!(!deny_list.denies("dar"));
+        assert!(!deny_list.denies("checked"));
+        assert!(!deny_list.denies("darnedest"));
+    }
+
+    #[test]
+    fn test_curate_filters_and_reports() {
+        let corpus = FrequencyCorpus::parse("cat 50\ndog 40\nzyzzyva 1\ndarn 90\n").unwrap();
+        let curator = Curator::new()
+            .with_corpus(corpus, 10)
+            .with_deny_list(DenyList::parse("darn"));
+
+        let raw = [
+            "DOG",
+            "cat",
+            "cat",
+            "x-ray",
+            "zyzzyva",
+            "darns",
+            "cafe\u{301}",
+        ];
+        let (words, report) = curator.curate(raw);
+
+        assert_eq!(words, vec!["cat", "dog"]);
+        assert_eq!(
+            report,
+            CurationReport {
+                ingested: 7,
+                unplayable: 2,
+                denied: 1,
+                rare: 1,
+                kept: 2,
+            }
+        );
+
+        // Without a corpus nothing is dropped for being rare
+        let (words, _) = Curator::new().curate(raw);
+        assert_eq!(words, vec!["cat", "darns", "dog", "zyzzyva"]);
+    }
+}
@@ -0,0 +1,379 @@
+use anyhow::{anyhow, Context, Result};
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+use super::formats::{entry_lines, read_source};
+use super::normalize_word;
+
+/// Where definitions for the shipped wordlist are kept unless DEFINITIONS_PATH
+/// says otherwise
+pub const DEFINITIONS_PATH: &str = "wordlist.definitions";
+
+/// Most senses kept for one word when converting WordNet; players want the
+/// gist, not the whole entry
+pub const MAX_SENSES: usize = 3;
+
+/// One meaning of a word
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sense {
+    /// e.g. `noun`, when the source says
+    pub part_of_speech: Option<String>,
+    pub definition: String,
+}
+
+/// Definitions holds the offline dictionary: the senses of each word, most
+/// common first. It is read from tab-separated `word part-of-speech
+/// definition` lines, as written by `api-server wordlist definitions` from
+/// WordNet; the part of speech may be left empty.
+#[derive(Debug, Clone, Default)]
+pub struct Definitions {
+    senses: BTreeMap<String, Vec<Sense>>,
+}
+
+impl Definitions {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut senses: BTreeMap<String, Vec<Sense>> = BTreeMap::new();
+        for line in entry_lines(text) {
+            let mut fields = line.splitn(3, '\t');
+            let (Some(word), Some(part_of_speech), Some(definition)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(anyhow!(
+                    "Definition line needs three tab-separated fields: {line}"
+                ));
+            };
+            let word = normalize_word(word)
+                .ok_or_else(|| anyhow!("Defined word is not playable: {word}"))?;
+            let part_of_speech = part_of_speech.trim();
+            senses.entry(word).or_default().push(Sense {
+                part_of_speech: (!part_of_speech.is_empty()).then(|| part_of_speech.to_string()),
+                definition: definition.trim().to_string(),
+            });
+        }
+        Ok(Self { senses })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::parse(&read_source(path)?)
+    }
+
+    /// load_or_default loads the definitions at `path`, falling back to none
+    /// when the file is missing or unreadable
+    pub fn load_or_default(path: &Path) -> Self {
+        if !path.exists() {
+            return Self::default();
+        }
+        Self::load(path).unwrap_or_else(|e| {
+            warn!("Ignoring definitions in {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    pub fn from_env() -> Self {
+        let path = env::var("DEFINITIONS_PATH").unwrap_or(DEFINITIONS_PATH.to_string());
+        Self::load_or_default(Path::new(&path))
+    }
+
+    /// from_wordnet collects up to MAX_SENSES senses for each word `keep`
+    /// accepts from WordNet `data.*` files. Multi-word entries are skipped and
+    /// glosses lose their usage examples.
+    pub fn from_wordnet<'a>(
+        data_files: impl IntoIterator<Item = &'a str>,
+        keep: impl Fn(&str) -> bool,
+    ) -> Self {
+        let mut senses: BTreeMap<String, Vec<Sense>> = BTreeMap::new();
+        for text in data_files {
+            // The license header is indented; synsets start with their offset
+            for line in text.lines().filter(|line| !line.starts_with(' ')) {
+                let Some((fields, gloss)) = line.split_once(" | ") else {
+                    continue;
+                };
+                let fields: Vec<&str> = fields.split_whitespace().collect();
+                let (Some(synset_type), Some(word_count)) = (fields.get(2), fields.get(3)) else {
+                    continue;
+                };
+                let Ok(word_count) = usize::from_str_radix(word_count, 16) else {
+                    continue;
+                };
+                let part_of_speech = match *synset_type {
+                    "n" => "noun",
+                    "v" => "verb",
+                    "a" | "s" => "adjective",
+                    "r" => "adverb",
+                    _ => continue,
+                };
+                // Examples follow the definition as `; "..."`
+                let definition = gloss.split("; \"").next().unwrap_or(gloss).trim();
+
+                // Words alternate with their lex ids; adjectives may carry a
+                // syntactic marker such as `(a)`
+                for word in fields.iter().skip(4).step_by(2).take(word_count) {
+                    let word = word.split('(').next().unwrap_or(word);
+                    let Some(word) = normalize_word(word).filter(|word| keep(word)) else {
+                        continue;
+                    };
+                    let word_senses = senses.entry(word).or_default();
+                    if word_senses.len() < MAX_SENSES {
+                        word_senses.push(Sense {
+                            part_of_speech: Some(part_of_speech.to_string()),
+                            definition: definition.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+        Self { senses }
+    }
+
+    /// to_text writes the definitions in the format `parse` reads
+    pub fn to_text(&self) -> String {
+        let mut text = String::from(
+            "# Definitions shown with solutions: word, part of speech and definition, tab-separated\n",
+        );
+        for (word, senses) in &self.senses {
+            for sense in senses {
+                let part_of_speech = sense.part_of_speech.as_deref().unwrap_or("");
+                text.push_str(&format!("{word}\t{part_of_speech}\t{}\n", sense.definition));
+            }
+        }
+        text
+    }
+
+    pub fn get(&self, word: &str) -> Option<&[Sense]> {
+        self.senses.get(word).map(Vec::as_slice)
+    }
+
+    pub fn len(&self) -> usize {
+        self.senses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.senses.is_empty()
+    }
+}
+
+/// An entry in the response of a dictionaryapi.dev-style API
+#[derive(Deserialize)]
+struct ApiEntry {
+    meanings: Vec<ApiMeaning>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiMeaning {
+    part_of_speech: Option<String>,
+    definitions: Vec<ApiDefinition>,
+}
+
+#[derive(Deserialize)]
+struct ApiDefinition {
+    definition: String,
+}
+
+/// DefinitionApi looks up words the offline dictionary lacks from an external
+/// dictionary API answering in the dictionaryapi.dev format
+#[derive(Clone)]
+pub struct DefinitionApi {
+    client: reqwest::Client,
+    /// Lookup URL with `{word}` where the word goes
+    url_template: String,
+}
+
+impl DefinitionApi {
+    pub fn new(url_template: String) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(3))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            url_template,
+        }
+    }
+
+    /// Creates the fallback from `DEFINITIONS_API_URL`, e.g.
+    /// `https://api.dictionaryapi.dev/api/v2/entries/en/{word}`. Returns None
+    /// when it isn't set.
+    pub fn from_env() -> Option<Self> {
+        env::var("DEFINITIONS_API_URL")
+            .ok()
+            .filter(|url| url.contains("{word}"))
+            .map(Self::new)
+    }
+
+    /// lookup returns the senses the API knows for `word`, none when it
+    /// doesn't know the word
+    async fn lookup(&self, word: &str) -> Result<Vec<Sense>> {
+        let url = self.url_template.replace("{word}", word);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .context("Definition lookup failed")?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+        let entries: Vec<ApiEntry> = response
+            .error_for_status()
+            .context("Definition lookup failed")?
+            .json()
+            .await
+            .context("Unreadable definition response")?;
+
+        Ok(entries
+            .into_iter()
+            .flat_map(|entry| entry.meanings)
+            .flat_map(|meaning| {
+                let part_of_speech = meaning.part_of_speech;
+                meaning.definitions.into_iter().map(move |d| Sense {
+                    part_of_speech: part_of_speech.clone(),
+                    definition: d.definition,
+                })
+            })
+            .take(MAX_SENSES)
+            .collect())
+    }
+}
+
+/// DefinitionLookup answers from the offline dictionary first and, when one
+/// is configured, from the external API for the words it lacks. API answers
+/// are cached, including words it doesn't know.
+#[derive(Clone, Default)]
+pub struct DefinitionLookup {
+    definitions: Arc<Definitions>,
+    fallback: Option<DefinitionApi>,
+    fetched: Option<Cache<String, Arc<Vec<Sense>>>>,
+}
+
+impl DefinitionLookup {
+    pub fn new(definitions: Definitions) -> Self {
+        Self {
+            definitions: Arc::new(definitions),
+            fallback: None,
+            fetched: None,
+        }
+    }
+
+    pub fn with_fallback(mut self, fallback: Option<DefinitionApi>) -> Self {
+        self.fetched = fallback.as_ref().map(|_| {
+            Cache::builder()
+                .max_capacity(10_000)
+                .time_to_live(Duration::from_secs(7 * 24 * 60 * 60))
+                .build()
+        });
+        self.fallback = fallback;
+        self
+    }
+
+    /// senses returns what `word` means, or nothing when neither source
+    /// knows it. A failing API is logged and treated as not knowing the word.
+    pub async fn senses(&self, word: &str) -> Vec<Sense> {
+        if let Some(senses) = self.definitions.get(word) {
+            return senses.to_vec();
+        }
+        let (Some(fallback), Some(fetched)) = (&self.fallback, &self.fetched) else {
+            return Vec::new();
+        };
+        if let Some(senses) = fetched.get(word).await {
+            return senses.to_vec();
+        }
+
+        match fallback.lookup(word).await {
+            Ok(senses) => {
+                fetched
+                    .insert(word.to_string(), Arc::new(senses.clone()))
+                    .await;
+                senses
+            }
+            Err(e) => {
+                warn!("Failed to look up a definition for '{}': {:#}", word, e);
+                Vec::new()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wordnet_conversion_round_trips() {
+        let data = "  1 This software and database is being provided\n\
+            00001740 03 n 02 entity 0 thing 1 003 ~ 00001930 n 0000 | that which exists; \"it was a thing\"\n\
+            00002098 00 a 01 able(a) 0 002 | having the necessary means or skill\n\
+            00003131 00 v 01 take_off 0 001 | leave the ground\n";
+        let definitions = Definitions::from_wordnet([data], |word| word != "entity");
+
+        assert_eq!(definitions.len(), 2);
+        assert_eq!(
+            definitions.get("thing").unwrap(),
+            &[Sense {
+                part_of_speech: Some("noun".to_string()),
+                definition: "that which exists".to_string(),
+            }]
+        );
+        assert_eq!(
+            definitions.get("able").unwrap()[0]
+                .part_of_speech
+                .as_deref(),
+            Some("adjective")
+        );
+
+        let parsed = Definitions::parse(&definitions.to_text()).unwrap();
+        assert_eq!(parsed.get("thing"), definitions.get("thing"));
+        assert!(Definitions::parse("thing\tnoun\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_lookup_falls_back_to_the_api_once_per_word() {
+        use axum::{extract::Path as UrlPath, http::StatusCode, routing::get, Json, Router};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let api = Router::new().route(
+            "/entries/:word",
+            get({
+                let requests = requests.clone();
+                move |UrlPath(word): UrlPath<String>| async move {
+                    requests.fetch_add(1, Ordering::SeqCst);
+                    if word != "zax" {
+                        return Err(StatusCode::NOT_FOUND);
+                    }
+                    Ok(Json(serde_json::json!([{
+                        "word": "zax",
+                        "meanings": [{
+                            "partOfSpeech": "noun",
+                            "definitions": [{"definition": "a tool for cutting roof slates"}]
+                        }]
+                    }])))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, api).await });
+
+        let definitions = Definitions::parse("qi\t\tlife force\n").unwrap();
+        let lookup = DefinitionLookup::new(definitions).with_fallback(Some(DefinitionApi::new(
+            format!("http://{addr}/entries/{{word}}"),
+        )));
+
+        assert_eq!(lookup.senses("qi").await[0].definition, "life force");
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+
+        let zax = lookup.senses("zax").await;
+        assert_eq!(zax[0].part_of_speech.as_deref(), Some("noun"));
+        assert!(lookup.senses("qat").await.is_empty());
+        assert_eq!(lookup.senses("zax").await, zax);
+        assert!(lookup.senses("qat").await.is_empty());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashSet;
+use std::fmt;
+
+/// WordlistDiff describes how a candidate wordlist differs from the current one
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WordlistDiff {
+    /// words in the candidate list that the current list lacks
+    pub added: Vec<String>,
+    /// words in the current list that the candidate list lacks
+    pub removed: Vec<String>,
+    /// number of words present in both lists
+    pub unchanged: usize,
+}
+
+impl WordlistDiff {
+    pub fn between(current: &[String], candidate: &[String]) -> Self {
+        let current_set: HashSet<&String> = current.iter().collect();
+        let candidate_set: HashSet<&String> = candidate.iter().collect();
+
+        let mut added: Vec<String> = candidate_set
+            .difference(&current_set)
+            .map(|w| w.to_string())
+            .collect();
+        let mut removed: Vec<String> = current_set
+            .difference(&candidate_set)
+            .map(|w| w.to_string())
+            .collect();
+        added.sort();
+        removed.sort();
+
+        Self {
+            added,
+            removed,
+            unchanged: current_set.intersection(&candidate_set).count(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl fmt::Display for WordlistDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} added, {} removed, {} unchanged",
+            self.added.len(),
+            self.removed.len(),
+            self.unchanged
+        )?;
+        for word in &self.added {
+            writeln!(f, "+ {word}")?;
+        }
+        for word in &self.removed {
+            writeln!(f, "- {word}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(list: &[&str]) -> Vec<String> {
+        list.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_diff_between() {
+        let current = words(&["apple", "bee", "cat"]);
+        let candidate = words(&["bee", "cat", "dog", "qi"]);
+
+        let diff = WordlistDiff::between(&current, &candidate);
+        assert_eq!(diff.added, words(&["dog", "qi"]));
+        assert_eq!(diff.removed, words(&["apple"]));
+        assert_eq!(diff.unchanged, 2);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn test_identical_lists_have_empty_diff() {
+        let list = words(&["apple", "bee"]);
+        let diff = WordlistDiff::between(&list, &list);
+        assert!(diff.is_empty());
+        assert_eq!(diff.unchanged, 2);
+    }
+
+    #[test]
+    fn test_display() {
+        let diff = WordlistDiff::between(&words(&["apple"]), &words(&["bee"]));
+        assert_eq!(
+            diff.to_string(),
+            "1 added, 1 removed, 0 unchanged\n+ bee\n- apple\n"
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::warn;
+
+use super::formats::{entry_lines, read_source};
+use super::normalize_word;
+
+/// Where the display casing for the shipped wordlist is kept
+pub const DISPLAY_CASING_PATH: &str = "wordlist.display";
+
+/// DisplayCasing maps the few words whose lowercase form reads wrong to how
+/// they should be shown, e.g. `qi` as `QI`. Words are still stored, matched
+/// and scored in lowercase; this only decides how responses render them so
+/// every client shows the same thing.
+#[derive(Debug, Clone, Default)]
+pub struct DisplayCasing {
+    forms: HashMap<String, String>,
+}
+
+impl DisplayCasing {
+    /// parse reads one display form per line, written the way it should be
+    /// shown. Each form must be a playable word apart from its casing.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut forms = HashMap::new();
+        for form in entry_lines(text) {
+            let word = normalize_word(form)
+                .ok_or_else(|| anyhow!("Display form is not a playable word: {form}"))?;
+            forms.insert(word, form.to_string());
+        }
+        Ok(Self { forms })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::parse(&read_source(path)?)
+    }
+
+    /// load_or_default loads the casing at `path`, falling back to showing
+    /// every word as stored when the file is missing or unreadable
+    pub fn load_or_default(path: &Path) -> Self {
+        if !path.exists() {
+            return Self::default();
+        }
+        Self::load(path).unwrap_or_else(|e| {
+            warn!("Ignoring display casing in {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    /// display returns how `word` should be shown
+    pub fn display<'a>(&'a self, word: &'a str) -> &'a str {
+        self.forms.get(word).map(String::as_str).unwrap_or(word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_casing() {
+        let casing = DisplayCasing::parse("# render forms\nQI\nTV\n").unwrap();
+        assert_eq!(casing.display("qi"), "QI");
+        assert_eq!(casing.display("tv"), "TV");
+        assert_eq!(casing.display("cat"), "cat");
+
+        assert!(DisplayCasing::parse("X-RAY\n").is_err());
+        assert_eq!(
+            DisplayCasing::load_or_default(Path::new("no/such/file")).display("qi"),
+            "qi"
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use anyhow::{anyhow, Result};
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+use super::normalize_words;
+
+/// Line formats found in published word lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordlistFormat {
+    /// One word per line and nothing else, like our own `wordlist` or most SOWPODS dumps
+    Plain,
+    /// One word per line followed by annotations, like the TWL and Collins lists
+    /// that ship with definitions and inflections (`AA rough, cindery lava [n AAS]`)
+    Annotated,
+}
+
+impl WordlistFormat {
+    /// detect guesses the format from the content. A list is considered
+    /// annotated if any entry line carries more than a single token.
+    pub fn detect(text: &str) -> Self {
+        let annotated = entry_lines(text)
+            .take(1000)
+            .any(|line| line.split_whitespace().nth(1).is_some());
+
+        if annotated {
+            WordlistFormat::Annotated
+        } else {
+            WordlistFormat::Plain
+        }
+    }
+
+    /// extract_words pulls the raw (un-normalized) word from every entry line
+    pub fn extract_words<'a>(&self, text: &'a str) -> Vec<&'a str> {
+        match self {
+            WordlistFormat::Plain => entry_lines(text).collect(),
+            WordlistFormat::Annotated => entry_lines(text)
+                .filter_map(|line| line.split_whitespace().next())
+                .collect(),
+        }
+    }
+}
+
+impl std::str::FromStr for WordlistFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "plain" => Ok(WordlistFormat::Plain),
+            "annotated" | "twl" | "collins" => Ok(WordlistFormat::Annotated),
+            other => Err(anyhow!("Unknown wordlist format: {other}")),
+        }
+    }
+}
+
+/// entry_lines yields trimmed lines that hold a dictionary entry, skipping
+/// blank lines and the comment/header lines some lexicon distributions include
+pub(super) fn entry_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines()
+        .map(|line| line.trim_start_matches('\u{feff}').trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with("//"))
+}
+
+/// read_source reads the text of a wordlist file. Zip archives (the usual way
+/// tournament lexicons are distributed) are unpacked and the text of every
+/// `.txt` entry is concatenated; if the archive has no `.txt` entries, every
+/// file in it is used.
+pub fn read_source(path: &Path) -> Result<String> {
+    let is_zip = path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("zip"))
+        .unwrap_or(false);
+
+    if !is_zip {
+        return Ok(std::fs::read_to_string(path)?);
+    }
+
+    let mut archive = zip::ZipArchive::new(BufReader::new(File::open(path)?))?;
+    let names: Vec<String> = archive
+        .file_names()
+        .filter(|name| !name.ends_with('/'))
+        .map(String::from)
+        .collect();
+    let text_names: Vec<&String> = names
+        .iter()
+        .filter(|name| name.to_lowercase().ends_with(".txt"))
+        .collect();
+    let selected: Vec<&String> = if text_names.is_empty() {
+        names.iter().collect()
+    } else {
+        text_names
+    };
+
+    if selected.is_empty() {
+        return Err(anyhow!("Archive {} contains no files", path.display()));
+    }
+
+    let mut text = String::new();
+    for name in selected {
+        let mut entry = archive.by_name(name)?;
+        entry.read_to_string(&mut text)?;
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+/// load_wordlist reads a wordlist in any supported format and returns the
+/// normalized, sorted, deduplicated words. The format is detected from the
+/// content when not given explicitly.
+pub fn load_wordlist(path: &Path, format: Option<WordlistFormat>) -> Result<Vec<String>> {
+    let text = read_source(path)?;
+    let format = format.unwrap_or_else(|| WordlistFormat::detect(&text));
+    Ok(normalize_words(format.extract_words(&text)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    const TWL_SAMPLE: &str = "# TWL06 sample\nAA rough, cindery lava [n AAS]\nAAH to exclaim in amazement [v -ED, -ING, -S]\n\nAAHED AAH, to exclaim in amazement [v]\n";
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(
+            WordlistFormat::detect("aa\naah\naahed\n"),
+            WordlistFormat::Plain
+        );
+        assert_eq!(
+            WordlistFormat::detect(TWL_SAMPLE),
+            WordlistFormat::Annotated
+        );
+    }
+
+    #[test]
+    fn test_extract_annotated_words() {
+        let words = WordlistFormat::Annotated.extract_words(TWL_SAMPLE);
+        assert_eq!(words, vec!["AA", "AAH", "AAHED"]);
+    }
+
+    #[test]
+    fn test_extract_plain_words_skips_comments_and_bom() {
+        let words = WordlistFormat::Plain.extract_words("\u{feff}AA\n// header\nAAH\r\n\nZZZ\n");
+        assert_eq!(words, vec!["AA", "AAH", "ZZZ"]);
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!(
+            "twl".parse::<WordlistFormat>().unwrap(),
+            WordlistFormat::Annotated
+        );
+        assert_eq!(
+            "Plain".parse::<WordlistFormat>().unwrap(),
+            WordlistFormat::Plain
+        );
+        assert!("csv".parse::<WordlistFormat>().is_err());
+    }
+
+    #[test]
+    fn test_load_plain_file() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "ZOO\nApple\napple\n").unwrap();
+
+        let words = load_wordlist(file.path(), None).unwrap();
+        assert_eq!(words, vec!["apple", "zoo"]);
+    }
+
+    #[test]
+    fn test_load_zipped_lexicon() {
+        let file = tempfile::Builder::new().suffix(".zip").tempfile().unwrap();
+        {
+            let mut writer = zip::ZipWriter::new(file.reopen().unwrap());
+            let options = zip::write::SimpleFileOptions::default();
+            writer.start_file("README.md", options).unwrap();
+            writer.write_all(b"not words").unwrap();
+            writer.start_file("twl.txt", options).unwrap();
+            writer.write_all(TWL_SAMPLE.as_bytes()).unwrap();
+            writer.finish().unwrap();
+        }
+
+        let words = load_wordlist(file.path(), None).unwrap();
+        assert_eq!(words, vec!["aa", "aah", "aahed"]);
+    }
+}
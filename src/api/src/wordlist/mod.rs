@@ -0,0 +1,67 @@
+pub mod curate;
+pub mod definitions;
+pub mod diff;
+pub mod display;
+pub mod formats;
+pub mod reference;
+pub mod replay;
+pub mod variants;
+
+pub use curate::{CurationReport, Curator, DenyList, FrequencyCorpus, WORD_FREQUENCY_PATH};
+pub use definitions::{DefinitionApi, DefinitionLookup, Definitions, Sense, DEFINITIONS_PATH};
+pub use diff::WordlistDiff;
+pub use display::{DisplayCasing, DISPLAY_CASING_PATH};
+pub use formats::{load_wordlist, WordlistFormat};
+pub use reference::{load_reference_words, reference_words_from_env, REFERENCE_WORDLIST_PATH};
+pub use replay::{replay_all, ReplayReport};
+pub use variants::{SpellingVariants, VariantUsage, VariantWords, SPELLING_VARIANTS_PATH};
+
+/// normalize_word converts a raw dictionary token into the form stored in the
+/// canonical wordlist: lowercase ascii letters only. Tokens containing anything
+/// else (digits, hyphens, apostrophes, accented letters) cannot be played on the
+/// board and are rejected.
+pub fn normalize_word(raw: &str) -> Option<String> {
+    let word = raw.trim().to_lowercase();
+    if word.is_empty() || !word.chars().all(|c| c.is_ascii_lowercase()) {
+        return None;
+    }
+    Some(word)
+}
+
+/// normalize_words normalizes every token, drops the ones that aren't playable,
+/// and returns the remainder sorted and deduplicated so it can be written out
+/// directly as a wordlist.
+pub fn normalize_words<I, S>(raw_words: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut words: Vec<String> = raw_words
+        .into_iter()
+        .filter_map(|w| normalize_word(w.as_ref()))
+        .collect();
+    words.sort();
+    words.dedup();
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_word() {
+        assert_eq!(normalize_word("AARDVARK"), Some("aardvark".to_string()));
+        assert_eq!(normalize_word("  qi \r"), Some("qi".to_string()));
+        assert_eq!(normalize_word(""), None);
+        assert_eq!(normalize_word("don't"), None);
+        assert_eq!(normalize_word("café"), None);
+        assert_eq!(normalize_word("b2b"), None);
+    }
+
+    #[test]
+    fn test_normalize_words_sorts_and_dedups() {
+        let words = normalize_words(vec!["ZOO", "apple", "Apple", "x-ray", "bee"]);
+        assert_eq!(words, vec!["apple", "bee", "zoo"]);
+    }
+}
@@ -0,0 +1,27 @@
+use std::env;
+use std::path::Path;
+use tracing::warn;
+
+use super::formats::load_wordlist;
+
+/// Where the reference list players can challenge rejections against is kept
+/// unless REFERENCE_WORDLIST_PATH says otherwise
+pub const REFERENCE_WORDLIST_PATH: &str = "wordlist.reference";
+
+/// load_reference_words loads the reference list at `path` in any format
+/// load_wordlist understands. A missing or unreadable list leaves it empty,
+/// which turns challenges off rather than stopping the server.
+pub fn load_reference_words(path: &Path) -> Vec<String> {
+    if !path.exists() {
+        return Vec::new();
+    }
+    load_wordlist(path, None).unwrap_or_else(|e| {
+        warn!("Ignoring reference wordlist {}: {}", path.display(), e);
+        Vec::new()
+    })
+}
+
+pub fn reference_words_from_env() -> Vec<String> {
+    let path = env::var("REFERENCE_WORDLIST_PATH").unwrap_or(REFERENCE_WORDLIST_PATH.to_string());
+    load_reference_words(Path::new(&path))
+}
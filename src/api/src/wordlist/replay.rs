@@ -0,0 +1,257 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use tracing::warn;
+
+use crate::db::Repository;
+use crate::game::{conversion::SerializableBoard, Board, GameEngine};
+
+/// A word from a game's stored optimal solution whose score has changed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoreChange {
+    pub word: String,
+    pub stored: i32,
+    /// None when the word is no longer valid on the board
+    pub current: Option<i32>,
+}
+
+/// How the current engine disagrees with what was stored for one game
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GameDiscrepancy {
+    pub game_id: String,
+    pub date: String,
+    /// Stored answers the engine no longer finds on the board
+    pub no_longer_valid: Vec<String>,
+    /// Words the engine finds that weren't stored
+    pub newly_valid: Vec<String>,
+    pub score_changes: Vec<ScoreChange>,
+}
+
+impl GameDiscrepancy {
+    pub fn is_empty(&self) -> bool {
+        self.no_longer_valid.is_empty()
+            && self.newly_valid.is_empty()
+            && self.score_changes.is_empty()
+    }
+}
+
+/// ReplayReport is the result of replaying every stored game's answers
+/// through the current engine, to check an engine or dictionary change
+/// before it ships
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ReplayReport {
+    pub games: usize,
+    /// Games whose board couldn't be read and were skipped
+    pub unreadable_boards: Vec<String>,
+    /// Only the games with at least one discrepancy, oldest first
+    pub discrepancies: Vec<GameDiscrepancy>,
+}
+
+impl ReplayReport {
+    pub fn is_clean(&self) -> bool {
+        self.discrepancies.is_empty() && self.unreadable_boards.is_empty()
+    }
+}
+
+impl fmt::Display for ReplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = |select: fn(&GameDiscrepancy) -> usize| -> usize {
+            self.discrepancies.iter().map(select).sum()
+        };
+        writeln!(
+            f,
+            "{} games replayed, {} differ: {} words no longer valid, {} newly valid, {} score changes",
+            self.games,
+            self.discrepancies.len(),
+            count(|d| d.no_longer_valid.len()),
+            count(|d| d.newly_valid.len()),
+            count(|d| d.score_changes.len()),
+        )?;
+        for date in &self.unreadable_boards {
+            writeln!(f, "! {date}: unreadable board")?;
+        }
+        for game in &self.discrepancies {
+            writeln!(f, "{} ({})", game.date, game.game_id)?;
+            for word in &game.no_longer_valid {
+                writeln!(f, "  - {word}")?;
+            }
+            for word in &game.newly_valid {
+                writeln!(f, "  + {word}")?;
+            }
+            for change in &game.score_changes {
+                match change.current {
+                    Some(current) => {
+                        writeln!(f, "  ~ {} {} -> {}", change.word, change.stored, current)?
+                    }
+                    None => writeln!(f, "  ~ {} {} -> invalid", change.word, change.stored)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// replay_game compares the words `game_engine` finds on `board` today with
+/// the answers and optimal word scores stored when the game was generated
+async fn replay_game(
+    game_engine: &GameEngine,
+    board: &Board,
+    stored_answers: &[String],
+    stored_optimal: &[(String, i32)],
+) -> Result<(Vec<String>, Vec<String>, Vec<ScoreChange>)> {
+    let current: HashMap<String, i32> = game_engine
+        .find_all_valid_words(board)
+        .await?
+        .into_iter()
+        .map(|answer| {
+            let score = answer.score();
+            (answer.word, score)
+        })
+        .collect();
+    let stored: BTreeSet<&str> = stored_answers.iter().map(String::as_str).collect();
+
+    let no_longer_valid = stored
+        .iter()
+        .filter(|word| !current.contains_key(**word))
+        .map(|word| word.to_string())
+        .collect();
+    let newly_valid: BTreeSet<&String> = current
+        .keys()
+        .filter(|word| !stored.contains(word.as_str()))
+        .collect();
+    let score_changes = stored_optimal
+        .iter()
+        .filter_map(|(word, stored)| {
+            let current = current.get(word).copied();
+            (current != Some(*stored)).then(|| ScoreChange {
+                word: word.clone(),
+                stored: *stored,
+                current,
+            })
+        })
+        .collect();
+
+    Ok((
+        no_longer_valid,
+        newly_valid.into_iter().cloned().collect(),
+        score_changes,
+    ))
+}
+
+/// replay_all replays every stored game, oldest first. `limit` keeps only
+/// the most recent games.
+pub async fn replay_all<R: Repository>(
+    repository: &R,
+    game_engine: &GameEngine,
+    limit: Option<usize>,
+) -> Result<ReplayReport> {
+    let boards = repository.get_game_boards().await?;
+    let skip = limit.map_or(0, |limit| boards.len().saturating_sub(limit));
+
+    let mut report = ReplayReport::default();
+    for stored in boards.into_iter().skip(skip) {
+        let Some(game) = repository.get_game_by_date(&stored.date).await? else {
+            continue;
+        };
+        report.games += 1;
+
+        let board = match serde_json::from_str::<SerializableBoard>(&game.board_data) {
+            Ok(board) => Board::from(board),
+            Err(e) => {
+                warn!("Skipping unreadable board for {}: {}", game.date, e);
+                report.unreadable_boards.push(game.date);
+                continue;
+            }
+        };
+        let answers: Vec<String> = repository
+            .get_game_answers(&game.id)
+            .await?
+            .into_iter()
+            .map(|answer| answer.word)
+            .collect();
+        let optimal: Vec<(String, i32)> = repository
+            .get_optimal_solutions(&game.id)
+            .await?
+            .into_iter()
+            .map(|answer| (answer.word, answer.score))
+            .collect();
+
+        let (no_longer_valid, newly_valid, score_changes) =
+            replay_game(game_engine, &board, &answers, &optimal).await?;
+        let discrepancy = GameDiscrepancy {
+            game_id: game.id,
+            date: game.date,
+            no_longer_valid,
+            newly_valid,
+            score_changes,
+        };
+        if !discrepancy.is_empty() {
+            report.discrepancies.push(discrepancy);
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::{NewGameAnswer, NewOptimalSolution};
+    use crate::db::InMemoryRepository;
+    use crate::test_utils::{
+        create_default_test_board, create_new_test_game, create_test_game_engine,
+    };
+
+    #[tokio::test]
+    async fn test_replay_reports_each_kind_of_discrepancy() {
+        let (game_engine, _wordlist) = create_test_game_engine();
+        let repository = InMemoryRepository::new();
+
+        // Stored before "stop" was in the dictionary and after "gone" was dropped
+        let stored: Vec<String> = game_engine
+            .find_all_valid_words(&create_default_test_board())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|answer| answer.word)
+            .filter(|word| word != "stop")
+            .chain(["gone".to_string()])
+            .collect();
+        let answers = stored
+            .into_iter()
+            .map(|word| NewGameAnswer {
+                game_id: String::new(),
+                word,
+                path_stats: None,
+            })
+            .collect();
+        let optimal = NewOptimalSolution {
+            game_id: String::new(),
+            words_and_scores: serde_json::to_string(&[("test", 4), ("thing", 99)]).unwrap(),
+            total_score: 103,
+        };
+        repository
+            .create_game_with_answers(create_new_test_game(), answers, Some(optimal))
+            .await
+            .unwrap();
+
+        let report = replay_all(&repository, &game_engine, None).await.unwrap();
+        assert_eq!(report.games, 1);
+        assert_eq!(report.discrepancies.len(), 1);
+        let game = &report.discrepancies[0];
+        assert_eq!(game.no_longer_valid, vec!["gone"]);
+        assert_eq!(game.newly_valid, vec!["stop"]);
+        assert_eq!(
+            game.score_changes,
+            vec![ScoreChange {
+                word: "thing".to_string(),
+                stored: 99,
+                current: Some(5),
+            }]
+        );
+        assert!(report.to_string().starts_with(
+            "1 games replayed, 1 differ: 1 words no longer valid, 1 newly valid, 1 score changes\n"
+        ));
+    }
+}
@@ -0,0 +1,190 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::warn;
+
+use super::formats::{entry_lines, read_source};
+use super::normalize_word;
+
+/// Where a deployment's spelling variants are kept unless SPELLING_VARIANTS_PATH
+/// says otherwise
+pub const SPELLING_VARIANTS_PATH: &str = "wordlist.variants";
+
+/// SpellingVariants lists groups of spellings of the same word, such as
+/// `colour color`, so either spelling is accepted when the dictionary only has
+/// one of them. A deployment without the file accepts spellings as listed.
+#[derive(Debug, Clone, Default)]
+pub struct SpellingVariants {
+    groups: Vec<Vec<String>>,
+}
+
+impl SpellingVariants {
+    /// parse reads one group of equivalent spellings per line, separated by
+    /// whitespace. Each spelling must be a playable word.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut groups = Vec::new();
+        for line in entry_lines(text) {
+            let group = line
+                .split_whitespace()
+                .map(|spelling| {
+                    normalize_word(spelling)
+                        .ok_or_else(|| anyhow!("Spelling is not a playable word: {spelling}"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            if group.len() < 2 {
+                return Err(anyhow!("Variant line needs at least two spellings: {line}"));
+            }
+            groups.push(group);
+        }
+        Ok(Self { groups })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Self::parse(&read_source(path)?)
+    }
+
+    /// load_or_default loads the variants at `path`, falling back to none when
+    /// the file is missing or unreadable
+    pub fn load_or_default(path: &Path) -> Self {
+        if !path.exists() {
+            return Self::default();
+        }
+        Self::load(path).unwrap_or_else(|e| {
+            warn!("Ignoring spelling variants in {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    pub fn from_env() -> Self {
+        let path = env::var("SPELLING_VARIANTS_PATH").unwrap_or(SPELLING_VARIANTS_PATH.to_string());
+        Self::load_or_default(Path::new(&path))
+    }
+
+    /// expand returns the spellings missing from the dictionary, each derived
+    /// from the first spelling in its group that `in_dictionary` accepts.
+    /// Groups with no spelling in the dictionary add nothing.
+    pub fn expand(&self, in_dictionary: impl Fn(&str) -> bool) -> VariantWords {
+        let mut entries = HashMap::new();
+        for group in &self.groups {
+            let Some(source) = group.iter().find(|spelling| in_dictionary(spelling)) else {
+                continue;
+            };
+            for spelling in group.iter().filter(|spelling| !in_dictionary(spelling)) {
+                entries
+                    .entry(spelling.clone())
+                    .or_insert_with(|| VariantEntry {
+                        source: source.clone(),
+                        accepted: AtomicU64::new(0),
+                    });
+            }
+        }
+        VariantWords { entries }
+    }
+}
+
+/// VariantWords are the spellings an expansion pass added to the dictionary,
+/// each with the dictionary word it came from and how often it has been
+/// accepted in an answer since startup
+#[derive(Debug, Default)]
+pub struct VariantWords {
+    entries: HashMap<String, VariantEntry>,
+}
+
+#[derive(Debug)]
+struct VariantEntry {
+    source: String,
+    accepted: AtomicU64,
+}
+
+/// How one added spelling has been played
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VariantUsage {
+    pub variant: String,
+    pub source: String,
+    pub accepted: u64,
+}
+
+impl VariantWords {
+    /// source returns the dictionary word `word` was added as a variant of,
+    /// or None if it is in the dictionary in its own right
+    pub fn source(&self, word: &str) -> Option<&str> {
+        self.entries.get(word).map(|entry| entry.source.as_str())
+    }
+
+    /// record_accepted counts an answer accepted as `word`, if it is a variant
+    pub fn record_accepted(&self, word: &str) {
+        if let Some(entry) = self.entries.get(word) {
+            entry.accepted.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// usage returns every added spelling with its source and play count,
+    /// ordered by spelling
+    pub fn usage(&self) -> Vec<VariantUsage> {
+        let mut usage: Vec<VariantUsage> = self
+            .entries
+            .iter()
+            .map(|(variant, entry)| VariantUsage {
+                variant: variant.clone(),
+                source: entry.source.clone(),
+                accepted: entry.accepted.load(Ordering::Relaxed),
+            })
+            .collect();
+        usage.sort_by(|a, b| a.variant.cmp(&b.variant));
+        usage
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_adds_missing_spellings_with_their_source() {
+        let variants =
+            SpellingVariants::parse("# US/UK\ncolour color\ngrey gray\nfavour favor\n").unwrap();
+        let dictionary = ["colour", "gray", "grey"];
+        let added = variants.expand(|word| dictionary.contains(&word));
+
+        assert_eq!(added.len(), 1);
+        assert_eq!(added.source("color"), Some("colour"));
+        // Both greys are already in the dictionary, and neither favour is
+        assert_eq!(added.source("grey"), None);
+        assert_eq!(added.source("favor"), None);
+
+        added.record_accepted("color");
+        added.record_accepted("colour");
+        assert_eq!(
+            added.usage(),
+            vec![VariantUsage {
+                variant: "color".to_string(),
+                source: "colour".to_string(),
+                accepted: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_lines() {
+        assert!(SpellingVariants::parse("colour\n").is_err());
+        assert!(SpellingVariants::parse("colour col-or\n").is_err());
+        assert!(SpellingVariants::load_or_default(Path::new("no/such/file"))
+            .expand(|_| true)
+            .is_empty());
+    }
+}
@@ -1,6 +1,6 @@
-import { useEffect, useMemo, useState } from 'react';
+import { useEffect, useMemo, useRef, useState } from 'react';
 import { useNavigate, useParams } from 'react-router-dom';
-import { ApiAnswer, ApiGame, ApiGameStats, convertApiBoardToBoard, gameApi } from './api/gameApi';
+import { ApiAnswer, ApiGame, ApiGameStats, ApiRequestError, convertApiBoardToBoard, gameApi, StaleGameError } from './api/gameApi';
 import AnswerSection from './components/AnswerSection';
 import Board from './components/Board';
 import HeatmapModal from './components/HeatmapModal';
@@ -33,6 +33,10 @@ function App() {
   const [isLoadingGame, setIsLoadingGame] = useState(true);
   const [apiError, setApiError] = useState<string | null>(null);
   const [isSubmitting, setIsSubmitting] = useState(false);
+  // One request id per game's submission, kept across retries
+  const submitRequestIds = useRef<Record<string, string>>({});
+  // Signed by the server when the game is started; stale after midnight
+  const [startToken, setStartToken] = useState<string | undefined>(undefined);
   const [isValidWordLoaded, setIsValidWordLoaded] = useState(false);
   const [isValidWordFn, setIsValidWordFn] = useState<((word: string) => boolean) | null>(null);
   const [isGameCompleted, setIsGameCompleted] = useState(false);
@@ -130,6 +134,7 @@ function App() {
     try {
       setIsLoadingGame(true);
       setApiError(null);
+      setStartToken(undefined);
       const game = sequenceNumber 
         ? await gameApi.getGameBySequence(parseInt(sequenceNumber))
         : await gameApi.getDailyGame();
@@ -182,6 +187,15 @@ function App() {
         setGameStats(null);
         setAnswers(['','','','','']);
       }
+
+      // Time the game for the speed leaderboard; only the first start counts
+      if (!gameEntry?.completed) {
+        gameApi.startGame(currentGame.id, user.user_id, user.cookie_token)
+          .then((start) => setStartToken(start.start_token))
+          .catch((error) => {
+            console.warn('Failed to record game start:', error);
+          });
+      }
     } catch (error) {
       console.warn('Failed to load existing game entry:', error);
       // If this is a 401 error, the user is invalid, clear localStorage
@@ -213,11 +227,15 @@ function App() {
           cookie_token: user.cookie_token,
           answers: apiAnswers,
           game_id: currentGame.id,
-          completed: false
+          completed: false,
+          start_token: startToken,
         });
       }
     } catch (error) {
       console.warn('Failed to save progress:', error);
+      if (error instanceof StaleGameError) {
+        setApiError(error.message);
+      }
     }
   };
 
@@ -282,13 +300,17 @@ function App() {
         return;
       }
 
-      // Submit to backend
+      // Submit to backend. Retries reuse the request id, so one whose first
+      // attempt was saved gets that result back instead of a conflict.
+      const requestId = (submitRequestIds.current[currentGame.id] ??= crypto.randomUUID());
       const response = await gameApi.submitAnswers({
         user_id: user?.user_id,
         cookie_token: user?.cookie_token,
         answers: apiAnswers,
         game_id: currentGame.id,
         completed: true,
+        start_token: startToken,
+        request_id: requestId,
       });
 
       // Mark game as completed after successful submission
@@ -297,7 +319,13 @@ function App() {
       setShowHeatmapModal(true);
     } catch (error) {
       console.error('Failed to submit answers:', error);
-      setApiError('Failed to submit answers. Please try again.');
+      if (error instanceof StaleGameError) {
+        setApiError(error.message);
+      } else if (error instanceof ApiRequestError && error.code === 'validation') {
+        setApiError(error.reason);
+      } else {
+        setApiError('Failed to submit answers. Please try again.');
+      }
     } finally {
       setIsSubmitting(false);
     }
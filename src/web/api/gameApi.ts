@@ -9,6 +9,46 @@ export interface ApiGame {
   board: ApiBoard;
   threshold_score: number;
   sequence_number: number;
+  difficulty?: 'easy' | 'medium' | 'hard' | null;
+  target_difficulty?: 'easy' | 'medium' | 'hard' | null;
+  // Id of the letter point table the board was scored with
+  scoring_profile: string;
+  // Bonuses words earn on top of their tiles in this game
+  bonus_rules: ApiBonusRules;
+  // Word length and answer limits entries are held to in this game
+  rules: ApiGameRules;
+  mode: 'daily' | 'blitz';
+  // Seconds a blitz player has from opening the board to submit
+  time_limit_seconds?: number;
+  // Counts up from 1 each time the date's board is replaced
+  revision: number;
+}
+
+// Bonus points a game awards; a zero bonus or a multiplier of 0 or 1 is off
+export interface ApiBonusRules {
+  // Words with more letters than long_word_length earn long_word_bonus
+  long_word_length: number;
+  long_word_bonus: number;
+  // Tile points are multiplied for words traced through both wildcards
+  wildcard_multiplier: number;
+  // Words seen fewer than rare_word_count times in the corpus earn rarity_bonus
+  rare_word_count: number;
+  rarity_bonus: number;
+}
+
+// Which lists of answers a game takes; most games have the defaults of 3, 5 and false
+export interface ApiGameRules {
+  min_word_len: number;
+  max_answers: number;
+  allow_repeats: boolean;
+}
+
+// The points each letter is worth on boards scored with this profile
+export interface ApiScoringProfile {
+  id: string;
+  version: number;
+  letter_points: Record<string, number>;
+  wildcard_points: number;
 }
 
 export interface ApiBoard {
@@ -21,11 +61,15 @@ export interface ApiTile {
   is_wildcard: boolean;
   row: number;
   col: number;
+  // Left out for tiles without a multiplier
+  modifier?: 'DL' | 'TL' | 'DW' | 'TW';
 }
 
 export interface ApiAnswer {
   word: string;
   score: number;
+  // The path the player traced, sent so the solution can be replayed
+  path?: ApiAnswerPath;
 }
 
 export interface ApiPosition {
@@ -33,9 +77,54 @@ export interface ApiPosition {
   col: number;
 }
 
+// Letters chosen for the board's first and second wildcards, as serialized by the API
+export type ApiPathConstraintSet =
+  | 'Unconstrainted'
+  | { FirstDecided: string }
+  | { SecondDecided: string }
+  | { BothDecided: [string, string] };
+
+export interface ApiAnswerPath {
+  positions: ApiPosition[];
+  constraints: ApiPathConstraintSet;
+}
+
+// The letters a wildcard could still be (null when it could be any) and the one it must be, if only one is left
+export interface ApiWildcardOptions {
+  letters: string[] | null;
+  locked: string | null;
+}
+
+export interface ApiConstraints {
+  words: string[];
+  constraint_sets: ApiPathConstraintSet[];
+  first_wildcard: ApiWildcardOptions;
+  second_wildcard: ApiWildcardOptions;
+}
+
+// What adding `word` would do to a player's answers; scores stay as they are when the constraints don't hold
+export interface ApiScorePreview {
+  word: string;
+  constraints_hold: boolean;
+  previous_total_score: number;
+  total_score: number;
+  word_score: number | null;
+  changes: { word: string; before: number; after: number }[];
+  conflict: { words: string[]; demands: ApiWildcardDemand[] } | null;
+}
+
+// The best a player's answers can still finish on, without the words that reach it
+export interface ApiBestFinish {
+  current_score: number;
+  best_total_score: number;
+  word_count: number;
+  complete: boolean;
+}
+
 export interface ValidateRequest {
   word: string;
   previous_answers: ApiAnswer[];
+  game_id?: string;
 }
 
 export interface ValidateResponse {
@@ -44,6 +133,7 @@ export interface ValidateResponse {
   path: ApiPosition[];
   wildcard_constraints: Record<string, string>;
   error_message: string;
+  error_code: 'language' | 'characters' | 'length' | null;
 }
 
 export interface UpdateProgressRequest {
@@ -52,12 +142,18 @@ export interface UpdateProgressRequest {
   answers: ApiAnswer[];
   game_id: string;
   completed: boolean;
+  // From startGame; binds the submission to the game that was started
+  start_token?: string;
+  // A UUID sent again when retrying the same save, so the retry gets the original result
+  request_id?: string;
 }
 
 export interface SubmitResponse {
   user_id: string;
   total_score: number;
   stats: ApiGameStats;
+  // Submitted after the game's stats were frozen, so not counted in them
+  off_board: boolean;
 }
 
 export interface ApiGameStats {
@@ -68,11 +164,365 @@ export interface ApiGameStats {
   highest_score: number;
 }
 
+export interface SaveProgressRequest {
+  user_id?: string;
+  cookie_token?: string;
+  // Every answer found so far
+  answers: ApiAnswer[];
+}
+
+export interface ApiProgressRevision {
+  answers: ApiAnswer[];
+  saved_at: string;
+}
+
+export interface ApiProgress {
+  // Newest first
+  revisions: ApiProgressRevision[];
+}
+
 export interface GameEntryResponse {
   answers: ApiAnswer[];
   completed: boolean;
   total_score: number;
   stats?: ApiGameStats;
+  off_board: boolean;
+}
+
+export interface ApiWordStats {
+  word: string;
+  // How to show the word, e.g. "QI" for "qi"; render this rather than `word`
+  display: string;
+  path_count: number;
+  requires_wildcard: boolean;
+  // Percentage (0-100) of players who completed the game that found the word
+  found_by: number;
+}
+
+export interface ApiHint {
+  level: number;
+  first_letter: string;
+  length?: number;
+  origin?: ApiPosition;
+  partial_path?: ApiPosition[];
+}
+
+export interface ApiHintsResponse {
+  hints: ApiHint[];
+  hints_used: number;
+  hint_budget: number;
+  score_penalty: number;
+}
+
+export interface ApiUserSession {
+  id: string;
+  user_agent_hash?: string;
+  first_seen: string;
+  last_seen: string;
+  current: boolean;
+}
+
+export interface ApiArchiveGame {
+  game_id: string;
+  sequence_number: number;
+  date: string;
+  threshold_score: number;
+  completed: boolean;
+  user_score?: number;
+  total_players: number;
+  average_score?: number;
+}
+
+export interface ApiArchivePage {
+  games: ApiArchiveGame[];
+  page: number;
+  page_size: number;
+  total_games: number;
+}
+
+export interface ApiShareCard {
+  text: string;
+  emoji_grid: string;
+  total_score: number;
+  threshold_score: number;
+  percentile: number | null;
+}
+
+export interface ApiLiveCounts {
+  in_progress: number;
+  completed: number;
+}
+
+export interface ApiLiveWord {
+  id: number;
+  length: number;
+  score: number;
+  // Only once the game's day has ended everywhere
+  word: string | null;
+  submitted_at: string;
+}
+
+export interface ApiLiveGame extends ApiLiveCounts {
+  words: ApiLiveWord[];
+}
+
+export interface ApiPracticeGame {
+  id: string;
+  seed: number;
+  board: ApiBoard;
+  // Short code for the board, readable with decodeBoard
+  board_code: string;
+  threshold_score: number;
+  optimal_score: number;
+  expires_at: string;
+}
+
+export interface ApiDecodedBoard {
+  code: string;
+  board: ApiBoard;
+}
+
+export interface PracticeSubmitResponse {
+  total_score: number;
+  threshold_score: number;
+  optimal_score: number;
+}
+
+export interface ApiCustomGame {
+  // Short id the game is shared by
+  slug: string;
+  board_code: string;
+  board: ApiBoard;
+  optimal_score: number;
+}
+
+export interface CustomGameSubmitResponse {
+  total_score: number;
+  // The player's best score on this game so far
+  best_score: number;
+  optimal_score: number;
+}
+
+export interface ApiCustomGameScore {
+  rank: number;
+  total_score: number;
+  is_you: boolean;
+}
+
+export interface ApiCustomGameScores {
+  optimal_score: number;
+  scores: ApiCustomGameScore[];
+}
+
+export interface ApiTournament {
+  id: string;
+  name: string;
+  // The first and last dates whose daily games count
+  start_date: string;
+  end_date: string;
+  language: string;
+  // Whether its final standings have been written
+  finalized: boolean;
+}
+
+export interface JoinTournamentResponse {
+  tournament: ApiTournament;
+  already_joined: boolean;
+}
+
+export interface ApiTournamentStanding {
+  // Players with the same total share a rank
+  rank: number;
+  total_score: number;
+  games_played: number;
+  is_you: boolean;
+}
+
+export interface ApiTournamentStandings {
+  tournament: ApiTournament;
+  total_players: number;
+  standings: ApiTournamentStanding[];
+  // The caller's own standing, if they joined
+  you: ApiTournamentStanding | null;
+}
+
+export interface ApiPasskey {
+  credential_id: string;
+  created_at: string;
+  last_used_at: string | null;
+}
+
+export interface ApiAccount {
+  email: string | null;
+  passkeys: ApiPasskey[];
+}
+
+// The identity a device should play as after signing in to an account
+export interface ApiAccountSession {
+  user_id: string;
+  cookie_token: string;
+  email: string | null;
+  merged_entries: number;
+}
+
+// base64url encoded options for navigator.credentials
+export interface ApiPasskeyOptions {
+  challenge: string;
+  rp_id: string;
+  user_handle: string | null;
+}
+
+// base64url encoded fields from a PublicKeyCredential
+export interface PasskeyRegistration {
+  credential_id: string;
+  public_key: string;
+  authenticator_data: string;
+  client_data_json: string;
+}
+
+export interface PasskeyAssertion {
+  credential_id: string;
+  authenticator_data: string;
+  client_data_json: string;
+  signature: string;
+}
+
+export interface ApiWordLengthCount {
+  length: number;
+  count: number;
+}
+
+export interface ApiUserStats {
+  games_played: number;
+  average_score: number;
+  best_score: number;
+  average_percentile: number;
+  word_lengths: ApiWordLengthCount[];
+  // Share of completed games that reached the threshold score, 0 to 1
+  threshold_hit_rate: number;
+}
+
+export interface ApiGameStart {
+  started_at: string;
+  start_token: string;
+}
+
+// The JSON body of every API error response
+export interface ApiErrorBody {
+  code: 'not_found' | 'validation' | 'unauthorized' | 'forbidden' | 'conflict' | 'internal';
+  message: string;
+  details?: unknown;
+}
+
+// The details of a validation error for answers the game won't accept
+export type ApiGameErrorDetails =
+  | { kind: 'empty_input' }
+  | { kind: 'unsupported'; word: string; reason: 'language' | 'characters' | 'length' }
+  | { kind: 'too_short'; word: string; min_word_len: number }
+  | { kind: 'too_many_answers'; max_answers: number }
+  | { kind: 'repeated'; word: string }
+  | { kind: 'not_in_dictionary'; word: string }
+  | { kind: 'not_on_board'; word: string }
+  | { kind: 'constraint_conflict'; words: string[]; demands: ApiWildcardDemand[] };
+
+// What a word in a wildcard conflict needs: the wildcard letters of any one of its options
+export interface ApiWildcardDemand {
+  word: string;
+  options: { first: string | null; second: string | null }[];
+}
+
+// Thrown for any failed API request. `reason` is the server's message, which
+// is safe to show to the player.
+export class ApiRequestError extends Error {
+  constructor(
+    public status: number,
+    public code: ApiErrorBody['code'] | undefined,
+    public reason: string,
+    public details?: unknown,
+  ) {
+    super(`API request failed: ${status} ${reason}`);
+    this.name = 'ApiRequestError';
+  }
+}
+
+// Thrown when a submission's start token is for another game, e.g. a tab
+// left open past midnight; the puzzle needs refreshing
+export class StaleGameError extends ApiRequestError {
+  constructor(reason: string, details?: unknown) {
+    super(409, 'conflict', reason, details);
+    this.message = reason;
+    this.name = 'StaleGameError';
+  }
+}
+
+async function errorFrom(response: Response): Promise<ApiRequestError> {
+  let body: ApiErrorBody | null = null;
+  try {
+    body = await response.json();
+  } catch {
+    // Not every failure has a JSON body, e.g. a proxy timing out
+  }
+  if (!body) {
+    return new ApiRequestError(response.status, undefined, response.statusText);
+  }
+  const details = body.details as { stale_game?: string } | undefined;
+  if (response.status === 409 && details?.stale_game) {
+    return new StaleGameError(body.message, body.details);
+  }
+  return new ApiRequestError(response.status, body.code, body.message, body.details);
+}
+
+export interface ApiSpeedRun {
+  rank: number;
+  // Milliseconds from starting the game to submitting it
+  elapsed_ms: number;
+  total_score: number;
+  is_you: boolean;
+}
+
+export interface ApiSpeedLeaderboard {
+  score_floor: number;
+  min_elapsed_ms: number;
+  runs: ApiSpeedRun[];
+}
+
+export interface ApiBlitzGame {
+  game: ApiGame;
+  started_at: string;
+  // Saves after this are turned away
+  ends_at: string;
+  start_token: string;
+}
+
+export interface ApiBlitzStats {
+  games_played: number;
+  average_score: number | null;
+  best_score: number | null;
+  // Games never submitted count their whole time limit
+  words_per_minute: number | null;
+  best_words_per_minute: number | null;
+}
+
+export interface ApiNotification {
+  id: string;
+  kind: string;
+  game_id: string | null;
+  message: string;
+  created_at: string;
+  read: boolean;
+}
+
+export interface ApiNotifications {
+  notifications: ApiNotification[];
+  unread: number;
+}
+
+export interface ApiNotificationPreferences {
+  final_rank: boolean;
+}
+
+export interface ApiDiscordLink {
+  discord_user_id: string;
 }
 
 class GameApi {
@@ -87,19 +537,16 @@ class GameApi {
     });
 
     if (!response.ok) {
-      throw new Error(`API request failed: ${response.status} ${response.statusText}`);
+      throw await errorFrom(response);
     }
 
     return response.json();
   }
 
   async getDailyGame(): Promise<ApiGame> {
-    const now = new Date();
-    const year = now.getFullYear();
-    const month = String(now.getMonth() + 1).padStart(2, '0');
-    const day = String(now.getDate()).padStart(2, '0');
-    const currentDate = `${year}-${month}-${day}`;
-    return this.request<ApiGame>(`/game/date/${currentDate}`);
+    // The server resolves "today" for our timezone
+    const tz = Intl.DateTimeFormat().resolvedOptions().timeZone;
+    return this.request<ApiGame>(`/game/today?tz=${encodeURIComponent(tz)}`);
   }
 
   async getGameByDate(date: string): Promise<ApiGame> {
@@ -144,12 +591,133 @@ class GameApi {
     });
   }
 
+  /** Autosave the answers found so far; the entry itself is only saved by updateProgress */
+  async saveProgress(gameId: string, request: SaveProgressRequest): Promise<ApiProgressRevision> {
+    return this.request<ApiProgressRevision>(`/game-entry/${gameId}/progress`, {
+      method: 'PATCH',
+      body: JSON.stringify(request),
+    });
+  }
+
+  async getProgress(gameId: string, userId: string, cookieToken: string): Promise<ApiProgress> {
+    const params = new URLSearchParams({ user_id: userId, cookie_token: cookieToken });
+    return this.request<ApiProgress>(`/game-entry/${gameId}/progress?${params.toString()}`);
+  }
+
   async createUser(): Promise<{ user_id: string; cookie_token: string }> {
     return this.request<{ user_id: string; cookie_token: string }>('/user', {
       method: 'POST',
     });
   }
 
+  async getUserSessions(userId: string, cookieToken: string): Promise<ApiUserSession[]> {
+    const params = new URLSearchParams({ user_id: userId, cookie_token: cookieToken });
+    return this.request<ApiUserSession[]>(`/user/sessions?${params.toString()}`);
+  }
+
+  async revokeUserSession(sessionId: string, userId: string, cookieToken: string): Promise<void> {
+    const params = new URLSearchParams({ user_id: userId, cookie_token: cookieToken });
+    const response = await fetch(`${API_BASE_URL}/user/sessions/${sessionId}?${params.toString()}`, {
+      method: 'DELETE',
+    });
+
+    if (!response.ok) {
+      throw await errorFrom(response);
+    }
+  }
+
+  async getUserStats(userId: string, cookieToken: string): Promise<ApiUserStats> {
+    const params = new URLSearchParams({ cookie_token: cookieToken });
+    return this.request<ApiUserStats>(`/user/${userId}/stats?${params.toString()}`);
+  }
+
+  /** URL that downloads the user's full play history as a CSV or JSON file */
+  historyExportUrl(userId: string, cookieToken: string, format: 'csv' | 'json' = 'csv'): string {
+    const params = new URLSearchParams({ user_id: userId, cookie_token: cookieToken, format });
+    return `${API_BASE_URL}/user/history/export?${params.toString()}`;
+  }
+
+  async getArchive(page = 1, userId?: string, cookieToken?: string): Promise<ApiArchivePage> {
+    const params = new URLSearchParams({ page: page.toString() });
+    if (userId) params.append('user_id', userId);
+    if (cookieToken) params.append('cookie_token', cookieToken);
+
+    return this.request<ApiArchivePage>(`/games/archive?${params.toString()}`);
+  }
+
+  async getShareCard(gameId: string, userId?: string, cookieToken?: string): Promise<ApiShareCard> {
+    const params = new URLSearchParams();
+    if (userId) params.append('user_id', userId);
+    if (cookieToken) params.append('cookie_token', cookieToken);
+
+    return this.request<ApiShareCard>(`/game-entry/${gameId}/share?${params.toString()}`);
+  }
+
+  /** URL of the share card rendered as a PNG */
+  shareCardImageUrl(gameId: string, userId?: string, cookieToken?: string): string {
+    const params = new URLSearchParams({ format: 'png' });
+    if (userId) params.append('user_id', userId);
+    if (cookieToken) params.append('cookie_token', cookieToken);
+
+    return `${API_BASE_URL}/game-entry/${gameId}/share?${params.toString()}`;
+  }
+
+  async getLiveCounts(gameId: string): Promise<ApiLiveCounts> {
+    return this.request<ApiLiveCounts>(`/game/${gameId}/live`);
+  }
+
+  /** Player counts and recently submitted words, only those after `since` if given */
+  async getLiveGame(gameId: string, since?: number): Promise<ApiLiveGame> {
+    const query = since === undefined ? '' : `?since=${since}`;
+    return this.request<ApiLiveGame>(`/game/${gameId}/live${query}`);
+  }
+
+  /** Calls onWord for each word submitted for a game; returns a function that stops listening */
+  subscribeLiveWords(gameId: string, onWord: (word: ApiLiveWord) => void): () => void {
+    const source = new EventSource(`${API_BASE_URL}/game/${gameId}/live`);
+    source.addEventListener('word', (event) => {
+      onWord(JSON.parse((event as MessageEvent).data) as ApiLiveWord);
+    });
+    return () => source.close();
+  }
+
+  /** Calls onCounts whenever a game's player counts change; returns a function that stops listening */
+  subscribeLiveCounts(gameId: string, onCounts: (counts: ApiLiveCounts) => void): () => void {
+    const source = new EventSource(`${API_BASE_URL}/game/${gameId}/live`);
+    source.addEventListener('counts', (event) => {
+      onCounts(JSON.parse((event as MessageEvent).data) as ApiLiveCounts);
+    });
+    return () => source.close();
+  }
+
+  /** What the board's wildcards can still be while all of `words` are played */
+  async getConstraints(gameId: string, words: string[]): Promise<ApiConstraints> {
+    const params = new URLSearchParams({ words: words.join(',') });
+    return this.request<ApiConstraints>(`/game/${gameId}/constraints?${params.toString()}`);
+  }
+
+  /** The score the player would have with `word` added to their answers, before submitting it */
+  async previewScore(
+    gameId: string,
+    answers: ApiAnswer[],
+    word: string,
+    userId?: string,
+    cookieToken?: string,
+  ): Promise<ApiScorePreview> {
+    return this.request<ApiScorePreview>(`/game/${gameId}/preview-score`, {
+      method: 'POST',
+      body: JSON.stringify({ user_id: userId ?? null, cookie_token: cookieToken ?? null, answers, word }),
+    });
+  }
+
+  /** The best total `answers` can reach with `n` more words, by default as many as the game has room for */
+  async getBestFinish(gameId: string, answers: ApiAnswer[], n?: number): Promise<ApiBestFinish> {
+    return this.request<ApiBestFinish>(`/game/${gameId}/best-finish`, {
+      method: 'POST',
+      body: JSON.stringify({ answers, n: n ?? null }),
+    });
+  }
+
   async getGameEntry(gameId: string, userId?: string, cookieToken?: string): Promise<GameEntryResponse | null> {
     const params = new URLSearchParams();
     if (userId) params.append('user_id', userId);
@@ -162,6 +730,253 @@ class GameApi {
   async getGameWords(gameId: string): Promise<string[]> {
     return this.request<string[]>(`/game/${gameId}/words`);
   }
+
+  async getGameWordStats(gameId: string): Promise<ApiWordStats[]> {
+    return this.request<ApiWordStats[]>(`/game/${gameId}/word-stats`);
+  }
+
+  async getScoringProfile(profileId: string): Promise<ApiScoringProfile> {
+    return this.request<ApiScoringProfile>(`/scoring-profile/${encodeURIComponent(profileId)}`);
+  }
+
+  async decodeBoard(code: string): Promise<ApiDecodedBoard> {
+    return this.request<ApiDecodedBoard>(`/board/decode?code=${encodeURIComponent(code)}`);
+  }
+
+  async getHints(gameId: string, userId?: string, cookieToken?: string): Promise<ApiHintsResponse> {
+    const params = new URLSearchParams();
+    if (userId) params.append('user_id', userId);
+    if (cookieToken) params.append('cookie_token', cookieToken);
+
+    const query = params.toString() ? `?${params.toString()}` : '';
+    return this.request<ApiHintsResponse>(`/game/${gameId}/hints${query}`);
+  }
+
+  async revealHint(gameId: string, userId?: string, cookieToken?: string): Promise<ApiHintsResponse> {
+    return this.request<ApiHintsResponse>(`/game/${gameId}/hints`, {
+      method: 'POST',
+      body: JSON.stringify({ user_id: userId, cookie_token: cookieToken }),
+    });
+  }
+
+  /** Start the server's clock for the speed leaderboard; later calls keep the first start */
+  async startGame(gameId: string, userId?: string, cookieToken?: string): Promise<ApiGameStart> {
+    return this.request<ApiGameStart>(`/game/${gameId}/start`, {
+      method: 'POST',
+      body: JSON.stringify({ user_id: userId, cookie_token: cookieToken }),
+    });
+  }
+
+  async getSpeedLeaderboard(gameId: string, userId?: string, cookieToken?: string): Promise<ApiSpeedLeaderboard> {
+    const params = new URLSearchParams();
+    if (userId) params.append('user_id', userId);
+    if (cookieToken) params.append('cookie_token', cookieToken);
+
+    return this.request<ApiSpeedLeaderboard>(`/game/${gameId}/speed-leaderboard?${params.toString()}`);
+  }
+
+  /** Get a date's blitz board; the first call starts the player's clock */
+  async getBlitzGame(date: string, userId?: string, cookieToken?: string): Promise<ApiBlitzGame> {
+    const params = new URLSearchParams();
+    if (userId) params.append('user_id', userId);
+    if (cookieToken) params.append('cookie_token', cookieToken);
+
+    return this.request<ApiBlitzGame>(`/blitz/date/${date}?${params.toString()}`);
+  }
+
+  async getBlitzStats(userId?: string, cookieToken?: string): Promise<ApiBlitzStats> {
+    const params = new URLSearchParams();
+    if (userId) params.append('user_id', userId);
+    if (cookieToken) params.append('cookie_token', cookieToken);
+
+    return this.request<ApiBlitzStats>(`/blitz/stats?${params.toString()}`);
+  }
+
+  async newPracticeGame(seed?: number): Promise<ApiPracticeGame> {
+    return this.request<ApiPracticeGame>('/practice/new', {
+      method: 'POST',
+      body: JSON.stringify({ seed }),
+    });
+  }
+
+  async getPracticeGame(practiceId: string): Promise<ApiPracticeGame> {
+    return this.request<ApiPracticeGame>(`/practice/${practiceId}`);
+  }
+
+  async submitPracticeGame(practiceId: string, answers: ApiAnswer[]): Promise<PracticeSubmitResponse> {
+    return this.request<PracticeSubmitResponse>(`/practice/${practiceId}/submit`, {
+      method: 'POST',
+      body: JSON.stringify({ answers }),
+    });
+  }
+
+  // Make a custom game from a board code or 16 letters; the same board always gets the same slug
+  async createCustomGame(board: string, userId?: string, cookieToken?: string): Promise<ApiCustomGame> {
+    return this.request<ApiCustomGame>('/custom-game', {
+      method: 'POST',
+      body: JSON.stringify({ board, user_id: userId, cookie_token: cookieToken }),
+    });
+  }
+
+  async getCustomGame(slug: string): Promise<ApiCustomGame> {
+    return this.request<ApiCustomGame>(`/custom-game/${slug}`);
+  }
+
+  async submitCustomGame(
+    slug: string,
+    answers: ApiAnswer[],
+    userId: string,
+    cookieToken: string
+  ): Promise<CustomGameSubmitResponse> {
+    return this.request<CustomGameSubmitResponse>(`/custom-game/${slug}/submit`, {
+      method: 'POST',
+      body: JSON.stringify({ answers, user_id: userId, cookie_token: cookieToken }),
+    });
+  }
+
+  async getCustomGameScores(slug: string, userId?: string, cookieToken?: string): Promise<ApiCustomGameScores> {
+    const params = new URLSearchParams();
+    if (userId) params.append('user_id', userId);
+    if (cookieToken) params.append('cookie_token', cookieToken);
+
+    return this.request<ApiCustomGameScores>(`/custom-game/${slug}/scores?${params.toString()}`);
+  }
+
+  async getTournaments(): Promise<ApiTournament[]> {
+    return this.request<ApiTournament[]>('/tournaments');
+  }
+
+  async joinTournament(tournamentId: string, userId: string, cookieToken: string): Promise<JoinTournamentResponse> {
+    return this.request<JoinTournamentResponse>(`/tournaments/${tournamentId}/join`, {
+      method: 'POST',
+      body: JSON.stringify({ user_id: userId, cookie_token: cookieToken }),
+    });
+  }
+
+  async getTournamentStandings(
+    tournamentId: string,
+    userId?: string,
+    cookieToken?: string
+  ): Promise<ApiTournamentStandings> {
+    const params = new URLSearchParams();
+    if (userId) params.append('user_id', userId);
+    if (cookieToken) params.append('cookie_token', cookieToken);
+
+    return this.request<ApiTournamentStandings>(`/tournaments/${tournamentId}/standings?${params.toString()}`);
+  }
+
+  async getAccount(userId: string, cookieToken: string): Promise<ApiAccount> {
+    const params = new URLSearchParams({ user_id: userId, cookie_token: cookieToken });
+    return this.request<ApiAccount>(`/account?${params.toString()}`);
+  }
+
+  // Email a link that confirms the address for this user's account
+  async attachEmail(email: string, userId: string, cookieToken: string): Promise<void> {
+    await this.post('/account/email', { email, user_id: userId, cookie_token: cookieToken });
+  }
+
+  // Email a sign in link; succeeds whether or not the address has an account
+  async requestEmailLogin(email: string): Promise<void> {
+    await this.post('/account/login/email', { email });
+  }
+
+  async useMagicLink(token: string, userId?: string, cookieToken?: string): Promise<ApiAccountSession> {
+    return this.request<ApiAccountSession>('/account/magic-link', {
+      method: 'POST',
+      body: JSON.stringify({ token, user_id: userId, cookie_token: cookieToken }),
+    });
+  }
+
+  async mergeIdentity(
+    userId: string,
+    cookieToken: string,
+    otherUserId: string,
+    otherCookieToken: string,
+  ): Promise<{ merged_entries: number }> {
+    return this.request<{ merged_entries: number }>('/account/merge', {
+      method: 'POST',
+      body: JSON.stringify({
+        user_id: userId,
+        cookie_token: cookieToken,
+        other_user_id: otherUserId,
+        other_cookie_token: otherCookieToken,
+      }),
+    });
+  }
+
+  async getPasskeyRegistrationOptions(userId: string, cookieToken: string): Promise<ApiPasskeyOptions> {
+    return this.request<ApiPasskeyOptions>('/account/passkeys/options', {
+      method: 'POST',
+      body: JSON.stringify({ user_id: userId, cookie_token: cookieToken }),
+    });
+  }
+
+  async registerPasskey(registration: PasskeyRegistration, userId: string, cookieToken: string): Promise<ApiPasskey> {
+    return this.request<ApiPasskey>('/account/passkeys', {
+      method: 'POST',
+      body: JSON.stringify({ ...registration, user_id: userId, cookie_token: cookieToken }),
+    });
+  }
+
+  async getPasskeyLoginOptions(): Promise<ApiPasskeyOptions> {
+    return this.request<ApiPasskeyOptions>('/account/login/passkey/options', {
+      method: 'POST',
+      body: JSON.stringify({}),
+    });
+  }
+
+  async loginWithPasskey(assertion: PasskeyAssertion, userId?: string, cookieToken?: string): Promise<ApiAccountSession> {
+    return this.request<ApiAccountSession>('/account/login/passkey', {
+      method: 'POST',
+      body: JSON.stringify({ ...assertion, user_id: userId, cookie_token: cookieToken }),
+    });
+  }
+
+  async getNotifications(userId: string, cookieToken: string): Promise<ApiNotifications> {
+    const params = new URLSearchParams({ user_id: userId, cookie_token: cookieToken });
+    return this.request<ApiNotifications>(`/notifications?${params.toString()}`);
+  }
+
+  async markNotificationsRead(userId: string, cookieToken: string): Promise<void> {
+    await this.post('/notifications/read', { user_id: userId, cookie_token: cookieToken });
+  }
+
+  async getNotificationPreferences(userId: string, cookieToken: string): Promise<ApiNotificationPreferences> {
+    const params = new URLSearchParams({ user_id: userId, cookie_token: cookieToken });
+    return this.request<ApiNotificationPreferences>(`/notifications/preferences?${params.toString()}`);
+  }
+
+  async updateNotificationPreferences(
+    preferences: ApiNotificationPreferences,
+    userId: string,
+    cookieToken: string,
+  ): Promise<ApiNotificationPreferences> {
+    return this.request<ApiNotificationPreferences>('/notifications/preferences', {
+      method: 'PUT',
+      body: JSON.stringify({ ...preferences, user_id: userId, cookie_token: cookieToken }),
+    });
+  }
+
+  /** Link the player to the Discord user the bot's /link command gave a code to */
+  async linkDiscord(code: string, userId: string, cookieToken: string): Promise<ApiDiscordLink> {
+    return this.request<ApiDiscordLink>('/discord/link', {
+      method: 'POST',
+      body: JSON.stringify({ code, user_id: userId, cookie_token: cookieToken }),
+    });
+  }
+
+  // POST to an endpoint that answers with no body
+  private async post(endpoint: string, body: unknown): Promise<void> {
+    const response = await fetch(`${API_BASE_URL}${endpoint}`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify(body),
+    });
+
+    if (!response.ok) {
+      throw await errorFrom(response);
+    }
+  }
 }
 
 export const gameApi = new GameApi();
@@ -174,6 +989,7 @@ export function convertApiTileToTile(apiTile: ApiTile): import('../utils/models'
     isWildcard: apiTile.is_wildcard,
     row: apiTile.row,
     col: apiTile.col,
+    modifier: apiTile.modifier,
   };
 }
 
@@ -1,7 +1,6 @@
 import React from 'react';
 import { convertConstraintSetsToConstraints } from '../utils/constraintResolution';
 import { AnswerGroupConstraintSet, Tile as TileType } from '../utils/models';
-import { getLetterPoints } from '../utils/scoring';
 
 interface TileProps {
   tile: TileType;
@@ -94,12 +93,15 @@ function Tile({ tile, isHighlighted, isLastLetter, board, wildcardConstraints}:
   // Calculate point value for non-wildcard tiles
   const getPointValue = (): number | null => {
     if (tile.isWildcard) return null;
-    const letterPoints = getLetterPoints();
-    return letterPoints[tile.letter.toLowerCase()];
+    // Points come with the board, so past games show the values they were scored with
+    return tile.points;
   };
 
   const pointValue = getPointValue();
 
+  // Letter multipliers are blue, word multipliers red
+  const modifierColor = tile.modifier?.endsWith('W') ? '#c62828' : '#1565c0';
+
   return (
     <div 
       className={`tile ${isHighlighted ? 'highlighted' : ''} ${tile.isWildcard ? 'wildcard' : ''}`}
@@ -119,6 +121,21 @@ function Tile({ tile, isHighlighted, isLastLetter, board, wildcardConstraints}:
       }}
     >
       {renderWildcardContent()}
+      {tile.modifier && (
+        <div
+          className="tile-modifier"
+          style={{
+            position: 'absolute',
+            top: '2px',
+            left: '4px',
+            fontSize: '9px',
+            fontWeight: 'bold',
+            color: modifierColor
+          }}
+        >
+          {tile.modifier}
+        </div>
+      )}
       {pointValue !== null && (
         <div
           style={{
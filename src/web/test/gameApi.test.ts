@@ -108,6 +108,21 @@ describe('GameApi', () => {
       )
     })
 
+    it('should surface the message and code of an error body', async () => {
+      mockFetch.mockResolvedValueOnce({
+        ok: false,
+        status: 404,
+        statusText: 'Not Found',
+        json: async () => ({ code: 'not_found', message: 'Game not found', details: null }),
+      })
+
+      await expect(gameApi.getGameBySequence(999)).rejects.toMatchObject({
+        status: 404,
+        code: 'not_found',
+        reason: 'Game not found',
+      })
+    })
+
     it('should handle network errors', async () => {
       mockFetch.mockRejectedValueOnce(new Error('Network error'))
 
@@ -116,10 +131,12 @@ describe('GameApi', () => {
   })
 
   describe('getDailyGame', () => {
-    it('should call correct endpoint without date parameter', async () => {
-      // Mock Date to return a fixed date for consistent testing
-      const mockDate = new Date('2025-06-08T12:00:00Z')
-      vi.spyOn(global, 'Date').mockImplementation(() => mockDate)
+    it('should ask the server for today in the client timezone', async () => {
+      // Mock the resolved timezone for consistent testing
+      vi.spyOn(Intl.DateTimeFormat.prototype, 'resolvedOptions').mockReturnValue({
+        ...new Intl.DateTimeFormat().resolvedOptions(),
+        timeZone: 'America/New_York',
+      })
 
       const mockGameData = {
         id: 'daily-game-id',
@@ -137,7 +154,7 @@ describe('GameApi', () => {
       const result = await gameApi.getDailyGame()
 
       expect(mockFetch).toHaveBeenCalledWith(
-        'undefined//undefined/api/game/date/2025-06-08',
+        'undefined//undefined/api/game/today?tz=America%2FNew_York',
         {
           headers: {
             'Content-Type': 'application/json',
@@ -209,4 +226,31 @@ describe('GameApi', () => {
       expect(sequenceResult.sequence_number).toBe(gameDataFromSequence.sequence_number)
     })
   })
+
+  describe('getScoringProfile', () => {
+    it('fetches the letter point table a game references', async () => {
+      const profile = {
+        id: 'standard',
+        version: 1,
+        letter_points: { e: 1, q: 6 },
+        wildcard_points: 0,
+      }
+      mockFetch.mockResolvedValueOnce({
+        ok: true,
+        json: async () => profile,
+      })
+
+      const result = await gameApi.getScoringProfile('standard')
+
+      expect(mockFetch).toHaveBeenCalledWith(
+        'undefined//undefined/api/scoring-profile/standard',
+        {
+          headers: {
+            'Content-Type': 'application/json',
+          },
+        }
+      )
+      expect(result).toEqual(profile)
+    })
+  })
 })
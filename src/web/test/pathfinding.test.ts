@@ -1,6 +1,6 @@
 import { describe, expect, it } from 'vitest'
 import { PathConstraintType } from '../utils/models'
-import { findAllPaths, findAllPathsGivenConstraints, isPathCompatibleWithConstraints } from '../utils/pathfinding'
+import { findAllPaths, findAllPathsGivenConstraints, isPathCompatibleWithConstraints, pathPoints } from '../utils/pathfinding'
 import { testBoard } from './util.test'
 
 
@@ -302,4 +302,15 @@ describe('Pathfinding Tests', () => {
       expect(isPathCompatibleWithConstraints(pathWithConstraints, emptyConstraint, board, 'ta')).toBe(true)
     })
   })
+
+  it('scores paths with their tile multipliers', () => {
+    const board = testBoard('tarae*oros*sotvi')
+    const tar = [{ row: 0, col: 0 }, { row: 0, col: 1 }, { row: 0, col: 2 }]
+    expect(pathPoints(board, tar)).toBe(3)
+
+    board[0][0].modifier = 'TL'
+    expect(pathPoints(board, tar)).toBe(5)
+    board[0][2].modifier = 'DW'
+    expect(pathPoints(board, tar)).toBe(10)
+  })
 })
@@ -3,12 +3,16 @@ export interface Position {
   col: number;
 }
 
+// Multiplier on a tile: double/triple letter or double/triple word
+export type TileModifier = 'DL' | 'TL' | 'DW' | 'TW';
+
 export interface Tile {
   letter: string;
   points: number;
   isWildcard: boolean;
   row: number;
   col: number;
+  modifier?: TileModifier;
 }
 
 export enum PathConstraintType {
@@ -348,6 +348,24 @@ function canWildcardBeUsedForLetter(pos: Position, letter: string, constraintSet
   });
 }
 
+// Points a path scores: tile points times any letter multiplier, the sum
+// times any word multipliers, as the server scores it
+export function pathPoints(board: Tile[][], path: Position[]): number {
+  let points = 0;
+  let wordMultiplier = 1;
+  for (const { row, col } of path) {
+    const tile = board[row][col];
+    switch (tile.modifier) {
+      case 'DL': points += tile.points * 2; break;
+      case 'TL': points += tile.points * 3; break;
+      case 'DW': points += tile.points; wordMultiplier *= 2; break;
+      case 'TW': points += tile.points; wordMultiplier *= 3; break;
+      default: points += tile.points;
+    }
+  }
+  return points * wordMultiplier;
+}
+
 function scorePathByPreference(board: Tile[][], path: Position[]): PathScore {
   let wildcardCount = 0;
   let pointCount = 0;
@@ -358,13 +376,11 @@ function scorePathByPreference(board: Tile[][], path: Position[]): PathScore {
 
     if (tile.isWildcard) {
       wildcardCount++;
-    } else {
-      pointCount += tile.points;
     }
   }
 
   return {
-    pointCount,
+    pointCount: pathPoints(board, path),
     wildcardCount
   };
 }
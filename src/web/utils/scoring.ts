@@ -1,7 +1,7 @@
 import { PathConstraintSet, PathConstraintType } from '../utils/models';
 import { mergeAllAnswerGroupConstraintSets, mergePathConstraintSets } from './constraintResolution';
 import { Position, Tile } from './models';
-import { findAllPaths } from './pathfinding';
+import { findAllPaths, pathPoints } from './pathfinding';
 
 export const letterFrequencies: Record<string, number> = {
   'a': 0.078,
@@ -32,31 +32,8 @@ export const letterFrequencies: Record<string, number> = {
   'z': 0.0044,
 };
 
-function pointsForLetter(letter: string): number {
-  if (letter === '*') return 0;
-  return Math.floor(Math.log2(letterFrequencies['e'] / letterFrequencies[letter.toLowerCase()])) + 1;
-}
-
-export function getLetterPoints(): Record<string, number> {
-  const points: Record<string, number> = { '*': 0 };
-  for (const letter in letterFrequencies) {
-    points[letter] = pointsForLetter(letter);
-  }
-  return points;
-}
-
 export function calculateWordScore(_word: string, path: Position[], board: Tile[][]): number {
-  let score = 0;
-  for (let i = 0; i < path.length; i++) {
-    const { row, col } = path[i];
-    const tile = board[row][col];
-    if (tile.isWildcard) {
-      score += 0;
-    } else {
-      score += pointsForLetter(tile.letter);
-    }
-  }
-  return score;
+  return pathPoints(board, path);
 }
 
 export function scoreAnswerGroup(words: string[], board: Tile[][]): { scores: Record<string, number>, optimalConstraintSets: PathConstraintSet[] } {
@@ -104,7 +81,7 @@ export function scoreAnswerGroup(words: string[], board: Tile[][]): { scores: Re
         // Check if this path's constraints are compatible with the current pathConstraint
         try {
           mergePathConstraintSets(path.constraints, pathConstraint);
-          const pathScore = path.path.reduce((sum, pos) => sum + board[pos.row][pos.col].points, 0);
+          const pathScore = pathPoints(board, path.path);
           
           // Track which wildcard positions are used by this path
           const pathConstraintsUsedThisPath: Record<string, boolean> = {};